@@ -232,7 +232,7 @@ where
                 mode: pack::data::output::entry::iter_from_counts::Mode::PackCopyAndBaseObjects,
                 allow_thin_pack: thin,
                 chunk_size,
-                version: Default::default(),
+                ..Default::default()
             },
         ))
     };
@@ -24,10 +24,13 @@ pub fn stream(
 ) -> anyhow::Result<()> {
     let format = format.map_or_else(|| format_from_ext(destination_path), Ok)?;
     let object = repo.rev_parse_single(rev_spec.unwrap_or("HEAD"))?.object()?;
-    let (modification_date, tree) = fetch_rev_info(object)?;
+    let (modification_date, commit_id, tree) = fetch_rev_info(object)?;
 
     let start = std::time::Instant::now();
-    let (mut stream, index) = repo.worktree_stream(tree)?;
+    let (mut stream, index) = match commit_id {
+        Some(id) => repo.worktree_stream_with_substitution(&repo.find_object(id)?.into_commit())?,
+        None => repo.worktree_stream(tree)?,
+    };
     if !add_paths.is_empty() {
         let root = gix::path::realpath(
             repo.work_dir()
@@ -69,6 +72,7 @@ pub fn stream(
                     .map(|d| d.as_secs())
                     .unwrap_or_default() as gix::date::SecondsSinceUnixEpoch
             }),
+            commit_id,
         },
     )?;
 
@@ -80,13 +84,21 @@ pub fn stream(
 
 fn fetch_rev_info(
     object: gix::Object<'_>,
-) -> anyhow::Result<(Option<gix::date::SecondsSinceUnixEpoch>, gix::ObjectId)> {
+) -> anyhow::Result<(
+    Option<gix::date::SecondsSinceUnixEpoch>,
+    Option<gix::ObjectId>,
+    gix::ObjectId,
+)> {
     Ok(match object.kind {
         gix::object::Kind::Commit => {
             let commit = object.into_commit();
-            (Some(commit.committer()?.time.seconds), commit.tree_id()?.detach())
+            (
+                Some(commit.committer()?.time.seconds),
+                Some(commit.id),
+                commit.tree_id()?.detach(),
+            )
         }
-        gix::object::Kind::Tree => (None, object.id),
+        gix::object::Kind::Tree => (None, None, object.id),
         gix::object::Kind::Tag => fetch_rev_info(object.peel_to_kind(gix::object::Kind::Commit)?)?,
         gix::object::Kind::Blob => bail!("Cannot derive commit or tree from blob at {}", object.id),
     })
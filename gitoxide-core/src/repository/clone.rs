@@ -95,6 +95,9 @@ pub(crate) mod function {
             }
             Status::Change {
                 update_refs, negotiate, ..
+            }
+            | Status::Unpacked {
+                update_refs, negotiate, ..
             } => {
                 let remote = repo
                     .find_default_remote(gix::remote::Direction::Fetch)
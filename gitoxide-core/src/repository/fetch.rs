@@ -120,6 +120,21 @@ pub(crate) mod function {
                 }
                 Ok(())
             }
+            Status::Unpacked {
+                update_refs,
+                unpack,
+                negotiate,
+            } => {
+                print_updates(&repo, &negotiate, update_refs, ref_specs, res.ref_map, &mut out, err)?;
+                writeln!(out, "unpacked {} objects", unpack.ids.len()).ok();
+                if negotiation_info {
+                    print_negotiate_info(&mut out, Some(&negotiate))?;
+                }
+                if let Some(path) = open_negotiation_graph {
+                    render_graph(&repo, &negotiate.graph, &path, progress)?;
+                }
+                Ok(())
+            }
         }?;
         if dry_run {
             writeln!(out, "DRY-RUN: No ref was updated and no pack was received.").ok();
@@ -86,6 +86,11 @@ pub struct Options {
     ///
     /// Defaults to the current time. The caller may set this to the commit time if available.
     pub modification_time: gix_date::SecondsSinceUnixEpoch,
+    /// The id of the commit the archived tree belongs to, if known.
+    ///
+    /// Just like `git archive`, it's stored as `comment` in a global extended `pax` header of `tar` archives, where
+    /// `git get-tar-commit-id` can find it, and as comment of `zip` archives.
+    pub commit_id: Option<gix_hash::ObjectId>,
 }
 
 impl Default for Options {
@@ -97,6 +102,7 @@ impl Default for Options {
                 .duration_since(std::time::UNIX_EPOCH)
                 .map(|t| t.as_secs() as i64)
                 .unwrap_or_default(),
+            commit_id: None,
         }
     }
 }
@@ -8,8 +8,8 @@ use crate::{Error, Format, Options};
 /// ### Performance
 ///
 /// * The caller should be sure `out` is fast enough. If in doubt, wrap in [`std::io::BufWriter`].
-/// * Further, big files aren't suitable for archival into `tar` archives as they require the size of the stream to be known
-///   prior to writing the header of each entry.
+/// * Further, big files aren't suitable for archival into `tar` archives if the size of their stream isn't known in advance,
+///   as it's required prior to writing the header of each entry. Such entries are held in memory.
 #[cfg_attr(not(feature = "tar"), allow(unused_mut, unused_variables))]
 pub fn write_stream<NextFn>(
     stream: &mut Stream,
@@ -23,6 +23,10 @@ where
     if opts.format == Format::InternalTransientNonPersistable {
         return Err(Error::InternalFormatMustNotPersist);
     }
+    #[cfg(not(any(feature = "tar", feature = "tar_gz")))]
+    {
+        Err(Error::SupportNotCompiledIn { wanted: opts.format })
+    }
     #[cfg(any(feature = "tar", feature = "tar_gz"))]
     {
         enum State<W: std::io::Write> {
@@ -84,6 +88,14 @@ where
         }
 
         let mut state = State::new(opts.format, opts.modification_time, out)?;
+        if let Some(commit_id) = opts.commit_id {
+            match &mut state {
+                #[cfg(feature = "tar")]
+                State::Tar((ar, _)) => append_tar_commit_id(ar, commit_id, opts.modification_time)?,
+                #[cfg(feature = "tar_gz")]
+                State::TarGz((ar, _)) => append_tar_commit_id(ar, commit_id, opts.modification_time)?,
+            }
+        }
         while let Some(entry) = next_entry(stream)? {
             match &mut state {
                 #[cfg(feature = "tar")]
@@ -107,14 +119,15 @@ where
                 ar.into_inner()?.finish()?;
             }
         }
+        Ok(())
     }
-    Ok(())
 }
 
 /// Like [`write_stream()`], but requires [`std::io::Seek`] for `out`.
 ///
-/// Note that `zip` is able to stream big files, which our `tar` implementation is not able to do, which makes it the
-/// only suitable container to support huge files from `git-lfs` without consuming excessive amounts of memory.
+/// Note that `zip` is able to stream big files even if their size isn't known in advance, which our `tar` implementation
+/// is not able to do, which makes it the only suitable container to support huge files from `git-lfs` without consuming
+/// excessive amounts of memory.
 #[cfg_attr(not(feature = "zip"), allow(unused_mut, unused_variables))]
 pub fn write_stream_seek<NextFn>(
     stream: &mut Stream,
@@ -133,6 +146,9 @@ where
     #[cfg(feature = "zip")]
     {
         let mut ar = zip::write::ZipWriter::new(out);
+        if let Some(commit_id) = opts.commit_id {
+            ar.set_comment(commit_id.to_string());
+        }
         let mut buf = Vec::new();
         let mtime = time::OffsetDateTime::from_unix_timestamp(opts.modification_time)
             .map_err(|err| Error::InvalidModificationTime(Box::new(err)))?
@@ -211,21 +227,54 @@ fn append_tar_entry<W: std::io::Write>(
     } else {
         0o644
     });
-    buf.clear();
-    std::io::copy(&mut entry, buf)?;
-
-    let path = gix_path::from_bstr(add_prefix(entry.relative_path(), opts.tree_prefix.as_ref()));
-    header.set_size(buf.len() as u64);
+    let path = gix_path::from_bstr(add_prefix(entry.relative_path(), opts.tree_prefix.as_ref())).into_owned();
+    match (entry.mode, entry.bytes_remaining()) {
+        (gix_object::tree::EntryMode::Link, _) => {
+            use bstr::ByteSlice;
+            buf.clear();
+            std::io::copy(&mut entry, buf)?;
+            let target = gix_path::from_bstr(buf.as_bstr());
+            header.set_entry_type(tar::EntryType::Symlink);
+            header.set_size(0);
+            ar.append_link(&mut header, path, target)?;
+        }
+        // Stream entries of known size right away to avoid holding big files in memory.
+        (_, Some(size)) => {
+            header.set_size(size as u64);
+            ar.append_data(&mut header, path, &mut entry)?;
+        }
+        (_, None) => {
+            buf.clear();
+            std::io::copy(&mut entry, buf)?;
+            header.set_size(buf.len() as u64);
+            ar.append_data(&mut header, path, buf.as_slice())?;
+        }
+    }
+    Ok(())
+}
 
-    if entry.mode == gix_object::tree::EntryMode::Link {
-        use bstr::ByteSlice;
-        let target = gix_path::from_bstr(buf.as_bstr());
-        header.set_entry_type(tar::EntryType::Symlink);
-        header.set_size(0);
-        ar.append_link(&mut header, path, target)?;
-    } else {
-        ar.append_data(&mut header, path, buf.as_slice())?;
+/// Write `commit_id` into a global extended `pax` header as the first entry, just like `git archive` does.
+#[cfg(any(feature = "tar", feature = "tar_gz"))]
+fn append_tar_commit_id<W: std::io::Write>(
+    ar: &mut tar::Builder<W>,
+    commit_id: gix_hash::ObjectId,
+    mtime_seconds_since_epoch: i64,
+) -> Result<(), Error> {
+    let value = commit_id.to_string();
+    // The length of a record includes the length of its own decimal representation.
+    let payload_len = " comment=\n".len() + value.len();
+    let mut len = payload_len + 1;
+    while len != payload_len + len.to_string().len() {
+        len = payload_len + len.to_string().len();
     }
+    let record = format!("{len} comment={value}\n");
+
+    let mut header = tar::Header::new_ustar();
+    header.set_entry_type(tar::EntryType::XGlobalHeader);
+    header.set_mtime(mtime_seconds_since_epoch as u64);
+    header.set_mode(0o666);
+    header.set_size(record.len() as u64);
+    ar.append_data(&mut header, "pax_global_header", record.as_bytes())?;
     Ok(())
 }
 
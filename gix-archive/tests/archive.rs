@@ -222,6 +222,56 @@ mod from_tree {
         )
     }
 
+    #[test]
+    #[cfg(all(feature = "tar", feature = "zip"))]
+    fn commit_id_is_recorded_like_git_does() -> gix_testtools::Result {
+        let commit_id = hex_to_id("0123456789012345678901234567890123456789");
+        let archive = |format| -> gix_testtools::Result<Vec<u8>> {
+            let (_dir, head_tree, odb, _cache) = basic()?;
+            let mut stream =
+                gix_worktree_stream::from_tree(head_tree, odb, noop_pipeline(), |_, _, _| Ok::<_, std::io::Error>(()));
+            let mut buf = Vec::new();
+            gix_archive::write_stream_seek(
+                &mut stream,
+                gix_worktree_stream::Stream::next_entry,
+                std::io::Cursor::new(&mut buf),
+                gix_archive::Options {
+                    format,
+                    tree_prefix: None,
+                    modification_time: 1820000000,
+                    commit_id: Some(commit_id),
+                },
+            )?;
+            Ok(buf)
+        };
+
+        let tar = archive(Format::Tar)?;
+        assert_eq!(tar, archive(Format::Tar)?, "the output is deterministic");
+        assert_eq!(
+            tar[156], b'g',
+            "the first entry is a global extended header, as expected by `git get-tar-commit-id`"
+        );
+        assert_eq!(
+            tar[512..564].as_bstr(),
+            format!("52 comment={commit_id}\n"),
+            "the record length includes its own length"
+        );
+        let mut ar = tar::Archive::new(tar.as_slice());
+        let paths = ar
+            .entries()?
+            .map(|entry| Ok(entry?.path_bytes().as_bstr().to_owned()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        assert_eq!(paths[0], "pax_global_header");
+        assert_eq!(paths[1], ".gitattributes", "the actual entries follow");
+
+        let zip = archive(Format::Zip {
+            compression_level: None,
+        })?;
+        let ar = zip::ZipArchive::new(std::io::Cursor::new(zip.as_slice()))?;
+        assert_eq!(ar.comment(), commit_id.to_string().as_bytes());
+        Ok(())
+    }
+
     fn basic_usage(
         format: gix_archive::Format,
         make_assertion: impl FnOnce(Vec<u8>) -> gix_testtools::Result,
@@ -257,6 +307,7 @@ mod from_tree {
                         format,
                         tree_prefix: Some("prefix/".into()),
                         modification_time: 1820000000, // needs to be within a certain bound to be a valid MSDos time!
+                        commit_id: None,
                     },
                 )?;
             } else {
@@ -268,6 +319,7 @@ mod from_tree {
                         format,
                         tree_prefix: Some("prefix/".into()),
                         modification_time: 120,
+                        commit_id: None,
                     },
                 )?;
             }
@@ -51,6 +51,72 @@ pub fn decode(data: &[u8]) -> Result<(Vec, &[u8]), decode::Error> {
     ))
 }
 
+mod encode {
+    use std::convert::TryFrom;
+
+    use super::Vec;
+
+    /// Encoding
+    impl Vec {
+        /// Compress the uncompressed bitmap in `words`, where bit `n` is stored in `words[n / 64] & (1 << (n % 64))`.
+        ///
+        /// Trailing words without any set bit are dropped, just like git does it.
+        pub fn from_words(words: &[u64]) -> Self {
+            let words = &words[..words.iter().rposition(|word| *word != 0).map_or(0, |pos| pos + 1)];
+            let mut bits = std::vec::Vec::with_capacity(words.len() + 1);
+            let mut iter = words.iter().copied().peekable();
+            let rlw = loop {
+                let rlw = bits.len();
+                bits.push(0);
+                let mut running_len = 0u64;
+                let running_bit = iter.peek().map_or(false, |word| *word == u64::MAX);
+                while running_len < RLW_LARGEST_RUNNING_COUNT
+                    && iter
+                        .next_if(|word| (running_bit && *word == u64::MAX) || (!running_bit && *word == 0))
+                        .is_some()
+                {
+                    running_len += 1;
+                }
+                let mut literal_words = 0u64;
+                while literal_words < RLW_LARGEST_LITERAL_COUNT {
+                    match iter.next_if(|word| *word != 0 && *word != u64::MAX) {
+                        Some(word) => {
+                            bits.push(word);
+                            literal_words += 1;
+                        }
+                        None => break,
+                    }
+                }
+                bits[rlw] = u64::from(running_bit) | (running_len << 1) | (literal_words << (1 + RLW_RUNNING_BITS));
+                if iter.peek().is_none() {
+                    break rlw;
+                }
+            };
+            Vec {
+                num_bits: u32::try_from(words.len() * 64).unwrap_or(u32::MAX),
+                bits,
+                rlw: rlw as u64,
+            }
+        }
+
+        /// Write this bitmap to `out` in the format understood by [`decode()`][super::decode()].
+        pub fn write_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+            out.write_all(&self.num_bits.to_be_bytes())?;
+            let len = u32::try_from(self.bits.len())
+                .map_err(|_| std::io::Error::new(std::io::ErrorKind::Other, "too many words in bitmap"))?;
+            out.write_all(&len.to_be_bytes())?;
+            for word in &self.bits {
+                out.write_all(&word.to_be_bytes())?;
+            }
+            out.write_all(&(self.rlw as u32).to_be_bytes())
+        }
+    }
+
+    const RLW_RUNNING_BITS: u64 = 4 * 8;
+    const RLW_LARGEST_RUNNING_COUNT: u64 = (1 << RLW_RUNNING_BITS) - 1;
+    const RLW_LARGEST_LITERAL_COUNT: u64 = (1 << (64 - 1 - RLW_RUNNING_BITS)) - 1;
+}
+
 mod access {
     use std::convert::{TryFrom, TryInto};
 
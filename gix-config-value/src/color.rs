@@ -31,6 +31,64 @@ impl Display for Color {
     }
 }
 
+/// The ANSI escape sequence to reset all colors and attributes, as written by `git` after colored output.
+pub const RESET: &str = "\x1b[m";
+
+/// Rendering
+impl Color {
+    /// Return the ANSI escape sequence to enable this color, exactly like `git` would produce it, or an empty string if
+    /// this color has no effect, like `normal`.
+    pub fn to_ansi(&self) -> String {
+        let mut codes = 0_u32;
+        for attr in self.attributes.iter() {
+            if let Some(code) = attr.ansi_code() {
+                codes |= 1 << code;
+            }
+        }
+        let has_reset = self.attributes.contains(Attribute::RESET);
+        let is_empty = |name: Option<Name>| matches!(name, None | Some(Name::Normal));
+        if !has_reset && codes == 0 && is_empty(self.foreground) && is_empty(self.background) {
+            return String::new();
+        }
+
+        let mut out = String::from("\x1b[");
+        let mut needs_separator = has_reset;
+        let mut separate = |out: &mut String| {
+            if needs_separator {
+                out.push(';');
+            }
+            needs_separator = true;
+        };
+        for code in (0..32).filter(|code| codes & (1 << code) != 0) {
+            separate(&mut out);
+            out.push_str(&code.to_string());
+        }
+        for (name, is_background) in [(self.foreground, false), (self.background, true)] {
+            if let Some(name) = name.filter(|name| *name != Name::Normal) {
+                separate(&mut out);
+                name.write_ansi(&mut out, is_background);
+            }
+        }
+        out.push('m');
+        out
+    }
+
+    /// Return `text` enclosed in the ANSI escape sequences to enable this color and to [reset](RESET) it, or `text` as is
+    /// if this color has no effect.
+    pub fn paint(&self, text: impl AsRef<[u8]>) -> BString {
+        let color = self.to_ansi();
+        let text = text.as_ref();
+        if color.is_empty() {
+            return text.into();
+        }
+        let mut out = Vec::with_capacity(color.len() + text.len() + RESET.len());
+        out.extend_from_slice(color.as_bytes());
+        out.extend_from_slice(text);
+        out.extend_from_slice(RESET.as_bytes());
+        out.into()
+    }
+}
+
 fn color_err(input: impl Into<BString>) -> Error {
     Error::new(
         "Colors are specific color values and their attributes, like 'brightred', or 'blue'",
@@ -221,6 +279,44 @@ impl FromStr for Name {
     }
 }
 
+impl Name {
+    /// Append the ANSI code for this color as foreground or `background` color to `out`.
+    fn write_ansi(self, out: &mut String, background: bool) {
+        let offset = if background { 10 } else { 0 };
+        let (ansi, bright) = match self {
+            Self::Normal => return,
+            Self::Default => (9, false),
+            Self::Black => (0, false),
+            Self::BrightBlack => (0, true),
+            Self::Red => (1, false),
+            Self::BrightRed => (1, true),
+            Self::Green => (2, false),
+            Self::BrightGreen => (2, true),
+            Self::Yellow => (3, false),
+            Self::BrightYellow => (3, true),
+            Self::Blue => (4, false),
+            Self::BrightBlue => (4, true),
+            Self::Magenta => (5, false),
+            Self::BrightMagenta => (5, true),
+            Self::Cyan => (6, false),
+            Self::BrightCyan => (6, true),
+            Self::White => (7, false),
+            Self::BrightWhite => (7, true),
+            Self::Ansi(num) if num < 8 => (num, false),
+            Self::Ansi(num) => {
+                out.push_str(&format!("{};5;{num}", 38 + offset));
+                return;
+            }
+            Self::Rgb(r, g, b) => {
+                out.push_str(&format!("{};2;{r};{g};{b}", 38 + offset));
+                return;
+            }
+        };
+        let base = if bright { 90 } else { 30 };
+        out.push_str(&(base + offset + ansi).to_string());
+    }
+}
+
 impl TryFrom<&BStr> for Name {
     type Error = Error;
 
@@ -294,6 +390,28 @@ impl Display for Attribute {
     }
 }
 
+impl Attribute {
+    /// Return the ANSI code of a single attribute, or `None` if it has none, like [`RESET`](Attribute::RESET).
+    fn ansi_code(self) -> Option<u8> {
+        Some(match self {
+            Attribute::BOLD => 1,
+            Attribute::DIM => 2,
+            Attribute::ITALIC => 3,
+            Attribute::UL => 4,
+            Attribute::BLINK => 5,
+            Attribute::REVERSE => 7,
+            Attribute::STRIKE => 9,
+            Attribute::NO_BOLD | Attribute::NO_DIM => 22,
+            Attribute::NO_ITALIC => 23,
+            Attribute::NO_UL => 24,
+            Attribute::NO_BLINK => 25,
+            Attribute::NO_REVERSE => 27,
+            Attribute::NO_STRIKE => 29,
+            _ => return None,
+        })
+    }
+}
+
 #[cfg(feature = "serde")]
 impl serde::Serialize for Attribute {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
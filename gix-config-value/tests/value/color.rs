@@ -197,3 +197,53 @@ mod from_git {
         Ok(Color::try_from(name.into())?.to_string())
     }
 }
+
+mod to_ansi {
+    use std::convert::TryFrom;
+
+    use bstr::BStr;
+    use gix_config_value::{color, Color};
+
+    #[test]
+    fn like_git() {
+        for (input, expected) in [
+            ("", ""),
+            ("normal", ""),
+            ("normal normal", ""),
+            ("reset", "\x1b[m"),
+            ("reset bold", "\x1b[;1m"),
+            ("red", "\x1b[31m"),
+            ("bold red", "\x1b[1;31m"),
+            ("red blue", "\x1b[31;44m"),
+            ("normal blue", "\x1b[44m"),
+            ("brightred brightblue", "\x1b[91;104m"),
+            ("default default", "\x1b[39;49m"),
+            ("0 7", "\x1b[30;47m"),
+            ("254 bold 255", "\x1b[1;38;5;254;48;5;255m"),
+            ("#ff0000 ul", "\x1b[4;38;2;255;0;0m"),
+            ("#ffffff #000000", "\x1b[38;2;255;255;255;48;2;0;0;0m"),
+            ("dim bold ul blink reverse italic strike red", "\x1b[1;2;3;4;5;7;9;31m"),
+            (
+                "nobold nodim noul noblink noreverse noitalic nostrike",
+                "\x1b[22;23;24;25;27;29m",
+            ),
+        ] {
+            assert_eq!(color(input).to_ansi(), expected, "{input:?}");
+        }
+    }
+
+    #[test]
+    fn paint() {
+        assert_eq!(color("bold red").paint("text"), "\x1b[1;31mtext\x1b[m");
+        assert_eq!(
+            color("normal").paint("text"),
+            "text",
+            "colors without effect leave the text as is"
+        );
+        assert_eq!(color::RESET, "\x1b[m");
+    }
+
+    fn color<'a>(name: impl Into<&'a BStr>) -> Color {
+        Color::try_from(name.into()).expect("input color is expected to be valid")
+    }
+}
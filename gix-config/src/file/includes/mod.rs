@@ -38,7 +38,45 @@ impl File<'static> {
 }
 
 pub(crate) fn resolve(config: &mut File<'static>, buf: &mut Vec<u8>, options: init::Options<'_>) -> Result<(), Error> {
-    resolve_includes_recursive(config, 0, buf, options)
+    let mut remote_urls = RemoteUrls {
+        unresolved: has_includes(config).then(|| Box::new(config.clone())),
+        urls: Vec::new(),
+    };
+    resolve_includes_recursive(config, 0, buf, options, &mut remote_urls)
+}
+
+/// The URLs of all remotes for use in `hasconfig:remote.*.url:` conditions, which are only collected once they are needed.
+#[derive(Default)]
+struct RemoteUrls {
+    /// The configuration before resolving its includes, or `None` if it has none or if its URLs were collected already.
+    unresolved: Option<Box<File<'static>>>,
+    urls: Vec<BString>,
+}
+
+impl RemoteUrls {
+    /// Return all remote URLs of the configuration along with the files it includes, except for those included
+    /// by `hasconfig` conditions, just like `git` does.
+    fn get(&mut self, buf: &mut Vec<u8>, options: init::Options<'_>) -> Result<&[BString], Error> {
+        if let Some(mut config) = self.unresolved.take() {
+            resolve_includes_recursive(&mut config, 0, buf, options, &mut RemoteUrls::default())?;
+            self.urls = config
+                .sections_by_name("remote")
+                .into_iter()
+                .flatten()
+                .filter(|section| section.header.subsection_name.is_some())
+                .flat_map(|section| section.values("url"))
+                .map(Cow::into_owned)
+                .collect();
+        }
+        Ok(&self.urls)
+    }
+}
+
+fn has_includes(config: &File<'_>) -> bool {
+    config.sections.values().any(|section| {
+        let name = section.header.name.as_ref();
+        name == "include" || name == "includeIf"
+    })
 }
 
 fn resolve_includes_recursive(
@@ -46,6 +84,7 @@ fn resolve_includes_recursive(
     depth: u8,
     buf: &mut Vec<u8>,
     options: init::Options<'_>,
+    remote_urls: &mut RemoteUrls,
 ) -> Result<(), Error> {
     if depth == options.includes.max_depth {
         return if options.includes.err_on_max_depth_exceeded {
@@ -70,14 +109,21 @@ fn resolve_includes_recursive(
         } else if header_name == "includeIf" {
             if let Some(condition) = &header.subsection_name {
                 let target_config_path = section.meta.path.as_deref();
-                if include_condition_match(condition.as_ref(), target_config_path, options.includes)? {
+                if include_condition_match(condition.as_ref(), target_config_path, options, buf, remote_urls)? {
                     detach_include_paths(&mut section_ids_and_include_paths, section, id)
                 }
             }
         }
     }
 
-    append_followed_includes_recursively(section_ids_and_include_paths, target_config, depth, options, buf)
+    append_followed_includes_recursively(
+        section_ids_and_include_paths,
+        target_config,
+        depth,
+        options,
+        buf,
+        remote_urls,
+    )
 }
 
 fn append_followed_includes_recursively(
@@ -86,6 +132,7 @@ fn append_followed_includes_recursively(
     depth: u8,
     options: init::Options<'_>,
     buf: &mut Vec<u8>,
+    remote_urls: &mut RemoteUrls,
 ) -> Result<(), Error> {
     for (section_id, config_path) in section_ids_and_include_paths {
         let meta = OwnShared::clone(&target_config.sections[&section_id].meta);
@@ -117,7 +164,7 @@ fn append_followed_includes_recursively(
                 init::Error::Interpolate(err) => Error::Interpolate(err),
                 init::Error::Includes(_) => unreachable!("BUG: {:?} not possible due to no-follow options", err),
             })?;
-        resolve_includes_recursive(&mut include_config, depth + 1, buf, options)?;
+        resolve_includes_recursive(&mut include_config, depth + 1, buf, options, remote_urls)?;
 
         target_config.append_or_insert(include_config, Some(section_id));
     }
@@ -141,8 +188,11 @@ fn detach_include_paths(
 fn include_condition_match(
     condition: &BStr,
     target_config_path: Option<&Path>,
-    options: Options<'_>,
+    init_options: init::Options<'_>,
+    buf: &mut Vec<u8>,
+    remote_urls: &mut RemoteUrls,
 ) -> Result<bool, Error> {
+    let options = init_options.includes;
     let mut tokens = condition.splitn(2, |b| *b == b':');
     let (prefix, condition) = match (tokens.next(), tokens.next()) {
         (Some(a), Some(b)) => (a, b),
@@ -163,6 +213,16 @@ fn include_condition_match(
             gix_glob::wildmatch::Mode::IGNORE_CASE,
         ),
         b"onbranch" => Ok(onbranch_matches(condition, options.conditional).is_some()),
+        b"hasconfig" => match condition.strip_prefix(b"remote.*.url:") {
+            Some(pattern) => Ok(remote_urls.get(buf, init_options)?.iter().any(|url| {
+                gix_glob::wildmatch(
+                    pattern.as_bstr(),
+                    url.as_bstr(),
+                    gix_glob::wildmatch::Mode::NO_MATCH_SLASH_LITERAL,
+                )
+            })),
+            None => Ok(false),
+        },
         _ => Ok(false),
     }
 }
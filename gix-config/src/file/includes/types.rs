@@ -60,6 +60,7 @@ impl<'a> Options<'a> {
     }
     /// Provide options to follow includes like git does, provided the required `conditional` and `interpolate` contexts
     /// to support `gitdir` and `onbranch` based `includeIf` directives as well as standard `include.path` resolution.
+    /// `hasconfig:remote.*.url` conditions are matched against the URLs of all remotes in the configuration that is resolved.
     /// Note that the follow-mode is `git`-style, following at most 10 indirections while
     /// producing an error if the depth is exceeded.
     pub fn follow(interpolate: interpolate::Context<'a>, conditional: conditional::Context<'a>) -> Self {
@@ -1,4 +1,4 @@
-use std::convert::TryFrom;
+use std::{convert::TryFrom, ffi::OsString};
 
 use bstr::{BStr, ByteSlice};
 
@@ -31,16 +31,31 @@ pub enum Error {
 /// Instantiation from environment variables
 impl File<'static> {
     /// Generates a config from `GIT_CONFIG_*` environment variables or returns `Ok(None)` if no configuration was found.
+    /// Just like in `git`, an empty `GIT_CONFIG_COUNT` is the same as `0`.
     /// See [`git-config`'s documentation] for more information on the environment variables in question.
     ///
     /// With `options` configured, it's possible to resolve `include.path` or `includeIf.<condition>.path` directives as well.
     ///
     /// [`git-config`'s documentation]: https://git-scm.com/docs/git-config#Documentation/git-config.txt-GITCONFIGCOUNT
     pub fn from_env(options: init::Options<'_>) -> Result<Option<File<'static>>, Error> {
-        use std::env;
-        let count: usize = match env::var("GIT_CONFIG_COUNT") {
-            Ok(v) => v.parse().map_err(|_| Error::InvalidConfigCount { input: v })?,
-            Err(_) => return Ok(None),
+        Self::from_env_vars(&mut |name| std::env::var_os(name), options)
+    }
+
+    /// Like [`from_env()`](Self::from_env()), but obtains the values of environment variables by `name` from `env_var(name)`,
+    /// which allows to control which variables are visible.
+    pub fn from_env_vars(
+        env_var: &mut dyn FnMut(&str) -> Option<OsString>,
+        options: init::Options<'_>,
+    ) -> Result<Option<File<'static>>, Error> {
+        let count: usize = match env_var("GIT_CONFIG_COUNT") {
+            Some(v) if v.is_empty() => 0,
+            Some(v) => v
+                .to_str()
+                .and_then(|v| v.parse().ok())
+                .ok_or_else(|| Error::InvalidConfigCount {
+                    input: v.to_string_lossy().into_owned(),
+                })?,
+            None => return Ok(None),
         };
 
         if count == 0 {
@@ -56,10 +71,10 @@ impl File<'static> {
         let mut config = File::new(meta);
         for i in 0..count {
             let key = gix_path::os_string_into_bstring(
-                env::var_os(format!("GIT_CONFIG_KEY_{i}")).ok_or(Error::InvalidKeyId { key_id: i })?,
+                env_var(&format!("GIT_CONFIG_KEY_{i}")).ok_or(Error::InvalidKeyId { key_id: i })?,
             )
             .map_err(|_| Error::IllformedUtf8 { index: i, kind: "key" })?;
-            let value = env::var_os(format!("GIT_CONFIG_VALUE_{i}")).ok_or(Error::InvalidValueId { value_id: i })?;
+            let value = env_var(&format!("GIT_CONFIG_VALUE_{i}")).ok_or(Error::InvalidValueId { value_id: i })?;
             let key = parse::key(<_ as AsRef<BStr>>::as_ref(&key)).ok_or_else(|| Error::InvalidKeyValue {
                 key_id: i,
                 key_val: key.to_string(),
@@ -149,6 +149,40 @@ impl<'a, 'event> SectionMut<'a, 'event> {
         Some(self.remove_internal(key_range, true))
     }
 
+    /// Removes all values of `key` for which `filter` returns `true`, along with their keys, and returns the number of
+    /// removed values.
+    ///
+    /// `filter` receives the normalized value, or `None` if the key has no value, like `key` in contrast to `key = value`.
+    pub fn remove_matching(&mut self, key: &str, mut filter: impl FnMut(Option<&BStr>) -> bool) -> usize {
+        let key = Key::from_str_unchecked(key);
+        let ranges = self.matching_key_ranges(&key, &mut filter);
+        for range in ranges.iter().rev() {
+            self.remove_internal(range.clone(), true);
+        }
+        ranges.len()
+    }
+
+    /// Replaces all values of `key` for which `filter` returns `true` with a single `key = value`, placed where the last of them was,
+    /// and returns the number of replaced values. Nothing is changed if no value matched.
+    ///
+    /// `filter` receives the normalized value, or `None` if the key has no value, like `key` in contrast to `key = value`.
+    pub fn replace_matching(
+        &mut self,
+        key: Key<'event>,
+        value: &BStr,
+        mut filter: impl FnMut(Option<&BStr>) -> bool,
+    ) -> usize {
+        let ranges = self.matching_key_ranges(&key, &mut filter);
+        if let Some((last, rest)) = ranges.split_last() {
+            self.remove_internal(last.clone(), false);
+            self.set_internal(Index(last.start), key, value);
+            for range in rest.iter().rev() {
+                self.remove_internal(range.clone(), true);
+            }
+        }
+        ranges.len()
+    }
+
     /// Adds a new line event. Note that you don't need to call this unless
     /// you've disabled implicit newlines.
     pub fn push_newline(&mut self) -> &mut Self {
@@ -270,6 +304,45 @@ impl<'a, 'event> SectionMut<'a, 'event> {
         Size(size)
     }
 
+    /// Return the ranges of all `key`-value pairs whose value passes `filter`, in order of occurrence.
+    fn matching_key_ranges(&self, key: &Key<'_>, filter: &mut dyn FnMut(Option<&BStr>) -> bool) -> Vec<Range<usize>> {
+        let mut out = Vec::new();
+        let mut current = None::<(Range<usize>, Option<BString>)>;
+        let mut flush = |current: Option<(Range<usize>, Option<BString>)>| {
+            if let Some((range, value)) = current {
+                if filter(value.map(normalize_bstring).as_deref()) {
+                    out.push(range);
+                }
+            }
+        };
+        for (i, event) in self.section.body.0.iter().enumerate() {
+            match event {
+                Event::SectionKey(k) => {
+                    flush(current.take());
+                    if k == key {
+                        current = Some((i..i + 1, None));
+                    }
+                }
+                Event::KeyValueSeparator => {
+                    if let Some((_, value)) = current.as_mut() {
+                        value.get_or_insert_with(Default::default);
+                    }
+                }
+                Event::Value(v) | Event::ValueNotDone(v) | Event::ValueDone(v) => {
+                    if let Some((range, value)) = current.as_mut() {
+                        range.end = i + 1;
+                        if let Some(value) = value {
+                            value.push_str(v.as_ref());
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        flush(current);
+        out
+    }
+
     /// Performs the removal, assuming the range is valid.
     fn remove_internal(&mut self, range: Range<usize>, fix_whitespace: bool) -> Cow<'event, BStr> {
         let events = &mut self.section.body.0;
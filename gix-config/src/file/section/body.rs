@@ -82,6 +82,43 @@ impl<'event> Body<'event> {
         values
     }
 
+    /// Retrieves all keys along with their values in order, with implicit values, i.e. keys without key separator `=`,
+    /// being `None`.
+    #[must_use]
+    pub fn entries(&self) -> Vec<(&Key<'event>, Option<Cow<'_, BStr>>)> {
+        let mut entries = Vec::new();
+        let mut key = None;
+        let mut has_separator = false;
+        let mut concatenated_value = BString::default();
+
+        for event in &self.0 {
+            match event {
+                Event::SectionKey(k) => {
+                    key = Some(k);
+                    has_separator = false;
+                }
+                Event::KeyValueSeparator => has_separator = true,
+                Event::Value(v) => {
+                    if let Some(key) = key.take() {
+                        entries.push((key, has_separator.then(|| normalize_bstr(v.as_ref()))));
+                    }
+                }
+                Event::ValueNotDone(v) => {
+                    concatenated_value.push_str(v.as_ref());
+                }
+                Event::ValueDone(v) => {
+                    concatenated_value.push_str(v.as_ref());
+                    if let Some(key) = key.take() {
+                        entries.push((key, Some(normalize_bstring(std::mem::take(&mut concatenated_value)))));
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        entries
+    }
+
     /// Returns an iterator visiting all keys in order.
     pub fn keys(&self) -> impl Iterator<Item = &Key<'event>> {
         self.0.iter().filter_map(|e| match e {
@@ -60,6 +60,9 @@ impl Source {
     /// Be aware that depending on environment overrides, multiple scopes might return the same path, which should
     /// only be loaded once nonetheless.
     ///
+    /// Just like in `git`, `GIT_CONFIG_GLOBAL` and `GIT_CONFIG_SYSTEM` override the paths of the global and system files,
+    /// and `GIT_CONFIG_NOSYSTEM` prevents the system file from being used if it's set to a value that is true.
+    ///
     /// With `env_var` it becomes possible to prevent accessing environment variables entirely to comply with `gix-sec`
     /// permissions for example.
     pub fn storage_location(self, env_var: &mut dyn FnMut(&str) -> Option<OsString>) -> Option<Cow<'static, Path>> {
@@ -67,7 +70,7 @@ impl Source {
         match self {
             GitInstallation => gix_path::env::installation_config().map(Into::into),
             System => {
-                if env_var("GIT_CONFIG_NO_SYSTEM").is_some() {
+                if env_var("GIT_CONFIG_NOSYSTEM").map_or(false, |v| crate::Boolean::try_from(v).map_or(true, |b| b.0)) {
                     None
                 } else {
                     env_var("GIT_CONFIG_SYSTEM")
@@ -2,4 +2,5 @@ pub use gix_testtools::Result;
 
 mod file;
 mod parse;
+mod source;
 mod value;
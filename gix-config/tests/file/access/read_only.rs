@@ -268,6 +268,28 @@ fn single_section() {
     );
 }
 
+#[test]
+fn section_entries_are_in_order_and_distinguish_implicit_values() -> crate::Result {
+    let config = File::try_from("[core]\na=b\nc\nA = \"x\" \\\n  y\nd =")?;
+    let section = config.section("core", None)?;
+    let entries: Vec<_> = section
+        .body()
+        .entries()
+        .into_iter()
+        .map(|(key, value)| (key.as_ref().to_owned(), value.map(Cow::into_owned)))
+        .collect();
+    assert_eq!(
+        entries,
+        [
+            ("a".into(), Some("b".into())),
+            ("c".into(), None),
+            ("A".into(), Some("x   y".into())),
+            ("d".into(), Some("".into()))
+        ]
+    );
+    Ok(())
+}
+
 #[test]
 fn sections_by_name() -> crate::Result {
     let config = r#"
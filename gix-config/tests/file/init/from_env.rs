@@ -25,6 +25,42 @@ fn empty_with_zero_count() {
     assert!(config.is_none());
 }
 
+#[test]
+#[serial]
+fn empty_with_empty_count() {
+    let _env = Env::new().set("GIT_CONFIG_COUNT", "");
+    let config = File::from_env(Default::default()).unwrap();
+    assert!(config.is_none(), "git treats an empty count like 0");
+}
+
+#[test]
+fn from_custom_environment() -> crate::Result {
+    let mut env_var = |name: &str| {
+        match name {
+            "GIT_CONFIG_COUNT" => Some("2"),
+            "GIT_CONFIG_KEY_0" => Some("core.a"),
+            "GIT_CONFIG_VALUE_0" => Some("a"),
+            "GIT_CONFIG_KEY_1" => Some("remote.origin.url"),
+            "GIT_CONFIG_VALUE_1" => Some("b"),
+            _ => None,
+        }
+        .map(Into::into)
+    };
+    let config = File::from_env_vars(&mut env_var, Default::default())?.expect("present");
+    assert_eq!(config.raw_value("core", None, "a")?, Cow::<[u8]>::Borrowed(b"a"));
+    assert_eq!(
+        config.raw_value("remote", Some("origin".into()), "url")?,
+        Cow::<[u8]>::Borrowed(b"b")
+    );
+    assert_eq!(config.num_values(), 2);
+
+    assert!(
+        File::from_env_vars(&mut |_| None, Default::default())?.is_none(),
+        "the process environment isn't used"
+    );
+    Ok(())
+}
+
 #[test]
 #[serial]
 fn parse_error_with_invalid_count() {
@@ -0,0 +1,67 @@
+use std::fs;
+
+use gix_config::File;
+use gix_testtools::tempfile::tempdir;
+
+use crate::file::{
+    cow_str,
+    init::from_paths::{escape_backslashes, includes::conditional::options_with_git_dir},
+};
+
+#[test]
+fn remote_urls_of_the_entire_configuration_are_matched() -> crate::Result {
+    let dir = tempdir()?;
+    let remote_path = dir.path().join("remote");
+    fs::write(
+        &remote_path,
+        "[remote \"origin\"]\n  url = https://example.com/org/repo.git",
+    )?;
+    let work_path = dir.path().join("work");
+    fs::write(
+        &work_path,
+        "[user]\n  email = work@example.com\n[remote \"other\"]\n  url = https://other.example.com/repo.git",
+    )?;
+    let never_path = dir.path().join("never");
+    fs::write(&never_path, "[user]\n  email = never@example.com")?;
+
+    let config_path = dir.path().join("config");
+    fs::write(
+        &config_path,
+        format!(
+            r#"
+[user]
+  email = base@example.com
+[includeIf "hasconfig:remote.*.url:https://example.com/**"]
+  path = {work}
+[includeIf "hasconfig:remote.*.url:https://example.com/*"]
+  path = {never}
+[includeIf "hasconfig:remote.*.url:https://other.example.com/**"]
+  path = {never}
+[includeIf "hasconfig:remote.origin.url:https://example.com/**"]
+  path = {never}
+[include]
+  path = {remote}"#,
+            work = escape_backslashes(&work_path),
+            never = escape_backslashes(&never_path),
+            remote = escape_backslashes(&remote_path),
+        ),
+    )?;
+
+    let git_dir = dir.path().join(".git");
+    let config = File::from_paths_metadata(
+        Some(gix_config::file::Metadata::try_from_path(
+            &config_path,
+            gix_config::Source::Local,
+        )?),
+        options_with_git_dir(&git_dir),
+    )?
+    .expect("non-empty");
+
+    assert_eq!(
+        config.strings("user", None, "email"),
+        Some(vec![cow_str("base@example.com"), cow_str("work@example.com")]),
+        "URLs of later includes are considered, `*` doesn't match slashes, \
+         URLs from files included by `hasconfig` are ignored, and only the `remote.*.url` form is supported"
+    );
+    Ok(())
+}
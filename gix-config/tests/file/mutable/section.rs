@@ -63,6 +63,64 @@ mod remove {
     }
 }
 
+mod remove_matching {
+    use crate::file::cow_str;
+
+    #[test]
+    fn only_matching_values_are_removed_including_implicit_ones() -> crate::Result {
+        let mut config: gix_config::File = "[a]\n\tk = 1\n\to = x\n\tk = 2\n\tk\n\tK = \"1\"".parse()?;
+        let mut section = config.section_mut("a", None)?;
+        assert_eq!(section.remove_matching("k", |v| v == Some("1".into())), 2);
+        assert_eq!(section.values("k"), &[cow_str("2"), cow_str("")]);
+
+        assert_eq!(
+            section.remove_matching("k", |v| v.is_none()),
+            1,
+            "implicit values are passed as `None`"
+        );
+        assert_eq!(section.remove_matching("k", |_| false), 0);
+        assert_eq!(config.to_string(), "[a]\n\to = x\n\tk = 2\n");
+        Ok(())
+    }
+}
+
+mod replace_matching {
+    use std::convert::TryInto;
+
+    #[test]
+    fn the_last_match_is_replaced_and_the_others_are_removed() -> crate::Result {
+        let mut config: gix_config::File = "[a]\n\tk = 1\n\to = x\n\tk = 2\n\tk = \"1\"\n\tz = y".parse()?;
+        let mut section = config.section_mut("a", None)?;
+        assert_eq!(
+            section.replace_matching("k".try_into()?, "new".into(), |v| v == Some("1".into())),
+            2,
+            "values are normalized before matching"
+        );
+        assert_eq!(config.to_string(), "[a]\n\to = x\n\tk = 2\n\tk = new\n\tz = y\n");
+
+        let mut section = config.section_mut("a", None)?;
+        assert_eq!(section.replace_matching("k".try_into()?, "other".into(), |_| false), 0);
+        assert_eq!(
+            config.to_string(),
+            "[a]\n\to = x\n\tk = 2\n\tk = new\n\tz = y\n",
+            "nothing changes without a match"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn implicit_values_can_be_replaced() -> crate::Result {
+        let mut config: gix_config::File = "[a]\n\tk\n\tz".parse()?;
+        let mut section = config.section_mut("a", None)?;
+        assert_eq!(
+            section.replace_matching("k".try_into()?, "v".into(), |v| v.is_none()),
+            1
+        );
+        assert_eq!(config.to_string(), "[a]\n\tk=v\n\tz\n");
+        Ok(())
+    }
+}
+
 mod pop {
     use super::multi_value_section;
 
@@ -0,0 +1,51 @@
+use std::path::Path;
+
+use gix_config::Source;
+
+#[test]
+fn storage_location_environment_overrides() {
+    let mut env_var = |name: &str| {
+        match name {
+            "GIT_CONFIG_SYSTEM" => Some("system-override"),
+            "GIT_CONFIG_GLOBAL" => Some("global-override"),
+            _ => None,
+        }
+        .map(Into::into)
+    };
+    assert_eq!(
+        Source::System.storage_location(&mut env_var).as_deref(),
+        Some(Path::new("system-override"))
+    );
+    for source in [Source::Git, Source::User] {
+        assert_eq!(
+            source.storage_location(&mut env_var).as_deref(),
+            Some(Path::new("global-override")),
+            "all global sources use the same override, which is loaded only once"
+        );
+    }
+}
+
+#[test]
+fn storage_location_without_system_configuration() {
+    for (value, is_disabled) in [
+        ("1", true),
+        ("true", true),
+        ("yes", true),
+        ("0", false),
+        ("false", false),
+    ] {
+        let mut env_var = |name: &str| {
+            match name {
+                "GIT_CONFIG_NOSYSTEM" => Some(value),
+                "GIT_CONFIG_SYSTEM" => Some("system-override"),
+                _ => None,
+            }
+            .map(Into::into)
+        };
+        assert_eq!(
+            Source::System.storage_location(&mut env_var).is_none(),
+            is_disabled,
+            "GIT_CONFIG_NOSYSTEM={value} is interpreted as boolean"
+        );
+    }
+}
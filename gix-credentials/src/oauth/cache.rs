@@ -0,0 +1,141 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use serde_json::{json, Map, Value};
+
+use super::{Error, Token};
+
+/// Tokens by the host they are valid for, which are optionally persisted in a file.
+///
+/// Note that tokens are stored in plain text, which is why the file is only readable by its owner on unix.
+/// It is replaced atomically when saving, so readers never see a partially written file.
+#[derive(Debug, Default, Clone)]
+pub struct Cache {
+    path: Option<PathBuf>,
+    tokens: BTreeMap<String, Token>,
+}
+
+/// Initialization
+impl Cache {
+    /// Create a cache that only keeps tokens in memory.
+    pub fn in_memory() -> Self {
+        Cache::default()
+    }
+
+    /// Create a cache that is persisted at `path`, and load its tokens if the file exists.
+    pub fn at(path: impl Into<PathBuf>) -> Result<Self, Error> {
+        let path = path.into();
+        let tokens = match std::fs::read(&path) {
+            Ok(data) => decode(&data).map_err(|err| Error::CacheDecode {
+                path: path.clone(),
+                source: err,
+            })?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(err) => return Err(Error::CacheIo { path, source: err }),
+        };
+        Ok(Cache {
+            path: Some(path),
+            tokens,
+        })
+    }
+}
+
+/// Access
+impl Cache {
+    /// The path at which the cache is persisted, if it isn't only kept in memory.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Return the token for `host`, if present.
+    pub fn get(&self, host: &str) -> Option<&Token> {
+        self.tokens.get(host)
+    }
+
+    /// Set `token` for `host` and return the previous one, if present. Call [`save()`](Self::save()) to persist the change.
+    pub fn insert(&mut self, host: impl Into<String>, token: Token) -> Option<Token> {
+        self.tokens.insert(host.into(), token)
+    }
+
+    /// Remove the token for `host` and return it, if present. Call [`save()`](Self::save()) to persist the change.
+    pub fn remove(&mut self, host: &str) -> Option<Token> {
+        self.tokens.remove(host)
+    }
+
+    /// Write all tokens to our file, or do nothing if the cache is only kept in memory.
+    pub fn save(&self) -> Result<(), Error> {
+        let path = match self.path.as_deref() {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        let io_err = |err| Error::CacheIo {
+            path: path.to_owned(),
+            source: err,
+        };
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).map_err(io_err)?;
+        }
+        let mut lock = gix_lock::File::acquire_to_update_resource(path, gix_lock::acquire::Fail::Immediately, None)
+            .map_err(|err| Error::CacheLock {
+                path: path.to_owned(),
+                source: err,
+            })?;
+        // The file replaces the previous one, so it has these permissions no matter which ones the previous one had.
+        #[cfg(unix)]
+        lock.with_mut(|file| {
+            use std::os::unix::fs::PermissionsExt;
+            file.set_permissions(std::fs::Permissions::from_mode(0o600))
+        })
+        .map_err(io_err)?;
+        std::io::Write::write_all(&mut lock, &encode(&self.tokens)).map_err(io_err)?;
+        lock.commit().map_err(|err| io_err(err.error))?;
+        Ok(())
+    }
+}
+
+fn encode(tokens: &BTreeMap<String, Token>) -> Vec<u8> {
+    let tokens: Map<String, Value> = tokens
+        .iter()
+        .map(|(host, token)| {
+            let expires_at = token
+                .expires_at
+                .map(|at| at.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_secs());
+            (
+                host.clone(),
+                json!({
+                    "access_token": token.access_token,
+                    "refresh_token": token.refresh_token,
+                    "expires_at": expires_at,
+                }),
+            )
+        })
+        .collect();
+    serde_json::to_vec_pretty(&tokens).expect("in-memory values can always be serialized")
+}
+
+fn decode(data: &[u8]) -> Result<BTreeMap<String, Token>, serde_json::Error> {
+    let tokens: Map<String, Value> = serde_json::from_slice(data)?;
+    Ok(tokens
+        .into_iter()
+        .filter_map(|(host, token)| {
+            let access_token = token.get("access_token")?.as_str()?.to_owned();
+            Some((
+                host,
+                Token {
+                    access_token,
+                    refresh_token: token
+                        .get("refresh_token")
+                        .and_then(Value::as_str)
+                        .map(ToOwned::to_owned),
+                    expires_at: token
+                        .get("expires_at")
+                        .and_then(Value::as_u64)
+                        .map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs)),
+                },
+            ))
+        })
+        .collect())
+}
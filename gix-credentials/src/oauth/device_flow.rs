@@ -0,0 +1,136 @@
+//! The individual steps of the device authorization grant, for use if more control than offered by the [`Provider`](super::Provider)
+//! is needed.
+use std::time::{Duration, SystemTime};
+
+use serde_json::{Map, Value};
+
+use super::{DeviceAuthorization, Error, Forge, Http, Token};
+
+/// The interval to wait between polls if the server doesn't specify it.
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(5);
+/// The amount of time by which the interval has to be increased if the server asks us to slow down.
+const SLOW_DOWN_INCREMENT: Duration = Duration::from_secs(5);
+
+/// Request a device and user code from `forge` using `http`, to let the user authorize the device.
+pub fn authorize(http: &mut dyn Http, forge: &Forge) -> Result<DeviceAuthorization, Error> {
+    let url = &forge.device_authorization_url;
+    let scope = forge.scopes.join(" ");
+    let response = post(http, url, &[("client_id", &forge.client_id), ("scope", &scope)])?;
+    server_error(&response)?;
+    Ok(DeviceAuthorization {
+        device_code: string(&response, url, "device_code")?,
+        user_code: string(&response, url, "user_code")?,
+        // Some servers deviate from RFC 8628 and use the name from an earlier draft.
+        verification_uri: string(&response, url, "verification_uri")
+            .or_else(|_| string(&response, url, "verification_url"))?,
+        expires_in: seconds(&response, "expires_in").ok_or_else(|| Error::MissingField {
+            url: url.clone(),
+            field: "expires_in",
+        })?,
+        interval: seconds(&response, "interval").unwrap_or(DEFAULT_INTERVAL),
+    })
+}
+
+/// Poll `forge` using `http` until the user authorized the device described by `authorization`, and return the token.
+///
+/// `sleep` is called to wait between polls, and is typically [`std::thread::sleep()`].
+pub fn poll(
+    http: &mut dyn Http,
+    forge: &Forge,
+    authorization: &DeviceAuthorization,
+    mut sleep: impl FnMut(Duration),
+) -> Result<Token, Error> {
+    let url = &forge.token_url;
+    let mut interval = authorization.interval;
+    let mut waited = Duration::default();
+    loop {
+        if waited >= authorization.expires_in {
+            return Err(Error::Expired);
+        }
+        sleep(interval);
+        waited += interval;
+        let response = post(
+            http,
+            url,
+            &[
+                ("client_id", &forge.client_id),
+                ("device_code", &authorization.device_code),
+                ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+            ],
+        )?;
+        match response.get("error").and_then(Value::as_str) {
+            Some("authorization_pending") => continue,
+            Some("slow_down") => interval += SLOW_DOWN_INCREMENT,
+            Some("access_denied") => return Err(Error::AccessDenied),
+            Some("expired_token") => return Err(Error::Expired),
+            Some(_) => return Err(server_error(&response).expect_err("error is present")),
+            None => return token(&response, url, None),
+        }
+    }
+}
+
+/// Use `refresh_token` to obtain a new token from `forge` using `http`.
+///
+/// If the server doesn't hand out a new refresh token, `refresh_token` will be kept in the returned token.
+pub fn refresh(http: &mut dyn Http, forge: &Forge, refresh_token: &str) -> Result<Token, Error> {
+    let url = &forge.token_url;
+    let response = post(
+        http,
+        url,
+        &[
+            ("client_id", &forge.client_id),
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+        ],
+    )?;
+    server_error(&response)?;
+    token(&response, url, Some(refresh_token))
+}
+
+fn post(http: &mut dyn Http, url: &str, form: &[(&str, &str)]) -> Result<Map<String, Value>, Error> {
+    let body = http.post_form(url, form).map_err(|err| Error::Http {
+        url: url.into(),
+        source: err,
+    })?;
+    serde_json::from_slice(&body).map_err(|err| Error::Decode {
+        url: url.into(),
+        source: err,
+    })
+}
+
+fn server_error(response: &Map<String, Value>) -> Result<(), Error> {
+    match response.get("error").and_then(Value::as_str) {
+        Some(error) => Err(Error::Server {
+            error: error.into(),
+            description: response
+                .get("error_description")
+                .and_then(Value::as_str)
+                .map(ToOwned::to_owned),
+        }),
+        None => Ok(()),
+    }
+}
+
+fn token(response: &Map<String, Value>, url: &str, previous_refresh_token: Option<&str>) -> Result<Token, Error> {
+    Ok(Token {
+        access_token: string(response, url, "access_token")?,
+        refresh_token: response
+            .get("refresh_token")
+            .and_then(Value::as_str)
+            .or(previous_refresh_token)
+            .map(ToOwned::to_owned),
+        expires_at: seconds(response, "expires_in").map(|expires_in| SystemTime::now() + expires_in),
+    })
+}
+
+fn string(response: &Map<String, Value>, url: &str, field: &'static str) -> Result<String, Error> {
+    response
+        .get(field)
+        .and_then(Value::as_str)
+        .map(ToOwned::to_owned)
+        .ok_or_else(|| Error::MissingField { url: url.into(), field })
+}
+
+fn seconds(response: &Map<String, Value>, field: &str) -> Option<Duration> {
+    response.get(field).and_then(Value::as_u64).map(Duration::from_secs)
+}
@@ -0,0 +1,147 @@
+//! Obtain access tokens for known forges with the [OAuth 2.0 device authorization grant](https://www.rfc-editor.org/rfc/rfc8628),
+//! similar to what `git-credential-manager` does, but in-process.
+//!
+//! The [`Provider`] can be used in place of a credential helper. It obtains tokens by asking the user to visit a URL and enter
+//! a code, and caches them for later use. HTTP requests are performed by an implementation of [`Http`] to not tie this crate
+//! to a particular HTTP client.
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+/// The endpoints and client information needed to obtain tokens from a forge.
+///
+/// Presets exist for some well-known forges, others like self-hosted instances or Bitbucket deployments with an authorization
+/// server that supports the device authorization grant can be configured by setting all fields.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Forge {
+    /// The host for which credentials are provided, including the port if it isn't the default one, like `github.com`.
+    pub host: String,
+    /// The id of the OAuth application registered with the forge.
+    pub client_id: String,
+    /// The URL at which to request device and user codes.
+    pub device_authorization_url: String,
+    /// The URL at which to obtain or refresh tokens.
+    pub token_url: String,
+    /// The scopes to request, which must allow to read and write repositories.
+    pub scopes: Vec<String>,
+    /// The username to use along with the token as password.
+    pub username: String,
+}
+
+/// Initialization
+impl Forge {
+    /// Endpoints for `github.com`, with an application identified by `client_id`.
+    pub fn github(client_id: impl Into<String>) -> Self {
+        Forge {
+            host: "github.com".into(),
+            client_id: client_id.into(),
+            device_authorization_url: "https://github.com/login/device/code".into(),
+            token_url: "https://github.com/login/oauth/access_token".into(),
+            scopes: vec!["repo".into(), "workflow".into()],
+            username: "oauth2".into(),
+        }
+    }
+
+    /// Endpoints for `gitlab.com`, with an application identified by `client_id`.
+    ///
+    /// Self-hosted instances can be configured by changing the `host` and the URLs accordingly.
+    pub fn gitlab(client_id: impl Into<String>) -> Self {
+        Forge {
+            host: "gitlab.com".into(),
+            client_id: client_id.into(),
+            device_authorization_url: "https://gitlab.com/oauth/authorize_device".into(),
+            token_url: "https://gitlab.com/oauth/token".into(),
+            scopes: vec!["read_repository".into(), "write_repository".into()],
+            username: "oauth2".into(),
+        }
+    }
+}
+
+/// A way to perform HTTP requests on behalf of the device authorization flow.
+pub trait Http {
+    /// Send a `POST` request to `url` with `form` encoded as `application/x-www-form-urlencoded` body, and return the body
+    /// of the response.
+    ///
+    /// The request must accept `application/json` responses. Note that the body must also be returned if the response
+    /// indicates an error through its status code, as the error details are contained in it.
+    fn post_form(&mut self, url: &str, form: &[(&str, &str)]) -> std::io::Result<Vec<u8>>;
+}
+
+impl<F> Http for F
+where
+    F: FnMut(&str, &[(&str, &str)]) -> std::io::Result<Vec<u8>>,
+{
+    fn post_form(&mut self, url: &str, form: &[(&str, &str)]) -> std::io::Result<Vec<u8>> {
+        self(url, form)
+    }
+}
+
+/// The information the user needs to authorize the device, as returned by [`device_flow::authorize()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceAuthorization {
+    /// The code to identify the device when polling for the token.
+    pub device_code: String,
+    /// The code the user has to enter at `verification_uri`.
+    pub user_code: String,
+    /// The URL the user has to visit to authorize the device.
+    pub verification_uri: String,
+    /// The time after which the codes expire.
+    pub expires_in: Duration,
+    /// The time to wait between polling for the token.
+    pub interval: Duration,
+}
+
+/// An access token along with the information to refresh it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    /// The token to use as password.
+    pub access_token: String,
+    /// A token to obtain a new access token once it expired, if the forge supports it.
+    pub refresh_token: Option<String>,
+    /// The time at which `access_token` expires, or `None` if it doesn't expire.
+    pub expires_at: Option<SystemTime>,
+}
+
+impl Token {
+    /// Return `true` if the token is expired at `now`.
+    pub fn is_expired(&self, now: SystemTime) -> bool {
+        self.expires_at.map_or(false, |expires_at| expires_at <= now)
+    }
+}
+
+/// The error returned by [`device_flow`] functions, the [`Cache`] and the [`Provider`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not send request to '{url}'")]
+    Http { url: String, source: std::io::Error },
+    #[error("Could not decode the response of '{url}'")]
+    Decode { url: String, source: serde_json::Error },
+    #[error("The response of '{url}' lacked the '{field}' field")]
+    MissingField { url: String, field: &'static str },
+    #[error("The user denied the authorization request")]
+    AccessDenied,
+    #[error("The device code expired before the user authorized the request")]
+    Expired,
+    #[error("The server responded with error '{error}'{}", description.as_ref().map(|d| format!(": {d}")).unwrap_or_default())]
+    Server { error: String, description: Option<String> },
+    #[error("Could not read or write the token cache at '{}'", path.display())]
+    CacheIo { path: PathBuf, source: std::io::Error },
+    #[error("Could not lock the token cache at '{}' for writing", path.display())]
+    CacheLock {
+        path: PathBuf,
+        source: gix_lock::acquire::Error,
+    },
+    #[error("Could not decode the token cache at '{}'", path.display())]
+    CacheDecode { path: PathBuf, source: serde_json::Error },
+}
+
+///
+pub mod device_flow;
+
+mod cache;
+pub use cache::Cache;
+
+mod provider;
+pub use provider::Provider;
@@ -0,0 +1,123 @@
+use std::time::{Duration, SystemTime};
+
+use super::{device_flow, Cache, DeviceAuthorization, Error, Forge, Http, Token};
+use crate::{helper, protocol, protocol::Context};
+
+/// An in-process credential provider that obtains tokens for known [forges](Forge) using the device authorization grant,
+/// and caches them for later use.
+///
+/// Use [`invoke()`](Self::invoke()) like any other credential helper.
+pub struct Provider<H> {
+    /// The implementation to perform HTTP requests with.
+    pub http: H,
+    /// The forges to provide credentials for, with the first one matching the host of the requested URL being used.
+    pub forges: Vec<Forge>,
+    /// The tokens obtained so far.
+    pub cache: Cache,
+    /// Called to tell the user which URL to visit and which code to enter there to authorize the device.
+    pub on_authorization: Box<dyn FnMut(&DeviceAuthorization)>,
+    /// Called to wait between polls for the token, [`std::thread::sleep()`] by default.
+    pub sleep: fn(Duration),
+}
+
+/// Initialization
+impl<H: Http> Provider<H> {
+    /// Create a new instance to perform requests with `http` to obtain tokens for `forges`, storing them in `cache`.
+    ///
+    /// `on_authorization` is called to ask the user to authorize the device if no usable token is cached.
+    pub fn new(
+        http: H,
+        forges: Vec<Forge>,
+        cache: Cache,
+        on_authorization: impl FnMut(&DeviceAuthorization) + 'static,
+    ) -> Self {
+        Provider {
+            http,
+            forges,
+            cache,
+            on_authorization: Box::new(on_authorization),
+            sleep: std::thread::sleep,
+        }
+    }
+}
+
+/// Credential helper functionality
+impl<H: Http> Provider<H> {
+    /// Perform `action` like a credential helper would.
+    ///
+    /// When getting credentials, a cached token is used unless it expired, in which case it is refreshed if possible.
+    /// Otherwise, the user is asked to authorize the device. Getting credentials for hosts without a matching forge fails
+    /// as the identity is missing.
+    /// Storing credentials does nothing as tokens are cached as soon as they are obtained, and erasing credentials removes
+    /// the token for the host from the cache.
+    #[allow(clippy::result_large_err)]
+    pub fn invoke(&mut self, action: helper::Action) -> protocol::Result {
+        match action {
+            helper::Action::Get(mut ctx) => {
+                if ctx.url.is_some() {
+                    ctx.destructure_url_in_place(false)?;
+                }
+                let forge = match ctx
+                    .host
+                    .as_deref()
+                    .and_then(|host| self.forges.iter().find(|forge| forge.host == host))
+                {
+                    Some(forge) => forge.clone(),
+                    None => return Err(protocol::Error::IdentityMissing { context: ctx }),
+                };
+                let token = self.token(&forge).map_err(into_protocol_error)?;
+                let identity = gix_sec::identity::Account {
+                    username: forge.username,
+                    password: token.access_token,
+                };
+                ctx.username = Some(identity.username.clone());
+                ctx.password = Some(identity.password.clone());
+                Ok(Some(protocol::Outcome {
+                    identity,
+                    next: ctx.into(),
+                }))
+            }
+            helper::Action::Store(_) => Ok(None),
+            helper::Action::Erase(payload) => {
+                let ctx = Context::from_bytes(&payload)?;
+                if let Some(host) = ctx.host {
+                    if self.cache.remove(&host).is_some() {
+                        self.cache.save().map_err(into_protocol_error)?;
+                    }
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    /// Return a usable token for `forge`, refreshing or obtaining a new one if needed.
+    fn token(&mut self, forge: &Forge) -> Result<Token, Error> {
+        if let Some(cached) = self.cache.get(&forge.host) {
+            if !cached.is_expired(SystemTime::now()) {
+                return Ok(cached.clone());
+            }
+            if let Some(refresh_token) = cached.refresh_token.clone() {
+                // Refresh tokens may expire or be revoked, so it's not an error if this doesn't work.
+                if let Ok(token) = device_flow::refresh(&mut self.http, forge, &refresh_token) {
+                    return self.store(forge, token);
+                }
+            }
+        }
+        let authorization = device_flow::authorize(&mut self.http, forge)?;
+        (self.on_authorization)(&authorization);
+        let token = device_flow::poll(&mut self.http, forge, &authorization, self.sleep)?;
+        self.store(forge, token)
+    }
+
+    fn store(&mut self, forge: &Forge, token: Token) -> Result<Token, Error> {
+        self.cache.insert(forge.host.clone(), token.clone());
+        self.cache.save()?;
+        Ok(token)
+    }
+}
+
+fn into_protocol_error(err: Error) -> protocol::Error {
+    protocol::Error::InvokeHelper(helper::Error::CredentialsHelperFailed {
+        source: std::io::Error::new(std::io::ErrorKind::Other, err),
+    })
+}
@@ -1,5 +1,7 @@
 pub use gix_testtools::Result;
 
 mod helper;
+#[cfg(feature = "oauth")]
+mod oauth;
 mod program;
 mod protocol;
@@ -0,0 +1,247 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, SystemTime},
+};
+
+use gix_credentials::{
+    helper,
+    oauth::{device_flow, Cache, Error, Forge, Http, Provider, Token},
+};
+
+/// A server that replies with predefined responses and records all requests.
+#[derive(Default)]
+struct Server {
+    responses: VecDeque<&'static str>,
+    requests: Vec<(String, Vec<(String, String)>)>,
+}
+
+impl Server {
+    fn new(responses: impl IntoIterator<Item = &'static str>) -> Self {
+        Server {
+            responses: responses.into_iter().collect(),
+            requests: Vec::new(),
+        }
+    }
+
+    fn request_urls(&self) -> Vec<&str> {
+        self.requests.iter().map(|(url, _)| url.as_str()).collect()
+    }
+
+    fn form_value(&self, request: usize, name: &str) -> Option<&str> {
+        self.requests[request]
+            .1
+            .iter()
+            .find_map(|(key, value)| (key == name).then_some(value.as_str()))
+    }
+}
+
+impl Http for Server {
+    fn post_form(&mut self, url: &str, form: &[(&str, &str)]) -> std::io::Result<Vec<u8>> {
+        self.requests.push((
+            url.into(),
+            form.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        ));
+        Ok(self.responses.pop_front().expect("enough responses").into())
+    }
+}
+
+const AUTHORIZATION: &str = r#"{"device_code":"device","user_code":"USER-CODE","verification_uri":"https://example.com/device","expires_in":900,"interval":5}"#;
+const PENDING: &str = r#"{"error":"authorization_pending"}"#;
+const TOKEN: &str = r#"{"access_token":"token","refresh_token":"refresh","expires_in":3600,"token_type":"bearer"}"#;
+
+fn forge() -> Forge {
+    Forge::github("client")
+}
+
+mod device_flow_ {
+    use super::*;
+
+    #[test]
+    fn polls_until_authorized_and_slows_down_if_asked() -> crate::Result {
+        let mut server = Server::new([AUTHORIZATION, PENDING, r#"{"error":"slow_down"}"#, PENDING, TOKEN]);
+        let authorization = device_flow::authorize(&mut server, &forge())?;
+        assert_eq!(authorization.user_code, "USER-CODE");
+        assert_eq!(authorization.verification_uri, "https://example.com/device");
+        assert_eq!(authorization.interval, Duration::from_secs(5));
+        assert_eq!(server.form_value(0, "client_id"), Some("client"));
+        assert_eq!(server.form_value(0, "scope"), Some("repo workflow"));
+
+        let mut sleeps = Vec::new();
+        let token = device_flow::poll(&mut server, &forge(), &authorization, |d| sleeps.push(d.as_secs()))?;
+        assert_eq!(token.access_token, "token");
+        assert_eq!(token.refresh_token.as_deref(), Some("refresh"));
+        assert!(!token.is_expired(SystemTime::now()));
+        assert_eq!(
+            sleeps,
+            [5, 5, 10, 10],
+            "the interval increases after the server asked to slow down"
+        );
+        assert_eq!(server.form_value(1, "device_code"), Some("device"));
+        assert_eq!(
+            server.form_value(1, "grant_type"),
+            Some("urn:ietf:params:oauth:grant-type:device_code")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn denial_and_expiry_are_errors() -> crate::Result {
+        let mut server = Server::new([AUTHORIZATION, r#"{"error":"access_denied"}"#]);
+        let authorization = device_flow::authorize(&mut server, &forge())?;
+        let err = device_flow::poll(&mut server, &forge(), &authorization, |_| {}).unwrap_err();
+        assert!(matches!(err, Error::AccessDenied));
+
+        let mut server = Server::new([
+            r#"{"device_code":"device","user_code":"code","verification_uri":"https://example.com/device","expires_in":10}"#,
+            PENDING,
+            PENDING,
+        ]);
+        let authorization = device_flow::authorize(&mut server, &forge())?;
+        let err = device_flow::poll(&mut server, &forge(), &authorization, |_| {}).unwrap_err();
+        assert!(
+            matches!(err, Error::Expired),
+            "we stop once the code expired, using the default interval of 5s"
+        );
+        assert_eq!(server.requests.len(), 3);
+
+        let mut server = Server::new([r#"{"error":"invalid_client","error_description":"unknown client"}"#]);
+        let err = device_flow::authorize(&mut server, &forge()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The server responded with error 'invalid_client': unknown client"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn refresh_keeps_the_previous_refresh_token_if_there_is_no_new_one() -> crate::Result {
+        let mut server = Server::new([r#"{"access_token":"new"}"#]);
+        let token = device_flow::refresh(&mut server, &forge(), "refresh")?;
+        assert_eq!(
+            token,
+            Token {
+                access_token: "new".into(),
+                refresh_token: Some("refresh".into()),
+                expires_at: None,
+            }
+        );
+        assert_eq!(server.form_value(0, "grant_type"), Some("refresh_token"));
+        Ok(())
+    }
+}
+
+mod provider {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+
+    fn provider(server: Server, cache: Cache) -> (Provider<Server>, Rc<RefCell<Vec<String>>>) {
+        let user_codes = Rc::new(RefCell::new(Vec::new()));
+        let mut provider = Provider::new(server, vec![forge()], cache, {
+            let user_codes = user_codes.clone();
+            move |authorization| user_codes.borrow_mut().push(authorization.user_code.clone())
+        });
+        provider.sleep = |_| {};
+        (provider, user_codes)
+    }
+
+    #[test]
+    fn tokens_are_obtained_once_and_cached_until_erased() -> crate::Result {
+        let dir = gix_testtools::tempfile::tempdir()?;
+        let cache_path = dir.path().join("oauth").join("tokens.json");
+        let (mut provider, user_codes) =
+            provider(Server::new([AUTHORIZATION, PENDING, TOKEN]), Cache::at(&cache_path)?);
+
+        let outcome = provider
+            .invoke(helper::Action::get_for_url("https://github.com/org/repo"))?
+            .expect("identity");
+        assert_eq!(outcome.identity.username, "oauth2");
+        assert_eq!(outcome.identity.password, "token");
+        assert_eq!(*user_codes.borrow(), ["USER-CODE"], "the user was asked to authorize");
+        assert_eq!(provider.http.requests.len(), 3);
+
+        let outcome = provider
+            .invoke(helper::Action::get_for_url("https://github.com/org/other"))?
+            .expect("identity");
+        assert_eq!(outcome.identity.password, "token");
+        assert_eq!(provider.http.requests.len(), 3, "the cached token was used");
+        assert_eq!(
+            Cache::at(&cache_path)?
+                .get("github.com")
+                .map(|t| t.access_token.as_str()),
+            Some("token"),
+            "tokens are persisted"
+        );
+
+        assert!(provider.invoke(outcome.next.clone().store())?.is_none());
+        assert!(provider.invoke(outcome.next.erase())?.is_none());
+        assert!(provider.cache.get("github.com").is_none());
+        assert!(
+            Cache::at(&cache_path)?.get("github.com").is_none(),
+            "erasing removes the token from disk as well"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn cache_files_are_only_readable_by_their_owner() -> crate::Result {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = gix_testtools::tempfile::tempdir()?;
+        let cache_path = dir.path().join("tokens.json");
+        std::fs::write(&cache_path, "{}")?;
+        std::fs::set_permissions(&cache_path, std::fs::Permissions::from_mode(0o644))?;
+
+        let mut cache = Cache::at(&cache_path)?;
+        cache.insert(
+            "github.com",
+            Token {
+                access_token: "token".into(),
+                refresh_token: None,
+                expires_at: None,
+            },
+        );
+        cache.save()?;
+        assert_eq!(
+            std::fs::metadata(&cache_path)?.permissions().mode() & 0o777,
+            0o600,
+            "existing files are replaced with one that has restricted permissions"
+        );
+        assert!(
+            !cache_path.with_extension("json.lock").exists(),
+            "the lock file was committed"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn expired_tokens_are_refreshed() -> crate::Result {
+        let mut cache = Cache::in_memory();
+        cache.insert(
+            "github.com",
+            Token {
+                access_token: "expired".into(),
+                refresh_token: Some("refresh".into()),
+                expires_at: Some(SystemTime::now() - Duration::from_secs(1)),
+            },
+        );
+        let (mut provider, user_codes) = provider(Server::new([TOKEN]), cache);
+        let outcome = provider
+            .invoke(helper::Action::get_for_url("https://github.com/org/repo"))?
+            .expect("identity");
+        assert_eq!(outcome.identity.password, "token");
+        assert!(user_codes.borrow().is_empty(), "no user interaction is needed");
+        assert_eq!(provider.http.request_urls(), [forge().token_url.as_str()]);
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_hosts_have_no_identity() {
+        let (mut provider, _) = provider(Server::default(), Cache::in_memory());
+        let err = provider
+            .invoke(helper::Action::get_for_url("https://example.com/org/repo"))
+            .unwrap_err();
+        assert!(matches!(err, gix_credentials::protocol::Error::IdentityMissing { .. }));
+        assert!(provider.http.requests.is_empty());
+    }
+}
@@ -3,6 +3,8 @@
 //! With the `fast-sha1` feature, the `Sha1` hash type will use a more elaborate implementation utilizing hardware support
 //! in case it is available. Otherwise the `rustsha1` feature should be set. `fast-sha1` will take precedence.
 //! Otherwise, a minimal yet performant implementation is used instead for a decent trade-off between compile times and run-time performance.
+//!
+//! `Sha256` is available with either of these features, and the [`Hasher`] can produce any of the supported hashes.
 #[cfg(all(feature = "rustsha1", not(feature = "fast-sha1")))]
 mod _impl {
     use super::Sha1Digest;
@@ -52,6 +54,62 @@ mod _impl {
 #[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
 pub use _impl::Sha1;
 
+/// A 32 bytes digest produced by a [`Sha256`] hash implementation.
+#[cfg(any(feature = "fast-sha1", feature = "rustsha1"))]
+pub type Sha256Digest = [u8; 32];
+
+#[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
+mod sha256 {
+    use sha2::Digest;
+
+    use super::Sha256Digest;
+
+    /// A implementation of the Sha256 hash, which can be used once.
+    #[derive(Default, Clone)]
+    pub struct Sha256(sha2::Sha256);
+
+    impl Sha256 {
+        /// Digest the given `bytes`.
+        pub fn update(&mut self, bytes: &[u8]) {
+            self.0.update(bytes)
+        }
+        /// Finalize the hash and produce a digest.
+        pub fn digest(self) -> Sha256Digest {
+            self.0.finalize().into()
+        }
+    }
+}
+#[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
+pub use sha256::Sha256;
+
+/// A hash implementation for any of the supported [kinds of hashes](gix_hash::Kind), which can be used once.
+#[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
+#[derive(Clone)]
+pub enum Hasher {
+    /// The Sha1 hash.
+    Sha1(Sha1),
+    /// The Sha256 hash.
+    Sha256(Sha256),
+}
+
+#[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
+impl Hasher {
+    /// Digest the given `bytes`.
+    pub fn update(&mut self, bytes: &[u8]) {
+        match self {
+            Hasher::Sha1(h) => h.update(bytes),
+            Hasher::Sha256(h) => h.update(bytes),
+        }
+    }
+    /// Finalize the hash and produce an object id of the kind of this hash.
+    pub fn digest(self) -> gix_hash::ObjectId {
+        match self {
+            Hasher::Sha1(h) => h.digest().into(),
+            Hasher::Sha256(h) => h.digest().into(),
+        }
+    }
+}
+
 /// Compute a CRC32 hash from the given `bytes`, returning the CRC32 hash.
 ///
 /// When calling this function for the first time, `previous_value` should be `0`. Otherwise it
@@ -76,9 +134,10 @@ pub fn crc32(bytes: &[u8]) -> u32 {
 
 /// Produce a hasher suitable for the given kind of hash.
 #[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
-pub fn hasher(kind: gix_hash::Kind) -> Sha1 {
+pub fn hasher(kind: gix_hash::Kind) -> Hasher {
     match kind {
-        gix_hash::Kind::Sha1 => Sha1::default(),
+        gix_hash::Kind::Sha1 => Hasher::Sha1(Sha1::default()),
+        gix_hash::Kind::Sha256 => Hasher::Sha256(Sha256::default()),
     }
 }
 
@@ -127,7 +186,7 @@ pub fn bytes(
 pub fn bytes_with_hasher(
     read: &mut dyn std::io::Read,
     num_bytes_from_start: u64,
-    mut hasher: Sha1,
+    mut hasher: Hasher,
     progress: &mut dyn crate::progress::Progress,
     should_interrupt: &std::sync::atomic::AtomicBool,
 ) -> std::io::Result<gix_hash::ObjectId> {
@@ -153,19 +212,19 @@ pub fn bytes_with_hasher(
         }
     }
 
-    let id = gix_hash::ObjectId::from(hasher.digest());
+    let id = hasher.digest();
     progress.show_throughput(start);
     Ok(id)
 }
 
 #[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
 mod write {
-    use crate::hash::Sha1;
+    use crate::hash::Hasher;
 
     /// A utility to automatically generate a hash while writing into an inner writer.
     pub struct Write<T> {
         /// The hash implementation.
-        pub hash: Sha1,
+        pub hash: Hasher,
         /// The inner writer.
         pub inner: T,
     }
@@ -191,11 +250,9 @@ mod write {
     {
         /// Create a new hash writer which hashes all bytes written to `inner` with a hash of `kind`.
         pub fn new(inner: T, object_hash: gix_hash::Kind) -> Self {
-            match object_hash {
-                gix_hash::Kind::Sha1 => Write {
-                    inner,
-                    hash: Sha1::default(),
-                },
+            Write {
+                inner,
+                hash: crate::hash::hasher(object_hash),
             }
         }
     }
@@ -7,17 +7,22 @@ const BUF_SIZE: usize = 4096 * 8;
 /// Be sure to call `flush()` when done to finalize the deflate stream.
 pub struct Write<W> {
     compressor: Compress,
+    level: u32,
     inner: W,
     buf: [u8; BUF_SIZE],
 }
 
+/// The compression level used by [`Write::new()`], which favors speed over size, just like git does for loose objects.
+pub const DEFAULT_LEVEL: u32 = 1;
+
 impl<W> Clone for Write<W>
 where
     W: Clone,
 {
     fn clone(&self) -> Self {
         Write {
-            compressor: impls::new_compress(),
+            compressor: impls::new_compress(self.level),
+            level: self.level,
             inner: self.inner.clone(),
             buf: self.buf,
         }
@@ -31,18 +36,27 @@ mod impls {
 
     use crate::zlib::stream::deflate;
 
-    pub(crate) fn new_compress() -> Compress {
-        Compress::new(Compression::fast(), true)
+    pub(crate) fn new_compress(level: u32) -> Compress {
+        Compress::new(Compression::new(level), true)
     }
 
     impl<W> deflate::Write<W>
     where
         W: io::Write,
     {
-        /// Create a new instance writing compressed bytes to `inner`.
+        /// Create a new instance writing compressed bytes to `inner`, using the [default level](deflate::DEFAULT_LEVEL).
         pub fn new(inner: W) -> deflate::Write<W> {
+            Self::with_level(inner, deflate::DEFAULT_LEVEL)
+        }
+
+        /// Create a new instance writing bytes compressed with `level` to `inner`.
+        ///
+        /// `level` ranges from 0 for no compression to 9 for the best compression, and is clamped to that range.
+        pub fn with_level(inner: W, level: u32) -> deflate::Write<W> {
+            let level = level.min(9);
             deflate::Write {
-                compressor: new_compress(),
+                compressor: new_compress(level),
+                level,
                 inner,
                 buf: [0; deflate::BUF_SIZE],
             }
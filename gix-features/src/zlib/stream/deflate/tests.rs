@@ -64,6 +64,22 @@ mod deflate_stream {
         assert_deflate_buffer(out, b"hello")
     }
 
+    #[test]
+    fn compression_levels() -> Result<(), Box<dyn std::error::Error>> {
+        let bytes = include_bytes!("mod.rs");
+        let mut sizes = Vec::new();
+        for level in [0, 1, 9] {
+            let mut w = deflate::Write::with_level(Vec::new(), level);
+            w.write_all(bytes)?;
+            w.flush()?;
+            sizes.push(w.inner.len());
+            assert_deflate_buffer(w.inner, bytes)?;
+        }
+        assert!(sizes[0] > bytes.len(), "level 0 stores the data as is");
+        assert!(sizes[2] <= sizes[1], "higher levels compress better");
+        Ok(())
+    }
+
     fn assert_deflate_buffer(out: Vec<u8>, expected: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
         let mut actual = Vec::new();
         InflateReader::from_read(out.as_slice()).read_to_end(&mut actual)?;
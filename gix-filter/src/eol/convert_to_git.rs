@@ -9,6 +9,17 @@ pub struct Options<'a> {
     pub config: crate::eol::Configuration,
 }
 
+/// A change to line endings that wouldn't survive a round-trip, i.e. the file would differ after being converted
+/// to `git` and checked out again, as reported by [`RoundTripCheck::Warn`] to
+/// [`convert_to_git_with_warnings()`][super::convert_to_git_with_warnings()].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum RoundTripWarning {
+    /// `CRLF` in the worktree would be replaced by `LF` once the file is checked out again.
+    CrlfWillBeReplacedByLf,
+    /// `LF` in the worktree would be replaced by `CRLF` once the file is checked out again.
+    LfWillBeReplacedByCrlf,
+}
+
 /// The kind of round-trip check to perform when converting line endings to `git`, i.e. `CRLF` to `LF`.
 #[derive(Debug, Copy, Clone)]
 pub enum RoundTripCheck<'a> {
@@ -47,7 +58,7 @@ pub(crate) mod function {
     use crate::{
         clear_and_set_capacity,
         eol::{
-            convert_to_git::{Error, Options, RoundTripCheck},
+            convert_to_git::{Error, Options, RoundTripCheck, RoundTripWarning},
             AttributesDigest, Stats,
         },
     };
@@ -60,6 +71,18 @@ pub(crate) mod function {
     /// outcome of this function.
     /// If `round_trip_check` is not `None`, round-tripping will be validated and handled accordingly.
     pub fn convert_to_git(
+        src: &[u8],
+        digest: AttributesDigest,
+        buf: &mut Vec<u8>,
+        index_object: &mut IndexObjectFn<'_>,
+        opts: Options<'_>,
+    ) -> Result<bool, Error> {
+        convert_to_git_with_warnings(src, digest, buf, index_object, opts, &mut |_| {})
+    }
+
+    /// Like [`convert_to_git()`], but call `on_warning` with each warning produced by a [`RoundTripCheck::Warn`],
+    /// in addition to it being traced.
+    pub fn convert_to_git_with_warnings(
         src: &[u8],
         digest: AttributesDigest,
         buf: &mut Vec<u8>,
@@ -68,6 +91,7 @@ pub(crate) mod function {
             round_trip_check,
             config,
         }: Options<'_>,
+        on_warning: &mut dyn FnMut(RoundTripWarning),
     ) -> Result<bool, Error> {
         if digest == AttributesDigest::Binary || src.is_empty() {
             return Ok(false);
@@ -119,7 +143,8 @@ pub(crate) mod function {
                         gix_trace::warn!(
                             "in the working copy of '{}', CRLF will be replaced by LF next time git touches it",
                             rela_path.display()
-                        )
+                        );
+                        on_warning(RoundTripWarning::CrlfWillBeReplacedByLf);
                     }
                 }
             } else if stats.lone_lf > 0 && new_stats.lone_lf == 0 {
@@ -136,7 +161,8 @@ pub(crate) mod function {
                         gix_trace::warn!(
                             "in the working copy of '{}', LF will be replaced by CRLF next time git touches it",
                             rela_path.display()
-                        )
+                        );
+                        on_warning(RoundTripWarning::LfWillBeReplacedByCrlf);
                     }
                 }
             }
@@ -1,6 +1,6 @@
 ///
 pub mod convert_to_git;
-pub use convert_to_git::function::convert_to_git;
+pub use convert_to_git::function::{convert_to_git, convert_to_git_with_warnings};
 
 mod convert_to_worktree;
 pub use convert_to_worktree::convert_to_worktree;
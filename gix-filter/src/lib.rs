@@ -44,6 +44,8 @@ pub struct Pipeline {
     processes: driver::State,
     /// A utility to handle multiple buffers to keep results of various filters.
     bufs: pipeline::util::Buffers,
+    /// The warning produced by the CRLF round-trip check of the last conversion to git, if any.
+    round_trip_warning: Option<eol::convert_to_git::RoundTripWarning>,
 }
 
 /// A declaration of a driver program.
@@ -68,6 +70,9 @@ pub struct Driver {
     /// exit code is ignored.
     /// Note that this is more of a suggestion as we will always report errors as they happen as the driver API is streaming in nature,
     /// which makes soft-failures impossible unless the caller takes precautions.
+    ///
+    /// The [`Pipeline`] takes these precautions just like `git`: if `true`, it's an error if the driver fails or has no program
+    /// for the operation, and otherwise the unfiltered content is used if the driver fails.
     pub required: bool,
 }
 
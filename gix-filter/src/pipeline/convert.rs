@@ -21,6 +21,8 @@ pub mod configuration {
 
 ///
 pub mod to_git {
+    use bstr::BString;
+
     /// A function that fills `buf` `fn(&mut buf)` with the data stored in the index of the file that should be converted.
     pub type IndexObjectFn<'a> =
         dyn FnMut(&mut Vec<u8>) -> Result<Option<()>, Box<dyn std::error::Error + Send + Sync>> + 'a;
@@ -39,11 +41,15 @@ pub mod to_git {
         Configuration(#[from] super::configuration::Error),
         #[error("Copy of driver process output to memory failed")]
         ReadProcessOutputToBuffer(#[from] std::io::Error),
+        #[error("The required filter driver '{name}' is unable to clean '{rela_path}'")]
+        RequiredDriverUnavailable { name: BString, rela_path: BString },
     }
 }
 
 ///
 pub mod to_worktree {
+    use bstr::BString;
+
     /// The error returned by [Pipeline::convert_to_worktree()][super::Pipeline::convert_to_worktree()].
     #[derive(Debug, thiserror::Error)]
     #[allow(missing_docs)]
@@ -54,6 +60,8 @@ pub mod to_worktree {
         Driver(#[from] crate::driver::apply::Error),
         #[error(transparent)]
         Configuration(#[from] super::configuration::Error),
+        #[error("The required filter driver '{name}' is unable to smudge '{rela_path}'")]
+        RequiredDriverUnavailable { name: BString, rela_path: BString },
     }
 }
 
@@ -63,6 +71,10 @@ impl Pipeline {
     /// based on the `attributes` at `rela_path` which is passed as first argument..
     /// When converting to `crlf`, and depending on the configuration, `index_object` might be called to obtain the index
     /// version of `src` if available. It can return `Ok(None)` if this information isn't available.
+    ///
+    /// Like `git`, the unfiltered content is used if a driver that isn't required fails. As the input is needed for that,
+    /// it's kept in memory while the driver consumes it, but the output of the driver is still streamed if possible.
+    /// If the driver fails after producing output, the error is returned when reading as the output can't be retracted anymore.
     pub fn convert_to_git<R>(
         &mut self,
         mut src: R,
@@ -73,6 +85,7 @@ impl Pipeline {
     where
         R: std::io::Read,
     {
+        self.round_trip_warning = None;
         let bstr_path = gix_path::into_bstr(rela_path);
         let Configuration {
             driver,
@@ -101,23 +114,77 @@ impl Pipeline {
             },
         )?;
 
-        if let Some(driver) = driver {
-            if let Some(mut read) = self.processes.apply(
+        if let Some(driver) = driver.filter(|driver| driver.required) {
+            match self.processes.apply(
                 driver,
                 &mut src,
                 driver::Operation::Clean,
                 self.context.with_path(bstr_path.as_ref()),
             )? {
-                if !apply_ident_filter && encoding.is_none() && !would_convert_eol {
-                    // Note that this is not typically a benefit in terms of saving memory as most filters
-                    // aren't expected to make the output file larger. It's more about who is waiting for the filter's
-                    // output to arrive, which won't be us now. For `git-lfs` it definitely won't matter though.
-                    return Ok(ToGitOutcome::Process(read));
+                Some(mut read) => {
+                    if !apply_ident_filter && encoding.is_none() && !would_convert_eol {
+                        // Note that this is not typically a benefit in terms of saving memory as most filters
+                        // aren't expected to make the output file larger. It's more about who is waiting for the filter's
+                        // output to arrive, which won't be us now. For `git-lfs` it definitely won't matter though.
+                        return Ok(ToGitOutcome::Process(read));
+                    }
+                    self.bufs.clear();
+                    read.read_to_end(&mut self.bufs.src)?;
+                    in_buffer = true;
+                }
+                None => {
+                    return Err(to_git::Error::RequiredDriverUnavailable {
+                        name: driver.name.clone(),
+                        rela_path: bstr_path.into_owned(),
+                    })
                 }
-                self.bufs.clear();
-                read.read_to_end(&mut self.bufs.src)?;
-                in_buffer = true;
             }
+        } else if let Some(driver) = driver {
+            // Like `git`, keep the input so the unfiltered content can be used if a driver that isn't required fails.
+            self.bufs.clear();
+            let driver = as_required(driver);
+            match self.processes.apply(
+                &driver,
+                &mut Tee {
+                    inner: &mut src,
+                    copy: &mut self.bufs.src,
+                },
+                driver::Operation::Clean,
+                self.context.with_path(bstr_path.as_ref()),
+            ) {
+                Ok(Some(read)) => {
+                    let mut read = FallbackRead {
+                        driver: read,
+                        original: std::io::Cursor::new(std::mem::take(&mut self.bufs.src)),
+                        state: FallbackState::Pending,
+                    };
+                    if !apply_ident_filter && encoding.is_none() && !would_convert_eol {
+                        return Ok(ToGitOutcome::Process(Box::new(read)));
+                    }
+                    read.read_to_end(&mut self.bufs.dest)?;
+                    let used_driver_output = read.state == FallbackState::Driver;
+                    self.bufs.src = read.original.into_inner();
+                    if used_driver_output {
+                        self.bufs.swap();
+                    }
+                    self.bufs.dest.clear();
+                }
+                Ok(None) => {
+                    src.read_to_end(&mut self.bufs.src)?;
+                }
+                #[allow(unused_variables)]
+                Err(err) => {
+                    gix_trace::warn!(
+                        "filter driver '{}' failed to clean '{}', using unfiltered content: {}",
+                        driver.name,
+                        bstr_path,
+                        err
+                    );
+                    // Obtain whatever the driver didn't consume.
+                    src.read_to_end(&mut self.bufs.src)?;
+                }
+            }
+            in_buffer = true;
         }
         if !in_buffer && (apply_ident_filter || encoding.is_some() || would_convert_eol) {
             self.bufs.clear();
@@ -139,7 +206,7 @@ impl Pipeline {
             self.bufs.swap();
         }
 
-        if eol::convert_to_git(
+        if eol::convert_to_git_with_warnings(
             &self.bufs.src,
             digest,
             &mut self.bufs.dest,
@@ -148,6 +215,7 @@ impl Pipeline {
                 round_trip_check: self.options.crlf_roundtrip_check.to_eol_roundtrip_check(rela_path),
                 config: self.options.eol_config,
             },
+            &mut |warning| self.round_trip_warning = Some(warning),
         )? {
             self.bufs.swap();
         }
@@ -207,16 +275,44 @@ impl Pipeline {
             bufs.swap();
         }
 
-        if let Some(driver) = driver {
+        if let Some(driver) = driver.filter(|driver| driver.required) {
             let (mut src, _dest) = bufs.src_and_dest();
-            if let Some(maybe_delayed) = self.processes.apply_delayed(
+            return match self.processes.apply_delayed(
                 driver,
                 &mut src,
                 driver::Operation::Smudge,
                 can_delay,
                 self.context.with_path(rela_path),
             )? {
-                return Ok(ToWorktreeOutcome::Process(maybe_delayed));
+                Some(maybe_delayed) => Ok(ToWorktreeOutcome::Process(maybe_delayed)),
+                None => Err(to_worktree::Error::RequiredDriverUnavailable {
+                    name: driver.name.clone(),
+                    rela_path: rela_path.to_owned(),
+                }),
+            };
+        } else if let Some(driver) = driver {
+            // Like `git`, use the unfiltered content if a driver that isn't required fails.
+            let driver = as_required(driver);
+            let (mut src, dest) = bufs.src_and_dest();
+            match self.processes.apply_delayed(
+                &driver,
+                &mut src,
+                driver::Operation::Smudge,
+                can_delay,
+                self.context.with_path(rela_path),
+            ) {
+                Ok(Some(driver::apply::MaybeDelayed::Delayed(key))) => {
+                    return Ok(ToWorktreeOutcome::Process(driver::apply::MaybeDelayed::Delayed(key)));
+                }
+                Ok(Some(driver::apply::MaybeDelayed::Immediate(mut read))) => {
+                    if read.read_to_end(dest).is_ok() {
+                        drop(read);
+                        bufs.swap();
+                    } else {
+                        dest.clear();
+                    }
+                }
+                Ok(None) | Err(_) => {}
             }
         }
 
@@ -227,6 +323,68 @@ impl Pipeline {
     }
 }
 
+/// A reader that copies everything read from `inner` into `copy`.
+struct Tee<'a, R> {
+    inner: &'a mut R,
+    copy: &'a mut Vec<u8>,
+}
+
+impl<R: std::io::Read> std::io::Read for Tee<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let num_read = self.inner.read(buf)?;
+        self.copy.extend_from_slice(&buf[..num_read]);
+        Ok(num_read)
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum FallbackState {
+    /// Nothing was read from the driver yet.
+    Pending,
+    /// The driver produced output, which is passed on, including its errors.
+    Driver,
+    /// The driver failed before producing output, so the original is used instead.
+    Original,
+}
+
+/// A reader for the output of a driver that isn't required, which yields `original` instead if the driver fails before
+/// producing any output.
+struct FallbackRead<'a> {
+    driver: Box<dyn std::io::Read + 'a>,
+    original: std::io::Cursor<Vec<u8>>,
+    state: FallbackState,
+}
+
+impl std::io::Read for FallbackRead<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self.state {
+            FallbackState::Driver => self.driver.read(buf),
+            FallbackState::Original => self.original.read(buf),
+            FallbackState::Pending if buf.is_empty() => Ok(0),
+            FallbackState::Pending => match self.driver.read(buf) {
+                Ok(num_read) => {
+                    self.state = FallbackState::Driver;
+                    Ok(num_read)
+                }
+                #[allow(unused_variables)]
+                Err(err) => {
+                    gix_trace::warn!("filter driver failed, using unfiltered content: {}", err);
+                    self.state = FallbackState::Original;
+                    self.original.read(buf)
+                }
+            },
+        }
+    }
+}
+
+/// Return a copy of `driver` that is required, to learn if it fails.
+fn as_required(driver: &crate::Driver) -> crate::Driver {
+    crate::Driver {
+        required: true,
+        ..driver.clone()
+    }
+}
+
 /// The result of a conversion with zero or more filters to be stored in git.
 pub enum ToGitOutcome<'pipeline, R> {
     /// The original input wasn't changed and the reader is still available for consumption.
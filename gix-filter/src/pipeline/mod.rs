@@ -66,6 +66,7 @@ impl Pipeline {
             processes: driver::State::default(),
             options,
             bufs: Default::default(),
+            round_trip_warning: None,
         }
     }
 
@@ -100,6 +101,12 @@ impl Pipeline {
         &mut self.context
     }
 
+    /// Return the warning about line endings that won't survive a round-trip produced by the last call to
+    /// [`convert_to_git()`][Self::convert_to_git()], which can only happen if [`CrlfRoundTripCheck::Warn`] is configured.
+    pub fn round_trip_warning(&self) -> Option<eol::convert_to_git::RoundTripWarning> {
+        self.round_trip_warning
+    }
+
     /// Return a set of options for configuration after instantiation.
     pub fn options_mut(&mut self) -> &mut Options {
         &mut self.options
@@ -1,7 +1,10 @@
 use std::path::Path;
 
 use bstr::{ByteSlice, ByteVec};
-use gix_filter::{eol, eol::AttributesDigest};
+use gix_filter::{
+    eol,
+    eol::{convert_to_git::RoundTripWarning, AttributesDigest},
+};
 
 #[test]
 fn with_binary_attribute_is_never_converted() {
@@ -144,7 +147,8 @@ fn round_trip_check() -> crate::Result {
         .unwrap_err();
         assert_eq!(err.to_string(), expected);
 
-        let changed = eol::convert_to_git(
+        let mut warnings = Vec::new();
+        let changed = eol::convert_to_git_with_warnings(
             input,
             AttributesDigest::TextCrlf,
             &mut buf,
@@ -155,11 +159,17 @@ fn round_trip_check() -> crate::Result {
                 }),
                 config: Default::default(),
             },
+            &mut |warning| warnings.push(warning),
         )?;
         assert!(
             changed,
             "in warn mode, we will get a result even though it won't round-trip"
-        )
+        );
+        assert_eq!(
+            warnings,
+            [RoundTripWarning::LfWillBeReplacedByCrlf],
+            "the warning is also available as structured value"
+        );
     }
     Ok(())
 }
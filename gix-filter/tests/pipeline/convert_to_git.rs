@@ -37,6 +37,48 @@ fn no_driver_but_filter_with_autocrlf() -> gix_testtools::Result {
     Ok(())
 }
 
+#[test]
+fn round_trip_warnings_are_available_until_the_next_conversion() -> gix_testtools::Result {
+    let (_cache, mut pipe) = pipeline("no-filter", || {
+        (
+            vec![],
+            Vec::new(),
+            CrlfRoundTripCheck::Warn,
+            eol::Configuration {
+                auto_crlf: eol::AutoCrlf::Input,
+                eol: None,
+            },
+        )
+    })?;
+
+    let out = pipe.convert_to_git(
+        "hi\r\n".as_bytes(),
+        Path::new("any.txt"),
+        &mut |_path, _attrs| {},
+        &mut no_object_in_index,
+    )?;
+    assert_eq!(out.as_bytes().expect("converted").as_bstr(), "hi\n");
+    drop(out);
+    assert_eq!(
+        pipe.round_trip_warning(),
+        Some(eol::convert_to_git::RoundTripWarning::CrlfWillBeReplacedByLf),
+        "CRLF won't be restored on checkout with `core.autocrlf=input`"
+    );
+
+    pipe.convert_to_git(
+        "hi\n".as_bytes(),
+        Path::new("any.txt"),
+        &mut |_path, _attrs| {},
+        &mut no_object_in_index,
+    )?;
+    assert_eq!(
+        pipe.round_trip_warning(),
+        None,
+        "the warning is reset with each conversion"
+    );
+    Ok(())
+}
+
 #[test]
 fn all_stages_mean_streaming_is_impossible() -> gix_testtools::Result {
     let (mut cache, mut pipe) = pipeline("all-filters", || {
@@ -137,3 +179,80 @@ fn no_call(_buf: &mut Vec<u8>) -> Result<Option<()>, Box<dyn std::error::Error +
 fn no_object_in_index(_buf: &mut Vec<u8>) -> Result<Option<()>, Box<dyn std::error::Error + Send + Sync>> {
     Ok(None)
 }
+
+#[test]
+fn driver_failure_falls_back_to_unfiltered_content_unless_required() -> gix_testtools::Result {
+    for required in [false, true] {
+        let (mut cache, mut pipe) = pipeline("driver-only", || {
+            let mut driver = driver_with_process();
+            driver.process = None;
+            driver.required = required;
+            (vec![driver], Vec::new(), CrlfRoundTripCheck::Skip, Default::default())
+        })?;
+        let mut attributes = |path: &bstr::BStr, attrs: &mut gix_attributes::search::Outcome| {
+            cache
+                .at_entry(path, Some(false), gix_object::find::Never)
+                .expect("cannot fail")
+                .matching_attributes(attrs);
+        };
+
+        let mut out = pipe.convert_to_git(
+            "➡a\n".as_bytes(),
+            Path::new("any.txt"),
+            &mut attributes,
+            &mut no_object_in_index,
+        )?;
+        let mut buf = Vec::new();
+        out.read_to_end(&mut buf)?;
+        assert_eq!(buf.as_bstr(), "a\n", "the driver works");
+        drop(out);
+
+        let res = pipe.convert_to_git(
+            "➡a\n".as_bytes(),
+            Path::new("do/fail"),
+            &mut attributes,
+            &mut no_object_in_index,
+        );
+        if required {
+            buf.clear();
+            let failed = match res {
+                Ok(mut out) => out.read_to_end(&mut buf).is_err(),
+                Err(_) => true,
+            };
+            assert!(failed, "required drivers must succeed");
+        } else {
+            let mut out = res?;
+            assert!(out.as_read().is_some(), "the output of the driver is streamed");
+            buf.clear();
+            out.read_to_end(&mut buf)?;
+            assert_eq!(buf.as_bstr(), "➡a\n", "the driver failed, so the original is used");
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn required_driver_without_clean_program_is_an_error() -> gix_testtools::Result {
+    let (mut cache, mut pipe) = pipeline("driver-only", || {
+        let mut driver = driver_with_process();
+        driver.process = None;
+        driver.clean = None;
+        (vec![driver], Vec::new(), CrlfRoundTripCheck::Skip, Default::default())
+    })?;
+    let res = pipe.convert_to_git(
+        "a\n".as_bytes(),
+        Path::new("any.txt"),
+        &mut |path, attrs| {
+            cache
+                .at_entry(path, Some(false), gix_object::find::Never)
+                .expect("cannot fail")
+                .matching_attributes(attrs);
+        },
+        &mut no_object_in_index,
+    );
+    assert!(matches!(
+        res,
+        Err(gix_filter::pipeline::convert::to_git::Error::RequiredDriverUnavailable { .. })
+    ));
+    Ok(())
+}
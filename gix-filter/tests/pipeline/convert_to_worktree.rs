@@ -98,3 +98,72 @@ fn no_filter() -> gix_testtools::Result {
     assert_eq!(actual.as_ptr(), input.as_ptr(), "…which means it's exactly the same");
     Ok(())
 }
+
+#[test]
+fn driver_failure_falls_back_to_unfiltered_content_unless_required() -> gix_testtools::Result {
+    for required in [false, true] {
+        let (mut cache, mut pipe) = pipeline("driver-only", || {
+            let mut driver = driver_with_process();
+            driver.process = None;
+            driver.required = required;
+            (vec![driver], Vec::new(), CrlfRoundTripCheck::Skip, Default::default())
+        })?;
+        let mut attributes = |path: &bstr::BStr, attrs: &mut gix_attributes::search::Outcome| {
+            cache
+                .at_entry(path, Some(false), gix_object::find::Never)
+                .expect("cannot fail")
+                .matching_attributes(attrs);
+        };
+
+        let mut out = pipe.convert_to_worktree(
+            b"a\n",
+            "any.txt".into(),
+            &mut attributes,
+            gix_filter::driver::apply::Delay::Forbid,
+        )?;
+        let mut buf = Vec::new();
+        out.read_to_end(&mut buf)?;
+        assert_eq!(buf.as_bstr(), "➡a\n", "the driver works");
+        drop(out);
+
+        let mut out = pipe.convert_to_worktree(
+            b"a\n",
+            "do/fail".into(),
+            &mut attributes,
+            gix_filter::driver::apply::Delay::Forbid,
+        )?;
+        if required {
+            assert!(out.read_to_end(&mut buf).is_err(), "required drivers must succeed");
+        } else {
+            assert!(!out.is_changed(), "the driver failed, so the original is used");
+            assert_eq!(out.as_bytes().expect("unchanged").as_bstr(), "a\n");
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn required_driver_without_smudge_program_is_an_error() -> gix_testtools::Result {
+    let (mut cache, mut pipe) = pipeline("driver-only", || {
+        let mut driver = driver_with_process();
+        driver.process = None;
+        driver.smudge = None;
+        (vec![driver], Vec::new(), CrlfRoundTripCheck::Skip, Default::default())
+    })?;
+    let res = pipe.convert_to_worktree(
+        b"a\n",
+        "any.txt".into(),
+        &mut |path, attrs| {
+            cache
+                .at_entry(path, Some(false), gix_object::find::Never)
+                .expect("cannot fail")
+                .matching_attributes(attrs);
+        },
+        gix_filter::driver::apply::Delay::Forbid,
+    );
+    assert!(matches!(
+        res,
+        Err(gix_filter::pipeline::convert::to_worktree::Error::RequiredDriverUnavailable { .. })
+    ));
+    Ok(())
+}
@@ -32,6 +32,8 @@ pub struct Prefix {
 
 /// The size of a SHA1 hash digest in bytes.
 const SIZE_OF_SHA1_DIGEST: usize = 20;
+/// The size of a SHA256 hash digest in bytes.
+const SIZE_OF_SHA256_DIGEST: usize = 32;
 
 /// Denotes the kind of function to produce a [`ObjectId`].
 #[derive(Default, PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
@@ -40,6 +42,8 @@ pub enum Kind {
     /// The Sha1 hash with 160 bits.
     #[default]
     Sha1 = 1,
+    /// The Sha256 hash with 256 bits, used in repositories with `extensions.objectFormat = sha256`.
+    Sha256 = 2,
 }
 
 mod kind;
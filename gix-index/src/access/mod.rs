@@ -1,10 +1,11 @@
 use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::ops::Range;
 
 use bstr::{BStr, ByteSlice, ByteVec};
 use filetime::FileTime;
 
-use crate::{entry, extension, Entry, PathStorage, PathStorageRef, State, Version};
+use crate::{entry, extension, AccelerateLookup, Entry, PathStorage, PathStorageRef, State, Version};
 
 // TODO: integrate this somehow, somewhere, depending on later usage.
 #[allow(dead_code)]
@@ -261,6 +262,74 @@ impl State {
     }
 }
 
+/// Case-insensitive lookups
+impl State {
+    /// Return a lookup table to find entries by path while ignoring case, for use in [`entry_by_path_icase()`](Self::entry_by_path_icase())
+    /// and [`entry_closest_to_directory_icase()`](Self::entry_closest_to_directory_icase()).
+    ///
+    /// Note that just like `git`, only ASCII characters are compared case-insensitively.
+    pub fn prepare_icase_backing(&self) -> AccelerateLookup<'_> {
+        let mut icase_entries = HashMap::with_capacity(self.entries.len());
+        let mut icase_dirs = HashMap::new();
+        for entry in &self.entries {
+            let path = entry.path(self).to_ascii_lowercase();
+            for pos in path.rfind_iter("/") {
+                match icase_dirs.entry(path[..pos].into()) {
+                    std::collections::hash_map::Entry::Occupied(_) => break,
+                    std::collections::hash_map::Entry::Vacant(dir) => {
+                        dir.insert(entry);
+                    }
+                }
+            }
+            if entry.stage() == 0 || entry.stage() == 2 {
+                icase_entries.entry(path.into()).or_insert(entry);
+            }
+        }
+        AccelerateLookup {
+            icase_entries,
+            icase_dirs,
+        }
+    }
+
+    /// Return the entry at `path` like [`entry_by_path()`](Self::entry_by_path()) does, but if `ignore_case` is `true`,
+    /// fall back to an entry whose path only differs in case using `lookup`.
+    ///
+    /// `lookup` is obtained by [`prepare_icase_backing()`](Self::prepare_icase_backing()) and is expected to match this instance.
+    pub fn entry_by_path_icase<'a>(
+        &'a self,
+        path: &BStr,
+        ignore_case: bool,
+        lookup: &AccelerateLookup<'a>,
+    ) -> Option<&'a Entry> {
+        self.entry_by_path(path).or_else(|| {
+            ignore_case
+                .then(|| lookup.icase_entries.get(path.to_ascii_lowercase().as_bstr()).copied())
+                .flatten()
+        })
+    }
+
+    /// Return an entry within `directory`, which is a path without trailing slash, possibly ignoring case if `ignore_case` is `true`,
+    /// or `None` if there is no entry in such a directory.
+    ///
+    /// `lookup` is obtained by [`prepare_icase_backing()`](Self::prepare_icase_backing()) and is expected to match this instance.
+    pub fn entry_closest_to_directory_icase<'a>(
+        &'a self,
+        directory: &BStr,
+        ignore_case: bool,
+        lookup: &AccelerateLookup<'a>,
+    ) -> Option<&'a Entry> {
+        let mut prefix = directory.to_owned();
+        prefix.push(b'/');
+        self.prefixed_entries(prefix.as_bstr())
+            .and_then(|entries| entries.first())
+            .or_else(|| {
+                ignore_case
+                    .then(|| lookup.icase_dirs.get(directory.to_ascii_lowercase().as_bstr()).copied())
+                    .flatten()
+            })
+    }
+}
+
 /// Mutation
 impl State {
     /// After usage of the storage obtained by [`take_path_backing()`][Self::take_path_backing()], return it here.
@@ -396,6 +465,10 @@ impl State {
     pub fn tree(&self) -> Option<&extension::Tree> {
         self.tree.as_ref()
     }
+    /// Remove the `tree` extension and return it, which must be done whenever entries are changed as it would otherwise be stale.
+    pub fn remove_tree(&mut self) -> Option<extension::Tree> {
+        self.tree.take()
+    }
     /// Access the `link` extension.
     pub fn link(&self) -> Option<&extension::Link> {
         self.link.as_ref()
@@ -42,7 +42,7 @@ impl Mode {
         executable_bit: bool,
     ) -> Option<Change> {
         match self {
-            Mode::FILE if !stat.is_file() => (),
+            Mode::FILE | Mode::FILE_EXECUTABLE if !stat.is_file() => (),
             Mode::SYMLINK if has_symlinks && !stat.is_symlink() => (),
             Mode::SYMLINK if !has_symlinks && !stat.is_file() => (),
             Mode::COMMIT | Mode::DIR if !stat.is_dir() => (),
@@ -54,6 +54,8 @@ impl Mode {
         };
         let new_mode = if stat.is_dir() {
             Mode::COMMIT
+        } else if stat.is_symlink() {
+            Mode::SYMLINK
         } else if executable_bit && gix_fs::is_executable(stat) {
             Mode::FILE_EXECUTABLE
         } else {
@@ -6,7 +6,7 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 #![deny(unsafe_code, missing_docs, rust_2018_idioms)]
 
-use std::{ops::Range, path::PathBuf};
+use std::{collections::HashMap, ops::Range, path::PathBuf};
 
 use filetime::FileTime;
 pub use gix_hash as hash;
@@ -109,6 +109,16 @@ pub struct State {
     fs_monitor: Option<extension::FsMonitor>,
 }
 
+/// A lookup table to find entries by path while ignoring the case of their paths, as created by
+/// [`State::prepare_icase_backing()`].
+#[derive(Clone)]
+pub struct AccelerateLookup<'a> {
+    /// Entries at stage 0 or 2 by their lower-cased path.
+    icase_entries: HashMap<bstr::BString, &'a Entry>,
+    /// The first entry within each directory by the lower-cased path of the directory.
+    icase_dirs: HashMap<bstr::BString, &'a Entry>,
+}
+
 mod impls {
     use std::fmt::{Debug, Formatter};
 
@@ -195,7 +205,7 @@ pub(crate) mod util {
 
 #[test]
 fn size_of_entry() {
-    assert_eq!(std::mem::size_of::<crate::Entry>(), 80);
+    assert_eq!(std::mem::size_of::<crate::Entry>(), 96);
 
     // the reason we have our own time is half the size.
     assert_eq!(std::mem::size_of::<crate::entry::stat::Time>(), 8);
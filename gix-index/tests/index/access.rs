@@ -1,3 +1,5 @@
+use bstr::ByteSlice;
+
 use crate::index::Fixture;
 
 #[test]
@@ -164,6 +166,40 @@ fn prefixed_entries() {
     );
 }
 
+#[test]
+fn entry_by_path_icase() {
+    let file = Fixture::Generated("v4_more_files_IEOT").open();
+    let lookup = file.prepare_icase_backing();
+    for entry in file.entries() {
+        let path = entry.path(&file);
+        assert_eq!(file.entry_by_path_icase(path, false, &lookup), Some(entry));
+        assert_eq!(
+            file.entry_by_path_icase(path.to_ascii_uppercase().as_bstr(), true, &lookup),
+            Some(entry)
+        );
+    }
+    assert_eq!(
+        file.entry_by_path_icase("D/LAST/123".into(), false, &lookup),
+        None,
+        "case matters unless ignored"
+    );
+    assert_eq!(file.entry_by_path_icase("D/LAST".into(), true, &lookup), None);
+
+    let in_dir = |dir: &str, ignore_case: bool| {
+        file.entry_closest_to_directory_icase(dir.into(), ignore_case, &lookup)
+            .map(|entry| entry.path(&file))
+    };
+    assert_eq!(in_dir("d/last", false), Some("d/last/123".into()));
+    assert_eq!(in_dir("D/Last", false), None);
+    assert_eq!(in_dir("D/Last", true), Some("d/last/123".into()));
+    assert_eq!(
+        in_dir("D", true),
+        Some("d/a".into()),
+        "the first entry in the directory"
+    );
+    assert_eq!(in_dir("d/last/123", true), None, "files aren't directories");
+}
+
 fn check_prefix(index: &gix_index::State, prefix: &str, expected: &[&str]) {
     assert_eq!(
         index
@@ -12,3 +12,47 @@ fn apply() {
         Mode::SYMLINK
     );
 }
+
+#[test]
+#[cfg(unix)]
+fn change_to_match_fs() -> crate::Result {
+    let tmp = gix_testtools::tempfile::tempdir()?;
+    let file = tmp.path().join("file");
+    std::fs::write(&file, b"content")?;
+    let link = tmp.path().join("link");
+    std::os::unix::fs::symlink("file", &link)?;
+    let file = std::fs::symlink_metadata(file)?;
+    let link = std::fs::symlink_metadata(link)?;
+    let dir = std::fs::symlink_metadata(tmp.path())?;
+
+    let new_mode = |mode: Mode, stat: &std::fs::Metadata, has_symlinks: bool, executable_bit: bool| match mode
+        .change_to_match_fs(stat, has_symlinks, executable_bit)
+    {
+        Some(Change::Type { new_mode }) => Some(new_mode),
+        Some(Change::ExecutableBit) => Some(Change::ExecutableBit.apply(mode)),
+        None => None,
+    };
+    assert_eq!(new_mode(Mode::FILE, &file, true, true), None);
+    assert_eq!(
+        new_mode(Mode::FILE_EXECUTABLE, &file, true, true),
+        Some(Mode::FILE),
+        "the executable bit is lost"
+    );
+    assert_eq!(
+        new_mode(Mode::FILE_EXECUTABLE, &file, true, false),
+        None,
+        "without executable bit support, it's retained"
+    );
+    for mode in [Mode::FILE, Mode::FILE_EXECUTABLE] {
+        assert_eq!(new_mode(mode, &link, true, true), Some(Mode::SYMLINK), "{mode:?}");
+        assert_eq!(new_mode(mode, &dir, true, true), Some(Mode::COMMIT), "{mode:?}");
+    }
+    assert_eq!(new_mode(Mode::SYMLINK, &link, true, true), None);
+    assert_eq!(
+        new_mode(Mode::SYMLINK, &file, false, true),
+        None,
+        "without symlink support, symlinks are files"
+    );
+    assert_eq!(new_mode(Mode::SYMLINK, &file, true, true), Some(Mode::FILE));
+    Ok(())
+}
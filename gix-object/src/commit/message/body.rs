@@ -66,6 +66,28 @@ impl<'a> Iterator for Trailers<'a> {
     }
 }
 
+/// Convenience
+impl<'a> Trailers<'a> {
+    /// Return an iterator over the values of all `Signed-off-by` trailers, with the token compared case-insensitively.
+    pub fn signed_off_by(self) -> impl Iterator<Item = &'a BStr> {
+        self.values_of("Signed-off-by")
+    }
+
+    /// Return an iterator over the values of all `Co-authored-by` trailers, with the token compared case-insensitively.
+    pub fn co_authored_by(self) -> impl Iterator<Item = &'a BStr> {
+        self.values_of("Co-authored-by")
+    }
+
+    fn values_of(self, token: &'static str) -> impl Iterator<Item = &'a BStr> {
+        self.filter_map(move |trailer| {
+            trailer
+                .token
+                .eq_ignore_ascii_case(token.as_bytes())
+                .then_some(trailer.value)
+        })
+    }
+}
+
 impl<'a> BodyRef<'a> {
     /// Parse `body` bytes into the trailer and the actual body.
     pub fn from_bytes(body: &'a [u8]) -> Self {
@@ -0,0 +1,229 @@
+use std::ops::Range;
+
+use crate::bstr::{BStr, BString, ByteSlice, ByteVec};
+
+/// Where to place a new trailer in the trailer block of a message, like `trailer.where` of `git interpret-trailers`.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum Placement {
+    /// Place it after the last trailer.
+    #[default]
+    End,
+    /// Place it before the first trailer.
+    Start,
+    /// Place it after the last trailer with the same token, or at the [end](Placement::End) if there is none.
+    After,
+    /// Place it before the first trailer with the same token, or at the [start](Placement::Start) if there is none.
+    Before,
+}
+
+/// What to do if a trailer with the same token already exists, like `trailer.ifExists` of `git interpret-trailers`.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum IfExists {
+    /// Add the trailer unless the trailer right next to where it would be placed has the same token and value.
+    #[default]
+    AddIfDifferentNeighbor,
+    /// Add the trailer unless any trailer has the same token and value.
+    AddIfDifferent,
+    /// Always add the trailer, even if the same one already exists.
+    Add,
+    /// Replace the trailer with the same token which is closest to where the new one would be placed.
+    Replace,
+    /// Leave the message unchanged.
+    DoNothing,
+}
+
+/// Options for use in [`add()`].
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// Where to place the trailer.
+    pub placement: Placement,
+    /// What to do if a trailer with the same token already exists.
+    pub if_exists: IfExists,
+}
+
+/// Add the trailer `token: value` to the trailer block of `message` as configured by `opts`, and return the new message.
+///
+/// Just like `git interpret-trailers`, the trailer block is the last paragraph of the message if it's not the title and
+/// consists only of trailers and their continuation lines, or of at least 25% trailers if one of them was generated by `git`,
+/// like `Signed-off-by`. Tokens are compared case-insensitively. If there is no trailer block, a new one is started after
+/// an empty line, unless the message is empty.
+///
+/// Note that all trailers are written as `token: value`, and that messages always end in a newline afterwards.
+pub fn add(message: &[u8], token: &BStr, value: &BStr, opts: Options) -> BString {
+    let content = message.trim_end();
+    let tail = match &message[content.len()..] {
+        tail if tail.contains(&b'\n') => tail,
+        _ => b"\n".as_slice(),
+    };
+    let new_trailer = {
+        let mut buf = BString::from(token.trim());
+        buf.push_str(": ");
+        buf.push_str(value.trim());
+        buf
+    };
+
+    let block = match Block::parse(content) {
+        Some(block) => block,
+        None => {
+            let mut out = BString::from(content);
+            if !out.is_empty() {
+                out.push_str("\n\n");
+            }
+            out.push_str(&new_trailer);
+            out.push_str(tail);
+            return out;
+        }
+    };
+
+    let same_token: Vec<&Trailer<'_>> = block
+        .trailers
+        .iter()
+        .filter(|trailer| trailer.token.eq_ignore_ascii_case(token.trim()))
+        .collect();
+    let (insert_at, after, neighbor) = match opts.placement {
+        Placement::After if !same_token.is_empty() => {
+            let trailer = same_token[same_token.len() - 1];
+            (trailer.range.end, true, Some(trailer))
+        }
+        Placement::Before if !same_token.is_empty() => (same_token[0].range.start, false, Some(same_token[0])),
+        Placement::End | Placement::After => (content.len(), true, block.trailers.last()),
+        Placement::Start | Placement::Before => (block.start, false, block.trailers.first()),
+    };
+
+    let is_same = |trailer: &Trailer<'_>| {
+        trailer.token.eq_ignore_ascii_case(token.trim()) && trailer.value.trim() == value.trim()
+    };
+    let mut replace = None;
+    if !same_token.is_empty() {
+        match opts.if_exists {
+            IfExists::DoNothing => return message.into(),
+            IfExists::AddIfDifferent if same_token.iter().any(|trailer| is_same(trailer)) => return message.into(),
+            IfExists::AddIfDifferentNeighbor if neighbor.map_or(false, is_same) => return message.into(),
+            IfExists::Replace => {
+                replace = Some(if after {
+                    same_token[same_token.len() - 1].range.clone()
+                } else {
+                    same_token[0].range.clone()
+                });
+            }
+            IfExists::AddIfDifferent | IfExists::AddIfDifferentNeighbor | IfExists::Add => {}
+        }
+    }
+
+    let mut out = BString::from(Vec::with_capacity(message.len() + new_trailer.len() + 1));
+    match replace {
+        Some(range) => {
+            out.push_str(&content[..range.start]);
+            out.push_str(&new_trailer);
+            out.push_str(&content[range.end..]);
+        }
+        None => {
+            out.push_str(&content[..insert_at]);
+            if after {
+                out.push_byte(b'\n');
+                out.push_str(&new_trailer);
+            } else {
+                out.push_str(&new_trailer);
+                out.push_byte(b'\n');
+            }
+            out.push_str(&content[insert_at..]);
+        }
+    }
+    out.push_str(tail);
+    out
+}
+
+/// A trailer in the trailer block, along with the byte range it occupies, including its continuation lines.
+struct Trailer<'a> {
+    token: &'a BStr,
+    value: &'a BStr,
+    range: Range<usize>,
+}
+
+/// The last paragraph of a message, if it is a trailer block.
+struct Block<'a> {
+    /// The byte offset at which the block starts.
+    start: usize,
+    trailers: Vec<Trailer<'a>>,
+}
+
+impl<'a> Block<'a> {
+    /// Parse the trailer block of `content`, which must not end in whitespace.
+    fn parse(content: &'a [u8]) -> Option<Self> {
+        let mut start = 0;
+        let mut offset = 0;
+        let mut seen_title = false;
+        for line in content.lines_with_terminator() {
+            offset += line.len();
+            if line.trim().is_empty() {
+                if !seen_title {
+                    continue;
+                }
+                start = offset;
+            } else if start == 0 {
+                seen_title = true;
+            }
+        }
+        if start == 0 {
+            return None;
+        }
+
+        let mut trailers = Vec::<Trailer<'a>>::new();
+        let (mut num_trailer_lines, mut num_other_lines) = (0, 0);
+        let mut recognized_prefix = false;
+        let mut last_line_was_trailer = false;
+        let mut offset = start;
+        for line in content[start..].lines_with_terminator() {
+            let line_start = offset;
+            offset += line.len();
+            let line = line.trim_end();
+            if line.starts_with(b"#") {
+                continue;
+            }
+            if line.starts_with(b" ") || line.starts_with(b"\t") {
+                if last_line_was_trailer {
+                    trailers.last_mut().expect("present").range.end = line_start + line.len();
+                } else {
+                    num_other_lines += 1;
+                }
+                continue;
+            }
+            if line.starts_with(b"(cherry picked from commit ") {
+                recognized_prefix = true;
+                num_trailer_lines += 1;
+                last_line_was_trailer = false;
+                continue;
+            }
+            match parse_line(line) {
+                Some((token, value)) => {
+                    recognized_prefix |= token.eq_ignore_ascii_case(b"Signed-off-by");
+                    num_trailer_lines += 1;
+                    last_line_was_trailer = true;
+                    trailers.push(Trailer {
+                        token,
+                        value,
+                        range: line_start..line_start + line.len(),
+                    });
+                }
+                None => {
+                    num_other_lines += 1;
+                    last_line_was_trailer = false;
+                }
+            }
+        }
+
+        let is_trailer_block = (num_trailer_lines > 0 && num_other_lines == 0)
+            || (recognized_prefix && num_trailer_lines * 3 >= num_other_lines);
+        is_trailer_block.then_some(Block { start, trailers })
+    }
+}
+
+/// Parse `line` as `token: value`, where `token` consists of alphanumeric characters and dashes, and may be followed by whitespace.
+fn parse_line(line: &[u8]) -> Option<(&BStr, &BStr)> {
+    let pos = line.find_byte(b':')?;
+    let token = line[..pos].trim_end();
+    if token.is_empty() || !token.iter().all(|b| b.is_ascii_alphanumeric() || *b == b'-') {
+        return None;
+    }
+    Some((token.as_bstr(), line[pos + 1..].trim().as_bstr()))
+}
@@ -113,6 +113,37 @@ impl Commit {
     pub fn extra_headers(&self) -> ExtraHeaders<impl Iterator<Item = (&BStr, &BStr)>> {
         ExtraHeaders::new(self.extra_headers.iter().map(|(k, v)| (k.as_bstr(), v.as_bstr())))
     }
+
+    /// Set the extra header `name` to `value`, which may span multiple lines.
+    ///
+    /// The first header with the same `name` is updated in place so the order of headers is retained, while all other
+    /// headers of that name are removed. If there is no such header, it's appended.
+    pub fn set_extra_header(&mut self, name: impl Into<BString>, value: impl Into<BString>) {
+        let name = name.into();
+        let mut value = Some(value.into());
+        self.extra_headers.retain_mut(|(existing_name, existing_value)| {
+            if *existing_name != name {
+                return true;
+            }
+            match value.take() {
+                Some(value) => {
+                    *existing_value = value;
+                    true
+                }
+                None => false,
+            }
+        });
+        if let Some(value) = value {
+            self.extra_headers.push((name, value));
+        }
+    }
+
+    /// Remove all extra headers with the given `name` and return `true` if at least one was removed.
+    pub fn remove_extra_header(&mut self, name: &str) -> bool {
+        let num_headers = self.extra_headers.len();
+        self.extra_headers.retain(|(existing_name, _)| existing_name != name);
+        self.extra_headers.len() != num_headers
+    }
 }
 
 /// An iterator over extra headers in [owned][crate::Commit] and [borrowed][crate::CommitRef] commits.
@@ -151,4 +182,10 @@ where
     pub fn pgp_signature(self) -> Option<&'a BStr> {
         self.find("gpgsig")
     }
+
+    /// Return the cryptographic signature over the SHA-256 representation of the commit verbatim, as provided
+    /// by gpg/pgp in the `gpgsig-sha256` header.
+    pub fn pgp_signature_sha256(self) -> Option<&'a BStr> {
+        self.find("gpgsig-sha256")
+    }
 }
@@ -1,12 +1,31 @@
 use std::io;
 
-use bstr::ByteSlice;
+use bstr::{BStr, BString, ByteSlice};
 
 use crate::{encode, encode::NL, Commit, CommitRef, Kind};
 
-impl crate::WriteTo for Commit {
-    /// Serializes this instance to `out` in the git serialization format.
-    fn write_to(&self, mut out: &mut dyn io::Write) -> io::Result<()> {
+/// Signing
+impl Commit {
+    /// Return the exact bytes that a signature for this commit has to be created over, which is its serialization
+    /// without any `gpgsig` or `gpgsig-sha256` header.
+    ///
+    /// Pass the produced signature to [`set_signature()`][Commit::set_signature()] before writing the commit.
+    pub fn signing_payload(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(crate::WriteTo::size(self) as usize);
+        self.write_to_inner(&mut buf, false)
+            .expect("writing to a vector never fails");
+        buf
+    }
+
+    /// Attach `signature`, as produced for the [signing payload][Commit::signing_payload()], to this commit,
+    /// replacing any previous signature.
+    pub fn set_signature(&mut self, signature: impl Into<BString>) {
+        self.extra_headers
+            .retain(|(name, _)| !is_signature_header(name.as_ref()));
+        self.extra_headers.push(("gpgsig".into(), signature.into()));
+    }
+
+    fn write_to_inner(&self, mut out: &mut dyn io::Write, with_signature: bool) -> io::Result<()> {
         encode::trusted_header_id(b"tree", &self.tree, &mut out)?;
         for parent in &self.parents {
             encode::trusted_header_id(b"parent", parent, &mut out)?;
@@ -16,12 +35,27 @@ impl crate::WriteTo for Commit {
         if let Some(encoding) = self.encoding.as_ref() {
             encode::header_field(b"encoding", encoding, &mut out)?;
         }
-        for (name, value) in &self.extra_headers {
+        for (name, value) in self
+            .extra_headers
+            .iter()
+            .filter(|(name, _)| with_signature || !is_signature_header(name.as_ref()))
+        {
             encode::header_field_multi_line(name, value, &mut out)?;
         }
         out.write_all(NL)?;
         out.write_all(&self.message)
     }
+}
+
+fn is_signature_header(name: &BStr) -> bool {
+    name == "gpgsig" || name == "gpgsig-sha256"
+}
+
+impl crate::WriteTo for Commit {
+    /// Serializes this instance to `out` in the git serialization format.
+    fn write_to(&self, out: &mut dyn io::Write) -> io::Result<()> {
+        self.write_to_inner(out, true)
+    }
 
     fn kind(&self) -> Kind {
         Kind::Commit
@@ -148,7 +148,7 @@ pub struct TagRef<'a> {
     pub tagger: Option<gix_actor::SignatureRef<'a>>,
     /// The message describing this release.
     pub message: &'a BStr,
-    /// A cryptographic signature over the entire content of the serialized tag object thus far.
+    /// A cryptographic signature over the entire content of the serialized tag object thus far, in the PGP, SSH or X.509 format.
     pub pgp_signature: Option<&'a BStr>,
 }
 
@@ -229,6 +229,8 @@ pub struct TreeRef<'a> {
 pub struct TreeRefIter<'a> {
     /// The directories and files contained in this tree.
     data: &'a [u8],
+    /// The kind of hash used for the object ids of all entries, which determines their length.
+    object_hash: gix_hash::Kind,
 }
 
 /// A mutable Tree, containing other trees, blobs or commits.
@@ -253,6 +255,8 @@ impl Tree {
 pub struct Data<'a> {
     /// kind of object
     pub kind: Kind,
+    /// The kind of hash used by the object database this object was read from, needed to decode trees.
+    pub object_hash: gix_hash::Kind,
     /// decoded, decompressed data, owned by a backing store.
     pub data: &'a [u8],
 }
@@ -190,8 +190,16 @@ pub enum LooseDecodeError {
 }
 
 impl<'a> ObjectRef<'a> {
-    /// Deserialize an object from a loose serialisation
+    /// Deserialize an object from a loose serialisation, which uses SHA-1 object ids.
     pub fn from_loose(data: &'a [u8]) -> Result<ObjectRef<'a>, LooseDecodeError> {
+        Self::from_loose_with_hash(data, gix_hash::Kind::Sha1)
+    }
+
+    /// Deserialize an object from a loose serialisation, with object ids of length defined by `object_hash`.
+    pub fn from_loose_with_hash(
+        data: &'a [u8],
+        object_hash: gix_hash::Kind,
+    ) -> Result<ObjectRef<'a>, LooseDecodeError> {
         let (kind, size, offset) = loose_header(data)?;
 
         let body = &data[offset..]
@@ -200,13 +208,22 @@ impl<'a> ObjectRef<'a> {
                 message: "object data was shorter than its size declared in the header",
             })?;
 
-        Ok(Self::from_bytes(kind, body)?)
+        Ok(Self::from_bytes_with_hash(kind, body, object_hash)?)
     }
 
-    /// Deserialize an object of `kind` from the given `data`.
+    /// Deserialize an object of `kind` from the given `data`, which uses SHA-1 object ids.
     pub fn from_bytes(kind: Kind, data: &'a [u8]) -> Result<ObjectRef<'a>, crate::decode::Error> {
+        Self::from_bytes_with_hash(kind, data, gix_hash::Kind::Sha1)
+    }
+
+    /// Deserialize an object of `kind` from the given `data`, with object ids of length defined by `object_hash`.
+    pub fn from_bytes_with_hash(
+        kind: Kind,
+        data: &'a [u8],
+        object_hash: gix_hash::Kind,
+    ) -> Result<ObjectRef<'a>, crate::decode::Error> {
         Ok(match kind {
-            Kind::Tree => ObjectRef::Tree(TreeRef::from_bytes(data)?),
+            Kind::Tree => ObjectRef::Tree(TreeRef::from_bytes_with_hash(data, object_hash)?),
             Kind::Blob => ObjectRef::Blob(BlobRef { data }),
             Kind::Commit => ObjectRef::Commit(CommitRef::from_bytes(data)?),
             Kind::Tag => ObjectRef::Tag(TagRef::from_bytes(data)?),
@@ -25,9 +25,10 @@ pub(crate) fn any_header_field_multi_line<'a, E: ParserError<&'a [u8]> + AddCont
         )
             .recognize()
             .map(|o: &[u8]| {
-                let bytes = o.as_bstr();
+                let bytes = o[..o.len() - 1].as_bstr();
                 let mut out = BString::from(Vec::with_capacity(bytes.len()));
-                let mut lines = bytes.lines();
+                // Split only at LF so that CR characters are preserved and the value can be written back verbatim.
+                let mut lines = bytes.split_str(b"\n");
                 out.push_str(lines.next().expect("first line"));
                 for line in lines {
                     out.push(b'\n');
@@ -37,35 +37,15 @@ pub fn git_tag<'a, E: ParserError<&'a [u8]> + AddContext<&'a [u8], StrContext>>(
 }
 
 pub fn message<'a, E: ParserError<&'a [u8]>>(i: &mut &'a [u8]) -> PResult<(&'a BStr, Option<&'a BStr>), E> {
-    const PGP_SIGNATURE_BEGIN: &[u8] = b"\n-----BEGIN PGP SIGNATURE-----";
-    const PGP_SIGNATURE_END: &[u8] = b"-----END PGP SIGNATURE-----";
-
     if i.is_empty() {
         return Ok((b"".as_bstr(), None));
     }
     delimited(
         NL,
         alt((
-            (
-                take_until0(PGP_SIGNATURE_BEGIN),
-                preceded(
-                    NL,
-                    (
-                        &PGP_SIGNATURE_BEGIN[1..],
-                        take_until0(PGP_SIGNATURE_END),
-                        PGP_SIGNATURE_END,
-                        rest,
-                    )
-                        .recognize()
-                        .map(|signature: &[u8]| {
-                            if signature.is_empty() {
-                                None
-                            } else {
-                                Some(signature.as_bstr())
-                            }
-                        }),
-                ),
-            ),
+            signature(b"\n-----BEGIN PGP SIGNATURE-----", b"-----END PGP SIGNATURE-----"),
+            signature(b"\n-----BEGIN SSH SIGNATURE-----", b"-----END SSH SIGNATURE-----"),
+            signature(b"\n-----BEGIN SIGNED MESSAGE-----", b"-----END SIGNED MESSAGE-----"),
             rest.map(|rest: &[u8]| (rest, None)),
         )),
         opt(NL),
@@ -73,3 +53,25 @@ pub fn message<'a, E: ParserError<&'a [u8]>>(i: &mut &'a [u8]) -> PResult<(&'a B
     .map(|(message, signature)| (message.as_bstr(), signature))
     .parse_next(i)
 }
+
+/// Parse a message followed by a signature that starts with `begin` on its own line and ends with `end`.
+fn signature<'a, E: ParserError<&'a [u8]>>(
+    begin: &'static [u8],
+    end: &'static [u8],
+) -> impl Parser<&'a [u8], (&'a [u8], Option<&'a BStr>), E> {
+    (
+        take_until0(begin),
+        preceded(
+            NL,
+            (&begin[1..], take_until0(end), end, rest)
+                .recognize()
+                .map(|signature: &[u8]| {
+                    if signature.is_empty() {
+                        None
+                    } else {
+                        Some(signature.as_bstr())
+                    }
+                }),
+        ),
+    )
+}
@@ -1,6 +1,6 @@
 use std::io;
 
-use bstr::BStr;
+use bstr::{BStr, BString};
 
 use crate::{encode, encode::NL, Kind, Tag, TagRef};
 
@@ -20,8 +20,28 @@ impl From<Error> for io::Error {
     }
 }
 
-impl crate::WriteTo for Tag {
-    fn write_to(&self, out: &mut dyn io::Write) -> io::Result<()> {
+/// Signing
+impl Tag {
+    /// Return the exact bytes that a signature for this tag has to be created over, which is its serialization
+    /// without the signature, or fail if the tag name is invalid.
+    ///
+    /// Pass the produced signature to [`set_signature()`][Tag::set_signature()] before writing the tag.
+    pub fn signing_payload(&self) -> Result<Vec<u8>, Error> {
+        validated_name(self.name.as_ref())?;
+        let mut buf = Vec::with_capacity(crate::WriteTo::size(self) as usize);
+        self.write_to_inner(&mut buf, false)
+            .expect("writing to a vector never fails and the name was validated");
+        buf.extend_from_slice(NL);
+        Ok(buf)
+    }
+
+    /// Attach `signature`, as produced for the [signing payload][Tag::signing_payload()], to this tag,
+    /// replacing any previous signature.
+    pub fn set_signature(&mut self, signature: impl Into<BString>) {
+        self.pgp_signature = Some(signature.into());
+    }
+
+    fn write_to_inner(&self, out: &mut dyn io::Write, with_signature: bool) -> io::Result<()> {
         encode::trusted_header_id(b"object", &self.target, out)?;
         encode::trusted_header_field(b"type", self.target_kind.as_bytes(), out)?;
         encode::header_field(b"tag", validated_name(self.name.as_ref())?, out)?;
@@ -33,12 +53,18 @@ impl crate::WriteTo for Tag {
         if !self.message.is_empty() {
             out.write_all(self.message.as_ref())?;
         }
-        if let Some(message) = &self.pgp_signature {
+        if let Some(message) = self.pgp_signature.as_ref().filter(|_| with_signature) {
             out.write_all(NL)?;
             out.write_all(message.as_ref())?;
         }
         Ok(())
     }
+}
+
+impl crate::WriteTo for Tag {
+    fn write_to(&self, out: &mut dyn io::Write) -> io::Result<()> {
+        self.write_to_inner(out, true)
+    }
 
     fn kind(&self) -> Kind {
         Kind::Tag
@@ -0,0 +1,200 @@
+use std::collections::BTreeMap;
+
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+use gix_hash::ObjectId;
+
+use crate::{
+    tree::{Entry, EntryMode},
+    FindExt, Tree,
+};
+
+/// The error returned by [`Editor`] operations.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Path {path:?} must not be empty or contain empty components")]
+    InvalidPath { path: BString },
+    #[error(transparent)]
+    FindTree(#[from] crate::find::existing_object::Error),
+}
+
+/// An editor to change a tree by inserting, updating and removing entries at arbitrary paths, to finally
+/// write only the trees that changed.
+///
+/// Trees along the way of an edited path are loaded as needed and kept in memory until they are [written](Editor::write()).
+pub struct Editor<'a> {
+    find: &'a dyn crate::Find,
+    object_hash: gix_hash::Kind,
+    /// All trees we hold in memory, by their path relative to the root tree, which is the empty path.
+    ///
+    /// Entries of trees which are also in this map have a null id, as it's only known once the tree is written.
+    trees: BTreeMap<BString, Tree>,
+    buf: Vec<u8>,
+}
+
+/// Lifecycle
+impl<'a> Editor<'a> {
+    /// Create a new editor to change the `root` tree, using `find` to load subtrees as needed, which contain
+    /// object ids of kind `object_hash`.
+    pub fn new(root: Tree, find: &'a dyn crate::Find, object_hash: gix_hash::Kind) -> Self {
+        Editor {
+            find,
+            object_hash,
+            trees: std::iter::once((BString::default(), root)).collect(),
+            buf: Vec::new(),
+        }
+    }
+}
+
+/// Editing
+impl<'a> Editor<'a> {
+    /// Insert a new entry of `mode` pointing to `id` at the slash-separated `rela_path`, or update the existing one.
+    ///
+    /// Missing intermediate trees are created, and entries along the way which aren't trees are replaced with trees.
+    /// If the entry at `rela_path` was a tree, its edits so far are discarded.
+    pub fn upsert(
+        &mut self,
+        rela_path: impl AsRef<BStr>,
+        mode: EntryMode,
+        id: impl Into<ObjectId>,
+    ) -> Result<&mut Self, Error> {
+        let (parent, name) = self.load_parents(rela_path.as_ref(), true)?.expect("created as needed");
+        let id = id.into();
+        let tree = self.trees.get_mut(&parent).expect("parent was loaded");
+        match tree.entries.iter_mut().find(|entry| entry.filename == name) {
+            Some(entry) => {
+                entry.mode = mode;
+                entry.oid = id;
+            }
+            None => tree.entries.push(Entry {
+                mode,
+                filename: name.clone(),
+                oid: id,
+            }),
+        }
+        self.forget_edits_at(join(parent.as_ref(), name.as_ref()).as_ref());
+        Ok(self)
+    }
+
+    /// Remove the entry at the slash-separated `rela_path`, which may also be a tree, or do nothing if it doesn't exist.
+    ///
+    /// Trees which become empty this way are removed once the editor is [written](Self::write()).
+    pub fn remove(&mut self, rela_path: impl AsRef<BStr>) -> Result<&mut Self, Error> {
+        if let Some((parent, name)) = self.load_parents(rela_path.as_ref(), false)? {
+            let tree = self.trees.get_mut(&parent).expect("parent was loaded");
+            tree.entries.retain(|entry| entry.filename != name);
+            self.forget_edits_at(join(parent.as_ref(), name.as_ref()).as_ref());
+        }
+        Ok(self)
+    }
+
+    /// Write all changed trees by passing them to `out`, which returns their id, and return the id of the root tree.
+    ///
+    /// Only trees which are on the path of an edit are written, while trees that became empty are removed from their parents.
+    /// The editor can be used for more edits afterwards, which will then be based on the written root tree.
+    pub fn write<E>(&mut self, mut out: impl FnMut(&Tree) -> Result<ObjectId, E>) -> Result<ObjectId, E> {
+        let root = self.write_at(BString::default(), &mut out)?;
+        Ok(root)
+    }
+}
+
+/// Utilities
+impl<'a> Editor<'a> {
+    /// Write the tree at `path` and all trees in memory below it, and return the id of the tree at `path`.
+    fn write_at<E>(&mut self, path: BString, out: &mut dyn FnMut(&Tree) -> Result<ObjectId, E>) -> Result<ObjectId, E> {
+        let mut tree = self.trees.remove(&path).expect("only called for trees in memory");
+        let mut written_empty_trees = Vec::new();
+        for entry in &mut tree.entries {
+            if !entry.mode.is_tree() {
+                continue;
+            }
+            let child_path = join(path.as_ref(), entry.filename.as_ref());
+            if self.trees.contains_key(&child_path) {
+                entry.oid = self.write_at(child_path, out)?;
+                if entry.oid.is_empty_tree() {
+                    written_empty_trees.push(entry.filename.clone());
+                }
+            }
+        }
+        tree.entries
+            .retain(|entry| !(entry.mode.is_tree() && written_empty_trees.contains(&entry.filename)));
+        tree.entries.sort();
+        let id = out(&tree)?;
+        if path.is_empty() {
+            self.trees.insert(path, tree);
+        }
+        Ok(id)
+    }
+
+    /// Load all trees leading to the last component of `rela_path` and return the path of its parent along with the last component.
+    /// If `create` is `true`, missing trees are created and non-trees are replaced with trees, otherwise `None` is returned if
+    /// one of them isn't a tree.
+    fn load_parents(&mut self, rela_path: &BStr, create: bool) -> Result<Option<(BString, BString)>, Error> {
+        let mut components = rela_path.split_str("/").peekable();
+        let mut parent = BString::default();
+        while let Some(component) = components.next() {
+            if component.is_empty() {
+                return Err(Error::InvalidPath {
+                    path: rela_path.to_owned(),
+                });
+            }
+            if components.peek().is_none() {
+                return Ok(Some((parent, component.into())));
+            }
+            let child = join(parent.as_ref(), component.as_bstr());
+            if !self.trees.contains_key(&child) && !self.load_child(parent.as_ref(), component.as_bstr(), create)? {
+                return Ok(None);
+            }
+            parent = child;
+        }
+        Err(Error::InvalidPath {
+            path: rela_path.to_owned(),
+        })
+    }
+
+    /// Put the tree named `name` within the tree at `parent` into memory, and return `true` if it was loaded or created.
+    fn load_child(&mut self, parent: &BStr, name: &BStr, create: bool) -> Result<bool, Error> {
+        let null = ObjectId::null(self.object_hash);
+        let parent_tree = self.trees.get_mut(parent).expect("parents are loaded first");
+        let existing = parent_tree.entries.iter_mut().find(|entry| entry.filename == name);
+        let child = match existing {
+            Some(entry) if entry.mode.is_tree() => {
+                let child = self.find.find_tree(&entry.oid, &mut self.buf)?.into();
+                entry.oid = null;
+                child
+            }
+            Some(entry) if create => {
+                entry.mode = EntryMode::Tree;
+                entry.oid = null;
+                Tree::empty()
+            }
+            None if create => {
+                parent_tree.entries.push(Entry {
+                    mode: EntryMode::Tree,
+                    filename: name.to_owned(),
+                    oid: null,
+                });
+                Tree::empty()
+            }
+            _ => return Ok(false),
+        };
+        self.trees.insert(join(parent, name), child);
+        Ok(true)
+    }
+
+    /// Drop all edited trees at or below `path` as it was replaced or removed.
+    fn forget_edits_at(&mut self, path: &BStr) {
+        self.trees.retain(|tree_path, _| {
+            !(tree_path == path || (tree_path.starts_with(path) && tree_path.get(path.len()) == Some(&b'/')))
+        });
+    }
+}
+
+fn join(parent: &BStr, name: &BStr) -> BString {
+    let mut path = parent.to_owned();
+    if !path.is_empty() {
+        path.push_byte(b'/');
+    }
+    path.push_str(name);
+    path
+}
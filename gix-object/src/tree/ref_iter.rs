@@ -6,16 +6,29 @@ use winnow::error::ParserError;
 use crate::{tree, tree::EntryRef, TreeRef, TreeRefIter};
 
 impl<'a> TreeRefIter<'a> {
-    /// Instantiate an iterator from the given tree data.
+    /// Instantiate an iterator from the given tree data, which uses SHA-1 object ids.
     pub fn from_bytes(data: &'a [u8]) -> TreeRefIter<'a> {
-        TreeRefIter { data }
+        Self::from_bytes_with_hash(data, gix_hash::Kind::Sha1)
+    }
+
+    /// Instantiate an iterator from the given tree data, whose object ids are of the length defined by `object_hash`.
+    pub fn from_bytes_with_hash(data: &'a [u8], object_hash: gix_hash::Kind) -> TreeRefIter<'a> {
+        TreeRefIter { data, object_hash }
     }
 }
 
 impl<'a> TreeRef<'a> {
-    /// Deserialize a Tree from `data`.
-    pub fn from_bytes(mut data: &'a [u8]) -> Result<TreeRef<'a>, crate::decode::Error> {
-        decode::tree(&mut data).map_err(crate::decode::Error::with_err)
+    /// Deserialize a Tree from `data`, which uses SHA-1 object ids.
+    pub fn from_bytes(data: &'a [u8]) -> Result<TreeRef<'a>, crate::decode::Error> {
+        Self::from_bytes_with_hash(data, gix_hash::Kind::Sha1)
+    }
+
+    /// Deserialize a Tree from `data`, whose object ids are of the length defined by `object_hash`.
+    pub fn from_bytes_with_hash(
+        mut data: &'a [u8],
+        object_hash: gix_hash::Kind,
+    ) -> Result<TreeRef<'a>, crate::decode::Error> {
+        decode::tree(&mut data, object_hash.len_in_bytes()).map_err(crate::decode::Error::with_err)
     }
 
     /// Find an entry named `name` knowing if the entry is a directory or not, using a binary search.
@@ -61,7 +74,7 @@ impl<'a> Iterator for TreeRefIter<'a> {
         if self.data.is_empty() {
             return None;
         }
-        match decode::fast_entry(self.data) {
+        match decode::fast_entry(self.data, self.object_hash.len_in_bytes()) {
             Some((data_left, entry)) => {
                 self.data = data_left;
                 Some(Ok(entry))
@@ -128,7 +141,7 @@ mod decode {
 
     const NULL: &[u8] = b"\0";
 
-    pub fn fast_entry(i: &[u8]) -> Option<(&[u8], EntryRef<'_>)> {
+    pub fn fast_entry(i: &[u8], hash_len: usize) -> Option<(&[u8], EntryRef<'_>)> {
         let mut mode = 0u32;
         let mut spacer_pos = 1;
         for b in i.iter().take_while(|b| **b != b' ') {
@@ -142,38 +155,37 @@ mod decode {
         let mode = tree::EntryMode::try_from(mode).ok()?;
         let (filename, i) = i.split_at(i.find_byte(0)?);
         let i = &i[1..];
-        const HASH_LEN_FIXME: usize = 20; // TODO(SHA256): know actual/desired length or we may overshoot
         let (oid, i) = match i.len() {
-            len if len < HASH_LEN_FIXME => return None,
-            _ => i.split_at(20),
+            len if len < hash_len => return None,
+            _ => i.split_at(hash_len),
         };
         Some((
             i,
             EntryRef {
                 mode,
                 filename: filename.as_bstr(),
-                oid: gix_hash::oid::try_from_bytes(oid).expect("we counted exactly 20 bytes"),
+                oid: gix_hash::oid::try_from_bytes(oid).expect("we counted exactly the hash length"),
             },
         ))
     }
 
-    pub fn entry<'a, E: ParserError<&'a [u8]>>(i: &mut &'a [u8]) -> PResult<EntryRef<'a>, E> {
+    pub fn entry<'a, E: ParserError<&'a [u8]>>(i: &mut &'a [u8], hash_len: usize) -> PResult<EntryRef<'a>, E> {
         (
             terminated(take_while(5..=6, AsChar::is_dec_digit), SPACE)
                 .verify_map(|mode| tree::EntryMode::try_from(mode).ok()),
             terminated(take_while(1.., |b| b != NULL[0]), NULL),
-            take(20u8), // TODO(SHA256): make this compatible with other hash lengths
+            take(hash_len),
         )
             .map(|(mode, filename, oid): (_, &[u8], _)| EntryRef {
                 mode,
                 filename: filename.as_bstr(),
-                oid: gix_hash::oid::try_from_bytes(oid).expect("we counted exactly 20 bytes"),
+                oid: gix_hash::oid::try_from_bytes(oid).expect("we counted exactly the hash length"),
             })
             .parse_next(i)
     }
 
-    pub fn tree<'a, E: ParserError<&'a [u8]>>(i: &mut &'a [u8]) -> PResult<TreeRef<'a>, E> {
-        terminated(repeat(0.., entry), eof)
+    pub fn tree<'a, E: ParserError<&'a [u8]>>(i: &mut &'a [u8], hash_len: usize) -> PResult<TreeRef<'a>, E> {
+        terminated(repeat(0.., move |i: &mut &'a [u8]| entry(i, hash_len)), eof)
             .map(|entries| TreeRef { entries })
             .parse_next(i)
     }
@@ -305,3 +305,53 @@ fn newline_right_after_signature_multiline_header() -> crate::Result {
     assert!(commit.message.starts_with(b"Rollup"));
     Ok(())
 }
+
+#[test]
+fn extra_headers_with_carriage_returns_and_sha256_signature() -> crate::Result {
+    let fixture = fixture_name("commit", "extra-headers.txt");
+    let commit = CommitRef::from_bytes(&fixture)?;
+    assert_eq!(commit.encoding, Some(b"ISO-8859-1".as_bstr()));
+    let names: Vec<_> = commit.extra_headers.iter().map(|(name, _)| *name).collect();
+    assert_eq!(names, ["custom-header", "gpgsig", "gpgsig-sha256"]);
+    assert_eq!(
+        commit.extra_headers().find("custom-header"),
+        Some(b"a single-line value".as_bstr())
+    );
+
+    let sig = commit.extra_headers().pgp_signature().expect("present");
+    assert!(
+        sig.starts_with(b"-----BEGIN PGP SIGNATURE-----\r\n\r\n"),
+        "carriage returns are kept verbatim"
+    );
+    assert!(sig.ends_with(b"\r\n-----END PGP SIGNATURE-----"));
+    let sig = commit.extra_headers().pgp_signature_sha256().expect("present");
+    assert!(sig.starts_with(b"-----BEGIN PGP SIGNATURE-----\n\n"));
+    Ok(())
+}
+
+#[test]
+fn extra_headers_can_be_set_and_removed() -> crate::Result {
+    let fixture = fixture_name("commit", "extra-headers.txt");
+    let mut commit: gix_object::Commit = CommitRef::from_bytes(&fixture)?.into();
+    commit.extra_headers.push(("custom-header".into(), "duplicate".into()));
+
+    commit.set_extra_header("custom-header", "changed");
+    let names: Vec<_> = commit.extra_headers.iter().map(|(name, _)| name.as_bstr()).collect();
+    assert_eq!(
+        names,
+        ["custom-header", "gpgsig", "gpgsig-sha256"],
+        "the first header is changed in place and duplicates are removed"
+    );
+    assert_eq!(commit.extra_headers().find("custom-header"), Some(b"changed".as_bstr()));
+
+    commit.set_extra_header(
+        "mergetag",
+        "object 1b2dfb4ac5e42080b682fc676e9738c94ce6d54d\ntype commit",
+    );
+    assert_eq!(commit.extra_headers[3].0, "mergetag", "new headers are appended");
+
+    assert!(commit.remove_extra_header("gpgsig-sha256"));
+    assert!(!commit.remove_extra_header("gpgsig-sha256"), "nothing left to remove");
+    assert_eq!(commit.extra_headers().pgp_signature_sha256(), None);
+    Ok(())
+}
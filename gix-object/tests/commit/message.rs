@@ -218,6 +218,139 @@ mod body {
         let input = "foo\nbar\n\nbar\r\n\r\nbaz";
         assert_eq!(body(input).as_ref(), input);
     }
+
+    #[test]
+    fn sign_offs_and_co_authors() {
+        let body = body("foo\n\nSigned-off-by: a <a@example.com>\nco-authored-by: b <b@example.com>\nsigned-off-by: c <c@example.com>");
+        assert_eq!(
+            body.trailers().signed_off_by().collect::<Vec<_>>(),
+            ["a <a@example.com>", "c <c@example.com>"],
+            "tokens are compared case-insensitively"
+        );
+        assert_eq!(
+            body.trailers().co_authored_by().collect::<Vec<_>>(),
+            ["b <b@example.com>"]
+        );
+    }
+}
+
+mod trailer {
+    use bstr::{BStr, ByteSlice};
+    use gix_object::commit::message::trailer::{add, IfExists, Options, Placement};
+
+    fn add_default(message: &str, token: &str, value: &str) -> String {
+        add_opts(message, token, value, Options::default())
+    }
+
+    fn add_opts(message: &str, token: &str, value: &str, opts: Options) -> String {
+        add(message.as_bytes(), BStr::new(token), BStr::new(value), opts)
+            .to_str()
+            .expect("valid UTF-8")
+            .to_owned()
+    }
+
+    #[test]
+    fn a_new_trailer_block_is_started_after_an_empty_line() {
+        assert_eq!(
+            add_default("title\n", "Signed-off-by", "A <a@example.com>"),
+            "title\n\nSigned-off-by: A <a@example.com>\n",
+            "the title is never a trailer block"
+        );
+        assert_eq!(
+            add_default("title\n\nbody without: trailers\nbut text", "Signed-off-by", "A"),
+            "title\n\nbody without: trailers\nbut text\n\nSigned-off-by: A\n"
+        );
+        assert_eq!(add_default("", "Signed-off-by", "A"), "Signed-off-by: A\n");
+    }
+
+    #[test]
+    fn trailers_are_appended_to_an_existing_block() {
+        assert_eq!(
+            add_default("title\n\nbody\n\nSigned-off-by: A\n", "Co-authored-by", "B"),
+            "title\n\nbody\n\nSigned-off-by: A\nCo-authored-by: B\n"
+        );
+        assert_eq!(
+            add_default("title\n\nFixes: #1\n  continued\n\n\n", "Signed-off-by", "A"),
+            "title\n\nFixes: #1\n  continued\nSigned-off-by: A\n\n\n",
+            "continuation lines belong to trailers, and trailing whitespace is kept"
+        );
+        assert_eq!(
+            add_default("title\n\nSigned-off-by: A\nnot a trailer\n", "Signed-off-by", "B"),
+            "title\n\nSigned-off-by: A\nnot a trailer\nSigned-off-by: B\n",
+            "a block with at least 25% trailers generated by git counts as trailer block"
+        );
+    }
+
+    #[test]
+    fn identical_neighbors_are_not_added_by_default() {
+        let message = "title\n\nSigned-off-by: A\nSigned-off-by: B\n";
+        assert_eq!(
+            add_default(message, "signed-off-by", "B"),
+            message,
+            "the last trailer is the same, with tokens compared case-insensitively"
+        );
+        assert_eq!(
+            add_default(message, "Signed-off-by", "A"),
+            "title\n\nSigned-off-by: A\nSigned-off-by: B\nSigned-off-by: A\n"
+        );
+        let opts = Options {
+            if_exists: IfExists::AddIfDifferent,
+            ..Default::default()
+        };
+        assert_eq!(add_opts(message, "Signed-off-by", "A", opts), message);
+        let opts = Options {
+            if_exists: IfExists::DoNothing,
+            ..Default::default()
+        };
+        assert_eq!(add_opts(message, "Signed-off-by", "C", opts), message);
+    }
+
+    #[test]
+    fn replace_the_closest_trailer() {
+        let message = "title\n\nSigned-off-by: A\nReviewed-by: R\nSigned-off-by: B\n";
+        let opts = Options {
+            if_exists: IfExists::Replace,
+            ..Default::default()
+        };
+        assert_eq!(
+            add_opts(message, "Signed-off-by", "C", opts),
+            "title\n\nSigned-off-by: A\nReviewed-by: R\nSigned-off-by: C\n"
+        );
+        let opts = Options {
+            if_exists: IfExists::Replace,
+            placement: Placement::Start,
+        };
+        assert_eq!(
+            add_opts(message, "Signed-off-by", "C", opts),
+            "title\n\nSigned-off-by: C\nReviewed-by: R\nSigned-off-by: B\n"
+        );
+    }
+
+    #[test]
+    fn placement() {
+        let message = "title\n\nA: 1\nB: 1\nA: 2\nC: 1";
+        let opts = |placement| Options {
+            placement,
+            if_exists: IfExists::Add,
+        };
+        assert_eq!(
+            add_opts(message, "B", "2", opts(Placement::Start)),
+            "title\n\nB: 2\nA: 1\nB: 1\nA: 2\nC: 1\n"
+        );
+        assert_eq!(
+            add_opts(message, "A", "3", opts(Placement::After)),
+            "title\n\nA: 1\nB: 1\nA: 2\nA: 3\nC: 1\n"
+        );
+        assert_eq!(
+            add_opts(message, "A", "0", opts(Placement::Before)),
+            "title\n\nA: 0\nA: 1\nB: 1\nA: 2\nC: 1\n"
+        );
+        assert_eq!(
+            add_opts(message, "D", "1", opts(Placement::After)),
+            "title\n\nA: 1\nB: 1\nA: 2\nC: 1\nD: 1\n",
+            "without a trailer of the same token, it's placed at the end"
+        );
+    }
 }
 
 mod summary {
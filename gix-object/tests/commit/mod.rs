@@ -157,6 +157,30 @@ mod method {
         assert_eq!(commit.tree, "1b2dfb4ac5e42080b682fc676e9738c94ce6d54d");
         Ok(())
     }
+
+    #[test]
+    fn signing_payload_and_set_signature() -> crate::Result {
+        use gix_object::{bstr::BString, CommitRefIter, WriteTo};
+        for name in ["signed.txt", "signed-singleline.txt", "signed-with-encoding.txt"] {
+            let fixture = fixture_name("commit", name);
+            let (signature, signed_data) = CommitRefIter::signature(&fixture)?.expect("signed");
+            let signature = signature.into_owned();
+
+            let mut commit: gix_object::Commit = CommitRef::from_bytes(&fixture)?.into();
+            assert_eq!(
+                BString::from(commit.signing_payload()),
+                signed_data.to_bstring(),
+                "{name}: the payload is everything but the signature"
+            );
+
+            commit.set_signature("other");
+            commit.set_signature(signature);
+            let mut buf = Vec::new();
+            commit.write_to(&mut buf)?;
+            assert_eq!(buf.as_slice(), fixture.as_slice(), "{name}: signatures are replaced");
+        }
+        Ok(())
+    }
 }
 
 mod from_bytes;
@@ -76,6 +76,7 @@ mod commit {
         gix_object::CommitRef,
         "commit/signed-whitespace.txt",
         "commit/two-multiline-headers.txt",
+        "commit/extra-headers.txt",
         "commit/mergetag.txt",
         "commit/merge.txt",
         "commit/signed.txt",
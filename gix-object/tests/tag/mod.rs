@@ -15,6 +15,29 @@ mod method {
         assert_eq!(tag.target, "ffa700b4aca13b80cb6b98a078e7c96804f8e0ec".as_bytes());
         Ok(())
     }
+
+    #[test]
+    fn signing_payload_and_set_signature() -> crate::Result {
+        use gix_object::WriteTo;
+        let fixture = fixture_name("tag", "signed.txt");
+        let tag_ref = TagRef::from_bytes(&fixture)?;
+        let signature = tag_ref.pgp_signature.expect("signed").to_owned();
+
+        let mut tag: gix_object::Tag = tag_ref.into();
+        let payload = tag.signing_payload()?;
+        assert_eq!(
+            payload.as_slice(),
+            &fixture[..fixture.len() - signature.len()],
+            "the payload is everything that precedes the signature"
+        );
+
+        tag.pgp_signature = None;
+        tag.set_signature(signature);
+        let mut buf = Vec::new();
+        tag.write_to(&mut buf)?;
+        assert_eq!(buf, fixture);
+        Ok(())
+    }
 }
 
 mod iter {
@@ -129,6 +152,24 @@ mod from_bytes {
         Ok(())
     }
 
+    #[test]
+    fn ssh_signed() -> crate::Result {
+        let fixture = fixture_name("tag", "ssh-signed.txt");
+        let tag = TagRef::from_bytes(&fixture)?;
+        assert_eq!(tag.message, "signed with ssh");
+        let signature = tag
+            .pgp_signature
+            .expect("signatures in the SSH format are detected as well");
+        assert!(signature.starts_with(b"-----BEGIN SSH SIGNATURE-----\n"));
+        assert!(signature.ends_with(b"-----END SSH SIGNATURE-----\n"));
+        assert_eq!(
+            &fixture[fixture.len() - signature.len()..],
+            signature,
+            "the signature is at the end"
+        );
+        Ok(())
+    }
+
     #[test]
     fn empty() -> crate::Result {
         assert_eq!(
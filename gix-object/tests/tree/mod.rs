@@ -187,3 +187,155 @@ mod entry_mode {
         );
     }
 }
+
+mod editor {
+    use std::{cell::RefCell, collections::HashMap};
+
+    use gix_hash::ObjectId;
+    use gix_object::{
+        tree::{Editor, EntryMode},
+        FindExt, Tree, WriteTo,
+    };
+
+    #[derive(Default)]
+    struct Odb(RefCell<HashMap<ObjectId, Vec<u8>>>);
+
+    impl Odb {
+        fn write(&self, tree: &Tree) -> Result<ObjectId, std::io::Error> {
+            let mut buf = Vec::new();
+            tree.write_to(&mut buf)?;
+            let id = gix_object::compute_hash(gix_hash::Kind::Sha1, gix_object::Kind::Tree, &buf);
+            self.0.borrow_mut().insert(id, buf);
+            Ok(id)
+        }
+
+        fn num_trees(&self) -> usize {
+            self.0.borrow().len()
+        }
+
+        /// Return all paths of non-tree entries below the tree `id`, along with their ids.
+        fn listing(&self, id: ObjectId) -> Vec<(String, ObjectId)> {
+            fn recurse(odb: &Odb, id: ObjectId, prefix: &str, out: &mut Vec<(String, ObjectId)>) {
+                let tree: Tree = odb.find_tree(&id, &mut Vec::new()).expect("present").into();
+                for entry in tree.entries {
+                    let path = format!("{prefix}{}", entry.filename);
+                    if entry.mode.is_tree() {
+                        recurse(odb, entry.oid, &format!("{path}/"), out);
+                    } else {
+                        out.push((path, entry.oid));
+                    }
+                }
+            }
+            let mut out = Vec::new();
+            recurse(self, id, "", &mut out);
+            out
+        }
+    }
+
+    impl gix_object::Find for Odb {
+        fn try_find<'a>(
+            &self,
+            id: &gix_hash::oid,
+            buffer: &'a mut Vec<u8>,
+        ) -> Result<Option<gix_object::Data<'a>>, gix_object::find::Error> {
+            Ok(self.0.borrow().get(id).map(|data| {
+                buffer.clear();
+                buffer.extend_from_slice(data);
+                gix_object::Data {
+                    kind: gix_object::Kind::Tree,
+                    object_hash: gix_hash::Kind::Sha1,
+                    data: buffer.as_slice(),
+                }
+            }))
+        }
+    }
+
+    fn blob(n: u8) -> ObjectId {
+        ObjectId::from([n; 20])
+    }
+
+    #[test]
+    fn upsert_creates_intermediate_trees_and_only_writes_what_changed() -> crate::Result {
+        let odb = Odb::default();
+        let mut edit = Editor::new(Tree::empty(), &odb, gix_hash::Kind::Sha1);
+        edit.upsert("a/b/file", EntryMode::Blob, blob(1))?
+            .upsert("a/c", EntryMode::BlobExecutable, blob(2))?
+            .upsert("top", EntryMode::Blob, blob(3))?;
+        let root = edit.write(|tree| odb.write(tree))?;
+        assert_eq!(
+            odb.listing(root),
+            vec![
+                ("a/b/file".into(), blob(1)),
+                ("a/c".into(), blob(2)),
+                ("top".into(), blob(3))
+            ]
+        );
+        assert_eq!(odb.num_trees(), 3, "root, a and a/b");
+
+        let mut edit = Editor::new(
+            odb.find_tree(&root, &mut Vec::new())?.into(),
+            &odb,
+            gix_hash::Kind::Sha1,
+        );
+        edit.upsert("a/c", EntryMode::Blob, blob(4))?;
+        let new_root = edit.write(|tree| odb.write(tree))?;
+        assert_eq!(
+            odb.num_trees(),
+            5,
+            "only a and the root were written, a/b remained unchanged"
+        );
+        assert_eq!(
+            odb.listing(new_root),
+            vec![
+                ("a/b/file".into(), blob(1)),
+                ("a/c".into(), blob(4)),
+                ("top".into(), blob(3))
+            ]
+        );
+
+        edit.upsert("top/replaced", EntryMode::Blob, blob(5))?
+            .upsert("a/b", EntryMode::Link, blob(6))?;
+        let root = edit.write(|tree| odb.write(tree))?;
+        assert_eq!(
+            odb.listing(root),
+            vec![
+                ("a/b".into(), blob(6)),
+                ("a/c".into(), blob(4)),
+                ("top/replaced".into(), blob(5))
+            ],
+            "blobs are turned into trees and vice versa, and the editor continues with the written root"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn remove_drops_trees_that_become_empty() -> crate::Result {
+        let odb = Odb::default();
+        let mut edit = Editor::new(Tree::empty(), &odb, gix_hash::Kind::Sha1);
+        edit.upsert("a/b/file", EntryMode::Blob, blob(1))?
+            .upsert("c", EntryMode::Blob, blob(2))?;
+        let root = edit.write(|tree| odb.write(tree))?;
+
+        let mut edit = Editor::new(
+            odb.find_tree(&root, &mut Vec::new())?.into(),
+            &odb,
+            gix_hash::Kind::Sha1,
+        );
+        edit.remove("a/b/file")?
+            .remove("does/not/exist")?
+            .remove("c/is-no-tree")?;
+        let root = edit.write(|tree| odb.write(tree))?;
+        assert_eq!(odb.listing(root), vec![("c".into(), blob(2))]);
+
+        edit.remove("c")?;
+        let root = edit.write(|tree| odb.write(tree))?;
+        assert!(root.is_empty_tree(), "all trees can become empty");
+
+        assert!(matches!(
+            edit.upsert("a//b", EntryMode::Blob, blob(1)),
+            Err(gix_object::tree::editor::Error::InvalidPath { .. })
+        ));
+        assert!(edit.remove("").is_err(), "empty paths are invalid");
+        Ok(())
+    }
+}
@@ -6,23 +6,31 @@
 //!
 //! ```text
 //! # a comment, empty lines are also allowed
-//! # relative paths resolve relative to the parent git repository
-//! ../path/relative/to/repo/.git
-//! /absolute/path/to/repo/.git
+//! # relative paths resolve relative to the objects directory containing this file
+//! ../../path/relative/to/repo/.git/objects
+//! /absolute/path/to/repo/.git/objects
 //!
-//! "/a/ansi-c-quoted/path/with/tabs\t/.git"
+//! "/a/ansi-c-quoted/path/with/tabs\t/.git/objects"
 //!
-//! # each .git directory should indeed be a directory, and not a file
+//! # each objects directory should indeed be a directory, and not a file
 //! ```
 //!
+//! Alternates may have alternates themselves, up to a nesting depth of [`MAX_DEPTH`].
+//!
 //! Based on the [canonical implementation](https://github.com/git/git/blob/master/sha1-file.c#L598:L609).
-use std::{fs, io, path::PathBuf};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
 
 use gix_path::realpath::MAX_SYMLINKS;
 
 ///
 pub mod parse;
 
+/// The maximum depth of nested alternates, whose alternates are ignored just like git does it.
+pub const MAX_DEPTH: usize = 5;
+
 /// Returned by [`resolve()`]
 #[derive(thiserror::Error, Debug)]
 #[allow(missing_docs)]
@@ -37,34 +45,183 @@ pub enum Error {
     Cycle(Vec<PathBuf>),
 }
 
+///
+pub mod edit {
+    use std::path::PathBuf;
+
+    /// Returned by [`add()`][super::add()] and [`remove()`][super::remove()].
+    #[derive(thiserror::Error, Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+        #[error(transparent)]
+        Realpath(#[from] gix_path::realpath::Error),
+        #[error(transparent)]
+        Parse(#[from] super::parse::Error),
+        #[error(transparent)]
+        Resolve(#[from] super::Error),
+        #[error("The alternate object directory at '{}' doesn't exist or isn't a directory", path.display())]
+        NotADirectory { path: PathBuf },
+        #[error("The alternate object directory at '{}' would lead back to '{}'", alternate.display(), objects_directory.display())]
+        Cycle {
+            objects_directory: PathBuf,
+            alternate: PathBuf,
+        },
+        #[error("Could not write the alternates file at '{}'", path.display())]
+        Write { path: PathBuf, source: std::io::Error },
+    }
+}
+
 /// Given an `objects_directory`, try to resolve alternate object directories possibly located in the
 /// `./info/alternates` file into canonical paths and resolve relative paths with the help of the `current_dir`.
 /// If no alternate object database was resolved, the resulting `Vec` is empty (it is not an error
 /// if there are no alternates).
-/// It is an error once a repository is seen again as it would lead to a cycle.
-pub fn resolve(objects_directory: PathBuf, current_dir: &std::path::Path) -> Result<Vec<PathBuf>, Error> {
-    let mut dirs = vec![(0, objects_directory.clone())];
+///
+/// Relative paths are relative to the objects directory whose `info/alternates` file lists them, and alternates
+/// are resolved recursively in the order they are listed, ignoring the alternates of alternates nested deeper than [`MAX_DEPTH`].
+/// Alternates that are reachable more than once are only returned the first time.
+/// It is an error if an objects directory is its own alternate, directly or indirectly, as it would lead to a cycle.
+pub fn resolve(objects_directory: PathBuf, current_dir: &Path) -> Result<Vec<PathBuf>, Error> {
+    let mut chain = vec![gix_path::realpath_opts(&objects_directory, current_dir, MAX_SYMLINKS)?];
+    let mut seen = chain.clone();
     let mut out = Vec::new();
-    let mut seen = vec![gix_path::realpath_opts(&objects_directory, current_dir, MAX_SYMLINKS)?];
-    while let Some((depth, dir)) = dirs.pop() {
-        match fs::read(dir.join("info").join("alternates")) {
-            Ok(input) => {
-                for path in parse::content(&input)?.into_iter() {
-                    let path = objects_directory.join(path);
-                    let path_canonicalized = gix_path::realpath_opts(&path, current_dir, MAX_SYMLINKS)?;
-                    if seen.contains(&path_canonicalized) {
-                        return Err(Error::Cycle(seen));
-                    }
-                    seen.push(path_canonicalized);
-                    dirs.push((depth + 1, path));
-                }
-            }
-            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
-            Err(err) => return Err(err.into()),
-        };
-        if depth != 0 {
-            out.push(dir);
+    resolve_recursive(&objects_directory, current_dir, 0, &mut chain, &mut seen, &mut out)?;
+    Ok(out)
+}
+
+fn resolve_recursive(
+    dir: &Path,
+    current_dir: &Path,
+    depth: usize,
+    chain: &mut Vec<PathBuf>,
+    seen: &mut Vec<PathBuf>,
+    out: &mut Vec<PathBuf>,
+) -> Result<(), Error> {
+    if depth > MAX_DEPTH {
+        return Ok(());
+    }
+    let input = match fs::read(alternates_file(dir)) {
+        Ok(input) => input,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err.into()),
+    };
+    for path in parse::content(&input)? {
+        let path = dir.join(path);
+        let path_canonicalized = gix_path::realpath_opts(&path, current_dir, MAX_SYMLINKS)?;
+        if chain.contains(&path_canonicalized) {
+            return Err(Error::Cycle(chain.clone()));
         }
+        if seen.contains(&path_canonicalized) {
+            continue;
+        }
+        seen.push(path_canonicalized.clone());
+        out.push(path.clone());
+        chain.push(path_canonicalized);
+        resolve_recursive(&path, current_dir, depth + 1, chain, seen, out)?;
+        chain.pop();
     }
-    Ok(out)
+    Ok(())
+}
+
+/// Add `alternate` to the `info/alternates` file of `objects_directory`, and return `true` if it was added
+/// or `false` if it was listed already.
+///
+/// `alternate` must be an existing directory which doesn't have `objects_directory` as alternate itself, and it's stored as is,
+/// so relative paths are relative to `objects_directory`. Paths that can't be stored verbatim are stored in their quoted form.
+/// The file is replaced atomically, and object databases pick up the new alternate once they are refreshed.
+pub fn add(objects_directory: &Path, alternate: &Path, current_dir: &Path) -> Result<bool, edit::Error> {
+    let (mut paths, canonicalized) = read_with_canonicalized(objects_directory, current_dir)?;
+    let path = objects_directory.join(alternate);
+    if !path.is_dir() {
+        return Err(edit::Error::NotADirectory { path });
+    }
+    let path_canonicalized = gix_path::realpath_opts(&path, current_dir, MAX_SYMLINKS)?;
+    if canonicalized.contains(&path_canonicalized) {
+        return Ok(false);
+    }
+    let objects_directory_canonicalized = gix_path::realpath_opts(objects_directory, current_dir, MAX_SYMLINKS)?;
+    let leads_back = path_canonicalized == objects_directory_canonicalized
+        || resolve(path.clone(), current_dir)?
+            .iter()
+            .map(|alternate| gix_path::realpath_opts(alternate, current_dir, MAX_SYMLINKS))
+            .collect::<Result<Vec<_>, _>>()?
+            .contains(&objects_directory_canonicalized);
+    if leads_back {
+        return Err(edit::Error::Cycle {
+            objects_directory: objects_directory.to_owned(),
+            alternate: path,
+        });
+    }
+    paths.push(alternate.to_owned());
+    write(objects_directory, &paths)?;
+    Ok(true)
+}
+
+/// Remove `alternate` from the `info/alternates` file of `objects_directory`, and return `true` if it was removed or
+/// `false` if it wasn't listed.
+///
+/// `alternate` is matched against each listed path after resolving both relative to `objects_directory`, without requiring it to exist.
+/// Comments in the file are not retained, and the file is removed if no alternate remains.
+pub fn remove(objects_directory: &Path, alternate: &Path, current_dir: &Path) -> Result<bool, edit::Error> {
+    let (paths, canonicalized) = read_with_canonicalized(objects_directory, current_dir)?;
+    let path_canonicalized = gix_path::realpath_opts(&objects_directory.join(alternate), current_dir, MAX_SYMLINKS)?;
+    let num_paths = paths.len();
+    let paths: Vec<_> = paths
+        .into_iter()
+        .zip(canonicalized)
+        .filter_map(|(path, canonicalized)| (canonicalized != path_canonicalized).then_some(path))
+        .collect();
+    if paths.len() == num_paths {
+        return Ok(false);
+    }
+    write(objects_directory, &paths)?;
+    Ok(true)
+}
+
+fn alternates_file(objects_directory: &Path) -> PathBuf {
+    objects_directory.join("info").join("alternates")
+}
+
+/// Read the paths listed directly in the alternates file of `objects_directory` along with their canonicalized form.
+fn read_with_canonicalized(
+    objects_directory: &Path,
+    current_dir: &Path,
+) -> Result<(Vec<PathBuf>, Vec<PathBuf>), edit::Error> {
+    let paths = match fs::read(alternates_file(objects_directory)) {
+        Ok(input) => parse::content(&input)?,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(err) => return Err(err.into()),
+    };
+    let canonicalized = paths
+        .iter()
+        .map(|path| gix_path::realpath_opts(&objects_directory.join(path), current_dir, MAX_SYMLINKS))
+        .collect::<Result<_, _>>()?;
+    Ok((paths, canonicalized))
+}
+
+/// Write `paths` into the alternates file of `objects_directory` by replacing it, or remove the file if there are no `paths`.
+fn write(objects_directory: &Path, paths: &[PathBuf]) -> Result<(), edit::Error> {
+    let path = alternates_file(objects_directory);
+    if paths.is_empty() {
+        return match fs::remove_file(&path) {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(source) => Err(edit::Error::Write { path, source }),
+        };
+    }
+    let mut content = Vec::new();
+    for alternate in paths {
+        content.extend_from_slice(&parse::quote(gix_path::into_bstr(alternate.as_path()).as_ref()));
+        content.push(b'\n');
+    }
+    let info_dir = path.parent().expect("alternates file is in info directory");
+    fs::create_dir_all(info_dir)
+        .and_then(|_| {
+            let mut file = tempfile::NamedTempFile::new_in(info_dir)?;
+            io::Write::write_all(&mut file, &content)?;
+            file.persist(&path).map_err(|err| err.error)?;
+            Ok(())
+        })
+        .map_err(|source| edit::Error::Write { path, source })
 }
@@ -1,6 +1,6 @@
 use std::{borrow::Cow, path::PathBuf};
 
-use gix_object::bstr::ByteSlice;
+use gix_object::bstr::{BStr, BString, ByteSlice};
 
 /// Returned as part of [`crate::alternate::Error::Parse`]
 #[derive(thiserror::Error, Debug)]
@@ -31,3 +31,26 @@ pub(crate) fn content(input: &[u8]) -> Result<Vec<PathBuf>, Error> {
     }
     Ok(out)
 }
+
+/// Return `path` ready to be written as line into an alternates file, which requires quoting if it would be parsed
+/// as something else verbatim.
+pub(crate) fn quote(path: &BStr) -> Cow<'_, BStr> {
+    let needs_quoting = path.starts_with(b"\"") || path.starts_with(b"#") || path.iter().any(u8::is_ascii_control);
+    if !needs_quoting {
+        return Cow::Borrowed(path);
+    }
+    let mut out = BString::from("\"");
+    for &b in path.iter() {
+        match b {
+            b'"' => out.extend_from_slice(b"\\\""),
+            b'\\' => out.extend_from_slice(b"\\\\"),
+            b'\n' => out.extend_from_slice(b"\\n"),
+            b'\r' => out.extend_from_slice(b"\\r"),
+            b'\t' => out.extend_from_slice(b"\\t"),
+            b if b.is_ascii_control() => out.extend_from_slice(format!("\\{b:03o}").as_bytes()),
+            b => out.push(b),
+        }
+    }
+    out.push(b'"');
+    Cow::Owned(out)
+}
@@ -17,6 +17,46 @@ pub type ObjectCache = dyn gix_pack::cache::Object + Send + 'static;
 /// A constructor for boxed object caches.
 pub type NewObjectCacheFn = dyn Fn() -> Box<ObjectCache> + Send + Sync + 'static;
 
+/// A pack or object cache that can be shared among handles, as it synchronizes access to the cache it wraps.
+///
+/// Clones refer to the same cache, so handing a clone to each handle lets them all benefit from objects decoded by any of them,
+/// at the cost of contention if they are used concurrently.
+#[derive(Default)]
+pub struct Shared<T>(Arc<parking_lot::Mutex<T>>);
+
+impl<T> Shared<T> {
+    /// Wrap `cache` to make it shareable.
+    pub fn new(cache: T) -> Self {
+        Shared(Arc::new(parking_lot::Mutex::new(cache)))
+    }
+}
+
+impl<T> Clone for Shared<T> {
+    fn clone(&self) -> Self {
+        Shared(self.0.clone())
+    }
+}
+
+impl<T: gix_pack::cache::DecodeEntry> gix_pack::cache::DecodeEntry for Shared<T> {
+    fn put(&mut self, pack_id: u32, offset: u64, data: &[u8], kind: gix_object::Kind, compressed_size: usize) {
+        self.0.lock().put(pack_id, offset, data, kind, compressed_size)
+    }
+
+    fn get(&mut self, pack_id: u32, offset: u64, out: &mut Vec<u8>) -> Option<(gix_object::Kind, usize)> {
+        self.0.lock().get(pack_id, offset, out)
+    }
+}
+
+impl<T: gix_pack::cache::Object> gix_pack::cache::Object for Shared<T> {
+    fn put(&mut self, id: gix_hash::ObjectId, kind: gix_object::Kind, data: &[u8]) {
+        self.0.lock().put(id, kind, data)
+    }
+
+    fn get(&mut self, id: &gix_hash::ObjectId, out: &mut Vec<u8>) -> Option<gix_object::Kind> {
+        self.0.lock().get(id, out)
+    }
+}
+
 impl Cache<crate::store::Handle<Rc<crate::Store>>> {
     /// Convert this cache's handle into one that keeps its store in an arc. This creates an entirely new store,
     /// so should be done early to avoid unnecessary work (and mappings).
@@ -204,7 +244,7 @@ mod impls {
         ) -> Result<Option<(Data<'a>, Option<gix_pack::data::entry::Location>)>, gix_object::find::Error> {
             if let Some(mut obj_cache) = self.object_cache.as_ref().map(RefCell::borrow_mut) {
                 if let Some(kind) = obj_cache.get(&id.as_ref().to_owned(), buffer) {
-                    return Ok(Some((Data::new(kind, buffer), None)));
+                    return Ok(Some((Data::new_with_hash(kind, id.kind(), buffer), None)));
                 }
             }
             let possibly_obj = self.inner.try_find_cached(id.as_ref(), buffer, pack_cache)?;
@@ -0,0 +1,297 @@
+//! A mapping of object ids between the hash of the object database and its compatibility hash, to be able to translate
+//! object ids between both hashes as configured with `extensions.compatObjectFormat`.
+//!
+//! Git maintains such a mapping for loose objects in the [`LOOSE_OBJECT_MAP`] file within the objects directory,
+//! with each line listing an object id followed by its compatibility object id.
+//! The compatibility id of an object is the hash of the object after all object ids it refers to were translated,
+//! which is what [`convert()`] does.
+use std::{collections::HashMap, io::Write, path::Path};
+
+use gix_hash::{oid, ObjectId};
+use gix_object::bstr::ByteSlice;
+
+/// The name of the file within the objects directory which maps the ids of loose objects to their compatibility ids.
+pub const LOOSE_OBJECT_MAP: &str = "loose-object-idx";
+
+/// The first line of the [`LOOSE_OBJECT_MAP`] file.
+const HEADER: &[u8] = b"# loose-object-idx\n";
+
+///
+pub mod decode {
+    /// The error returned by [`Map::from_bytes()`][super::Map::from_bytes()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The object map doesn't start with the expected header")]
+        Header,
+        #[error(
+            "Line {line_number} must contain an object id of kind {object_hash} followed by one of kind {compat_hash}"
+        )]
+        Line {
+            line_number: usize,
+            object_hash: gix_hash::Kind,
+            compat_hash: gix_hash::Kind,
+        },
+    }
+}
+
+///
+pub mod init {
+    /// The error returned by [`Map::at()`][super::Map::at()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not read the object map at '{}'", path.display())]
+        Io {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+        #[error("Could not decode the object map at '{}'", path.display())]
+        Decode {
+            path: std::path::PathBuf,
+            source: super::decode::Error,
+        },
+    }
+}
+
+///
+pub mod convert {
+    use gix_hash::ObjectId;
+
+    /// The error returned by [`convert()`][super::convert()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The {kind} object could not be parsed to translate the object ids it refers to")]
+        Decode { kind: gix_object::Kind },
+        #[error("There is no known translation of object {id}")]
+        Unmapped { id: ObjectId },
+    }
+}
+
+/// A bidirectional mapping of object ids of one hash kind to those of the compatibility hash kind.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Map {
+    object_hash: gix_hash::Kind,
+    compat_hash: gix_hash::Kind,
+    to_compat: HashMap<ObjectId, ObjectId>,
+    from_compat: HashMap<ObjectId, ObjectId>,
+}
+
+/// Lifecycle
+impl Map {
+    /// Create an empty map to translate ids of kind `object_hash` to ids of kind `compat_hash`, and back.
+    pub fn new(object_hash: gix_hash::Kind, compat_hash: gix_hash::Kind) -> Self {
+        Map {
+            object_hash,
+            compat_hash,
+            to_compat: Default::default(),
+            from_compat: Default::default(),
+        }
+    }
+
+    /// Decode a map from `data` in the format of the [`LOOSE_OBJECT_MAP`] file.
+    pub fn from_bytes(
+        data: &[u8],
+        object_hash: gix_hash::Kind,
+        compat_hash: gix_hash::Kind,
+    ) -> Result<Self, decode::Error> {
+        let mut map = Map::new(object_hash, compat_hash);
+        if data.is_empty() {
+            return Ok(map);
+        }
+        let data = data.strip_prefix(HEADER).ok_or(decode::Error::Header)?;
+        for (line_number, line) in data.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(b"#") {
+                continue;
+            }
+            let err = || decode::Error::Line {
+                line_number: line_number + 2,
+                object_hash,
+                compat_hash,
+            };
+            let (id, compat_id) = line.split_once_str(b" ").ok_or_else(err)?;
+            let id = ObjectId::from_hex(id).ok().filter(|id| id.kind() == object_hash);
+            let compat_id = ObjectId::from_hex(compat_id.trim())
+                .ok()
+                .filter(|id| id.kind() == compat_hash);
+            match id.zip(compat_id) {
+                Some((id, compat_id)) => map.insert(id, compat_id),
+                None => return Err(err()),
+            }
+        }
+        Ok(map)
+    }
+
+    /// Read the map from the file at `path`, which is typically the [`LOOSE_OBJECT_MAP`] file in the objects directory,
+    /// or return an empty map if it doesn't exist.
+    pub fn at(
+        path: impl AsRef<Path>,
+        object_hash: gix_hash::Kind,
+        compat_hash: gix_hash::Kind,
+    ) -> Result<Self, init::Error> {
+        let path = path.as_ref();
+        let data = match std::fs::read(path) {
+            Ok(data) => data,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(source) => {
+                return Err(init::Error::Io {
+                    path: path.to_owned(),
+                    source,
+                })
+            }
+        };
+        Map::from_bytes(&data, object_hash, compat_hash).map_err(|source| init::Error::Decode {
+            path: path.to_owned(),
+            source,
+        })
+    }
+}
+
+/// Access
+impl Map {
+    /// The kind of hash of the object database.
+    pub fn object_hash(&self) -> gix_hash::Kind {
+        self.object_hash
+    }
+
+    /// The kind of hash we translate to.
+    pub fn compat_hash(&self) -> gix_hash::Kind {
+        self.compat_hash
+    }
+
+    /// Return the compatibility id of the object with `id`, if known.
+    pub fn to_compat(&self, id: &oid) -> Option<ObjectId> {
+        self.to_compat.get(id).copied()
+    }
+
+    /// Return the id of the object whose compatibility id is `compat_id`, if known.
+    pub fn from_compat(&self, compat_id: &oid) -> Option<ObjectId> {
+        self.from_compat.get(compat_id).copied()
+    }
+
+    /// The amount of mapped objects.
+    pub fn len(&self) -> usize {
+        self.to_compat.len()
+    }
+
+    /// Return `true` if no object is mapped.
+    pub fn is_empty(&self) -> bool {
+        self.to_compat.is_empty()
+    }
+
+    /// Return all pairs of `(id, compat_id)`, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&oid, &oid)> + '_ {
+        self.to_compat
+            .iter()
+            .map(|(id, compat_id)| (id.as_ref(), compat_id.as_ref()))
+    }
+
+    /// Compute the compatibility id of an object of `kind` with `data` as stored in the object database,
+    /// translating the ids it refers to using this map.
+    pub fn compat_id(&self, kind: gix_object::Kind, data: &[u8]) -> Result<ObjectId, convert::Error> {
+        let mut buf = Vec::new();
+        let data = if kind == gix_object::Kind::Blob {
+            data
+        } else {
+            convert(kind, data, self.object_hash, &|id| self.to_compat(id), &mut buf)?;
+            &buf
+        };
+        Ok(gix_object::compute_hash(self.compat_hash, kind, data))
+    }
+}
+
+/// Mutation
+impl Map {
+    /// Record that `id` is known as `compat_id` in the compatibility hash.
+    pub fn insert(&mut self, id: ObjectId, compat_id: ObjectId) {
+        self.to_compat.insert(id, compat_id);
+        self.from_compat.insert(compat_id, id);
+    }
+
+    /// Append all pairs of `(id, compat_id)` in `entries` to the map file at `path`, which is created if needed.
+    ///
+    /// This is how new loose objects are recorded, without having to rewrite the whole file.
+    pub fn append_to(path: impl AsRef<Path>, entries: &[(ObjectId, ObjectId)]) -> std::io::Result<()> {
+        let path = path.as_ref();
+        let mut buf = Vec::new();
+        let is_new = path.metadata().map_or(true, |md| md.len() == 0);
+        if is_new {
+            buf.extend_from_slice(HEADER);
+        }
+        for (id, compat_id) in entries {
+            id.write_hex_to(&mut buf)?;
+            buf.push(b' ');
+            compat_id.write_hex_to(&mut buf)?;
+            buf.push(b'\n');
+        }
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?
+            .write_all(&buf)
+    }
+}
+
+/// Write the `data` of an object of `kind`, whose object ids are of kind `object_hash`, into `out` after translating all object ids
+/// it refers to using `map`.
+///
+/// This rewrites the entries of trees, the `tree` and `parent` fields of commits and the `object` field of tags,
+/// while everything else, including signatures, is copied as is. Blobs are copied unchanged.
+pub fn convert(
+    kind: gix_object::Kind,
+    data: &[u8],
+    object_hash: gix_hash::Kind,
+    map: &dyn Fn(&oid) -> Option<ObjectId>,
+    out: &mut Vec<u8>,
+) -> Result<(), convert::Error> {
+    use gix_object::Kind;
+    out.clear();
+    let decode_err = || convert::Error::Decode { kind };
+    let translate = |id: &oid| map(id).ok_or_else(|| convert::Error::Unmapped { id: id.to_owned() });
+    match kind {
+        Kind::Blob => out.extend_from_slice(data),
+        Kind::Tree => {
+            let hash_len = object_hash.len_in_bytes();
+            let mut data = data;
+            while !data.is_empty() {
+                let name_end = data.find_byte(0).ok_or_else(decode_err)?;
+                let (mode_and_name, rest) = data.split_at(name_end + 1);
+                if rest.len() < hash_len {
+                    return Err(decode_err());
+                }
+                let (id, rest) = rest.split_at(hash_len);
+                let id = oid::try_from_bytes(id).map_err(|_| decode_err())?;
+                out.extend_from_slice(mode_and_name);
+                out.extend_from_slice(translate(id)?.as_slice());
+                data = rest;
+            }
+        }
+        Kind::Commit | Kind::Tag => {
+            let mut in_headers = true;
+            for line in data.lines_with_terminator() {
+                let field = match kind {
+                    Kind::Commit => [b"tree ".as_slice(), b"parent "]
+                        .into_iter()
+                        .find(|field| line.starts_with(field)),
+                    _ => Some(b"object ".as_slice()).filter(|field| line.starts_with(field)),
+                };
+                match field {
+                    Some(field) if in_headers => {
+                        let hex = line[field.len()..].trim_end();
+                        let id = ObjectId::from_hex(hex).map_err(|_| decode_err())?;
+                        out.extend_from_slice(field);
+                        translate(&id)?.write_hex_to(&mut *out).expect("write to memory works");
+                        out.extend_from_slice(&line[field.len() + hex.len()..]);
+                    }
+                    _ => {
+                        in_headers &= line != b"\n";
+                        out.extend_from_slice(line);
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -60,3 +60,109 @@ mod header {
         }
     }
 }
+
+/// A reader for the decompressed data of an object, obtained with [`Handle::find_stream()`][crate::store::Handle::find_stream()],
+/// which inflates the data incrementally as it is read.
+///
+/// This allows to process objects of any size without holding them in memory, with the exception of deltified packed objects
+/// which are fully decoded before they can be read.
+pub struct Stream {
+    /// The kind of the object.
+    pub kind: gix_object::Kind,
+    /// The size of the object's data in bytes, which is the amount of bytes that will be read.
+    pub size: u64,
+    inner: stream::Inner,
+}
+
+pub(crate) mod stream {
+    use std::{
+        io::{self, BufRead, Read},
+        sync::Arc,
+    };
+
+    use gix_features::zlib;
+
+    use super::Stream;
+
+    pub(crate) enum Inner {
+        /// A loose object whose header was already read, along with the data that was decompressed with it.
+        Loose {
+            file: io::BufReader<std::fs::File>,
+            inflate: zlib::Decompress,
+            prefix: io::Cursor<Vec<u8>>,
+        },
+        /// A base object in a pack, whose compressed data starts at `offset`.
+        Packed {
+            pack: Arc<gix_pack::data::File>,
+            offset: usize,
+            inflate: zlib::Decompress,
+        },
+        /// A deltified object that was decoded into memory.
+        Decoded(io::Cursor<Vec<u8>>),
+    }
+
+    impl Stream {
+        pub(crate) fn from_loose(
+            kind: gix_object::Kind,
+            size: u64,
+            file: io::BufReader<std::fs::File>,
+            inflate: zlib::Decompress,
+            prefix: Vec<u8>,
+        ) -> Self {
+            Stream {
+                kind,
+                size,
+                inner: Inner::Loose {
+                    file,
+                    inflate,
+                    prefix: io::Cursor::new(prefix),
+                },
+            }
+        }
+
+        /// Create a stream for the base object in `pack` at `entry`, which must not be a delta.
+        pub(crate) fn from_pack_entry(pack: Arc<gix_pack::data::File>, entry: &gix_pack::data::Entry) -> Self {
+            Stream {
+                kind: entry.header.as_kind().expect("only called for base objects"),
+                size: entry.decompressed_size,
+                inner: Inner::Packed {
+                    offset: entry.data_offset.try_into().expect("offset representable by machine"),
+                    pack,
+                    inflate: zlib::Decompress::new(true),
+                },
+            }
+        }
+
+        pub(crate) fn from_data(data: gix_object::Data<'_>) -> Self {
+            Stream {
+                kind: data.kind,
+                size: data.data.len() as u64,
+                inner: Inner::Decoded(io::Cursor::new(data.data.to_owned())),
+            }
+        }
+    }
+
+    impl Read for Stream {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match &mut self.inner {
+                Inner::Loose { file, inflate, prefix } => {
+                    if !prefix.fill_buf()?.is_empty() {
+                        return prefix.read(buf);
+                    }
+                    zlib::stream::inflate::read(file, inflate, buf)
+                }
+                Inner::Packed { pack, offset, inflate } => {
+                    let input = pack
+                        .entry_slice(*offset as u64..pack.pack_end() as u64)
+                        .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "pack entry out of bounds"))?;
+                    let mut input = input;
+                    let before = input.len();
+                    let num_read = zlib::stream::inflate::read(&mut input, inflate, buf)?;
+                    *offset += before - input.len();
+                    Ok(num_read)
+                }
+                Inner::Decoded(data) => data.read(buf),
+            }
+        }
+    }
+}
@@ -30,6 +30,11 @@ pub use store_impls::{dynamic as store, loose};
 
 pub mod alternate;
 
+pub mod compat;
+
+pub mod quarantine;
+pub use quarantine::Quarantine;
+
 /// A way to access objects along with pre-configured thread-local caches for packed base objects as well as objects themselves.
 ///
 /// By default, no cache will be used.
@@ -133,6 +138,24 @@ pub struct Store {
 
     /// The amount of times we re-read the disk state to consolidate our in-memory representation.
     pub(crate) num_disk_state_consolidation: AtomicUsize,
+    /// The time at which we last re-read the disk state, or `None` if this didn't happen yet.
+    pub(crate) last_disk_state_consolidation: parking_lot::Mutex<Option<std::time::Instant>>,
+    /// Functions to call with the changes discovered when re-reading the disk state.
+    pub(crate) refresh_callbacks: parking_lot::RwLock<Vec<store::refresh::Callback>>,
+    /// The function to fetch missing objects with, if set.
+    pub(crate) promisor: parking_lot::RwLock<Option<store::promisor::Promisor>>,
+    /// The maximum amount of pack data files to keep open, or `None` if there is no limit.
+    pub(crate) max_open_packs: Option<usize>,
+    /// The maximum amount of bytes of pack data to keep mapped, or `None` if there is no limit.
+    pub(crate) max_mapped_pack_bytes: Option<u64>,
+    /// The hint to pass when mapping pack data files.
+    pub(crate) pack_mmap_advice: gix_pack::mmap::Advice,
+    /// The zlib compression level of newly written loose objects.
+    pub(crate) loose_compression_level: u32,
+    /// Information to decide which packs to close first to respect `max_open_packs` and `max_mapped_pack_bytes`.
+    pub(crate) pack_usage: parking_lot::Mutex<store::types::PackUsage>,
+    /// The amount of times we closed a pack to respect `max_open_packs` or `max_mapped_pack_bytes`.
+    pub(crate) num_pack_evictions: AtomicUsize,
     /// If true, we are allowed to use multi-pack indices and they must have the `object_hash` or be ignored.
     use_multi_pack_index: bool,
     /// The hash kind to use for some operations
@@ -0,0 +1,255 @@
+//! A temporary object directory to receive objects in isolation, to be migrated into the main object database
+//! once they are deemed acceptable, or to be discarded otherwise.
+//!
+//! This is what `git receive-pack` does to prevent objects of a rejected push from ever entering the repository,
+//! and what can be used to validate the objects of a fetch before making them visible to other readers.
+//!
+//! The quarantine directory is created within the objects directory, and it lists the objects directory as
+//! alternate in its `info/alternates` file. This way, a [`Store`][crate::Store] opened at
+//! [`Quarantine::path()`] sees all objects of the repository, but writes new loose objects and packs into the quarantine.
+use std::{
+    ffi::OsString,
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::Store;
+
+/// The prefix of quarantine directories within the objects directory, just like git uses it.
+pub const DIRECTORY_PREFIX: &str = "tmp_objdir-incoming-";
+
+///
+pub mod init {
+    /// The error returned by [`Quarantine::new()`][super::Quarantine::new()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not create quarantine directory in '{}'", objects_dir.display())]
+        CreateDirectory {
+            objects_dir: std::path::PathBuf,
+            source: std::io::Error,
+        },
+        #[error("Could not make the objects directory at '{}' absolute", objects_dir.display())]
+        Realpath {
+            objects_dir: std::path::PathBuf,
+            source: gix_path::realpath::Error,
+        },
+        #[error("Could not write the alternates file at '{}'", path.display())]
+        WriteAlternates {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+    }
+}
+
+///
+pub mod migrate {
+    /// The error returned by [`Quarantine::migrate()`][super::Quarantine::migrate()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not move '{}' into the objects directory at '{}'", source_path.display(), destination.display())]
+        Move {
+            source_path: std::path::PathBuf,
+            destination: std::path::PathBuf,
+            source: std::io::Error,
+        },
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+    }
+
+    /// The outcome of [`Quarantine::migrate()`][super::Quarantine::migrate()].
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+    pub struct Outcome {
+        /// The amount of loose objects that were moved into the objects directory.
+        pub loose_objects: usize,
+        /// The amount of packs that were moved into the objects directory.
+        pub packs: usize,
+    }
+}
+
+/// A directory that receives new objects, which can be [migrated](Quarantine::migrate()) into the objects directory or
+/// [discarded](Quarantine::discard()).
+///
+/// If dropped without being migrated, the quarantine directory and all of its objects are removed.
+#[derive(Debug)]
+pub struct Quarantine {
+    /// The directory containing all objects, the parent of `path`.
+    objects_dir: PathBuf,
+    /// The quarantine directory, or `None` once it was migrated or discarded.
+    path: Option<PathBuf>,
+}
+
+/// Lifecycle
+impl Quarantine {
+    /// Create a new quarantine directory within `objects_dir` whose `info/alternates` file points back to `objects_dir`.
+    pub fn new(objects_dir: impl Into<PathBuf>) -> Result<Self, init::Error> {
+        use init::Error;
+        let objects_dir = objects_dir.into();
+        let objects_dir = gix_path::realpath(&objects_dir).map_err(|source| Error::Realpath { source, objects_dir })?;
+        let path = tempfile::Builder::new()
+            .prefix(DIRECTORY_PREFIX)
+            .tempdir_in(&objects_dir)
+            .map_err(|source| Error::CreateDirectory {
+                objects_dir: objects_dir.clone(),
+                source,
+            })?
+            .into_path();
+        let quarantine = Quarantine {
+            objects_dir,
+            path: Some(path),
+        };
+
+        let info_dir = quarantine.path().join("info");
+        let alternates = info_dir.join("alternates");
+        let mut content = gix_path::into_bstr(quarantine.objects_dir.as_path()).into_owned();
+        content.push(b'\n');
+        std::fs::create_dir(&info_dir)
+            .and_then(|_| std::fs::write(&alternates, content))
+            .map_err(|source| Error::WriteAlternates {
+                path: alternates,
+                source,
+            })?;
+        Ok(quarantine)
+    }
+
+    /// Move all objects in the quarantine into the objects directory and remove the quarantine directory.
+    ///
+    /// Loose objects are moved first, followed by packs whose index is moved last so that readers of the
+    /// objects directory only pick up packs that are complete. Objects that already exist in the objects directory
+    /// are not overwritten.
+    ///
+    /// Note that the migration as a whole isn't atomic, but each object becomes visible atomically.
+    pub fn migrate(mut self) -> Result<migrate::Outcome, migrate::Error> {
+        let path = self.path.take().expect("only unset when consumed");
+        let mut out = migrate::Outcome::default();
+        migrate_dir(&path, &self.objects_dir, true, &mut out)?;
+        std::fs::remove_dir_all(&path)?;
+        Ok(out)
+    }
+
+    /// Remove the quarantine directory along with all objects in it.
+    ///
+    /// This is also done when the instance is dropped, but allows to observe errors.
+    pub fn discard(mut self) -> io::Result<()> {
+        let path = self.path.take().expect("only unset when consumed");
+        std::fs::remove_dir_all(path)
+    }
+}
+
+/// Access
+impl Quarantine {
+    /// The directory to write objects into, suitable for opening a [`Store`] on it to see all objects, while writing
+    /// only into the quarantine.
+    pub fn path(&self) -> &Path {
+        self.path.as_deref().expect("only unset when consumed")
+    }
+
+    /// The objects directory that will receive all objects once [migrated](Quarantine::migrate()).
+    pub fn objects_dir(&self) -> &Path {
+        &self.objects_dir
+    }
+
+    /// Return the environment variables to set for git processes, like hooks, to see and write objects as if they
+    /// were using a [`Store`] opened at [`path()`](Quarantine::path()).
+    pub fn environment(&self) -> [(&'static str, OsString); 3] {
+        [
+            ("GIT_QUARANTINE_PATH", self.path().into()),
+            ("GIT_OBJECT_DIRECTORY", self.path().into()),
+            ("GIT_ALTERNATE_OBJECT_DIRECTORIES", self.objects_dir.clone().into()),
+        ]
+    }
+
+    /// Open a store at our [path](Quarantine::path()) which is configured like `store`.
+    pub fn open_store_like(&self, store: &Store) -> io::Result<Store> {
+        Store::at_opts(
+            self.path().to_owned(),
+            &mut store.replacements(),
+            crate::store::init::Options {
+                slots: Default::default(),
+                object_hash: store.object_hash,
+                use_multi_pack_index: store.use_multi_pack_index,
+                current_dir: Some(store.current_dir.clone()),
+                max_open_packs: store.max_open_packs,
+                max_mapped_pack_bytes: store.max_mapped_pack_bytes,
+                pack_mmap_advice: store.pack_mmap_advice,
+                loose_compression_level: store.loose_compression_level,
+            },
+        )
+    }
+}
+
+impl Drop for Quarantine {
+    fn drop(&mut self) {
+        if let Some(path) = self.path.take() {
+            std::fs::remove_dir_all(path).ok();
+        }
+    }
+}
+
+fn migrate_dir(src: &Path, dst: &Path, is_root: bool, out: &mut migrate::Outcome) -> Result<(), migrate::Error> {
+    let is_pack_dir = !is_root && src.file_name().map_or(false, |name| name == "pack");
+    let mut entries = Vec::new();
+    for entry in std::fs::read_dir(src)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        if is_root && name == "info" {
+            continue;
+        }
+        entries.push((copy_priority(&name), entry.file_type()?.is_dir(), entry.path()));
+    }
+    entries.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.2.cmp(&b.2)));
+
+    for (_, is_dir, path) in entries {
+        let destination = dst.join(path.file_name().expect("read_dir yields file names"));
+        if is_dir {
+            match std::fs::create_dir(&destination) {
+                Ok(()) => {}
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {}
+                Err(source) => {
+                    return Err(migrate::Error::Move {
+                        source_path: path,
+                        destination,
+                        source,
+                    })
+                }
+            }
+            migrate_dir(&path, &destination, false, out)?;
+        } else {
+            if !destination.exists() {
+                std::fs::rename(&path, &destination).map_err(|source| migrate::Error::Move {
+                    source_path: path.clone(),
+                    destination,
+                    source,
+                })?;
+            }
+            if is_pack_dir {
+                if path.extension().map_or(false, |ext| ext == "pack") {
+                    out.packs += 1;
+                }
+            } else if !is_root {
+                out.loose_objects += 1;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Order loose objects before packs, and within packs make sure the index comes last, as it's what makes a pack visible.
+fn copy_priority(name: &std::ffi::OsStr) -> usize {
+    let name = name.to_string_lossy();
+    if !name.starts_with("pack") {
+        return 0;
+    }
+    if name.ends_with(".keep") {
+        1
+    } else if name.ends_with(".pack") {
+        2
+    } else if name.ends_with(".rev") {
+        3
+    } else if name.ends_with(".idx") {
+        4
+    } else {
+        5
+    }
+}
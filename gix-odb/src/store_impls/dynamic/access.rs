@@ -17,6 +17,11 @@ impl Store {
         self.use_multi_pack_index
     }
 
+    /// The zlib compression level used when writing loose objects.
+    pub fn loose_compression_level(&self) -> u32 {
+        self.loose_compression_level
+    }
+
     /// An iterator over replacements from object-ids `X` to `X-replaced` as `(X, X-replaced)`, sorted by the original id `X`.
     pub fn replacements(&self) -> impl Iterator<Item = (gix_hash::ObjectId, gix_hash::ObjectId)> + '_ {
         self.replacements.iter().copied()
@@ -42,6 +42,13 @@ pub(crate) mod error {
             /// The original object to lookup
             id: gix_hash::ObjectId,
         },
+        #[error("The promisor failed to fetch the missing object {}", .id)]
+        Promisor {
+            /// The object that was missing
+            id: gix_hash::ObjectId,
+            #[source]
+            source: crate::store::promisor::Error,
+        },
     }
 
     #[derive(Copy, Clone)]
@@ -84,7 +91,7 @@ impl<S> super::Handle<S>
 where
     S: Deref<Target = super::Store> + Clone,
 {
-    fn try_find_cached_inner<'a, 'b>(
+    pub(crate) fn try_find_cached_inner<'a, 'b>(
         &'b self,
         mut id: &'b gix_hash::oid,
         buffer: &'a mut Vec<u8>,
@@ -110,6 +117,7 @@ where
             }
         }
 
+        let mut fetched_from_promisor = false;
         'outer: loop {
             {
                 let marker = snapshot.marker;
@@ -121,7 +129,10 @@ where
                     }) = index.lookup(id)
                     {
                         let pack = match possibly_pack {
-                            Some(pack) => pack,
+                            Some(pack) => {
+                                self.store.mark_pack_used(pack_id);
+                                pack
+                            }
                             None => match self.store.load_pack(pack_id, marker)? {
                                 Some(pack) => {
                                     *possibly_pack = Some(pack);
@@ -161,6 +172,7 @@ where
                             Ok(r) => Ok((
                                 gix_object::Data {
                                     kind: r.kind,
+                                    object_hash: id.kind(),
                                     data: buffer.as_slice(),
                                 },
                                 Some(gix_pack::data::entry::Location {
@@ -262,6 +274,7 @@ where
                                     (
                                         gix_object::Data {
                                             kind: r.kind,
+                                            object_hash: id.kind(),
                                             data: buffer.as_slice(),
                                         },
                                         Some(gix_pack::data::entry::Location {
@@ -298,6 +311,9 @@ where
                     *snapshot = new_snapshot;
                     self.clear_cache();
                 }
+                None if !fetched_from_promisor && self.fetch_from_promisor(id, snapshot)? => {
+                    fetched_from_promisor = true;
+                }
                 None => return Ok(None),
             }
         }
@@ -349,6 +365,7 @@ where
         pack_cache: &mut dyn DecodeEntry,
     ) -> Result<Option<(gix_object::Data<'a>, Option<gix_pack::data::entry::Location>)>, gix_object::find::Error> {
         let mut snapshot = self.snapshot.borrow_mut();
+        self.store.drop_closed_packs(&mut snapshot);
         let mut inflate = self.inflate.borrow_mut();
         self.try_find_cached_inner(id, buffer, &mut inflate, pack_cache, &mut snapshot, None)
             .map_err(|err| Box::new(err) as _)
@@ -363,6 +380,7 @@ where
         assert!(self.store_ref().replacements.is_empty() || self.ignore_replacements, "Everything related to packing must not use replacements. These are not used here, but it should be turned off for good measure.");
 
         let mut snapshot = self.snapshot.borrow_mut();
+        self.store.drop_closed_packs(&mut snapshot);
         let mut inflate = self.inflate.borrow_mut();
         'outer: loop {
             {
@@ -375,7 +393,10 @@ where
                     }) = index.lookup(id)
                     {
                         let pack = match possibly_pack {
-                            Some(pack) => pack,
+                            Some(pack) => {
+                                self.store.mark_pack_used(pack_id);
+                                pack
+                            }
                             None => match self.store.load_pack(pack_id, marker).ok()? {
                                 Some(pack) => {
                                     *possibly_pack = Some(pack);
@@ -464,13 +485,17 @@ where
         );
         let pack_id = PackId::from_intrinsic_pack_id(location.pack_id);
         let mut snapshot = self.snapshot.borrow_mut();
+        self.store.drop_closed_packs(&mut snapshot);
         let marker = snapshot.marker;
         loop {
             {
                 for index in &mut snapshot.indices {
                     if let Some(possibly_pack) = index.pack(pack_id) {
                         let pack = match possibly_pack {
-                            Some(pack) => pack,
+                            Some(pack) => {
+                                self.store.mark_pack_used(pack_id);
+                                pack
+                            }
                             None => {
                                 let pack = self.store.load_pack(pack_id, marker).ok()?.expect(
                                 "BUG: pack must exist from previous call to location_by_oid() and must not be unloaded",
@@ -0,0 +1,146 @@
+use std::{ops::Deref, sync::Arc};
+
+use gix_features::{parallel, zlib};
+use gix_hash::ObjectId;
+
+use super::{find::Error, locate::Location};
+
+/// An object as returned by [`Handle::find_many()`][super::Handle::find_many()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Object {
+    /// The kind of the object.
+    pub kind: gix_object::Kind,
+    /// The decoded data of the object.
+    pub data: Vec<u8>,
+}
+
+/// Options for use in [`Handle::find_many()`][super::Handle::find_many()].
+#[derive(Debug, Clone)]
+pub struct Options<F> {
+    /// The amount of threads to use for decoding packed objects, or `None` to use all logical cores.
+    pub thread_limit: Option<usize>,
+    /// A function to create a pack cache for each thread, to speed up decoding of deltified objects with shared bases.
+    pub make_pack_cache: F,
+}
+
+impl Default for Options<fn() -> gix_pack::cache::Never> {
+    fn default() -> Self {
+        Options {
+            thread_limit: Some(1),
+            make_pack_cache: || gix_pack::cache::Never,
+        }
+    }
+}
+
+/// A packed object to decode, along with its position in the input.
+type PackedObject = (usize, Arc<gix_pack::data::File>, gix_pack::data::Entry);
+
+impl<S> super::Handle<S>
+where
+    S: Deref<Target = super::Store> + Clone,
+{
+    /// Find all objects in `ids` and return them in the same order, with `None` for each object that doesn't exist.
+    ///
+    /// Packed objects are decoded in the order they are stored in their packs to benefit from locality, possibly
+    /// on multiple threads as configured in `options`. This is much faster than looking up each object individually
+    /// if many objects are needed, for example to index or to blame all files of a tree.
+    pub fn find_many<C, F>(&self, ids: &[ObjectId], options: Options<F>) -> Result<Vec<Option<Object>>, Error>
+    where
+        C: gix_pack::cache::DecodeEntry,
+        F: Fn() -> C + Send + Clone,
+    {
+        let mut out = vec![None; ids.len()];
+        let mut packed = Vec::new();
+        let mut unpacked = Vec::new();
+        {
+            let mut snapshot = self.snapshot.borrow_mut();
+            self.store.drop_closed_packs(&mut snapshot);
+            for (idx, id) in ids.iter().enumerate() {
+                match self.locate(id, &mut snapshot)? {
+                    Location::Packed { pack, entry } => packed.push((idx, pack, entry)),
+                    Location::Loose { .. } => unpacked.push(idx),
+                    Location::Missing => {}
+                }
+            }
+        }
+        packed.sort_by_key(|(_idx, pack, entry)| (Arc::as_ptr(pack) as usize, entry.data_offset));
+
+        let num_threads = parallel::num_threads(options.thread_limit);
+        let chunk_size = (packed.len() / num_threads).max(1);
+        let make_pack_cache = options.make_pack_cache;
+        parallel::in_parallel_if(
+            || packed.len() > 1,
+            packed.chunks(chunk_size),
+            options.thread_limit,
+            move |_| (zlib::Inflate::default(), make_pack_cache(), Vec::new()),
+            |chunk: &[PackedObject], (inflate, pack_cache, buf)| {
+                chunk
+                    .iter()
+                    .map(|(idx, pack, entry)| {
+                        // Bases of ref-deltas need a lookup by id, which is left to the handle.
+                        let res = pack
+                            .decode_entry(entry.clone(), buf, inflate, &|_, _| None, pack_cache)
+                            .map(|outcome| Object {
+                                kind: outcome.kind,
+                                data: buf.clone(),
+                            });
+                        (*idx, res)
+                    })
+                    .collect::<Vec<_>>()
+            },
+            Collect {
+                out: &mut out,
+                unresolved: &mut unpacked,
+            },
+        )?;
+
+        unpacked.sort_unstable();
+        let mut snapshot = self.snapshot.borrow_mut();
+        let mut inflate = self.inflate.borrow_mut();
+        let mut buf = Vec::new();
+        for idx in unpacked {
+            out[idx] = self
+                .try_find_cached_inner(
+                    &ids[idx],
+                    &mut buf,
+                    &mut inflate,
+                    &mut gix_pack::cache::Never,
+                    &mut snapshot,
+                    None,
+                )?
+                .map(|(data, _location)| Object {
+                    kind: data.kind,
+                    data: data.data.to_owned(),
+                });
+        }
+        Ok(out)
+    }
+}
+
+/// Put decoded objects into place, and remember which ones need to be decoded by the handle.
+struct Collect<'a> {
+    out: &'a mut Vec<Option<Object>>,
+    unresolved: &'a mut Vec<usize>,
+}
+
+impl parallel::Reduce for Collect<'_> {
+    type Input = Vec<(usize, Result<Object, gix_pack::data::decode::Error>)>;
+    type FeedProduce = ();
+    type Output = ();
+    type Error = Error;
+
+    fn feed(&mut self, items: Self::Input) -> Result<Self::FeedProduce, Self::Error> {
+        for (idx, res) in items {
+            match res {
+                Ok(object) => self.out[idx] = Some(object),
+                Err(gix_pack::data::decode::Error::DeltaBaseUnresolved(_)) => self.unresolved.push(idx),
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Ok(())
+    }
+
+    fn finalize(self) -> Result<Self::Output, Self::Error> {
+        Ok(())
+    }
+}
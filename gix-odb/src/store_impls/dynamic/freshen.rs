@@ -0,0 +1,58 @@
+use std::ops::Deref;
+
+use gix_hash::oid;
+
+use super::locate::Location;
+
+mod error {
+    /// The error returned by [`Handle::freshen()`](crate::store::Handle::freshen()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Find(#[from] crate::store::find::Error),
+        #[error("Could not update the modification time of '{}'", path.display())]
+        Io {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+    }
+}
+pub use error::Error;
+
+impl<S> super::Handle<S>
+where
+    S: Deref<Target = super::Store> + Clone,
+{
+    /// Set the modification time of the object with `id` to now and return `true`, so it isn't pruned before new objects that
+    /// refer to it become reachable. This is what `git` does instead of writing objects that already exist.
+    ///
+    /// Loose objects are freshened directly, and packed objects by freshening their pack.
+    /// Return `false` if the object doesn't exist, or if it's stored in a cruft pack whose objects have their own modification
+    /// times. In that case, the object should be written again as loose object to protect it.
+    pub fn freshen(&self, id: &oid) -> Result<bool, Error> {
+        let mut snapshot = self.snapshot.borrow_mut();
+        self.store.drop_closed_packs(&mut snapshot);
+        match self.locate(id, &mut snapshot)? {
+            Location::Packed { pack, .. } => {
+                let path = pack.path();
+                if path.with_extension("mtimes").is_file() {
+                    return Ok(false);
+                }
+                filetime::set_file_mtime(path, filetime::FileTime::now()).map_err(|source| Error::Io {
+                    path: path.to_owned(),
+                    source,
+                })?;
+                Ok(true)
+            }
+            Location::Loose { db_index, id } => {
+                let db = &snapshot.loose_dbs[db_index];
+                db.freshen(&id).map_err(|source| Error::Io {
+                    path: db.object_path(&id),
+                    source,
+                })
+            }
+            Location::Missing => Ok(false),
+        }
+    }
+}
@@ -112,6 +112,31 @@ pub(crate) mod index_lookup {
             })
         }
 
+        /// Forget the pack with `pack_id` if we hold it, so it can be closed, which requires it to be loaded from the store again.
+        pub(crate) fn drop_pack(&mut self, pack_id: types::PackId) {
+            if self.id != pack_id.index {
+                return;
+            }
+            match (&mut self.file, pack_id.multipack_index) {
+                (handle::SingleOrMultiIndex::Single { data, .. }, None) => *data = None,
+                (handle::SingleOrMultiIndex::Multi { data, .. }, Some(pack_index)) => {
+                    if let Some(pack) = data.get_mut(pack_index as usize) {
+                        *pack = None;
+                    }
+                }
+                // The slot now holds a different kind of index, and we can't hold the pack.
+                _ => {}
+            }
+        }
+
+        /// Forget all packs we hold so they can be closed, which requires them to be loaded from the store again.
+        pub(crate) fn drop_packs(&mut self) {
+            match &mut self.file {
+                handle::SingleOrMultiIndex::Single { data, .. } => *data = None,
+                handle::SingleOrMultiIndex::Multi { data, .. } => data.iter_mut().for_each(|pack| *pack = None),
+            }
+        }
+
         /// Return true if the given object id exists in this index
         pub(crate) fn contains(&self, object_id: &oid) -> bool {
             match &self.file {
@@ -353,6 +378,10 @@ impl TryFrom<&super::Store> for super::Store {
                 object_hash: Default::default(),
                 use_multi_pack_index: false,
                 current_dir: s.current_dir.clone().into(),
+                max_open_packs: s.max_open_packs,
+                max_mapped_pack_bytes: s.max_mapped_pack_bytes,
+                pack_mmap_advice: s.pack_mmap_advice,
+                loose_compression_level: s.loose_compression_level,
             },
         )
     }
@@ -37,6 +37,7 @@ where
             }
         }
 
+        let mut fetched_from_promisor = false;
         'outer: loop {
             {
                 let marker = snapshot.marker;
@@ -48,7 +49,10 @@ where
                     }) = index.lookup(id)
                     {
                         let pack = match possibly_pack {
-                            Some(pack) => pack,
+                            Some(pack) => {
+                                self.store.mark_pack_used(pack_id);
+                                pack
+                            }
                             None => match self.store.load_pack(pack_id, marker)? {
                                 Some(pack) => {
                                     *possibly_pack = Some(pack);
@@ -172,6 +176,9 @@ where
                     *snapshot = new_snapshot;
                     self.clear_cache();
                 }
+                None if !fetched_from_promisor && self.fetch_from_promisor(id, snapshot)? => {
+                    fetched_from_promisor = true;
+                }
                 None => return Ok(None),
             }
         }
@@ -184,6 +191,7 @@ where
 {
     fn try_header(&self, id: &oid) -> Result<Option<Header>, gix_object::find::Error> {
         let mut snapshot = self.snapshot.borrow_mut();
+        self.store.drop_closed_packs(&mut snapshot);
         let mut inflate = self.inflate.borrow_mut();
         self.try_header_inner(id, &mut inflate, &mut snapshot, None)
             .map_err(|err| Box::new(err) as _)
@@ -19,6 +19,26 @@ pub struct Options {
     /// The current directory of the process at the time of instantiation.
     /// If unset, it will be retrieved using `std::env::current_dir()`.
     pub current_dir: Option<std::path::PathBuf>,
+    /// The maximum amount of pack data files to keep open at the same time, or `None` to keep all packs open once they were needed.
+    ///
+    /// If the limit is exceeded, the least recently used packs are closed and will be reopened when needed again.
+    /// This is useful to not run out of file handles or memory maps in long-running processes that access repositories with many packs.
+    /// Note that handles keep the packs they use open until they learn that packs were closed, which they check on each object lookup.
+    pub max_open_packs: Option<usize>,
+    /// The maximum amount of bytes of pack data to keep memory-mapped at the same time, or `None` to not limit it,
+    /// similar to `core.packedGitLimit`.
+    ///
+    /// Packs are always mapped in their entirety, so if the limit is exceeded the least recently used packs are closed
+    /// just like with [`max_open_packs`](Self::max_open_packs). The pack that was used last is never closed, even if it
+    /// alone exceeds the limit.
+    pub max_mapped_pack_bytes: Option<u64>,
+    /// The hint to pass to the operating system about how the memory maps of pack data files are going to be accessed.
+    ///
+    /// [`Random`](gix_pack::mmap::Advice::Random) helps with very large packs of which only small portions are needed,
+    /// whereas [`WillNeed`](gix_pack::mmap::Advice::WillNeed) is beneficial for small packs that are read entirely.
+    pub pack_mmap_advice: gix_pack::mmap::Advice,
+    /// The zlib compression level to use when writing loose objects, ranging from 0 for no compression to 9 for the best compression.
+    pub loose_compression_level: u32,
 }
 
 impl Default for Options {
@@ -28,6 +48,10 @@ impl Default for Options {
             object_hash: Default::default(),
             use_multi_pack_index: true,
             current_dir: None,
+            max_open_packs: None,
+            max_mapped_pack_bytes: None,
+            pack_mmap_advice: Default::default(),
+            loose_compression_level: gix_features::zlib::stream::deflate::DEFAULT_LEVEL,
         }
     }
 }
@@ -77,6 +101,10 @@ impl Store {
             object_hash,
             use_multi_pack_index,
             current_dir,
+            max_open_packs,
+            max_mapped_pack_bytes,
+            pack_mmap_advice,
+            loose_compression_level,
         }: Options,
     ) -> std::io::Result<Self> {
         let _span = gix_features::trace::detail!("gix_odb::Store::at()");
@@ -121,6 +149,15 @@ impl Store {
             num_handles_stable: Default::default(),
             num_handles_unstable: Default::default(),
             num_disk_state_consolidation: Default::default(),
+            last_disk_state_consolidation: Default::default(),
+            refresh_callbacks: Default::default(),
+            promisor: Default::default(),
+            max_open_packs,
+            max_mapped_pack_bytes,
+            pack_mmap_advice,
+            loose_compression_level,
+            pack_usage: Default::default(),
+            num_pack_evictions: Default::default(),
         })
     }
 }
@@ -7,10 +7,10 @@ use std::{
         atomic::{AtomicU16, AtomicUsize, Ordering},
         Arc,
     },
-    time::SystemTime,
+    time::{Instant, SystemTime},
 };
 
-use crate::store::{handle, types, RefreshMode};
+use crate::store::{handle, refresh, types, RefreshMode};
 
 pub(crate) struct Snapshot {
     /// Indices ready for object lookup or contains checks, ordered usually by modification data, recent ones first.
@@ -19,6 +19,8 @@ pub(crate) struct Snapshot {
     pub(crate) loose_dbs: Arc<Vec<crate::loose::Store>>,
     /// remember what this state represents and to compare to other states.
     pub(crate) marker: types::SlotIndexMarker,
+    /// The amount of pack evictions in the store at the time the snapshot was taken, to learn when packs we hold were closed.
+    pub(crate) num_pack_evictions: usize,
 }
 
 mod error {
@@ -96,6 +98,20 @@ impl super::Store {
                     RefreshMode::AfterAllIndicesLoaded => {
                         self.consolidate_with_disk_state(false /* needs init */, true /*load one new index*/)
                     }
+                    RefreshMode::AfterAllIndicesLoadedAtMostEvery(interval) => {
+                        let is_due = self
+                            .last_disk_state_consolidation
+                            .lock()
+                            .map_or(true, |last| last.elapsed() >= interval);
+                        if is_due {
+                            self.consolidate_with_disk_state(
+                                false, /* needs init */
+                                true,  /*load one new index*/
+                            )
+                        } else {
+                            Ok(None)
+                        }
+                    }
                 }
             }
         }
@@ -179,6 +195,17 @@ impl super::Store {
         needs_init: bool,
         load_new_index: bool,
     ) -> Result<Option<Snapshot>, Error> {
+        self.consolidate_with_disk_state_and_changes(needs_init, load_new_index)
+            .map(|(snapshot, _changes)| snapshot)
+    }
+
+    /// Like [`consolidate_with_disk_state()`](Self::consolidate_with_disk_state()), but also return the changes we discovered,
+    /// which are empty if another thread refreshed in the meantime.
+    pub(crate) fn consolidate_with_disk_state_and_changes(
+        &self,
+        needs_init: bool,
+        load_new_index: bool,
+    ) -> Result<(Option<Snapshot>, refresh::Changes), Error> {
         let index = self.index.load();
         let previous_index_state = Arc::as_ptr(&index) as usize;
 
@@ -190,7 +217,7 @@ impl super::Store {
         let index = self.index.load();
         if previous_index_state != Arc::as_ptr(&index) as usize {
             // Someone else took the look before and changed the index. Return it without doing any additional work.
-            return Ok(Some(self.collect_snapshot()));
+            return Ok((Some(self.collect_snapshot()), Default::default()));
         }
 
         let was_uninitialized = !index.is_initialized();
@@ -201,13 +228,28 @@ impl super::Store {
         // If so, make sure we collect the snapshot instead of returning None in case nothing actually changed, which is likely with a
         // race like this.
         if !was_uninitialized && needs_init {
-            return Ok(Some(self.collect_snapshot()));
+            return Ok((Some(self.collect_snapshot()), Default::default()));
         }
         self.num_disk_state_consolidation.fetch_add(1, Ordering::Relaxed);
+        *self.last_disk_state_consolidation.lock() = Some(Instant::now());
 
         let db_paths: Vec<_> = std::iter::once(objects_directory.to_owned())
             .chain(crate::alternate::resolve(objects_directory.clone(), &self.current_dir)?)
             .collect();
+        let mut changes = refresh::Changes::default();
+        if !was_uninitialized {
+            let previous_db_paths: Vec<_> = index.loose_dbs.iter().map(|db| &db.path).collect();
+            changes.added_databases = db_paths
+                .iter()
+                .filter(|path| !previous_db_paths.contains(path))
+                .cloned()
+                .collect();
+            changes.removed_databases = previous_db_paths
+                .into_iter()
+                .filter(|path| !db_paths.contains(path))
+                .cloned()
+                .collect();
+        }
 
         // turn db paths into loose object databases. Reuse what's there, but only if it is in the right order.
         let loose_dbs = if was_uninitialized
@@ -220,7 +262,10 @@ impl super::Store {
             Arc::new(
                 db_paths
                     .iter()
-                    .map(|path| crate::loose::Store::at(path, self.object_hash))
+                    .map(|path| {
+                        crate::loose::Store::at(path, self.object_hash)
+                            .with_compression_level(self.loose_compression_level)
+                    })
                     .collect::<Vec<_>>(),
             )
         } else {
@@ -256,6 +301,8 @@ impl super::Store {
                     let files =
                         Option::as_ref(&files_guard).expect("slot is set or we wouldn't know it points to this file");
                     if index_info.is_multi_index() && files.mtime() != mtime {
+                        changes.added_indices.push(index_info.path().to_owned());
+                        changes.removed_indices.push(index_info.path().to_owned());
                         // we have a changed multi-pack index. We can't just change the existing slot as it may alter slot indices
                         // that are currently available. Instead we have to move what's there into a new slot, along with the changes,
                         // and later free the slot or dispose of the index in the slot (like we do for removed/missing files).
@@ -274,9 +321,13 @@ impl super::Store {
                         new_slot_map_indices.push(slot_idx);
                     }
                 }
-                None => index_paths_to_add.push_back((index_info, mtime, None)),
+                None => {
+                    changes.added_indices.push(index_info.path().to_owned());
+                    index_paths_to_add.push_back((index_info, mtime, None))
+                }
             }
         }
+        changes.removed_indices.extend(idx_by_index_path.keys().cloned());
         let needs_stable_indices = self.maintain_stable_indices(&write);
 
         let mut next_possibly_free_index = index
@@ -407,7 +458,7 @@ impl super::Store {
         }
 
         let new_index = self.index.load();
-        Ok(if index.state_id() == new_index.state_id() {
+        let snapshot = if index.state_id() == new_index.state_id() {
             // there was no change, and nothing was loaded in the meantime, reflect that in the return value to not get into loops
             None
         } else {
@@ -415,7 +466,16 @@ impl super::Store {
                 self.load_next_index(new_index);
             }
             Some(self.collect_snapshot())
-        })
+        };
+        drop(write);
+
+        if was_uninitialized {
+            changes = Default::default();
+        } else if !changes.is_empty() {
+            // Only call back without holding the lock so callbacks are free to use the store.
+            self.notify_refresh_callbacks(&changes);
+        }
+        Ok((snapshot, changes))
     }
 
     pub(crate) fn collect_indices_and_mtime_sorted_by_size(
@@ -426,7 +486,7 @@ impl super::Store {
         let mut indices_by_modification_time = Vec::with_capacity(initial_capacity.unwrap_or_default());
         for db_path in db_paths {
             let packs = db_path.join("pack");
-            let entries = match std::fs::read_dir(packs) {
+            let entries = match std::fs::read_dir(&packs) {
                 Ok(e) => e,
                 Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
                 Err(err) => return Err(err.into()),
@@ -443,44 +503,71 @@ impl super::Store {
                 .map(|(p, md)| md.modified().map_err(Error::from).map(|mtime| (p, mtime, md.len())))
                 .collect::<Result<Vec<_>, _>>()?;
 
-            let multi_index_info = multi_pack_index_object_hash
-                .and_then(|hash| {
-                    indices.iter().find_map(|(p, a, b)| {
-                        is_multipack_index(p)
-                            .then(|| {
-                                // we always open the multi-pack here to be able to remove indices
-                                gix_pack::multi_index::File::at(p)
-                                    .ok()
-                                    .filter(|midx| midx.object_hash() == hash)
-                                    .map(|midx| (midx, *a, *b))
+            let check_num_packs = |midx: gix_pack::multi_index::File, mtime: SystemTime, flen: u64| {
+                if midx.num_indices() > PackId::max_packs_in_multi_index() {
+                    Err(Error::TooManyPacksInMultiIndex {
+                        index_path: midx.path().to_owned(),
+                        actual: midx.num_indices(),
+                        limit: PackId::max_packs_in_multi_index(),
+                    })
+                } else {
+                    Ok((midx, mtime, flen))
+                }
+            };
+            // A chain of multi-pack-index layers takes precedence over a single multi-pack-index, just like in `git`.
+            let multi_indices = match multi_pack_index_object_hash {
+                Some(hash) => {
+                    let layers = gix_pack::multi_index::chain::file_path(&packs)
+                        .is_file()
+                        .then(|| gix_pack::multi_index::File::at_chain(&packs).ok())
+                        .flatten()
+                        .filter(|layers| !layers.is_empty() && layers.iter().all(|midx| midx.object_hash() == hash));
+                    match layers {
+                        Some(layers) => layers
+                            .into_iter()
+                            .map(|midx| {
+                                let md = std::fs::metadata(midx.path())?;
+                                check_num_packs(midx, md.modified()?, md.len())
                             })
-                            .flatten()
-                            .map(|t| {
-                                if t.0.num_indices() > PackId::max_packs_in_multi_index() {
-                                    Err(Error::TooManyPacksInMultiIndex {
-                                        index_path: p.to_owned(),
-                                        actual: t.0.num_indices(),
-                                        limit: PackId::max_packs_in_multi_index(),
+                            .collect::<Result<Vec<_>, _>>()?,
+                        None => indices
+                            .iter()
+                            .find_map(|(p, a, b)| {
+                                is_multipack_index(p)
+                                    .then(|| {
+                                        // we always open the multi-pack here to be able to remove indices
+                                        gix_pack::multi_index::File::at(p)
+                                            .ok()
+                                            .filter(|midx| midx.object_hash() == hash)
+                                            .map(|midx| check_num_packs(midx, *a, *b))
                                     })
-                                } else {
-                                    Ok(t)
-                                }
+                                    .flatten()
                             })
-                    })
-                })
-                .transpose()?;
-            if let Some((multi_index, mtime, flen)) = multi_index_info {
-                let index_names_in_multi_index: Vec<_> = multi_index.index_names().iter().map(AsRef::as_ref).collect();
+                            .transpose()?
+                            .into_iter()
+                            .collect(),
+                    }
+                }
+                None => Vec::new(),
+            };
+            if !multi_indices.is_empty() {
+                let index_names_in_multi_index: Vec<_> = multi_indices
+                    .iter()
+                    .flat_map(|(midx, _, _)| midx.index_names().iter().map(AsRef::as_ref))
+                    .collect();
                 let mut indices_not_in_multi_index: Vec<(Either, _, _)> = indices
                     .into_iter()
                     .filter_map(|(path, a, b)| {
-                        (path != multi_index.path()
+                        (!is_multipack_index(&path)
                             && !index_names_in_multi_index
                                 .contains(&Path::new(path.file_name().expect("file name present"))))
                         .then_some((Either::IndexPath(path), a, b))
                     })
                     .collect();
-                indices_not_in_multi_index.insert(0, (Either::MultiIndexFile(Arc::new(multi_index)), mtime, flen));
+                for (pos, (multi_index, mtime, flen)) in multi_indices.into_iter().enumerate() {
+                    indices_not_in_multi_index
+                        .insert(pos, (Either::MultiIndexFile(Arc::new(multi_index)), mtime, flen));
+                }
                 indices_by_modification_time.extend(indices_not_in_multi_index);
             } else {
                 indices_by_modification_time.extend(
@@ -619,6 +706,8 @@ impl super::Store {
     }
 
     pub(crate) fn collect_snapshot(&self) -> Snapshot {
+        // Obtain this first so we rather drop packs once too often than hold on to closed ones.
+        let num_pack_evictions = self.num_pack_evictions.load(Ordering::SeqCst);
         let index = self.index.load();
         let indices = if index.is_initialized() {
             index
@@ -647,6 +736,7 @@ impl super::Store {
             indices,
             loose_dbs: Arc::clone(&index.loose_dbs),
             marker: index.marker(),
+            num_pack_evictions,
         }
     }
 }
@@ -3,7 +3,7 @@ use std::{
     sync::{atomic::Ordering, Arc},
 };
 
-use crate::store::{handle, types};
+use crate::store::{handle, load_index, types};
 
 impl super::Store {
     /// If Ok(None) is returned, the pack-id was stale and referred to an unloaded pack or a pack which couldn't be
@@ -11,10 +11,24 @@ impl super::Store {
     /// If the oid is known, just load indices again to continue
     /// (objects rarely ever removed so should be present, maybe in another pack though),
     /// and redo the entire lookup for a valid pack id whose pack can probably be loaded next time.
+    ///
+    /// If a pack is returned, other packs may be closed to respect the maximum amount of open packs or mapped pack bytes.
     pub(crate) fn load_pack(
         &self,
         id: types::PackId,
         marker: types::SlotIndexMarker,
+    ) -> std::io::Result<Option<Arc<gix_pack::data::File>>> {
+        let pack = self.load_pack_inner(id, marker)?;
+        if pack.is_some() {
+            self.respect_pack_limits(id);
+        }
+        Ok(pack)
+    }
+
+    fn load_pack_inner(
+        &self,
+        id: types::PackId,
+        marker: types::SlotIndexMarker,
     ) -> std::io::Result<Option<Arc<gix_pack::data::File>>> {
         let index = self.index.load();
         if index.generation != marker.generation {
@@ -24,10 +38,15 @@ impl super::Store {
             path: &Path,
             id: types::PackId,
             object_hash: gix_hash::Kind,
+            advice: gix_pack::mmap::Advice,
         ) -> std::io::Result<Arc<gix_pack::data::File>> {
             gix_pack::data::File::at(path, object_hash)
                 .map(|mut pack| {
                     pack.id = id.to_intrinsic_pack_id();
+                    if advice != gix_pack::mmap::Advice::Normal {
+                        // Hints are only an optimization, so not being able to apply them isn't an error.
+                        pack.advise(advice).ok();
+                    }
                     Arc::new(pack)
                 })
                 .map_err(|err| match err {
@@ -56,9 +75,11 @@ impl super::Store {
                                 let mut files = slot.files.load_full();
                                 let files_mut = Arc::make_mut(&mut files);
                                 let pack = match files_mut {
-                                    Some(types::IndexAndPacks::Index(bundle)) => bundle
-                                        .data
-                                        .load_with_recovery(|path| load_pack(path, id, self.object_hash))?,
+                                    Some(types::IndexAndPacks::Index(bundle)) => {
+                                        bundle.data.load_with_recovery(|path| {
+                                            load_pack(path, id, self.object_hash, self.pack_mmap_advice)
+                                        })?
+                                    }
                                     Some(types::IndexAndPacks::MultiIndex(_)) => {
                                         // something changed between us getting the lock, trigger a complete index refresh.
                                         None
@@ -100,7 +121,9 @@ impl super::Store {
                                             .data
                                             .get_mut(pack_index as usize)
                                             .expect("BUG: must set this handle to be stable")
-                                            .load_with_recovery(|path| load_pack(path, id, self.object_hash))?,
+                                            .load_with_recovery(|path| {
+                                                load_pack(path, id, self.object_hash, self.pack_mmap_advice)
+                                            })?,
                                         None => {
                                             unreachable!("BUG: must set this handle to be stable to avoid slots to be cleared/changed")
                                         }
@@ -122,6 +145,125 @@ impl super::Store {
         }
     }
 
+    /// Remember that the pack with `id` was just used, so it's among the last to be closed to respect the maximum amount of
+    /// open packs or mapped pack bytes.
+    pub(crate) fn mark_pack_used(&self, id: types::PackId) {
+        if !self.has_pack_limits() {
+            return;
+        }
+        self.pack_usage.lock().mark_used(id);
+    }
+
+    fn has_pack_limits(&self) -> bool {
+        self.max_open_packs.is_some() || self.max_mapped_pack_bytes.is_some()
+    }
+
+    /// Remember that the pack with `id` was just used, and close the least recently used other packs if
+    /// there are more open or more bytes mapped than allowed.
+    fn respect_pack_limits(&self, id: types::PackId) {
+        if !self.has_pack_limits() {
+            return;
+        }
+        let max_open_packs = self.max_open_packs.unwrap_or(usize::MAX);
+        let max_mapped_pack_bytes = self.max_mapped_pack_bytes.unwrap_or(u64::MAX);
+        let mut usage = self.pack_usage.lock();
+        usage.mark_used(id);
+
+        let (mut num_open_packs, mut num_mapped_bytes) = self.open_packs_and_mapped_bytes();
+        while num_open_packs > max_open_packs || num_mapped_bytes > max_mapped_pack_bytes {
+            let least_recently_used = usage
+                .last_used
+                .iter()
+                .filter(|(pack_id, _)| **pack_id != id)
+                .min_by_key(|(_, last_used)| **last_used)
+                .map(|(pack_id, _)| *pack_id);
+            let pack_id = match least_recently_used {
+                Some(pack_id) => pack_id,
+                None => break,
+            };
+            usage.last_used.remove(&pack_id);
+            // The pack may have been unloaded in the mean time, for instance as it was removed from disk.
+            if let Some(pack_len) = self.unload_pack(pack_id) {
+                num_open_packs -= 1;
+                num_mapped_bytes = num_mapped_bytes.saturating_sub(pack_len);
+                usage.record_eviction(pack_id);
+                self.num_pack_evictions.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+    }
+
+    /// Return the amount of pack data files that are currently loaded, whether they are reachable or not,
+    /// along with the total amount of bytes they map.
+    fn open_packs_and_mapped_bytes(&self) -> (usize, u64) {
+        fn count(pack: &types::OnDiskFile<Arc<gix_pack::data::File>>) -> (usize, u64) {
+            pack.loaded().map_or((0, 0), |pack| (1, pack.data_len() as u64))
+        }
+        self.files
+            .iter()
+            .map(|slot| match &**slot.files.load() {
+                Some(types::IndexAndPacks::Index(bundle)) => count(&bundle.data),
+                Some(types::IndexAndPacks::MultiIndex(bundle)) => bundle
+                    .data
+                    .iter()
+                    .map(count)
+                    .fold((0, 0), |(packs, bytes), (p, b)| (packs + p, bytes + b)),
+                None => (0, 0),
+            })
+            .fold((0, 0), |(packs, bytes), (p, b)| (packs + p, bytes + b))
+    }
+
+    /// Unload the pack with `id` so it's closed once all handles drop it, and return the amount of bytes it mapped
+    /// if it was loaded.
+    fn unload_pack(&self, id: types::PackId) -> Option<u64> {
+        let slot = &self.files[id.index];
+        let _lock = slot.write.lock();
+        let mut files = slot.files.load_full();
+        let files_mut = Arc::make_mut(&mut files);
+        fn unload(pack: &mut types::OnDiskFile<Arc<gix_pack::data::File>>) -> Option<u64> {
+            let pack_len = pack.loaded().map(|pack| pack.data_len() as u64)?;
+            pack.unload().then_some(pack_len)
+        }
+        let unloaded = match (files_mut, id.multipack_index) {
+            (Some(types::IndexAndPacks::Index(bundle)), None) => unload(&mut bundle.data),
+            (Some(types::IndexAndPacks::MultiIndex(bundle)), Some(pack_index)) => {
+                bundle.data.get_mut(pack_index as usize).and_then(unload)
+            }
+            // The slot was reused for something else in the mean time.
+            _ => None,
+        };
+        if unloaded.is_some() {
+            slot.files.store(files);
+        }
+        unloaded
+    }
+
+    /// Drop the packs held by `snapshot` that were closed since it was taken, so closed packs aren't kept open by handles.
+    /// They will be obtained from the store again when needed.
+    pub(crate) fn drop_closed_packs(&self, snapshot: &mut load_index::Snapshot) {
+        if snapshot.num_pack_evictions == self.num_pack_evictions.load(Ordering::Relaxed) {
+            return;
+        }
+        let usage = self.pack_usage.lock();
+        // Evictions are recorded while holding the lock, so this is consistent with the log of evicted packs.
+        let num_pack_evictions = self.num_pack_evictions.load(Ordering::SeqCst);
+        match usage.evicted_since(snapshot.num_pack_evictions) {
+            Some(evicted) => {
+                for pack_id in evicted {
+                    for index in &mut snapshot.indices {
+                        index.drop_pack(*pack_id);
+                    }
+                }
+            }
+            None => {
+                // Too many packs were closed to remember them all, so drop all of them to be safe.
+                for index in &mut snapshot.indices {
+                    index.drop_packs();
+                }
+            }
+        }
+        snapshot.num_pack_evictions = num_pack_evictions;
+    }
+
     /// Similar to `.load_pack()`, but for entire indices, bypassing the index entirely and going solely by marker and id.
     /// Returns `None` if the index wasn't available anymore or could otherwise not be loaded, which can be considered a bug
     /// as we should always keep needed indices available.
@@ -0,0 +1,104 @@
+use std::{ops::Deref, sync::Arc};
+
+use gix_hash::oid;
+
+use super::find::Error;
+use crate::store::{handle, load_index};
+
+/// The place an object is stored in.
+pub(crate) enum Location {
+    /// The object is in `pack` at `entry`, which may be a delta.
+    Packed {
+        pack: Arc<gix_pack::data::File>,
+        entry: gix_pack::data::Entry,
+    },
+    /// The object with `id`, which may be a replacement, is in the loose database at `db_index` in the snapshot.
+    Loose {
+        db_index: usize,
+        id: gix_hash::ObjectId,
+    },
+    Missing,
+}
+
+impl<S> super::Handle<S>
+where
+    S: Deref<Target = super::Store> + Clone,
+{
+    /// Find where the object with `id` is stored, after applying replacements, without decoding it.
+    pub(crate) fn locate(&self, id: &oid, snapshot: &mut load_index::Snapshot) -> Result<Location, Error> {
+        let mut id = id;
+        if !self.ignore_replacements {
+            if let Ok(pos) = self
+                .store
+                .replacements
+                .binary_search_by(|(map_this, _)| map_this.as_ref().cmp(id))
+            {
+                id = self.store.replacements[pos].1.as_ref();
+            }
+        }
+
+        let mut fetched_from_promisor = false;
+        'outer: loop {
+            {
+                let marker = snapshot.marker;
+                for (idx, index) in snapshot.indices.iter_mut().enumerate() {
+                    if let Some(handle::index_lookup::Outcome {
+                        object_index: handle::IndexForObjectInPack { pack_id, pack_offset },
+                        index_file: _,
+                        pack: possibly_pack,
+                    }) = index.lookup(id)
+                    {
+                        let pack = match possibly_pack {
+                            Some(pack) => {
+                                self.store.mark_pack_used(pack_id);
+                                pack.clone()
+                            }
+                            None => match self.store.load_pack(pack_id, marker)? {
+                                Some(pack) => {
+                                    *possibly_pack = Some(pack.clone());
+                                    pack
+                                }
+                                None => {
+                                    // The pack wasn't available anymore so we are supposed to try another round with a fresh index
+                                    match self.store.load_one_index(self.refresh, snapshot.marker)? {
+                                        Some(new_snapshot) => {
+                                            *snapshot = new_snapshot;
+                                            self.clear_cache();
+                                            continue 'outer;
+                                        }
+                                        None => return Ok(Location::Missing),
+                                    }
+                                }
+                            },
+                        };
+                        let entry = pack.entry(pack_offset);
+                        if idx != 0 {
+                            snapshot.indices.swap(0, idx);
+                        }
+                        return Ok(Location::Packed { pack, entry });
+                    }
+                }
+            }
+
+            for (idx, lodb) in snapshot.loose_dbs.iter().enumerate() {
+                if lodb.contains(id) {
+                    return Ok(Location::Loose {
+                        db_index: idx,
+                        id: id.to_owned(),
+                    });
+                }
+            }
+
+            match self.store.load_one_index(self.refresh, snapshot.marker)? {
+                Some(new_snapshot) => {
+                    *snapshot = new_snapshot;
+                    self.clear_cache();
+                }
+                None if !fetched_from_promisor && self.fetch_from_promisor(id, snapshot)? => {
+                    fetched_from_promisor = true;
+                }
+                None => return Ok(Location::Missing),
+            }
+        }
+    }
+}
@@ -75,6 +75,7 @@ impl super::Store {
             loose_dbs: index.loose_dbs.len(),
             unreachable_indices,
             unreachable_packs,
+            num_pack_evictions: self.num_pack_evictions.load(Ordering::Relaxed),
         }
     }
 }
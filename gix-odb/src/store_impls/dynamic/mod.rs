@@ -40,6 +40,12 @@ pub enum RefreshMode {
     /// Use this if you expect a lot of missing objects that shouldn't trigger refreshes even after all packs are loaded.
     /// This comes at the risk of not learning that the packs have changed in the mean time.
     Never,
+    /// Like [`AfterAllIndicesLoaded`](Self::AfterAllIndicesLoaded), but only check for changes if the last check, which may have been
+    /// performed by any handle, was at least the given duration ago.
+    ///
+    /// This is useful if missing objects are common, but packs are known to only change every now and then, for instance when repacking
+    /// on a server.
+    AfterAllIndicesLoadedAtMostEvery(std::time::Duration),
 }
 
 impl RefreshMode {
@@ -55,8 +61,18 @@ pub mod find;
 ///
 pub mod prefix;
 
+///
+pub mod find_many;
+
 mod header;
 
+mod locate;
+
+///
+pub mod freshen;
+
+mod stream;
+
 ///
 pub mod iter;
 
@@ -74,6 +90,12 @@ pub(crate) mod handle;
 ///
 pub mod load_index;
 
+///
+pub mod refresh;
+
+///
+pub mod promisor;
+
 ///
 pub mod verify;
 
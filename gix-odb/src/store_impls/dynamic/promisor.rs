@@ -0,0 +1,85 @@
+use std::{ops::Deref, sync::Arc};
+
+use gix_hash::ObjectId;
+
+use crate::store::{find, load_index};
+
+/// The error returned by [promisors](super::Store::set_promisor()) if they couldn't fetch the objects they were asked for.
+pub type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// A function to fetch the given objects and to write them into the object database.
+pub(crate) type Promisor = Arc<dyn Fn(&[ObjectId]) -> Result<(), Error> + Send + Sync + 'static>;
+
+impl super::Store {
+    /// Call `fetch` with the ids of objects that couldn't be found to let it put them into the object database,
+    /// which is how partial clones retrieve objects from their promisor remote on demand.
+    ///
+    /// Once `fetch` returns successfully, the object database is refreshed and the lookup is retried once.
+    /// This affects all lookups of objects and their headers through handles of this store, but
+    /// not [existence checks](gix_object::Exists), which are expected to be cheap.
+    ///
+    /// Note that `fetch` must not look up missing objects through handles of this store, as it would be called recursively.
+    pub fn set_promisor(&self, fetch: impl Fn(&[ObjectId]) -> Result<(), Error> + Send + Sync + 'static) {
+        *self.promisor.write() = Some(Arc::new(fetch));
+    }
+
+    /// Remove the function previously set with [`set_promisor()`](Self::set_promisor()), to make missing objects
+    /// stay missing.
+    pub fn unset_promisor(&self) {
+        self.promisor.write().take();
+    }
+
+    /// Return `true` if a [promisor](Self::set_promisor()) is set.
+    pub fn has_promisor(&self) -> bool {
+        self.promisor.read().is_some()
+    }
+}
+
+impl<S> super::Handle<S>
+where
+    S: Deref<Target = super::Store> + Clone,
+{
+    /// Fetch all objects in `ids` that don't exist yet in a single call to the [promisor](super::Store::set_promisor()),
+    /// and return the amount of objects that were requested.
+    ///
+    /// This is useful to obtain many objects at once, instead of having them fetched one at a time as they are looked up.
+    /// If no promisor is set, nothing happens and `0` is returned.
+    pub fn fetch_missing(&self, ids: impl IntoIterator<Item = ObjectId>) -> Result<usize, Error> {
+        let promisor = match self.store.promisor.read().clone() {
+            Some(promisor) => promisor,
+            None => return Ok(0),
+        };
+        let mut missing: Vec<_> = ids
+            .into_iter()
+            .filter(|id| !gix_pack::Find::contains(self, id))
+            .collect();
+        missing.sort();
+        missing.dedup();
+        if missing.is_empty() {
+            return Ok(0);
+        }
+        promisor(&missing)?;
+        self.refresh()?;
+        Ok(missing.len())
+    }
+
+    /// Fetch `id` from the promisor if one is set, and update `snapshot` to see it, returning `true` if a fetch was performed.
+    pub(crate) fn fetch_from_promisor(
+        &self,
+        id: &gix_hash::oid,
+        snapshot: &mut load_index::Snapshot,
+    ) -> Result<bool, find::Error> {
+        let promisor = match self.store.promisor.read().clone() {
+            Some(promisor) => promisor,
+            None => return Ok(false),
+        };
+        promisor(&[id.to_owned()]).map_err(|source| find::Error::Promisor {
+            id: id.to_owned(),
+            source,
+        })?;
+        self.store.refresh()?;
+        *snapshot = self.store.collect_snapshot();
+        self.clear_cache();
+        Ok(true)
+    }
+}
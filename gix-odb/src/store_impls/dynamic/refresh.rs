@@ -0,0 +1,78 @@
+use std::{ops::Deref, path::PathBuf};
+
+use crate::store::load_index;
+
+/// Changes to the object database that were discovered while refreshing it, as returned by [`Store::refresh()`](super::Store::refresh())
+/// and passed to callbacks registered with [`Store::on_refresh()`](super::Store::on_refresh()).
+///
+/// Note that the initial discovery of the object database isn't considered a change.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Changes {
+    /// The paths of pack indices or multi-pack indices that were discovered.
+    ///
+    /// Multi-pack indices that changed on disk are listed here as well as in [`removed_indices`](Self::removed_indices).
+    pub added_indices: Vec<PathBuf>,
+    /// The paths of pack indices or multi-pack indices that disappeared from disk.
+    pub removed_indices: Vec<PathBuf>,
+    /// The objects directories of object databases that were added, typically through `objects/info/alternates`.
+    pub added_databases: Vec<PathBuf>,
+    /// The objects directories of object databases that are no longer used, typically as they were removed from `objects/info/alternates`.
+    pub removed_databases: Vec<PathBuf>,
+}
+
+impl Changes {
+    /// Return `true` if nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.added_indices.is_empty()
+            && self.removed_indices.is_empty()
+            && self.added_databases.is_empty()
+            && self.removed_databases.is_empty()
+    }
+}
+
+/// A function to call with all changes discovered while refreshing the object database.
+pub(crate) type Callback = std::sync::Arc<dyn Fn(&Changes) + Send + Sync + 'static>;
+
+impl super::Store {
+    /// Check the object database on disk for new or removed packs and alternates, update our state accordingly, and return what changed.
+    ///
+    /// Refreshes also happen automatically if objects can't be found, unless prevented by the [refresh mode](super::RefreshMode)
+    /// of the handle doing the lookup. Calling this is useful to learn about the results of repacking right away,
+    /// for instance to evict caches that refer to packs that don't exist anymore.
+    ///
+    /// Note that changes that were picked up concurrently by another thread aren't reported here, but only to the
+    /// [callbacks](Self::on_refresh()).
+    pub fn refresh(&self) -> Result<Changes, load_index::Error> {
+        self.consolidate_with_disk_state_and_changes(false /* needs init */, false /* load one new index */)
+            .map(|(_snapshot, changes)| changes)
+    }
+
+    /// Call `callback` with the changes of each refresh that discovered any, no matter if it was [explicit](Self::refresh())
+    /// or triggered by a handle that couldn't find an object.
+    ///
+    /// The callback is called from the thread that performed the refresh once the new state is visible, and shouldn't
+    /// block for long as it holds up all other callbacks. Callbacks registered while others are called only see subsequent refreshes.
+    pub fn on_refresh(&self, callback: impl Fn(&Changes) + Send + Sync + 'static) {
+        self.refresh_callbacks.write().push(std::sync::Arc::new(callback));
+    }
+
+    pub(crate) fn notify_refresh_callbacks(&self, changes: &Changes) {
+        let callbacks = self.refresh_callbacks.read().clone();
+        for callback in callbacks {
+            callback(changes);
+        }
+    }
+}
+
+impl<S> super::Handle<S>
+where
+    S: Deref<Target = super::Store> + Clone,
+{
+    /// Refresh the object database [like the store does](super::Store::refresh()), and make its current state
+    /// available to this handle right away.
+    pub fn refresh(&self) -> Result<Changes, load_index::Error> {
+        let changes = self.store.refresh()?;
+        *self.snapshot.borrow_mut() = self.store.collect_snapshot();
+        Ok(changes)
+    }
+}
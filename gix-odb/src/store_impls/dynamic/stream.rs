@@ -0,0 +1,42 @@
+use std::ops::Deref;
+
+use gix_hash::oid;
+
+use super::{find::Error, locate::Location};
+use crate::find::Stream;
+
+impl<S> super::Handle<S>
+where
+    S: Deref<Target = super::Store> + Clone,
+{
+    /// Return a stream to read the object identified by `id` as it is decompressed, or `None` if it doesn't exist.
+    ///
+    /// Loose objects and objects stored as base objects in a pack are inflated incrementally while reading, so that
+    /// objects of any size can be checksummed or copied without holding them in memory.
+    /// Deltified objects are decoded fully before they can be read, as delta resolution requires random access to the base object.
+    pub fn find_stream(&self, id: &oid) -> Result<Option<Stream>, Error> {
+        let mut snapshot = self.snapshot.borrow_mut();
+        self.store.drop_closed_packs(&mut snapshot);
+        match self.locate(id, &mut snapshot)? {
+            Location::Packed { pack, entry } if entry.header.is_base() => {
+                return Ok(Some(Stream::from_pack_entry(pack, &entry)))
+            }
+            Location::Packed { .. } => {}
+            Location::Loose { db_index, id } => return Ok(snapshot.loose_dbs[db_index].try_find_stream(&id)?),
+            Location::Missing => return Ok(None),
+        }
+
+        let mut inflate = self.inflate.borrow_mut();
+        let mut buf = Vec::new();
+        Ok(self
+            .try_find_cached_inner(
+                id,
+                &mut buf,
+                &mut inflate,
+                &mut gix_pack::cache::Never,
+                &mut snapshot,
+                None,
+            )?
+            .map(|(data, _location)| Stream::from_data(data)))
+    }
+}
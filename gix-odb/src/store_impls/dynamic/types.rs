@@ -1,4 +1,5 @@
 use std::{
+    collections::{HashMap, VecDeque},
     path::{Path, PathBuf},
     sync::{
         atomic::{AtomicU16, AtomicU32, AtomicUsize, Ordering},
@@ -30,7 +31,7 @@ pub struct SlotIndexMarker {
 }
 
 /// A way to load and refer to a pack uniquely, namespaced by their indexing mechanism, aka multi-pack or not.
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct PackId {
     /// This is the index in the slot map at which the packs index is located.
     pub(crate) index: IndexId,
@@ -226,6 +227,17 @@ impl<T: Clone> OnDiskFile<T> {
             }
         }
     }
+
+    /// Drop our loaded value so it will be loaded from disk again when needed, and return `true` if it was loaded.
+    /// Garbage is kept as it can't be loaded again.
+    pub fn unload(&mut self) -> bool {
+        if matches!(self.state, OnDiskFileState::Loaded(_)) {
+            self.state = OnDiskFileState::Unloaded;
+            true
+        } else {
+            false
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -368,7 +380,7 @@ impl IndexAndPacks {
     fn index_names_to_pack_paths(
         multi_index: &gix_pack::multi_index::File,
     ) -> Vec<OnDiskFile<Arc<gix_pack::data::File>>> {
-        let parent_dir = multi_index.path().parent().expect("parent present");
+        let parent_dir = multi_index.pack_directory();
         let data = multi_index
             .index_names()
             .iter()
@@ -392,6 +404,45 @@ pub(crate) struct MutableIndexAndPack {
     pub(crate) generation: AtomicGeneration,
 }
 
+/// Bookkeeping to be able to close the least recently used packs if there are more open than allowed.
+#[derive(Default)]
+pub(crate) struct PackUsage {
+    /// A counter to track the order in which packs are requested.
+    pub(crate) clock: u64,
+    /// The time at which each loaded pack was last requested.
+    pub(crate) last_used: HashMap<PackId, u64>,
+    /// The most recently closed packs, in the order they were closed, so handles can drop exactly these.
+    evicted: VecDeque<PackId>,
+    /// The amount of evictions that happened before the first one in `evicted`.
+    num_forgotten_evictions: usize,
+}
+
+impl PackUsage {
+    /// The maximum amount of evicted packs to remember before handles that didn't catch up have to drop all their packs.
+    const MAX_EVICTED: usize = 1024;
+
+    /// Remember that the pack with `id` was just requested.
+    pub(crate) fn mark_used(&mut self, id: PackId) {
+        self.clock += 1;
+        self.last_used.insert(id, self.clock);
+    }
+
+    /// Remember that the pack with `id` was closed.
+    pub(crate) fn record_eviction(&mut self, id: PackId) {
+        if self.evicted.len() == Self::MAX_EVICTED {
+            self.evicted.pop_front();
+            self.num_forgotten_evictions += 1;
+        }
+        self.evicted.push_back(id);
+    }
+
+    /// Return the packs that were closed after the first `num_evictions`, or `None` if we don't remember all of them anymore.
+    pub(crate) fn evicted_since(&self, num_evictions: usize) -> Option<impl Iterator<Item = &PackId>> {
+        let skip = num_evictions.checked_sub(self.num_forgotten_evictions)?;
+        Some(self.evicted.iter().skip(skip))
+    }
+}
+
 /// A snapshot about resource usage.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -420,6 +471,9 @@ pub struct Metrics {
     pub unreachable_indices: usize,
     /// Equivalent to `unreachable_indices`, but for mapped packed data files
     pub unreachable_packs: usize,
+    /// The amount of packs that were closed to respect the [maximum of open packs](crate::store::init::Options::max_open_packs)
+    /// or the [maximum of mapped pack bytes](crate::store::init::Options::max_mapped_pack_bytes).
+    pub num_pack_evictions: usize,
     /// The amount of loose object databases currently available for object retrieval.
     ///
     /// There may be more than one if 'alternates' are used.
@@ -207,7 +207,7 @@ impl super::Store {
                         );
                         let outcome = index.verify_integrity(&mut child_progress, should_interrupt, options.clone())?;
 
-                        let index_dir = bundle.multi_index.path().parent().expect("file in a directory");
+                        let index_dir = index.pack_directory();
                         statistics.push(IndexStatistics {
                             path: Default::default(),
                             statistics: SingleOrMultiStatistics::Multi(
@@ -178,6 +178,43 @@ impl Store {
         Ok(Some((size, kind)))
     }
 
+    /// Return a stream to read the object identified by `id` as it is decompressed, or `None` if it does not exist in the database.
+    ///
+    /// Only the header is read to learn about the kind and size of the object, making this suitable for objects of any size.
+    pub fn try_find_stream(&self, id: &gix_hash::oid) -> Result<Option<crate::find::Stream>, Error> {
+        debug_assert_eq!(self.object_hash, id.kind());
+        let path = hash_path(id, self.path.clone());
+        let file = match fs::File::open(&path) {
+            Ok(f) => f,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(Error::Io {
+                    source: err,
+                    action: Self::OPEN_ACTION,
+                    path,
+                })
+            }
+        };
+
+        let mut file = std::io::BufReader::new(file);
+        let mut inflate = zlib::Decompress::new(true);
+        let mut header_buf = [0_u8; HEADER_MAX_SIZE];
+        let consumed_out =
+            zlib::stream::inflate::read(&mut file, &mut inflate, &mut header_buf).map_err(|e| Error::Io {
+                source: e,
+                action: "deflate",
+                path,
+            })?;
+        let (kind, size, header_size) = gix_object::decode::loose_header(&header_buf[..consumed_out])?;
+        Ok(Some(crate::find::Stream::from_loose(
+            kind,
+            size,
+            file,
+            inflate,
+            header_buf[header_size..consumed_out].to_vec(),
+        )))
+    }
+
     fn find_inner<'a>(&self, id: &gix_hash::oid, buf: &'a mut Vec<u8>) -> Result<gix_object::Data<'a>, Error> {
         let path = hash_path(id, self.path.clone());
 
@@ -260,6 +297,10 @@ impl Store {
                 .expect("BUG: here the size is already confirmed to fit into memory"),
             0,
         );
-        Ok(gix_object::Data { kind, data: buf })
+        Ok(gix_object::Data {
+            kind,
+            object_hash: self.object_hash,
+            data: buf,
+        })
     }
 }
@@ -12,6 +12,8 @@ pub struct Store {
     pub(crate) path: PathBuf,
     /// The kind of hash we should assume during iteration and when writing new objects.
     pub(crate) object_hash: gix_hash::Kind,
+    /// The zlib compression level to use when writing objects.
+    pub(crate) compression_level: u32,
 }
 
 /// Initialization
@@ -26,9 +28,18 @@ impl Store {
         Store {
             path: objects_directory.into(),
             object_hash,
+            compression_level: gix_features::zlib::stream::deflate::DEFAULT_LEVEL,
         }
     }
 
+    /// Compress newly written objects with `level`, ranging from 0 for no compression to 9 for the best compression.
+    ///
+    /// This is what `core.looseCompression` controls in git.
+    pub fn with_compression_level(mut self, level: u32) -> Self {
+        self.compression_level = level;
+        self
+    }
+
     /// Return the path to our `objects` directory.
     pub fn path(&self) -> &Path {
         &self.path
@@ -38,6 +49,11 @@ impl Store {
     pub fn object_hash(&self) -> gix_hash::Kind {
         self.object_hash
     }
+
+    /// Return the zlib compression level used when writing objects.
+    pub fn compression_level(&self) -> u32 {
+        self.compression_level
+    }
 }
 
 fn hash_path(id: &gix_hash::oid, mut root: PathBuf) -> PathBuf {
@@ -19,6 +19,10 @@ pub enum Error {
     },
     #[error("An IO error occurred while writing an object")]
     IoRaw(#[from] io::Error),
+    #[error("The object was said to have {expected} bytes, but its data stream ended after {actual} bytes")]
+    StreamTooShort { expected: u64, actual: u64 },
+    #[error("The object was said to have {expected} bytes, but its data stream yielded more")]
+    StreamTooLong { expected: u64 },
     #[error("Could not turn temporary file into persisted file at '{target}'")]
     Persist {
         source: tempfile::PersistError,
@@ -64,14 +68,15 @@ impl crate::traits::Write for Store {
         Ok(self.finalize_object(to)?)
     }
 
-    /// Write the given stream in `from` to disk with at least one syscall.
+    /// Write the given stream in `from` to disk with at least one syscall, hashing and compressing it as it is read.
     ///
+    /// `from` must yield exactly `size` bytes, or no object is written.
     /// This will cost at least 4 IO operations.
     fn write_stream(
         &self,
         kind: gix_object::Kind,
         size: u64,
-        mut from: &mut dyn io::Read,
+        from: &mut dyn io::Read,
     ) -> Result<gix_hash::ObjectId, crate::write::Error> {
         let mut to = self.dest().map_err(Box::new)?;
         to.write_all(&gix_object::encode::loose_header(kind, size))
@@ -81,13 +86,18 @@ impl crate::traits::Write for Store {
                 path: self.path.to_owned(),
             })?;
 
-        io::copy(&mut from, &mut to)
-            .map_err(|err| Error::Io {
-                source: err,
-                message: "stream all data into tempfile in",
-                path: self.path.to_owned(),
-            })
-            .map_err(Box::new)?;
+        let copy_err = |err| Error::Io {
+            source: err,
+            message: "stream all data into tempfile in",
+            path: self.path.to_owned(),
+        };
+        let actual = io::copy(&mut io::Read::take(&mut *from, size), &mut to).map_err(copy_err)?;
+        if actual != size {
+            return Err(Error::StreamTooShort { expected: size, actual }.into());
+        }
+        if from.read(&mut [0])? != 0 {
+            return Err(Error::StreamTooLong { expected: size }.into());
+        }
         to.flush().map_err(Box::new)?;
         Ok(self.finalize_object(to)?)
     }
@@ -105,14 +115,32 @@ impl Store {
     }
 }
 
+/// Freshening
+impl Store {
+    /// Set the modification time of the object with `id` to now if it exists, and return `true`, or `false` if it doesn't exist.
+    ///
+    /// Writers should call this instead of skipping to write an object that is already present, to prevent it from being pruned
+    /// before the new objects referring to it become reachable.
+    pub fn freshen(&self, id: &gix_hash::oid) -> io::Result<bool> {
+        match filetime::set_file_mtime(self.object_path(id), filetime::FileTime::now()) {
+            Ok(()) => Ok(true),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+}
+
 impl Store {
     fn dest(&self) -> Result<hash::Write<CompressedTempfile>, Error> {
         Ok(hash::Write::new(
-            deflate::Write::new(NamedTempFile::new_in(&self.path).map_err(|err| Error::Io {
-                source: err,
-                message: "create named temp file in",
-                path: self.path.to_owned(),
-            })?),
+            deflate::Write::with_level(
+                NamedTempFile::new_in(&self.path).map_err(|err| Error::Io {
+                    source: err,
+                    message: "create named temp file in",
+                    path: self.path.to_owned(),
+                })?,
+                self.compression_level,
+            ),
             self.object_hash,
         ))
     }
@@ -121,7 +149,7 @@ impl Store {
         &self,
         hash::Write { hash, inner: file }: hash::Write<CompressedTempfile>,
     ) -> Result<gix_hash::ObjectId, Error> {
-        let id = gix_hash::ObjectId::from(hash.digest());
+        let id = hash.digest();
         let object_path = loose::hash_path(&id, self.path.clone());
         let object_dir = object_path
             .parent()
@@ -100,3 +100,106 @@ fn no_alternate_in_first_objects_dir() -> crate::Result {
     assert!(alternate::resolve(tmp.path().to_owned(), &std::env::current_dir()?)?.is_empty());
     Ok(())
 }
+
+#[test]
+fn relative_paths_are_relative_to_the_objects_dir_listing_them_and_nesting_is_limited() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let dirs: Vec<_> = (0..=alternate::MAX_DEPTH + 2)
+        .map(|idx| tmp.path().join(format!("d{idx}")))
+        .collect();
+    for pair in dirs.windows(2) {
+        let relative_path = Path::new("..").join(pair[1].file_name().expect("non-root"));
+        alternate_with_content(
+            &pair[0],
+            &pair[1],
+            relative_path.to_str().expect("valid UTF-8").as_bytes().to_owned(),
+            None,
+        )?;
+    }
+
+    let alternates = alternate::resolve(dirs[0].clone(), &std::env::current_dir()?)?;
+    assert_eq!(
+        alternates
+            .iter()
+            .map(|p| p.file_name().expect("non-root").to_str().expect("utf8").to_owned())
+            .collect::<Vec<_>>(),
+        ["d1", "d2", "d3", "d4", "d5", "d6"],
+        "alternates of the directory at the maximum depth are ignored"
+    );
+    Ok(())
+}
+
+#[test]
+fn alternates_reachable_multiple_times_are_returned_once() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let (a, b) = alternate(tmp.path().join("a"), tmp.path().join("b"))?;
+    let c = tmp.path().join("c");
+    alternate_with_content(&a, &c, format!("{}\n{}", b.display(), c.display()).into_bytes(), None)?;
+    alternate(&c, &b)?;
+
+    let alternates = alternate::resolve(a, &std::env::current_dir()?)?;
+    assert_eq!(alternates, [b, c], "diamonds are no cycles");
+    Ok(())
+}
+
+mod edit {
+    use std::path::Path;
+
+    use gix_odb::alternate;
+
+    #[test]
+    fn add_and_remove() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let cwd = std::env::current_dir()?;
+        let objects_dir = tmp.path().join("objects");
+        let unusual = tmp.path().join("#with\ttab\nand newline");
+        let other = tmp.path().join("other");
+        for dir in [&objects_dir, &unusual, &other] {
+            std::fs::create_dir(dir)?;
+        }
+
+        assert!(alternate::add(&objects_dir, &unusual, &cwd)?);
+        assert!(
+            alternate::add(&objects_dir, Path::new("../other"), &cwd)?,
+            "relative paths are relative to the objects directory"
+        );
+        assert!(
+            !alternate::add(&objects_dir, &other, &cwd)?,
+            "adding the same directory again is a no-op"
+        );
+        assert_eq!(
+            alternate::resolve(objects_dir.clone(), &cwd)?,
+            [unusual.clone(), objects_dir.join("../other")],
+            "paths that need it are quoted to survive a round-trip"
+        );
+
+        assert!(matches!(
+            alternate::add(&objects_dir, &tmp.path().join("missing"), &cwd),
+            Err(alternate::edit::Error::NotADirectory { .. })
+        ));
+        assert!(matches!(
+            alternate::add(&objects_dir, &objects_dir, &cwd),
+            Err(alternate::edit::Error::Cycle { .. })
+        ));
+        assert!(
+            alternate::add(&other, &objects_dir, &cwd).is_err(),
+            "cycles are prevented"
+        );
+
+        assert!(alternate::remove(&objects_dir, &other, &cwd)?);
+        assert!(
+            !alternate::remove(&objects_dir, &other, &cwd)?,
+            "it's not listed anymore"
+        );
+        assert_eq!(
+            alternate::resolve(objects_dir.clone(), &cwd)?,
+            std::slice::from_ref(&unusual)
+        );
+        assert!(alternate::remove(&objects_dir, &unusual, &cwd)?);
+        assert!(
+            !objects_dir.join("info").join("alternates").exists(),
+            "the file is removed once there are no alternates"
+        );
+        Ok(())
+    }
+}
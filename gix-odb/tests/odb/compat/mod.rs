@@ -0,0 +1,64 @@
+use gix_hash::Kind;
+use gix_odb::compat::{convert, Map, LOOSE_OBJECT_MAP};
+
+#[test]
+fn map_round_trips_through_its_file() -> crate::Result {
+    let dir = gix_testtools::tempfile::tempdir()?;
+    let path = dir.path().join(LOOSE_OBJECT_MAP);
+    let id = gix_object::compute_hash(Kind::Sha256, gix_object::Kind::Blob, b"a");
+    let compat_id = gix_object::compute_hash(Kind::Sha1, gix_object::Kind::Blob, b"a");
+
+    assert!(
+        Map::at(&path, Kind::Sha256, Kind::Sha1)?.is_empty(),
+        "a missing file is an empty map"
+    );
+    Map::append_to(&path, &[(id, compat_id)])?;
+    Map::append_to(&path, &[])?;
+    let data = std::fs::read(&path)?;
+    assert!(data.starts_with(b"# loose-object-idx\n"), "the header is written once");
+
+    let map = Map::at(&path, Kind::Sha256, Kind::Sha1)?;
+    assert_eq!(map.len(), 1);
+    assert_eq!(map.to_compat(&id), Some(compat_id));
+    assert_eq!(map.from_compat(&compat_id), Some(id));
+    assert_eq!(map.compat_id(gix_object::Kind::Blob, b"a")?, compat_id);
+
+    assert!(
+        Map::from_bytes(&data, Kind::Sha1, Kind::Sha256).is_err(),
+        "ids must be of the expected kinds"
+    );
+    assert!(Map::from_bytes(b"no header\n", Kind::Sha256, Kind::Sha1).is_err());
+    Ok(())
+}
+
+#[test]
+fn convert_commit_translates_only_header_ids() -> crate::Result {
+    let tree = gix_object::compute_hash(Kind::Sha256, gix_object::Kind::Tree, b"");
+    let compat_tree = gix_object::compute_hash(Kind::Sha1, gix_object::Kind::Tree, b"");
+    let commit = format!("tree {tree}\nauthor a <a@example.com> 0 +0000\n\ntree {tree}\n");
+
+    let mut out = Vec::new();
+    convert(
+        gix_object::Kind::Commit,
+        commit.as_bytes(),
+        Kind::Sha256,
+        &|id| (id == tree.as_ref()).then_some(compat_tree),
+        &mut out,
+    )?;
+    assert_eq!(
+        out,
+        format!("tree {compat_tree}\nauthor a <a@example.com> 0 +0000\n\ntree {tree}\n").as_bytes(),
+        "the message is left untouched"
+    );
+
+    let err = convert(
+        gix_object::Kind::Commit,
+        commit.as_bytes(),
+        Kind::Sha256,
+        &|_| None,
+        &mut out,
+    )
+    .unwrap_err();
+    assert!(matches!(err, gix_odb::compat::convert::Error::Unmapped { id } if id == tree));
+    Ok(())
+}
@@ -26,3 +26,66 @@ fn pack_object() {
     can_find(&db, "4dac9989f96bc5b5b1263b582c08f0c5f0b58542"); // pack a2bf
     can_find(&db, "dd25c539efbb0ab018caa4cda2d133285634e9b5"); // pack c043
 }
+
+#[test]
+fn stream_matches_fully_decoded_objects() -> crate::Result {
+    use std::io::Read;
+
+    let db = db();
+    let (mut buf, mut streamed) = (Vec::new(), Vec::new());
+    let (mut num_loose, mut num_packed_base, mut num_packed_delta) = (0, 0, 0);
+    for id in db.iter()? {
+        let id = id?;
+        let header = gix_odb::HeaderExt::header(&db, id)?;
+        match header.num_deltas() {
+            None => num_loose += 1,
+            Some(0) => num_packed_base += 1,
+            Some(_) => num_packed_delta += 1,
+        }
+
+        let data = gix_object::FindExt::find(&db, &id, &mut buf)?;
+        let mut stream = db.find_stream(&id)?.expect("present");
+        assert_eq!(stream.kind, data.kind);
+        assert_eq!(stream.size, data.data.len() as u64);
+        streamed.clear();
+        stream.read_to_end(&mut streamed)?;
+        assert_eq!(streamed, data.data, "{id} streams the same data as it decodes");
+    }
+    assert!(num_loose > 0 && num_packed_base > 0 && num_packed_delta > 0);
+
+    assert!(db
+        .find_stream(&hex_to_id("0000000000000000000000000000000000000000"))?
+        .is_none());
+    Ok(())
+}
+
+#[test]
+fn many_objects_at_once_in_input_order() -> crate::Result {
+    let db = db();
+    let mut ids = db.iter()?.collect::<Result<Vec<_>, _>>()?;
+    ids.reverse();
+    ids.push(hex_to_id("0000000000000000000000000000000000000000"));
+
+    let mut buf = Vec::new();
+    for thread_limit in [Some(1), None] {
+        let objects = db.find_many(
+            &ids,
+            gix_odb::store::find_many::Options {
+                thread_limit,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(objects.len(), ids.len());
+        for (id, object) in ids.iter().zip(objects) {
+            match gix_object::Find::try_find(&db, id, &mut buf)? {
+                Some(data) => {
+                    let object = object.expect("present");
+                    assert_eq!(object.kind, data.kind);
+                    assert_eq!(object.data, data.data, "{id}");
+                }
+                None => assert!(object.is_none(), "missing objects are `None`"),
+            }
+        }
+    }
+    Ok(())
+}
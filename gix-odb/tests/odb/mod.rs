@@ -17,8 +17,10 @@ fn db_small_packs() -> gix_odb::Handle {
 }
 
 pub mod alternate;
+pub mod compat;
 pub mod find;
 pub mod header;
+pub mod quarantine;
 pub mod regression;
 pub mod sink;
 pub mod store;
@@ -0,0 +1,80 @@
+use gix_object::Exists;
+use gix_odb::{Quarantine, Write};
+use gix_testtools::fixture_path_standalone;
+
+use crate::hex_to_id;
+
+#[test]
+fn objects_are_invisible_until_migrated() -> crate::Result {
+    let dir = gix_testtools::tempfile::tempdir()?;
+    let db = gix_odb::at(dir.path())?;
+    let existing = db.write_buf(gix_object::Kind::Blob, b"existing")?;
+
+    let quarantine = Quarantine::new(dir.path())?;
+    assert!(quarantine
+        .path()
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map_or(false, |name| name.starts_with(gix_odb::quarantine::DIRECTORY_PREFIX)));
+    let quarantined_db = gix_odb::at(quarantine.path())?;
+    assert!(
+        quarantined_db.exists(&existing),
+        "objects of the main database are visible"
+    );
+
+    let new = quarantined_db.write_buf(gix_object::Kind::Blob, b"new")?;
+    let pack_name = "pack-11fdfa9e156ab73caae3b6da867192221f2089c2";
+    let pack_dir = quarantine.path().join("pack");
+    std::fs::create_dir(&pack_dir)?;
+    for ext in ["pack", "idx"] {
+        let file_name = format!("{pack_name}.{ext}");
+        std::fs::copy(
+            fixture_path_standalone("objects/pack").join(&file_name),
+            pack_dir.join(file_name),
+        )?;
+    }
+    let packed = hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0");
+    assert!(quarantined_db.exists(&packed));
+    assert!(!db.exists(&new), "new objects don't leak into the main database");
+    assert!(!db.exists(&packed));
+
+    let quarantine_path = quarantine.path().to_owned();
+    let outcome = quarantine.migrate()?;
+    assert_eq!(
+        outcome,
+        gix_odb::quarantine::migrate::Outcome {
+            loose_objects: 1,
+            packs: 1
+        }
+    );
+    assert!(!quarantine_path.exists(), "the quarantine is removed after migration");
+    assert!(
+        !dir.path().join("info").join("alternates").exists(),
+        "our alternates file isn't migrated"
+    );
+    assert!(db.exists(&new));
+    assert!(db.exists(&packed));
+    assert!(db.exists(&existing));
+    Ok(())
+}
+
+#[test]
+fn objects_are_removed_when_discarded_or_dropped() -> crate::Result {
+    let dir = gix_testtools::tempfile::tempdir()?;
+    let db = gix_odb::at(dir.path())?;
+
+    let quarantine = Quarantine::new(dir.path())?;
+    let path = quarantine.path().to_owned();
+    let new = gix_odb::at(&path)?.write_buf(gix_object::Kind::Blob, b"new")?;
+    quarantine.discard()?;
+    assert!(!path.exists());
+    assert!(!db.exists(&new));
+
+    let quarantine = Quarantine::new(dir.path())?;
+    let path = quarantine.path().to_owned();
+    gix_odb::at(&path)?.write_buf(gix_object::Kind::Blob, b"new")?;
+    drop(quarantine);
+    assert!(!path.exists());
+    assert!(!db.exists(&new));
+    Ok(())
+}
@@ -55,7 +55,8 @@ fn multi_index_access() -> crate::Result {
             unused_slots: 32,
             loose_dbs: 0,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            num_pack_evictions: 0,
         },
         "it starts out knowing nothing, it's completely lazy"
     );
@@ -87,7 +88,8 @@ fn multi_index_access() -> crate::Result {
             unused_slots: 31,
             loose_dbs: 1,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            num_pack_evictions: 0,
         },
         "it opened only a single multi-index and its pack - hard to see it's actually a multi-index as it's just one index anyway…"
     );
@@ -107,7 +109,8 @@ fn multi_index_access() -> crate::Result {
             unused_slots: 31,
             loose_dbs: 1,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            num_pack_evictions: 0,
         },
         "A miss means just another refresh with no other change"
     );
@@ -130,7 +133,8 @@ fn multi_index_access() -> crate::Result {
             unused_slots: 31,
             loose_dbs: 1,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            num_pack_evictions: 0,
         },
         "everything seems to remain as it was, even though we moved our multi-index to a new slot and removed the old one"
     );
@@ -167,7 +171,8 @@ fn multi_index_keep_open() -> crate::Result {
             unused_slots: 31,
             loose_dbs: 1,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            num_pack_evictions: 0,
         },
         "it opened the multi-pack index for iteration"
     );
@@ -196,7 +201,8 @@ fn multi_index_keep_open() -> crate::Result {
             unused_slots: 30,
             loose_dbs: 1,
             unreachable_indices: 1,
-            unreachable_packs: 1
+            unreachable_packs: 1,
+            num_pack_evictions: 0,
         },
         "now there is an unreachable index and pack which is still loaded, but whose pack hasn't been loaded"
     );
@@ -338,7 +344,8 @@ fn contains() {
             unused_slots: 29,
             loose_dbs: 1,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            num_pack_evictions: 0,
         },
         "it only refreshed the file list, yielding the loose db to find this object, but no pack was opened yet"
     );
@@ -358,7 +365,8 @@ fn contains() {
             unused_slots: 29,
             loose_dbs: 1,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            num_pack_evictions: 0,
         },
         "it loaded the biggest back only, which is the first in the list"
     );
@@ -380,7 +388,8 @@ fn contains() {
             unused_slots: 29,
             loose_dbs: 1,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            num_pack_evictions: 0,
         },
         "when asking for an object in the smallest pack, all in between packs are also loaded."
     );
@@ -398,7 +407,8 @@ fn contains() {
             unused_slots: 29,
             loose_dbs: 1,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            num_pack_evictions: 0,
         },
         "trigger refreshes each time there is an object miss"
     );
@@ -417,7 +427,8 @@ fn contains() {
             unused_slots: 29,
             loose_dbs: 1,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            num_pack_evictions: 0,
         },
         "if no refreshes are allowed, there is no additional refresh"
     );
@@ -452,7 +463,8 @@ fn lookup() {
             unused_slots: 32,
             loose_dbs: 0,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            num_pack_evictions: 0,
         },
         "nothing happened yet, the store is totally lazy"
     );
@@ -473,6 +485,7 @@ fn lookup() {
         loose_dbs: 1,
         unreachable_indices: 0,
         unreachable_packs: 0,
+        num_pack_evictions: 0,
     };
     assert_eq!(
         handle.store_ref().metrics(),
@@ -506,6 +519,138 @@ fn lookup() {
     );
 }
 
+#[test]
+fn least_recently_used_packs_are_closed_if_too_many_are_open() -> crate::Result {
+    let handle = gix_odb::at_opts(
+        fixture_path_standalone("objects"),
+        None,
+        store::init::Options {
+            max_open_packs: Some(2),
+            ..Default::default()
+        },
+    )?;
+    let mut buf = Vec::new();
+    let mut find = |hex_id: &str| {
+        handle
+            .find(&hex_to_id(hex_id), &mut buf)
+            .map(|obj| obj.kind)
+            .expect("object can always be found")
+    };
+    let open_packs_and_evictions = || {
+        let metrics = handle.store_ref().metrics();
+        (metrics.open_reachable_packs, metrics.num_pack_evictions)
+    };
+
+    find("501b297447a8255d3533c6858bb692575cdefaa0"); // pack 11fd
+    find("4dac9989f96bc5b5b1263b582c08f0c5f0b58542"); // pack a2bf
+    assert_eq!(open_packs_and_evictions(), (2, 0), "the limit isn't reached yet");
+
+    find("dd25c539efbb0ab018caa4cda2d133285634e9b5"); // pack c043
+    assert_eq!(
+        open_packs_and_evictions(),
+        (2, 1),
+        "the least recently used pack was closed to make room for the new one"
+    );
+
+    find("501b297447a8255d3533c6858bb692575cdefaa0");
+    assert_eq!(
+        open_packs_and_evictions(),
+        (2, 2),
+        "closed packs are opened again on demand, closing the next least recently used one"
+    );
+
+    find("dd25c539efbb0ab018caa4cda2d133285634e9b5");
+    assert_eq!(
+        open_packs_and_evictions(),
+        (2, 2),
+        "using a pack that is still open doesn't close any other"
+    );
+    Ok(())
+}
+
+#[test]
+fn packs_count_as_used_when_accessed_not_only_when_opened() -> crate::Result {
+    let handle = gix_odb::at_opts(
+        fixture_path_standalone("objects"),
+        None,
+        store::init::Options {
+            max_open_packs: Some(2),
+            ..Default::default()
+        },
+    )?;
+    let mut buf = Vec::new();
+    let mut find = |hex_id: &str| {
+        handle
+            .find(&hex_to_id(hex_id), &mut buf)
+            .map(|obj| obj.kind)
+            .expect("object can always be found")
+    };
+    let num_evictions = || handle.store_ref().metrics().num_pack_evictions;
+
+    find("501b297447a8255d3533c6858bb692575cdefaa0"); // pack 11fd
+    find("4dac9989f96bc5b5b1263b582c08f0c5f0b58542"); // pack a2bf
+    find("501b297447a8255d3533c6858bb692575cdefaa0");
+    find("dd25c539efbb0ab018caa4cda2d133285634e9b5"); // pack c043
+    assert_eq!(num_evictions(), 1, "one pack had to be closed");
+
+    find("501b297447a8255d3533c6858bb692575cdefaa0");
+    assert_eq!(
+        num_evictions(),
+        1,
+        "the first pack was used more recently than the second one, so it stayed open"
+    );
+    find("4dac9989f96bc5b5b1263b582c08f0c5f0b58542");
+    assert_eq!(
+        num_evictions(),
+        2,
+        "the second pack was closed and has to be opened again"
+    );
+    Ok(())
+}
+
+#[test]
+fn least_recently_used_packs_are_closed_if_too_many_bytes_are_mapped() -> crate::Result {
+    let handle = gix_odb::at_opts(
+        fixture_path_standalone("objects"),
+        None,
+        store::init::Options {
+            max_mapped_pack_bytes: Some(60_000),
+            pack_mmap_advice: gix_pack::mmap::Advice::Random,
+            ..Default::default()
+        },
+    )?;
+    let mut buf = Vec::new();
+    let mut find = |hex_id: &str| {
+        handle
+            .find(&hex_to_id(hex_id), &mut buf)
+            .map(|obj| obj.kind)
+            .expect("object can always be found")
+    };
+    let open_packs_and_evictions = || {
+        let metrics = handle.store_ref().metrics();
+        (metrics.open_reachable_packs, metrics.num_pack_evictions)
+    };
+
+    find("501b297447a8255d3533c6858bb692575cdefaa0"); // pack 11fd, 51875 bytes
+    find("4dac9989f96bc5b5b1263b582c08f0c5f0b58542"); // pack a2bf, 3732 bytes
+    assert_eq!(open_packs_and_evictions(), (2, 0), "the limit isn't reached yet");
+
+    find("dd25c539efbb0ab018caa4cda2d133285634e9b5"); // pack c043, 49113 bytes
+    assert_eq!(
+        open_packs_and_evictions(),
+        (2, 1),
+        "closing the least recently used pack suffices to get below the limit"
+    );
+
+    find("501b297447a8255d3533c6858bb692575cdefaa0");
+    assert_eq!(
+        open_packs_and_evictions(),
+        (1, 3),
+        "as many packs as needed are closed to get below the limit again"
+    );
+    Ok(())
+}
+
 fn assert_all_indices_loaded(handle: &gix_odb::Handle, num_refreshes: usize, open_reachable_indices: usize) {
     assert_eq!(
         handle.store_ref().metrics(),
@@ -519,7 +664,8 @@ fn assert_all_indices_loaded(handle: &gix_odb::Handle, num_refreshes: usize, ope
             unused_slots: 30,
             loose_dbs: 1,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            num_pack_evictions: 0,
         },
         "all indices must be loaded and searched to assure unambiguous object ids"
     );
@@ -748,7 +894,8 @@ fn missing_objects_triggers_everything_is_loaded() {
             unused_slots: 29,
             loose_dbs: 1,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            num_pack_evictions: 0,
         },
         "first refresh triggered by on-disk check, second refresh triggered to see if something changed, contains() only sees indices"
     );
@@ -770,7 +917,8 @@ fn missing_objects_triggers_everything_is_loaded() {
             unused_slots: 29,
             loose_dbs: 1,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            num_pack_evictions: 0,
         },
         "there are still no packs opened as no index contained the object"
     );
@@ -844,7 +992,8 @@ fn auto_refresh_with_and_without_id_stability() -> crate::Result {
             unused_slots: 31,
             loose_dbs: 1,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            num_pack_evictions: 0,
         },
         "one pack was opened"
     );
@@ -869,7 +1018,8 @@ fn auto_refresh_with_and_without_id_stability() -> crate::Result {
             unused_slots: 31,
             loose_dbs: 1,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            num_pack_evictions: 0,
         },
         "the old pack was removed, the new was loaded"
     );
@@ -907,7 +1057,8 @@ fn auto_refresh_with_and_without_id_stability() -> crate::Result {
                 unused_slots: 30,
                 loose_dbs: 1,
                 unreachable_indices: 1,
-                unreachable_packs: 1
+                unreachable_packs: 1,
+                num_pack_evictions: 0,
             },
             "the removed pack is still loaded"
         );
@@ -942,13 +1093,166 @@ fn auto_refresh_with_and_without_id_stability() -> crate::Result {
             unused_slots: 30,
             loose_dbs: 1,
             unreachable_indices: 1,
-            unreachable_packs: 1
+            unreachable_packs: 1,
+            num_pack_evictions: 0,
         },
         "garbaged slots aren't reclaimed until there is the need. Keeping indices open despite them not being accessible anymore."
     );
     Ok(())
 }
 
+#[test]
+fn explicit_refresh_reports_changes_to_callbacks_and_handles_may_throttle_refreshes() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let packs_dir = tmp.path().join("objects/pack");
+    std::fs::create_dir_all(&packs_dir)?;
+    gix_testtools::copy_recursively_into_existing_dir(fixture_path_standalone("objects/pack"), &packs_dir)?;
+    let stem = packs_dir.join("pack-11fdfa9e156ab73caae3b6da867192221f2089c2");
+    let rename_pack = |from: &str, to: &str| {
+        std::fs::rename(
+            stem.with_extension(format!("idx{from}")),
+            stem.with_extension(format!("idx{to}")),
+        )
+        .unwrap();
+        std::fs::rename(
+            stem.with_extension(format!("pack{from}")),
+            stem.with_extension(format!("pack{to}")),
+        )
+        .unwrap();
+    };
+    rename_pack("", ".bak");
+
+    let mut handle = gix_odb::at(tmp.path().join("objects"))?;
+    let observed = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    handle.store_ref().on_refresh({
+        let observed = observed.clone();
+        move |changes| observed.lock().unwrap().push(changes.clone())
+    });
+    assert!(
+        handle.refresh()?.is_empty(),
+        "the initial discovery isn't a change, nor is a refresh without changes on disk"
+    );
+
+    rename_pack(".bak", "");
+    let changes = handle.refresh()?;
+    assert_eq!(
+        changes,
+        store::refresh::Changes {
+            added_indices: vec![stem.with_extension("idx")],
+            ..Default::default()
+        }
+    );
+    assert_eq!(*observed.lock().unwrap(), [changes], "callbacks see the same changes");
+
+    rename_pack("", ".bak");
+    handle.refresh = store::RefreshMode::AfterAllIndicesLoadedAtMostEvery(std::time::Duration::from_secs(3600));
+    let id_in_hidden_pack = hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0");
+    let num_refreshes = handle.store_ref().metrics().num_refreshes;
+    assert!(!handle.exists(&id_in_hidden_pack));
+    assert_eq!(
+        handle.store_ref().metrics().num_refreshes,
+        num_refreshes,
+        "the last refresh was too recent to refresh again"
+    );
+
+    handle.refresh = store::RefreshMode::AfterAllIndicesLoaded;
+    assert!(!handle.exists(&id_in_hidden_pack));
+    let observed = observed.lock().unwrap();
+    assert_eq!(
+        observed.last().map(|changes| &changes.removed_indices),
+        Some(&vec![stem.with_extension("idx")]),
+        "refreshes triggered by missing objects are observed as well"
+    );
+    assert_eq!(observed.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn refresh_callbacks_may_use_the_store() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let packs_dir = tmp.path().join("objects/pack");
+    std::fs::create_dir_all(&packs_dir)?;
+    let store = std::sync::Arc::new(gix_odb::Store::at_opts(
+        tmp.path().join("objects"),
+        &mut None.into_iter(),
+        Default::default(),
+    )?);
+    let nested_changes = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    store.on_refresh({
+        let store = std::sync::Arc::downgrade(&store);
+        let nested_changes = nested_changes.clone();
+        move |_changes| {
+            let store = store.upgrade().expect("store still alive");
+            nested_changes
+                .lock()
+                .unwrap()
+                .push(store.refresh().expect("refresh works"));
+            store.on_refresh(|_| {});
+        }
+    });
+    assert!(store.refresh()?.is_empty());
+
+    gix_testtools::copy_recursively_into_existing_dir(fixture_path_standalone("objects/pack"), &packs_dir)?;
+    assert!(!store.refresh()?.added_indices.is_empty());
+    assert_eq!(
+        *nested_changes.lock().unwrap(),
+        [store::refresh::Changes::default()],
+        "callbacks can refresh and register callbacks without deadlocking, and see the state they were called for"
+    );
+    Ok(())
+}
+
+#[test]
+fn promisor_fetches_missing_objects_on_lookup_and_in_batches() -> crate::Result {
+    let dir = gix_testtools::tempfile::tempdir()?;
+    let handle = gix_odb::at(dir.path())?;
+    let requested = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    handle.store_ref().set_promisor({
+        let requested = requested.clone();
+        let objects_dir = dir.path().to_owned();
+        move |ids| {
+            requested.lock().unwrap().push(ids.to_vec());
+            let source = db();
+            let destination = gix_odb::at(&objects_dir)?;
+            let mut buf = Vec::new();
+            for id in ids {
+                let obj = source.find(id, &mut buf)?;
+                destination.write_buf(obj.kind, obj.data)?;
+            }
+            Ok(())
+        }
+    });
+    assert!(handle.store_ref().has_promisor());
+
+    let blob = hex_to_id("37d4e6c5c48ba0d245164c4e10d5f41140cab980");
+    assert!(!handle.exists(&blob), "existence checks don't fetch objects");
+    let mut buf = Vec::new();
+    assert_eq!(handle.find(&blob, &mut buf)?.kind, gix_object::Kind::Blob);
+    assert_eq!(*requested.lock().unwrap(), [vec![blob]]);
+
+    let tree = hex_to_id("6ba2a0ded519f737fd5b8d5ccfb141125ef3176f");
+    assert_eq!(
+        gix_odb::HeaderExt::header(&handle, tree)?.kind(),
+        gix_object::Kind::Tree
+    );
+
+    let commit = hex_to_id("ffa700b4aca13b80cb6b98a078e7c96804f8e0ec");
+    let tag = hex_to_id("722fe60ad4f0276d5a8121970b5bb9dccdad4ef9");
+    assert_eq!(
+        handle.fetch_missing([tag, blob, commit, tag])?,
+        2,
+        "only missing objects are requested, once"
+    );
+    assert_eq!(requested.lock().unwrap().last(), Some(&vec![tag, commit]));
+    assert!(handle.exists(&commit) && handle.exists(&tag));
+
+    handle.store_ref().unset_promisor();
+    let missing = hex_to_id("595dfd62fc1ad283d61bb47a24e7a1f66398f84d");
+    assert!(gix_object::Find::try_find(&handle, &missing, &mut buf)?.is_none());
+    assert_eq!(requested.lock().unwrap().len(), 3);
+    Ok(())
+}
+
 mod verify {
     use std::sync::atomic::AtomicBool;
 
@@ -997,7 +1301,8 @@ mod verify {
                 unused_slots: 29,
                 loose_dbs: 1,
                 unreachable_indices: 0,
-                unreachable_packs: 0
+                unreachable_packs: 0,
+                num_pack_evictions: 0,
             },
             "verification only discovers files on disk but won't cause them to be opened permanently"
         );
@@ -74,6 +74,38 @@ mod write {
         Ok(())
     }
 
+    #[test]
+    fn streams_must_have_the_given_size() -> crate::Result {
+        let dir = gix_testtools::tempfile::tempdir()?;
+        let db = loose::Store::at(dir.path(), gix_hash::Kind::Sha1);
+        let data = b"hello world";
+
+        let id = db.write_stream(gix_object::Kind::Blob, data.len() as u64, &mut data.as_slice())?;
+        assert_eq!(
+            id,
+            gix_object::compute_hash(gix_hash::Kind::Sha1, gix_object::Kind::Blob, data)
+        );
+
+        for wrong_size in [data.len() as u64 - 1, data.len() as u64 + 1] {
+            let err = db
+                .write_stream(gix_object::Kind::Blob, wrong_size, &mut data.as_slice())
+                .unwrap_err();
+            assert!(
+                err.downcast_ref::<loose::write::Error>().map_or(false, |err| matches!(
+                    err,
+                    loose::write::Error::StreamTooShort { .. } | loose::write::Error::StreamTooLong { .. }
+                )),
+                "{err:?}"
+            );
+        }
+        assert_eq!(
+            db.iter().count(),
+            1,
+            "nothing is written if the stream doesn't have the expected size"
+        );
+        Ok(())
+    }
+
     #[test]
     #[cfg(unix)]
     fn it_writes_objects_with_similar_permissions() -> crate::Result {
@@ -131,6 +163,25 @@ mod write {
     }
 }
 
+#[test]
+fn freshen() -> crate::Result {
+    let dir = gix_testtools::tempfile::tempdir()?;
+    let db = Store::at(dir.path(), gix_hash::Kind::Sha1);
+    let empty_blob = gix_hash::ObjectId::empty_blob(gix_hash::Kind::Sha1);
+    assert!(!db.freshen(&empty_blob)?, "the object doesn't exist yet");
+
+    let id = gix_odb::Write::write_buf(&db, gix_object::Kind::Blob, &[])?;
+    let path = db.object_path(&id);
+    let long_ago = filetime::FileTime::from_unix_time(1_000_000_000, 0);
+    filetime::set_file_mtime(&path, long_ago)?;
+    assert!(db.freshen(&id)?, "the object exists");
+    assert!(
+        filetime::FileTime::from_last_modification_time(&path.metadata()?) > long_ago,
+        "its modification time is now"
+    );
+    Ok(())
+}
+
 mod contains {
     use crate::store::loose::ldb;
 
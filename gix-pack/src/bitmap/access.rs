@@ -0,0 +1,100 @@
+use std::path::Path;
+
+use crate::bitmap::{File, Version};
+
+/// Access methods
+impl File {
+    /// Returns the version of the bitmap file.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+    /// Returns the path from which the bitmap file was loaded.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+    /// Returns the kind of hash function used for the checksums in this file.
+    pub fn object_hash(&self) -> gix_hash::Kind {
+        self.object_hash
+    }
+    /// Returns the [flags](crate::bitmap::flags) stored in the header.
+    pub fn flags(&self) -> u16 {
+        self.flags
+    }
+    /// Returns the amount of commits which have a bitmap.
+    pub fn num_bitmaps(&self) -> u32 {
+        self.entries.len() as u32
+    }
+    /// Returns the checksum of the pack data file these bitmaps belong to.
+    pub fn pack_checksum(&self) -> gix_hash::ObjectId {
+        gix_hash::ObjectId::from(&self.data[Self::HEADER_LEN..][..self.hash_len])
+    }
+    /// Returns the checksum over the entire content of the file (excluding the checksum itself).
+    pub fn checksum(&self) -> gix_hash::ObjectId {
+        gix_hash::ObjectId::from(&self.data[self.data.len() - self.hash_len..])
+    }
+    /// Returns the positions in the pack index of all commits which have a bitmap, in the order in which they are stored.
+    pub fn index_positions(&self) -> impl Iterator<Item = u32> + '_ {
+        self.entries.iter().map(|entry| entry.index_position)
+    }
+    /// Returns the positions of all objects of the given `kind` in the pack, in ascending order.
+    ///
+    /// A position is the index of the object if all objects were sorted by their pack offset.
+    pub fn pack_positions_of_kind(&self, kind: gix_object::Kind) -> Vec<u32> {
+        let bitmap = &self.kinds[Self::KINDS
+            .iter()
+            .position(|k| *k == kind)
+            .expect("all kinds are known")];
+        set_bits(&to_words(bitmap))
+    }
+    /// Returns the positions of all objects in the pack which are reachable from the commit at `index_position` in the
+    /// pack index, in ascending order, or `None` if there is no bitmap for the commit.
+    ///
+    /// A position is the index of the object if all objects were sorted by their pack offset.
+    pub fn reachable_from(&self, index_position: u32) -> Option<Vec<u32>> {
+        let entry = self
+            .entries
+            .iter()
+            .position(|entry| entry.index_position == index_position)?;
+        Some(set_bits(&self.resolve(entry)))
+    }
+
+    /// Return the uncompressed bitmap of `entry`, undoing the XOR with the bitmaps of previous entries.
+    fn resolve(&self, mut entry: usize) -> Vec<u64> {
+        let mut words = to_words(&self.entries[entry].bitmap);
+        while self.entries[entry].xor_offset != 0 {
+            entry -= self.entries[entry].xor_offset as usize;
+            let base = to_words(&self.entries[entry].bitmap);
+            if base.len() > words.len() {
+                words.resize(base.len(), 0);
+            }
+            for (word, base) in words.iter_mut().zip(base) {
+                *word ^= base;
+            }
+        }
+        words
+    }
+}
+
+fn to_words(bitmap: &gix_bitmap::ewah::Vec) -> Vec<u64> {
+    let mut words = Vec::new();
+    bitmap.for_each_set_bit(|bit| {
+        if words.len() <= bit / 64 {
+            words.resize(bit / 64 + 1, 0);
+        }
+        words[bit / 64] |= 1 << (bit % 64);
+        Some(())
+    });
+    words
+}
+
+fn set_bits(words: &[u64]) -> Vec<u32> {
+    words
+        .iter()
+        .enumerate()
+        .flat_map(|(index, word)| {
+            (0..64)
+                .filter(move |bit| word & (1 << bit) != 0)
+                .map(move |bit| (index * 64 + bit) as u32)
+        })
+        .collect()
+}
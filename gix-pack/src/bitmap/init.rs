@@ -0,0 +1,135 @@
+use std::path::Path;
+
+use crate::bitmap::{flags, Entry, File, Version};
+
+mod error {
+    /// The error returned by [File::at()][super::File::at()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not open bitmap file at '{path}'")]
+        Io {
+            source: std::io::Error,
+            path: std::path::PathBuf,
+        },
+        #[error("{message}")]
+        Corrupt { message: &'static str },
+        #[error("Unsupported bitmap version: {version})")]
+        UnsupportedVersion { version: u16 },
+        #[error("The bitmap of the {kind} objects is corrupt")]
+        KindBitmap {
+            kind: gix_object::Kind,
+            source: gix_bitmap::ewah::decode::Error,
+        },
+        #[error("The bitmap of commit entry {entry} is corrupt")]
+        EntryBitmap {
+            entry: u32,
+            source: gix_bitmap::ewah::decode::Error,
+        },
+    }
+}
+
+pub use error::Error;
+
+/// Initialization
+impl File {
+    /// The signature at the start of every bitmap file.
+    pub const SIGNATURE: &'static [u8] = b"BITM";
+    /// The length of the header without the trailing pack checksum, consisting of the signature, the version, the flags
+    /// and the amount of commits with a bitmap.
+    pub const HEADER_LEN: usize = 4 /*signature*/ + 2 /*version*/ + 2 /*flags*/ + 4 /*num entries*/;
+    /// The order in which the bitmaps of objects of a kind are stored right after the header.
+    pub const KINDS: [gix_object::Kind; 4] = [
+        gix_object::Kind::Commit,
+        gix_object::Kind::Tree,
+        gix_object::Kind::Blob,
+        gix_object::Kind::Tag,
+    ];
+
+    /// Open the bitmap file at the given `path`, expecting checksums of kind `object_hash`.
+    pub fn at(path: impl AsRef<Path>, object_hash: gix_hash::Kind) -> Result<Self, Error> {
+        Self::at_inner(path.as_ref(), object_hash)
+    }
+
+    fn at_inner(path: &Path, object_hash: gix_hash::Kind) -> Result<Self, Error> {
+        let data = crate::mmap::read_only(path).map_err(|source| Error::Io {
+            source,
+            path: path.to_owned(),
+        })?;
+        let hash_len = object_hash.len_in_bytes();
+        if data.len() < Self::HEADER_LEN + hash_len * 2 {
+            return Err(Error::Corrupt {
+                message: "bitmap file is too small to contain even a header",
+            });
+        }
+        let (signature, d) = data.split_at(Self::SIGNATURE.len());
+        if signature != Self::SIGNATURE {
+            return Err(Error::Corrupt {
+                message: "Invalid signature",
+            });
+        }
+        let (version, d) = d.split_at(2);
+        let version = match u16::from_be_bytes([version[0], version[1]]) {
+            1 => Version::V1,
+            version => return Err(Error::UnsupportedVersion { version }),
+        };
+        let (flags, d) = d.split_at(2);
+        let flags = u16::from_be_bytes([flags[0], flags[1]]);
+        if flags & flags::FULL_DAG == 0 {
+            return Err(Error::Corrupt {
+                message: "Bitmaps which don't cover all reachable objects are unsupported",
+            });
+        }
+        let (num_entries, d) = d.split_at(4);
+        let num_entries = crate::read_u32(num_entries);
+        let mut d = &d[hash_len..data.len() - Self::HEADER_LEN - hash_len];
+
+        let mut decode_kind = |kind| {
+            let (bitmap, rest) = gix_bitmap::ewah::decode(d).map_err(|source| Error::KindBitmap { kind, source })?;
+            d = rest;
+            Ok::<_, Error>(bitmap)
+        };
+        let kinds = [
+            decode_kind(Self::KINDS[0])?,
+            decode_kind(Self::KINDS[1])?,
+            decode_kind(Self::KINDS[2])?,
+            decode_kind(Self::KINDS[3])?,
+        ];
+
+        let mut entries = Vec::with_capacity(num_entries as usize);
+        for entry in 0..num_entries {
+            const ENTRY_HEADER_LEN: usize = 4 /*index position*/ + 1 /*xor offset*/ + 1 /*flags*/;
+            if d.len() < ENTRY_HEADER_LEN {
+                return Err(Error::Corrupt {
+                    message: "bitmap file ends before all commit bitmaps were read",
+                });
+            }
+            let index_position = crate::read_u32(&d[..4]);
+            let xor_offset = d[4];
+            if u32::from(xor_offset) > entry {
+                return Err(Error::Corrupt {
+                    message: "a commit bitmap refers to a bitmap before the first one",
+                });
+            }
+            let (bitmap, rest) = gix_bitmap::ewah::decode(&d[ENTRY_HEADER_LEN..])
+                .map_err(|source| Error::EntryBitmap { entry, source })?;
+            d = rest;
+            entries.push(Entry {
+                index_position,
+                xor_offset,
+                bitmap,
+            });
+        }
+
+        Ok(File {
+            data,
+            path: path.to_owned(),
+            version,
+            hash_len,
+            object_hash,
+            flags,
+            kinds,
+            entries,
+        })
+    }
+}
@@ -0,0 +1,50 @@
+use memmap2::Mmap;
+
+/// Known `.bitmap` file versions.
+#[derive(Default, PartialEq, Eq, Ord, PartialOrd, Debug, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(missing_docs)]
+pub enum Version {
+    #[default]
+    V1 = 1,
+}
+
+/// A reachability bitmap index of a pack, typically stored next to it as `pack-<hash>.bitmap`.
+///
+/// For a selection of commits it stores which objects of the pack are reachable from them, which allows to enumerate
+/// the objects to send to a client without traversing the commit-graph and all trees. Bits refer to objects in the
+/// order in which they appear in the pack, which is the order of their pack offsets.
+pub struct File {
+    data: Mmap,
+    path: std::path::PathBuf,
+    version: Version,
+    hash_len: usize,
+    object_hash: gix_hash::Kind,
+    flags: u16,
+    /// The objects of each kind, in the order commits, trees, blobs and tags.
+    kinds: [gix_bitmap::ewah::Vec; 4],
+    entries: Vec<Entry>,
+}
+
+/// A commit along with a bitmap of all objects reachable from it.
+struct Entry {
+    index_position: u32,
+    xor_offset: u8,
+    bitmap: gix_bitmap::ewah::Vec,
+}
+
+/// The flags stored in the header of a bitmap file.
+pub mod flags {
+    /// All objects reachable from the commits with a bitmap are contained in the pack. It's always set.
+    pub const FULL_DAG: u16 = 0x1;
+    /// A cache of path-name hashes for all objects follows the bitmaps.
+    pub const HASH_CACHE: u16 = 0x4;
+    /// A lookup table for the commits with a bitmap follows the bitmaps.
+    pub const LOOKUP_TABLE: u16 = 0x10;
+}
+
+mod access;
+///
+pub mod init;
+///
+pub mod write;
@@ -0,0 +1,343 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io::Write,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use gix_features::progress::{Count, DynNestedProgress, Progress};
+
+use crate::bitmap;
+
+mod error {
+    /// The error returned by [`bitmap::File::write_to()`][super::bitmap::File::write_to()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+        #[error("Interrupted")]
+        Interrupted,
+        #[error("Object {id} is reachable from the selected commits but isn't contained in the pack")]
+        MissingObject { id: gix_hash::ObjectId },
+        #[error(transparent)]
+        DecodeEntry(#[from] crate::data::decode::Error),
+        #[error(transparent)]
+        DecodeObject(#[from] gix_object::decode::Error),
+    }
+}
+pub use error::Error;
+
+/// The way commits are selected to receive a bitmap, in addition to the tips which always have one.
+///
+/// The more commits have a bitmap, the less traversal is needed to enumerate the objects reachable from an arbitrary
+/// commit, at the cost of a bigger bitmap file that takes longer to generate.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Selection {
+    /// Only the commits at the tips receive a bitmap.
+    Tips,
+    /// Every `n`-th of all commits reachable from the tips receives a bitmap as well, with commits ordered by their
+    /// commit time from most recent to oldest. `0` is the same as [`Tips`][Selection::Tips].
+    Interval(u32),
+}
+
+impl Default for Selection {
+    fn default() -> Self {
+        Selection::Interval(100)
+    }
+}
+
+/// Options for use in [`bitmap::File::write_to()`].
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// Determine which commits besides the tips receive a bitmap.
+    pub selection: Selection,
+}
+
+/// The result of [`bitmap::File::write_to()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The checksum over the written bitmap file, which is also written as its trailer.
+    pub checksum: gix_hash::ObjectId,
+    /// The amount of commits that received a bitmap.
+    pub num_bitmaps: u32,
+}
+
+/// The progress ids used in [`bitmap::File::write_to()`].
+///
+/// Use this information to selectively extract the progress of interest in case the parent application has custom visualization.
+#[derive(Debug, Copy, Clone)]
+pub enum ProgressId {
+    /// Counts the commits reachable from the tips, which are traversed to select the ones to receive a bitmap.
+    SelectingCommits,
+    /// Counts the commits whose bitmap was computed.
+    ComputingBitmaps,
+}
+
+impl From<ProgressId> for gix_features::progress::Id {
+    fn from(v: ProgressId) -> Self {
+        match v {
+            ProgressId::SelectingCommits => *b"BMSC",
+            ProgressId::ComputingBitmaps => *b"BMCB",
+        }
+    }
+}
+
+/// Writing
+impl bitmap::File {
+    /// Write a new bitmap file to `out` for the pack and index in `bundle`, creating bitmaps for the commits that
+    /// `tips` point to, as well as for other commits reachable from them as configured in `options`.
+    ///
+    /// Annotated tags in `tips` are peeled, and tips which don't point to a commit eventually are ignored.
+    /// All objects reachable from the tips must be contained in the pack, which is typically the case if it was created to
+    /// contain all objects of a repository.
+    ///
+    /// Progress is sent to `progress` and interruptions checked via `should_interrupt`.
+    pub fn write_to(
+        bundle: &crate::Bundle,
+        tips: impl IntoIterator<Item = gix_hash::ObjectId>,
+        out: &mut dyn std::io::Write,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        Options { selection }: Options,
+    ) -> Result<Outcome, Error> {
+        let mut objects = Objects::new(bundle)?;
+        let mut tip_commits = Vec::new();
+        for tip in tips {
+            if let Some(commit) = objects.peel_to_commit(tip)? {
+                tip_commits.push(commit);
+            }
+        }
+
+        let commits = {
+            let mut progress =
+                progress.add_child_with_id("Selecting commits".into(), ProgressId::SelectingCommits.into());
+            progress.init(None, gix_features::progress::count("commits"));
+            let mut commits = HashMap::<u32, Commit>::new();
+            let mut stack = tip_commits.clone();
+            while let Some(position) = stack.pop() {
+                if commits.contains_key(&position) {
+                    continue;
+                }
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(Error::Interrupted);
+                }
+                let commit = objects.commit(position)?;
+                stack.extend(commit.parents.iter().copied());
+                commits.insert(position, commit);
+                progress.inc();
+            }
+            commits
+        };
+
+        let selected = {
+            let mut by_time: Vec<_> = commits
+                .iter()
+                .map(|(position, commit)| (commit.time, *position))
+                .collect();
+            by_time.sort_by(|a, b| b.cmp(a));
+            let mut selected: HashSet<u32> = tip_commits.iter().copied().collect();
+            if let Selection::Interval(interval) = selection {
+                if interval != 0 {
+                    selected.extend(by_time.iter().step_by(interval as usize).map(|(_, position)| *position));
+                }
+            }
+            // Oldest commits first, so that the bitmaps of their descendants can reuse theirs.
+            let mut selected: Vec<_> = selected
+                .into_iter()
+                .map(|position| (commits[&position].time, position))
+                .collect();
+            selected.sort();
+            selected.into_iter().map(|(_, position)| position).collect::<Vec<_>>()
+        };
+
+        let mut progress = progress.add_child_with_id("Computing bitmaps".into(), ProgressId::ComputingBitmaps.into());
+        progress.init(Some(selected.len()), gix_features::progress::count("commits"));
+        let num_words = (objects.kinds.len() + 63) / 64;
+        let mut bitmaps = HashMap::<u32, Vec<u64>>::new();
+        for commit in &selected {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            let mut bits = vec![0u64; num_words];
+            let mut stack = vec![*commit];
+            while let Some(position) = stack.pop() {
+                let (word, bit) = (position as usize / 64, 1u64 << (position % 64));
+                if bits[word] & bit != 0 {
+                    continue;
+                }
+                bits[word] |= bit;
+                match objects.kinds[position as usize] {
+                    gix_object::Kind::Commit => match bitmaps.get(&position) {
+                        Some(reachable) => {
+                            for (word, reachable) in bits.iter_mut().zip(reachable) {
+                                *word |= reachable;
+                            }
+                        }
+                        None => {
+                            let commit = &commits[&position];
+                            stack.push(commit.tree);
+                            stack.extend(commit.parents.iter().copied());
+                        }
+                    },
+                    gix_object::Kind::Tree => objects.tree_entries(position, &mut stack)?,
+                    gix_object::Kind::Blob | gix_object::Kind::Tag => {}
+                }
+            }
+            bitmaps.insert(*commit, bits);
+            progress.inc();
+        }
+
+        let mut out = gix_features::hash::Write::new(out, bundle.index.object_hash());
+        out.write_all(Self::SIGNATURE)?;
+        out.write_all(&(bitmap::Version::V1 as u16).to_be_bytes())?;
+        out.write_all(&bitmap::flags::FULL_DAG.to_be_bytes())?;
+        out.write_all(&(selected.len() as u32).to_be_bytes())?;
+        out.write_all(bundle.pack.checksum().as_slice())?;
+        for kind in Self::KINDS {
+            let mut words = vec![0u64; num_words];
+            for (position, _) in objects.kinds.iter().enumerate().filter(|(_, k)| **k == kind) {
+                words[position / 64] |= 1 << (position % 64);
+            }
+            gix_bitmap::ewah::Vec::from_words(&words).write_to(&mut out)?;
+        }
+        for commit in &selected {
+            out.write_all(&objects.pack_order[*commit as usize].to_be_bytes())?;
+            out.write_all(&[0 /*xor offset*/, 0 /*flags*/])?;
+            gix_bitmap::ewah::Vec::from_words(&bitmaps[commit]).write_to(&mut out)?;
+        }
+
+        let checksum = out.hash.digest();
+        out.inner.write_all(checksum.as_slice())?;
+        Ok(Outcome {
+            checksum,
+            num_bitmaps: selected.len() as u32,
+        })
+    }
+}
+
+/// A decoded commit, with objects identified by their position in the pack.
+struct Commit {
+    tree: u32,
+    parents: Vec<u32>,
+    time: i64,
+}
+
+/// Access to the objects of a pack by their position in the pack, which is the order of their pack offsets.
+struct Objects<'a> {
+    bundle: &'a crate::Bundle,
+    /// The position in the index of each object in pack order.
+    pack_order: Vec<u32>,
+    /// The position in the pack of each object in index order.
+    pack_positions: Vec<u32>,
+    /// The kind of each object in pack order.
+    kinds: Vec<gix_object::Kind>,
+    buf: Vec<u8>,
+    inflate: gix_features::zlib::Inflate,
+}
+
+impl<'a> Objects<'a> {
+    fn new(bundle: &'a crate::Bundle) -> Result<Self, Error> {
+        let index = &bundle.index;
+        let mut pack_order: Vec<u32> = (0..index.num_objects()).collect();
+        pack_order.sort_by_key(|index_position| index.pack_offset_at_index(*index_position));
+        let mut pack_positions = vec![0; pack_order.len()];
+        for (pack_position, index_position) in pack_order.iter().enumerate() {
+            pack_positions[*index_position as usize] = pack_position as u32;
+        }
+
+        let kinds = pack_order
+            .iter()
+            .map(|index_position| {
+                let mut entry = bundle.pack.entry(index.pack_offset_at_index(*index_position));
+                loop {
+                    let base_offset = match entry.header {
+                        crate::data::entry::Header::OfsDelta { base_distance } => entry.base_pack_offset(base_distance),
+                        crate::data::entry::Header::RefDelta { base_id } => index
+                            .lookup(base_id)
+                            .map(|base| index.pack_offset_at_index(base))
+                            .ok_or(Error::MissingObject { id: base_id })?,
+                        header => break Ok(header.as_kind().expect("not a delta")),
+                    };
+                    entry = bundle.pack.entry(base_offset);
+                }
+            })
+            .collect::<Result<_, Error>>()?;
+        Ok(Objects {
+            bundle,
+            pack_order,
+            pack_positions,
+            kinds,
+            buf: Vec::new(),
+            inflate: Default::default(),
+        })
+    }
+
+    fn position(&self, id: &gix_hash::oid) -> Result<u32, Error> {
+        self.bundle
+            .index
+            .lookup(id)
+            .map(|index_position| self.pack_positions[index_position as usize])
+            .ok_or_else(|| Error::MissingObject { id: id.to_owned() })
+    }
+
+    fn decode(&mut self, position: u32) -> Result<gix_object::Data<'_>, Error> {
+        Ok(self
+            .bundle
+            .get_object_by_index(
+                self.pack_order[position as usize],
+                &mut self.buf,
+                &mut self.inflate,
+                &mut crate::cache::Never,
+            )?
+            .0)
+    }
+
+    fn peel_to_commit(&mut self, id: gix_hash::ObjectId) -> Result<Option<u32>, Error> {
+        let mut position = self.position(&id)?;
+        loop {
+            match self.kinds[position as usize] {
+                gix_object::Kind::Commit => return Ok(Some(position)),
+                gix_object::Kind::Tag => {
+                    let target = gix_object::TagRefIter::from_bytes(self.decode(position)?.data).target_id()?;
+                    position = self.position(&target)?;
+                }
+                gix_object::Kind::Tree | gix_object::Kind::Blob => return Ok(None),
+            }
+        }
+    }
+
+    fn commit(&mut self, position: u32) -> Result<Commit, Error> {
+        let (tree, parents, time) = {
+            let commit = gix_object::CommitRef::from_bytes(self.decode(position)?.data)?;
+            (
+                commit.tree(),
+                commit.parents().collect::<Vec<_>>(),
+                commit.time().seconds,
+            )
+        };
+        Ok(Commit {
+            tree: self.position(&tree)?,
+            parents: parents
+                .iter()
+                .map(|parent| self.position(parent))
+                .collect::<Result<_, _>>()?,
+            time,
+        })
+    }
+
+    fn tree_entries(&mut self, position: u32, out: &mut Vec<u32>) -> Result<(), Error> {
+        let mut ids = Vec::new();
+        let object_hash = self.bundle.index.object_hash();
+        for entry in gix_object::TreeRefIter::from_bytes_with_hash(self.decode(position)?.data, object_hash) {
+            let entry = entry?;
+            // Submodule commits are never part of the pack.
+            if entry.mode != gix_object::tree::EntryMode::Commit {
+                ids.push(entry.oid.to_owned());
+            }
+        }
+        for id in ids {
+            out.push(self.position(&id)?);
+        }
+        Ok(())
+    }
+}
@@ -45,10 +45,6 @@ where
             matches!(version, crate::data::Version::V2),
             "currently only pack version 2 can be written",
         );
-        assert!(
-            matches!(object_hash, gix_hash::Kind::Sha1),
-            "currently only Sha1 is supported, right now we don't know how other hashes are encoded",
-        );
         EntriesToBytesIter {
             input: input.peekable(),
             output,
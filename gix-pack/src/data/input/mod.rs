@@ -39,3 +39,7 @@ pub use lookup_ref_delta_objects::LookupRefDeltaObjectsIter;
 
 mod entries_to_bytes;
 pub use entries_to_bytes::EntriesToBytesIter;
+
+///
+pub mod unpack;
+pub use unpack::unpack;
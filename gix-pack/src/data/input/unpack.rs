@@ -0,0 +1,154 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use gix_features::{progress::Progress, zlib};
+
+use crate::data::{self, entry::Header, input};
+
+/// The error returned by [`unpack()`](super::unpack()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Iteration(#[from] input::Error),
+    #[error("Failed to decompress the entry at offset {pack_offset}")]
+    Inflate {
+        pack_offset: data::Offset,
+        source: zlib::inflate::Error,
+    },
+    #[error("The base object at offset {base_offset} of the delta at offset {pack_offset} wasn't seen before")]
+    MissingOffsetBase {
+        base_offset: data::Offset,
+        pack_offset: data::Offset,
+    },
+    #[error("The base object {id} of the delta at offset {pack_offset} could not be found")]
+    MissingBase {
+        id: gix_hash::ObjectId,
+        pack_offset: data::Offset,
+    },
+    #[error(transparent)]
+    FindBase(#[from] gix_object::find::Error),
+    #[error("Could not write the object at offset {pack_offset}")]
+    Write {
+        pack_offset: data::Offset,
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+    #[error("The operation was interrupted")]
+    Interrupted,
+}
+
+/// Configuration for [`unpack()`](super::unpack()).
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    /// Determines how much the pack data is checked while it's streamed.
+    pub iteration_mode: input::Mode,
+    /// The kind of hash used for all objects in the pack.
+    pub object_hash: gix_hash::Kind,
+}
+
+/// The outcome of [`unpack()`](super::unpack()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The ids of all objects that were written, in the order they appeared in the pack.
+    pub ids: Vec<gix_hash::ObjectId>,
+    /// The checksum at the end of the pack.
+    pub pack_checksum: Option<gix_hash::ObjectId>,
+}
+
+/// Stream the pack in `pack` and call `write(kind, data)` for each of its objects, with deltas resolved, to write them
+/// into an object database, typically as loose objects. This is the inverse of indexing a pack, similar to
+/// `git unpack-objects`.
+///
+/// `find` is used to look up the base objects of deltas, which are either objects that were previously written
+/// by `write()`, or objects that already existed in case of thin packs. Thus it must be able to see the objects
+/// that `write()` adds.
+///
+/// `progress` counts the objects that were written.
+#[allow(clippy::type_complexity)]
+pub fn unpack(
+    pack: impl std::io::BufRead,
+    find: &dyn gix_object::Find,
+    write: &mut dyn FnMut(
+        gix_object::Kind,
+        &[u8],
+    ) -> Result<gix_hash::ObjectId, Box<dyn std::error::Error + Send + Sync + 'static>>,
+    progress: &mut dyn Progress,
+    should_interrupt: &AtomicBool,
+    Options {
+        iteration_mode,
+        object_hash,
+    }: Options,
+) -> Result<Outcome, Error> {
+    let entries =
+        input::BytesToEntriesIter::new_from_header(pack, iteration_mode, input::EntryDataMode::Keep, object_hash)?;
+    progress.init(Some(entries.len()), gix_features::progress::count("objects"));
+    let mut out = Outcome {
+        ids: Vec::with_capacity(entries.len()),
+        pack_checksum: None,
+    };
+    // Offsets are increasing, allowing to find ids by offset with a binary search.
+    let mut offsets = Vec::with_capacity(entries.len());
+    let mut inflate = zlib::Inflate::default();
+    let (mut decompressed, mut base, mut resolved) = (Vec::new(), Vec::new(), Vec::new());
+    for entry in entries {
+        if should_interrupt.load(Ordering::Relaxed) {
+            return Err(Error::Interrupted);
+        }
+        let entry = entry?;
+        let pack_offset = entry.pack_offset;
+        decompressed.resize(entry.decompressed_size as usize, 0);
+        inflate.reset();
+        inflate
+            .once(
+                entry.compressed.as_deref().expect("compressed data is kept"),
+                &mut decompressed,
+            )
+            .map_err(|source| Error::Inflate { pack_offset, source })?;
+
+        let base_id = match entry.header {
+            Header::OfsDelta { base_distance } => {
+                let base_offset = pack_offset.saturating_sub(base_distance);
+                let index = offsets
+                    .binary_search(&base_offset)
+                    .map_err(|_| Error::MissingOffsetBase {
+                        base_offset,
+                        pack_offset,
+                    })?;
+                Some(out.ids[index])
+            }
+            Header::RefDelta { base_id } => Some(base_id),
+            Header::Commit | Header::Tree | Header::Blob | Header::Tag => None,
+        };
+        let (kind, data) = match base_id {
+            Some(id) => {
+                let kind = find
+                    .try_find(&id, &mut base)?
+                    .ok_or(Error::MissingBase { id, pack_offset })?
+                    .kind;
+                let (base_size, consumed) = data::delta::decode_header_size(&decompressed);
+                let mut header_ofs = consumed;
+                assert_eq!(
+                    base.len(),
+                    base_size as usize,
+                    "recorded base size in delta does match the actual one"
+                );
+                let (result_size, consumed) = data::delta::decode_header_size(&decompressed[consumed..]);
+                header_ofs += consumed;
+                resolved.resize(result_size as usize, 0);
+                data::delta::apply(&base, &mut resolved, &decompressed[header_ofs..]);
+                (kind, resolved.as_slice())
+            }
+            None => (
+                entry.header.as_kind().expect("only deltas don't have a kind"),
+                decompressed.as_slice(),
+            ),
+        };
+        let id = write(kind, data).map_err(|source| Error::Write { pack_offset, source })?;
+        offsets.push(pack_offset);
+        out.ids.push(id);
+        if entry.trailer.is_some() {
+            out.pack_checksum = entry.trailer;
+        }
+        progress.inc();
+    }
+    Ok(out)
+}
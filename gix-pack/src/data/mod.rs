@@ -107,6 +107,13 @@ impl File {
         &self.path
     }
 
+    /// Hint to the operating system how the mapped pack data is going to be accessed.
+    ///
+    /// This is a no-op on platforms that don't support such hints.
+    pub fn advise(&self, advice: crate::mmap::Advice) -> std::io::Result<()> {
+        crate::mmap::advise(&self.data, advice)
+    }
+
     /// Returns the pack data at the given slice if its range is contained in the mapped pack data
     pub fn entry_slice(&self, slice: EntryRange) -> Option<&[u8]> {
         let entry_end: usize = slice.end.try_into().expect("end of pack fits into usize");
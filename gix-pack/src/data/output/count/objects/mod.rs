@@ -202,7 +202,7 @@ mod expand {
                                     push_obj_count_unique(
                                         &mut out, seen_objs, &tree_id, location, objects, stats, true,
                                     );
-                                    gix_object::TreeRefIter::from_bytes(obj.data)
+                                    gix_object::TreeRefIter::from_bytes_with_hash(obj.data, obj.object_hash)
                                 };
 
                                 let objects_ref = if parent_commit_ids.is_empty() {
@@ -240,7 +240,10 @@ mod expand {
                                                 stats,
                                                 true,
                                             );
-                                            gix_object::TreeRefIter::from_bytes(parent_tree_obj.data)
+                                            gix_object::TreeRefIter::from_bytes_with_hash(
+                                                parent_tree_obj.data,
+                                                parent_tree_obj.object_hash,
+                                            )
                                         };
 
                                         changes_delegate.clear();
@@ -277,7 +280,7 @@ mod expand {
                                 {
                                     let objects = ExpandedCountingObjects::new(db, out, objects);
                                     gix_traverse::tree::breadthfirst(
-                                        gix_object::TreeRefIter::from_bytes(obj.0.data),
+                                        gix_object::TreeRefIter::from_bytes_with_hash(obj.0.data, obj.0.object_hash),
                                         &mut tree_traversal_state,
                                         &objects,
                                         &mut traverse_delegate,
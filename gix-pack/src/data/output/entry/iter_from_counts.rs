@@ -36,7 +36,49 @@ pub(crate) mod function {
     ///   so with minimal overhead (especially compared to `gix index-from-pack`)~~ Probably works now by chaining Iterators
     ///  or keeping enough state to write a pack and then generate an index with recorded data.
     ///
+    ///
+    /// If thin packs are allowed, all objects outside of the pack are assumed to be present on the receiving side,
+    /// use [`iter_from_counts_with_known_bases()`] to control which objects may serve as base for deltas.
     pub fn iter_from_counts<Find>(
+        counts: Vec<output::Count>,
+        db: Find,
+        progress: Box<dyn DynNestedProgress + 'static>,
+        options: Options,
+    ) -> impl Iterator<Item = Result<(SequenceId, Vec<output::Entry>), Error>>
+           + parallel::reduce::Finalize<Reduce = reduce::Statistics<Error>>
+    where
+        Find: crate::Find + Send + Clone + 'static,
+    {
+        iter_from_counts_with_known_bases(counts, db, progress, options, |_: &gix_hash::oid| true)
+    }
+
+    /// Like [`iter_from_counts()`], but when producing a thin pack, only refer to delta bases outside of the pack
+    /// if `is_known_to_receiver(base_id)` returns `true`, which typically is the case for all objects reachable from the
+    /// tips the receiver advertised. Deltas against other bases are decoded and recompressed as base objects to keep
+    /// the pack valid for the receiver, who will [resolve](crate::Bundle::write_to_directory()) all remaining ref-deltas
+    /// against its own object database.
+    ///
+    /// Note that only deltas which already exist in packs can be reused, no new deltas are computed.
+    pub fn iter_from_counts_with_known_bases<Find, Known>(
+        counts: Vec<output::Count>,
+        db: Find,
+        progress: Box<dyn DynNestedProgress + 'static>,
+        options: Options,
+        is_known_to_receiver: Known,
+    ) -> impl Iterator<Item = Result<(SequenceId, Vec<output::Entry>), Error>>
+           + parallel::reduce::Finalize<Reduce = reduce::Statistics<Error>>
+    where
+        Find: crate::Find + Send + Clone + 'static,
+        Known: Fn(&gix_hash::oid) -> bool + Send + Clone + 'static,
+    {
+        iter_from_counts_with_delta_filter(counts, db, progress, options, is_known_to_receiver, |_, _| true)
+    }
+
+    /// Like [`iter_from_counts_with_known_bases()`], but only reuse an existing delta of an object if
+    /// `may_delta_against(object_id, base_id)` returns `true`, or store the object as base object otherwise.
+    ///
+    /// This is useful to keep deltas within [delta islands](output::Islands::allows_delta()).
+    pub fn iter_from_counts_with_delta_filter<Find, Known, MayDelta>(
         mut counts: Vec<output::Count>,
         db: Find,
         mut progress: Box<dyn DynNestedProgress + 'static>,
@@ -46,11 +88,16 @@ pub(crate) mod function {
             allow_thin_pack,
             thread_limit,
             chunk_size,
+            compression_level,
         }: Options,
+        is_known_to_receiver: Known,
+        may_delta_against: MayDelta,
     ) -> impl Iterator<Item = Result<(SequenceId, Vec<output::Entry>), Error>>
            + parallel::reduce::Finalize<Reduce = reduce::Statistics<Error>>
     where
         Find: crate::Find + Send + Clone + 'static,
+        Known: Fn(&gix_hash::oid) -> bool + Send + Clone + 'static,
+        MayDelta: Fn(&gix_hash::oid, &gix_hash::oid) -> bool + Send + Clone + 'static,
     {
         assert!(
             matches!(version, crate::data::Version::V2),
@@ -191,15 +238,28 @@ pub(crate) mod function {
                                                     .expect("pack used for counts is still available")
                                             });
                                             debug_assert_eq!(*cached_pack_id, pack_id);
-                                            stats.ref_delta_objects += 1;
-                                            cache
+                                            let base_id = cache
                                                 .binary_search_by_key(&base_offset, |e| e.0)
                                                 .ok()
                                                 .map(|idx| cache[idx].1)
+                                                .filter(|id| {
+                                                    is_known_to_receiver(id) && may_delta_against(&count.id, id)
+                                                });
+                                            if base_id.is_some() {
+                                                stats.ref_delta_objects += 1;
+                                            }
+                                            base_id
                                         }
                                     }),
                                     version,
-                                ) {
+                                )
+                                .filter(|entry| match entry {
+                                    Ok(output::Entry {
+                                        kind: output::entry::Kind::DeltaRef { object_index },
+                                        ..
+                                    }) => may_delta_against(&count.id, &counts[*object_index].id),
+                                    _ => true,
+                                }) {
                                     Some(entry) => {
                                         stats.objects_copied_from_pack += 1;
                                         entry
@@ -207,7 +267,11 @@ pub(crate) mod function {
                                     None => match db.try_find(&count.id, buf).map_err(Error::Find)? {
                                         Some((obj, _location)) => {
                                             stats.decoded_and_recompressed_objects += 1;
-                                            output::Entry::from_data(count, &obj)
+                                            output::Entry::from_data_with_compression_level(
+                                                count,
+                                                &obj,
+                                                compression_level,
+                                            )
                                         }
                                         None => {
                                             stats.missing_objects += 1;
@@ -219,7 +283,7 @@ pub(crate) mod function {
                             None => match db.try_find(&count.id, buf).map_err(Error::Find)? {
                                 Some((obj, _location)) => {
                                     stats.decoded_and_recompressed_objects += 1;
-                                    output::Entry::from_data(count, &obj)
+                                    output::Entry::from_data_with_compression_level(count, &obj, compression_level)
                                 }
                                 None => {
                                     stats.missing_objects += 1;
@@ -378,6 +442,9 @@ mod types {
         pub chunk_size: usize,
         /// The pack data version to produce for each entry
         pub version: crate::data::Version,
+        /// The zlib compression level for objects that have to be compressed, ranging from 0 for no compression
+        /// to 9 for the best compression. Entries copied from existing packs keep their compression.
+        pub compression_level: u32,
     }
 
     impl Default for Options {
@@ -388,6 +455,7 @@ mod types {
                 allow_thin_pack: false,
                 chunk_size: 10,
                 version: Default::default(),
+                compression_level: gix_features::zlib::stream::deflate::DEFAULT_LEVEL,
             }
         }
     }
@@ -6,7 +6,9 @@ use crate::{data, data::output, find};
 
 ///
 pub mod iter_from_counts;
-pub use iter_from_counts::function::iter_from_counts;
+pub use iter_from_counts::function::{
+    iter_from_counts, iter_from_counts_with_delta_filter, iter_from_counts_with_known_bases,
+};
 
 /// The kind of pack entry to be written
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
@@ -127,12 +129,21 @@ impl output::Entry {
 
     /// Create a new instance from the given `oid` and its corresponding git object data `obj`.
     pub fn from_data(count: &output::Count, obj: &gix_object::Data<'_>) -> Result<Self, Error> {
+        Self::from_data_with_compression_level(count, obj, gix_features::zlib::stream::deflate::DEFAULT_LEVEL)
+    }
+
+    /// Like [`from_data()`](Self::from_data()), but compresses `obj` with the zlib compression `level`.
+    pub fn from_data_with_compression_level(
+        count: &output::Count,
+        obj: &gix_object::Data<'_>,
+        level: u32,
+    ) -> Result<Self, Error> {
         Ok(output::Entry {
             id: count.id.to_owned(),
             kind: Kind::Base(obj.kind),
             decompressed_size: obj.data.len(),
             compressed_data: {
-                let mut out = gix_features::zlib::stream::deflate::Write::new(Vec::new());
+                let mut out = gix_features::zlib::stream::deflate::Write::with_level(Vec::new(), level);
                 if let Err(err) = std::io::copy(&mut &*obj.data, &mut out) {
                     match err.kind() {
                         std::io::ErrorKind::Other => return Err(Error::ZlibDeflate(err)),
@@ -0,0 +1,128 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use gix_hash::ObjectId;
+use gix_object::bstr::{BStr, BString};
+
+use crate::{data::output::Islands, FindExt};
+
+mod error {
+    /// The error returned by [`Islands::from_tips()`][super::Islands::from_tips()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindExisting(#[from] gix_object::find::existing::Error),
+        #[error(transparent)]
+        Decode(#[from] gix_object::decode::Error),
+        #[error("Interrupted")]
+        Interrupted,
+    }
+}
+pub use error::Error;
+
+/// Initialization
+impl Islands {
+    /// Compute the islands of all objects reachable from `tips`, pairs of island name and the object a reference of the
+    /// island points to, with objects being looked up in `db`.
+    ///
+    /// Objects reachable from tips of multiple islands are part of all of them. Submodule commits are ignored.
+    pub fn from_tips(
+        tips: impl IntoIterator<Item = (BString, ObjectId)>,
+        db: &dyn crate::Find,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Self, Error> {
+        let mut out = Islands::default();
+        let mut stack = Vec::new();
+        for (name, id) in tips {
+            let island = match out.names.iter().position(|existing| *existing == name) {
+                Some(island) => island,
+                None => {
+                    out.names.push(name);
+                    out.names.len() - 1
+                }
+            };
+            let mut bits = vec![0; island / 64 + 1];
+            bits[island / 64] |= 1 << (island % 64);
+            stack.push((id, bits));
+        }
+
+        // Propagate only the islands an object didn't have yet, so each object is visited at most once per island.
+        let mut buf = Vec::new();
+        while let Some((id, bits)) = stack.pop() {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            let existing = out.membership.entry(id).or_default();
+            if existing.len() < bits.len() {
+                existing.resize(bits.len(), 0);
+            }
+            let mut added = bits;
+            for (added, existing) in added.iter_mut().zip(existing.iter_mut()) {
+                *added &= !*existing;
+                *existing |= *added;
+            }
+            if added.iter().all(|word| *word == 0) {
+                continue;
+            }
+
+            let object = db.find(&id, &mut buf)?.0;
+            match object.kind {
+                gix_object::Kind::Commit => {
+                    let commit = gix_object::CommitRef::from_bytes(object.data)?;
+                    stack.push((commit.tree(), added.clone()));
+                    stack.extend(commit.parents().map(|parent| (parent, added.clone())));
+                }
+                gix_object::Kind::Tree => {
+                    for entry in gix_object::TreeRefIter::from_bytes_with_hash(object.data, object.object_hash) {
+                        let entry = entry?;
+                        if entry.mode != gix_object::tree::EntryMode::Commit {
+                            stack.push((entry.oid.to_owned(), added.clone()));
+                        }
+                    }
+                }
+                gix_object::Kind::Tag => {
+                    let target = gix_object::TagRefIter::from_bytes(object.data).target_id()?;
+                    stack.push((target, added));
+                }
+                gix_object::Kind::Blob => {}
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Access
+impl Islands {
+    /// Return the names of all islands, in the order in which they were first encountered.
+    pub fn names(&self) -> impl Iterator<Item = &BStr> + '_ {
+        self.names.iter().map(AsRef::as_ref)
+    }
+
+    /// Return the names of all islands that `id` is a part of.
+    pub fn islands_of(&self, id: &gix_hash::oid) -> impl Iterator<Item = &BStr> + '_ {
+        let bits = self.membership.get(id).map(Vec::as_slice).unwrap_or_default();
+        self.names
+            .iter()
+            .enumerate()
+            .filter(move |(island, _)| {
+                bits.get(island / 64)
+                    .map_or(false, |word| word & (1 << (island % 64)) != 0)
+            })
+            .map(|(_, name)| name.as_ref())
+    }
+
+    /// Return `true` if the object with `id` may be stored as delta against the object with `base_id`.
+    ///
+    /// This is the case if `id` isn't part of any island, or if `base_id` is part of all islands `id` is in.
+    /// Objects outside of all islands are never suitable as base for objects inside of an island.
+    pub fn allows_delta(&self, id: &gix_hash::oid, base_id: &gix_hash::oid) -> bool {
+        let (bits, base_bits) = match (self.membership.get(id), self.membership.get(base_id)) {
+            (None, _) => return true,
+            (Some(_), None) => return false,
+            (Some(bits), Some(base_bits)) => (bits, base_bits),
+        };
+        bits.iter()
+            .enumerate()
+            .all(|(index, word)| word & !base_bits.get(index).copied().unwrap_or_default() == 0)
+    }
+}
@@ -34,6 +34,22 @@ pub struct Entry {
     pub compressed_data: Vec<u8>,
 }
 
+/// The delta islands of objects, which prevent deltas across forks that share an object database.
+///
+/// Each island is a set of references, and each object belongs to all islands whose references reach it. An object may
+/// only be stored as delta against a base that is part of all of its islands, so that packs for the references of one
+/// island never need objects that are only reachable from another island.
+#[derive(Default, Debug, Clone)]
+pub struct Islands {
+    /// The names of all islands, with the name's index being the island's bit in `membership`.
+    names: Vec<gix_object::bstr::BString>,
+    /// A bitmap of islands for each object that is reachable from at least one island.
+    membership: gix_hashtable::HashMap<ObjectId, Vec<u64>>,
+}
+
+///
+pub mod islands;
+
 ///
 pub mod entry;
 
@@ -63,6 +63,15 @@ impl index::File {
         }
     }
 
+    /// Iterate entries using the per-index accessors, which work for all index versions.
+    fn iter_by_index(&self) -> impl Iterator<Item = Entry> + '_ {
+        (0..self.num_objects).map(move |index| Entry {
+            oid: self.oid_at_index(index).to_owned(),
+            pack_offset: self.pack_offset_at_index(index),
+            crc32: self.crc32_at_index(index),
+        })
+    }
+
     /// Returns the object hash at the given index in our list of (sorted) sha1 hashes.
     /// The index ranges from 0 to `self.num_objects()`
     ///
@@ -74,6 +83,7 @@ impl index::File {
         let start = match self.version {
             index::Version::V2 => V2_HEADER_SIZE + index * self.hash_len,
             index::Version::V1 => V1_HEADER_SIZE + index * (N32_SIZE + self.hash_len) + N32_SIZE,
+            index::Version::V3 => self.tables_v3.ids + self.pack_order_v3(index) * self.hash_len,
         };
         gix_hash::oid::from_bytes_unchecked(&self.data[start..][..self.hash_len])
     }
@@ -90,6 +100,10 @@ impl index::File {
                 let start = self.offset_pack_offset_v2() + index * N32_SIZE;
                 self.pack_offset_from_offset_v2(&self.data[start..][..N32_SIZE], self.offset_pack_offset64_v2())
             }
+            index::Version::V3 => {
+                let start = self.tables_v3.pack_offsets + index * N32_SIZE;
+                self.pack_offset_from_offset_v2(&self.data[start..][..N32_SIZE], self.tables_v3.pack_offsets64)
+            }
             index::Version::V1 => {
                 let start = V1_HEADER_SIZE + index * (N32_SIZE + self.hash_len);
                 crate::read_u32(&self.data[start..][..N32_SIZE]) as u64
@@ -110,6 +124,10 @@ impl index::File {
                 let start = self.offset_crc32_v2() + index * N32_SIZE;
                 Some(crate::read_u32(&self.data[start..start + N32_SIZE]))
             }
+            index::Version::V3 => {
+                let start = self.tables_v3.crc32 + self.pack_order_v3(index) * N32_SIZE;
+                Some(crate::read_u32(&self.data[start..start + N32_SIZE]))
+            }
             index::Version::V1 => None,
         }
     }
@@ -151,6 +169,7 @@ impl index::File {
         match self.version {
             index::Version::V2 => Box::new(self.iter_v2()),
             index::Version::V1 => Box::new(self.iter_v1()),
+            index::Version::V3 => Box::new(self.iter_by_index()),
         }
     }
 
@@ -160,9 +179,12 @@ impl index::File {
     pub fn sorted_offsets(&self) -> Vec<data::Offset> {
         let mut ofs: Vec<_> = match self.version {
             index::Version::V1 => self.iter().map(|e| e.pack_offset).collect(),
-            index::Version::V2 => {
-                let offset32_start = &self.data[self.offset_pack_offset_v2()..];
-                let pack_offset_64_start = self.offset_pack_offset64_v2();
+            index::Version::V2 | index::Version::V3 => {
+                let (offset32_start, pack_offset_64_start) = match self.version {
+                    index::Version::V3 => (self.tables_v3.pack_offsets, self.tables_v3.pack_offsets64),
+                    _ => (self.offset_pack_offset_v2(), self.offset_pack_offset64_v2()),
+                };
+                let offset32_start = &self.data[offset32_start..];
                 offset32_start
                     .chunks(N32_SIZE)
                     .take(self.num_objects as usize)
@@ -189,9 +211,16 @@ impl index::File {
         self.offset_pack_offset_v2() + self.num_objects as usize * N32_SIZE
     }
 
+    /// Return the position of the object at the sorted `index` in the tables that are in pack order.
+    #[inline]
+    fn pack_order_v3(&self, index: usize) -> usize {
+        let start = self.tables_v3.pack_order + index * N32_SIZE;
+        crate::read_u32(&self.data[start..][..N32_SIZE]) as usize
+    }
+
     #[inline]
     fn pack_offset_from_offset_v2(&self, offset: &[u8], pack64_offset: usize) -> data::Offset {
-        debug_assert_eq!(self.version, index::Version::V2);
+        debug_assert_ne!(self.version, index::Version::V1);
         let ofs32 = crate::read_u32(offset);
         if (ofs32 & N32_HIGH_BIT) == N32_HIGH_BIT {
             let from = pack64_offset + (ofs32 ^ N32_HIGH_BIT) as usize * N64_SIZE;
@@ -75,10 +75,11 @@ mod function {
         entries_sorted_by_oid: Vec<crate::cache::delta::Item<crate::index::write::TreeEntry>>,
         pack_hash: &gix_hash::ObjectId,
         kind: crate::index::Version,
+        object_hash: gix_hash::Kind,
         progress: &mut dyn DynNestedProgress,
     ) -> io::Result<gix_hash::ObjectId> {
         use io::Write;
-        assert_eq!(kind, crate::index::Version::V2, "Can only write V2 packs right now");
+        assert_ne!(kind, crate::index::Version::V1, "Cannot write V1 indices");
         assert!(
             entries_sorted_by_oid.len() <= u32::MAX as usize,
             "a pack cannot have more than u32::MAX objects"
@@ -87,13 +88,43 @@ mod function {
         // Write header
         let mut out = Count::new(std::io::BufWriter::with_capacity(
             8 * 4096,
-            hash::Write::new(out, kind.hash()),
+            hash::Write::new(out, object_hash),
         ));
         out.write_all(V2_SIGNATURE)?;
         out.write_all(&(kind as u32).to_be_bytes())?;
 
         progress.init(Some(4), progress::steps());
         let start = std::time::Instant::now();
+        if kind == crate::index::Version::V3 {
+            write_tables_v3(&mut out, &entries_sorted_by_oid, object_hash, progress)?;
+        } else {
+            write_tables_v2(&mut out, &entries_sorted_by_oid, progress)?;
+        }
+
+        out.write_all(pack_hash.as_slice())?;
+
+        let bytes_written_without_trailer = out.bytes;
+        let out = out.inner.into_inner()?;
+        let index_hash = out.hash.digest();
+        out.inner.write_all(index_hash.as_slice())?;
+        out.inner.flush()?;
+
+        progress.inc();
+        progress.show_throughput_with(
+            start,
+            (bytes_written_without_trailer + 20) as usize,
+            progress::bytes().expect("unit always set"),
+            progress::MessageLevel::Success,
+        );
+
+        Ok(index_hash)
+    }
+
+    fn write_tables_v2(
+        out: &mut dyn io::Write,
+        entries_sorted_by_oid: &[crate::cache::delta::Item<crate::index::write::TreeEntry>],
+        progress: &mut dyn DynNestedProgress,
+    ) -> io::Result<()> {
         let _info = progress.add_child_with_id("writing fan-out table".into(), gix_features::progress::UNKNOWN);
         let fan_out = fanout(&mut entries_sorted_by_oid.iter().map(|e| e.data.id.first_byte()));
 
@@ -103,55 +134,107 @@ mod function {
 
         progress.inc();
         let _info = progress.add_child_with_id("writing ids".into(), gix_features::progress::UNKNOWN);
-        for entry in &entries_sorted_by_oid {
+        for entry in entries_sorted_by_oid {
             out.write_all(entry.data.id.as_slice())?;
         }
 
         progress.inc();
         let _info = progress.add_child_with_id("writing crc32".into(), gix_features::progress::UNKNOWN);
-        for entry in &entries_sorted_by_oid {
+        for entry in entries_sorted_by_oid {
             out.write_all(&entry.data.crc32.to_be_bytes())?;
         }
 
         progress.inc();
         let _info = progress.add_child_with_id("writing offsets".into(), gix_features::progress::UNKNOWN);
-        {
-            let mut offsets64 = Vec::<u64>::new();
-            for entry in &entries_sorted_by_oid {
-                let offset: u32 = if entry.offset > LARGE_OFFSET_THRESHOLD {
-                    assert!(
-                        offsets64.len() < LARGE_OFFSET_THRESHOLD as usize,
-                        "Encoding breakdown - way too many 64bit offsets"
-                    );
-                    offsets64.push(entry.offset);
-                    ((offsets64.len() - 1) as u32) | HIGH_BIT
-                } else {
-                    entry.offset as u32
-                };
-                out.write_all(&offset.to_be_bytes())?;
-            }
-            for value in offsets64 {
-                out.write_all(&value.to_be_bytes())?;
-            }
+        write_offsets(out, entries_sorted_by_oid)
+    }
+
+    /// Write everything past the signature and version of a V3 index, which has a single object format.
+    fn write_tables_v3(
+        out: &mut dyn io::Write,
+        entries_sorted_by_oid: &[crate::cache::delta::Item<crate::index::write::TreeEntry>],
+        object_hash: gix_hash::Kind,
+        progress: &mut dyn DynNestedProgress,
+    ) -> io::Result<()> {
+        const HEADER_LEN: u32 = 4 * 9;
+        let num_objects = entries_sorted_by_oid.len() as u32;
+        let hash_len = object_hash.len_in_bytes() as u32;
+        let short_id_len = entries_sorted_by_oid
+            .windows(2)
+            .map(|pair| common_prefix_len(pair[0].data.id.as_slice(), pair[1].data.id.as_slice()) + 1)
+            .max()
+            .unwrap_or(1) as u32;
+        let mut pack_order: Vec<_> = (0..num_objects).collect();
+        pack_order.sort_by_key(|idx| entries_sorted_by_oid[*idx as usize].offset);
+        let mut sorted_to_pack_order = vec![0u32; pack_order.len()];
+        for (pack_idx, sorted_idx) in pack_order.iter().enumerate() {
+            sorted_to_pack_order[*sorted_idx as usize] = pack_idx as u32;
+        }
+        let num_large_offsets = entries_sorted_by_oid
+            .iter()
+            .filter(|entry| entry.offset > LARGE_OFFSET_THRESHOLD)
+            .count() as u32;
+        let trailer_offset = HEADER_LEN + num_objects * (short_id_len + hash_len + 4 * 3) + num_large_offsets * 8;
+
+        let _info = progress.add_child_with_id("writing header".into(), gix_features::progress::UNKNOWN);
+        for value in [HEADER_LEN, num_objects, 1] {
+            out.write_all(&value.to_be_bytes())?;
+        }
+        out.write_all(&crate::index::format_id_v3(object_hash))?;
+        for value in [short_id_len, HEADER_LEN, trailer_offset] {
+            out.write_all(&value.to_be_bytes())?;
         }
 
-        out.write_all(pack_hash.as_slice())?;
+        progress.inc();
+        let _info = progress.add_child_with_id("writing ids".into(), gix_features::progress::UNKNOWN);
+        for entry in entries_sorted_by_oid {
+            out.write_all(&entry.data.id.as_slice()[..short_id_len as usize])?;
+        }
+        for idx in &pack_order {
+            out.write_all(entries_sorted_by_oid[*idx as usize].data.id.as_slice())?;
+        }
+        for pack_idx in sorted_to_pack_order {
+            out.write_all(&pack_idx.to_be_bytes())?;
+        }
 
-        let bytes_written_without_trailer = out.bytes;
-        let out = out.inner.into_inner()?;
-        let index_hash: gix_hash::ObjectId = out.hash.digest().into();
-        out.inner.write_all(index_hash.as_slice())?;
-        out.inner.flush()?;
+        progress.inc();
+        let _info = progress.add_child_with_id("writing crc32".into(), gix_features::progress::UNKNOWN);
+        for idx in &pack_order {
+            out.write_all(&entries_sorted_by_oid[*idx as usize].data.crc32.to_be_bytes())?;
+        }
 
         progress.inc();
-        progress.show_throughput_with(
-            start,
-            (bytes_written_without_trailer + 20) as usize,
-            progress::bytes().expect("unit always set"),
-            progress::MessageLevel::Success,
-        );
+        let _info = progress.add_child_with_id("writing offsets".into(), gix_features::progress::UNKNOWN);
+        write_offsets(out, entries_sorted_by_oid)
+    }
 
-        Ok(index_hash)
+    /// Write 32 bit offsets for all entries, followed by the table of 64 bit offsets for those which don't fit.
+    fn write_offsets(
+        out: &mut dyn io::Write,
+        entries_sorted_by_oid: &[crate::cache::delta::Item<crate::index::write::TreeEntry>],
+    ) -> io::Result<()> {
+        let mut offsets64 = Vec::<u64>::new();
+        for entry in entries_sorted_by_oid {
+            let offset: u32 = if entry.offset > LARGE_OFFSET_THRESHOLD {
+                assert!(
+                    offsets64.len() < LARGE_OFFSET_THRESHOLD as usize,
+                    "Encoding breakdown - way too many 64bit offsets"
+                );
+                offsets64.push(entry.offset);
+                ((offsets64.len() - 1) as u32) | HIGH_BIT
+            } else {
+                entry.offset as u32
+            };
+            out.write_all(&offset.to_be_bytes())?;
+        }
+        for value in offsets64 {
+            out.write_all(&value.to_be_bytes())?;
+        }
+        Ok(())
+    }
+
+    fn common_prefix_len(a: &[u8], b: &[u8]) -> usize {
+        a.iter().zip(b).take_while(|(a, b)| a == b).count()
     }
 }
 #[cfg(feature = "streaming-input")]
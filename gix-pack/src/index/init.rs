@@ -1,6 +1,6 @@
 use std::{mem::size_of, path::Path};
 
-use crate::index::{self, Version, FAN_LEN, V2_SIGNATURE};
+use crate::index::{self, format_id_v3, TablesV3, Version, FAN_LEN, V2_SIGNATURE};
 
 /// Returned by [`index::File::at()`].
 #[derive(thiserror::Error, Debug)]
@@ -38,37 +38,43 @@ impl index::File {
         let hash_len = object_hash.len_in_bytes();
 
         let footer_size = hash_len * 2;
-        if idx_len < FAN_LEN * N32_SIZE + footer_size {
+        let is_v3 = data.starts_with(V2_SIGNATURE) && data.get(4..8) == Some(&3u32.to_be_bytes()[..]);
+        let min_len = if is_v3 { 8 } else { FAN_LEN * N32_SIZE };
+        if idx_len < min_len + footer_size {
             return Err(Error::Corrupt {
                 message: format!("Pack index of size {idx_len} is too small for even an empty index"),
             });
         }
-        let (kind, fan, num_objects) = {
+        let (kind, fan, num_objects, tables_v3) = {
             let (kind, d) = {
                 let (sig, d) = data.split_at(V2_SIGNATURE.len());
                 if sig == V2_SIGNATURE {
-                    (Version::V2, d)
-                } else {
-                    (Version::V1, &data[..])
-                }
-            };
-            let d = {
-                if let Version::V2 = kind {
                     let (vd, dr) = d.split_at(N32_SIZE);
                     let version = crate::read_u32(vd);
-                    if version != Version::V2 as u32 {
-                        return Err(Error::UnsupportedVersion { version });
+                    match version {
+                        2 => (Version::V2, dr),
+                        3 => (Version::V3, &data[..]),
+                        _ => return Err(Error::UnsupportedVersion { version }),
                     }
-                    dr
                 } else {
-                    d
+                    (Version::V1, &data[..])
                 }
             };
-            let (fan, bytes_read) = read_fan(d);
-            let (_, _d) = d.split_at(bytes_read);
-            let num_objects = fan[FAN_LEN - 1];
+            if kind == Version::V3 {
+                let (tables, num_objects) = read_header_v3(d, object_hash)?;
+                let fan = index::encode::fanout(
+                    &mut d[tables.short_ids..][..num_objects as usize * tables.short_id_len]
+                        .chunks(tables.short_id_len)
+                        .map(|id| id[0]),
+                );
+                (kind, fan, num_objects, tables)
+            } else {
+                let (fan, bytes_read) = read_fan(d);
+                let (_, _d) = d.split_at(bytes_read);
+                let num_objects = fan[FAN_LEN - 1];
 
-            (kind, fan, num_objects)
+                (kind, fan, num_objects, Default::default())
+            }
         };
         Ok(index::File {
             data,
@@ -78,6 +84,7 @@ impl index::File {
             fan,
             hash_len,
             object_hash,
+            tables_v3,
         })
     }
 }
@@ -89,3 +96,64 @@ fn read_fan(d: &[u8]) -> ([u32; FAN_LEN], usize) {
     }
     (fan, FAN_LEN * N32_SIZE)
 }
+
+/// Parse the header of a V3 index in `data`, which is the whole file, and return the location of all tables of the first
+/// object format along with the amount of objects.
+///
+/// Only the first object format is used, as it's the only one with CRC32 values and pack offsets, and it must be `object_hash`.
+fn read_header_v3(data: &[u8], object_hash: gix_hash::Kind) -> Result<(TablesV3, u32), Error> {
+    let corrupt = |message: String| Error::Corrupt { message };
+    let read = |ofs: usize| -> Result<usize, Error> {
+        data.get(ofs..ofs + N32_SIZE)
+            .map(|bytes| crate::read_u32(bytes) as usize)
+            .ok_or_else(|| corrupt(format!("Pack index header is truncated at byte {ofs}")))
+    };
+    let header_len = read(8)?;
+    let num_objects = read(12)?;
+    let num_formats = read(16)?;
+    let trailer_ofs = read(20 + num_formats * N32_SIZE * 3)?;
+    if num_formats == 0 || header_len > data.len() || 20 + num_formats * N32_SIZE * 3 + N32_SIZE > header_len {
+        return Err(corrupt(format!(
+            "Pack index header of {header_len} bytes with {num_formats} object formats is invalid"
+        )));
+    }
+    let format_id = &data[20..][..N32_SIZE];
+    if format_id != format_id_v3(object_hash) {
+        return Err(corrupt(format!(
+            "Pack index uses object format '{}', but {object_hash} was expected",
+            String::from_utf8_lossy(format_id)
+        )));
+    }
+    let short_id_len = read(24)?;
+    let hash_len = object_hash.len_in_bytes();
+    if short_id_len == 0 || short_id_len > hash_len {
+        return Err(corrupt(format!(
+            "Invalid length of shortened object ids: {short_id_len}"
+        )));
+    }
+
+    let short_ids = read(28)?;
+    let ids = short_ids + num_objects * short_id_len;
+    let pack_order = ids + num_objects * hash_len;
+    let crc32 = pack_order + num_objects * N32_SIZE;
+    let pack_offsets = crc32 + num_objects * N32_SIZE;
+    let pack_offsets64 = pack_offsets + num_objects * N32_SIZE;
+    if short_ids < header_len || pack_offsets64 > trailer_ofs || trailer_ofs + hash_len * 2 != data.len() {
+        return Err(corrupt(format!(
+            "Pack index tables of {num_objects} objects don't fit into a file of {} bytes",
+            data.len()
+        )));
+    }
+    Ok((
+        TablesV3 {
+            short_id_len,
+            short_ids,
+            ids,
+            pack_order,
+            crc32,
+            pack_offsets,
+            pack_offsets64,
+        },
+        num_objects as u32,
+    ))
+}
@@ -83,6 +83,9 @@ pub enum Version {
     V1 = 1,
     #[default]
     V2 = 2,
+    /// The version designed for the transition to SHA-256, which records the kind of hash it uses and which
+    /// has its tables in pack order to be able to add more object formats in future.
+    V3 = 3,
 }
 
 impl Version {
@@ -90,6 +93,7 @@ impl Version {
     pub fn hash(&self) -> gix_hash::Kind {
         match self {
             Version::V1 | Version::V2 => gix_hash::Kind::Sha1,
+            Version::V3 => gix_hash::Kind::Sha256,
         }
     }
 }
@@ -103,6 +107,25 @@ pub type EntryIndex = u32;
 
 const FAN_LEN: usize = 256;
 
+/// The start of each table of a [V3](Version::V3) index, as they aren't at fixed offsets.
+#[derive(Default, Debug, Clone, Copy)]
+struct TablesV3 {
+    /// The amount of bytes of each id in the table of shortened ids.
+    short_id_len: usize,
+    /// The sorted table of shortened ids.
+    short_ids: usize,
+    /// The table of full ids in pack order.
+    ids: usize,
+    /// The table mapping the index of sorted ids to the position in pack order.
+    pack_order: usize,
+    /// The table of CRC32 values in pack order.
+    crc32: usize,
+    /// The table of pack offsets in the order of sorted ids.
+    pack_offsets: usize,
+    /// The table of 64 bit pack offsets.
+    pack_offsets64: usize,
+}
+
 /// A representation of a pack index file
 pub struct File {
     data: Mmap,
@@ -112,6 +135,8 @@ pub struct File {
     fan: [u32; FAN_LEN],
     hash_len: usize,
     object_hash: gix_hash::Kind,
+    /// Only set for [V3](Version::V3) indices.
+    tables_v3: TablesV3,
 }
 
 /// Basic file information
@@ -124,6 +149,12 @@ impl File {
     pub fn path(&self) -> &std::path::Path {
         &self.path
     }
+    /// Hint to the operating system how the mapped index data is going to be accessed.
+    ///
+    /// This is a no-op on platforms that don't support such hints.
+    pub fn advise(&self, advice: crate::mmap::Advice) -> std::io::Result<()> {
+        crate::mmap::advise(&self.data, advice)
+    }
     /// The amount of objects stored in the pack and index, as one past the highest entry index.
     pub fn num_objects(&self) -> EntryIndex {
         self.num_objects
@@ -135,6 +166,14 @@ impl File {
 }
 
 const V2_SIGNATURE: &[u8] = b"\xfftOc";
+
+/// Return the identifier of `object_hash` as used in [V3](Version::V3) indices.
+pub(crate) fn format_id_v3(object_hash: gix_hash::Kind) -> [u8; 4] {
+    match object_hash {
+        gix_hash::Kind::Sha1 => *b"sha1",
+        gix_hash::Kind::Sha256 => *b"s256",
+    }
+}
 ///
 pub mod init;
 
@@ -87,6 +87,97 @@ pub mod integrity {
     }
 }
 
+///
+pub mod statistics {
+    use std::collections::BTreeMap;
+
+    use crate::data;
+
+    /// Returned by [`index::File::verify_integrity_with_statistics()`][crate::index::File::verify_integrity_with_statistics()].
+    #[derive(thiserror::Error, Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Object {id} is said to be at offset {offset}, which is past the end of the pack")]
+        OffsetOutOfBounds {
+            id: gix_hash::ObjectId,
+            offset: data::Offset,
+        },
+        #[error("The base object {base_id} of delta object {id} isn't contained in the pack")]
+        MissingBase {
+            id: gix_hash::ObjectId,
+            base_id: gix_hash::ObjectId,
+        },
+        #[error("The delta entry at offset {offset} in the chain of object {id} doesn't have a base")]
+        MissingDeltaBase {
+            id: gix_hash::ObjectId,
+            offset: data::Offset,
+        },
+        #[error("The delta chain of object {id} refers back to itself")]
+        DeltaCycle { id: gix_hash::ObjectId },
+        #[error("Interrupted")]
+        Interrupted,
+    }
+
+    /// Information about a single object in a pack, similar to what `git verify-pack -v` prints.
+    #[derive(Debug, PartialEq, Eq, Hash, Clone)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct Object {
+        /// The id of the object.
+        pub id: gix_hash::ObjectId,
+        /// The kind of the object, which for deltas is the kind of the object at the end of its delta chain.
+        pub kind: gix_object::Kind,
+        /// The size of the decompressed entry, which for deltas is the size of the delta, not of the object.
+        pub size: u64,
+        /// The amount of bytes the entry occupies in the pack, including its header.
+        pub size_in_pack: u64,
+        /// The offset of the entry in the pack.
+        pub pack_offset: data::Offset,
+        /// The amount of deltas to apply to obtain the object, 0 for objects stored as is.
+        pub depth: u32,
+        /// The id of the object this delta is applied to, or `None` if it's not a delta.
+        pub base: Option<gix_hash::ObjectId>,
+    }
+
+    /// Statistics about the delta chains of all objects in a pack.
+    #[derive(Default, Debug, PartialEq, Eq, Hash, Clone)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct Chains {
+        /// The amount of objects stored as is.
+        pub num_non_deltas: u32,
+        /// The amount of objects stored as delta.
+        pub num_deltas: u32,
+        /// The length of the longest delta chain.
+        pub max_depth: u32,
+        /// The sum of the depth of all objects.
+        pub total_depth: u64,
+        /// A histogram mapping the depth of objects to the amount of objects with that depth.
+        ///
+        /// Only depths of 1 or more are listed, like `git verify-pack -v` does it.
+        pub objects_per_depth: BTreeMap<u32, u32>,
+    }
+
+    impl Chains {
+        /// The average depth of all deltified objects, or 0 if there is none.
+        pub fn average_depth(&self) -> f32 {
+            if self.num_deltas == 0 {
+                0.0
+            } else {
+                self.total_depth as f32 / self.num_deltas as f32
+            }
+        }
+    }
+
+    /// Returned by [`index::File::verify_integrity_with_statistics()`][crate::index::File::verify_integrity_with_statistics()].
+    pub struct Outcome {
+        /// The outcome of the integrity check of index and pack, which is `Ok` if the pack is valid.
+        pub integrity: Result<super::integrity::Outcome, crate::index::traverse::Error<super::integrity::Error>>,
+        /// All objects in the pack, in the order they are stored in the pack.
+        pub objects: Vec<Object>,
+        /// Statistics about the delta chains of `objects`.
+        pub chains: Chains,
+    }
+}
+
 ///
 pub mod checksum {
     /// Returned by [`index::File::verify_checksum()`][crate::index::File::verify_checksum()].
@@ -228,6 +319,118 @@ impl index::File {
         }
     }
 
+    /// Like [`verify_integrity()`](Self::verify_integrity()), but additionally obtain information about every object in `pack`
+    /// along with statistics about its delta chains, similar to what `git verify-pack -v` provides.
+    ///
+    /// Statistics are gathered even if the integrity check fails, which is reported in the returned outcome.
+    /// Errors are only returned if the statistics can't be gathered at all.
+    pub fn verify_integrity_with_statistics<C, F>(
+        &self,
+        pack: PackContext<'_, F>,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<statistics::Outcome, statistics::Error>
+    where
+        C: crate::cache::DecodeEntry,
+        F: Fn() -> C + Send + Clone,
+    {
+        use gix_features::progress::Count;
+        use std::{collections::HashMap, sync::atomic::Ordering};
+        let data = pack.data;
+        let integrity = self.verify_integrity(Some(pack), progress, should_interrupt);
+
+        let mut entries: Vec<_> = self.iter().collect();
+        entries.sort_by_key(|e| e.pack_offset);
+        let id_by_offset: HashMap<_, _> = entries.iter().map(|e| (e.pack_offset, e.oid)).collect();
+        let pack_end = data.pack_end() as u64;
+        if let Some(entry) = entries.iter().find(|e| e.pack_offset >= pack_end) {
+            return Err(statistics::Error::OffsetOutOfBounds {
+                id: entry.oid,
+                offset: entry.pack_offset,
+            });
+        }
+
+        let mut objects_progress = progress.add_child("computing statistics".into());
+        objects_progress.init(Some(entries.len()), gix_features::progress::count("objects"));
+        let mut depth_and_kind_by_offset = HashMap::<crate::data::Offset, (u32, gix_object::Kind)>::new();
+        let mut objects = Vec::with_capacity(entries.len());
+        let mut chains = statistics::Chains::default();
+        let mut chain = Vec::new();
+        for (idx, entry) in entries.iter().enumerate() {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(statistics::Error::Interrupted);
+            }
+            let pack_entry = data.entry(entry.pack_offset);
+            let base_of = |pack_entry: &crate::data::Entry, id: gix_hash::ObjectId| {
+                use crate::data::entry::Header::*;
+                Ok(match pack_entry.header {
+                    OfsDelta { base_distance } => Some(pack_entry.base_pack_offset(base_distance)),
+                    RefDelta { base_id } => Some(
+                        self.lookup(base_id)
+                            .map(|idx| self.pack_offset_at_index(idx))
+                            .ok_or(statistics::Error::MissingBase { id, base_id })?,
+                    ),
+                    _ => None,
+                })
+            };
+            let base_offset = base_of(&pack_entry, entry.oid)?;
+
+            chain.clear();
+            let mut offset = entry.pack_offset;
+            let (base_depth, kind) = loop {
+                if let Some(depth_and_kind) = depth_and_kind_by_offset.get(&offset) {
+                    break *depth_and_kind;
+                }
+                let pack_entry = data.entry(offset);
+                match pack_entry.header.as_kind() {
+                    Some(kind) => {
+                        depth_and_kind_by_offset.insert(offset, (0, kind));
+                        break (0, kind);
+                    }
+                    None => {
+                        if chain.len() > entries.len() {
+                            return Err(statistics::Error::DeltaCycle { id: entry.oid });
+                        }
+                        chain.push(offset);
+                        offset = base_of(&pack_entry, entry.oid)?
+                            .ok_or(statistics::Error::MissingDeltaBase { id: entry.oid, offset })?;
+                    }
+                }
+            };
+            for (distance, offset) in chain.iter().rev().enumerate() {
+                depth_and_kind_by_offset.insert(*offset, (base_depth + distance as u32 + 1, kind));
+            }
+            let depth = depth_and_kind_by_offset[&entry.pack_offset].0;
+
+            if depth == 0 {
+                chains.num_non_deltas += 1;
+            } else {
+                chains.num_deltas += 1;
+                *chains.objects_per_depth.entry(depth).or_insert(0) += 1;
+            }
+            chains.max_depth = chains.max_depth.max(depth);
+            chains.total_depth += depth as u64;
+
+            let next_offset = entries.get(idx + 1).map_or(pack_end, |e| e.pack_offset);
+            objects.push(statistics::Object {
+                id: entry.oid,
+                kind,
+                size: pack_entry.decompressed_size,
+                size_in_pack: next_offset - entry.pack_offset,
+                pack_offset: entry.pack_offset,
+                depth,
+                base: base_offset.and_then(|offset| id_by_offset.get(&offset).copied()),
+            });
+            objects_progress.inc();
+        }
+
+        Ok(statistics::Outcome {
+            integrity,
+            objects,
+            chains,
+        })
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn verify_entry(
         verify_mode: Mode,
@@ -241,13 +444,12 @@ impl index::File {
             use gix_object::Kind::*;
             match object_kind {
                 Tree | Commit | Tag => {
-                    let object = gix_object::ObjectRef::from_bytes(object_kind, buf).map_err(|err| {
-                        integrity::Error::ObjectDecode {
+                    let object = gix_object::ObjectRef::from_bytes_with_hash(object_kind, buf, index_entry.oid.kind())
+                        .map_err(|err| integrity::Error::ObjectDecode {
                             source: err,
                             kind: object_kind,
                             id: index_entry.oid,
-                        }
-                    })?;
+                        })?;
                     if let Mode::HashCrc32DecodeEncode = verify_mode {
                         encode_buf.clear();
                         object.write_to(&mut *encode_buf)?;
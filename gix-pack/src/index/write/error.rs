@@ -8,7 +8,7 @@ pub enum Error {
     Io(#[from] io::Error),
     #[error("A pack entry could not be extracted")]
     PackEntryDecode(#[from] crate::data::input::Error),
-    #[error("Indices of type {} cannot be written, only {} are supported", *.0 as usize, crate::index::Version::default() as usize)]
+    #[error("Indices of type {} cannot be written, only 2 and 3 are supported", *.0 as usize)]
     Unsupported(crate::index::Version),
     #[error("Ref delta objects are not supported as there is no way to look them up. Resolve them beforehand.")]
     IteratorInvariantNoRefDelta,
@@ -67,7 +67,8 @@ impl crate::index::File {
     /// The resolver produced by `make_resolver` must resolve pack entries from the same pack data file that produced the
     /// `entries` iterator.
     ///
-    /// * `kind` is the version of pack index to produce, use [`crate::index::Version::default()`] if in doubt.
+    /// * `kind` is the version of pack index to produce, use [`crate::index::Version::default()`] if in doubt, as
+    ///   [V3](crate::index::Version::V3) indices can't be read by `git`.
     /// * `tread_limit` is used for a parallel tree traversal for obtaining object hashes with optimal performance.
     /// * `root_progress` is the top-level progress to stay informed about the progress of this potentially long-running
     ///    computation.
@@ -99,7 +100,7 @@ impl crate::index::File {
         R: Send + Sync,
         F2: for<'r> Fn(crate::data::EntryRange, &'r R) -> Option<&'r [u8]> + Send + Clone,
     {
-        if version != crate::index::Version::default() {
+        if version == crate::index::Version::V1 {
             return Err(Error::Unsupported(version));
         }
         let mut num_objects: usize = 0;
@@ -194,7 +195,7 @@ impl crate::index::File {
                      decompressed: bytes,
                      ..
                  }| {
-                    modify_base(data, entry, bytes, version.hash());
+                    modify_base(data, entry, bytes, object_hash);
                     Ok::<_, Error>(())
                 },
                 traverse::Options {
@@ -228,7 +229,7 @@ impl crate::index::File {
                 let header = crate::data::header::encode(pack_version, 0);
                 let mut hasher = gix_features::hash::hasher(object_hash);
                 hasher.update(&header);
-                gix_hash::ObjectId::from(hasher.digest())
+                hasher.digest()
             }
             None => return Err(Error::IteratorInvariantTrailer),
         };
@@ -237,6 +238,7 @@ impl crate::index::File {
             sorted_pack_offsets_by_oid,
             &pack_hash,
             version,
+            object_hash,
             &mut root_progress.add_child_with_id("writing index file".into(), ProgressId::IndexBytesWritten.into()),
         )?;
         root_progress.show_throughput_with(
@@ -18,6 +18,8 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 #![deny(missing_docs, rust_2018_idioms, unsafe_code)]
 
+///
+pub mod bitmap;
 ///
 pub mod bundle;
 /// A bundle of pack data and the corresponding pack index
@@ -44,13 +46,41 @@ pub mod index;
 ///
 pub mod multi_index;
 
+///
+pub mod mtimes;
+
 ///
 pub mod verify;
 
-mod mmap {
+///
+pub mod mmap {
     use std::path::Path;
 
-    pub fn read_only(path: &Path) -> std::io::Result<memmap2::Mmap> {
+    /// A hint to the operating system about how a memory map is going to be accessed, which allows it to adjust
+    /// read-ahead and caching of the mapped file accordingly.
+    ///
+    /// Note that hints are only applied on unix, and are ignored on other platforms.
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub enum Advice {
+        /// Don't give any hint and let the operating system decide, which typically means moderate read-ahead.
+        #[default]
+        Normal,
+        /// Pages will be accessed in order, so aggressive read-ahead is beneficial and pages may be freed soon after access.
+        ///
+        /// This is best when streaming entire packs, for instance when verifying or copying them.
+        Sequential,
+        /// Pages will be accessed in no particular order, so read-ahead is wasteful.
+        ///
+        /// This is best for large packs in which only a few objects are looked up.
+        Random,
+        /// Pages will be needed soon, so they should be read ahead of time.
+        ///
+        /// This is best for small packs that are accessed as a whole, but can cause a lot of IO for large ones.
+        WillNeed,
+    }
+
+    pub(crate) fn read_only(path: &Path) -> std::io::Result<memmap2::Mmap> {
         let file = std::fs::File::open(path)?;
         // SAFETY: we have to take the risk of somebody changing the file underneath. Git never writes into the same file.
         #[allow(unsafe_code)]
@@ -58,6 +88,23 @@ mod mmap {
             memmap2::Mmap::map(&file)
         }
     }
+
+    /// Pass `advice` to the operating system for the entirety of `map`.
+    #[cfg(unix)]
+    pub(crate) fn advise(map: &memmap2::Mmap, advice: Advice) -> std::io::Result<()> {
+        map.advise(match advice {
+            Advice::Normal => memmap2::Advice::Normal,
+            Advice::Sequential => memmap2::Advice::Sequential,
+            Advice::Random => memmap2::Advice::Random,
+            Advice::WillNeed => memmap2::Advice::WillNeed,
+        })
+    }
+
+    /// Ignore `advice` as this platform doesn't support it.
+    #[cfg(not(unix))]
+    pub(crate) fn advise(_map: &memmap2::Mmap, _advice: Advice) -> std::io::Result<()> {
+        Ok(())
+    }
 }
 
 use std::convert::TryInto;
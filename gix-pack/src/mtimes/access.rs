@@ -0,0 +1,49 @@
+use std::path::Path;
+
+use crate::mtimes::{File, Version};
+
+/// Access methods
+impl File {
+    /// Returns the version of the mtimes file.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+    /// Returns the path from which the mtimes file was loaded.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+    /// Returns the amount of objects whose modification time is stored, which is the same as the amount of objects in the
+    /// corresponding pack.
+    pub fn num_objects(&self) -> u32 {
+        self.num_objects
+    }
+    /// Returns the kind of hash function used for the checksums in this file.
+    pub fn object_hash(&self) -> gix_hash::Kind {
+        self.object_hash
+    }
+    /// Returns the checksum of the pack data file these modification times belong to.
+    pub fn pack_checksum(&self) -> gix_hash::ObjectId {
+        let from = self.data.len() - self.hash_len * 2;
+        gix_hash::ObjectId::from(&self.data[from..][..self.hash_len])
+    }
+    /// Returns the checksum over the entire content of the file (excluding the checksum itself).
+    pub fn checksum(&self) -> gix_hash::ObjectId {
+        gix_hash::ObjectId::from(&self.data[self.data.len() - self.hash_len..])
+    }
+    /// Returns the modification time in seconds since the unix epoch of the object at `index` in the pack index.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    pub fn mtime_at_index(&self, index: u32) -> u32 {
+        let start = Self::HEADER_LEN + index as usize * 4;
+        crate::read_u32(&self.data[start..][..4])
+    }
+    /// Returns an iterator over the modification times in seconds since the unix epoch of all objects, in the order
+    /// of the pack index.
+    pub fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        self.data[Self::HEADER_LEN..][..self.num_objects as usize * 4]
+            .chunks(4)
+            .map(crate::read_u32)
+    }
+}
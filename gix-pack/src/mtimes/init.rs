@@ -0,0 +1,88 @@
+use std::path::Path;
+
+use crate::mtimes::{File, Version};
+
+mod error {
+    /// The error returned by [File::at()][super::File::at()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not open mtimes file at '{path}'")]
+        Io {
+            source: std::io::Error,
+            path: std::path::PathBuf,
+        },
+        #[error("{message}")]
+        Corrupt { message: &'static str },
+        #[error("Unsupported mtimes version: {version})")]
+        UnsupportedVersion { version: u32 },
+        #[error("Unsupported hash kind: {kind})")]
+        UnsupportedObjectHash { kind: u32 },
+    }
+}
+
+pub use error::Error;
+
+/// Initialization
+impl File {
+    /// The signature at the start of every mtimes file.
+    pub const SIGNATURE: &'static [u8] = b"MTME";
+    /// The length of the header, consisting of the signature, the version and the hash kind.
+    pub const HEADER_LEN: usize = 4 /*signature*/ + 4 /*version*/ + 4 /*hash kind*/;
+
+    /// Open the mtimes file at the given `path`.
+    pub fn at(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::at_inner(path.as_ref())
+    }
+
+    fn at_inner(path: &Path) -> Result<Self, Error> {
+        let data = crate::mmap::read_only(path).map_err(|source| Error::Io {
+            source,
+            path: path.to_owned(),
+        })?;
+        if data.len() < Self::HEADER_LEN {
+            return Err(Error::Corrupt {
+                message: "mtimes file is too small to contain even a header",
+            });
+        }
+        let (signature, d) = data.split_at(Self::SIGNATURE.len());
+        if signature != Self::SIGNATURE {
+            return Err(Error::Corrupt {
+                message: "Invalid signature",
+            });
+        }
+        let (version, d) = d.split_at(4);
+        let version = match crate::read_u32(version) {
+            1 => Version::V1,
+            version => return Err(Error::UnsupportedVersion { version }),
+        };
+        let object_hash = match crate::read_u32(&d[..4]) {
+            1 => gix_hash::Kind::Sha1,
+            2 => gix_hash::Kind::Sha256,
+            kind => return Err(Error::UnsupportedObjectHash { kind }),
+        };
+        let hash_len = object_hash.len_in_bytes();
+        let table_len = data
+            .len()
+            .checked_sub(Self::HEADER_LEN + hash_len * 2)
+            .ok_or(Error::Corrupt {
+                message: "mtimes file is too small to contain the trailing checksums",
+            })?;
+        if table_len % 4 != 0 {
+            return Err(Error::Corrupt {
+                message: "The modification time table isn't a multiple of 4 bytes",
+            });
+        }
+        let num_objects = (table_len / 4).try_into().map_err(|_| Error::Corrupt {
+            message: "The modification time table has more than 2^32 entries",
+        })?;
+        Ok(File {
+            data,
+            path: path.to_owned(),
+            version,
+            hash_len,
+            object_hash,
+            num_objects,
+        })
+    }
+}
@@ -0,0 +1,30 @@
+use memmap2::Mmap;
+
+/// Known `.mtimes` file versions.
+#[derive(Default, PartialEq, Eq, Ord, PartialOrd, Debug, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(missing_docs)]
+pub enum Version {
+    #[default]
+    V1 = 1,
+}
+
+/// A file with the modification times of all objects in a _cruft pack_, typically stored next to it as `pack-<hash>.mtimes`.
+///
+/// Cruft packs contain unreachable objects which are kept until they expire. As individual objects can't have a
+/// modification time once they are packed, it's stored in this file instead, in the same order as the objects appear
+/// in the corresponding [pack index](crate::index::File).
+pub struct File {
+    data: Mmap,
+    path: std::path::PathBuf,
+    version: Version,
+    hash_len: usize,
+    object_hash: gix_hash::Kind,
+    num_objects: u32,
+}
+
+mod access;
+///
+pub mod init;
+///
+pub mod write;
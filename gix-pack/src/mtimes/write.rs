@@ -0,0 +1,30 @@
+use std::io::Write;
+
+use crate::mtimes;
+
+/// Writing
+impl mtimes::File {
+    /// Write a new mtimes file to `out` with the modification times in seconds since the unix epoch of all objects in
+    /// a pack, ordered like the objects in its index, along with the checksum of the pack data file as `pack_checksum`.
+    ///
+    /// Returns the checksum over the written data, which is also written as trailer.
+    pub fn write_to(
+        mtimes: impl IntoIterator<Item = u32>,
+        pack_checksum: &gix_hash::oid,
+        out: &mut dyn std::io::Write,
+        object_hash: gix_hash::Kind,
+    ) -> std::io::Result<gix_hash::ObjectId> {
+        let mut out = gix_features::hash::Write::new(out, object_hash);
+        out.write_all(Self::SIGNATURE)?;
+        out.write_all(&(mtimes::Version::V1 as u32).to_be_bytes())?;
+        out.write_all(&(object_hash as u32).to_be_bytes())?;
+        for mtime in mtimes {
+            out.write_all(&mtime.to_be_bytes())?;
+        }
+        out.write_all(pack_checksum.as_bytes())?;
+
+        let checksum = out.hash.digest();
+        out.inner.write_all(checksum.as_slice())?;
+        Ok(checksum)
+    }
+}
@@ -53,6 +53,26 @@ impl File {
     pub fn checksum(&self) -> gix_hash::ObjectId {
         gix_hash::ObjectId::from(&self.data[self.data.len() - self.hash_len..])
     }
+    /// Returns the directory containing the packs whose objects we contain.
+    ///
+    /// It's the directory containing this file, or its parent directory if this file is a layer of a [chain](crate::multi_index::chain).
+    pub fn pack_directory(&self) -> &Path {
+        let parent = self.path.parent().expect("file in a directory");
+        if self.is_chain_layer {
+            parent.parent().expect("layer in a sub-directory of the pack directory")
+        } else {
+            parent
+        }
+    }
+    /// Returns the amount of packs in all layers below this one if it's a layer of a [chain](crate::multi_index::chain), or 0.
+    ///
+    /// Layers are recognized by their location in the [chain directory](crate::multi_index::chain::DIRECTORY).
+    ///
+    /// Pack indices of all layers are counted across the whole chain on disk, whereas all methods of this instance
+    /// return pack indices relative to this layer.
+    pub fn num_base_indices(&self) -> PackIndex {
+        self.num_base_indices
+    }
     /// Return all names of index files (`*.idx`) whose objects we contain.
     ///
     /// The corresponding pack can be found by replacing the `.idx` extension with `.pack`.
@@ -111,7 +131,7 @@ impl File {
 
         const HIGH_BIT: u32 = 1 << 31;
 
-        let pack_index = crate::read_u32(&self.data[start..][..4]);
+        let pack_index = crate::read_u32(&self.data[start..][..4]) - self.num_base_indices;
         let offset = &self.data[start + 4..][..4];
         let ofs32 = crate::read_u32(offset);
         let pack_offset = if (ofs32 & HIGH_BIT) == HIGH_BIT {
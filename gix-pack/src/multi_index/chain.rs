@@ -0,0 +1,126 @@
+//! An incremental multi-pack-index, made of layers which each add the objects of new packs to the layers below them.
+//!
+//! The layers are stored in the [`DIRECTORY`] within the pack directory, and the [`FILE_NAME`] therein lists the checksums of
+//! all layers, one per line, starting with the bottom-most one. This way, new packs can be added by writing a small layer
+//! instead of rewriting the entire multi-pack-index.
+use std::path::{Path, PathBuf};
+
+use crate::multi_index::File;
+
+/// The name of the directory within the pack directory which contains the chain.
+pub const DIRECTORY: &str = "multi-pack-index.d";
+/// The name of the file within the [`DIRECTORY`] that lists the checksums of all layers.
+pub const FILE_NAME: &str = "multi-pack-index-chain";
+
+/// Return the file name of the layer with the given `checksum`.
+pub fn layer_file_name(checksum: &gix_hash::oid) -> String {
+    format!("multi-pack-index-{checksum}.midx")
+}
+
+/// Return the path to the file listing all layers of the chain in `pack_dir`.
+pub fn file_path(pack_dir: &Path) -> PathBuf {
+    pack_dir.join(DIRECTORY).join(FILE_NAME)
+}
+
+mod error {
+    /// The error returned by [File::at_chain()][super::File::at_chain()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not read multi-pack-index chain at '{path}'")]
+        Io {
+            source: std::io::Error,
+            path: std::path::PathBuf,
+        },
+        #[error("The multi-pack-index chain contained an invalid checksum: {line:?}")]
+        InvalidChecksum { line: String },
+        #[error(transparent)]
+        Layer(#[from] crate::multi_index::init::Error),
+        #[error("The layer at '{path}' has checksum {actual}, but the chain expected {expected}")]
+        ChecksumMismatch {
+            path: std::path::PathBuf,
+            expected: gix_hash::ObjectId,
+            actual: gix_hash::ObjectId,
+        },
+    }
+}
+
+pub use error::Error;
+
+/// Return the checksums of all layers listed in the chain file at `chain_path`, starting with the bottom-most one.
+fn read_checksums(chain_path: &Path) -> Result<Vec<gix_hash::ObjectId>, Error> {
+    let chain = std::fs::read_to_string(chain_path).map_err(|source| Error::Io {
+        source,
+        path: chain_path.to_owned(),
+    })?;
+    chain
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            gix_hash::ObjectId::from_hex(line.as_bytes()).map_err(|_| Error::InvalidChecksum { line: line.to_owned() })
+        })
+        .collect()
+}
+
+/// Return the amount of packs in all layers below the layer at `layer_path`, by reading the headers of the layers
+/// listed before it in the chain.
+pub(crate) fn num_base_indices(layer_path: &Path) -> Result<u32, crate::multi_index::init::Error> {
+    use crate::multi_index::init::Error as InitError;
+    let layers_dir = layer_path.parent().expect("layer in chain directory");
+    let io_err = |source: std::io::Error, path: &Path| InitError::Io {
+        source,
+        path: path.to_owned(),
+    };
+    let checksums = read_checksums(&layers_dir.join(FILE_NAME)).map_err(|err| match err {
+        Error::Io { source, path } => io_err(source, &path),
+        _ => InitError::Corrupt {
+            message: "The multi-pack-index chain contained an invalid checksum",
+        },
+    })?;
+    let mut num_base_indices = 0;
+    for checksum in checksums {
+        let path = layers_dir.join(layer_file_name(&checksum));
+        if path == layer_path {
+            return Ok(num_base_indices);
+        }
+        let mut header = [0; File::HEADER_LEN];
+        std::io::Read::read_exact(
+            &mut std::fs::File::open(&path).map_err(|err| io_err(err, &path))?,
+            &mut header,
+        )
+        .map_err(|err| io_err(err, &path))?;
+        num_base_indices += crate::read_u32(&header[File::HEADER_LEN - 4..]);
+    }
+    Err(InitError::LayerNotInChain {
+        path: layer_path.to_owned(),
+    })
+}
+
+/// Initialization
+impl File {
+    /// Open all layers of the multi-pack-index chain in `pack_dir`, starting with the bottom-most one.
+    ///
+    /// Each layer only contains packs and objects that aren't contained in the layers below it.
+    pub fn at_chain(pack_dir: impl AsRef<Path>) -> Result<Vec<File>, Error> {
+        let chain_path = file_path(pack_dir.as_ref());
+        let layers_dir = chain_path.parent().expect("chain file in directory");
+        read_checksums(&chain_path)?
+            .into_iter()
+            .map(|expected| {
+                let path = layers_dir.join(layer_file_name(&expected));
+                let layer = File::at(&path)?;
+                let actual = layer.checksum();
+                if actual != expected {
+                    return Err(Error::ChecksumMismatch { path, expected, actual });
+                }
+                Ok(layer)
+            })
+            .collect()
+    }
+
+    /// Return `true` if this file is a layer of a [chain](self).
+    pub fn is_chain_layer(&self) -> bool {
+        self.is_chain_layer
+    }
+}
@@ -1,6 +1,6 @@
 use std::{convert::TryFrom, path::Path};
 
-use crate::multi_index::{chunk, File, Version};
+use crate::multi_index::{chain, chunk, File, Version};
 
 mod error {
     use crate::multi_index::chunk;
@@ -32,6 +32,8 @@ mod error {
         PackNames(#[from] chunk::index_names::decode::Error),
         #[error("multi-index chunk {:?} has invalid size: {message}", String::from_utf8_lossy(.id))]
         InvalidChunkSize { id: gix_chunk::Id, message: &'static str },
+        #[error("The multi-pack-index layer at '{path}' isn't listed in its chain")]
+        LayerNotInChain { path: std::path::PathBuf },
     }
 }
 
@@ -139,6 +141,15 @@ impl TryFrom<&Path> for File {
             });
         }
 
+        let is_chain_layer = path
+            .parent()
+            .and_then(Path::file_name)
+            .map_or(false, |name| name == chain::DIRECTORY);
+        let num_base_indices = if is_chain_layer {
+            chain::num_base_indices(path)?
+        } else {
+            0
+        };
         Ok(File {
             data,
             path: path.to_owned(),
@@ -152,6 +163,8 @@ impl TryFrom<&Path> for File {
             large_offsets_ofs: large_offsets.map(|r| r.start),
             num_objects,
             num_indices,
+            num_base_indices,
+            is_chain_layer,
         })
     }
 }
@@ -34,11 +34,18 @@ pub struct File {
     lookup_ofs: usize,
     offsets_ofs: usize,
     large_offsets_ofs: Option<usize>,
+    /// The amount of packs in all layers below this one if it is part of a [chain](chain), or 0 otherwise.
+    num_base_indices: u32,
+    /// If `true`, this file is a layer of a [chain](chain) and stored in a sub-directory of the pack directory.
+    is_chain_layer: bool,
 }
 
 ///
 pub mod write;
 
+///
+pub mod chain;
+
 ///
 mod access;
 
@@ -139,7 +139,7 @@ impl File {
         C: crate::cache::DecodeEntry,
         F: Fn() -> C + Send + Clone,
     {
-        let parent = self.path.parent().expect("must be in a directory");
+        let parent = self.pack_directory();
 
         let actual_index_checksum = self
             .verify_checksum(
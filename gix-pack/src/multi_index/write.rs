@@ -1,6 +1,6 @@
 use std::{
     convert::TryInto,
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::atomic::{AtomicBool, Ordering},
     time::{Instant, SystemTime},
 };
@@ -78,12 +78,48 @@ impl multi_index::File {
     ///
     /// Progress is sent to `progress` and interruptions checked via `should_interrupt`.
     pub fn write_from_index_paths(
+        index_paths: Vec<PathBuf>,
+        out: &mut dyn std::io::Write,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        Self::write_from_index_paths_inner(index_paths, &[], out, progress, should_interrupt, options)
+    }
+
+    /// Create a new layer of a [multi-pack-index chain](multi_index::chain) for writing to `out` from the pack index files at
+    /// `index_paths`, on top of the `base` layers as opened with [`File::at_chain()`](multi_index::File::at_chain()).
+    ///
+    /// Packs and objects which are already contained in one of the `base` layers are skipped.
+    /// The caller is expected to store the file as [layer file](multi_index::chain::layer_file_name()) named after the returned
+    /// checksum, and to append the checksum to the chain file.
+    pub fn write_layer_from_index_paths(
+        index_paths: Vec<PathBuf>,
+        base: &[multi_index::File],
+        out: &mut dyn std::io::Write,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        Self::write_from_index_paths_inner(index_paths, base, out, progress, should_interrupt, options)
+    }
+
+    fn write_from_index_paths_inner(
         mut index_paths: Vec<PathBuf>,
+        base: &[multi_index::File],
         out: &mut dyn std::io::Write,
         progress: &mut dyn DynNestedProgress,
         should_interrupt: &AtomicBool,
         Options { object_hash }: Options,
     ) -> Result<Outcome, Error> {
+        index_paths.retain(|path| {
+            let file_name = Path::new(path.file_name().expect("file name present"));
+            !base
+                .iter()
+                .any(|layer| layer.index_names().iter().any(|name| name == file_name))
+        });
+        // Pack indices are counted across all layers of a chain.
+        let num_base_indices: u32 = base.iter().map(multi_index::File::num_indices).sum();
         let out = gix_features::hash::Write::new(out, object_hash);
         let (index_paths_sorted, index_filenames_sorted) = {
             index_paths.sort();
@@ -112,12 +148,17 @@ impl multi_index::File {
                 let index = crate::index::File::at(index, object_hash)?;
 
                 entries.reserve(index.num_objects() as usize);
-                entries.extend(index.iter().map(|e| Entry {
-                    id: e.oid,
-                    pack_index: index_id as u32,
-                    pack_offset: e.pack_offset,
-                    index_mtime: mtime,
-                }));
+                entries.extend(
+                    index
+                        .iter()
+                        .filter(|e| base.iter().all(|layer| layer.lookup(e.oid).is_none()))
+                        .map(|e| Entry {
+                            id: e.oid,
+                            pack_index: num_base_indices + index_id as u32,
+                            pack_offset: e.pack_offset,
+                            index_mtime: mtime,
+                        }),
+                );
                 progress.inc();
                 if should_interrupt.load(Ordering::Relaxed) {
                     return Err(Error::Interrupted);
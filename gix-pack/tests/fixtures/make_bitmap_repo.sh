@@ -0,0 +1,26 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+for round in $(seq 12); do
+  mkdir -p "dir-$(( round % 3 ))"
+  seq "$round" > "dir-$(( round % 3 ))/file"
+  echo "$round" > "file-$round"
+  git add .
+  git commit -qm "$round"
+  if [ "$round" = 6 ]; then
+    git branch side
+  fi
+done
+
+git checkout -q side
+echo side > side-file
+git add side-file
+git commit -qm "side"
+git checkout -q main
+git merge -q --no-ff -m "merge side" side
+git tag -m "a tag object" v1
+
+git repack -adbq
@@ -0,0 +1,101 @@
+use std::sync::atomic::AtomicBool;
+
+use gix_features::progress;
+use gix_pack::bitmap;
+
+fn repo_pack() -> crate::Result<(std::path::PathBuf, std::path::PathBuf)> {
+    let pack_dir = crate::scripted_fixture_read_only("make_bitmap_repo.sh")?
+        .join(".git")
+        .join("objects")
+        .join("pack");
+    let index_path = std::fs::read_dir(&pack_dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .find(|path| path.extension().map_or(false, |ext| ext == "idx"))
+        .expect("a single pack");
+    Ok((pack_dir, index_path))
+}
+
+fn write(
+    bundle: &gix_pack::Bundle,
+    tips: impl IntoIterator<Item = gix_hash::ObjectId>,
+    selection: bitmap::write::Selection,
+    path: &std::path::Path,
+) -> crate::Result<bitmap::write::Outcome> {
+    let mut out = std::fs::File::create(path)?;
+    Ok(bitmap::File::write_to(
+        bundle,
+        tips,
+        &mut out,
+        &mut progress::Discard,
+        &AtomicBool::default(),
+        bitmap::write::Options { selection },
+    )?)
+}
+
+fn tips(pack_dir: &std::path::Path) -> crate::Result<Vec<gix_hash::ObjectId>> {
+    let refs_dir = pack_dir.join("..").join("..").join("refs");
+    ["heads/main", "heads/side", "tags/v1"]
+        .into_iter()
+        .map(|name| {
+            let hex = std::fs::read(refs_dir.join(name))?;
+            Ok(gix_hash::ObjectId::from_hex(&hex[..40])?)
+        })
+        .collect()
+}
+
+#[test]
+fn written_bitmaps_match_the_ones_written_by_git() -> crate::Result {
+    let (pack_dir, index_path) = repo_pack()?;
+    let bundle = gix_pack::Bundle::at(&index_path, gix_hash::Kind::Sha1)?;
+    let expected = bitmap::File::at(index_path.with_extension("bitmap"), gix_hash::Kind::Sha1)?;
+    assert_eq!(expected.pack_checksum(), bundle.pack.checksum());
+
+    let dir = gix_testtools::tempfile::TempDir::new()?;
+    let path = dir.path().join("pack.bitmap");
+    let outcome = write(&bundle, tips(&pack_dir)?, bitmap::write::Selection::Interval(1), &path)?;
+    let actual = bitmap::File::at(&path, gix_hash::Kind::Sha1)?;
+    assert_eq!(actual.checksum(), outcome.checksum);
+    assert_eq!(actual.num_bitmaps(), 14, "every commit receives a bitmap");
+    assert_eq!(actual.num_bitmaps(), outcome.num_bitmaps);
+    assert_eq!(actual.pack_checksum(), bundle.pack.checksum());
+    assert_eq!(actual.flags(), bitmap::flags::FULL_DAG);
+
+    for kind in bitmap::File::KINDS {
+        assert_eq!(
+            actual.pack_positions_of_kind(kind),
+            expected.pack_positions_of_kind(kind),
+            "{kind}"
+        );
+    }
+    assert_ne!(expected.num_bitmaps(), 0);
+    for index_position in expected.index_positions() {
+        assert_eq!(
+            actual.reachable_from(index_position),
+            expected.reachable_from(index_position),
+            "the objects reachable from {}",
+            bundle.index.oid_at_index(index_position)
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn selection_of_tips_only() -> crate::Result {
+    let (pack_dir, index_path) = repo_pack()?;
+    let bundle = gix_pack::Bundle::at(&index_path, gix_hash::Kind::Sha1)?;
+    let dir = gix_testtools::tempfile::TempDir::new()?;
+    let path = dir.path().join("pack.bitmap");
+    for selection in [bitmap::write::Selection::Tips, bitmap::write::Selection::Interval(0)] {
+        let outcome = write(&bundle, tips(&pack_dir)?, selection, &path)?;
+        assert_eq!(
+            outcome.num_bitmaps, 2,
+            "`main` and `side`, with the tag peeled to the commit of `main`"
+        );
+    }
+
+    let outcome = write(&bundle, tips(&pack_dir)?, bitmap::write::Selection::Interval(5), &path)?;
+    assert_eq!(outcome.num_bitmaps, 5, "every 5th of 14 commits, along with the tips");
+    Ok(())
+}
@@ -32,6 +32,7 @@ mod lookup_ref_delta_objects {
     fn entry(header: Header, data: &'static [u8]) -> input::Entry {
         let obj = gix_object::Data {
             kind: header.as_kind().unwrap_or(gix_object::Kind::Blob),
+            object_hash: gix_hash::Kind::Sha1,
             data,
         };
         let mut entry = input::Entry::from_data_obj(&obj, 0).expect("valid object");
@@ -85,6 +86,7 @@ mod lookup_ref_delta_objects {
                 buf.copy_from_slice(data);
                 Ok(Some(gix_object::Data {
                     kind: gix_object::Kind::Blob,
+                    object_hash: gix_hash::Kind::Sha1,
                     data: buf.as_slice(),
                 }))
             } else {
@@ -210,3 +212,56 @@ mod lookup_ref_delta_objects {
         }
     }
 }
+
+mod unpack {
+    use std::sync::atomic::AtomicBool;
+
+    use crate::{
+        fixture_path,
+        pack::{SMALL_PACK, SMALL_PACK_INDEX},
+    };
+    use gix_odb::Write;
+    use gix_pack::data::input;
+
+    #[test]
+    fn all_objects_are_written_with_deltas_resolved() -> crate::Result {
+        let dir = gix_testtools::tempfile::tempdir()?;
+        let db = gix_odb::at(dir.path())?;
+        let pack = std::io::BufReader::new(std::fs::File::open(fixture_path(SMALL_PACK))?);
+        let outcome = input::unpack(
+            pack,
+            &db,
+            &mut |kind, data| db.write_buf(kind, data),
+            &mut gix_features::progress::Discard,
+            &AtomicBool::default(),
+            input::unpack::Options {
+                iteration_mode: input::Mode::Verify,
+                object_hash: gix_hash::Kind::Sha1,
+            },
+        )?;
+
+        let index = gix_pack::index::File::at(fixture_path(SMALL_PACK_INDEX), gix_hash::Kind::Sha1)?;
+        assert_eq!(outcome.pack_checksum, Some(index.pack_checksum()));
+        let mut actual = outcome.ids.clone();
+        actual.sort();
+        let expected: Vec<_> = index.iter().map(|entry| entry.oid).collect();
+        assert_eq!(actual, expected, "each object was written");
+
+        let pack = gix_pack::data::File::at(fixture_path(SMALL_PACK), gix_hash::Kind::Sha1)?;
+        let num_deltas = index
+            .iter()
+            .filter(|entry| pack.entry(entry.pack_offset).header.is_delta())
+            .count();
+        assert!(num_deltas > 0, "there are deltas to resolve");
+        for id in outcome.ids {
+            let mut buf = Vec::new();
+            let object = gix_object::Find::try_find(&db, &id, &mut buf)?.expect("written");
+            assert_eq!(
+                gix_object::compute_hash(gix_hash::Kind::Sha1, object.kind, object.data),
+                id,
+                "objects are complete"
+            );
+        }
+        Ok(())
+    }
+}
@@ -321,6 +321,172 @@ fn traversals() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn thin_pack_only_refers_to_bases_known_to_receiver() -> crate::Result {
+    let db = db(DbKind::DeterministicGeneratedContent)?;
+    let count = || {
+        output::count::objects(
+            db.clone(),
+            Box::new(std::iter::once(Ok(hex_to_id(
+                "dfcb5e39ac6eb30179808bbab721e8a28ce1b52e",
+            )))),
+            &progress::Discard,
+            &AtomicBool::new(false),
+            count::objects::Options {
+                input_object_expansion: count::objects::ObjectExpansion::TreeAdditionsComparedToAncestor,
+                thread_limit: Some(1),
+                ..Default::default()
+            },
+        )
+        .map(|(counts, _)| counts)
+    };
+    let entries = |is_known_to_receiver: Box<dyn Fn(&gix_hash::oid) -> bool + Send + Sync>| -> crate::Result<_> {
+        let is_known_to_receiver = std::sync::Arc::new(is_known_to_receiver);
+        let mut iter = output::entry::iter_from_counts_with_known_bases(
+            count()?,
+            db.clone(),
+            Box::new(progress::Discard),
+            output::entry::iter_from_counts::Options {
+                allow_thin_pack: true,
+                ..Default::default()
+            },
+            move |id: &gix_hash::oid| is_known_to_receiver(id),
+        );
+        let entries: Vec<_> = InOrderIter::from(iter.by_ref())
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .flatten()
+            .collect();
+        Ok((entries, iter.finalize()?))
+    };
+    let ref_delta_bases = |entries: &[output::Entry]| {
+        entries
+            .iter()
+            .filter_map(|e| match e.kind {
+                output::entry::Kind::DeltaOid { id } => Some(id),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let (all_known, stats) = entries(Box::new(|_| true))?;
+    let bases = ref_delta_bases(&all_known);
+    assert_eq!(stats.ref_delta_objects, 74, "the same as with `iter_from_counts()`");
+    assert_eq!(bases.len(), stats.ref_delta_objects);
+
+    let (none_known, stats) = entries(Box::new(|_| false))?;
+    assert!(ref_delta_bases(&none_known).is_empty());
+    assert_eq!(stats.ref_delta_objects, 0);
+    assert_eq!(
+        stats.decoded_and_recompressed_objects, 74,
+        "deltas against unknown bases are turned into base objects"
+    );
+    assert_eq!(none_known.len(), all_known.len());
+
+    let known_base = bases[0];
+    let (one_known, stats) = entries(Box::new(move |id| id == known_base))?;
+    let expected = bases.iter().filter(|id| **id == known_base).count();
+    assert_eq!(ref_delta_bases(&one_known).len(), expected);
+    assert_eq!(stats.ref_delta_objects, expected);
+
+    for entries in [none_known, one_known] {
+        write_and_verify(db.clone(), entries, gix_hash::Kind::Sha1.null(), None)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn delta_islands_keep_deltas_within_islands() -> crate::Result {
+    let db = db(DbKind::DeterministicGeneratedContent)?;
+    let packed_refs = std::fs::read(
+        crate::scripted_fixture_read_only("make_pack_gen_repo.sh")?
+            .join(".git")
+            .join("packed-refs"),
+    )?;
+    let tips: Vec<_> = packed_refs
+        .split(|b| *b == b'\n')
+        .filter(|line| !line.is_empty() && line[0] != b'#' && line[0] != b'^')
+        .map(|line| {
+            let (id, name) = line.split_at(40);
+            let island = name.rsplit(|b| *b == b'/').next().expect("ref name");
+            (island.into(), gix_hash::ObjectId::from_hex(id).expect("valid hex"))
+        })
+        .collect();
+    let (main, tag) = (tips[0].1, tips[1].1);
+
+    let islands = output::Islands::from_tips(tips.clone(), &db, &AtomicBool::new(false))?;
+    assert_eq!(islands.names().collect::<Vec<_>>(), ["main", "referrer"]);
+    assert_eq!(
+        islands.islands_of(&main).collect::<Vec<_>>(),
+        ["main", "referrer"],
+        "the tagged commit is a child of main"
+    );
+    let tagged_commit = db.find_tag(&tag, &mut Vec::new())?.0.target();
+    assert_eq!(islands.islands_of(&tagged_commit).collect::<Vec<_>>(), ["referrer"]);
+    assert!(islands.allows_delta(&tagged_commit, &main));
+    assert!(!islands.allows_delta(&main, &tagged_commit));
+    assert!(
+        islands.allows_delta(&gix_hash::Kind::Sha1.null(), &main),
+        "objects outside of islands"
+    );
+
+    let entries = |may_delta: Box<dyn Fn(&gix_hash::oid, &gix_hash::oid) -> bool + Send + Sync>| -> crate::Result<_> {
+        let (counts, _) = output::count::objects(
+            db.clone(),
+            Box::new(vec![Ok(main), Ok(tag)].into_iter()),
+            &progress::Discard,
+            &AtomicBool::new(false),
+            count::objects::Options {
+                input_object_expansion: count::objects::ObjectExpansion::TreeContents,
+                thread_limit: Some(1),
+                ..Default::default()
+            },
+        )?;
+        let may_delta = std::sync::Arc::new(may_delta);
+        let mut iter = output::entry::iter_from_counts_with_delta_filter(
+            counts,
+            db.clone(),
+            Box::new(progress::Discard),
+            Default::default(),
+            |_: &gix_hash::oid| true,
+            move |id: &gix_hash::oid, base: &gix_hash::oid| may_delta(id, base),
+        );
+        let entries: Vec<_> = InOrderIter::from(iter.by_ref())
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .flatten()
+            .collect();
+        Ok((entries, iter.finalize()?))
+    };
+    let num_deltas = |entries: &[output::Entry]| {
+        entries
+            .iter()
+            .filter(|e| matches!(e.kind, output::entry::Kind::DeltaRef { .. }))
+            .count()
+    };
+
+    let (unrestricted, stats) = entries(Box::new(|_, _| true))?;
+    assert_eq!(stats.decoded_and_recompressed_objects, 0);
+    let (no_deltas, stats) = entries(Box::new(|_, _| false))?;
+    assert_eq!(num_deltas(&no_deltas), 0);
+    assert_eq!(stats.decoded_and_recompressed_objects, num_deltas(&unrestricted));
+
+    let islands = std::sync::Arc::new(islands);
+    let (with_islands, _stats) = entries(Box::new({
+        let islands = islands.clone();
+        move |id, base| islands.allows_delta(id, base)
+    }))?;
+    for entry in &with_islands {
+        if let output::entry::Kind::DeltaRef { object_index } = entry.kind {
+            assert!(islands.allows_delta(&entry.id, &with_islands[object_index].id));
+        }
+    }
+    for entries in [no_deltas, with_islands] {
+        write_and_verify(db.clone(), entries, gix_hash::Kind::Sha1.null(), None)?;
+    }
+    Ok(())
+}
+
 #[test]
 fn empty_pack_is_allowed() {
     write_and_verify(
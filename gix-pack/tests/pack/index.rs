@@ -489,3 +489,131 @@ fn iter() -> Result<(), Box<dyn std::error::Error>> {
     }
     Ok(())
 }
+
+#[test]
+fn write_v3_and_read_it_back() -> Result<(), Box<dyn std::error::Error>> {
+    fn slice_map(entry: gix_pack::data::EntryRange, map: &memmap2::Mmap) -> Option<&[u8]> {
+        map.get(entry.start as usize..entry.end as usize)
+    }
+    let dir = gix_testtools::tempfile::TempDir::new()?;
+    for (index_path, pack_path) in [(INDEX_V2, PACK_FOR_INDEX_V2), (SMALL_PACK_INDEX, SMALL_PACK)] {
+        let mut entries = pack::data::input::BytesToEntriesIter::new_from_header(
+            std::io::BufReader::new(std::fs::File::open(fixture_path(pack_path))?),
+            pack::data::input::Mode::Verify,
+            pack::data::input::EntryDataMode::Crc32,
+            gix_hash::Kind::Sha1,
+        )?;
+        let pack_version = entries.version();
+        let mut buf = Vec::new();
+        let outcome = index::File::write_data_iter_to_stream(
+            index::Version::V3,
+            || {
+                let file = std::fs::File::open(fixture_path(pack_path))?;
+                let map = unsafe { memmap2::Mmap::map(&file)? };
+                Ok((slice_map, map))
+            },
+            &mut entries,
+            None,
+            &mut progress::Discard,
+            &mut buf,
+            &AtomicBool::new(false),
+            gix_hash::Kind::Sha1,
+            pack_version,
+        )?;
+        assert_eq!(outcome.index_version, index::Version::V3);
+        let path = dir.path().join("pack.idx");
+        std::fs::write(&path, &buf)?;
+
+        let expected = index::File::at(fixture_path(index_path), gix_hash::Kind::Sha1)?;
+        let actual = index::File::at(&path, gix_hash::Kind::Sha1)?;
+        assert_eq!(actual.version(), index::Version::V3);
+        assert_eq!(actual.num_objects(), expected.num_objects());
+        assert_eq!(actual.index_checksum(), outcome.index_hash);
+        assert_eq!(actual.pack_checksum(), expected.pack_checksum());
+        assert_eq!(
+            actual.iter().collect::<Vec<_>>(),
+            expected.iter().collect::<Vec<_>>(),
+            "entries are listed by id, even though some tables are in pack order"
+        );
+        assert_eq!(actual.sorted_offsets(), expected.sorted_offsets());
+        for entry in expected.iter() {
+            let idx = actual.lookup(entry.oid).expect("present");
+            assert_eq!(actual.oid_at_index(idx), entry.oid);
+            let prefix = gix_hash::Prefix::new(&entry.oid, 7)?;
+            assert_eq!(actual.lookup_prefix(prefix, None), Some(Ok(idx)));
+        }
+
+        let pack = pack::data::File::at(fixture_path(pack_path), gix_hash::Kind::Sha1)?;
+        actual.verify_integrity(
+            Some(gix_pack::index::verify::PackContext {
+                data: &pack,
+                options: gix_pack::index::verify::integrity::Options {
+                    verify_mode: index::verify::Mode::HashCrc32Decode,
+                    traversal: index::traverse::Algorithm::DeltaTreeLookup,
+                    make_pack_lookup_cache: || cache::Never,
+                    thread_limit: None,
+                },
+            }),
+            &mut progress::Discard,
+            &AtomicBool::new(false),
+        )?;
+
+        assert!(
+            index::File::at(&path, gix_hash::Kind::Sha256).is_err(),
+            "the object format is recorded and checked"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn verify_integrity_with_statistics() -> Result<(), Box<dyn std::error::Error>> {
+    let idx = index::File::at(fixture_path(INDEX_V2), gix_hash::Kind::Sha1)?;
+    let pack = pack::data::File::at(fixture_path(PACK_FOR_INDEX_V2), gix_hash::Kind::Sha1)?;
+    let outcome = idx.verify_integrity_with_statistics(
+        gix_pack::index::verify::PackContext {
+            data: &pack,
+            options: Default::default(),
+        },
+        &mut progress::Discard,
+        &AtomicBool::new(false),
+    )?;
+    let traverse_stats = outcome
+        .integrity?
+        .pack_traverse_statistics
+        .expect("present as the pack was traversed");
+
+    assert_eq!(outcome.objects.len(), idx.num_objects() as usize);
+    assert_eq!(
+        outcome.chains.objects_per_depth,
+        btreemap! { 1 => 4, 2 => 3, 3 => 1, 4 => 2, 5 => 1, 6 => 1 },
+        "the histogram matches the chain lengths seen when decoding objects, without undeltified objects"
+    );
+    assert_eq!(
+        outcome.chains.num_non_deltas,
+        traverse_stats.objects_per_chain_length[&0]
+    );
+    assert_eq!(outcome.chains.num_deltas, 12);
+    assert_eq!(outcome.chains.max_depth, 6);
+    assert_eq!(outcome.chains.total_depth, 4 + 3 * 2 + 3 + 4 * 2 + 5 + 6);
+
+    let header_size = 12;
+    assert_eq!(
+        outcome.objects.iter().map(|o| o.size_in_pack).sum::<u64>(),
+        pack.pack_end() as u64 - header_size,
+        "entries are adjacent"
+    );
+    assert_eq!(
+        outcome.objects.iter().map(|o| o.size).sum::<u64>(),
+        traverse_stats.total_decompressed_entries_size
+    );
+    for object in &outcome.objects {
+        assert_eq!(object.base.is_some(), object.depth > 0, "only deltas have a base");
+        if let Some(base) = object.base {
+            let base = outcome.objects.iter().find(|o| o.id == base).expect("base is in pack");
+            assert_eq!(base.depth + 1, object.depth);
+            assert_eq!(base.kind, object.kind, "deltas have the kind of their base");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use crate::hex_to_id;
+
+#[test]
+fn write_and_read() -> crate::Result {
+    let dir = gix_testtools::tempfile::TempDir::new()?;
+    let path = dir.path().join("pack-a.mtimes");
+    let pack_checksum = hex_to_id("d34d327039a3554f8a644b29e07b903fa71ef269");
+    let mtimes = [1_700_000_000, 0, u32::MAX];
+
+    let mut out = std::fs::File::create(&path)?;
+    let checksum = gix_pack::mtimes::File::write_to(mtimes, &pack_checksum, &mut out, gix_hash::Kind::Sha1)?;
+    drop(out);
+
+    let file = gix_pack::mtimes::File::at(&path)?;
+    assert_eq!(file.version(), gix_pack::mtimes::Version::V1);
+    assert_eq!(file.object_hash(), gix_hash::Kind::Sha1);
+    assert_eq!(file.num_objects(), 3);
+    assert_eq!(file.pack_checksum(), pack_checksum);
+    assert_eq!(file.checksum(), checksum);
+    assert_eq!(file.mtime_at_index(2), u32::MAX);
+    assert_eq!(file.iter().collect::<Vec<_>>(), mtimes);
+    assert_eq!(file.path(), path);
+    Ok(())
+}
+
+#[test]
+fn corrupt_files_are_rejected() -> crate::Result {
+    let dir = gix_testtools::tempfile::TempDir::new()?;
+    let path = dir.path().join("pack-a.mtimes");
+    for (data, expected) in [
+        (&b"MTME"[..], "mtimes file is too small to contain even a header"),
+        (b"MTMX\0\0\0\x01\0\0\0\x01", "Invalid signature"),
+        (b"MTME\0\0\0\x02\0\0\0\x01", "Unsupported mtimes version: 2)"),
+        (
+            b"MTME\0\0\0\x01\0\0\0\x01",
+            "mtimes file is too small to contain the trailing checksums",
+        ),
+    ] {
+        std::fs::write(&path, data)?;
+        assert_eq!(
+            gix_pack::mtimes::File::at(&path).err().expect("corrupt").to_string(),
+            expected
+        );
+    }
+    Ok(())
+}
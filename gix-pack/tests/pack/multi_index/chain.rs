@@ -0,0 +1,94 @@
+use std::{path::PathBuf, sync::atomic::AtomicBool};
+
+use gix_features::progress;
+use gix_pack::multi_index::{chain, File};
+use gix_testtools::fixture_path_standalone;
+
+fn write_layer(pack_dir: &std::path::Path, index_paths: Vec<PathBuf>) -> crate::Result<Vec<File>> {
+    let base = if chain::file_path(pack_dir).is_file() {
+        File::at_chain(pack_dir)?
+    } else {
+        Vec::new()
+    };
+    let mut buf = Vec::new();
+    let outcome = File::write_layer_from_index_paths(
+        index_paths,
+        &base,
+        &mut buf,
+        &mut progress::Discard,
+        &AtomicBool::new(false),
+        gix_pack::multi_index::write::Options {
+            object_hash: gix_hash::Kind::Sha1,
+        },
+    )?;
+    let layers_dir = pack_dir.join(chain::DIRECTORY);
+    std::fs::create_dir_all(&layers_dir)?;
+    std::fs::write(
+        layers_dir.join(chain::layer_file_name(&outcome.multi_index_checksum)),
+        buf,
+    )?;
+    let mut lines: String = base.iter().map(|layer| format!("{}\n", layer.checksum())).collect();
+    lines.push_str(&format!("{}\n", outcome.multi_index_checksum));
+    std::fs::write(chain::file_path(pack_dir), lines)?;
+    Ok(File::at_chain(pack_dir)?)
+}
+
+#[test]
+fn layers_only_add_new_packs_and_objects() -> crate::Result {
+    let dir = gix_testtools::tempfile::TempDir::new()?;
+    let mut index_paths = Vec::new();
+    for entry in std::fs::read_dir(fixture_path_standalone("objects/pack"))? {
+        let path = entry?.path();
+        if path.extension().map_or(false, |ext| ext == "idx") {
+            for path in [path.clone(), path.with_extension("pack")] {
+                std::fs::copy(&path, dir.path().join(path.file_name().expect("present")))?;
+            }
+            index_paths.push(dir.path().join(path.file_name().expect("present")));
+        }
+    }
+    index_paths.sort();
+    assert_eq!(index_paths.len(), 3);
+
+    let layers = write_layer(dir.path(), index_paths[..2].to_vec())?;
+    assert_eq!(layers.len(), 1);
+    assert_eq!(layers[0].num_indices(), 2);
+    assert_eq!(layers[0].num_base_indices(), 0);
+
+    let layers = write_layer(dir.path(), index_paths.clone())?;
+    assert_eq!(layers.len(), 2);
+    let top = &layers[1];
+    assert!(top.is_chain_layer());
+    assert_eq!(top.num_indices(), 1, "only the new pack is added");
+    assert_eq!(top.num_base_indices(), 2);
+    assert_eq!(top.pack_directory(), dir.path());
+    assert_eq!(
+        layers.iter().map(File::num_objects).sum::<u32>(),
+        139,
+        "objects are only contained in one layer, like in a single multi-pack-index"
+    );
+
+    let index = gix_pack::index::File::at(&index_paths[2], gix_hash::Kind::Sha1)?;
+    for entry in top.iter() {
+        assert_eq!(entry.pack_index, 0, "pack indices are relative to the layer");
+        let index_entry = index.lookup(entry.oid).expect("object is in the new pack");
+        assert_eq!(index.pack_offset_at_index(index_entry), entry.pack_offset);
+        assert!(layers[0].lookup(entry.oid).is_none());
+    }
+    assert_eq!(
+        top.verify_integrity_fast(&mut progress::Discard, &AtomicBool::new(false))?,
+        top.checksum()
+    );
+    Ok(())
+}
+
+#[test]
+fn mismatching_checksums_are_detected() -> crate::Result {
+    let dir = gix_testtools::tempfile::TempDir::new()?;
+    std::fs::create_dir_all(dir.path().join(chain::DIRECTORY))?;
+    std::fs::write(chain::file_path(dir.path()), "not-a-checksum\n")?;
+    assert!(matches!(
+        File::at_chain(dir.path()),
+        Err(chain::Error::InvalidChecksum { .. })
+    ));
+    Ok(())
+}
@@ -12,6 +12,8 @@ fn multi_index() -> (File, PathBuf) {
 
 mod access;
 
+mod chain;
+
 mod verify;
 
 mod write;
@@ -80,7 +80,7 @@ mod overlay_iter;
 
 ///
 pub mod iter {
-    pub use super::overlay_iter::{LooseThenPacked, Platform};
+    pub use super::overlay_iter::{LooseThenPacked, Matching, Platform};
 
     ///
     pub mod loose_then_packed {
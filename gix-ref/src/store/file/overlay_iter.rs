@@ -9,7 +9,7 @@ use std::{
 use crate::{
     file::{loose, loose::iter::SortedLoosePaths, path_to_name},
     store_impl::{file, packed},
-    BString, FullName, Namespace, Reference,
+    BStr, BString, FullName, Namespace, Reference,
 };
 
 /// An iterator stepping through sorted input of loose references and packed references, preferring loose refs over otherwise
@@ -204,6 +204,97 @@ impl<'s> Platform<'s> {
     }
 }
 
+/// An iterator over references whose names match at least one of a set of patterns, but none of the patterns to exclude.
+///
+/// It's obtained with [`Platform::matching()`].
+pub struct Matching<'p, 's> {
+    inner: LooseThenPacked<'p, 's>,
+    include: Vec<BString>,
+    exclude: Vec<BString>,
+}
+
+impl<'p, 's> Iterator for Matching<'p, 's> {
+    type Item = Result<Reference, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for res in self.inner.by_ref() {
+            match res {
+                Ok(r) => {
+                    let name = r.name.as_bstr();
+                    let is_included =
+                        self.include.is_empty() || self.include.iter().any(|p| pattern_matches(p.as_ref(), name));
+                    if is_included && !self.exclude.iter().any(|p| pattern_matches(p.as_ref(), name)) {
+                        return Some(Ok(r));
+                    }
+                }
+                Err(err) => return Some(Err(err)),
+            }
+        }
+        None
+    }
+}
+
+/// Like `git for-each-ref`, let `pattern` match `name` if it is equal to it, is a prefix of it that ends at a `/`,
+/// or matches as glob where `*` doesn't match `/`.
+fn pattern_matches(pattern: &BStr, name: &BStr) -> bool {
+    if let Some(remainder) = name.strip_prefix(pattern.as_ref() as &[u8]) {
+        if remainder.is_empty() || pattern.ends_with(b"/") || remainder.starts_with(b"/") {
+            return true;
+        }
+    }
+    gix_glob::wildmatch(pattern, name, gix_glob::wildmatch::Mode::NO_MATCH_SLASH_LITERAL)
+}
+
+/// Return the portion of `pattern` up to and including the last `/` before its first glob character,
+/// which is the directory all references that it matches are contained in.
+fn literal_directory_prefix(pattern: &BStr) -> &BStr {
+    let literal = pattern
+        .iter()
+        .position(|b| matches!(b, b'*' | b'?' | b'[' | b'\\'))
+        .map_or(pattern.as_ref() as &[u8], |pos| &pattern[..pos]);
+    literal
+        .iter()
+        .rposition(|b| *b == b'/')
+        .map_or(&[][..], |pos| &literal[..=pos])
+        .into()
+}
+
+impl<'s> Platform<'s> {
+    /// Return an iterator over all references whose names match at least one of the `include` patterns, or all references
+    /// if there are none, while skipping all references matching one of the `exclude` patterns.
+    ///
+    /// Patterns like `refs/heads/feature/*` match like they do in `git for-each-ref`, i.e. a pattern also matches if it is equal to
+    /// the name of a reference or a prefix of it that ends at a `/`, and `*` doesn't match `/`.
+    /// Only the directory shared by the literal portions of all `include` patterns is traversed,
+    /// so `refs/heads/feature/*` won't look at any reference outside of `refs/heads/feature/`.
+    pub fn matching(&self, include: &[&BStr], exclude: &[&BStr]) -> std::io::Result<Matching<'_, '_>> {
+        let mut prefix = include
+            .first()
+            .map_or(&[][..], |p| literal_directory_prefix(p).as_ref());
+        for pattern in include.iter().skip(1) {
+            let other = literal_directory_prefix(pattern);
+            let common = prefix.iter().zip(other.iter()).take_while(|(a, b)| a == b).count();
+            prefix = prefix[..common]
+                .iter()
+                .rposition(|b| *b == b'/')
+                .map_or(&[][..], |pos| &prefix[..=pos]);
+        }
+        let inner = if prefix.is_empty() {
+            self.all()?
+        } else {
+            let prefix = gix_path::try_from_byte_slice(prefix).map_err(|_| {
+                std::io::Error::new(std::io::ErrorKind::InvalidInput, "pattern contains ill-formed UTF-8")
+            })?;
+            self.prefixed(prefix)?
+        };
+        Ok(Matching {
+            inner,
+            include: include.iter().map(|p| (*p).to_owned()).collect(),
+            exclude: exclude.iter().map(|p| (*p).to_owned()).collect(),
+        })
+    }
+}
+
 impl file::Store {
     /// Return a platform to obtain iterator over all references, or prefixed ones, loose or packed, sorted by their name.
     ///
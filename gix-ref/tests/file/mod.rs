@@ -40,6 +40,7 @@ impl gix_object::Find for EmptyCommit {
     ) -> Result<Option<gix_object::Data<'a>>, gix_object::find::Error> {
         Ok(Some(gix_object::Data {
             kind: gix_object::Kind::Commit,
+            object_hash: gix_hash::Kind::Sha1,
             data: &[],
         }))
     }
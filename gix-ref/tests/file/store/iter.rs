@@ -463,3 +463,57 @@ fn overlay_partial_prefix_iter() -> crate::Result {
     assert_eq!(ref_names, vec![(b"refs/heads/main".as_bstr().to_owned(), Peeled(c1)),]);
     Ok(())
 }
+
+#[test]
+fn overlay_matching_iter() -> crate::Result {
+    let store = store_at("make_packed_ref_repository_for_overlay.sh")?;
+    let matching = |include: &[&str], exclude: &[&str]| -> crate::Result<Vec<String>> {
+        let include: Vec<_> = include.iter().map(|p| p.as_bytes().as_bstr()).collect();
+        let exclude: Vec<_> = exclude.iter().map(|p| p.as_bytes().as_bstr()).collect();
+        Ok(store
+            .iter()?
+            .matching(&include, &exclude)?
+            .map(|r| r.map(|r| r.name.as_bstr().to_string()))
+            .collect::<Result<Vec<_>, _>>()?)
+    };
+
+    assert_eq!(
+        matching(&["refs/heads/*"], &[])?,
+        ["refs/heads/main", "refs/heads/newer-as-loose"]
+    );
+    assert_eq!(
+        matching(
+            &["refs/remotes/origin/*", "refs/heads/m*"],
+            &["refs/remotes/origin/HEAD"]
+        )?,
+        ["refs/heads/main", "refs/remotes/origin/main"],
+        "multiple patterns are combined, and the output remains sorted"
+    );
+    assert_eq!(
+        matching(&["refs/remotes"], &[])?,
+        ["refs/remotes/origin/HEAD", "refs/remotes/origin/main"],
+        "literal patterns match leading path components"
+    );
+    assert_eq!(
+        matching(&["refs/heads/main", "refs/heads/ma"], &[])?,
+        ["refs/heads/main"],
+        "…but not partial ones"
+    );
+    assert_eq!(
+        matching(&["refs/*"], &[])?,
+        Vec::<String>::new(),
+        "stars don't match slashes"
+    );
+    assert_eq!(
+        matching(&["refs/*/ma[i]n"], &[])?,
+        ["refs/heads/main"],
+        "globs can be anywhere"
+    );
+    assert_eq!(
+        matching(&[], &["refs/remotes", "refs/tags/*"])?,
+        ["refs/heads/main", "refs/heads/newer-as-loose"],
+        "without include patterns, everything but the exclusions is returned"
+    );
+    assert_eq!(matching(&["refs/heads/does-not-exist/*"], &[])?, Vec::<String>::new());
+    Ok(())
+}
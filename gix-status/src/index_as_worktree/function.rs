@@ -1,6 +1,6 @@
 use std::slice::Chunks;
 use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
-use std::{io, path::Path};
+use std::{io, io::Read, path::Path};
 
 use bstr::BStr;
 use filetime::FileTime;
@@ -516,7 +516,7 @@ where
         //
         let is_symlink = self.entry.mode == gix_index::entry::Mode::SYMLINK;
         // TODO: what to do about precompose unicode and ignore_case for symlinks
-        let out = if is_symlink && self.options.fs.symlink {
+        let out = if is_symlink && (self.options.fs.symlink || self.path.is_symlink()) {
             // conversion to bstr can never fail because symlinks are only used
             // on unix (by git) so no reason to use the try version here
             let symlink_path = gix_path::into_bstr(std::fs::read_link(self.path)?);
@@ -527,6 +527,16 @@ where
                 bytes: None,
                 len: None,
             }
+        } else if is_symlink {
+            // Without symlink support, the file contains the path it links to, and it's never filtered.
+            let mut file = std::fs::File::open(self.path)?;
+            file.read_to_end(self.buf)?;
+            self.worktree_bytes.fetch_add(self.buf.len() as u64, Ordering::Relaxed);
+            Stream {
+                inner: ToGitOutcome::Buffer(self.buf),
+                bytes: None,
+                len: None,
+            }
         } else {
             self.buf.clear();
             let platform = self.attr_stack.at_entry(self.rela_path, Some(false), &self.objects)?;
@@ -157,6 +157,7 @@ pub fn new() -> (
                     verbose,
                     ssl_ca_info,
                     ssl_version,
+                    ssl_verify,
                     http_version,
                     backend,
                 },
@@ -176,6 +177,8 @@ pub fn new() -> (
             if let Some(ca_info) = ssl_ca_info {
                 handle.cainfo(ca_info)?;
             }
+            handle.ssl_verify_peer(ssl_verify)?;
+            handle.ssl_verify_host(ssl_verify)?;
 
             if let Some(ref mut curl_options) = backend.as_ref().and_then(|backend| backend.lock().ok()) {
                 if let Some(opts) = curl_options.downcast_mut::<super::Options>() {
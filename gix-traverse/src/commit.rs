@@ -5,6 +5,7 @@ use smallvec::SmallVec;
 pub struct Ancestors<Find, Predicate, StateMut> {
     objects: Find,
     cache: Option<gix_commitgraph::Graph>,
+    grafts: Grafts,
     predicate: Predicate,
     state: StateMut,
     parents: Parents,
@@ -74,6 +75,12 @@ pub enum Sorting {
 /// Note that this list is truncated if [`Parents::First`] was used.
 pub type ParentIds = SmallVec<[gix_hash::ObjectId; 1]>;
 
+/// A mapping of commits to the parents they are assumed to have during traversal, overriding the parents stored in the
+/// commits themselves.
+///
+/// This is how `info/grafts` is applied, and how commits listed in `.git/shallow` become commits without parents.
+pub type Grafts = gix_hashtable::HashMap<gix_hash::ObjectId, ParentIds>;
+
 /// Information about a commit that we obtained naturally as part of the iteration.
 #[derive(Debug, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
 pub struct Info {
@@ -99,7 +106,7 @@ pub mod ancestors {
     use gix_object::{CommitRefIter, FindExt};
     use smallvec::SmallVec;
 
-    use crate::commit::{collect_parents, Ancestors, Either, Info, ParentIds, Parents, Sorting};
+    use crate::commit::{collect_parents, Ancestors, Either, Grafts, Info, ParentIds, Parents, Sorting};
 
     /// The error is part of the item returned by the [Ancestors] iterator.
     #[derive(Debug, thiserror::Error)]
@@ -135,6 +142,19 @@ pub mod ancestors {
         }
     }
 
+    /// A snapshot of the [`State`] of a traversal in progress, which can be persisted and turned back into a `State`
+    /// to continue the traversal later with [`Ancestors::resume()`].
+    #[derive(Debug, Default, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct Checkpoint {
+        /// The commits that are yet to be returned, in order, if the traversal is by topology.
+        pub next: Vec<ObjectId>,
+        /// The commits that are yet to be returned along with their commit time, if the traversal is by commit time.
+        pub queue: Vec<(SecondsSinceUnixEpoch, ObjectId)>,
+        /// All commits that were encountered so far, including those that are yet to be returned, sorted by id.
+        pub seen: Vec<ObjectId>,
+    }
+
     impl State {
         fn clear(&mut self) {
             self.next.clear();
@@ -142,6 +162,29 @@ pub mod ancestors {
             self.buf.clear();
             self.seen.clear();
         }
+
+        /// Capture the commits that are yet to be returned along with all commits seen so far, to be able to
+        /// [resume](Ancestors::resume()) the traversal later.
+        pub fn checkpoint(&self) -> Checkpoint {
+            let mut seen: Vec<_> = self.seen.iter().copied().collect();
+            seen.sort();
+            Checkpoint {
+                next: self.next.iter().copied().collect(),
+                queue: self.queue.clone().into_iter_unordered().collect(),
+                seen,
+            }
+        }
+    }
+
+    impl From<Checkpoint> for State {
+        fn from(Checkpoint { next, queue, seen }: Checkpoint) -> Self {
+            State {
+                next: next.into(),
+                queue: queue.into_iter().collect(),
+                seen: seen.into_iter().collect(),
+                ..Default::default()
+            }
+        }
     }
 
     /// Builder
@@ -196,6 +239,16 @@ pub mod ancestors {
             self
         }
 
+        /// Use the parents in `grafts` instead of the ones stored in the commits they are listed for.
+        ///
+        /// This is how the walk terminates at the boundary of shallow repositories, and follows the history as it was
+        /// rewritten by `info/grafts`. A commit-graph can still be used as [cache](Self::commit_graph()), as grafted commits
+        /// never use the parents stored in it.
+        pub fn grafts(mut self, grafts: Grafts) -> Self {
+            self.grafts = grafts;
+            self
+        }
+
         fn queue_to_vecdeque(&mut self) {
             let state = self.state.borrow_mut();
             state.next.extend(
@@ -247,6 +300,21 @@ pub mod ancestors {
         /// * `predicate` - indicate whether a given commit should be included in the result as well
         ///   as whether its parent commits should be traversed.
         pub fn filtered(
+            tips: impl IntoIterator<Item = impl Into<ObjectId>>,
+            mut state: StateMut,
+            find: Find,
+            predicate: Predicate,
+        ) -> Self {
+            state.borrow_mut().clear();
+            Self::resume(tips, state, find, predicate)
+        }
+
+        /// Create a new instance like [`filtered()`](Self::filtered()), but continue the traversal with the commits that are
+        /// yet to be returned from `state` instead of resetting it, typically after creating it from a [`Checkpoint`].
+        ///
+        /// `tips` are added to the commits to traverse unless they were seen already, and the [sorting](Self::sorting())
+        /// and [parents mode](Self::parents()) should be the same as the ones of the traversal that produced `state`.
+        pub fn resume(
             tips: impl IntoIterator<Item = impl Into<ObjectId>>,
             mut state: StateMut,
             find: Find,
@@ -255,7 +323,6 @@ pub mod ancestors {
             let tips = tips.into_iter();
             {
                 let state = state.borrow_mut();
-                state.clear();
                 state.next.reserve(tips.size_hint().0);
                 for tip in tips.map(Into::into) {
                     let was_inserted = state.seen.insert(tip);
@@ -267,6 +334,7 @@ pub mod ancestors {
             Self {
                 objects: find,
                 cache: None,
+                grafts: Default::default(),
                 predicate,
                 state,
                 parents: Default::default(),
@@ -288,6 +356,11 @@ pub mod ancestors {
         pub fn commit_data(&self) -> &[u8] {
             &self.state.borrow().buf
         }
+
+        /// Return the state of the traversal, which can be used to create a [`Checkpoint`].
+        pub fn state(&self) -> &State {
+            self.state.borrow()
+        }
     }
 
     impl<Find, Predicate, StateMut> Iterator for Ancestors<Find, Predicate, StateMut>
@@ -338,6 +411,30 @@ pub mod ancestors {
 
             let (commit_time, oid) = state.queue.pop()?;
             let mut parents: ParentIds = Default::default();
+            if let Some(graft_parents) = self.grafts.get(&oid) {
+                if let Err(err) = super::find(self.cache.as_ref(), &self.objects, &oid, &mut state.buf) {
+                    return Some(Err(err.into()));
+                }
+                for id in graft_parents.iter().copied() {
+                    parents.push(id);
+                    let was_inserted = state.seen.insert(id);
+                    if !(was_inserted && (self.predicate)(&id)) {
+                        continue;
+                    }
+
+                    let parent_commit_time =
+                        super::commit_time(self.cache.as_ref(), &self.objects, &id, &mut state.parents_buf);
+                    match cutoff_older_than {
+                        Some(cutoff_older_than) if parent_commit_time < cutoff_older_than => continue,
+                        Some(_) | None => state.queue.insert(parent_commit_time, id),
+                    }
+                }
+                return Some(Ok(Info {
+                    id: oid,
+                    parent_ids: parents,
+                    commit_time: Some(commit_time),
+                }));
+            }
             match super::find(self.cache.as_ref(), &self.objects, &oid, &mut state.buf) {
                 Ok(Either::CachedCommit(commit)) => {
                     if !collect_parents(&mut state.parent_ids, self.cache.as_ref(), commit.iter_parents()) {
@@ -405,6 +502,26 @@ pub mod ancestors {
             let state = self.state.borrow_mut();
             let oid = state.next.pop_front()?;
             let mut parents: ParentIds = Default::default();
+            if let Some(graft_parents) = self.grafts.get(&oid) {
+                if let Err(err) = super::find(self.cache.as_ref(), &self.objects, &oid, &mut state.buf) {
+                    return Some(Err(err.into()));
+                }
+                for id in graft_parents.iter().copied() {
+                    parents.push(id);
+                    let was_inserted = state.seen.insert(id);
+                    if was_inserted && (self.predicate)(&id) {
+                        state.next.push_back(id);
+                    }
+                    if matches!(self.parents, Parents::First) {
+                        break;
+                    }
+                }
+                return Some(Ok(Info {
+                    id: oid,
+                    parent_ids: parents,
+                    commit_time: None,
+                }));
+            }
             match super::find(self.cache.as_ref(), &self.objects, &oid, &mut state.buf) {
                 Ok(Either::CachedCommit(commit)) => {
                     if !collect_parents(&mut state.parent_ids, self.cache.as_ref(), commit.iter_parents()) {
@@ -481,6 +598,26 @@ fn collect_parents(
     true
 }
 
+/// Obtain the commit time of the commit with `id`, or `0` if it couldn't be found.
+fn commit_time<Find>(
+    cache: Option<&gix_commitgraph::Graph>,
+    objects: Find,
+    id: &gix_hash::oid,
+    buf: &mut Vec<u8>,
+) -> gix_date::SecondsSinceUnixEpoch
+where
+    Find: gix_object::Find,
+{
+    match find(cache, objects, id, buf) {
+        Ok(Either::CachedCommit(commit)) => commit.committer_timestamp() as gix_date::SecondsSinceUnixEpoch,
+        Ok(Either::CommitRefIter(commit)) => commit
+            .committer()
+            .map(|committer| committer.time.seconds)
+            .unwrap_or_default(),
+        Err(_) => Default::default(),
+    }
+}
+
 fn find<'cache, 'buf, Find>(
     cache: Option<&'cache gix_commitgraph::Graph>,
     objects: Find,
@@ -92,6 +92,35 @@ mod ancestor {
                     .map(|res| res.map(|info| info.id))
                     .collect::<Result<Vec<_>, _>>()?;
                 assert_eq!(oids, expected);
+
+                for split in 0..=expected.len() {
+                    let mut traversal =
+                        commit::Ancestors::new(tips.clone(), commit::ancestors::State::default(), &store)
+                            .sorting(self.sorting)?
+                            .parents(self.mode)
+                            .commit_graph(self.setup_commitgraph(store.store_ref(), use_commitgraph));
+                    let mut oids = traversal
+                        .by_ref()
+                        .take(split)
+                        .map(|res| res.map(|info| info.id))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    let checkpoint = traversal.state().checkpoint();
+                    drop(traversal);
+
+                    let resumed = commit::Ancestors::resume(
+                        tips.clone(),
+                        commit::ancestors::State::from(checkpoint),
+                        &store,
+                        |_: &oid| true,
+                    )
+                    .sorting(self.sorting)?
+                    .parents(self.mode)
+                    .commit_graph(self.setup_commitgraph(store.store_ref(), use_commitgraph));
+                    for res in resumed {
+                        oids.push(res?.id);
+                    }
+                    assert_eq!(oids, expected, "resuming after {split} commits yields the same result");
+                }
             }
             Ok(())
         }
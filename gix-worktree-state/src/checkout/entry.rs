@@ -127,7 +127,7 @@ where
                 ToWorktreeOutcome::Process(MaybeDelayed::Delayed(key)) => {
                     return Ok(Outcome::Delayed(DelayedFilteredStream {
                         key,
-                        needs_executable_bit: false,
+                        needs_executable_bit: executable_bit && entry.mode == gix_index::entry::Mode::FILE_EXECUTABLE,
                         validated_file_path: dest.to_owned(),
                         entry,
                         entry_path,
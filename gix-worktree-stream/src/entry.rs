@@ -26,6 +26,11 @@ pub enum Error {
     Traverse(#[from] gix_traverse::tree::breadthfirst::Error),
     #[error(transparent)]
     ConvertToWorktree(#[from] gix_filter::pipeline::convert::to_worktree::Error),
+    #[error("Could not substitute placeholders in \"{path}\"")]
+    Substitute {
+        path: BString,
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
 }
 
 impl Stream {
@@ -1,6 +1,6 @@
 use std::io::Write;
 
-use gix_object::bstr::BStr;
+use gix_object::bstr::{BStr, BString};
 use gix_object::FindExt;
 
 use crate::{entry, entry::Error, protocol, AdditionalEntry, SharedErrorSlot, Stream};
@@ -32,7 +32,7 @@ use crate::{entry, entry::Error, protocol, AdditionalEntry, SharedErrorSlot, Str
 ///
 /// ### Limitations
 ///
-/// * `export-subst` is not support, as it requires the entire formatting engine of `git log`.
+/// * `export-subst` is ignored, use [`from_tree_with_substitution()`] to support it.
 pub fn from_tree<Find, E>(
     tree: gix_hash::ObjectId,
     objects: Find,
@@ -41,6 +41,56 @@ pub fn from_tree<Find, E>(
         + Send
         + 'static,
 ) -> Stream
+where
+    Find: gix_object::Find + Clone + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    from_tree_inner(tree, objects, pipeline, attributes, None)
+}
+
+/// Like [`from_tree()`], but also honor the `export-subst` attribute by calling `substitute(format, out)` for each
+/// `$Format:<format>$` placeholder in the worktree representation of blobs it is set for, to append the expansion
+/// of `format` to `out` in place of the placeholder.
+///
+/// Just like `git archive`, `format` is typically expanded like `git log --format=<format>` would for the commit
+/// whose tree is streamed.
+pub fn from_tree_with_substitution<Find, E, SE>(
+    tree: gix_hash::ObjectId,
+    objects: Find,
+    pipeline: gix_filter::Pipeline,
+    attributes: impl FnMut(&BStr, gix_object::tree::EntryMode, &mut gix_attributes::search::Outcome) -> Result<(), E>
+        + Send
+        + 'static,
+    mut substitute: impl FnMut(&BStr, &mut BString) -> Result<(), SE> + Send + 'static,
+) -> Stream
+where
+    Find: gix_object::Find + Clone + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+    SE: std::error::Error + Send + Sync + 'static,
+{
+    from_tree_inner(
+        tree,
+        objects,
+        pipeline,
+        attributes,
+        Some(Box::new(move |format, out| {
+            substitute(format, out).map_err(|err| Box::new(err) as Box<dyn std::error::Error + Send + Sync>)
+        })),
+    )
+}
+
+pub(crate) type Substitute =
+    Box<dyn FnMut(&BStr, &mut BString) -> Result<(), Box<dyn std::error::Error + Send + Sync>> + Send>;
+
+fn from_tree_inner<Find, E>(
+    tree: gix_hash::ObjectId,
+    objects: Find,
+    pipeline: gix_filter::Pipeline,
+    attributes: impl FnMut(&BStr, gix_object::tree::EntryMode, &mut gix_attributes::search::Outcome) -> Result<(), E>
+        + Send
+        + 'static,
+    substitute: Option<Substitute>,
+) -> Stream
 where
     Find: gix_object::Find + Clone + Send + 'static,
     E: std::error::Error + Send + Sync + 'static,
@@ -54,6 +104,7 @@ where
                 objects,
                 pipeline,
                 attributes,
+                substitute,
                 &mut write,
                 slot.clone(),
                 additional_entries,
@@ -76,6 +127,7 @@ where
     stream
 }
 
+#[allow(clippy::too_many_arguments)]
 fn run<Find, E>(
     tree: gix_hash::ObjectId,
     objects: Find,
@@ -83,6 +135,7 @@ fn run<Find, E>(
     mut attributes: impl FnMut(&BStr, gix_object::tree::EntryMode, &mut gix_attributes::search::Outcome) -> Result<(), E>
         + Send
         + 'static,
+    substitute: Option<Substitute>,
     out: &mut gix_features::io::pipe::Writer,
     err: SharedErrorSlot,
     additional_entries: std::sync::mpsc::Receiver<AdditionalEntry>,
@@ -98,7 +151,7 @@ where
     }
 
     let mut attrs = gix_attributes::search::Outcome::default();
-    attrs.initialize_with_selection(&Default::default(), Some("export-ignore"));
+    attrs.initialize_with_selection(&Default::default(), ["export-ignore", "export-subst"]);
     let mut dlg = traverse::Delegate {
         out,
         err,
@@ -111,6 +164,7 @@ where
                 path: a.to_owned(),
             })
         },
+        substitute,
         path_deque: Default::default(),
         path: Default::default(),
         buf: Vec::with_capacity(1024),
@@ -1,4 +1,7 @@
-use std::{collections::VecDeque, io::Write};
+use std::{
+    collections::VecDeque,
+    io::{Read, Write},
+};
 
 use gix_filter::{driver::apply::MaybeDelayed, pipeline::convert::ToWorktreeOutcome};
 use gix_object::{
@@ -20,6 +23,7 @@ where
     pub(crate) pipeline: gix_filter::Pipeline,
     pub(crate) attrs: gix_attributes::search::Outcome,
     pub(crate) fetch_attributes: AttributesFn,
+    pub(crate) substitute: Option<super::Substitute>,
     pub(crate) objects: Find,
     pub(crate) buf: Vec<u8>,
 }
@@ -44,14 +48,15 @@ where
         }
         self.path.push_str(name);
     }
-    /// Return the state of the `export-ignore` attribute.
-    fn ignore_state(&self) -> gix_attributes::StateRef<'_> {
+    /// Return `true` if the attribute `name`, which must be one of the selected ones, is set.
+    fn is_set(&self, name: &str) -> bool {
         self.attrs
             .iter_selected()
-            .next()
-            .expect("initialized with one attr")
+            .find(|m| m.assignment.name.as_str() == name)
+            .expect("initialized with all attributes we query")
             .assignment
             .state
+            .is_set()
     }
 
     fn handle_entry(&mut self, entry: &tree::EntryRef<'_>) -> Result<Action, Error> {
@@ -59,9 +64,10 @@ where
             return Ok(Action::Continue);
         }
         (self.fetch_attributes)(self.path.as_ref(), entry.mode, &mut self.attrs)?;
-        if self.ignore_state().is_set() {
+        if self.is_set("export-ignore") {
             return Ok(Action::Continue);
         }
+        let substitute = self.substitute.is_some() && self.is_set("export-subst");
         self.objects.find(entry.oid, &mut self.buf)?;
 
         self.pipeline.driver_context_mut().blob = Some(entry.oid.into());
@@ -74,6 +80,35 @@ where
             gix_filter::driver::apply::Delay::Forbid,
         )?;
 
+        if substitute {
+            let mut data = Vec::new();
+            match converted {
+                ToWorktreeOutcome::Unchanged(buf) | ToWorktreeOutcome::Buffer(buf) => data.extend_from_slice(buf),
+                ToWorktreeOutcome::Process(MaybeDelayed::Immediate(mut read)) => {
+                    read.read_to_end(&mut data)?;
+                }
+                ToWorktreeOutcome::Process(MaybeDelayed::Delayed(_)) => {
+                    unreachable!("we forbade it")
+                }
+            }
+            let substitute = self.substitute.as_mut().expect("checked before");
+            let data = substitute_placeholders(&data, substitute).map_err(|source| Error::Substitute {
+                path: self.path.clone(),
+                source,
+            })?;
+            protocol::write_entry_header_and_path(
+                self.path.as_ref(),
+                entry.oid,
+                entry.mode,
+                Some(data.len()),
+                self.out,
+            )?;
+            // Our pipe writer always writes the whole amount.
+            #[allow(clippy::unused_io_amount)]
+            self.out.write(&data)?;
+            return Ok(Action::Continue);
+        }
+
         // Our pipe writer always writes the whole amount.
         #[allow(clippy::unused_io_amount)]
         match converted {
@@ -99,6 +134,28 @@ where
     }
 }
 
+/// Replace each `$Format:<format>$` placeholder in `input` with what `substitute` produces for `format`, just like `git archive`.
+fn substitute_placeholders(
+    input: &[u8],
+    substitute: &mut super::Substitute,
+) -> Result<BString, Box<dyn std::error::Error + Send + Sync>> {
+    const START: &[u8] = b"$Format:";
+    let mut out = BString::default();
+    let mut rest = input;
+    while let Some(start) = rest.find(START) {
+        let format_start = start + START.len();
+        let end = match rest[format_start..].find_byte(b'$') {
+            Some(end) => format_start + end,
+            None => break,
+        };
+        out.extend_from_slice(&rest[..start]);
+        substitute(rest[format_start..end].as_bstr(), &mut out)?;
+        rest = &rest[end + 1..];
+    }
+    out.extend_from_slice(rest);
+    Ok(out)
+}
+
 impl<AttributesFn, Find> Visit for Delegate<'_, AttributesFn, Find>
 where
     Find: gix_object::Find,
@@ -129,7 +186,7 @@ where
         if let Err(err) = (self.fetch_attributes)(self.path.as_ref(), entry.mode, &mut self.attrs) {
             *self.err.lock() = Some(err);
             Action::Cancel
-        } else if self.ignore_state().is_set() {
+        } else if self.is_set("export-ignore") {
             Action::Skip
         } else {
             Action::Continue
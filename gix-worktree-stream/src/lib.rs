@@ -30,7 +30,7 @@ pub mod entry;
 pub(crate) mod protocol;
 
 mod from_tree;
-pub use from_tree::from_tree;
+pub use from_tree::{from_tree, from_tree_with_substitution};
 
 pub(crate) type SharedErrorSlot = Arc<parking_lot::Mutex<Option<entry::Error>>>;
 
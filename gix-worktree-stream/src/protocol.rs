@@ -91,6 +91,7 @@ pub(crate) fn write_stream(
 fn byte_to_hash(b: u8) -> gix_hash::Kind {
     match b {
         0 => gix_hash::Kind::Sha1,
+        1 => gix_hash::Kind::Sha256,
         _ => unreachable!("BUG: we control the protocol"),
     }
 }
@@ -110,6 +111,7 @@ fn byte_to_mode(b: u8) -> gix_object::tree::EntryMode {
 fn hash_to_byte(h: gix_hash::Kind) -> u8 {
     match h {
         gix_hash::Kind::Sha1 => 0,
+        gix_hash::Kind::Sha256 => 1,
     }
 }
 
@@ -0,0 +1,13 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init
+
+echo 'version: $Format:%H$, date: $Format:%cs$, unterminated: $Format:%H' > substituted
+echo 'version: $Format:%H$' > unchanged
+echo "/substituted export-subst" > .gitattributes
+
+git add .
+git commit -m "init"
+
+git rev-parse @^{tree} > head.hex
@@ -59,6 +59,74 @@ mod from_tree {
         Ok(())
     }
 
+    #[test]
+    fn export_subst_placeholders_are_substituted_if_requested() -> gix_testtools::Result {
+        let contents = |substitute: bool| -> gix_testtools::Result<Vec<(String, String)>> {
+            let (_dir, head_tree, odb, mut cache) = fixture("export_subst.sh")?;
+            let attributes = {
+                let odb = odb.clone();
+                move |rela_path: &gix_object::bstr::BStr, mode: EntryMode, attrs: &mut _| {
+                    cache
+                        .at_entry(rela_path, mode.is_tree().into(), &odb)
+                        .map(|entry| entry.matching_attributes(attrs))
+                        .map(|_| ())
+                }
+            };
+            let mut stream = if substitute {
+                gix_worktree_stream::from_tree_with_substitution(
+                    head_tree,
+                    odb,
+                    gix_filter::Pipeline::new(&Default::default(), Default::default()),
+                    attributes,
+                    |format, out| -> Result<_, Infallible> {
+                        out.extend_from_slice(b"<");
+                        out.extend_from_slice(format);
+                        out.extend_from_slice(b">");
+                        Ok(())
+                    },
+                )
+            } else {
+                gix_worktree_stream::from_tree(
+                    head_tree,
+                    odb,
+                    gix_filter::Pipeline::new(&Default::default(), Default::default()),
+                    attributes,
+                )
+            };
+            let mut out = Vec::new();
+            while let Some(mut entry) = stream.next_entry()? {
+                let mut buf = String::new();
+                entry.read_to_string(&mut buf)?;
+                assert_eq!(
+                    entry.bytes_remaining(),
+                    Some(0),
+                    "the size of the entry matches its substituted content"
+                );
+                out.push((entry.relative_path().to_string(), buf));
+            }
+            Ok(out)
+        };
+
+        let expected = |substituted: &str| {
+            vec![
+                (".gitattributes".to_string(), "/substituted export-subst\n".to_string()),
+                ("substituted".into(), substituted.into()),
+                ("unchanged".into(), "version: $Format:%H$\n".into()),
+            ]
+        };
+        assert_eq!(
+            contents(true)?,
+            expected("version: <%H>, date: <%cs>, unterminated: $Format:%H\n"),
+            "only files with the attribute are affected, and unterminated placeholders remain"
+        );
+        assert_eq!(
+            contents(false)?,
+            expected("version: $Format:%H$, date: $Format:%cs$, unterminated: $Format:%H\n"),
+            "without substitution, `export-subst` has no effect"
+        );
+        Ok(())
+    }
+
     #[test]
     fn will_provide_all_information_and_respect_export_ignore() -> gix_testtools::Result {
         let (dir, head_tree, odb, mut cache) = basic()?;
@@ -237,7 +305,13 @@ mod from_tree {
     }
 
     fn basic() -> gix_testtools::Result<(PathBuf, gix_hash::ObjectId, gix_odb::HandleArc, gix_worktree::Stack)> {
-        let dir = gix_testtools::scripted_fixture_read_only("basic.sh")?;
+        fixture("basic.sh")
+    }
+
+    fn fixture(
+        script_name: &str,
+    ) -> gix_testtools::Result<(PathBuf, gix_hash::ObjectId, gix_odb::HandleArc, gix_worktree::Stack)> {
+        let dir = gix_testtools::scripted_fixture_read_only(script_name)?;
 
         let head = {
             let hex = std::fs::read(dir.join("head.hex"))?;
@@ -0,0 +1,152 @@
+//! Look up many objects by id or revision in a row, similar to `git cat-file --batch` and `git cat-file --batch-check`.
+//!
+//! Use [`Repository::cat_file()`](crate::Repository::cat_file()) to obtain a [`Platform`] for lookups. It keeps a buffer
+//! for the object data which is reused for each lookup, so large amounts of objects can be streamed without allocating
+//! for each of them.
+use std::io::Write;
+
+use gix_hash::ObjectId;
+use gix_object::Find;
+
+use crate::bstr::BStr;
+
+/// What to output for each object in [`Platform::write_batch()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// Output only the header line with id, kind and size of each object, like `git cat-file --batch-check`.
+    Header,
+    /// Output the header line followed by the data of each object, like `git cat-file --batch`.
+    Contents,
+}
+
+/// Information about an object, as returned by [`Platform::header()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Header {
+    /// The id of the object.
+    pub id: ObjectId,
+    /// The kind of the object.
+    pub kind: gix_object::Kind,
+    /// The size of the object's data in bytes.
+    pub size: u64,
+}
+
+/// An object along with its data, as returned by [`Platform::contents()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Contents<'a> {
+    /// The id of the object.
+    pub id: ObjectId,
+    /// The kind of the object.
+    pub kind: gix_object::Kind,
+    /// The data of the object, which is borrowed from the [`Platform`] and valid until the next lookup.
+    pub data: &'a [u8],
+}
+
+/// The error returned by the methods of [`Platform`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Find(#[from] crate::object::find::Error),
+    #[error("Could not write the output of the batch")]
+    Io(#[from] std::io::Error),
+}
+
+/// A utility to look up objects one after another, obtained with [`Repository::cat_file()`](crate::Repository::cat_file()).
+///
+/// Each input is either the full hexadecimal id of an object, which is used as is, or a revision specification like
+/// `HEAD:path` or an abbreviated id, which is resolved [like `git` does](crate::Repository::rev_parse_single()).
+/// Inputs that can't be resolved, or that resolve to objects which don't exist, are reported as missing instead of
+/// failing the operation.
+pub struct Platform<'repo> {
+    pub(crate) repo: &'repo crate::Repository,
+    pub(crate) buf: Vec<u8>,
+}
+
+/// Lookups
+impl<'repo> Platform<'repo> {
+    /// Return the id, kind and size of the object that `input` refers to, or `None` if it's missing.
+    ///
+    /// Only the header of objects is decoded, which is much faster than obtaining the [contents](Self::contents()).
+    pub fn header<'a>(&mut self, input: impl Into<&'a BStr>) -> Result<Option<Header>, Error> {
+        let id = match self.resolve(input.into()) {
+            Some(id) => id,
+            None => return Ok(None),
+        };
+        Ok(self.repo.try_find_header(id)?.map(|header| Header {
+            id,
+            kind: header.kind(),
+            size: header.size(),
+        }))
+    }
+
+    /// Return the object that `input` refers to along with its data, or `None` if it's missing.
+    pub fn contents<'a>(&mut self, input: impl Into<&'a BStr>) -> Result<Option<Contents<'_>>, Error> {
+        let id = match self.resolve(input.into()) {
+            Some(id) => id,
+            None => return Ok(None),
+        };
+        if id == ObjectId::empty_tree(self.repo.object_hash()) {
+            return Ok(Some(Contents {
+                id,
+                kind: gix_object::Kind::Tree,
+                data: &[],
+            }));
+        }
+        Ok(self
+            .repo
+            .objects
+            .try_find(&id, &mut self.buf)
+            .map_err(crate::object::find::Error)?
+            .map(|data| Contents {
+                id,
+                kind: data.kind,
+                data: data.data,
+            }))
+    }
+
+    /// Look up each of `inputs` in order and write the result to `out` in the format of `git cat-file --batch` or
+    /// `git cat-file --batch-check`, depending on `mode`.
+    ///
+    /// Each object is written as `<id> SP <kind> SP <size> LF`, followed by its data and `LF` if `mode` is [`Mode::Contents`].
+    /// Missing objects are written as `<input> SP missing LF`.
+    ///
+    /// Note that `out` should be buffered, and that it's not flushed.
+    pub fn write_batch<'a>(
+        &mut self,
+        inputs: impl IntoIterator<Item = &'a BStr>,
+        mode: Mode,
+        out: &mut dyn Write,
+    ) -> Result<(), Error> {
+        for input in inputs {
+            match mode {
+                Mode::Header => match self.header(input)? {
+                    Some(header) => writeln!(out, "{} {} {}", header.id, header.kind, header.size)?,
+                    None => missing(input, out)?,
+                },
+                Mode::Contents => match self.contents(input)? {
+                    Some(contents) => {
+                        writeln!(out, "{} {} {}", contents.id, contents.kind, contents.data.len())?;
+                        out.write_all(contents.data)?;
+                        out.write_all(b"\n")?;
+                    }
+                    None => missing(input, out)?,
+                },
+            }
+        }
+        Ok(())
+    }
+
+    fn resolve(&self, input: &BStr) -> Option<ObjectId> {
+        if input.len() == self.repo.object_hash().len_in_hex() {
+            if let Ok(id) = ObjectId::from_hex(input) {
+                return Some(id);
+            }
+        }
+        self.repo.rev_parse_single(input).ok().map(crate::Id::detach)
+    }
+}
+
+fn missing(input: &BStr, out: &mut dyn Write) -> std::io::Result<()> {
+    out.write_all(input)?;
+    out.write_all(b" missing\n")
+}
@@ -0,0 +1,83 @@
+//! Look up the attributes of paths, similar to `git check-attr`.
+//!
+//! Use [`Repository::check_attr()`](crate::Repository::check_attr()) to obtain a [`Platform`] for queries.
+use crate::{bstr::BStr, AttributeStack};
+
+/// Where to read `.gitattributes` files from, as used in [`Repository::check_attr()`](crate::Repository::check_attr()).
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Source {
+    /// Read `.gitattributes` files from the worktree, or from the index if they don't exist there.
+    ///
+    /// In bare repositories, only the index is used.
+    #[default]
+    WorktreeThenIndex,
+    /// Read `.gitattributes` files only from the index, similar to `git check-attr --cached`.
+    Index,
+    /// Read `.gitattributes` files only from the given tree, similar to `git check-attr --source <tree-ish>`.
+    Tree(gix_hash::ObjectId),
+}
+
+/// A utility to look up attributes of paths, obtained with [`Repository::check_attr()`](crate::Repository::check_attr()).
+///
+/// Attributes are resolved like `git` does, with later sources overriding earlier ones:
+///
+/// * the file configured in `core.attributesFile`, or `$XDG_CONFIG_HOME/git/attributes` if unset.
+/// * `.gitattributes` files in the root of the repository and all directories leading up to the path, the closest one last.
+/// * `$GIT_DIR/info/attributes`
+///
+/// Macros like `binary` are expanded into the attributes they set.
+pub struct Platform<'repo> {
+    pub(crate) stack: AttributeStack<'repo>,
+    pub(crate) all: gix_attributes::search::Outcome,
+}
+
+/// Queries
+impl<'repo> Platform<'repo> {
+    /// Return all attributes of `rela_path` that are set, unset or have a value, similar to `git check-attr --all`.
+    ///
+    /// `rela_path` is relative to the root of the worktree, using slashes as separators. `is_dir` should be set to
+    /// `Some(true)` for directories, and if `None`, `rela_path` is assumed to be a directory if it ends with a slash.
+    pub fn all<'a>(
+        &mut self,
+        rela_path: impl Into<&'a BStr>,
+        is_dir: Option<bool>,
+    ) -> std::io::Result<Vec<gix_attributes::Assignment>> {
+        let platform = self.stack.at_entry(rela_path, is_dir)?;
+        self.all.reset();
+        platform.matching_attributes(&mut self.all);
+        Ok(self
+            .all
+            .iter()
+            .filter(|m| m.assignment.state != gix_attributes::StateRef::Unspecified)
+            .map(|m| m.assignment.to_owned())
+            .collect())
+    }
+
+    /// Return the state of each attribute in `names` for `rela_path`, in the order of `names`, similar to
+    /// `git check-attr <names> -- <path>`. Attributes that don't apply are returned as [unspecified](gix_attributes::State::Unspecified).
+    ///
+    /// See [`all()`](Self::all()) for the meaning of `rela_path` and `is_dir`.
+    pub fn selected<'a, 'b>(
+        &mut self,
+        rela_path: impl Into<&'a BStr>,
+        is_dir: Option<bool>,
+        names: impl IntoIterator<Item = &'b str>,
+    ) -> std::io::Result<Vec<gix_attributes::Assignment>> {
+        let mut outcome = self.stack.selected_attribute_matches(names);
+        let platform = self.stack.at_entry(rela_path, is_dir)?;
+        platform.matching_attributes(&mut outcome);
+        Ok(outcome.iter_selected().map(|m| m.assignment.to_owned()).collect())
+    }
+}
+
+/// The error returned by [`Repository::check_attr()`](crate::Repository::check_attr()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    IndexFromTree(#[from] gix_traverse::tree::breadthfirst::Error),
+    #[error(transparent)]
+    AttributeStack(#[from] crate::config::attribute_stack::Error),
+}
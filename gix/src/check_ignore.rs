@@ -0,0 +1,88 @@
+//! Learn whether paths are ignored and which pattern caused it, similar to `git check-ignore`.
+//!
+//! Use [`Repository::check_ignore()`](crate::Repository::check_ignore()) to obtain a [`Platform`] for queries.
+use std::path::PathBuf;
+
+use crate::{bstr::BStr, AttributeStack};
+
+/// A pattern that matched a path along with the place it was defined at, as returned by [`Platform::matching_pattern()`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Match {
+    /// The pattern that matched, which excludes the path unless it is negative, like `!keep.log`.
+    pub pattern: gix_glob::Pattern,
+    /// The file the pattern was read from, like `.gitignore`, `.git/info/exclude` or the file configured in `core.excludesFile`,
+    /// or `None` if the pattern was provided programmatically.
+    pub source: Option<PathBuf>,
+    /// The line at which the pattern was found in its `source` file, starting at 1, or the position at which it was provided.
+    pub line_number: usize,
+}
+
+impl Match {
+    /// Return `true` if the path that matched is excluded, i.e. the pattern isn't negative.
+    pub fn is_excluded(&self) -> bool {
+        !self.pattern.is_negative()
+    }
+}
+
+/// A utility to check paths for being ignored, obtained with [`Repository::check_ignore()`](crate::Repository::check_ignore()).
+///
+/// Patterns are looked up in the following order, with the first matching pattern deciding the outcome:
+///
+/// * `.gitignore` files in the directory of the path and all of its parent directories, the closest one first.
+/// * `$GIT_DIR/info/exclude`
+/// * the file configured in `core.excludesFile`, or `$XDG_CONFIG_HOME/git/ignore` if unset.
+///
+/// `.gitignore` files are read from the worktree, or from the index if they don't exist there and aren't marked
+/// with `skip-worktree`.
+pub struct Platform<'repo> {
+    pub(crate) stack: AttributeStack<'repo>,
+    pub(crate) index: crate::worktree::Index,
+}
+
+/// Queries
+impl<'repo> Platform<'repo> {
+    /// Return the pattern that decides whether `rela_path` is ignored, or `None` if no pattern matches it.
+    ///
+    /// `rela_path` is relative to the root of the worktree, using slashes as separators. `is_dir` should be set to
+    /// `Some(true)` for directories, as patterns like `build/` only match these. If `None`, `rela_path` is assumed to be a
+    /// directory if it ends with a slash.
+    ///
+    /// Note that the returned pattern may be [negative](Match::is_excluded()), which means the path is explicitly included.
+    /// Tracked files are matched as well, similar to `git check-ignore --no-index`.
+    pub fn matching_pattern<'a>(
+        &mut self,
+        rela_path: impl Into<&'a BStr>,
+        is_dir: Option<bool>,
+    ) -> std::io::Result<Option<Match>> {
+        let platform = self.stack.at_entry(rela_path, is_dir)?;
+        Ok(platform.matching_exclude_pattern().map(|m| Match {
+            pattern: m.pattern.clone(),
+            source: m.source.map(ToOwned::to_owned),
+            line_number: m.sequence_number,
+        }))
+    }
+
+    /// Return `true` if `rela_path` is ignored, with the same arguments as [`matching_pattern()`](Self::matching_pattern()).
+    ///
+    /// Like `git check-ignore`, tracked files are never considered ignored, even if a pattern matches them.
+    pub fn is_ignored<'a>(&mut self, rela_path: impl Into<&'a BStr>, is_dir: Option<bool>) -> std::io::Result<bool> {
+        let rela_path = rela_path.into();
+        let tracked_path = rela_path.strip_suffix(b"/").unwrap_or(rela_path);
+        if self.index.entry_range(tracked_path.into()).is_some() {
+            return Ok(false);
+        }
+        Ok(self
+            .matching_pattern(rela_path, is_dir)?
+            .map_or(false, |m| m.is_excluded()))
+    }
+}
+
+/// The error returned by [`Repository::check_ignore()`](crate::Repository::check_ignore()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    ExcludeStack(#[from] crate::config::exclude_stack::Error),
+}
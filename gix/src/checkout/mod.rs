@@ -0,0 +1,276 @@
+//! Learn what it takes to change the worktree and the index from the tree of `HEAD` to another tree, similar to what
+//! `git checkout <tree-ish>` would do.
+//!
+//! Use [`Repository::checkout_plan()`](crate::Repository::checkout_plan()) to obtain a [`Plan`] of the filesystem operations
+//! that would be performed, along with all [conflicts](Conflict) that prevent the checkout as they would overwrite
+//! local changes or untracked files.
+//! [`Repository::checkout_tree()`](crate::Repository::checkout_tree()) performs such a plan, and
+//! [`Repository::switch()`](crate::Repository::switch()) also moves `HEAD` to switch branches, like `git switch <branch>`.
+use crate::bstr::BString;
+
+/// A single filesystem operation to perform as part of a checkout, with paths relative to the root of the worktree.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Operation {
+    /// Create a file, symlink or submodule at `rela_path` where there is nothing yet.
+    Create {
+        /// The path to create.
+        rela_path: BString,
+        /// The object to write to `rela_path`, or the commit of the submodule.
+        id: gix_hash::ObjectId,
+        /// The kind of entry to create.
+        mode: gix_object::tree::EntryMode,
+    },
+    /// Replace what's currently at `rela_path`, which is either a tracked entry without local changes,
+    /// or an ignored file that will be lost.
+    Overwrite {
+        /// The path to overwrite.
+        rela_path: BString,
+        /// The object to write to `rela_path`, or the commit of the submodule.
+        id: gix_hash::ObjectId,
+        /// The kind of entry to write.
+        mode: gix_object::tree::EntryMode,
+    },
+    /// Delete the tracked entry at `rela_path`, along with directories that are empty afterwards.
+    Delete {
+        /// The path to delete.
+        rela_path: BString,
+    },
+}
+
+impl Operation {
+    /// Return the path this operation affects, relative to the root of the worktree.
+    pub fn rela_path(&self) -> &BString {
+        match self {
+            Operation::Create { rela_path, .. }
+            | Operation::Overwrite { rela_path, .. }
+            | Operation::Delete { rela_path } => rela_path,
+        }
+    }
+}
+
+/// The reason for a [`Conflict`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ConflictReason {
+    /// The index has unresolved merge conflicts at the path, which have to be resolved first.
+    Unmerged,
+    /// The path has staged changes that differ from both `HEAD` and the target tree.
+    StagedChanges,
+    /// The path has changes in the worktree that would be overwritten or deleted.
+    WorktreeChanges,
+    /// An untracked file would be overwritten.
+    UntrackedOverwritten,
+    /// A directory with untracked files would have to be removed to make room for a file.
+    UntrackedRemoved,
+    /// The path only differs in case from another path that would be checked out as well, which makes both use the same file
+    /// if the filesystem ignores case as configured by `core.ignoreCase`.
+    CaseCollision,
+}
+
+/// A path that prevents the checkout, as local changes or untracked files would be lost.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Conflict {
+    /// The path relative to the root of the worktree.
+    pub rela_path: BString,
+    /// The reason for the conflict.
+    pub reason: ConflictReason,
+}
+
+/// The plan of filesystem operations needed to check out a tree, as returned by
+/// [`Repository::checkout_plan()`](crate::Repository::checkout_plan()).
+///
+/// Only paths that differ between the tree of `HEAD` and the target tree are affected, all other paths,
+/// including their local changes, remain untouched.
+#[derive(Debug, Clone)]
+pub struct Plan {
+    /// The tree the checkout starts from, or `None` if `HEAD` is unborn.
+    pub from_tree: Option<gix_hash::ObjectId>,
+    /// The tree to check out.
+    pub to_tree: gix_hash::ObjectId,
+    /// All operations that would be performed, sorted by path.
+    pub operations: Vec<Operation>,
+    /// All paths preventing the checkout, sorted by path.
+    pub conflicts: Vec<Conflict>,
+}
+
+impl Plan {
+    /// Return `true` if there are [conflicts](Self::conflicts) which prevent the checkout.
+    pub fn is_conflicted(&self) -> bool {
+        !self.conflicts.is_empty()
+    }
+}
+
+///
+pub mod plan {
+    use crate::error::{Classify, Kind};
+
+    /// Options for use with [`Repository::checkout_plan()`](crate::Repository::checkout_plan()).
+    #[derive(Debug, Default, Copy, Clone)]
+    pub struct Options {
+        /// If `true`, ignored files are protected just like untracked files, similar to `--no-overwrite-ignore`.
+        /// Otherwise they are overwritten or removed as needed, which is the default in `git` as well.
+        pub protect_ignored: bool,
+        /// If set, don't use more than this amount of threads when comparing the index with the worktree.
+        pub thread_limit: Option<usize>,
+    }
+
+    /// The error returned by [`Repository::checkout_plan()`](crate::Repository::checkout_plan()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("A working tree is required to check out a tree")]
+        MissingWorkDir,
+        #[error(transparent)]
+        FindHead(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        PeelHead(#[from] crate::head::peel::to_commit::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        PeelToTree(#[from] crate::object::peel::to_kind::Error),
+        #[error(transparent)]
+        Diff(#[from] gix_diff::tree::changes::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        ExcludeStack(#[from] crate::config::exclude_stack::Error),
+        #[error(transparent)]
+        Status(#[from] crate::status::Error),
+        #[error(transparent)]
+        FilesystemOptions(#[from] crate::config::boolean::Error),
+        #[error("Could not read '{}' to see if it's in the way", path.display())]
+        Io {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+    }
+
+    impl Classify for Error {
+        fn kind(&self) -> Kind {
+            match self {
+                Error::FindHead(err) => err.kind(),
+                Error::FindObject(err) => err.kind(),
+                Error::PeelToTree(err) => err.kind(),
+                Error::DecodeCommit(_) => Kind::CorruptObject,
+                _ => Kind::Other,
+            }
+        }
+    }
+}
+
+///
+pub mod tree {
+    use crate::{
+        bstr::BString,
+        error::{Classify, Kind},
+    };
+
+    /// The error returned by [`Repository::checkout_tree()`](crate::Repository::checkout_tree()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ReadOnly(#[from] crate::repository::read_only::Error),
+        #[error(transparent)]
+        Plan(#[from] super::plan::Error),
+        #[error("The checkout would overwrite local changes or untracked files at {} path(s)", conflicts.len())]
+        Conflicts { conflicts: Vec<super::Conflict> },
+        #[error(transparent)]
+        Stage(#[from] crate::stage::init::Error),
+        #[error(transparent)]
+        AttributeStack(#[from] crate::config::attribute_stack::Error),
+        #[error(transparent)]
+        FilterPipeline(#[from] crate::filter::pipeline::options::Error),
+        #[error(transparent)]
+        ConvertToWorktree(#[from] crate::filter::pipeline::convert_to_worktree::Error),
+        #[error(transparent)]
+        FilesystemOptions(#[from] crate::config::boolean::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error("Could not update '{rela_path}' in the worktree")]
+        Io { rela_path: BString, source: std::io::Error },
+        #[error("Could not obtain the modification time of '{rela_path}'")]
+        Stat {
+            rela_path: BString,
+            source: std::time::SystemTimeError,
+        },
+        #[error(transparent)]
+        ListDelayed(#[from] gix_filter::driver::delayed::list::Error),
+        #[error(transparent)]
+        FetchDelayed(#[from] gix_filter::driver::delayed::fetch::Error),
+        #[error("The filter process listed '{rela_path}' as delayed even though it never received it")]
+        FilterPathUnknown { rela_path: BString },
+        #[error("The filter process didn't produce {} delayed path(s)", rela_paths.len())]
+        FilterPathsUnprocessed { rela_paths: Vec<BString> },
+        #[error(transparent)]
+        WriteIndex(#[from] crate::stage::write::Error),
+    }
+
+    impl Classify for Error {
+        fn kind(&self) -> Kind {
+            match self {
+                Error::Plan(err) => err.kind(),
+                Error::Conflicts { .. } => Kind::WouldOverwrite,
+                Error::FindObject(err) => err.kind(),
+                _ => Kind::Other,
+            }
+        }
+    }
+}
+
+///
+pub mod switch {
+    use crate::error::{Classify, Kind};
+
+    /// Options for use with [`Repository::switch()`](crate::Repository::switch()).
+    #[derive(Debug, Default, Copy, Clone)]
+    pub struct Options {
+        /// Options to configure how the checkout plan is computed.
+        pub plan: super::plan::Options,
+        /// If `true`, detach `HEAD` at the commit the reference points to even if it's a local branch, like `git switch --detach`.
+        ///
+        /// Note that `HEAD` is always detached if the reference isn't a local branch.
+        pub detach: bool,
+    }
+
+    /// The error returned by [`Repository::switch()`](crate::Repository::switch()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ReadOnly(#[from] crate::repository::read_only::Error),
+        #[error(transparent)]
+        FindReference(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        PeelReference(#[from] crate::reference::peel::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        PeelToCommit(#[from] crate::object::peel::to_kind::Error),
+        #[error(transparent)]
+        Checkout(#[from] super::tree::Error),
+        #[error(transparent)]
+        EditHead(#[from] crate::reference::edit::Error),
+    }
+
+    impl Classify for Error {
+        fn kind(&self) -> Kind {
+            match self {
+                Error::FindReference(err) => err.kind(),
+                Error::FindObject(err) => err.kind(),
+                Error::PeelToCommit(err) => err.kind(),
+                Error::Checkout(err) => err.kind(),
+                Error::EditHead(err) => err.kind(),
+                Error::ReadOnly(_) | Error::PeelReference(_) => Kind::Other,
+            }
+        }
+
+        fn is_retryable(&self) -> bool {
+            match self {
+                Error::EditHead(err) => err.is_retryable(),
+                _ => false,
+            }
+        }
+    }
+}
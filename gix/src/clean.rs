@@ -0,0 +1,103 @@
+//! Remove untracked and ignored files from the worktree, similar to `git clean`.
+//!
+//! Use [`Repository::clean()`](crate::Repository::clean()) to learn what would be removed, and to remove it if
+//! [`Options::dry_run`] is turned off.
+use crate::bstr::BString;
+
+/// Which files to consider depending on whether they are ignored.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Ignored {
+    /// Only consider untracked files that aren't ignored, which is the default of `git clean`.
+    #[default]
+    Keep,
+    /// Consider ignored files along with untracked ones, like `git clean -x`.
+    Also,
+    /// Only consider ignored files, like `git clean -X`.
+    Only,
+}
+
+/// Options for use in [`Repository::clean()`](crate::Repository::clean()).
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// If `true`, which is the default, only report what would be removed without touching the worktree, like `git clean --dry-run`.
+    pub dry_run: bool,
+    /// If `true`, remove directories that only contain files to remove as a whole, like `git clean -d`.
+    ///
+    /// Otherwise, only files in directories with tracked files are removed.
+    pub directories: bool,
+    /// Control which files to consider depending on whether they are ignored.
+    pub ignored: Ignored,
+    /// If `true`, also remove nested repositories, like `git clean -ff`. This requires [`directories`](Self::directories)
+    /// to be set as well.
+    pub repositories: bool,
+    /// Pathspecs to limit the files to remove, or an empty list to consider the whole worktree.
+    pub patterns: Vec<BString>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            dry_run: true,
+            directories: false,
+            ignored: Default::default(),
+            repositories: false,
+            patterns: Vec::new(),
+        }
+    }
+}
+
+/// The kind of an [`Entry`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Kind {
+    /// A file or symlink.
+    File,
+    /// A directory along with all of its content.
+    Directory,
+    /// A nested repository along with its worktree.
+    Repository,
+}
+
+/// A path that was or would be removed by [`Repository::clean()`](crate::Repository::clean()).
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Entry {
+    /// The path relative to the root of the worktree, with a trailing slash if it's a directory or a nested repository.
+    pub rela_path: BString,
+    /// The kind of entry.
+    pub kind: Kind,
+    /// If `true`, the entry is ignored, or a directory that only contains ignored files.
+    pub is_ignored: bool,
+}
+
+/// The result of [`Repository::clean()`](crate::Repository::clean()).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Outcome {
+    /// All entries that were removed, or would have been removed if [`Options::dry_run`] was set, sorted by path.
+    pub entries: Vec<Entry>,
+}
+
+/// The error returned by [`Repository::clean()`](crate::Repository::clean()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("A working tree is required to clean it")]
+    MissingWorkDir,
+    #[error(transparent)]
+    ReadOnly(#[from] crate::repository::read_only::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    Pathspec(#[from] crate::pathspec::init::Error),
+    #[error("Could not prepare the pathspec for use")]
+    DetachPathspec(#[source] std::io::Error),
+    #[error(transparent)]
+    ExcludeStack(#[from] crate::config::exclude_stack::Error),
+    #[error(transparent)]
+    FilesystemOptions(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    Status(#[from] crate::status::Error),
+    #[error("Could not remove '{}'", path.display())]
+    Io {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+}
@@ -34,6 +34,38 @@ impl PrepareFetch {
         self.shallow = shallow;
         self
     }
+
+    /// Make this clone a partial one by asking the remote to omit objects according to the filter `spec`, like `blob:none`.
+    ///
+    /// The remote is configured as promisor, but note that objects which were filtered out won't be fetched on demand
+    /// unless lazy fetching is enabled with `Repository::enable_lazy_fetch()`, so a checkout may fail if it needs them.
+    pub fn with_filter(mut self, spec: impl Into<BString>) -> Self {
+        self.filter = Some(spec.into());
+        self
+    }
+
+    /// If `toggle` is `true`, map all references of the remote to the same names locally and mark the remote as mirror,
+    /// like `git clone --mirror`. This is only sensible for bare repositories.
+    pub fn with_mirror(mut self, toggle: bool) -> Self {
+        self.mirror = toggle;
+        self
+    }
+
+    /// If `toggle` is `true`, only fetch the branch set with [`with_branch()`](Self::with_branch()), or the branch that
+    /// `HEAD` points to on the remote, like `git clone --single-branch`.
+    ///
+    /// Note that without an explicit branch, learning the `HEAD` branch of the remote requires an additional connection.
+    pub fn with_single_branch(mut self, toggle: bool) -> Self {
+        self.single_branch = toggle;
+        self
+    }
+
+    /// Point `HEAD` to the local branch with the given short `name` after fetching, or detach it at the tag of that name,
+    /// instead of using what `HEAD` points to on the remote. This is like `git clone --branch <name>`.
+    pub fn with_branch(mut self, name: impl Into<BString>) -> Self {
+        self.branch = Some(name.into());
+        self
+    }
 }
 
 /// Consumption
@@ -0,0 +1,369 @@
+use std::{convert::TryInto, path::PathBuf, sync::atomic::AtomicBool};
+
+use crate::{
+    bstr::{BString, ByteSlice, ByteVec},
+    clone::{fetch::util, Builder, PrepareCheckout, PrepareFetch},
+    remote, Repository,
+};
+
+/// The error returned by [`Builder::run()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Prepare(#[from] crate::clone::Error),
+    #[error("Could not open reference repository at \"{}\"", path.display())]
+    OpenReference {
+        path: PathBuf,
+        source: Box<crate::open::Error>,
+    },
+    #[error("Could not set up alternates to borrow objects from reference repositories")]
+    Alternates(#[source] std::io::Error),
+    #[error(transparent)]
+    Fetch(#[from] crate::clone::fetch::Error),
+    #[error("Could not detach HEAD at the commit recorded for the submodule")]
+    DetachHead(#[from] crate::reference::edit::Error),
+    #[error(transparent)]
+    Checkout(#[from] crate::clone::checkout::main_worktree::Error),
+    #[error(transparent)]
+    Submodules(#[from] crate::submodule::modules::Error),
+    #[error(transparent)]
+    SubmodulePath(#[from] gix_submodule::config::path::Error),
+    #[error(transparent)]
+    SubmoduleUrl(#[from] gix_submodule::config::url::Error),
+    #[error(transparent)]
+    SubmoduleHeadId(#[from] crate::submodule::head_id::Error),
+    #[error("Failed to clone submodule {name:?}")]
+    Submodule { name: BString, source: Box<Error> },
+    #[error("Could not write the configuration of cloned submodules")]
+    SubmoduleConfig(#[source] std::io::Error),
+}
+
+impl crate::error::Classify for Error {
+    fn kind(&self) -> crate::error::Kind {
+        match self {
+            Error::Fetch(err) => err.kind(),
+            Error::DetachHead(err) => err.kind(),
+            Error::Submodule { source, .. } => source.kind(),
+            _ => crate::error::Kind::Other,
+        }
+    }
+
+    fn is_retryable(&self) -> bool {
+        match self {
+            Error::Fetch(err) => err.is_retryable(),
+            Error::Submodule { source, .. } => source.is_retryable(),
+            _ => false,
+        }
+    }
+}
+
+/// The outcome of [`Builder::run()`].
+pub struct Outcome {
+    /// The newly cloned repository.
+    pub repo: Repository,
+    /// The outcome of fetching from the remote.
+    pub fetch: remote::fetch::Outcome,
+    /// The outcome of checking out the main worktree, or `None` if the repository is bare or the checkout was disabled.
+    pub checkout: Option<gix_worktree_state::checkout::Outcome>,
+    /// All submodules that were cloned, in the order they were cloned.
+    pub submodules: Vec<Submodule>,
+}
+
+/// A submodule that was cloned as part of [`Builder::run()`].
+pub struct Submodule {
+    /// The name of the submodule.
+    pub name: BString,
+    /// The path of the submodule relative to the worktree of the superproject.
+    pub path: BString,
+    /// The outcome of cloning the submodule, which includes its own submodules.
+    pub outcome: Outcome,
+}
+
+/// Instantiation
+impl Builder {
+    /// Prepare to clone from `url` into the empty or non-existing directory at `path`.
+    ///
+    /// By default, a clone with worktree is made from all branches of the remote, just like `git clone` would.
+    pub fn new<Url, E>(url: Url, path: impl Into<PathBuf>) -> Result<Self, gix_url::parse::Error>
+    where
+        Url: TryInto<gix_url::Url, Error = E>,
+        gix_url::parse::Error: From<E>,
+    {
+        Ok(Self::from_url(
+            url.try_into().map_err(gix_url::parse::Error::from)?,
+            path.into(),
+        ))
+    }
+
+    fn from_url(url: gix_url::Url, path: PathBuf) -> Self {
+        Builder {
+            url,
+            path,
+            bare: false,
+            mirror: false,
+            shallow: remote::fetch::Shallow::NoChange,
+            filter: None,
+            branch: None,
+            single_branch: false,
+            remote_name: None,
+            references: Vec::new(),
+            recurse_submodules: false,
+            checkout: true,
+            open_options: Default::default(),
+            detach_head_at: None,
+        }
+    }
+}
+
+/// Builder
+impl Builder {
+    /// If `toggle` is `true`, create a bare repository without worktree, like `git clone --bare`.
+    pub fn with_bare(mut self, toggle: bool) -> Self {
+        self.bare = toggle;
+        self
+    }
+
+    /// If `toggle` is `true`, create a bare repository which mirrors all references of the remote, like `git clone --mirror`.
+    pub fn with_mirror(mut self, toggle: bool) -> Self {
+        self.mirror = toggle;
+        self
+    }
+
+    /// Only fetch the last `depth` commits of the history, like `git clone --depth`.
+    pub fn with_depth(self, depth: std::num::NonZeroU32) -> Self {
+        self.with_shallow(remote::fetch::Shallow::DepthAtRemote(depth))
+    }
+
+    /// Make this clone a shallow one with the respective choice of shallow-ness.
+    pub fn with_shallow(mut self, shallow: remote::fetch::Shallow) -> Self {
+        self.shallow = shallow;
+        self
+    }
+
+    /// Ask the remote to omit objects according to the filter `spec`, like `blob:none`, like `git clone --filter`.
+    ///
+    /// Objects that were filtered out won't be fetched on demand, which is why a checkout is likely to fail unless
+    /// it's disabled with [`with_checkout(false)`](Self::with_checkout()).
+    pub fn with_filter(mut self, spec: impl Into<BString>) -> Self {
+        self.filter = Some(spec.into());
+        self
+    }
+
+    /// Point `HEAD` to the branch with the given short `name`, or detach it at the tag of that name, instead of using
+    /// what `HEAD` points to on the remote, like `git clone --branch`.
+    pub fn with_branch(mut self, name: impl Into<BString>) -> Self {
+        self.branch = Some(name.into());
+        self
+    }
+
+    /// If `toggle` is `true`, only fetch the branch set with [`with_branch()`](Self::with_branch()), or the one `HEAD`
+    /// points to on the remote, like `git clone --single-branch`.
+    pub fn with_single_branch(mut self, toggle: bool) -> Self {
+        self.single_branch = toggle;
+        self
+    }
+
+    /// Set the name of the remote to clone from, instead of `origin` or the value of `clone.defaultRemoteName`.
+    pub fn with_remote_name(mut self, name: impl Into<BString>) -> Result<Self, remote::name::Error> {
+        self.remote_name = Some(remote::name::validated(name)?);
+        Ok(self)
+    }
+
+    /// Borrow objects from the repository at `path` by means of alternates, like `git clone --reference`.
+    /// Can be called multiple times to add more reference repositories.
+    ///
+    /// # Deviation
+    ///
+    /// Objects that are present in the reference repository are still received from the remote.
+    pub fn with_reference(mut self, path: impl Into<PathBuf>) -> Self {
+        self.references.push(path.into());
+        self
+    }
+
+    /// If `toggle` is `true`, clone all submodules recursively after checking out the main worktree,
+    /// like `git clone --recurse-submodules`.
+    ///
+    /// # Deviation
+    ///
+    /// The repository of each submodule is placed into its worktree, instead of into the `modules` directory of the superproject.
+    pub fn with_recurse_submodules(mut self, toggle: bool) -> Self {
+        self.recurse_submodules = toggle;
+        self
+    }
+
+    /// If `toggle` is `false`, don't check out the main worktree after fetching, like `git clone --no-checkout`.
+    /// Submodules are only cloned if the main worktree is checked out.
+    pub fn with_checkout(mut self, toggle: bool) -> Self {
+        self.checkout = toggle;
+        self
+    }
+
+    /// Use `options` when opening the newly created repository.
+    pub fn with_open_options(mut self, options: crate::open::Options) -> Self {
+        self.open_options = options;
+        self
+    }
+}
+
+/// Consumption
+impl Builder {
+    /// Perform the clone with `progress` and checking `should_interrupt` to stop the operation, and return the new repository
+    /// along with information about all steps taken.
+    ///
+    /// If fetching or checking out the main worktree fails, the newly created directory will be removed.
+    pub fn run<P>(self, mut progress: P, should_interrupt: &AtomicBool) -> Result<Outcome, Error>
+    where
+        P: crate::NestedProgress,
+        P::SubProgress: 'static,
+    {
+        self.run_inner(&mut progress, should_interrupt)
+    }
+
+    fn run_inner(
+        self,
+        progress: &mut dyn crate::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error> {
+        let bare = self.bare || self.mirror;
+        let mut prepare = PrepareFetch::new(
+            self.url,
+            &self.path,
+            if bare {
+                crate::create::Kind::Bare
+            } else {
+                crate::create::Kind::WithWorktree
+            },
+            Default::default(),
+            self.open_options.clone(),
+        )?
+        .with_shallow(self.shallow)
+        .with_mirror(self.mirror)
+        .with_single_branch(self.single_branch);
+        prepare.remote_name = self.remote_name;
+        if let Some(spec) = self.filter {
+            prepare = prepare.with_filter(spec);
+        }
+        if let Some(branch) = self.branch {
+            prepare = prepare.with_branch(branch);
+        }
+        if !self.references.is_empty() {
+            write_alternates(prepare.repo.as_ref().expect("present"), &self.references)?;
+        }
+
+        let (repo, fetch) = prepare.fetch_only_inner(&mut *progress, should_interrupt)?;
+        if bare || !self.checkout {
+            return Ok(Outcome {
+                repo,
+                fetch,
+                checkout: None,
+                submodules: Vec::new(),
+            });
+        }
+
+        if let Some(id) = self.detach_head_at {
+            use gix_ref::transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog};
+            repo.edit_reference(RefEdit {
+                change: Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        message: "clone: checkout submodule".into(),
+                    },
+                    expected: PreviousValue::Any,
+                    new: gix_ref::Target::Peeled(id),
+                },
+                name: "HEAD".try_into().expect("valid"),
+                deref: false,
+            })?;
+        }
+        let (mut repo, checkout) =
+            PrepareCheckout { repo: Some(repo) }.main_worktree_inner(progress, should_interrupt)?;
+
+        let submodules = if self.recurse_submodules {
+            clone_submodules(&mut repo, &self.open_options, progress, should_interrupt)?
+        } else {
+            Vec::new()
+        };
+        Ok(Outcome {
+            repo,
+            fetch,
+            checkout: Some(checkout),
+            submodules,
+        })
+    }
+}
+
+fn write_alternates(repo: &Repository, references: &[PathBuf]) -> Result<(), Error> {
+    let mut alternates = BString::default();
+    for path in references {
+        let reference =
+            crate::open_opts(path, crate::open::Options::isolated()).map_err(|err| Error::OpenReference {
+                path: path.to_owned(),
+                source: err.into(),
+            })?;
+        let objects_dir = std::fs::canonicalize(reference.common_dir().join("objects")).map_err(Error::Alternates)?;
+        alternates.extend_from_slice(&gix_path::into_bstr(objects_dir));
+        alternates.push_byte(b'\n');
+    }
+    let info_dir = repo.common_dir().join("objects").join("info");
+    std::fs::create_dir_all(&info_dir).map_err(Error::Alternates)?;
+    std::fs::write(info_dir.join("alternates"), alternates).map_err(Error::Alternates)
+}
+
+fn clone_submodules(
+    repo: &mut Repository,
+    open_options: &crate::open::Options,
+    progress: &mut dyn crate::DynNestedProgress,
+    should_interrupt: &AtomicBool,
+) -> Result<Vec<Submodule>, Error> {
+    let remote_url = repo
+        .find_default_remote(remote::Direction::Fetch)
+        .and_then(Result::ok)
+        .and_then(|remote| remote.url(remote::Direction::Fetch).cloned());
+    let mut to_clone = Vec::new();
+    if let Some(submodules) = repo.submodules()? {
+        for submodule in submodules {
+            let id = match submodule.head_id()? {
+                Some(id) => id,
+                None => continue,
+            };
+            let url = submodule.url()?;
+            let url = match remote_url.as_ref() {
+                Some(base) => crate::submodule::resolve_relative_url(base, url),
+                None => url,
+            };
+            to_clone.push((submodule.name().to_owned(), submodule.path()?.into_owned(), url, id));
+        }
+    }
+
+    let work_dir = repo.work_dir().expect("checkout was performed").to_owned();
+    let mut config = util::new_local_config(repo);
+    let mut out = Vec::new();
+    for (name, path, url, id) in to_clone {
+        let mut child = progress.add_child(format!("submodule '{name}'"));
+        let url_value = url.to_bstring();
+        let outcome = Builder {
+            detach_head_at: Some(id),
+            recurse_submodules: true,
+            open_options: open_options.clone(),
+            ..Builder::from_url(url, work_dir.join(gix_path::from_bstr(path.as_ref())))
+        }
+        .run_inner(&mut child, should_interrupt)
+        .map_err(|err| Error::Submodule {
+            name: name.clone(),
+            source: err.into(),
+        })?;
+
+        for (key, value) in [("url", url_value.as_bstr()), ("active", "true".into())] {
+            config
+                .set_raw_value("submodule", Some(name.as_ref()), key, value)
+                .expect("submodule names are valid subsection names as they were read from configuration");
+        }
+        out.push(Submodule { name, path, outcome });
+    }
+    if !out.is_empty() {
+        util::append_to_local_config(repo, config).map_err(Error::SubmoduleConfig)?;
+    }
+    Ok(out)
+}
@@ -72,7 +72,7 @@ pub mod main_worktree {
             self.main_worktree_inner(&mut progress, should_interrupt)
         }
 
-        fn main_worktree_inner(
+        pub(crate) fn main_worktree_inner(
             &mut self,
             progress: &mut dyn gix_features::progress::DynNestedProgress,
             should_interrupt: &AtomicBool,
@@ -85,8 +85,11 @@ pub mod main_worktree {
             let workdir = repo.work_dir().ok_or_else(|| Error::BareRepository {
                 git_dir: repo.git_dir().to_owned(),
             })?;
-            let root_tree = match repo.head()?.try_peel_to_id_in_place()? {
-                Some(id) => id.object().expect("downloaded from remote").peel_to_tree()?.id,
+            let (head_id, root_tree) = match repo.head()?.try_peel_to_id_in_place()? {
+                Some(id) => (
+                    id.detach(),
+                    id.object().expect("downloaded from remote").peel_to_tree()?.id,
+                ),
                 None => {
                     return Ok((
                         self.repo.take().expect("still present"),
@@ -125,6 +128,13 @@ pub mod main_worktree {
             bytes.show_throughput(start);
 
             index.write(Default::default())?;
+            // Just like in `git`, the outcome of this hook doesn't affect the checkout.
+            repo.invoke_hook(crate::hook::Invocation::post_checkout(
+                gix_hash::ObjectId::null(repo.object_hash()),
+                head_id,
+                true,
+            ))
+            .ok();
             Ok((self.repo.take().expect("still present"), outcome))
         }
     }
@@ -1,4 +1,7 @@
-use crate::clone::PrepareFetch;
+use crate::{
+    clone::PrepareFetch,
+    error::{Classify, Kind},
+};
 
 /// The error returned by [`PrepareFetch::fetch_only()`].
 #[derive(Debug, thiserror::Error)]
@@ -31,6 +34,37 @@ pub enum Error {
     },
     #[error("Failed to update HEAD with values from remote")]
     HeadUpdate(#[from] crate::reference::edit::Error),
+    #[error(transparent)]
+    RefMap(#[from] crate::remote::ref_map::Error),
+    #[error("The branch to fetch or check out led to an invalid ref-spec")]
+    BranchRefSpec(#[from] gix_refspec::parse::Error),
+    #[error("Remote branch or tag {name:?} was not found on the remote")]
+    RemoteBranchMissing { name: crate::bstr::BString },
+}
+
+impl Classify for Error {
+    fn kind(&self) -> Kind {
+        match self {
+            Error::Connect(err) => err.kind(),
+            Error::PrepareFetch(err) => err.kind(),
+            Error::Fetch(err) => err.kind(),
+            Error::HeadUpdate(err) => err.kind(),
+            Error::RefMap(err) => err.kind(),
+            Error::RemoteBranchMissing { .. } => Kind::NotFound,
+            _ => Kind::Other,
+        }
+    }
+
+    fn is_retryable(&self) -> bool {
+        match self {
+            Error::Connect(err) => err.is_retryable(),
+            Error::PrepareFetch(err) => err.is_retryable(),
+            Error::Fetch(err) => err.is_retryable(),
+            Error::HeadUpdate(err) => err.is_retryable(),
+            Error::RefMap(err) => err.is_retryable(),
+            _ => false,
+        }
+    }
 }
 
 /// Modification
@@ -62,12 +96,18 @@ impl PrepareFetch {
     }
 
     #[gix_protocol::maybe_async::maybe_async]
-    async fn fetch_only_inner(
+    pub(crate) async fn fetch_only_inner(
         &mut self,
         progress: &mut dyn crate::DynNestedProgress,
         should_interrupt: &std::sync::atomic::AtomicBool,
     ) -> Result<(crate::Repository, crate::remote::fetch::Outcome), Error> {
-        use crate::{bstr::ByteVec, remote, remote::fetch::RefLogMessage};
+        use crate::{
+            bstr::{BStr, ByteSlice, ByteVec},
+            config,
+            config::tree::Key,
+            remote,
+            remote::fetch::RefLogMessage,
+        };
 
         let repo = self
             .repo
@@ -85,35 +125,93 @@ impl PrepareFetch {
                 .unwrap_or_else(|| "origin".into()),
         };
 
+        // Add HEAD after the remote was written to config, we need it to know what to checkout later, and assure
+        // the ref that HEAD points to is present no matter what.
+        let head_refspec = gix_refspec::parse(
+            format!("HEAD:refs/remotes/{remote_name}/HEAD").as_str().into(),
+            gix_refspec::parse::Operation::Fetch,
+        )
+        .expect("valid")
+        .to_owned();
+
+        let single_branch = match (self.single_branch, self.branch.as_ref()) {
+            (false, _) => None,
+            (true, Some(branch)) => Some(branch.to_owned()),
+            (true, None) => {
+                let remote = repo.remote_at(self.url.clone())?;
+                let mut connection = remote.connect(remote::Direction::Fetch).await?;
+                if let Some(f) = self.configure_connection.as_mut() {
+                    f(&mut connection).map_err(Error::RemoteConnection)?;
+                }
+                let ref_map = connection
+                    .ref_map(&mut *progress, {
+                        let mut opts = self.fetch_options.clone();
+                        opts.extra_refspecs.push(head_refspec.clone());
+                        opts
+                    })
+                    .await?;
+                ref_map.remote_refs.iter().find_map(|r| match r {
+                    gix_protocol::handshake::Ref::Symbolic {
+                        full_ref_name, target, ..
+                    }
+                    | gix_protocol::handshake::Ref::Unborn { full_ref_name, target }
+                        if full_ref_name == "HEAD" =>
+                    {
+                        target
+                            .strip_prefix(b"refs/heads/")
+                            .map(|name| name.as_bstr().to_owned())
+                    }
+                    _ => None,
+                })
+            }
+        };
+        // Mirrors don't track the remote HEAD, and a chosen branch replaces it, but we still have to receive it
+        // to know what to check out.
+        let head_refspecs: Vec<_> = match (self.mirror, self.branch.as_ref()) {
+            (false, None) => vec![head_refspec.clone()],
+            (true, None) => vec![fetch_only_refspec("HEAD".into())?],
+            (_, Some(branch)) => vec![
+                fetch_only_refspec(format!("refs/heads/{branch}"))?,
+                fetch_only_refspec(format!("refs/tags/{branch}"))?,
+            ],
+        };
+        let fetch_refspec = if self.mirror {
+            "+refs/*:refs/*".into()
+        } else {
+            match single_branch.as_ref() {
+                Some(branch) => format!("+refs/heads/{branch}:refs/remotes/{remote_name}/{branch}"),
+                None => format!("+refs/heads/*:refs/remotes/{remote_name}/*"),
+            }
+        };
         let mut remote = repo
             .remote_at(self.url.clone())?
-            .with_refspecs(
-                Some(format!("+refs/heads/*:refs/remotes/{remote_name}/*").as_str()),
-                remote::Direction::Fetch,
-            )
-            .expect("valid static spec");
+            .with_refspecs(Some(fetch_refspec.as_str()), remote::Direction::Fetch)?;
         let mut clone_fetch_tags = None;
         if let Some(f) = self.configure_remote.as_mut() {
             remote = f(remote).map_err(Error::RemoteConfiguration)?;
-        } else {
+        } else if !self.mirror && single_branch.is_none() {
             clone_fetch_tags = remote::fetch::Tags::All.into();
         }
 
-        let config = util::write_remote_to_local_config_file(&mut remote, remote_name.clone())?;
+        let mut extra_values: Vec<(&'static dyn Key, &BStr)> = Vec::new();
+        if self.mirror {
+            extra_values.push((&config::tree::Remote::MIRROR, "true".into()));
+        }
+        if let Some(spec) = self.filter.as_ref() {
+            extra_values.extend([
+                (&config::tree::Remote::PROMISOR as &dyn Key, "true".into()),
+                (&config::tree::Remote::PARTIAL_CLONE_FILTER, spec.as_ref()),
+                (&config::tree::Core::REPOSITORY_FORMAT_VERSION, "1".into()),
+                (&config::tree::Extensions::PARTIAL_CLONE, remote_name.as_ref()),
+            ]);
+        }
+        let config = util::write_remote_to_local_config_file(&mut remote, remote_name.clone(), &extra_values)?;
 
         // Now we are free to apply remote configuration we don't want to be written to disk.
         if let Some(fetch_tags) = clone_fetch_tags {
             remote = remote.with_fetch_tags(fetch_tags);
         }
 
-        // Add HEAD after the remote was written to config, we need it to know what to checkout later, and assure
-        // the ref that HEAD points to is present no matter what.
-        let head_refspec = gix_refspec::parse(
-            format!("HEAD:refs/remotes/{remote_name}/HEAD").as_str().into(),
-            gix_refspec::parse::Operation::Fetch,
-        )
-        .expect("valid")
-        .to_owned();
         let pending_pack: remote::fetch::Prepare<'_, '_, _> = {
             let mut connection = remote.connect(remote::Direction::Fetch).await?;
             if let Some(f) = self.configure_connection.as_mut() {
@@ -122,8 +220,14 @@ impl PrepareFetch {
             connection
                 .prepare_fetch(&mut *progress, {
                     let mut opts = self.fetch_options.clone();
-                    if !opts.extra_refspecs.contains(&head_refspec) {
-                        opts.extra_refspecs.push(head_refspec)
+                    if self.mirror {
+                        // The prefix of `refs/*` can't be used to filter refs on the remote.
+                        opts.prefix_from_spec_as_filter_on_remote = false;
+                    }
+                    for spec in head_refspecs {
+                        if !opts.extra_refspecs.contains(&spec) {
+                            opts.extra_refspecs.push(spec)
+                        }
                     }
                     opts
                 })
@@ -143,16 +247,21 @@ impl PrepareFetch {
                 message: reflog_message.clone(),
             })
             .with_shallow(self.shallow.clone())
+            .with_filter(self.filter.clone())
             .receive_inner(progress, should_interrupt)
             .await?;
 
         util::append_config_to_repo_config(repo, config);
-        util::update_head(
-            repo,
-            &outcome.ref_map.remote_refs,
-            reflog_message.as_ref(),
-            remote_name.as_ref(),
-        )?;
+        let branch_head;
+        let remote_refs = match self.branch.as_ref() {
+            Some(branch) => {
+                branch_head = util::head_for_branch(&outcome.ref_map.remote_refs, branch.as_ref())
+                    .ok_or_else(|| Error::RemoteBranchMissing { name: branch.clone() })?;
+                std::slice::from_ref(&branch_head)
+            }
+            None => &outcome.ref_map.remote_refs,
+        };
+        util::update_head(repo, remote_refs, reflog_message.as_ref(), remote_name.as_ref())?;
 
         Ok((self.repo.take().expect("still present"), outcome))
     }
@@ -173,4 +282,8 @@ impl PrepareFetch {
     }
 }
 
-mod util;
+fn fetch_only_refspec(spec: String) -> Result<gix_refspec::RefSpec, Error> {
+    Ok(gix_refspec::parse(spec.as_str().into(), gix_refspec::parse::Operation::Fetch)?.to_owned())
+}
+
+pub(super) mod util;
@@ -8,6 +8,8 @@ use gix_ref::{
 use super::Error;
 use crate::{
     bstr::{BStr, BString, ByteSlice},
+    config,
+    config::tree::Key,
     Repository,
 };
 
@@ -16,13 +18,26 @@ enum WriteMode {
     Append,
 }
 
+/// Write `remote` as `remote_name` to the local configuration file, along with `extra_values` which are placed into the section
+/// of the remote if their key requires a subsection.
 #[allow(clippy::result_large_err)]
 pub fn write_remote_to_local_config_file(
     remote: &mut crate::Remote<'_>,
     remote_name: BString,
+    extra_values: &[(&'static dyn Key, &BStr)],
 ) -> Result<gix_config::File<'static>, Error> {
     let mut config = gix_config::File::new(local_config_meta(remote.repo));
-    remote.save_as_to(remote_name, &mut config)?;
+    remote.save_as_to(remote_name.clone(), &mut config)?;
+    for (key, value) in extra_values {
+        let subsection = matches!(
+            key.subsection_requirement(),
+            Some(config::tree::SubSectionRequirement::Parameter(_))
+        )
+        .then(|| remote_name.as_ref());
+        config
+            .set_raw_value(key.section().name(), subsection, key.name(), *value)
+            .expect("statically known keys and a validated remote name");
+    }
 
     write_to_local_config(&config, WriteMode::Append)?;
     Ok(config)
@@ -53,11 +68,60 @@ fn write_to_local_config(config: &gix_config::File<'static>, mode: WriteMode) ->
     config.write_to_filter(&mut local_config, &mut |s| s.meta().source == gix_config::Source::Local)
 }
 
+/// Return an empty configuration file whose sections will be written to the local configuration file of `repo`.
+#[cfg(all(feature = "blocking-network-client", feature = "worktree-mutation"))]
+pub fn new_local_config(repo: &Repository) -> gix_config::File<'static> {
+    gix_config::File::new(local_config_meta(repo))
+}
+
+/// Append `config` to the local configuration file of `repo`, as well as to its configuration in memory.
+#[cfg(all(feature = "blocking-network-client", feature = "worktree-mutation"))]
+pub fn append_to_local_config(repo: &mut Repository, config: gix_config::File<'static>) -> std::io::Result<()> {
+    write_to_local_config(&config, WriteMode::Append)?;
+    append_config_to_repo_config(repo, config);
+    Ok(())
+}
+
 pub fn append_config_to_repo_config(repo: &mut Repository, config: gix_config::File<'static>) {
     let repo_config = gix_features::threading::OwnShared::make_mut(&mut repo.config.resolved);
     repo_config.append(config);
 }
 
+/// Produce a `HEAD` ref which points to the remote branch named `branch`, or which is detached at the tag of that name,
+/// for use in [`update_head()`], or `None` if there is no such branch or tag among `remote_refs`.
+pub fn head_for_branch(
+    remote_refs: &[gix_protocol::handshake::Ref],
+    branch: &BStr,
+) -> Option<gix_protocol::handshake::Ref> {
+    use gix_protocol::handshake::Ref;
+    let find = |prefix: &str| {
+        let name = {
+            let mut name = BString::from(prefix);
+            name.extend_from_slice(branch);
+            name
+        };
+        remote_refs.iter().find_map(move |r| match r {
+            Ref::Direct { full_ref_name, object }
+            | Ref::Peeled {
+                full_ref_name, object, ..
+            } if *full_ref_name == name => Some((name.clone(), *object)),
+            _ => None,
+        })
+    };
+    if let Some((target, object)) = find("refs/heads/") {
+        return Some(Ref::Symbolic {
+            full_ref_name: "HEAD".into(),
+            target,
+            tag: None,
+            object,
+        });
+    }
+    find("refs/tags/").map(|(_, object)| Ref::Direct {
+        full_ref_name: "HEAD".into(),
+        object,
+    })
+}
+
 /// HEAD cannot be written by means of refspec by design, so we have to do it manually here. Also create the pointed-to ref
 /// if we have to, as it might not have been naturally included in the ref-specs.
 pub fn update_head(
@@ -34,6 +34,18 @@ pub struct PrepareFetch {
     /// How to handle shallow clones
     #[cfg_attr(not(feature = "blocking-network-client"), allow(dead_code))]
     shallow: remote::fetch::Shallow,
+    /// The filter to ask the remote to apply to the objects it sends, for partial clones.
+    #[cfg_attr(not(feature = "blocking-network-client"), allow(dead_code))]
+    filter: Option<BString>,
+    /// If `true`, mirror all references of the remote instead of setting up remote tracking branches.
+    #[cfg_attr(not(feature = "blocking-network-client"), allow(dead_code))]
+    mirror: bool,
+    /// If `true`, fetch only a single branch, either `branch` or the one `HEAD` points to on the remote.
+    #[cfg_attr(not(feature = "blocking-network-client"), allow(dead_code))]
+    single_branch: bool,
+    /// The branch or tag to point `HEAD` to instead of using the remote `HEAD`.
+    #[cfg_attr(not(feature = "blocking-network-client"), allow(dead_code))]
+    branch: Option<BString>,
 }
 
 /// The error returned by [`PrepareFetch::new()`].
@@ -131,17 +143,56 @@ impl PrepareFetch {
             #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
             configure_connection: None,
             shallow: remote::fetch::Shallow::NoChange,
+            filter: None,
+            mirror: false,
+            single_branch: false,
+            branch: None,
         })
     }
 }
 
+/// A utility to configure all aspects of a clone in one place, like `git clone` does, to fetch from a remote, check out
+/// the main worktree and clone submodules recursively with a single call to [`run()`](Self::run()).
+#[must_use]
+#[cfg(all(feature = "blocking-network-client", feature = "worktree-mutation"))]
+pub struct Builder {
+    /// The url to clone from.
+    url: gix_url::Url,
+    /// The directory to clone into.
+    path: std::path::PathBuf,
+    /// If `true`, don't create a worktree.
+    bare: bool,
+    /// If `true`, mirror all references of the remote, which implies `bare`.
+    mirror: bool,
+    /// How to handle shallow clones.
+    shallow: remote::fetch::Shallow,
+    /// The filter to ask the remote to apply, for partial clones.
+    filter: Option<BString>,
+    /// The branch or tag to point `HEAD` to instead of the remote `HEAD`.
+    branch: Option<BString>,
+    /// If `true`, fetch only a single branch.
+    single_branch: bool,
+    /// The name of the remote, which defaults to `origin` if not overridden.
+    remote_name: Option<BString>,
+    /// Repositories to borrow objects from.
+    references: Vec<std::path::PathBuf>,
+    /// If `true`, clone all submodules after checking out the main worktree, recursively.
+    recurse_submodules: bool,
+    /// If `false`, don't check out the main worktree after fetching.
+    checkout: bool,
+    /// Options for opening the newly created repository.
+    open_options: crate::open::Options,
+    /// The commit to detach `HEAD` at before checking out, which is used when cloning submodules.
+    detach_head_at: Option<gix_hash::ObjectId>,
+}
+
 /// A utility to collect configuration on how to perform a checkout into a working tree, and when dropped without checking out successfully
 /// the fetched repository will be dropped.
 #[must_use]
 #[cfg(feature = "worktree-mutation")]
 pub struct PrepareCheckout {
     /// A freshly initialized repository which is owned by us, or `None` if it was handed to the user
-    pub(self) repo: Option<crate::Repository>,
+    pub(crate) repo: Option<crate::Repository>,
 }
 
 mod access;
@@ -184,3 +235,7 @@ pub mod fetch;
 ///
 #[cfg(feature = "worktree-mutation")]
 pub mod checkout;
+
+///
+#[cfg(all(feature = "blocking-network-client", feature = "worktree-mutation"))]
+pub mod builder;
@@ -19,6 +19,26 @@ pub enum Error {
     WriteObject(#[from] crate::object::write::Error),
     #[error(transparent)]
     ReferenceEdit(#[from] crate::reference::edit::Error),
+    #[error("Could not sign the commit")]
+    Sign(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error(transparent)]
+    ConfigBoolean(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    Signer(#[from] crate::gpg::signer::Error),
+    #[error(transparent)]
+    RunHook(#[from] crate::hook::run::Error),
+    #[error(transparent)]
+    ReadOnly(#[from] crate::repository::read_only::Error),
+    #[error("The '{name}' hook rejected the commit with {status}")]
+    HookRejected {
+        name: &'static str,
+        status: std::process::ExitStatus,
+    },
+    #[error("Could not pass the commit message to and from the 'commit-msg' hook through '{}'", path.display())]
+    CommitMessageFile {
+        source: std::io::Error,
+        path: std::path::PathBuf,
+    },
 }
 
 ///
@@ -0,0 +1,31 @@
+//! Translate object ids between the object hash of a repository and its compatibility hash, as configured with
+//! `extensions.compatObjectFormat`.
+pub(crate) type MapStorage = gix_features::threading::OwnShared<gix_fs::SharedFileSnapshotMut<gix_odb::compat::Map>>;
+/// A lazily loaded and auto-updated mapping of object ids to their compatibility ids and back.
+pub type Map = gix_fs::SharedFileSnapshot<gix_odb::compat::Map>;
+
+///
+pub mod open {
+    /// The error returned by [`Repository::compat_map()`][crate::Repository::compat_map()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Init(#[from] gix_odb::compat::init::Error),
+    }
+}
+
+///
+pub mod record {
+    /// The error returned when recording the compatibility id of a newly written object.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Open(#[from] super::open::Error),
+        #[error(transparent)]
+        Convert(#[from] gix_odb::compat::convert::Error),
+        #[error("Could not append to the object map")]
+        Io(#[from] std::io::Error),
+    }
+}
@@ -9,7 +9,7 @@ use crate::{
     config::{
         boolean,
         cache::util::{ApplyLeniency, ApplyLeniencyDefaultValue},
-        tree::{Core, Key},
+        tree::{gitoxide, Core, Key},
         Cache,
     },
     remote,
@@ -152,6 +152,44 @@ impl Cache {
         Some(path.interpolate(ctx))
     }
 
+    /// Return the zlib compression level configured with `key`, falling back to `core.compression`,
+    /// or `None` if neither is set.
+    pub(crate) fn compression_level(
+        &self,
+        key: &'static config::tree::keys::CompressionLevel,
+    ) -> Result<Option<u32>, config::key::GenericError> {
+        let level = |key: &'static config::tree::keys::CompressionLevel| {
+            self.resolved
+                .integer_by_key(key.logical_name().as_str())
+                .map(|res| key.try_into_compression_level(res))
+                .transpose()
+                .with_leniency(self.lenient_config)
+        };
+        Ok(match level(key)? {
+            Some(level) => Some(level),
+            None => level(&Core::COMPRESSION)?,
+        })
+    }
+
+    /// Return the maximum amount of bytes of pack data to keep mapped as configured by `core.packedGitLimit`,
+    /// along with the hint to pass when mapping packs as configured by `gitoxide.objects.packMmapAdvice`.
+    pub(crate) fn pack_mmap_options(&self) -> Result<(Option<u64>, gix_odb::pack::mmap::Advice), config::Error> {
+        let max_mapped_pack_bytes = self
+            .resolved
+            .integer_by_key(Core::PACKED_GIT_LIMIT.logical_name().as_str())
+            .map(|res| Core::PACKED_GIT_LIMIT.try_into_u64(res))
+            .transpose()
+            .with_leniency(self.lenient_config)?;
+        let advice = self
+            .resolved
+            .string_by_key(gitoxide::Objects::PACK_MMAP_ADVICE.logical_name().as_str())
+            .map(|value| gitoxide::Objects::PACK_MMAP_ADVICE.try_into_advice(value))
+            .transpose()
+            .with_leniency(self.lenient_config)?
+            .unwrap_or_default();
+        Ok((max_mapped_pack_bytes, advice))
+    }
+
     pub(crate) fn apply_leniency<T, E>(&self, res: Option<Result<T, E>>) -> Result<Option<T>, E> {
         res.transpose().with_leniency(self.lenient_config)
     }
@@ -182,6 +220,24 @@ impl Cache {
         })
     }
 
+    /// Return whether long-running filter processes may delay their output when checking out files.
+    #[cfg(any(feature = "worktree-mutation", feature = "status"))]
+    pub(crate) fn filter_process_delay(&self) -> Result<gix_filter::driver::apply::Delay, boolean::Error> {
+        use crate::config::tree::gitoxide;
+        Ok(
+            if boolean(
+                self,
+                "gitoxide.core.filterProcessDelay",
+                &gitoxide::Core::FILTER_PROCESS_DELAY,
+                true,
+            )? {
+                gix_filter::driver::apply::Delay::Allow
+            } else {
+                gix_filter::driver::apply::Delay::Forbid
+            },
+        )
+    }
+
     /// Collect everything needed to checkout files into a worktree.
     /// Note that some of the options being returned will be defaulted so safe settings, the caller might have to override them
     /// depending on the use-case.
@@ -191,7 +247,6 @@ impl Cache {
         repo: &crate::Repository,
         attributes_source: gix_worktree::stack::state::attributes::Source,
     ) -> Result<gix_worktree_state::checkout::Options, config::checkout_options::Error> {
-        use crate::config::tree::gitoxide;
         let git_dir = repo.git_dir();
         let thread_limit = self.apply_leniency(
             self.resolved
@@ -209,18 +264,8 @@ impl Cache {
             }
             filters
         };
-        let filter_process_delay = if boolean(
-            self,
-            "gitoxide.core.filterProcessDelay",
-            &gitoxide::Core::FILTER_PROCESS_DELAY,
-            true,
-        )? {
-            gix_filter::driver::apply::Delay::Allow
-        } else {
-            gix_filter::driver::apply::Delay::Forbid
-        };
         Ok(gix_worktree_state::checkout::Options {
-            filter_process_delay,
+            filter_process_delay: self.filter_process_delay()?,
             filters,
             attributes: self
                 .assemble_attribute_globals(git_dir, attributes_source, self.attributes)?
@@ -11,6 +11,7 @@ pub(crate) struct StageOne {
     pub is_bare: bool,
     pub lossy: Option<bool>,
     pub object_hash: gix_hash::Kind,
+    pub compat_object_hash: Option<gix_hash::Kind>,
     pub reflog: Option<gix_ref::store::WriteReflog>,
 }
 
@@ -50,6 +51,15 @@ impl StageOne {
             })
             .transpose()?
             .unwrap_or(gix_hash::Kind::Sha1);
+        let compat_object_hash = (repo_format_version == 1)
+            .then(|| {
+                config
+                    .string("extensions", None, "compatObjectFormat")
+                    .map(|format| Extensions::COMPAT_OBJECT_FORMAT.try_into_object_format(format))
+            })
+            .flatten()
+            .transpose()?
+            .filter(|compat_hash| *compat_hash != object_hash);
 
         let extension_worktree = util::config_bool(
             &config,
@@ -77,6 +87,7 @@ impl StageOne {
             is_bare,
             lossy,
             object_hash,
+            compat_object_hash,
             reflog,
         })
     }
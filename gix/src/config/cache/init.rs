@@ -27,6 +27,7 @@ impl Cache {
             lossy,
             is_bare,
             object_hash,
+            compat_object_hash,
             reflog: _,
         }: StageOne,
         git_dir: &std::path::Path,
@@ -119,7 +120,10 @@ impl Cache {
             globals.append(git_dir_config);
             globals.resolve_includes(options)?;
             if use_env {
-                globals.append(gix_config::File::from_env(options)?.unwrap_or_default());
+                globals.append(
+                    gix_config::File::from_env_vars(&mut Self::make_source_env(environment), options)?
+                        .unwrap_or_default(),
+                );
             }
             if !cli_config_overrides.is_empty() {
                 config::overrides::append(&mut globals, cli_config_overrides, gix_config::Source::Cli, |_| None)
@@ -161,6 +165,7 @@ impl Cache {
             resolved: config.into(),
             use_multi_pack_index,
             object_hash,
+            compat_object_hash,
             #[cfg(feature = "revision")]
             object_kind_hint,
             static_pack_cache_limit_bytes,
@@ -510,6 +515,15 @@ fn apply_environment_overrides(
                 },
             ],
         ),
+        (
+            "core",
+            None,
+            git_prefix,
+            &[{
+                let key = &Core::NOTES_REF;
+                (env(key), key.name)
+            }],
+        ),
         (
             "ssh",
             None,
@@ -552,10 +566,6 @@ fn apply_environment_overrides(
                 let key = &Core::SSH_COMMAND;
                 (env(key), key.name, git_prefix)
             },
-            {
-                let key = &Core::USE_REPLACE_REFS;
-                (env(key), key.name, objects)
-            },
         ] {
             if let Some(value) = var_as_bstring(var, permission) {
                 section.push_with_comment(
@@ -566,6 +576,19 @@ fn apply_environment_overrides(
             }
         }
 
+        {
+            // Like git, any value disables replacements, even empty ones.
+            let key = &Core::USE_REPLACE_REFS;
+            let var = env(key);
+            if var_as_bstring(var, objects).is_some() {
+                section.push_with_comment(
+                    key.name.try_into().expect("statically known to be valid"),
+                    Some("false".into()),
+                    format!("from {var}").as_str(),
+                );
+            }
+        }
+
         if section.num_values() == 0 {
             let id = section.id();
             env_override.remove_section_by_id(id);
@@ -7,6 +7,10 @@ pub(crate) mod cache;
 mod snapshot;
 #[cfg(feature = "credentials")]
 pub use snapshot::credential_helpers;
+pub use snapshot::{
+    alias, origin, validate,
+    value_pattern::{self, ValuePattern},
+};
 
 ///
 pub mod overrides;
@@ -64,6 +68,38 @@ pub mod set_value {
     }
 }
 
+///
+pub mod persist {
+    /// The error produced when persisting values in a configuration file of a repository.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ReadOnly(#[from] crate::repository::read_only::Error),
+        #[error(transparent)]
+        Open(#[from] gix_config::file::init::from_paths::Error),
+        #[error(transparent)]
+        SetValue(#[from] crate::config::set_value::Error),
+        #[error(transparent)]
+        Lock(#[from] gix_lock::acquire::Error),
+        #[error("Could not write the configuration file at '{}'", path.display())]
+        Io {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+        #[error(transparent)]
+        Apply(#[from] crate::config::Error),
+        #[error("Configuration of scope {scope:?} has no location it could be written to")]
+        MissingLocation { scope: gix_config::Source },
+        #[error(transparent)]
+        SectionHeader(#[from] gix_config::parse::section::header::Error),
+        #[error(transparent)]
+        WorktreeConfigExtension(#[from] crate::config::boolean::Error),
+        #[error("Configuration can't be written for a single worktree as there are multiple worktrees, and extensions.worktreeConfig is disabled")]
+        WorktreeConfigDisabled,
+    }
+}
+
 /// The error returned when failing to initialize the repository configuration.
 ///
 /// This configuration is on the critical path when opening a repository.
@@ -80,6 +116,8 @@ pub enum Error {
     UnsupportedObjectFormat { name: BString },
     #[error(transparent)]
     CoreAbbrev(#[from] abbrev::Error),
+    #[error(transparent)]
+    CompressionLevel(#[from] key::GenericError),
     #[error("Could not read configuration file at \"{}\"", path.display())]
     Io {
         source: std::io::Error,
@@ -223,6 +261,7 @@ pub mod key {
             'i' => "The timeout at key",       // timeout
             'd' => "The duration [ms] at key", // duration
             'b' => "The boolean at key",       // boolean
+            'c' => "The color at key",         // color
             'v' => "The key",                  // generic key with value
             'r' => "The refspec at",           // refspec
             's' => "The ssl version at",       // ssl-version
@@ -387,6 +426,12 @@ pub mod boolean {
     pub type Error = super::key::Error<gix_config::value::Error, 'b', 'i'>;
 }
 
+///
+pub mod color {
+    /// The error produced when failing to parse a color from configuration.
+    pub type Error = super::key::Error<gix_config::value::Error, 'c', 'p'>;
+}
+
 ///
 pub mod unsigned_integer {
     /// The error produced when failing to parse a signed integer from configuration.
@@ -503,6 +548,8 @@ pub(crate) struct Cache {
     pub is_bare: bool,
     /// The type of hash to use.
     pub object_hash: gix_hash::Kind,
+    /// The type of hash to additionally maintain object ids for, as configured by `extensions.compatObjectFormat`.
+    pub compat_object_hash: Option<gix_hash::Kind>,
     /// If true, multi-pack indices, whether present or not, may be used by the object database.
     pub use_multi_pack_index: bool,
     /// The representation of `core.logallrefupdates`, or `None` if the variable wasn't set.
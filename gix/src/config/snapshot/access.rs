@@ -1,5 +1,8 @@
 #![allow(clippy::result_large_err)]
-use std::borrow::Cow;
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+};
 
 use gix_features::threading::OwnShared;
 use gix_macros::momo;
@@ -72,6 +75,124 @@ impl<'repo> Snapshot<'repo> {
 }
 
 /// Utilities and additional access
+/// Typed access to configuration values, using `key` like `section_name.[subsection_name.]value_name`.
+///
+/// Like their untyped counterparts, these methods take the most recent value at `key` even if it is from a file with reduced trust.
+/// The `try_*()` variants report conversion errors which include the key and the offending value, whereas all other methods
+/// degenerate these into `None`.
+impl<'repo> Snapshot<'repo> {
+    /// Return the color at `key`, like `color.diff.meta`, or `None` if there is no such value or if it isn't a valid color.
+    pub fn color<'a>(&self, key: impl Into<&'a BStr>) -> Option<gix_config::Color> {
+        self.try_color(key).and_then(Result::ok)
+    }
+
+    /// Like [`color()`](Self::color()), but it will report an error if the value couldn't be interpreted as color.
+    #[momo]
+    pub fn try_color<'a>(
+        &self,
+        key: impl Into<&'a BStr>,
+    ) -> Option<Result<gix_config::Color, crate::config::color::Error>> {
+        let key = key.into();
+        let value = self.string(key)?;
+        Some(gix_config::Color::try_from(value.as_ref()).map_err(|err| value_error(key, value.as_ref(), err)))
+    }
+
+    /// Return the size in bytes at `key`, which may have a `k`, `m` or `g` suffix like `10m`, or `None` if there is no such value
+    /// or if it isn't a valid size.
+    pub fn byte_size<'a>(&self, key: impl Into<&'a BStr>) -> Option<u64> {
+        self.try_byte_size(key).and_then(Result::ok)
+    }
+
+    /// Like [`byte_size()`](Self::byte_size()), but it will report an error if the value couldn't be interpreted as
+    /// non-negative size or overflows.
+    #[momo]
+    pub fn try_byte_size<'a>(
+        &self,
+        key: impl Into<&'a BStr>,
+    ) -> Option<Result<u64, crate::config::unsigned_integer::Error>> {
+        let key = key.into();
+        let value = self.string(key)?;
+        Some(
+            integer(value.as_ref())
+                .and_then(|size| {
+                    size.try_into()
+                        .map_err(|_| gix_config::value::Error::new("Sizes must not be negative", value.as_ref()))
+                })
+                .map_err(|err| value_error(key, value.as_ref(), err)),
+        )
+    }
+
+    /// Return the duration at `key`, interpreted as milliseconds which may have a `k`, `m` or `g` suffix,
+    /// or `None` if there is no such value or if it isn't a valid duration.
+    ///
+    /// Negative values are interpreted as infinite duration, just like it's done for keys like `core.filesRefLockTimeout`.
+    pub fn duration<'a>(&self, key: impl Into<&'a BStr>) -> Option<std::time::Duration> {
+        self.try_duration(key).and_then(Result::ok)
+    }
+
+    /// Like [`duration()`](Self::duration()), but it will report an error if the value couldn't be interpreted as duration.
+    #[momo]
+    pub fn try_duration<'a>(
+        &self,
+        key: impl Into<&'a BStr>,
+    ) -> Option<Result<std::time::Duration, crate::config::duration::Error>> {
+        let key = key.into();
+        let value = self.string(key)?;
+        Some(
+            integer(value.as_ref())
+                .map(|millis| match millis.try_into() {
+                    Ok(millis) => std::time::Duration::from_millis(millis),
+                    Err(_) => std::time::Duration::from_secs(u64::MAX),
+                })
+                .map_err(|err| value_error(key, value.as_ref(), err)),
+        )
+    }
+
+    /// Return the variant out of `variants` whose name matches the value at `key` exactly, or `None` if there is no such value
+    /// or if it doesn't match any of the `variants`.
+    ///
+    /// This is useful for keys that accept one of a set of values, like `push.default` or `core.autocrlf`.
+    pub fn one_of<'a, T: Copy>(&self, key: impl Into<&'a BStr>, variants: &[(&str, T)]) -> Option<T> {
+        self.try_one_of(key, variants).and_then(Result::ok)
+    }
+
+    /// Like [`one_of()`](Self::one_of()), but it will report an error if the value at `key` doesn't match any of the `variants`.
+    pub fn try_one_of<'a, T: Copy>(
+        &self,
+        key: impl Into<&'a BStr>,
+        variants: &[(&str, T)],
+    ) -> Option<Result<T, crate::config::key::GenericErrorWithValue>> {
+        let key = key.into();
+        let value = self.string(key)?;
+        Some(
+            variants
+                .iter()
+                .find_map(|(name, variant)| (name.as_bytes() == value.as_ref().as_bytes()).then_some(*variant))
+                .ok_or_else(|| {
+                    let err =
+                        gix_config::value::Error::new("The value isn't one of the supported variants", value.as_ref());
+                    value_error(key, value.as_ref(), err)
+                }),
+        )
+    }
+
+    /// Return the fully interpolated path at `key`, with `~` and `%(prefix)` expanded, or `None` if there is no such value.
+    /// An error occurs if the path could not be interpolated to its final value.
+    ///
+    /// Use [`trusted_path()`](Self::trusted_path()) to only consider values from trusted files.
+    #[momo]
+    pub fn path<'a>(
+        &self,
+        key: impl Into<&'a BStr>,
+    ) -> Option<Result<Cow<'_, std::path::Path>, gix_config::path::interpolate::Error>> {
+        let path = self.repo.config.resolved.path_by_key(key)?;
+        let install_dir = crate::path::install_dir().ok();
+        let home = self.repo.config.home_dir();
+        let ctx = crate::config::cache::interpolate_context(install_dir.as_deref(), home.as_deref());
+        Some(path.interpolate(ctx))
+    }
+}
+
 impl<'repo> Snapshot<'repo> {
     /// Returns the underlying configuration implementation for a complete API, despite being a little less convenient.
     ///
@@ -105,6 +226,101 @@ impl<'repo> SnapshotMut<'repo> {
         self.commit_inner(repo)
     }
 
+    /// Apply all changes made to this instance like [`commit()`](Self::commit()), but also write them to the configuration
+    /// file of the given `scope`, creating it and its leading directories if needed.
+    ///
+    /// Changes are the values that differ from the configuration of the repository at the time this instance was created,
+    /// with all values of a changed key being replaced in the file. Untouched sections keep their formatting and comments.
+    /// Sources without a location, like [`Cli`](gix_config::Source::Cli), can't be written to and will cause an error.
+    ///
+    /// Like `git config --worktree`, the [`Worktree`](gix_config::Source::Worktree) scope is written to `config.worktree` if
+    /// `extensions.worktreeConfig` is enabled. Otherwise, it's the local configuration file, unless there are multiple worktrees
+    /// which is an error.
+    ///
+    /// Note that nothing is applied if the file couldn't be written, which includes the repository being
+    /// [read-only](crate::open::Options::read_only()).
+    pub fn commit_to(
+        mut self,
+        scope: gix_config::Source,
+    ) -> Result<&'repo mut crate::Repository, crate::config::persist::Error> {
+        let repo = self.repo.take().expect("always present here");
+        repo.assure_writable("write the configuration")?;
+        let (path, scope) = match scope {
+            gix_config::Source::Local => (repo.common_dir().join("config"), scope),
+            gix_config::Source::Worktree => {
+                let (path, source) = repo.worktree_config_location()?;
+                let has_worktrees = || {
+                    repo.worktrees()
+                        .map(|worktrees| !worktrees.is_empty())
+                        .map_err(|source| crate::config::persist::Error::Io {
+                            path: repo.common_dir().join("worktrees"),
+                            source,
+                        })
+                };
+                if source != scope && (repo.git_dir() != repo.common_dir() || has_worktrees()?) {
+                    return Err(crate::config::persist::Error::WorktreeConfigDisabled);
+                }
+                (path, source)
+            }
+            _ => (
+                scope
+                    .storage_location(&mut crate::config::Cache::make_source_env(repo.config.environment))
+                    .ok_or(crate::config::persist::Error::MissingLocation { scope })?
+                    .into_owned(),
+                scope,
+            ),
+        };
+        let mut file = if path.is_file() {
+            gix_config::File::from_path_no_includes(path.clone(), scope)?
+        } else {
+            gix_config::File::new(gix_config::file::Metadata::from(scope).at(&path))
+        };
+
+        let before = values_by_key(&repo.config.resolved);
+        let after = values_by_key(&self.config);
+        let changed = after
+            .iter()
+            .filter(|(id, (_, _, values))| before.get(id).map_or(true, |(_, _, prev)| prev != values))
+            .map(|(id, (section, key, values))| (id, section.as_str(), key, values.as_slice()))
+            .chain(
+                before
+                    .iter()
+                    .filter(|(id, _)| !after.contains_key(id))
+                    .map(|(id, (section, key, _))| (id, section.as_str(), key, &[][..])),
+            );
+        for ((_, subsection, _), section_name, key, values) in changed {
+            let subsection = subsection.as_ref().map(AsRef::as_ref);
+            let ids: Vec<_> = file
+                .sections_and_ids_by_name(section_name)
+                .into_iter()
+                .flatten()
+                .filter_map(|(section, id)| (section.header().subsection_name() == subsection).then_some(id))
+                .collect();
+            for id in ids {
+                let mut section = file.section_mut_by_id(id).expect("id is valid");
+                while section.remove(key.as_ref()).is_some() {}
+            }
+            if !values.is_empty() {
+                let mut section = file.section_mut_or_create_new(section_name, subsection)?;
+                for value in values {
+                    section.push(key.clone(), Some(value.as_ref()));
+                }
+            }
+        }
+
+        let io_err = |source| crate::config::persist::Error::Io {
+            path: path.clone(),
+            source,
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(io_err)?;
+        }
+        let mut lock = gix_lock::File::acquire_to_update_resource(&path, gix_lock::acquire::Fail::Immediately, None)?;
+        file.write_to(&mut lock).map_err(io_err)?;
+        lock.commit().map_err(|err| io_err(err.error))?;
+        Ok(self.commit_inner(repo)?)
+    }
+
     /// Set the value at `key` to `new_value`, possibly creating the section if it doesn't exist yet, or overriding the most recent existing
     /// value, which will be returned.
     #[momo]
@@ -149,6 +365,90 @@ impl<'repo> SnapshotMut<'repo> {
         Ok(current.map(std::borrow::Cow::into_owned))
     }
 
+    /// Append `new_value` to the values at `key` in the given `subsection`, like `git config --add` would, possibly creating the section if it
+    /// doesn't exist yet. This is useful for multi-valued keys like `remote.<name>.fetch`.
+    #[momo]
+    pub fn append_value<'a, 'b>(
+        &mut self,
+        key: &'static dyn crate::config::tree::Key,
+        subsection: Option<&'a BStr>,
+        new_value: impl Into<&'b BStr>,
+    ) -> Result<(), crate::config::set_value::Error> {
+        let value = new_value.into();
+        key.validate(value)?;
+        let name = full_key_name(key, subsection)?;
+        let key = gix_config::parse::key(name.as_bstr()).expect("statically known keys can always be parsed");
+        let value_name = gix_config::parse::section::Key::try_from(key.value_name.to_owned())
+            .expect("statically known keys can always be parsed");
+        self.config
+            .section_mut_or_create_new(key.section_name, key.subsection_name)
+            .map_err(gix_config::file::set_raw_value::Error::from)?
+            .push(value_name, Some(value));
+        Ok(())
+    }
+
+    /// Replace all values at `key` in the given `subsection` that are matched by `pattern` with a single `new_value`, like
+    /// `git config --replace-all` would, and return the number of replaced values.
+    ///
+    /// The new value is placed where the last matching value was, or is appended if there was no match.
+    /// Note that values in all sections of all configuration files of the snapshot are considered.
+    #[momo]
+    pub fn replace_values<'a, 'b>(
+        &mut self,
+        key: &'static dyn crate::config::tree::Key,
+        subsection: Option<&'a BStr>,
+        new_value: impl Into<&'b BStr>,
+        pattern: &crate::config::ValuePattern,
+    ) -> Result<usize, crate::config::set_value::Error> {
+        let value = new_value.into();
+        key.validate(value)?;
+        let name = full_key_name(key, subsection)?;
+        let parsed = gix_config::parse::key(name.as_bstr()).expect("statically known keys can always be parsed");
+        let mut num_matches = 0;
+        for id in section_ids(&self.config, parsed.section_name, parsed.subsection_name)
+            .into_iter()
+            .rev()
+        {
+            let mut section = self.config.section_mut_by_id(id).expect("id is valid");
+            let filter = |value: Option<&BStr>| pattern.matches(value);
+            num_matches += if num_matches == 0 {
+                let value_name = gix_config::parse::section::Key::try_from(parsed.value_name.to_owned())
+                    .expect("statically known keys can always be parsed");
+                section.replace_matching(value_name, value, filter)
+            } else {
+                section.remove_matching(parsed.value_name, filter)
+            };
+        }
+        if num_matches == 0 {
+            self.append_value(key, subsection, value)?;
+        }
+        Ok(num_matches)
+    }
+
+    /// Remove all values at `key` in the given `subsection` that are matched by `pattern`, like `git config --unset-all` would,
+    /// and return the number of removed values.
+    ///
+    /// Note that values in all sections of all configuration files of the snapshot are considered, and that sections are kept
+    /// even if they end up empty.
+    pub fn remove_values(
+        &mut self,
+        key: &'static dyn crate::config::tree::Key,
+        subsection: Option<&BStr>,
+        pattern: &crate::config::ValuePattern,
+    ) -> Result<usize, crate::config::set_value::Error> {
+        let name = full_key_name(key, subsection)?;
+        let key = gix_config::parse::key(name.as_bstr()).expect("statically known keys can always be parsed");
+        let mut num_removed = 0;
+        for id in section_ids(&self.config, key.section_name, key.subsection_name) {
+            num_removed += self
+                .config
+                .section_mut_by_id(id)
+                .expect("id is valid")
+                .remove_matching(key.value_name, |value| pattern.matches(value));
+        }
+        Ok(num_removed)
+    }
+
     pub(crate) fn commit_inner(
         &mut self,
         repo: &'repo mut crate::Repository,
@@ -191,3 +491,85 @@ impl<'repo> CommitAutoRollback<'repo> {
         Ok(repo)
     }
 }
+
+/// Return the full name of `key` in `subsection`, after assuring `subsection` is valid for `key`.
+fn full_key_name(
+    key: &'static dyn crate::config::tree::Key,
+    subsection: Option<&BStr>,
+) -> Result<BString, crate::config::set_value::Error> {
+    use crate::config::tree::SubSectionRequirement;
+    match (key.subsection_requirement(), subsection) {
+        (Some(SubSectionRequirement::Parameter(_)), None) => Err(crate::config::set_value::Error::SubSectionRequired),
+        (Some(SubSectionRequirement::Never), Some(_)) => Err(crate::config::set_value::Error::SubSectionForbidden),
+        _ => Ok(key.full_name(subsection).expect("subsection requirements were checked")),
+    }
+}
+
+/// Return the ids of all sections named `section_name` with the given `subsection`, in order of occurrence.
+fn section_ids(
+    config: &gix_config::File<'static>,
+    section_name: &str,
+    subsection: Option<&BStr>,
+) -> Vec<gix_config::file::SectionId> {
+    config
+        .sections_and_ids_by_name(section_name)
+        .into_iter()
+        .flatten()
+        .filter_map(|(section, id)| (section.header().subsection_name() == subsection).then_some(id))
+        .collect()
+}
+
+/// All values of `config` by section name, subsection name and key, with names in lower-case, along with the
+/// name of the section and the key as they were seen first.
+#[allow(clippy::type_complexity)]
+fn values_by_key(
+    config: &gix_config::File<'static>,
+) -> BTreeMap<(String, Option<BString>, String), (String, gix_config::parse::section::Key<'static>, Vec<BString>)> {
+    let mut out = BTreeMap::<_, (String, gix_config::parse::section::Key<'static>, Vec<BString>)>::new();
+    for section in config.sections() {
+        let header = section.header();
+        let section_name = header
+            .name()
+            .to_str()
+            .expect("section names are valid ascii")
+            .to_owned();
+        let mut seen = BTreeSet::new();
+        for key in section.body().keys() {
+            if !seen.insert(key.as_ref().to_ascii_lowercase()) {
+                continue;
+            }
+            let values = section.body().values(key.as_ref());
+            out.entry((
+                section_name.to_ascii_lowercase(),
+                header.subsection_name().map(ToOwned::to_owned),
+                key.as_ref().to_ascii_lowercase(),
+            ))
+            .or_insert_with(|| (section_name.clone(), key.clone(), Vec::new()))
+            .2
+            .extend(values.into_iter().map(std::borrow::Cow::into_owned));
+        }
+    }
+    out
+}
+
+fn integer(value: &BStr) -> Result<i64, gix_config::value::Error> {
+    gix_config::Integer::try_from(value)?
+        .to_decimal()
+        .ok_or_else(|| gix_config::value::Error::new("Integer overflow", value))
+}
+
+fn value_error<E, const PREFIX: char, const SUFFIX: char>(
+    key: &BStr,
+    value: &BStr,
+    err: E,
+) -> crate::config::key::Error<E, PREFIX, SUFFIX>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    crate::config::key::Error {
+        key: key.to_owned(),
+        value: Some(value.to_owned()),
+        environment_override: None,
+        source: Some(err),
+    }
+}
@@ -0,0 +1,138 @@
+use crate::{
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    config::Snapshot,
+};
+
+/// The expansion of an alias as configured in `alias.<name>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Alias {
+    /// The alias expands to a sub-command, which is the first argument, followed by its arguments.
+    ///
+    /// The configured value was split into arguments like `git` would, respecting single and double quotes
+    /// as well as backslash-escapes.
+    Command(Vec<BString>),
+    /// The alias is a shell command as its value started with `!`, which is to be executed by the shell.
+    Shell {
+        /// The value of the alias without the leading `!`, to be run by the shell verbatim.
+        command: BString,
+        /// Additional arguments to pass to `command`, like `sh -c '<command> "$@"' <command> <args>…` would.
+        args: Vec<BString>,
+    },
+}
+
+/// The error returned by [`Snapshot::alias()`] and [`Snapshot::expand_alias()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The alias '{name}' is empty")]
+    Empty { name: BString },
+    #[error("The alias '{name}' contains an unclosed quote")]
+    UnclosedQuote { name: BString },
+    #[error("The alias '{name}' ends with a backslash")]
+    TrailingBackslash { name: BString },
+    #[error("Alias loop detected: {}", names.iter().map(|n| n.to_str_lossy()).collect::<Vec<_>>().join(" -> "))]
+    Loop { names: Vec<BString> },
+}
+
+impl Snapshot<'_> {
+    /// Return the expansion of the alias `name` as configured in `alias.<name>` of trusted configuration files,
+    /// or `None` if there is no such alias.
+    ///
+    /// Note that aliases are not expanded recursively, use [`expand_alias()`](Self::expand_alias()) for that.
+    pub fn alias(&self, name: &str) -> Result<Option<Alias>, Error> {
+        let config = &self.repo.config.resolved;
+        let value = match config.string_filter("alias", None, name, &mut self.repo.filter_config_section()) {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        if let Some(command) = value.strip_prefix(b"!") {
+            return Ok(Some(Alias::Shell {
+                command: command.into(),
+                args: Vec::new(),
+            }));
+        }
+        let args = split_cmdline(value.as_ref()).map_err(|kind| {
+            let name = name.into();
+            match kind {
+                SplitError::UnclosedQuote => Error::UnclosedQuote { name },
+                SplitError::TrailingBackslash => Error::TrailingBackslash { name },
+            }
+        })?;
+        if args.is_empty() {
+            return Err(Error::Empty { name: name.into() });
+        }
+        Ok(Some(Alias::Command(args)))
+    }
+
+    /// Expand the sub-command in the first of `args` if it is an alias, and return the resulting arguments with the
+    /// remaining `args` appended, or `None` if it isn't an alias.
+    ///
+    /// Aliases expanding to other aliases are expanded recursively, just like `git` does, and loops are detected.
+    /// Note that it's the callers responsibility to not expand sub-commands that are built-in, as `git` will never let aliases
+    /// shadow them.
+    pub fn expand_alias(&self, args: &[BString]) -> Result<Option<Alias>, Error> {
+        let (first, rest) = match args.split_first() {
+            Some(t) => t,
+            None => return Ok(None),
+        };
+        let mut seen = Vec::<BString>::new();
+        let mut name = first.clone();
+        let mut expanded = None::<Vec<BString>>;
+        while let Some(alias) = name.to_str().ok().map(|name| self.alias(name)).transpose()?.flatten() {
+            seen.push(name.clone());
+            let remaining = expanded.as_ref().map_or(rest, |args| &args[1..]).to_vec();
+            match alias {
+                Alias::Shell { command, mut args } => {
+                    args.extend(remaining);
+                    return Ok(Some(Alias::Shell { command, args }));
+                }
+                Alias::Command(mut args) => {
+                    args.extend(remaining);
+                    name = args[0].clone();
+                    if seen.iter().any(|seen| seen.eq_ignore_ascii_case(&name)) {
+                        seen.push(name);
+                        return Err(Error::Loop { names: seen });
+                    }
+                    expanded = Some(args);
+                }
+            }
+        }
+        Ok(expanded.map(Alias::Command))
+    }
+}
+
+enum SplitError {
+    UnclosedQuote,
+    TrailingBackslash,
+}
+
+/// Split `input` into arguments at unquoted whitespace, handling single and double quotes and backslash-escapes
+/// outside of single quotes, similar to `split_cmdline()` in `git`.
+fn split_cmdline(input: &BStr) -> Result<Vec<BString>, SplitError> {
+    let mut out = Vec::new();
+    let mut arg = None::<BString>;
+    let mut quote = None;
+    let mut bytes = input.iter().copied();
+    while let Some(b) = bytes.next() {
+        match b {
+            b if quote.is_none() && b.is_ascii_whitespace() => {
+                out.extend(arg.take());
+            }
+            b'\'' | b'"' if quote.is_none() => {
+                quote = Some(b);
+                arg.get_or_insert_with(Default::default);
+            }
+            b if Some(b) == quote => quote = None,
+            b'\\' if quote != Some(b'\'') => {
+                let escaped = bytes.next().ok_or(SplitError::TrailingBackslash)?;
+                arg.get_or_insert_with(Default::default).push_byte(escaped);
+            }
+            b => arg.get_or_insert_with(Default::default).push_byte(b),
+        }
+    }
+    if quote.is_some() {
+        return Err(SplitError::UnclosedQuote);
+    }
+    out.extend(arg);
+    Ok(out)
+}
@@ -1,6 +1,18 @@
 mod _impls;
 mod access;
 
+///
+pub mod alias;
+
+///
+pub mod origin;
+
+///
+pub mod validate;
+
+///
+pub mod value_pattern;
+
 ///
 #[cfg(feature = "credentials")]
 pub mod credential_helpers;
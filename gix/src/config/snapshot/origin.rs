@@ -0,0 +1,247 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    config::Snapshot,
+};
+
+/// The error returned by [`Snapshot::values_with_origin()`] and [`Snapshot::values_with_origin_by_key()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not read configuration file at '{}'", path.display())]
+    Io { source: std::io::Error, path: PathBuf },
+    #[error("Could not parse configuration file at '{}'", path.display())]
+    Parse {
+        source: gix_config::parse::Error,
+        path: PathBuf,
+    },
+}
+
+/// Where a configuration value was defined.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Origin {
+    /// The source of the configuration containing the value.
+    pub source: gix_config::Source,
+    /// The path to the configuration file containing the value, or `None` if it wasn't read from a file,
+    /// like values provided via the command-line.
+    pub path: Option<PathBuf>,
+    /// The line number at which the key is defined in `path`, starting at 1, or `None` if it isn't known.
+    ///
+    /// This is the case if there is no `path`, or if the value was changed in memory after the file was read.
+    pub line: Option<usize>,
+    /// The paths of the files that lead to `path` being included, starting with the file loaded directly and ending with
+    /// the one including `path`. It's empty if `path` wasn't included.
+    pub included_from: Vec<PathBuf>,
+}
+
+impl Origin {
+    /// Return the name of the scope of the value like `git config --show-scope` would, like `local` or `global`.
+    pub fn scope(&self) -> &'static str {
+        use gix_config::Source::*;
+        match self.source {
+            GitInstallation | System => "system",
+            Git | User => "global",
+            Local => "local",
+            Worktree => "worktree",
+            Env | Cli | Api | EnvOverride => "command",
+        }
+    }
+}
+
+/// Display the origin like `git config --show-origin` would, like `file:.git/config` or `command line:`.
+impl std::fmt::Display for Origin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "file:{}", path.display()),
+            None => f.write_str("command line:"),
+        }
+    }
+}
+
+/// A configuration value along with its origin.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Value {
+    /// The full name of the key, like `core.bare` or `remote.origin.url`, with the section and sub-section name as they were
+    /// written.
+    pub key: BString,
+    /// The value of the key, or `None` if it was implicitly set without `=`, like `key` is equivalent to `key = true`.
+    pub value: Option<BString>,
+    /// Where the value was defined.
+    pub origin: Origin,
+}
+
+impl Snapshot<'_> {
+    /// Return all configuration values in order of occurrence along with their origin, similar to
+    /// `git config --list --show-origin --show-scope`.
+    ///
+    /// Note that configuration files are read from disk once more to learn the line numbers of their values.
+    pub fn values_with_origin(&self) -> Result<Vec<Value>, Error> {
+        self.values_with_origin_inner(None)
+    }
+
+    /// Return all values of `key`, like `remote.origin.url`, in order of occurrence along with their origin, similar to
+    /// `git config --get-all --show-origin --show-scope <key>`. The last value is the one that is effective for single-valued keys.
+    ///
+    /// Note that configuration files are read from disk once more to learn the line numbers of their values.
+    pub fn values_with_origin_by_key<'a>(&self, key: impl Into<&'a BStr>) -> Result<Vec<Value>, Error> {
+        let key = key.into();
+        match gix_config::parse::key(key) {
+            Some(key) => self.values_with_origin_inner(Some(key)),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn values_with_origin_inner(&self, filter: Option<gix_config::parse::Key<'_>>) -> Result<Vec<Value>, Error> {
+        let mut out = Vec::new();
+        let mut sections_by_path = HashMap::<&Path, Vec<FileSection>>::new();
+        let mut cursor_by_meta = HashMap::<*const gix_config::file::Metadata, usize>::new();
+        let mut include_stack = Vec::<Option<PathBuf>>::new();
+        for section in self.repo.config.resolved.sections() {
+            let meta = section.meta();
+            let level = usize::from(meta.level);
+            include_stack.truncate(level);
+            let included_from: Vec<_> = include_stack.iter().flatten().cloned().collect();
+            include_stack.push(meta.path.clone());
+
+            let (name, subsection) = (section.header().name(), section.header().subsection_name());
+            let file_section = match meta.path.as_deref() {
+                Some(path) => {
+                    if !sections_by_path.contains_key(path) {
+                        sections_by_path.insert(path, read_sections(path)?);
+                    }
+                    let cursor = cursor_by_meta.entry(meta as *const _).or_default();
+                    let file_section = sections_by_path[path].get(*cursor).filter(|s| {
+                        s.name.eq_ignore_ascii_case(name) && s.subsection.as_ref().map(AsRef::as_ref) == subsection
+                    });
+                    *cursor += 1;
+                    file_section
+                }
+                None => None,
+            };
+
+            if let Some(filter) = &filter {
+                if !(name.eq_ignore_ascii_case(filter.section_name.as_bytes()) && subsection == filter.subsection_name)
+                {
+                    continue;
+                }
+            }
+            let entries = section.body().entries();
+            let lines: Option<Vec<_>> = file_section
+                .filter(|s| {
+                    s.values.len() == entries.len()
+                        && s.values
+                            .iter()
+                            .zip(entries.iter())
+                            .all(|(file_value, (key, _))| file_value.key.eq_ignore_ascii_case(key.as_ref().as_bytes()))
+                })
+                .map(|s| s.values.iter().map(|v| v.line).collect());
+            for (idx, (key, value)) in entries.into_iter().enumerate() {
+                if let Some(filter) = &filter {
+                    if !key.as_ref().eq_ignore_ascii_case(filter.value_name) {
+                        continue;
+                    }
+                }
+                out.push(Value {
+                    key: full_name(name, subsection, key.as_ref()),
+                    value: value.map(std::borrow::Cow::into_owned),
+                    origin: Origin {
+                        source: meta.source,
+                        path: meta.path.clone(),
+                        line: lines.as_ref().map(|lines| lines[idx]),
+                        included_from: included_from.clone(),
+                    },
+                });
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// A section as read from a configuration file.
+pub(crate) struct FileSection {
+    pub name: BString,
+    pub subsection: Option<BString>,
+    pub values: Vec<FileValue>,
+}
+
+/// A value as read from a configuration file.
+pub(crate) struct FileValue {
+    pub key: BString,
+    /// The line at which the key is defined, starting at 1.
+    pub line: usize,
+    /// The normalized value, or `None` if the key has no value.
+    pub value: Option<BString>,
+}
+
+/// Read the configuration file at `path` without resolving includes, and return all of its sections in order.
+pub(crate) fn read_sections(path: &Path) -> Result<Vec<FileSection>, Error> {
+    use gix_config::parse::Event;
+
+    let buf = std::fs::read(path).map_err(|err| Error::Io {
+        source: err,
+        path: path.to_owned(),
+    })?;
+    let events = gix_config::parse::Events::from_bytes(&buf, None).map_err(|err| Error::Parse {
+        source: err,
+        path: path.to_owned(),
+    })?;
+
+    let mut out = Vec::<FileSection>::new();
+    let mut line = 1;
+    let mut has_separator = false;
+    for event in events.into_iter() {
+        match event {
+            Event::SectionHeader(header) => out.push(FileSection {
+                name: header.name().to_owned(),
+                subsection: header.subsection_name().map(ToOwned::to_owned),
+                values: Vec::new(),
+            }),
+            Event::SectionKey(key) => {
+                has_separator = false;
+                if let Some(section) = out.last_mut() {
+                    let key: &str = key.as_ref();
+                    section.values.push(FileValue {
+                        key: key.into(),
+                        line,
+                        value: None,
+                    });
+                }
+            }
+            Event::KeyValueSeparator => has_separator = true,
+            Event::Value(value) | Event::ValueNotDone(value) | Event::ValueDone(value) => {
+                if let Some(pending) = out.last_mut().and_then(|section| section.values.last_mut()) {
+                    if has_separator {
+                        pending
+                            .value
+                            .get_or_insert_with(Default::default)
+                            .push_str(value.as_ref());
+                    }
+                }
+            }
+            Event::Newline(newlines) => line += newlines.find_iter("\n").count(),
+            Event::Comment(_) | Event::Whitespace(_) => {}
+        }
+    }
+    for value in out.iter_mut().flat_map(|section| section.values.iter_mut()) {
+        if let Some(v) = value.value.take() {
+            value.value = Some(gix_config::value::normalize_bstring(v).into_owned());
+        }
+    }
+    Ok(out)
+}
+
+/// Return the full name of `key` in `section` and `subsection`.
+pub(crate) fn full_name(section: &BStr, subsection: Option<&BStr>, key: &str) -> BString {
+    let mut buf = BString::from(section);
+    if let Some(subsection) = subsection {
+        buf.push_byte(b'.');
+        buf.push_str(subsection);
+    }
+    buf.push_byte(b'.');
+    buf.push_str(key);
+    buf
+}
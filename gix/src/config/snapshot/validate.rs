@@ -0,0 +1,142 @@
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    config::{
+        snapshot::origin::{self, full_name, read_sections},
+        tree::Note,
+        Snapshot, Tree,
+    },
+};
+
+/// The error returned by [`Snapshot::validate()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not read configuration file at '{}'", path.display())]
+    Io { source: std::io::Error, path: PathBuf },
+    #[error("Could not parse configuration file at '{}'", path.display())]
+    Parse {
+        source: gix_config::parse::Error,
+        path: PathBuf,
+    },
+}
+
+/// The kind of problem found with a configuration value.
+#[derive(Debug)]
+pub enum Kind {
+    /// The section of the key isn't known to the [configuration tree](Tree).
+    UnknownSection,
+    /// The section is known, but the key isn't.
+    UnknownKey,
+    /// The value isn't valid for the key.
+    InvalidValue(crate::config::tree::key::validate::Error),
+    /// The key is deprecated, with the message explaining what to use instead.
+    Deprecated(&'static str),
+}
+
+/// A problem with a configuration value, along with where it was defined.
+#[derive(Debug)]
+pub struct Diagnostic {
+    /// The full name of the key, like `core.bare` or `remote.origin.url`.
+    pub key: BString,
+    /// The value of the key, or `None` if it was implicitly set without `=`, like `key` is equivalent to `key = true`.
+    pub value: Option<BString>,
+    /// The kind of problem.
+    pub kind: Kind,
+    /// The source of the configuration containing the value.
+    pub source: gix_config::Source,
+    /// The path to the configuration file containing the value, or `None` if it wasn't read from a file,
+    /// like values provided via the command-line.
+    pub path: Option<PathBuf>,
+    /// The line number at which the key is defined in `path`, starting at 1.
+    pub line: Option<usize>,
+}
+
+impl Snapshot<'_> {
+    /// Validate all configuration values against the [configuration tree](Tree), and return diagnostics for keys that aren't known
+    /// to it, for invalid values and for deprecated keys, in order of occurrence.
+    ///
+    /// Note that configuration files are read from disk once more to learn the line numbers of their values. As the tree only
+    /// contains keys that are used by `gitoxide`, unknown keys may still be valid for `git`.
+    pub fn validate(&self) -> Result<Vec<Diagnostic>, Error> {
+        let mut out = Vec::new();
+        let mut seen_paths = Vec::<&Path>::new();
+        for section in self.repo.config.resolved.sections() {
+            let meta = section.meta();
+            match meta.path.as_deref() {
+                Some(path) => {
+                    if seen_paths.contains(&path) {
+                        continue;
+                    }
+                    seen_paths.push(path);
+                    validate_file(path, meta.source, &mut out)?;
+                }
+                None => {
+                    let (name, subsection) = (section.header().name(), section.header().subsection_name());
+                    for (key, value) in section.body().entries() {
+                        let value = value.map(Cow::into_owned);
+                        for kind in diagnose(name, subsection, key.as_ref(), value.as_ref()) {
+                            out.push(Diagnostic {
+                                key: full_name(name, subsection, key.as_ref()),
+                                value: value.clone(),
+                                kind,
+                                source: meta.source,
+                                path: None,
+                                line: None,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+fn validate_file(path: &Path, source: gix_config::Source, out: &mut Vec<Diagnostic>) -> Result<(), Error> {
+    let sections = read_sections(path).map_err(|err| match err {
+        origin::Error::Io { source, path } => Error::Io { source, path },
+        origin::Error::Parse { source, path } => Error::Parse { source, path },
+    })?;
+    for section in &sections {
+        let (name, subsection) = (section.name.as_ref(), section.subsection.as_ref().map(AsRef::as_ref));
+        for value in &section.values {
+            let key = value.key.to_str_lossy();
+            for kind in diagnose(name, subsection, &key, value.value.as_ref()) {
+                out.push(Diagnostic {
+                    key: full_name(name, subsection, &key),
+                    value: value.value.clone(),
+                    kind,
+                    source,
+                    path: Some(path.to_owned()),
+                    line: Some(value.line),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+fn diagnose(section: &BStr, subsection: Option<&BStr>, key: &str, value: Option<&BString>) -> Vec<Kind> {
+    let tree = Tree;
+    let section = section.to_str_lossy();
+    let key = match tree.find_key(&section, subsection, key) {
+        Some(key) => key,
+        None if tree.sections().iter().any(|s| s.name().eq_ignore_ascii_case(&section)) => {
+            return vec![Kind::UnknownKey]
+        }
+        None => return vec![Kind::UnknownSection],
+    };
+    let mut out = Vec::new();
+    if let Some(Note::Deprecated(message)) = key.note() {
+        out.push(Kind::Deprecated(message));
+    }
+    if let Some(err) = value.and_then(|value| key.validate(value.as_ref()).err()) {
+        out.push(Kind::InvalidValue(err));
+    }
+    out
+}
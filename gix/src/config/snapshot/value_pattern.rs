@@ -0,0 +1,76 @@
+use crate::bstr::{BStr, BString};
+
+/// A pattern to select values of multi-valued keys, with semantics identical to the `value-pattern` of `git config`.
+#[derive(Debug, Clone)]
+pub struct ValuePattern {
+    inner: Inner,
+}
+
+#[derive(Debug, Clone)]
+enum Inner {
+    All,
+    Fixed(BString),
+    #[cfg(feature = "regex")]
+    Regex {
+        regex: regex::bytes::Regex,
+        negated: bool,
+    },
+}
+
+/// The error returned by [`ValuePattern::regex()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+#[cfg(feature = "regex")]
+pub enum Error {
+    #[error("The value pattern '{pattern}' is not a valid regular expression")]
+    Regex { source: regex::Error, pattern: String },
+}
+
+/// Initialization
+impl ValuePattern {
+    /// A pattern that matches all values.
+    pub fn all() -> Self {
+        ValuePattern { inner: Inner::All }
+    }
+
+    /// A pattern that matches values that are exactly `value`, like `git config --fixed-value` would.
+    ///
+    /// Keys without a value, like `key` in contrast to `key = value`, are matched by an empty `value`.
+    pub fn fixed(value: impl Into<BString>) -> Self {
+        ValuePattern {
+            inner: Inner::Fixed(value.into()),
+        }
+    }
+
+    /// A pattern that matches values in which the regular expression `pattern` can be found, or that are not matched by it
+    /// if it starts with `!`.
+    ///
+    /// Just like in `git`, keys without a value, like `key` in contrast to `key = value`, are only matched by negated patterns.
+    #[cfg(feature = "regex")]
+    pub fn regex(pattern: &str) -> Result<Self, Error> {
+        let (negated, regex) = match pattern.strip_prefix('!') {
+            Some(regex) => (true, regex),
+            None => (false, pattern),
+        };
+        let regex = regex::bytes::Regex::new(regex).map_err(|err| Error::Regex {
+            source: err,
+            pattern: pattern.into(),
+        })?;
+        Ok(ValuePattern {
+            inner: Inner::Regex { regex, negated },
+        })
+    }
+}
+
+/// Matching
+impl ValuePattern {
+    /// Return `true` if `value` is matched by this pattern, with `None` indicating a key without value.
+    pub fn matches(&self, value: Option<&BStr>) -> bool {
+        match &self.inner {
+            Inner::All => true,
+            Inner::Fixed(fixed) => value.unwrap_or_default() == fixed,
+            #[cfg(feature = "regex")]
+            Inner::Regex { regex, negated } => *negated ^ value.map_or(false, |value| regex.is_match(value)),
+        }
+    }
+}
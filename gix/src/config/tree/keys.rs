@@ -83,6 +83,12 @@ impl<T: Validate> Any<T> {
         self.note = Some(Note::Deviation(message));
         self
     }
+
+    /// Mark this key as deprecated, with `message` informing about what to use instead.
+    pub const fn with_deprecation(mut self, message: &'static str) -> Self {
+        self.note = Some(Note::Deprecated(message));
+        self
+    }
 }
 
 /// Conversion
@@ -144,6 +150,13 @@ impl<T: Validate> Key for Any<T> {
     fn note(&self) -> Option<&Note> {
         self.note.as_ref()
     }
+
+    fn value_type(&self) -> &str {
+        match std::any::type_name::<T>().rsplit("::").next() {
+            Some("All") | None => "Any",
+            Some(name) => name,
+        }
+    }
 }
 
 /// A key which represents a date.
@@ -158,6 +171,9 @@ pub type DurationInMilliseconds = Any<validate::DurationInMilliseconds>;
 /// A key which represents any unsigned integer.
 pub type UnsignedInteger = Any<validate::UnsignedInteger>;
 
+/// A key which represents a zlib compression level, like `core.compression`.
+pub type CompressionLevel = Any<validate::CompressionLevel>;
+
 /// A key that represents a remote name, either as url or symbolic name.
 pub type RemoteName = Any<validate::RemoteName>;
 
@@ -321,6 +337,30 @@ impl Path {
     }
 }
 
+mod compression_level {
+    use crate::config::tree::{keys::CompressionLevel, Section};
+
+    impl CompressionLevel {
+        /// Create a new instance.
+        pub const fn new_compression_level(name: &'static str, section: &'static dyn Section) -> Self {
+            Self::new_with_validate(name, section, super::validate::CompressionLevel)
+        }
+
+        /// Convert `value` into a zlib compression level between 0 and 9, where `-1` is the zlib default of 6.
+        pub fn try_into_compression_level(
+            &'static self,
+            value: Result<i64, gix_config::value::Error>,
+        ) -> Result<u32, crate::config::key::GenericError> {
+            let value = value.map_err(|err| crate::config::key::GenericError::from(self).with_source(err))?;
+            Ok(match value {
+                -1 => 6,
+                0..=9 => value as u32,
+                _ => return Err(crate::config::key::GenericError::from(self)),
+            })
+        }
+    }
+}
+
 mod workers {
     use crate::config::tree::{keys::UnsignedInteger, Section};
 
@@ -517,6 +557,22 @@ pub mod validate {
         }
     }
 
+    /// Assure that values are valid zlib compression levels.
+    #[derive(Default)]
+    pub struct CompressionLevel;
+
+    impl Validate for CompressionLevel {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
+            let value = gix_config::Integer::try_from(value)?
+                .to_decimal()
+                .ok_or_else(|| format!("integer {value} cannot be represented as `i64`"))?;
+            if !(-1..=9).contains(&value) {
+                return Err(format!("compression level {value} must be between -1 and 9").into());
+            }
+            Ok(())
+        }
+    }
+
     /// Assure that values that parse as git booleans are valid.
     #[derive(Default)]
     pub struct Boolean;
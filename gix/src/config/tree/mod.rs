@@ -24,6 +24,8 @@ pub(crate) mod root {
         pub const CHECKOUT: sections::Checkout = sections::Checkout;
         /// The `clone` section.
         pub const CLONE: sections::Clone = sections::Clone;
+        /// The `commit` section.
+        pub const COMMIT: sections::Commit = sections::Commit;
         /// The `committer` section.
         pub const COMMITTER: sections::Committer = sections::Committer;
         /// The `core` section.
@@ -37,8 +39,12 @@ pub(crate) mod root {
         pub const EXTENSIONS: sections::Extensions = sections::Extensions;
         /// The `fetch` section.
         pub const FETCH: sections::Fetch = sections::Fetch;
+        /// The `gc` section.
+        pub const GC: sections::Gc = sections::Gc;
         /// The `gitoxide` section.
         pub const GITOXIDE: sections::Gitoxide = sections::Gitoxide;
+        /// The `gpg` section.
+        pub const GPG: sections::Gpg = sections::Gpg;
         /// The `http` section.
         pub const HTTP: sections::Http = sections::Http;
         /// The `index` section.
@@ -51,10 +57,19 @@ pub(crate) mod root {
         pub const PROTOCOL: sections::Protocol = sections::Protocol;
         /// The `remote` section.
         pub const REMOTE: sections::Remote = sections::Remote;
+        /// The `repack` section.
+        pub const REPACK: sections::Repack = sections::Repack;
         /// The `safe` section.
         pub const SAFE: sections::Safe = sections::Safe;
         /// The `ssh` section.
         pub const SSH: sections::Ssh = sections::Ssh;
+        /// The `status` section.
+        #[cfg(feature = "status")]
+        pub const STATUS: sections::Status = sections::Status;
+        /// The `submodule` section.
+        pub const SUBMODULE: sections::Submodule = sections::Submodule;
+        /// The `transfer` section.
+        pub const TRANSFER: sections::Transfer = sections::Transfer;
         /// The `user` section.
         pub const USER: sections::User = sections::User;
         /// The `url` section.
@@ -67,6 +82,7 @@ pub(crate) mod root {
                 &Self::BRANCH,
                 &Self::CHECKOUT,
                 &Self::CLONE,
+                &Self::COMMIT,
                 &Self::COMMITTER,
                 &Self::CORE,
                 &Self::CREDENTIAL,
@@ -74,15 +90,22 @@ pub(crate) mod root {
                 &Self::DIFF,
                 &Self::EXTENSIONS,
                 &Self::FETCH,
+                &Self::GC,
                 &Self::GITOXIDE,
+                &Self::GPG,
                 &Self::HTTP,
                 &Self::INDEX,
                 &Self::INIT,
                 &Self::PACK,
                 &Self::PROTOCOL,
                 &Self::REMOTE,
+                &Self::REPACK,
                 &Self::SAFE,
                 &Self::SSH,
+                #[cfg(feature = "status")]
+                &Self::STATUS,
+                &Self::SUBMODULE,
+                &Self::TRANSFER,
                 &Self::USER,
                 &Self::URL,
             ]
@@ -92,16 +115,21 @@ pub(crate) mod root {
 
 mod sections;
 pub use sections::{
-    branch, checkout, core, credential, extensions, fetch, gitoxide, http, index, protocol, remote, ssh, Author,
-    Branch, Checkout, Clone, Committer, Core, Credential, Extensions, Fetch, Gitoxide, Http, Index, Init, Pack,
-    Protocol, Remote, Safe, Ssh, Url, User,
+    branch, checkout, core, credential, extensions, fetch, gc, gitoxide, gpg, http, index, protocol, remote, ssh,
+    Author, Branch, Checkout, Clone, Commit, Committer, Core, Credential, Extensions, Fetch, Gc, Gitoxide, Gpg, Http,
+    Index, Init, Pack, Protocol, Remote, Repack, Safe, Ssh, Submodule, Transfer, Url, User,
 };
 #[cfg(feature = "blob-diff")]
 pub use sections::{diff, Diff};
+#[cfg(feature = "status")]
+pub use sections::{status, Status};
 
 /// Generic value implementations for static instantiation.
 pub mod keys;
 
+///
+pub mod schema;
+
 ///
 pub mod key {
     ///
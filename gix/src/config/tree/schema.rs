@@ -0,0 +1,112 @@
+use crate::{
+    bstr::BStr,
+    config::{
+        tree::{Key, Link, Note, Section, SubSectionRequirement},
+        Tree,
+    },
+};
+
+/// A machine-readable description of a key known to the [configuration tree](Tree).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Entry {
+    /// The logical name of the key, like `core.bare` or `remote.<name>.url`.
+    pub name: String,
+    /// The type of value the key expects, see [`Key::value_type()`].
+    pub value_type: String,
+    /// The environment variable which overrides the value of this key, if any.
+    pub environment_override: Option<String>,
+    /// The logical name of the key which is used if this key isn't set, if any.
+    pub fallback: Option<String>,
+    /// Additional information about the key.
+    pub note: Option<String>,
+    /// An explanation of how the key is interpreted differently than in `git`.
+    pub deviation: Option<String>,
+    /// If set, the key is deprecated and this explains what to use instead.
+    pub deprecation: Option<String>,
+}
+
+impl Entry {
+    fn from_key(key: &dyn Key) -> Self {
+        let (mut note, mut deviation, mut deprecation) = (None, None, None);
+        match key.note() {
+            Some(Note::Informative(message)) => note = Some(message.to_string()),
+            Some(Note::Deviation(message)) => deviation = Some(message.to_string()),
+            Some(Note::Deprecated(message)) => deprecation = Some(message.to_string()),
+            None => {}
+        }
+        Entry {
+            name: key.logical_name(),
+            value_type: key.value_type().to_owned(),
+            environment_override: key.environment_override().map(ToOwned::to_owned),
+            fallback: key.link().and_then(|link| match link {
+                Link::FallbackKey(key) => Some(key.logical_name()),
+                Link::EnvironmentOverride(_) => None,
+            }),
+            note,
+            deviation,
+            deprecation,
+        }
+    }
+}
+
+impl Tree {
+    /// Return a description of all keys known to this tree, sorted by their logical name.
+    pub fn schema(&self) -> Vec<Entry> {
+        let mut out: Vec<_> = self
+            .sections()
+            .iter()
+            .flat_map(|section| {
+                section
+                    .sub_sections()
+                    .iter()
+                    .flat_map(|sub_section| sub_section.keys())
+                    .chain(section.keys())
+                    .map(|key| Entry::from_key(*key))
+            })
+            .collect();
+        out.sort_by(|a, b| a.name.cmp(&b.name));
+        out.dedup_by(|a, b| a.name == b.name);
+        out
+    }
+
+    /// Find the key named `key` in `section` and `subsection`, or return `None` if it isn't known.
+    ///
+    /// Section and key names are compared case-insensitively, and `subsection` is either the name of a statically known
+    /// sub-section, like `core` in `gitoxide.core.useNsec`, or a parameter, like `origin` in `remote.origin.url`.
+    pub fn find_key(&self, section: &str, subsection: Option<&BStr>, key: &str) -> Option<&dyn Key> {
+        fn find<'a>(keys: &'a [&'a dyn Key], name: &str) -> Option<&'a dyn Key> {
+            keys.iter().find(|key| key.name().eq_ignore_ascii_case(name)).copied()
+        }
+        fn is_parameter(section: &dyn Section) -> bool {
+            section.name().starts_with('<')
+        }
+
+        let section = self
+            .sections()
+            .iter()
+            .find(|s| s.name().eq_ignore_ascii_case(section))?;
+        match subsection {
+            Some(subsection) => {
+                if let Some(sub_section) = section
+                    .sub_sections()
+                    .iter()
+                    .find(|s| !is_parameter(**s) && s.name().as_bytes() == subsection.as_ref() as &[u8])
+                {
+                    return find(sub_section.keys(), key);
+                }
+                section
+                    .sub_sections()
+                    .iter()
+                    .filter(|s| is_parameter(**s))
+                    .find_map(|s| find(s.keys(), key))
+                    .or_else(|| {
+                        find(section.keys(), key)
+                            .filter(|key| !matches!(key.subsection_requirement(), Some(SubSectionRequirement::Never)))
+                    })
+            }
+            None => find(section.keys(), key)
+                .filter(|key| !matches!(key.subsection_requirement(), Some(SubSectionRequirement::Parameter(_)))),
+        }
+    }
+}
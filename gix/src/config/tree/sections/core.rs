@@ -11,11 +11,25 @@ impl Core {
     /// The `core.checkStat` key.
     pub const CHECK_STAT: CheckStat =
         CheckStat::new_with_validate("checkStat", &config::Tree::CORE, validate::CheckStat);
+    /// The `core.compression` key.
+    pub const COMPRESSION: keys::CompressionLevel =
+        keys::CompressionLevel::new_compression_level("compression", &config::Tree::CORE);
+    /// The `core.looseCompression` key.
+    pub const LOOSE_COMPRESSION: keys::CompressionLevel =
+        keys::CompressionLevel::new_compression_level("looseCompression", &config::Tree::CORE);
     /// The `core.deltaBaseCacheLimit` key.
     pub const DELTA_BASE_CACHE_LIMIT: keys::UnsignedInteger =
         keys::UnsignedInteger::new_unsigned_integer("deltaBaseCacheLimit", &config::Tree::CORE)
             .with_environment_override("GITOXIDE_PACK_CACHE_MEMORY")
             .with_note("if unset, we default to a small 64 slot fixed-size cache that holds at most 64 full delta base objects of any size. Set to 0 to deactivate it entirely");
+    /// The `core.packedGitLimit` key.
+    pub const PACKED_GIT_LIMIT: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("packedGitLimit", &config::Tree::CORE)
+            .with_deviation("packs are mapped in their entirety, and the least recently used ones are closed once the total size of all mapped packs exceeds the limit. If unset, there is no limit");
+    /// The `core.packedGitWindowSize` key.
+    pub const PACKED_GIT_WINDOW_SIZE: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("packedGitWindowSize", &config::Tree::CORE)
+            .with_deviation("packs are always mapped in their entirety, so there are no windows and this value is ignored. Use `core.packedGitLimit` to limit the amount of mapped pack data instead");
     /// The `core.disambiguate` key.
     pub const DISAMBIGUATE: Disambiguate =
         Disambiguate::new_with_validate("disambiguate", &config::Tree::CORE, validate::Disambiguate);
@@ -42,6 +56,11 @@ impl Core {
     /// The `core.repositoryFormatVersion` key.
     pub const REPOSITORY_FORMAT_VERSION: keys::UnsignedInteger =
         keys::UnsignedInteger::new_unsigned_integer("repositoryFormatVersion", &config::Tree::CORE);
+    /// The `core.sparseCheckout` key.
+    pub const SPARSE_CHECKOUT: keys::Boolean = keys::Boolean::new_boolean("sparseCheckout", &config::Tree::CORE);
+    /// The `core.sparseCheckoutCone` key.
+    pub const SPARSE_CHECKOUT_CONE: keys::Boolean =
+        keys::Boolean::new_boolean("sparseCheckoutCone", &config::Tree::CORE);
     /// The `core.symlinks` key.
     pub const SYMLINKS: keys::Boolean = keys::Boolean::new_boolean("symlinks", &config::Tree::CORE);
     /// The `core.trustCTime` key.
@@ -60,12 +79,17 @@ impl Core {
     pub const ATTRIBUTES_FILE: keys::Executable =
         keys::Executable::new_executable("attributesFile", &config::Tree::CORE)
             .with_deviation("for checkout - it's already queried but needs building of attributes group, and of course support during checkout");
+    /// The `core.hooksPath` key.
+    pub const HOOKS_PATH: keys::Path = keys::Path::new_path("hooksPath", &config::Tree::CORE);
     /// The `core.sshCommand` key.
     pub const SSH_COMMAND: keys::Executable = keys::Executable::new_executable("sshCommand", &config::Tree::CORE)
         .with_environment_override("GIT_SSH_COMMAND");
     /// The `core.useReplaceRefs` key.
     pub const USE_REPLACE_REFS: keys::Boolean = keys::Boolean::new_boolean("useReplaceRefs", &config::Tree::CORE)
         .with_environment_override("GIT_NO_REPLACE_OBJECTS");
+    /// The `core.notesRef` key.
+    pub const NOTES_REF: keys::Any =
+        keys::Any::new("notesRef", &config::Tree::CORE).with_environment_override("GIT_NOTES_REF");
     /// The `core.commitGraph` key.
     pub const COMMIT_GRAPH: keys::Boolean = keys::Boolean::new_boolean("commitGraph", &config::Tree::CORE);
     /// The `core.safecrlf` key.
@@ -96,7 +120,11 @@ impl Section for Core {
             &Self::ABBREV,
             &Self::BARE,
             &Self::CHECK_STAT,
+            &Self::COMPRESSION,
+            &Self::LOOSE_COMPRESSION,
             &Self::DELTA_BASE_CACHE_LIMIT,
+            &Self::PACKED_GIT_LIMIT,
+            &Self::PACKED_GIT_WINDOW_SIZE,
             &Self::DISAMBIGUATE,
             &Self::FILE_MODE,
             &Self::IGNORE_CASE,
@@ -106,14 +134,18 @@ impl Section for Core {
             &Self::LOG_ALL_REF_UPDATES,
             &Self::PRECOMPOSE_UNICODE,
             &Self::REPOSITORY_FORMAT_VERSION,
+            &Self::SPARSE_CHECKOUT,
+            &Self::SPARSE_CHECKOUT_CONE,
             &Self::SYMLINKS,
             &Self::TRUST_C_TIME,
             &Self::WORKTREE,
             &Self::ASKPASS,
             &Self::EXCLUDES_FILE,
             &Self::ATTRIBUTES_FILE,
+            &Self::HOOKS_PATH,
             &Self::SSH_COMMAND,
             &Self::USE_REPLACE_REFS,
+            &Self::NOTES_REF,
             &Self::COMMIT_GRAPH,
             #[cfg(feature = "attributes")]
             &Self::SAFE_CRLF,
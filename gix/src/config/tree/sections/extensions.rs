@@ -8,12 +8,17 @@ impl Extensions {
     pub const WORKTREE_CONFIG: keys::Boolean = keys::Boolean::new_boolean("worktreeConfig", &config::Tree::EXTENSIONS);
     /// The `extensions.objectFormat` key.
     pub const OBJECT_FORMAT: ObjectFormat =
-        ObjectFormat::new_with_validate("objectFormat", &config::Tree::EXTENSIONS, validate::ObjectFormat).with_note(
-            "Support for SHA256 is prepared but not fully implemented yet. For now we abort when encountered",
-        );
+        ObjectFormat::new_with_validate("objectFormat", &config::Tree::EXTENSIONS, validate::ObjectFormat);
+    /// The `extensions.compatObjectFormat` key, the hash to additionally maintain object ids for to interoperate with
+    /// repositories that use it as their `extensions.objectFormat`.
+    pub const COMPAT_OBJECT_FORMAT: ObjectFormat =
+        ObjectFormat::new_with_validate("compatObjectFormat", &config::Tree::EXTENSIONS, validate::ObjectFormat);
+    /// The `extensions.partialClone` key, naming the remote that promises to provide missing objects.
+    pub const PARTIAL_CLONE: keys::RemoteName =
+        keys::RemoteName::new_remote_name("partialClone", &config::Tree::EXTENSIONS);
 }
 
-/// The `core.checkStat` key.
+/// The `extensions.objectFormat` key.
 pub type ObjectFormat = keys::Any<validate::ObjectFormat>;
 
 mod object_format {
@@ -28,6 +33,8 @@ mod object_format {
         ) -> Result<gix_hash::Kind, config::key::GenericErrorWithValue> {
             if value.as_ref().eq_ignore_ascii_case(b"sha1") {
                 Ok(gix_hash::Kind::Sha1)
+            } else if value.as_ref().eq_ignore_ascii_case(b"sha256") {
+                Ok(gix_hash::Kind::Sha256)
             } else {
                 Err(config::key::GenericErrorWithValue::from_value(self, value.into_owned()))
             }
@@ -41,7 +48,12 @@ impl Section for Extensions {
     }
 
     fn keys(&self) -> &[&dyn Key] {
-        &[&Self::OBJECT_FORMAT, &Self::WORKTREE_CONFIG]
+        &[
+            &Self::OBJECT_FORMAT,
+            &Self::COMPAT_OBJECT_FORMAT,
+            &Self::WORKTREE_CONFIG,
+            &Self::PARTIAL_CLONE,
+        ]
     }
 }
 
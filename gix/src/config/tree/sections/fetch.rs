@@ -14,6 +14,10 @@ impl Fetch {
     #[cfg(feature = "attributes")]
     pub const RECURSE_SUBMODULES: RecurseSubmodules =
         RecurseSubmodules::new_with_validate("recurseSubmodules", &config::Tree::FETCH, validate::RecurseSubmodules);
+    /// The `fetch.unpackLimit` key.
+    pub const UNPACK_LIMIT: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("unpackLimit", &config::Tree::FETCH)
+            .with_note("falls back to 'transfer.unpackLimit', and packs are always kept if neither is set");
 }
 
 impl Section for Fetch {
@@ -26,6 +30,7 @@ impl Section for Fetch {
             &Self::NEGOTIATION_ALGORITHM,
             #[cfg(feature = "attributes")]
             &Self::RECURSE_SUBMODULES,
+            &Self::UNPACK_LIMIT,
         ]
     }
 }
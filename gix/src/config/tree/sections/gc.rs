@@ -0,0 +1,77 @@
+use crate::{
+    config,
+    config::tree::{keys, Gc, Key, Section},
+};
+
+impl Gc {
+    /// The `gc.cruftPacks` key.
+    pub const CRUFT_PACKS: keys::Boolean = keys::Boolean::new_boolean("cruftPacks", &config::Tree::GC);
+    /// The `gc.pruneExpire` key.
+    pub const PRUNE_EXPIRE: Expiry = Expiry::new_with_validate("pruneExpire", &config::Tree::GC, validate::Expiry);
+    /// The `gc.reflogExpire` key.
+    pub const REFLOG_EXPIRE: Expiry = Expiry::new_with_validate("reflogExpire", &config::Tree::GC, validate::Expiry);
+}
+
+impl Section for Gc {
+    fn name(&self) -> &str {
+        "gc"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::CRUFT_PACKS, &Self::PRUNE_EXPIRE, &Self::REFLOG_EXPIRE]
+    }
+}
+
+/// The `gc.pruneExpire` and `gc.reflogExpire` keys.
+pub type Expiry = keys::Any<validate::Expiry>;
+
+mod expiry {
+    use std::{borrow::Cow, time::SystemTime};
+
+    use crate::{
+        bstr::{BStr, ByteSlice},
+        config,
+        config::tree::sections::gc::Expiry,
+    };
+
+    impl Expiry {
+        /// Convert `value` into the point in time before which items expire, with `now` as reference for relative dates
+        /// like `2.weeks.ago`, or `None` if items never expire.
+        pub fn try_into_expiry(
+            &'static self,
+            value: Cow<'_, BStr>,
+            now: SystemTime,
+        ) -> Result<Option<SystemTime>, config::key::GenericErrorWithValue> {
+            let err = || config::key::GenericErrorWithValue::from_value(self, value.clone().into_owned());
+            let input = value.to_str().map_err(|_| err())?.to_ascii_lowercase();
+            Ok(match input.as_str() {
+                "never" | "false" => None,
+                "now" | "all" => Some(now),
+                _ => {
+                    let input = if input.ends_with("ago") {
+                        input.replace('.', " ")
+                    } else {
+                        input
+                    };
+                    let time = gix_date::parse(&input, Some(now)).map_err(|_| err())?;
+                    Some(
+                        std::time::UNIX_EPOCH
+                            + std::time::Duration::from_secs(time.seconds.try_into().unwrap_or_default()),
+                    )
+                }
+            })
+        }
+    }
+}
+
+mod validate {
+    use crate::{bstr::BStr, config::tree::keys};
+
+    pub struct Expiry;
+    impl keys::Validate for Expiry {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Gc::PRUNE_EXPIRE.try_into_expiry(value.into(), std::time::SystemTime::now())?;
+            Ok(())
+        }
+    }
+}
@@ -386,6 +386,40 @@ mod subsections {
         /// The `gitoxide.objects.replaceRefBase` key.
         pub const REPLACE_REF_BASE: keys::Any =
             keys::Any::new("replaceRefBase", &Gitoxide::OBJECTS).with_environment_override("GIT_REPLACE_REF_BASE");
+        /// The `gitoxide.objects.packMmapAdvice` key.
+        pub const PACK_MMAP_ADVICE: PackMmapAdvice =
+            PackMmapAdvice::new_with_validate("packMmapAdvice", &Gitoxide::OBJECTS, super::validate::PackMmapAdvice)
+                .with_note("One of 'normal' (default), 'sequential', 'random' or 'willneed', passed as hint to the operating system when mapping packs");
+    }
+
+    /// The `gitoxide.objects.packMmapAdvice` key.
+    pub type PackMmapAdvice = keys::Any<super::validate::PackMmapAdvice>;
+
+    mod pack_mmap_advice {
+        use std::borrow::Cow;
+
+        use super::PackMmapAdvice;
+        use crate::{
+            bstr::{BStr, ByteSlice},
+            config,
+        };
+
+        impl PackMmapAdvice {
+            /// Convert `value` into the memory-map hint it names.
+            pub fn try_into_advice(
+                &'static self,
+                value: Cow<'_, BStr>,
+            ) -> Result<gix_odb::pack::mmap::Advice, config::key::GenericErrorWithValue> {
+                use gix_odb::pack::mmap::Advice;
+                Ok(match value.as_ref().as_bytes() {
+                    b"normal" => Advice::Normal,
+                    b"sequential" => Advice::Sequential,
+                    b"random" => Advice::Random,
+                    b"willneed" => Advice::WillNeed,
+                    _ => return Err(config::key::GenericErrorWithValue::from_value(self, value.into_owned())),
+                })
+            }
+        }
     }
 
     impl Section for Objects {
@@ -394,7 +428,7 @@ mod subsections {
         }
 
         fn keys(&self) -> &[&dyn Key] {
-            &[&Self::CACHE_LIMIT, &Self::REPLACE_REF_BASE]
+            &[&Self::CACHE_LIMIT, &Self::REPLACE_REF_BASE, &Self::PACK_MMAP_ADVICE]
         }
 
         fn parent(&self) -> Option<&dyn Section> {
@@ -496,4 +530,12 @@ pub mod validate {
             Ok(())
         }
     }
+
+    pub struct PackMmapAdvice;
+    impl Validate for PackMmapAdvice {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
+            super::Objects::PACK_MMAP_ADVICE.try_into_advice(value.into())?;
+            Ok(())
+        }
+    }
 }
@@ -0,0 +1,180 @@
+use crate::{
+    config,
+    config::tree::{keys, Gpg, Key, Section},
+};
+
+impl Gpg {
+    /// The `gpg.program` key.
+    pub const PROGRAM: keys::Executable = keys::Executable::new_executable("program", &config::Tree::GPG)
+        .with_note("used for OpenPGP signatures if `gpg.openpgp.program` isn't set, and defaults to 'gpg'");
+    /// The `gpg.format` key.
+    pub const FORMAT: Format = Format::new_with_validate("format", &config::Tree::GPG, validate::Format);
+    /// The `gpg.minTrustLevel` key.
+    pub const MIN_TRUST_LEVEL: MinTrustLevel =
+        MinTrustLevel::new_with_validate("minTrustLevel", &config::Tree::GPG, validate::MinTrustLevel);
+
+    /// The `gpg.openpgp` subsection.
+    pub const OPENPGP: Openpgp = Openpgp;
+    /// The `gpg.x509` subsection.
+    pub const X509: X509 = X509;
+    /// The `gpg.ssh` subsection.
+    pub const SSH: Ssh = Ssh;
+}
+
+/// The `gpg.format` key.
+pub type Format = keys::Any<validate::Format>;
+
+/// The `gpg.minTrustLevel` key.
+pub type MinTrustLevel = keys::Any<validate::MinTrustLevel>;
+
+mod format {
+    use std::borrow::Cow;
+
+    use crate::{
+        bstr::{BStr, ByteSlice},
+        config,
+        config::tree::gpg::{Format, MinTrustLevel},
+        gpg,
+    };
+
+    impl Format {
+        /// Convert `value` into the signature format it names.
+        pub fn try_into_format(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<gpg::Format, config::key::GenericErrorWithValue> {
+            Ok(match value.as_ref().as_bytes() {
+                b"openpgp" => gpg::Format::OpenPgp,
+                b"x509" => gpg::Format::X509,
+                b"ssh" => gpg::Format::Ssh,
+                _ => return Err(config::key::GenericErrorWithValue::from_value(self, value.into_owned())),
+            })
+        }
+    }
+
+    impl MinTrustLevel {
+        /// Convert `value` into the trust level it names.
+        pub fn try_into_trust(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<gpg::verify::Trust, config::key::GenericErrorWithValue> {
+            use gpg::verify::Trust;
+            Ok(match value.as_ref().as_bytes() {
+                b"undefined" => Trust::Undefined,
+                b"never" => Trust::Never,
+                b"marginal" => Trust::Marginal,
+                b"fully" => Trust::Fully,
+                b"ultimate" => Trust::Ultimate,
+                _ => return Err(config::key::GenericErrorWithValue::from_value(self, value.into_owned())),
+            })
+        }
+    }
+}
+
+impl Section for Gpg {
+    fn name(&self) -> &str {
+        "gpg"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::PROGRAM, &Self::FORMAT, &Self::MIN_TRUST_LEVEL]
+    }
+
+    fn sub_sections(&self) -> &[&dyn Section] {
+        &[&Self::OPENPGP, &Self::X509, &Self::SSH]
+    }
+}
+
+/// The `gpg.openpgp` subsection.
+#[derive(Copy, Clone, Default)]
+pub struct Openpgp;
+
+impl Openpgp {
+    /// The `gpg.openpgp.program` key.
+    pub const PROGRAM: keys::Executable = keys::Executable::new_executable("program", &Gpg::OPENPGP);
+}
+
+impl Section for Openpgp {
+    fn name(&self) -> &str {
+        "openpgp"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::PROGRAM]
+    }
+
+    fn parent(&self) -> Option<&dyn Section> {
+        Some(&config::Tree::GPG)
+    }
+}
+
+/// The `gpg.x509` subsection.
+#[derive(Copy, Clone, Default)]
+pub struct X509;
+
+impl X509 {
+    /// The `gpg.x509.program` key.
+    pub const PROGRAM: keys::Executable = keys::Executable::new_executable("program", &Gpg::X509);
+}
+
+impl Section for X509 {
+    fn name(&self) -> &str {
+        "x509"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::PROGRAM]
+    }
+
+    fn parent(&self) -> Option<&dyn Section> {
+        Some(&config::Tree::GPG)
+    }
+}
+
+/// The `gpg.ssh` subsection.
+#[derive(Copy, Clone, Default)]
+pub struct Ssh;
+
+impl Ssh {
+    /// The `gpg.ssh.program` key.
+    pub const PROGRAM: keys::Executable =
+        keys::Executable::new_executable("program", &Gpg::SSH).with_note("defaults to 'ssh-keygen'");
+    /// The `gpg.ssh.allowedSignersFile` key.
+    pub const ALLOWED_SIGNERS_FILE: keys::Path = keys::Path::new_path("allowedSignersFile", &Gpg::SSH);
+    /// The `gpg.ssh.revocationFile` key.
+    pub const REVOCATION_FILE: keys::Path = keys::Path::new_path("revocationFile", &Gpg::SSH);
+}
+
+impl Section for Ssh {
+    fn name(&self) -> &str {
+        "ssh"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::PROGRAM, &Self::ALLOWED_SIGNERS_FILE, &Self::REVOCATION_FILE]
+    }
+
+    fn parent(&self) -> Option<&dyn Section> {
+        Some(&config::Tree::GPG)
+    }
+}
+
+mod validate {
+    use crate::{bstr::BStr, config::tree::keys};
+
+    pub struct Format;
+    impl keys::Validate for Format {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Gpg::FORMAT.try_into_format(value.into())?;
+            Ok(())
+        }
+    }
+
+    pub struct MinTrustLevel;
+    impl keys::Validate for MinTrustLevel {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Gpg::MIN_TRUST_LEVEL.try_into_trust(value.into())?;
+            Ok(())
+        }
+    }
+}
@@ -48,6 +48,30 @@ impl Http {
     /// The `http.schannelCheckRevoke` key.
     pub const SCHANNEL_CHECK_REVOKE: keys::Boolean =
         keys::Boolean::new_boolean("schannelCheckRevoke", &config::Tree::HTTP);
+    /// The `http.sslVerify` key.
+    pub const SSL_VERIFY: keys::Boolean = keys::Boolean::new_boolean("sslVerify", &config::Tree::HTTP)
+        .with_deviation("only respected by the curl backend");
+
+    /// The `http.<url>` subsection.
+    pub const URL_PARAMETER: UrlParameter = UrlParameter;
+}
+
+/// The `http.<url>` parameter section, which may contain all keys of the `http` section to apply them only to matching urls.
+pub struct UrlParameter;
+
+impl Section for UrlParameter {
+    fn name(&self) -> &str {
+        "<url>"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        let http: &'static Http = &config::Tree::HTTP;
+        http.keys()
+    }
+
+    fn parent(&self) -> Option<&dyn Section> {
+        Some(&config::Tree::HTTP)
+    }
 }
 
 impl Section for Http {
@@ -69,8 +93,13 @@ impl Section for Http {
             &Self::SCHANNEL_USE_SSL_CA_INFO,
             &Self::SSL_CA_INFO,
             &Self::SCHANNEL_CHECK_REVOKE,
+            &Self::SSL_VERIFY,
         ]
     }
+
+    fn sub_sections(&self) -> &[&dyn Section] {
+        &[&Self::URL_PARAMETER]
+    }
 }
 
 /// The `http.followRedirects` key.
@@ -20,6 +20,11 @@ pub mod checkout;
 pub struct Clone;
 mod clone;
 
+/// The `commit` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Commit;
+mod commit;
+
 /// The `committer` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Committer;
@@ -52,11 +57,21 @@ pub mod extensions;
 pub struct Fetch;
 pub mod fetch;
 
+/// The `gc` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Gc;
+pub mod gc;
+
 /// The `gitoxide` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Gitoxide;
 pub mod gitoxide;
 
+/// The `gpg` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Gpg;
+pub mod gpg;
+
 /// The `http` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Http;
@@ -87,6 +102,11 @@ pub mod protocol;
 pub struct Remote;
 pub mod remote;
 
+/// The `repack` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Repack;
+mod repack;
+
 /// The `safe` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Safe;
@@ -97,6 +117,23 @@ mod safe;
 pub struct Ssh;
 pub mod ssh;
 
+/// The `status` top-level section.
+#[derive(Copy, Clone, Default)]
+#[cfg(feature = "status")]
+pub struct Status;
+#[cfg(feature = "status")]
+pub mod status;
+
+/// The `submodule` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Submodule;
+mod submodule;
+
+/// The `transfer` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Transfer;
+mod transfer;
+
 /// The `user` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct User;
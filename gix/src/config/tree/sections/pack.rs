@@ -9,9 +9,23 @@ impl Pack {
         keys::UnsignedInteger::new_unsigned_integer("threads", &config::Tree::PACK)
             .with_deviation("Leaving this key unspecified uses all available cores, instead of 1");
 
+    /// The `pack.compression` key.
+    pub const COMPRESSION: keys::CompressionLevel =
+        keys::CompressionLevel::new_compression_level("compression", &config::Tree::PACK).with_deviation(
+            "Leaving this key and `core.compression` unspecified uses level 1 instead of the zlib default",
+        );
+
+    /// The `pack.island` key.
+    pub const ISLAND: keys::String = keys::String::new_string("island", &config::Tree::PACK)
+        .with_note("a regular expression matched against reference names, whose capture groups form the island name");
+
     /// The `pack.indexVersion` key.
     pub const INDEX_VERSION: IndexVersion =
         IndexVersion::new_with_validate("indexVersion", &config::Tree::PACK, validate::IndexVersion);
+
+    /// The `pack.writeBitmaps` key.
+    pub const WRITE_BITMAPS: keys::Boolean = keys::Boolean::new_boolean("writeBitmaps", &config::Tree::PACK)
+        .with_deprecation("a deprecated synonym for `repack.writeBitmaps`");
 }
 
 /// The `pack.indexVersion` key.
@@ -42,7 +56,13 @@ impl Section for Pack {
     }
 
     fn keys(&self) -> &[&dyn Key] {
-        &[&Self::THREADS, &Self::INDEX_VERSION]
+        &[
+            &Self::THREADS,
+            &Self::COMPRESSION,
+            &Self::ISLAND,
+            &Self::INDEX_VERSION,
+            &Self::WRITE_BITMAPS,
+        ]
     }
 }
 
@@ -31,6 +31,16 @@ impl Remote {
         http::ProxyAuthMethod::new_proxy_auth_method("proxyAuthMethod", &config::Tree::REMOTE)
             .with_subsection_requirement(NAME_PARAMETER)
             .with_deviation("implemented like git, but never actually tried");
+    /// The `remote.<name>.mirror` key.
+    pub const MIRROR: keys::Boolean =
+        keys::Boolean::new_boolean("mirror", &config::Tree::REMOTE).with_subsection_requirement(NAME_PARAMETER);
+    /// The `remote.<name>.promisor` key.
+    pub const PROMISOR: keys::Boolean =
+        keys::Boolean::new_boolean("promisor", &config::Tree::REMOTE).with_subsection_requirement(NAME_PARAMETER);
+    /// The `remote.<name>.partialCloneFilter` key.
+    pub const PARTIAL_CLONE_FILTER: keys::String =
+        keys::String::new_string("partialCloneFilter", &config::Tree::REMOTE)
+            .with_subsection_requirement(NAME_PARAMETER);
 }
 
 impl Section for Remote {
@@ -48,6 +58,9 @@ impl Section for Remote {
             &Self::PUSH,
             &Self::PROXY,
             &Self::PROXY_AUTH_METHOD,
+            &Self::MIRROR,
+            &Self::PROMISOR,
+            &Self::PARTIAL_CLONE_FILTER,
         ]
     }
 }
@@ -0,0 +1,24 @@
+use crate::{
+    config,
+    config::tree::{keys, Key, Repack, Section},
+};
+
+impl Repack {
+    /// The `repack.writeBitmaps` key.
+    pub const WRITE_BITMAPS: keys::Boolean = keys::Boolean::new_boolean("writeBitmaps", &config::Tree::REPACK)
+        .with_fallback(&config::tree::Pack::WRITE_BITMAPS);
+    /// The `repack.useDeltaIslands` key.
+    pub const USE_DELTA_ISLANDS: keys::Boolean = keys::Boolean::new_boolean("useDeltaIslands", &config::Tree::REPACK);
+    /// The `repack.packKeptObjects` key.
+    pub const PACK_KEPT_OBJECTS: keys::Boolean = keys::Boolean::new_boolean("packKeptObjects", &config::Tree::REPACK);
+}
+
+impl Section for Repack {
+    fn name(&self) -> &str {
+        "repack"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::WRITE_BITMAPS, &Self::USE_DELTA_ISLANDS, &Self::PACK_KEPT_OBJECTS]
+    }
+}
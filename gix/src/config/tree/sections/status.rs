@@ -0,0 +1,24 @@
+use crate::{
+    config,
+    config::tree::{diff, keys, Key, Section, Status},
+};
+
+impl Status {
+    /// The `status.renameLimit` key.
+    pub const RENAME_LIMIT: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("renameLimit", &config::Tree::STATUS)
+            .with_note("Defaults to `diff.renameLimit` if unset");
+    /// The `status.renames` key.
+    pub const RENAMES: diff::Renames =
+        diff::Renames::new_renames("renames", &config::Tree::STATUS).with_note("Defaults to `diff.renames` if unset");
+}
+
+impl Section for Status {
+    fn name(&self) -> &str {
+        "status"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::RENAME_LIMIT, &Self::RENAMES]
+    }
+}
@@ -0,0 +1,20 @@
+use crate::{
+    config,
+    config::tree::{keys, Key, Section, Submodule},
+};
+
+impl Submodule {
+    /// The `submodule.recurse` key.
+    pub const RECURSE: keys::Boolean = keys::Boolean::new_boolean("recurse", &config::Tree::SUBMODULE)
+        .with_note("Used by `status` and `fetch` if they aren't configured to recurse into submodules otherwise");
+}
+
+impl Section for Submodule {
+    fn name(&self) -> &str {
+        "submodule"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::RECURSE]
+    }
+}
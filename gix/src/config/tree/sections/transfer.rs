@@ -0,0 +1,20 @@
+use crate::{
+    config,
+    config::tree::{keys, Key, Section, Transfer},
+};
+
+impl Transfer {
+    /// The `transfer.unpackLimit` key.
+    pub const UNPACK_LIMIT: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("unpackLimit", &config::Tree::TRANSFER);
+}
+
+impl Section for Transfer {
+    fn name(&self) -> &str {
+        "transfer"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::UNPACK_LIMIT]
+    }
+}
@@ -44,6 +44,8 @@ pub enum Note {
     Informative(&'static str),
     /// This key works differently than is described by git, explaining the deviation further.
     Deviation(&'static str),
+    /// This key is deprecated, explaining what to use instead.
+    Deprecated(&'static str),
 }
 
 /// A leaf-level entry in the git configuration, like `url` in `remote.origin.url`.
@@ -68,6 +70,10 @@ pub trait Key: std::fmt::Debug {
     fn note(&self) -> Option<&Note> {
         None
     }
+    /// The name of the type of value this key expects, like `Boolean` or `Path`, or `Any` if values aren't constrained.
+    fn value_type(&self) -> &str {
+        "Any"
+    }
 
     /// Return the name of an environment variable that would override this value (after following links until one is found).
     fn environment_override(&self) -> Option<&str> {
@@ -0,0 +1,52 @@
+//! Assure that objects newly added to the object database are connected, i.e. that everything reachable from them is present.
+//!
+//! This is what `git rev-list --objects <tips> --not --all` is used for after receiving a pack, so that references are
+//! never updated to point to objects whose history or trees are incomplete.
+//!
+//! Use [`Repository::check_connectivity()`](crate::Repository::check_connectivity()) to perform the check.
+//!
+//! ### Deviation
+//!
+//! * Only the direct targets of references are considered connected, without traversing their history, which makes this
+//!   check cheap if the new objects build on top of existing references like it's typical for fetches.
+//! * If the objects that were newly added are known, objects outside of them are assumed to be connected
+//!   as long as they exist, instead of traversing them as well.
+//! * Commits of submodules aren't expected to be present.
+use gix_hash::ObjectId;
+
+/// The error returned by [`Repository::check_connectivity()`](crate::Repository::check_connectivity()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The object {id} to check for connectivity doesn't exist")]
+    MissingTip { id: ObjectId },
+    #[error("The object {id} is missing, but is referred to by {referenced_by}")]
+    Missing { id: ObjectId, referenced_by: ObjectId },
+    #[error("The {kind} {id} could not be decoded")]
+    Decode {
+        id: ObjectId,
+        kind: gix_object::Kind,
+        source: gix_object::decode::Error,
+    },
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::Error),
+    #[error("Could not iterate references")]
+    ReferencesInit(#[from] crate::reference::iter::Error),
+    #[error("Could not iterate references")]
+    ReferencesIter(#[from] crate::reference::iter::init::Error),
+    #[error("Could not iterate references")]
+    References(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error(transparent)]
+    Shallow(#[from] crate::shallow::open::Error),
+    #[error("The operation was interrupted")]
+    Interrupted,
+}
+
+/// The outcome of [`Repository::check_connectivity()`](crate::Repository::check_connectivity()).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The amount of objects that were decoded to learn about the objects they refer to.
+    pub num_traversed: usize,
+    /// The amount of objects that were only checked for existence, as they were considered connected already.
+    pub num_boundary: usize,
+}
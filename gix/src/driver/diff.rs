@@ -0,0 +1,123 @@
+use std::process::Stdio;
+
+use crate::{
+    bstr::{BStr, BString},
+    driver::{BoxedError, Error, Platform, TempFile, NULL_DEVICE},
+};
+
+/// A diff driver implemented in Rust, registered with [`Registry::register_diff()`](super::Registry::register_diff()).
+///
+/// It's implemented for all closures taking a [`Context`].
+pub trait Driver {
+    /// Produce the diff between the resources in `ctx` in a format suitable for display.
+    fn diff(&self, ctx: Context<'_>) -> Result<Vec<u8>, BoxedError>;
+}
+
+impl<F> Driver for F
+where
+    F: Fn(Context<'_>) -> Result<Vec<u8>, BoxedError>,
+{
+    fn diff(&self, ctx: Context<'_>) -> Result<Vec<u8>, BoxedError> {
+        self(ctx)
+    }
+}
+
+/// A version of a file to diff.
+#[derive(Debug, Clone, Copy)]
+pub struct Resource<'a> {
+    /// The id of the blob with `data`.
+    pub id: &'a gix_hash::oid,
+    /// The mode of the file.
+    pub mode: gix_object::tree::EntryMode,
+    /// The content of the file.
+    pub data: &'a [u8],
+}
+
+/// The information passed to a diff [`Driver`].
+#[derive(Debug, Clone, Copy)]
+pub struct Context<'a> {
+    /// The path of the file relative to the root of the repository.
+    pub rela_path: &'a BStr,
+    /// The previous version of the file, or `None` if it was added.
+    pub old: Option<Resource<'a>>,
+    /// The current version of the file, or `None` if it was deleted.
+    pub new: Option<Resource<'a>>,
+}
+
+/// The result of [`Platform::diff()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The name of the driver that produced `output`.
+    pub driver: BString,
+    /// The diff as produced by the driver.
+    pub output: Vec<u8>,
+}
+
+/// Diffing
+impl Platform<'_, '_> {
+    /// Produce the diff between `old` and `new` versions of the file at `rela_path` with the driver named in its `diff` attribute,
+    /// or return `None` if there is no such driver and the built-in diff should be used.
+    ///
+    /// External commands in `diff.<driver>.command` are called like `GIT_EXTERNAL_DIFF`, that is with
+    /// `<path> <old-file> <old-hex> <old-mode> <new-file> <new-hex> <new-mode>` as arguments, with `/dev/null` (or `NUL` on Windows),
+    /// `.` and `.` for a missing side, and their standard output is the diff.
+    pub fn diff(
+        &mut self,
+        rela_path: &BStr,
+        old: Option<Resource<'_>>,
+        new: Option<Resource<'_>>,
+    ) -> Result<Option<Outcome>, Error> {
+        let name = match self.driver_name(rela_path, "diff", None)? {
+            Some(name) => name,
+            None => return Ok(None),
+        };
+        if let Some(driver) = self.registry.diff_driver(name.as_ref()) {
+            let output = driver
+                .diff(Context { rela_path, old, new })
+                .map_err(|source| Error::Driver {
+                    name: name.clone(),
+                    source,
+                })?;
+            return Ok(Some(Outcome { driver: name, output }));
+        }
+        let command = match self.driver_command("diff", name.as_ref(), "command") {
+            Some(command) => command,
+            None => return Ok(None),
+        };
+
+        let mut files = Vec::new();
+        let mut args: Vec<std::ffi::OsString> = vec![gix_path::from_bstr(rela_path).into_owned().into()];
+        for resource in [old, new] {
+            match resource {
+                Some(resource) => {
+                    let file = TempFile::new(resource.data).map_err(Error::TemporaryFile)?;
+                    args.push(file.path.clone().into());
+                    args.push(resource.id.to_string().into());
+                    args.push(format!("{:06o}", resource.mode as u16).into());
+                    files.push(file);
+                }
+                None => args.extend([NULL_DEVICE, ".", "."].map(Into::into)),
+            }
+        }
+        let mut cmd: std::process::Command = gix_command::prepare(gix_path::from_bstr(command).into_owned())
+            .with_shell()
+            .args(args)
+            .stdout(Stdio::piped())
+            .into();
+        cmd.current_dir(self.repo.work_dir().unwrap_or(self.repo.git_dir()));
+        let output = match cmd.output() {
+            Ok(output) => output,
+            Err(source) => return Err(Error::SpawnCommand { source, command: cmd }),
+        };
+        if !output.status.success() {
+            return Err(Error::CommandFailed {
+                status: output.status,
+                command: cmd,
+            });
+        }
+        Ok(Some(Outcome {
+            driver: name,
+            output: output.stdout,
+        }))
+    }
+}
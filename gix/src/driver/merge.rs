@@ -0,0 +1,150 @@
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    driver::{BoxedError, Error, Platform, TempFile},
+    path::shell_quote,
+};
+
+/// A merge driver implemented in Rust, registered with [`Registry::register_merge()`](super::Registry::register_merge()).
+///
+/// It's implemented for all closures taking a [`Context`].
+pub trait Driver {
+    /// Merge the versions of the file in `ctx`, and indicate whether conflicts remain in the result.
+    fn merge(&self, ctx: Context<'_>) -> Result<Resolution, BoxedError>;
+}
+
+impl<F> Driver for F
+where
+    F: Fn(Context<'_>) -> Result<Resolution, BoxedError>,
+{
+    fn merge(&self, ctx: Context<'_>) -> Result<Resolution, BoxedError> {
+        self(ctx)
+    }
+}
+
+/// The information passed to a merge [`Driver`].
+#[derive(Debug, Clone, Copy)]
+pub struct Context<'a> {
+    /// The path of the file relative to the root of the repository.
+    pub rela_path: &'a BStr,
+    /// The content of the file in the merge base.
+    pub base: &'a [u8],
+    /// The content of our version of the file.
+    pub ours: &'a [u8],
+    /// The content of their version of the file.
+    pub theirs: &'a [u8],
+    /// The length of conflict markers, as configured in the `conflict-marker-size` attribute, defaulting to 7.
+    pub conflict_marker_size: usize,
+}
+
+/// The merged content as produced by a merge driver.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resolution {
+    /// The merged content of the file, which may contain conflict markers.
+    pub content: Vec<u8>,
+    /// If `true`, the merge wasn't clean and the `content` needs to be resolved by the user.
+    pub is_conflicted: bool,
+}
+
+/// The result of [`Platform::merge()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The name of the driver that produced the `resolution`.
+    pub driver: BString,
+    /// The merged content.
+    pub resolution: Resolution,
+}
+
+const DEFAULT_CONFLICT_MARKER_SIZE: usize = 7;
+
+/// Merging
+impl Platform<'_, '_> {
+    /// Merge `ours` and `theirs` versions of the file at `rela_path` with `base` as common ancestor using the driver named in
+    /// its `merge` attribute, or in `merge.default` if the attribute isn't specified, or return `None` if there is no such driver
+    /// and the built-in merge should be used.
+    ///
+    /// External commands in `merge.<driver>.driver` can use `%O`, `%A` and `%B` for the files with the content of `base`,
+    /// `ours` and `theirs`, `%L` for the conflict marker size and `%P` for the path. The command is expected to write the result
+    /// to `%A` and to exit with a non-zero status if conflicts remain.
+    pub fn merge(
+        &mut self,
+        rela_path: &BStr,
+        base: &[u8],
+        ours: &[u8],
+        theirs: &[u8],
+    ) -> Result<Option<Outcome>, Error> {
+        let default = self
+            .repo
+            .config
+            .resolved
+            .string_filter("merge", None, "default", &mut self.repo.filter_config_section())
+            .map(std::borrow::Cow::into_owned);
+        let name = match self.driver_name(rela_path, "merge", default)? {
+            Some(name) => name,
+            None => return Ok(None),
+        };
+        let conflict_marker_size = self.conflict_marker_size();
+        if let Some(driver) = self.registry.merge_driver(name.as_ref()) {
+            let resolution = driver
+                .merge(Context {
+                    rela_path,
+                    base,
+                    ours,
+                    theirs,
+                    conflict_marker_size,
+                })
+                .map_err(|source| Error::Driver {
+                    name: name.clone(),
+                    source,
+                })?;
+            return Ok(Some(Outcome {
+                driver: name,
+                resolution,
+            }));
+        }
+        let command = match self.driver_command("merge", name.as_ref(), "driver") {
+            Some(command) => command,
+            None => return Ok(None),
+        };
+
+        let base = TempFile::new(base).map_err(Error::TemporaryFile)?;
+        let ours = TempFile::new(ours).map_err(Error::TemporaryFile)?;
+        let theirs = TempFile::new(theirs).map_err(Error::TemporaryFile)?;
+        let mut command = command.to_str_lossy().into_owned();
+        for (placeholder, value) in [
+            ("%O", shell_quote(&base.path)),
+            ("%A", shell_quote(&ours.path)),
+            ("%B", shell_quote(&theirs.path)),
+            ("%L", conflict_marker_size.to_string()),
+            ("%P", shell_quote(&gix_path::from_bstr(rela_path))),
+        ] {
+            command = command.replace(placeholder, &value);
+        }
+        let mut cmd: std::process::Command = gix_command::prepare(command).with_shell().into();
+        cmd.current_dir(self.repo.work_dir().unwrap_or(self.repo.git_dir()));
+        let status = match cmd.status() {
+            Ok(status) => status,
+            Err(source) => return Err(Error::SpawnCommand { source, command: cmd }),
+        };
+        let content = std::fs::read(&ours.path).map_err(Error::TemporaryFile)?;
+        Ok(Some(Outcome {
+            driver: name,
+            resolution: Resolution {
+                content,
+                is_conflicted: !status.success(),
+            },
+        }))
+    }
+
+    fn conflict_marker_size(&self) -> usize {
+        self.attrs
+            .iter_selected()
+            .find(|m| m.assignment.name.as_str() == "conflict-marker-size")
+            .and_then(|m| match m.assignment.state {
+                gix_attributes::StateRef::Value(value) => {
+                    std::str::from_utf8(value.as_bstr()).ok().and_then(|v| v.parse().ok())
+                }
+                _ => None,
+            })
+            .unwrap_or(DEFAULT_CONFLICT_MARKER_SIZE)
+    }
+}
@@ -0,0 +1,164 @@
+//! Run diff and merge drivers selected by the `diff` and `merge` attributes of a path, with drivers implemented in Rust
+//! taking precedence over external commands configured in `diff.<driver>.command` and `merge.<driver>.driver`.
+//!
+//! Register in-process drivers by name in a [`Registry`], and use [`Repository::drivers()`](crate::Repository::drivers())
+//! to obtain a [`Platform`] that runs them.
+use std::collections::HashMap;
+
+use crate::{
+    bstr::{BStr, BString},
+    AttributeStack, Repository,
+};
+
+///
+pub mod diff;
+///
+pub mod merge;
+
+/// The error returned by in-process drivers.
+pub type BoxedError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// A set of in-process diff and merge drivers, keyed by the driver name as used in the `diff` and `merge` attributes.
+///
+/// For instance, a diff driver registered as `jupyter` is used for all paths with the `diff=jupyter` attribute.
+#[derive(Default)]
+pub struct Registry {
+    diff: HashMap<BString, Box<dyn diff::Driver>>,
+    merge: HashMap<BString, Box<dyn merge::Driver>>,
+}
+
+/// Lifecycle
+impl Registry {
+    /// Register `driver` to be used for all paths with the `diff=<name>` attribute, replacing a previous driver of the same name.
+    pub fn register_diff(&mut self, name: impl Into<BString>, driver: impl diff::Driver + 'static) -> &mut Self {
+        self.diff.insert(name.into(), Box::new(driver));
+        self
+    }
+
+    /// Register `driver` to be used for all paths with the `merge=<name>` attribute, replacing a previous driver of the same name.
+    pub fn register_merge(&mut self, name: impl Into<BString>, driver: impl merge::Driver + 'static) -> &mut Self {
+        self.merge.insert(name.into(), Box::new(driver));
+        self
+    }
+}
+
+/// Access
+impl Registry {
+    /// Return the diff driver registered as `name`, if there is one.
+    pub fn diff_driver(&self, name: &BStr) -> Option<&dyn diff::Driver> {
+        self.diff.get(name).map(AsRef::as_ref)
+    }
+
+    /// Return the merge driver registered as `name`, if there is one.
+    pub fn merge_driver(&self, name: &BStr) -> Option<&dyn merge::Driver> {
+        self.merge.get(name).map(AsRef::as_ref)
+    }
+}
+
+/// A utility to run the diff and merge drivers configured for paths, obtained with [`Repository::drivers()`](crate::Repository::drivers()).
+///
+/// The driver name is taken from the `diff` or `merge` attribute of a path, and used to find a driver in this order:
+///
+/// * an in-process driver of the same name in the [`Registry`].
+/// * an external command configured in `diff.<driver>.command` or `merge.<driver>.driver`.
+///
+/// If neither is present, or if the attribute doesn't name a driver, the caller is expected to use its built-in
+/// algorithm.
+pub struct Platform<'repo, 'registry> {
+    pub(crate) repo: &'repo Repository,
+    pub(crate) registry: &'registry Registry,
+    pub(crate) stack: AttributeStack<'repo>,
+    pub(crate) attrs: gix_attributes::search::Outcome,
+}
+
+/// The error returned by [`Platform::diff()`] and [`Platform::merge()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not obtain the attributes of the path")]
+    Attributes(#[source] std::io::Error),
+    #[error("The in-process driver '{name}' failed")]
+    Driver { name: BString, source: BoxedError },
+    #[error("Could not write the temporary file to pass to the driver command")]
+    TemporaryFile(#[source] std::io::Error),
+    #[error("Could not run the driver command {command:?}")]
+    SpawnCommand {
+        source: std::io::Error,
+        command: std::process::Command,
+    },
+    #[error("The driver command {command:?} failed with {status}")]
+    CommandFailed {
+        status: std::process::ExitStatus,
+        command: std::process::Command,
+    },
+}
+
+/// The error returned by [`Repository::drivers()`](crate::Repository::drivers()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum InitError {
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    AttributeStack(#[from] crate::config::attribute_stack::Error),
+}
+
+/// Utilities
+impl Platform<'_, '_> {
+    /// Return the name of the driver in the `attr` attribute of `rela_path`, or `default` if it isn't specified.
+    fn driver_name(
+        &mut self,
+        rela_path: &BStr,
+        attr: &str,
+        default: Option<BString>,
+    ) -> Result<Option<BString>, Error> {
+        use gix_attributes::StateRef;
+        let platform = self.stack.at_entry(rela_path, Some(false)).map_err(Error::Attributes)?;
+        self.attrs.reset();
+        platform.matching_attributes(&mut self.attrs);
+        let state = self
+            .attrs
+            .iter_selected()
+            .find(|m| m.assignment.name.as_str() == attr)
+            .map(|m| m.assignment.state);
+        Ok(match state {
+            Some(StateRef::Value(name)) => Some(name.as_bstr().to_owned()),
+            Some(StateRef::Unspecified) | None => default,
+            Some(StateRef::Set | StateRef::Unset) => None,
+        })
+    }
+
+    fn driver_command(&self, section: &str, name: &BStr, key: &str) -> Option<BString> {
+        self.repo
+            .config
+            .resolved
+            .string_filter(section, Some(name), key, &mut self.repo.filter_config_section())
+            .map(std::borrow::Cow::into_owned)
+    }
+}
+
+/// A file with a unique name in the temporary directory that is removed when dropped.
+struct TempFile {
+    path: std::path::PathBuf,
+    _file: gix_tempfile::Handle<gix_tempfile::handle::Closed>,
+}
+
+impl TempFile {
+    fn new(data: &[u8]) -> std::io::Result<Self> {
+        use std::io::Write;
+        let mut file = gix_tempfile::new(
+            std::env::temp_dir(),
+            gix_tempfile::ContainingDirectory::Exists,
+            gix_tempfile::AutoRemove::Tempfile,
+        )?;
+        file.write_all(data)?;
+        let path = file.with_mut(|file| file.path().to_owned())?;
+        Ok(TempFile {
+            path,
+            _file: file.close()?,
+        })
+    }
+}
+
+/// The path to pass to external commands in place of a file that doesn't exist.
+const NULL_DEVICE: &str = if cfg!(windows) { "NUL" } else { "/dev/null" };
@@ -100,6 +100,34 @@ pub mod collate {
             }
         }
 
+        #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
+        impl<E> crate::error::Classify for Error<E>
+        where
+            E: std::error::Error + Send + Sync + 'static,
+        {
+            fn kind(&self) -> crate::error::Kind {
+                match self {
+                    Error::FindExistingReference(err) => err.kind(),
+                    Error::Connect(err) => err.kind(),
+                    Error::PrepareFetch(err) => err.kind(),
+                    Error::Fetch(err) => err.kind(),
+                    Error::FindExistingRemote(crate::remote::find::existing::Error::NotFound { .. }) => {
+                        crate::error::Kind::NotFound
+                    }
+                    _ => crate::error::Kind::Other,
+                }
+            }
+
+            fn is_retryable(&self) -> bool {
+                match self {
+                    Error::Connect(err) => err.is_retryable(),
+                    Error::PrepareFetch(err) => err.is_retryable(),
+                    Error::Fetch(err) => err.is_retryable(),
+                    _ => false,
+                }
+            }
+        }
+
         /// Queries
         impl<E> Error<E>
         where
@@ -0,0 +1,191 @@
+//! A classification of errors returned by high-level operations.
+//!
+//! Most errors in this crate are enums which mirror the steps of an operation, which makes them precise but hard to act on
+//! for applications that just want to know *what kind* of failure occurred. Use [`Classify::kind()`] to obtain a [`Kind`]
+//! that can be matched on to produce a user-facing message, and [`Classify::is_retryable()`] to decide whether it's worth
+//! trying again, without having to look at error messages.
+
+/// The class of failure an error belongs to, as obtained with [`Classify::kind()`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Kind {
+    /// Credentials could not be obtained, or they were rejected by the remote.
+    Authentication,
+    /// Communicating with a remote failed, or it responded in an unexpected way.
+    Network,
+    /// An object, reference or repository that was asked for doesn't exist.
+    NotFound,
+    /// An object or the storage holding it could not be decoded, which indicates that the repository is corrupted.
+    CorruptObject,
+    /// The operation would have overwritten local changes, untracked files or a reference that changed in the meantime.
+    WouldOverwrite,
+    /// A reference update was rejected as it wouldn't have been a fast-forward.
+    NonFastForward,
+    /// Any other failure, like invalid configuration or input, or local IO errors.
+    Other,
+}
+
+/// An error which can tell which [`Kind`] of failure it represents, and if the operation should be retried.
+pub trait Classify: std::error::Error {
+    /// Return the class of failure this error represents.
+    fn kind(&self) -> Kind;
+
+    /// Return `true` if retrying the operation might result in a different outcome, for instance because the
+    /// network was temporarily unavailable or a lock was held by another process.
+    fn is_retryable(&self) -> bool {
+        false
+    }
+}
+
+/// Classify errors returned by the object database, which are type-erased.
+pub(crate) fn find_kind(err: &gix_object::find::Error) -> Kind {
+    use gix_odb::{loose, store::find::Error};
+    match err.downcast_ref::<Error>() {
+        Some(
+            Error::Loose(
+                loose::find::Error::DecompressFile { .. }
+                | loose::find::Error::SizeMismatch { .. }
+                | loose::find::Error::Decode(_),
+            )
+            | Error::Pack(_)
+            | Error::DeltaBaseMissing { .. },
+        ) => Kind::CorruptObject,
+        _ => match err.downcast_ref::<gix_object::decode::Error>() {
+            Some(_) => Kind::CorruptObject,
+            None => Kind::Other,
+        },
+    }
+}
+
+impl Classify for gix_object::find::existing::Error {
+    fn kind(&self) -> Kind {
+        match self {
+            gix_object::find::existing::Error::Find(err) => find_kind(err),
+            gix_object::find::existing::Error::NotFound { .. } => Kind::NotFound,
+        }
+    }
+}
+
+impl Classify for gix_lock::acquire::Error {
+    fn kind(&self) -> Kind {
+        Kind::Other
+    }
+
+    fn is_retryable(&self) -> bool {
+        matches!(self, gix_lock::acquire::Error::PermanentlyLocked { .. })
+    }
+}
+
+impl Classify for gix_ref::file::transaction::prepare::Error {
+    fn kind(&self) -> Kind {
+        use gix_ref::file::transaction::prepare::Error;
+        match self {
+            Error::MustNotExist { .. } | Error::ReferenceOutOfDate { .. } => Kind::WouldOverwrite,
+            Error::MustExist { .. } | Error::DeleteReferenceMustExist { .. } => Kind::NotFound,
+            _ => Kind::Other,
+        }
+    }
+
+    fn is_retryable(&self) -> bool {
+        use gix_ref::file::transaction::prepare::Error;
+        match self {
+            Error::LockAcquire { source, .. } | Error::PackedTransactionAcquire(source) => source.is_retryable(),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
+mod network {
+    use gix_protocol::transport::{client, IsSpuriousError};
+
+    use super::{Classify, Kind};
+
+    fn io_kind(err: &std::io::Error) -> Kind {
+        match err.kind() {
+            std::io::ErrorKind::PermissionDenied => Kind::Authentication,
+            _ => Kind::Network,
+        }
+    }
+
+    impl Classify for client::Error {
+        fn kind(&self) -> Kind {
+            match self {
+                client::Error::Io(err) => io_kind(err),
+                client::Error::AuthenticationUnsupported | client::Error::AuthenticationRefused(_) => {
+                    Kind::Authentication
+                }
+                client::Error::UnsupportedProtocolVersion(_)
+                | client::Error::InvokeProgram { .. }
+                | client::Error::AmbiguousPath { .. } => Kind::Other,
+                _ => Kind::Network,
+            }
+        }
+
+        fn is_retryable(&self) -> bool {
+            self.is_spurious()
+        }
+    }
+
+    impl Classify for client::connect::Error {
+        fn kind(&self) -> Kind {
+            match self {
+                client::connect::Error::Connection(err) => match err.downcast_ref::<client::Error>() {
+                    Some(err) => err.kind(),
+                    None => Kind::Network,
+                },
+                _ => Kind::Other,
+            }
+        }
+
+        fn is_retryable(&self) -> bool {
+            self.is_spurious()
+        }
+    }
+
+    impl Classify for gix_protocol::handshake::Error {
+        fn kind(&self) -> Kind {
+            use gix_protocol::handshake::Error;
+            match self {
+                Error::Credentials(_) | Error::EmptyCredentials | Error::InvalidCredentials { .. } => {
+                    Kind::Authentication
+                }
+                Error::Transport(err) => err.kind(),
+                Error::TransportProtocolPolicyViolation { .. } | Error::ParseRefs(_) => Kind::Network,
+            }
+        }
+
+        fn is_retryable(&self) -> bool {
+            self.is_spurious()
+        }
+    }
+
+    impl Classify for gix_protocol::ls_refs::Error {
+        fn kind(&self) -> Kind {
+            use gix_protocol::ls_refs::Error;
+            match self {
+                Error::Io(err) => io_kind(err),
+                Error::Transport(err) => err.kind(),
+                Error::Parse(_) => Kind::Network,
+            }
+        }
+
+        fn is_retryable(&self) -> bool {
+            self.is_spurious()
+        }
+    }
+
+    impl Classify for gix_protocol::fetch::response::Error {
+        fn kind(&self) -> Kind {
+            use gix_protocol::fetch::response::Error;
+            match self {
+                Error::Io(err) => io_kind(err),
+                Error::Transport(err) => err.kind(),
+                _ => Kind::Network,
+            }
+        }
+
+        fn is_retryable(&self) -> bool {
+            self.is_spurious()
+        }
+    }
+}
@@ -1,5 +1,4 @@
 //! lower-level access to filters which are applied to create working tree checkouts or to 'clean' working tree contents for storage in git.
-use std::borrow::Cow;
 
 pub use gix_filter as plumbing;
 use gix_object::Find;
@@ -191,35 +190,68 @@ impl<'repo> Pipeline<'repo> {
     pub fn driver_context_mut(&mut self) -> &mut gix_filter::pipeline::Context {
         self.inner.driver_context_mut()
     }
+
+    /// Return the warning about line endings that wouldn't survive a round-trip, produced by the last call to
+    /// [`convert_to_git()`][Self::convert_to_git()] if `core.safecrlf` is set to `warn`.
+    pub fn round_trip_warning(&self) -> Option<gix_filter::eol::convert_to_git::RoundTripWarning> {
+        self.inner.round_trip_warning()
+    }
+
+    /// Return the state of all long-running filter processes, for instance to [obtain delayed results][gix_filter::driver::State::list_delayed_paths()]
+    /// after [converting to the worktree][Self::convert_to_worktree()] while allowing delays.
+    pub fn driver_state_mut(&mut self) -> &mut gix_filter::driver::State {
+        self.inner.driver_state_mut()
+    }
 }
 
 /// Obtain a list of all configured driver, but ignore those in sections that we don't trust enough.
+///
+/// Like `git`, values of the same driver in multiple sections are merged, with later values overriding earlier ones.
 fn extract_drivers(repo: &Repository) -> Result<Vec<gix_filter::Driver>, pipeline::options::Error> {
-    repo.config
+    let mut drivers = Vec::<gix_filter::Driver>::new();
+    for section in repo
+        .config
         .resolved
         .sections_by_name("filter")
         .into_iter()
         .flatten()
         .filter(|s| repo.filter_config_section()(s.meta()))
-        .filter_map(|s| {
-            s.header().subsection_name().map(|name| {
-                Ok(gix_filter::Driver {
+    {
+        let name = match section.header().subsection_name() {
+            Some(name) => name,
+            None => continue,
+        };
+        let idx = match drivers.iter().position(|driver| driver.name == name) {
+            Some(idx) => idx,
+            None => {
+                drivers.push(gix_filter::Driver {
                     name: name.to_owned(),
-                    clean: s.value("clean").map(Cow::into_owned),
-                    smudge: s.value("smudge").map(Cow::into_owned),
-                    process: s.value("process").map(Cow::into_owned),
-                    required: s
-                        .value("required")
-                        .map(|value| gix_config::Boolean::try_from(value.as_ref()))
-                        .transpose()
-                        .map_err(|err| pipeline::options::Error::Driver {
-                            name: name.to_owned(),
-                            source: err,
-                        })?
-                        .unwrap_or_default()
-                        .into(),
-                })
-            })
-        })
-        .collect::<Result<Vec<_>, pipeline::options::Error>>()
+                    clean: None,
+                    smudge: None,
+                    process: None,
+                    required: false,
+                });
+                drivers.len() - 1
+            }
+        };
+        let driver = &mut drivers[idx];
+        for (field, key) in [
+            (&mut driver.clean, "clean"),
+            (&mut driver.smudge, "smudge"),
+            (&mut driver.process, "process"),
+        ] {
+            if let Some(value) = section.value(key) {
+                *field = Some(value.into_owned());
+            }
+        }
+        if let Some(value) = section.value("required") {
+            driver.required = gix_config::Boolean::try_from(value.as_ref())
+                .map_err(|err| pipeline::options::Error::Driver {
+                    name: name.to_owned(),
+                    source: err,
+                })?
+                .into();
+        }
+    }
+    Ok(drivers)
 }
@@ -0,0 +1,637 @@
+//! Check the consistency of objects, references and reflogs, similar to `git fsck`.
+//!
+//! Use [`Repository::fsck()`](crate::Repository::fsck()) to obtain a [`Platform`] and [run](Platform::run()) all checks.
+//! Problems are reported as [`Finding`]s, each with a [`MessageId`] whose [`Severity`] can be configured with
+//! `fsck.<msgId>`, like `fsck.zeroPaddedFilemode = ignore`, or with [`Platform::with_severity()`].
+//!
+//! Objects which aren't reachable from references, their reflogs, the `HEAD` of all worktrees or their indices are
+//! unreachable, and those among them which aren't referred to by other unreachable objects are dangling.
+//!
+//! ### Deviation
+//!
+//! * Only the [`MessageId`]s listed here are known, and `fsck.skipList` isn't supported.
+//! * Objects of alternate object databases are checked as well, as if they were local objects.
+//! * Commits, tags and their signatures are only checked as far as necessary to decode them.
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use gix_hash::ObjectId;
+
+use crate::{bstr::BString, Repository};
+
+///
+pub mod init {
+    /// The error returned by [`Repository::fsck()`](crate::Repository::fsck()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The value of fsck.{id} must be one of 'error', 'warn', 'info' or 'ignore', got {value:?}")]
+        InvalidSeverity {
+            id: super::MessageId,
+            value: crate::bstr::BString,
+        },
+    }
+}
+
+/// The error returned by [`Platform::run()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not iterate all objects")]
+    IterObjects(#[from] gix_odb::store::load_index::Error),
+    #[error("Could not iterate all objects")]
+    IterObject(#[from] gix_odb::loose::iter::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::Error),
+    #[error("Could not iterate references")]
+    ReferencesInit(#[from] crate::reference::iter::Error),
+    #[error("Could not iterate references")]
+    ReferencesIter(#[from] crate::reference::iter::init::Error),
+    #[error("Could not iterate references")]
+    References(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error(transparent)]
+    FindReference(#[from] crate::reference::find::Error),
+    #[error("Could not obtain the HEAD reference")]
+    Head(#[from] crate::reference::find::existing::Error),
+    #[error("Could not open a linked worktree to learn about the objects it refers to")]
+    OpenWorktree(#[from] crate::open::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    Shallow(#[from] crate::shallow::open::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("The operation was interrupted")]
+    Interrupted,
+}
+
+/// How serious a [`Finding`] is.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Severity {
+    /// The finding is not reported at all.
+    Ignore,
+    /// The finding is informational, and doesn't indicate a problem.
+    Info,
+    /// The finding indicates a problem which doesn't affect the integrity of the repository.
+    Warn,
+    /// The finding indicates a corrupt repository.
+    Error,
+}
+
+impl Severity {
+    /// Parse `value` as it's used in `fsck.<msgId>`, or return `None` if it's unknown.
+    pub fn from_bytes(value: &[u8]) -> Option<Self> {
+        Some(match value.to_ascii_lowercase().as_slice() {
+            b"error" => Severity::Error,
+            b"warn" => Severity::Warn,
+            b"info" => Severity::Info,
+            b"ignore" => Severity::Ignore,
+            _ => return None,
+        })
+    }
+}
+
+/// The kind of problem a [`Finding`] is about, named like the `<msgId>` in `fsck.<msgId>`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum MessageId {
+    /// An object couldn't be decoded.
+    BadObject,
+    /// The hash of an object's content doesn't match its id.
+    HashMismatch,
+    /// The entries of a tree aren't sorted like git sorts them.
+    TreeNotSorted,
+    /// A tree contains multiple entries with the same name.
+    DuplicateEntries,
+    /// The mode of a tree entry has a leading zero.
+    ZeroPaddedFilemode,
+    /// A tree entry has an empty name.
+    EmptyName,
+    /// A tree entry has a name containing a slash.
+    FullPathname,
+    /// A tree entry is named `.`.
+    HasDot,
+    /// A tree entry is named `..`.
+    HasDotdot,
+    /// A tree entry is named `.git`, in any case.
+    HasDotgit,
+    /// A tag has no tagger.
+    MissingTaggerEntry,
+    /// An object refers to an object that doesn't exist.
+    BrokenLink,
+    /// A reference points to an object that doesn't exist.
+    BadRefTarget,
+    /// A symbolic reference points to a reference that doesn't exist.
+    DanglingSymref,
+    /// A reflog entry couldn't be decoded.
+    BadReflogEntry,
+    /// A reflog entry refers to an object that doesn't exist.
+    MissingReflogObject,
+}
+
+impl MessageId {
+    /// All known message ids.
+    pub const ALL: &'static [MessageId] = &[
+        MessageId::BadObject,
+        MessageId::HashMismatch,
+        MessageId::TreeNotSorted,
+        MessageId::DuplicateEntries,
+        MessageId::ZeroPaddedFilemode,
+        MessageId::EmptyName,
+        MessageId::FullPathname,
+        MessageId::HasDot,
+        MessageId::HasDotdot,
+        MessageId::HasDotgit,
+        MessageId::MissingTaggerEntry,
+        MessageId::BrokenLink,
+        MessageId::BadRefTarget,
+        MessageId::DanglingSymref,
+        MessageId::BadReflogEntry,
+        MessageId::MissingReflogObject,
+    ];
+
+    /// The name of the id as used in `fsck.<msgId>`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MessageId::BadObject => "badObject",
+            MessageId::HashMismatch => "hashMismatch",
+            MessageId::TreeNotSorted => "treeNotSorted",
+            MessageId::DuplicateEntries => "duplicateEntries",
+            MessageId::ZeroPaddedFilemode => "zeroPaddedFilemode",
+            MessageId::EmptyName => "emptyName",
+            MessageId::FullPathname => "fullPathname",
+            MessageId::HasDot => "hasDot",
+            MessageId::HasDotdot => "hasDotdot",
+            MessageId::HasDotgit => "hasDotgit",
+            MessageId::MissingTaggerEntry => "missingTaggerEntry",
+            MessageId::BrokenLink => "brokenLink",
+            MessageId::BadRefTarget => "badRefTarget",
+            MessageId::DanglingSymref => "danglingSymref",
+            MessageId::BadReflogEntry => "badReflogEntry",
+            MessageId::MissingReflogObject => "missingReflogObject",
+        }
+    }
+
+    /// The severity of findings with this id unless configured otherwise.
+    pub fn default_severity(&self) -> Severity {
+        match self {
+            MessageId::BadObject
+            | MessageId::HashMismatch
+            | MessageId::TreeNotSorted
+            | MessageId::DuplicateEntries
+            | MessageId::BrokenLink
+            | MessageId::BadRefTarget
+            | MessageId::BadReflogEntry
+            | MessageId::MissingReflogObject => Severity::Error,
+            MessageId::ZeroPaddedFilemode
+            | MessageId::EmptyName
+            | MessageId::FullPathname
+            | MessageId::HasDot
+            | MessageId::HasDotdot
+            | MessageId::HasDotgit
+            | MessageId::DanglingSymref => Severity::Warn,
+            MessageId::MissingTaggerEntry => Severity::Info,
+        }
+    }
+}
+
+impl std::fmt::Display for MessageId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// What a [`Finding`] is about.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Subject {
+    /// An object, along with its kind if it is known.
+    Object {
+        /// The id of the object.
+        id: ObjectId,
+        /// The kind of the object, or `None` if it couldn't be determined.
+        kind: Option<gix_object::Kind>,
+    },
+    /// A reference.
+    Reference(gix_ref::FullName),
+    /// An entry in the reflog of a reference.
+    Reflog {
+        /// The reference whose reflog contains the entry.
+        reference: gix_ref::FullName,
+        /// The zero-based index of the entry in the reflog, with the oldest entry first.
+        line: usize,
+    },
+}
+
+/// A problem found by [`Platform::run()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    /// The kind of problem.
+    pub id: MessageId,
+    /// How serious the problem is.
+    pub severity: Severity,
+    /// What the problem is about.
+    pub subject: Subject,
+    /// A human-readable description of the problem.
+    pub message: BString,
+}
+
+/// The outcome of [`Platform::run()`].
+#[derive(Debug, Clone, Default)]
+pub struct Outcome {
+    /// All findings whose severity isn't [`Ignore`](Severity::Ignore), in no particular order.
+    pub findings: Vec<Finding>,
+    /// The amount of distinct objects that were checked.
+    pub num_objects: usize,
+    /// Unreachable objects which no other unreachable object refers to, sorted by id, or empty if
+    /// [disabled](Platform::with_dangling()).
+    pub dangling: Vec<(ObjectId, gix_object::Kind)>,
+    /// All unreachable objects sorted by id, or empty unless [enabled](Platform::with_unreachable()).
+    pub unreachable: Vec<(ObjectId, gix_object::Kind)>,
+}
+
+impl Outcome {
+    /// Return `true` if there is at least one finding with [`Error`](Severity::Error) severity.
+    pub fn has_errors(&self) -> bool {
+        self.findings.iter().any(|finding| finding.severity == Severity::Error)
+    }
+}
+
+/// A utility to check the consistency of a repository, obtained with [`Repository::fsck()`](crate::Repository::fsck()).
+pub struct Platform<'repo> {
+    pub(crate) repo: &'repo Repository,
+    pub(crate) severities: Vec<(MessageId, Severity)>,
+    pub(crate) dangling: bool,
+    pub(crate) unreachable: bool,
+}
+
+/// Configuration
+impl Platform<'_> {
+    /// Report findings with `id` with `severity`, overriding the value of `fsck.<msgId>`.
+    pub fn with_severity(mut self, id: MessageId, severity: Severity) -> Self {
+        self.severities.retain(|(existing, _)| *existing != id);
+        self.severities.push((id, severity));
+        self
+    }
+
+    /// If `true`, the default, report unreachable objects that no other unreachable object refers to.
+    pub fn with_dangling(mut self, toggle: bool) -> Self {
+        self.dangling = toggle;
+        self
+    }
+
+    /// If `true`, report all unreachable objects. Defaults to `false`.
+    pub fn with_unreachable(mut self, toggle: bool) -> Self {
+        self.unreachable = toggle;
+        self
+    }
+
+    /// Return the severity with which findings with `id` are reported.
+    pub fn severity(&self, id: MessageId) -> Severity {
+        self.severities
+            .iter()
+            .find_map(|(existing, severity)| (*existing == id).then_some(*severity))
+            .unwrap_or_else(|| id.default_severity())
+    }
+}
+
+/// Checks
+impl Platform<'_> {
+    /// Check all objects, references and their reflogs, and find unreachable objects, reporting progress of the checked
+    /// objects to `progress`.
+    pub fn run(&self, mut progress: impl crate::Progress, should_interrupt: &AtomicBool) -> Result<Outcome, Error> {
+        let mut out = Outcome::default();
+        let mut objects = gix_hashtable::HashMap::<ObjectId, gix_object::Kind>::default();
+        let mut links = gix_hashtable::HashMap::<ObjectId, Vec<ObjectId>>::default();
+
+        // Objects are checked as they are stored, just like `git` does, as replacements would make them appear to have
+        // the content and links of their replacement.
+        let mut db = self.repo.objects.clone();
+        db.ignore_replacements = true;
+
+        progress.init(None, gix_features::progress::count("objects"));
+        let mut buf = Vec::new();
+        for id in db.store_ref().iter()? {
+            let id = id?;
+            if objects.contains_key(&id) {
+                continue;
+            }
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            let data = match gix_object::Find::try_find(&db, &id, &mut buf).map_err(crate::object::find::Error)? {
+                Some(data) => data,
+                None => continue,
+            };
+            objects.insert(id, data.kind);
+            let object_links = self.check_object(id, data, &mut out.findings);
+            if !object_links.is_empty() {
+                links.insert(id, object_links);
+            }
+            progress.inc();
+        }
+        out.num_objects = objects.len();
+
+        let shallow = self.repo.shallow_commits()?;
+        let is_shallow = |id: &ObjectId| {
+            shallow
+                .as_ref()
+                .map_or(false, |commits| commits.binary_search(id).is_ok())
+        };
+        for (id, object_links) in &links {
+            for link in object_links.iter().filter(|link| !objects.contains_key(*link)) {
+                if objects.get(id) == Some(&gix_object::Kind::Commit) && is_shallow(id) {
+                    continue;
+                }
+                self.report(
+                    &mut out.findings,
+                    MessageId::BrokenLink,
+                    Subject::Object {
+                        id: *id,
+                        kind: objects.get(id).copied(),
+                    },
+                    format!("broken link from {id} to {link}"),
+                );
+            }
+        }
+
+        let roots = self.check_references(&objects, &mut out.findings)?;
+        if self.dangling || self.unreachable {
+            let mut reachable = gix_hashtable::HashSet::default();
+            let mut stack: Vec<_> = roots.into_iter().filter(|id| objects.contains_key(id)).collect();
+            while let Some(id) = stack.pop() {
+                if !reachable.insert(id) {
+                    continue;
+                }
+                if let Some(object_links) = links.get(&id) {
+                    stack.extend(
+                        object_links
+                            .iter()
+                            .filter(|link| objects.contains_key(*link) && !reachable.contains(*link)),
+                    );
+                }
+            }
+
+            let mut unreachable: Vec<_> = objects
+                .iter()
+                .filter(|(id, _)| !reachable.contains(*id))
+                .map(|(id, kind)| (*id, *kind))
+                .collect();
+            unreachable.sort();
+            if self.dangling {
+                let referred_to: gix_hashtable::HashSet<_> = unreachable
+                    .iter()
+                    .filter_map(|(id, _)| links.get(id))
+                    .flatten()
+                    .copied()
+                    .collect();
+                out.dangling = unreachable
+                    .iter()
+                    .filter(|(id, _)| !referred_to.contains(id))
+                    .copied()
+                    .collect();
+            }
+            if self.unreachable {
+                out.unreachable = unreachable;
+            }
+        }
+        Ok(out)
+    }
+}
+
+impl Platform<'_> {
+    fn report(&self, findings: &mut Vec<Finding>, id: MessageId, subject: Subject, message: impl Into<BString>) {
+        let severity = self.severity(id);
+        if severity != Severity::Ignore {
+            findings.push(Finding {
+                id,
+                severity,
+                subject,
+                message: message.into(),
+            });
+        }
+    }
+
+    /// Check the object with `id` and `data`, and return the ids of all objects it refers to.
+    fn check_object(&self, id: ObjectId, data: gix_object::Data<'_>, findings: &mut Vec<Finding>) -> Vec<ObjectId> {
+        let subject = || Subject::Object {
+            id,
+            kind: Some(data.kind),
+        };
+        let actual = gix_object::compute_hash(id.kind(), data.kind, data.data);
+        if actual != id {
+            self.report(
+                findings,
+                MessageId::HashMismatch,
+                subject(),
+                format!("{} {id} hashes to {actual}", data.kind),
+            );
+        }
+
+        let mut links = Vec::new();
+        let res = match data.kind {
+            gix_object::Kind::Blob => Ok(()),
+            gix_object::Kind::Commit => gix_object::CommitRef::from_bytes(data.data).map(|commit| {
+                links.push(commit.tree());
+                links.extend(commit.parents());
+            }),
+            gix_object::Kind::Tag => gix_object::TagRef::from_bytes(data.data).map(|tag| {
+                links.push(tag.target());
+                if tag.tagger.is_none() {
+                    self.report(
+                        findings,
+                        MessageId::MissingTaggerEntry,
+                        subject(),
+                        format!("tag {id} has no tagger"),
+                    );
+                }
+            }),
+            gix_object::Kind::Tree => self.check_tree(id, data.data, &mut links, findings),
+        };
+        if let Err(err) = res {
+            self.report(
+                findings,
+                MessageId::BadObject,
+                subject(),
+                format!("{} {id} could not be decoded: {err}", data.kind),
+            );
+        }
+        links
+    }
+
+    fn check_tree(
+        &self,
+        id: ObjectId,
+        data: &[u8],
+        links: &mut Vec<ObjectId>,
+        findings: &mut Vec<Finding>,
+    ) -> Result<(), gix_object::decode::Error> {
+        let subject = || Subject::Object {
+            id,
+            kind: Some(gix_object::Kind::Tree),
+        };
+        let mut report = |message_id, message: String| self.report(findings, message_id, subject(), message);
+        let mut previous = None::<gix_object::tree::EntryRef<'_>>;
+        let mut raw = data;
+        for entry in gix_object::TreeRefIter::from_bytes_with_hash(data, id.kind()) {
+            let entry = entry?;
+            if raw.first() == Some(&b'0') {
+                report(
+                    MessageId::ZeroPaddedFilemode,
+                    format!("tree {id} has a zero-padded file mode for '{}'", entry.filename),
+                );
+            }
+            raw = raw
+                .iter()
+                .position(|b| *b == 0)
+                .and_then(|pos| raw.get(pos + 1 + id.kind().len_in_bytes()..))
+                .unwrap_or_default();
+
+            let name = entry.filename;
+            if name.is_empty() {
+                report(
+                    MessageId::EmptyName,
+                    format!("tree {id} has an entry with an empty name"),
+                );
+            } else if name.contains(&b'/') {
+                report(
+                    MessageId::FullPathname,
+                    format!("tree {id} has an entry with a full path name '{name}'"),
+                );
+            } else if name == "." {
+                report(MessageId::HasDot, format!("tree {id} has an entry named '.'"));
+            } else if name == ".." {
+                report(MessageId::HasDotdot, format!("tree {id} has an entry named '..'"));
+            } else if name.eq_ignore_ascii_case(b".git") {
+                report(MessageId::HasDotgit, format!("tree {id} has an entry named '{name}'"));
+            }
+
+            if let Some(previous) = previous {
+                if previous.filename == entry.filename {
+                    report(
+                        MessageId::DuplicateEntries,
+                        format!("tree {id} contains the entry '{name}' more than once"),
+                    );
+                } else if previous > entry {
+                    report(
+                        MessageId::TreeNotSorted,
+                        format!("tree {id} is not sorted at entry '{name}'"),
+                    );
+                }
+            }
+            // Submodule commits are never expected to be present.
+            if entry.mode != gix_object::tree::EntryMode::Commit {
+                links.push(entry.oid.to_owned());
+            }
+            previous = Some(entry);
+        }
+        Ok(())
+    }
+
+    /// Check all references and their reflogs, and return the ids of all objects they refer to, along with the
+    /// objects that the `HEAD` and index of each worktree refer to.
+    fn check_references(
+        &self,
+        objects: &gix_hashtable::HashMap<ObjectId, gix_object::Kind>,
+        findings: &mut Vec<Finding>,
+    ) -> Result<Vec<ObjectId>, Error> {
+        let repo = self.repo;
+        let mut roots = Vec::new();
+        let mut references = Vec::new();
+        let platform = repo.references()?;
+        for reference in platform.all()? {
+            references.push(reference.map_err(Error::References)?);
+        }
+        let head = repo.try_find_reference("HEAD")?;
+        let is_head =
+            |reference: &gix_ref::Reference| head.as_ref().map_or(false, |head| head.inner.name == reference.name);
+
+        for platform in references.iter().chain(head.iter()) {
+            let reference = &platform.inner;
+            match &reference.target {
+                gix_ref::Target::Peeled(id) => {
+                    roots.push(*id);
+                    if !objects.contains_key(id) {
+                        self.report(
+                            findings,
+                            MessageId::BadRefTarget,
+                            Subject::Reference(reference.name.clone()),
+                            format!("{} points to the missing object {id}", reference.name.as_bstr()),
+                        );
+                    }
+                }
+                gix_ref::Target::Symbolic(target) => {
+                    // An unborn `HEAD` is normal in new repositories.
+                    if !is_head(reference) && repo.try_find_reference(target.as_ref())?.is_none() {
+                        self.report(
+                            findings,
+                            MessageId::DanglingSymref,
+                            Subject::Reference(reference.name.clone()),
+                            format!(
+                                "{} points to the missing reference {}",
+                                reference.name.as_bstr(),
+                                target.as_bstr()
+                            ),
+                        );
+                    }
+                }
+            }
+
+            let mut log = platform.log_iter();
+            let lines = match log.all()? {
+                Some(lines) => lines,
+                None => continue,
+            };
+            for (line_index, line) in lines.enumerate() {
+                let subject = || Subject::Reflog {
+                    reference: reference.name.clone(),
+                    line: line_index,
+                };
+                let line = match line {
+                    Ok(line) => line,
+                    Err(err) => {
+                        self.report(
+                            findings,
+                            MessageId::BadReflogEntry,
+                            subject(),
+                            format!("the reflog of {} is corrupt: {err}", reference.name.as_bstr()),
+                        );
+                        continue;
+                    }
+                };
+                for id in [line.previous_oid(), line.new_oid()] {
+                    if id.is_null() {
+                        continue;
+                    }
+                    if objects.contains_key(&id) {
+                        roots.push(id);
+                    } else {
+                        self.report(
+                            findings,
+                            MessageId::MissingReflogObject,
+                            subject(),
+                            format!(
+                                "the reflog of {} refers to the missing object {id}",
+                                reference.name.as_bstr()
+                            ),
+                        );
+                    }
+                }
+            }
+        }
+
+        let mut indices = vec![repo.index_or_empty()?];
+        for proxy in repo.worktrees()? {
+            let worktree = proxy.into_repo_with_possibly_inaccessible_worktree()?;
+            roots.extend(worktree.head()?.id().map(crate::Id::detach));
+            indices.push(worktree.index_or_empty()?);
+        }
+        for index in &indices {
+            roots.extend(
+                index
+                    .entries()
+                    .iter()
+                    .filter(|entry| entry.mode != gix_index::entry::Mode::COMMIT)
+                    .map(|entry| entry.id),
+            );
+        }
+        Ok(roots)
+    }
+}
@@ -0,0 +1,563 @@
+//! Verify signatures of commits and tags, configured in the `gpg` section just like `git` does.
+//!
+//! Signatures are verified by a [`Verifier`](verify::Verifier), which can be any implementation, like one backed by a pure-Rust
+//! OpenPGP library, or the [`Programs`] verifier which runs `gpg`, `gpgsm` or `ssh-keygen` just like `git` does, depending on the
+//! [format](Format) of the signature.
+//! Use [`Repository::signature_verifier()`](crate::Repository::signature_verifier()) to obtain the latter as configured.
+//!
+//! Signatures are created by a [`Signer`](sign::Signer) when creating commits or tags, with the [`ProgramSigner`] running
+//! `gpg`, `gpgsm` or `ssh-keygen` as configured, see [`Repository::signer()`](crate::Repository::signer()).
+use std::{
+    io::Write,
+    path::PathBuf,
+    process::{Command, Stdio},
+};
+
+use crate::bstr::{BStr, BString, ByteSlice};
+
+/// The format of a signature, as configured with `gpg.format` or detected from the signature itself.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Format {
+    /// An OpenPGP signature, as produced by `gpg`.
+    OpenPgp,
+    /// An X.509 signature, as produced by `gpgsm`.
+    X509,
+    /// An SSH signature, as produced by `ssh-keygen`.
+    Ssh,
+}
+
+impl Format {
+    /// Detect the format of `signature` by its armor, or return `None` if it's unknown.
+    pub fn from_signature(signature: &BStr) -> Option<Self> {
+        let first_line = signature.lines().next()?;
+        Some(match first_line {
+            b"-----BEGIN PGP SIGNATURE-----" | b"-----BEGIN PGP MESSAGE-----" => Format::OpenPgp,
+            b"-----BEGIN SIGNED MESSAGE-----" => Format::X509,
+            b"-----BEGIN SSH SIGNATURE-----" => Format::Ssh,
+            _ => return None,
+        })
+    }
+}
+
+///
+pub mod verify {
+    use crate::bstr::{BStr, BString};
+
+    /// The result of checking a signature.
+    #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+    pub enum Status {
+        /// The signature is good, and its key is trusted at least as much as required.
+        Good,
+        /// The signature is good, but its key isn't trusted as much as required.
+        Untrusted,
+        /// The signature is bad, i.e. the data was changed or the signature doesn't belong to it.
+        Bad,
+        /// The signature is good, but expired.
+        ExpiredSignature,
+        /// The signature is good, but was made by a key that expired.
+        ExpiredKey,
+        /// The signature is good, but was made by a key that was revoked.
+        RevokedKey,
+        /// The signature couldn't be checked, for instance because the key is missing.
+        CannotCheck,
+    }
+
+    /// The level of trust in the key that made a signature, in ascending order.
+    #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
+    pub enum Trust {
+        /// It's not known whether the key can be trusted.
+        Undefined,
+        /// The key must never be trusted.
+        Never,
+        /// The key is trusted marginally.
+        Marginal,
+        /// The key is fully trusted.
+        Fully,
+        /// The key is trusted ultimately, usually as it's our own.
+        Ultimate,
+    }
+
+    /// The outcome of verifying a signature with a [`Verifier`].
+    #[derive(Debug, Clone, Eq, PartialEq)]
+    pub struct Outcome {
+        /// The result of the check.
+        pub status: Status,
+        /// The identity of the signer, like `Name <email>`, if known.
+        pub signer: Option<BString>,
+        /// The id of the key that made the signature, if known.
+        pub key: Option<BString>,
+        /// The fingerprint of the key that made the signature, if known.
+        pub fingerprint: Option<BString>,
+        /// The fingerprint of the primary key of the key that made the signature, if known.
+        pub primary_key_fingerprint: Option<BString>,
+        /// The trust in the key that made the signature, if known.
+        pub trust: Option<Trust>,
+        /// The human-readable output of the verification, suitable to be shown to the user.
+        pub output: BString,
+    }
+
+    impl Outcome {
+        /// Return `true` if the signature is good and its key is trusted enough.
+        pub fn is_valid(&self) -> bool {
+            self.status == Status::Good
+        }
+    }
+
+    /// Verify signatures over data.
+    pub trait Verifier {
+        /// Verify that `signature` was made over `signed_data`, and return the outcome.
+        ///
+        /// Errors are only returned if the signature couldn't be verified at all, while invalid signatures are
+        /// indicated in the [`Outcome`].
+        fn verify(
+            &self,
+            signature: &BStr,
+            signed_data: &[u8],
+        ) -> Result<Outcome, Box<dyn std::error::Error + Send + Sync + 'static>>;
+    }
+
+    impl<F> Verifier for F
+    where
+        F: Fn(&BStr, &[u8]) -> Result<Outcome, Box<dyn std::error::Error + Send + Sync + 'static>>,
+    {
+        fn verify(
+            &self,
+            signature: &BStr,
+            signed_data: &[u8],
+        ) -> Result<Outcome, Box<dyn std::error::Error + Send + Sync + 'static>> {
+            self(signature, signed_data)
+        }
+    }
+
+    /// The error returned when verifying the signature of commits or tags.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Decode(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        Verifier(#[from] super::verifier::Error),
+        #[error("Could not verify the signature")]
+        Verify(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+    }
+}
+
+///
+pub mod sign {
+    use crate::bstr::BString;
+
+    /// Produce signatures over data, for use when creating signed commits and tags.
+    pub trait Signer {
+        /// Return the signature over `data`, which is stored in the object as is.
+        fn sign(&mut self, data: &[u8]) -> Result<BString, Box<dyn std::error::Error + Send + Sync + 'static>>;
+    }
+
+    impl<F> Signer for F
+    where
+        F: FnMut(&[u8]) -> Result<BString, Box<dyn std::error::Error + Send + Sync + 'static>>,
+    {
+        fn sign(&mut self, data: &[u8]) -> Result<BString, Box<dyn std::error::Error + Send + Sync + 'static>> {
+            self(data)
+        }
+    }
+}
+
+///
+pub mod signer {
+    /// The error returned by [`Repository::signer()`](crate::Repository::signer()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ConfigValue(#[from] crate::config::key::GenericErrorWithValue),
+        #[error(transparent)]
+        ProgramPath(#[from] gix_config::path::interpolate::Error),
+        #[error(transparent)]
+        ParseTime(#[from] crate::config::time::Error),
+    }
+}
+
+///
+pub mod verifier {
+    /// The error returned by [`Repository::signature_verifier()`](crate::Repository::signature_verifier()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ConfigValue(#[from] crate::config::key::GenericErrorWithValue),
+        #[error(transparent)]
+        ProgramPath(#[from] gix_config::path::interpolate::Error),
+    }
+}
+
+///
+pub mod program {
+    use crate::bstr::BString;
+
+    /// The error returned by the [`Programs`](super::Programs) verifier and the [`ProgramSigner`](super::ProgramSigner).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The format of the signature couldn't be determined")]
+        UnknownFormat,
+        #[error("gpg.ssh.allowedSignersFile needs to be configured and exist for SSH signature verification")]
+        MissingAllowedSigners,
+        #[error("Could not write the signature to a temporary file")]
+        SignatureFile(#[source] std::io::Error),
+        #[error("user.signingKey needs to be configured to create signatures in the {format:?} format")]
+        MissingSigningKey { format: super::Format },
+        #[error("Could not write the signing key to a temporary file")]
+        SigningKeyFile(#[source] std::io::Error),
+        #[error("Could not run '{}'", program.display())]
+        Spawn {
+            source: std::io::Error,
+            program: std::path::PathBuf,
+        },
+        #[error("Could not pass input to '{}'", program.display())]
+        WriteInput {
+            source: std::io::Error,
+            program: std::path::PathBuf,
+        },
+        #[error("'{}' failed: {stderr}", program.display())]
+        Failed {
+            program: std::path::PathBuf,
+            stderr: BString,
+        },
+    }
+}
+
+/// A [`Verifier`](verify::Verifier) which runs external programs to verify signatures like `git` does, choosing the
+/// program by the [format](Format) of each signature.
+#[derive(Debug, Clone)]
+pub struct Programs {
+    /// The program to verify OpenPGP signatures with, which is compatible to `gpg`.
+    pub openpgp: PathBuf,
+    /// The program to verify X.509 signatures with, which is compatible to `gpgsm`.
+    pub x509: PathBuf,
+    /// The program to verify SSH signatures with, which is compatible to `ssh-keygen`.
+    pub ssh: PathBuf,
+    /// The file with the principals that are allowed to make SSH signatures, along with their keys.
+    ///
+    /// Without it, SSH signatures can't be verified.
+    pub ssh_allowed_signers: Option<PathBuf>,
+    /// The file with SSH keys which were revoked, and whose signatures are thus rejected.
+    pub ssh_revocation_file: Option<PathBuf>,
+    /// The minimal trust in the key of a good signature for it to be considered [valid](verify::Status::Good).
+    pub min_trust_level: verify::Trust,
+}
+
+impl Default for Programs {
+    fn default() -> Self {
+        Programs {
+            openpgp: "gpg".into(),
+            x509: "gpgsm".into(),
+            ssh: "ssh-keygen".into(),
+            ssh_allowed_signers: None,
+            ssh_revocation_file: None,
+            min_trust_level: verify::Trust::Undefined,
+        }
+    }
+}
+
+impl verify::Verifier for Programs {
+    fn verify(
+        &self,
+        signature: &BStr,
+        signed_data: &[u8],
+    ) -> Result<verify::Outcome, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        let format = Format::from_signature(signature).ok_or(program::Error::UnknownFormat)?;
+        let (_signature_file, signature_path) = tempfile_with(signature).map_err(program::Error::SignatureFile)?;
+
+        let (program, args): (_, &[&str]) = match format {
+            Format::OpenPgp => (&self.openpgp, &["--keyid-format=long"]),
+            Format::X509 => (&self.x509, &[]),
+            Format::Ssh => return Ok(self.verify_ssh(&signature_path, signed_data)?),
+        };
+        let output = run(
+            Command::new(program)
+                .args(args)
+                .args(["--status-fd=1", "--verify"])
+                .arg(&signature_path)
+                .arg("-"),
+            signed_data,
+        )?;
+        let outcome = parse_status(&output.stdout, output.stderr.into(), self.min_trust_level);
+        if outcome.status == verify::Status::CannotCheck && output.stdout.is_empty() && !output.status.success() {
+            return Err(program::Error::Failed {
+                program: program.clone(),
+                stderr: outcome.output,
+            }
+            .into());
+        }
+        Ok(outcome)
+    }
+}
+
+impl Programs {
+    /// Verify the SSH signature at `signature_path` like `git` does, by finding the principals that are allowed to sign
+    /// with its key, and verifying it on behalf of each of them until one succeeds.
+    /// If no principal is allowed to use the key, the signature is still checked, but won't be trusted.
+    fn verify_ssh(
+        &self,
+        signature_path: &std::path::Path,
+        signed_data: &[u8],
+    ) -> Result<verify::Outcome, program::Error> {
+        use verify::{Status, Trust};
+
+        let allowed_signers = self
+            .ssh_allowed_signers
+            .as_deref()
+            .ok_or(program::Error::MissingAllowedSigners)?;
+        let program = &self.ssh;
+        let principals = run(
+            Command::new(program)
+                .args(["-Y", "find-principals", "-f"])
+                .arg(allowed_signers)
+                .arg("-s")
+                .arg(signature_path),
+            &[],
+        )?;
+
+        let mut outcome = verify::Outcome {
+            status: Status::Bad,
+            signer: None,
+            key: None,
+            fingerprint: None,
+            primary_key_fingerprint: None,
+            trust: None,
+            output: BString::default(),
+        };
+        let principals = if principals.status.success() {
+            principals.stdout
+        } else {
+            Vec::new()
+        };
+        for principal in principals.lines().filter(|line| !line.is_empty()) {
+            let mut cmd = Command::new(program);
+            cmd.args(["-Y", "verify", "-n", "git", "-f"])
+                .arg(allowed_signers)
+                .arg("-I")
+                .arg(gix_path::from_bstr(principal.as_bstr()).as_ref())
+                .arg("-s")
+                .arg(signature_path);
+            if let Some(revocation_file) = &self.ssh_revocation_file {
+                cmd.arg("-r").arg(revocation_file);
+            }
+            let output = run(&mut cmd, signed_data)?;
+            outcome.output = output
+                .stdout
+                .iter()
+                .chain(&output.stderr)
+                .copied()
+                .collect::<Vec<_>>()
+                .into();
+            if output.status.success() {
+                outcome.status = Status::Good;
+                outcome.signer = Some(principal.into());
+                outcome.trust = Some(Trust::Fully);
+                outcome.fingerprint = ssh_fingerprint(&output.stdout).map(Into::into);
+                break;
+            }
+        }
+
+        if principals.is_empty() {
+            let output = run(
+                Command::new(program)
+                    .args(["-Y", "check-novalidate", "-n", "git", "-s"])
+                    .arg(signature_path),
+                signed_data,
+            )?;
+            if output.status.success() {
+                outcome.status = Status::Untrusted;
+                outcome.trust = Some(Trust::Undefined);
+                outcome.fingerprint = ssh_fingerprint(&output.stdout).map(Into::into);
+            }
+            outcome.output = output
+                .stdout
+                .iter()
+                .chain(&output.stderr)
+                .copied()
+                .collect::<Vec<_>>()
+                .into();
+        }
+        if outcome.status == Status::Good && outcome.trust.unwrap_or(Trust::Undefined) < self.min_trust_level {
+            outcome.status = Status::Untrusted;
+        }
+        outcome.key = outcome.fingerprint.clone();
+        Ok(outcome)
+    }
+}
+
+/// A [`Signer`](sign::Signer) which runs an external program to sign data like `git` does, depending on the [format](Format)
+/// of the signatures to create.
+#[derive(Debug, Clone)]
+pub struct ProgramSigner {
+    /// The format of the signatures to create.
+    pub format: Format,
+    /// The program to run, which is compatible to `gpg`, `gpgsm` or `ssh-keygen` depending on the `format`.
+    pub program: PathBuf,
+    /// The key to sign with, which is passed to the program as is.
+    ///
+    /// For SSH signatures, it's the path to the private key, or to a public key whose private key is available to the `ssh-agent`,
+    /// or the public key itself if it starts with `ssh-` or `key::`.
+    pub key: Option<BString>,
+}
+
+impl sign::Signer for ProgramSigner {
+    fn sign(&mut self, data: &[u8]) -> Result<BString, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        let format = self.format;
+        let key = self.key.as_ref().ok_or(program::Error::MissingSigningKey { format })?;
+        let mut cmd = Command::new(&self.program);
+        let mut _key_file = None;
+        match format {
+            Format::OpenPgp | Format::X509 => {
+                cmd.args(["--status-fd=2", "-bsau"])
+                    .arg(gix_path::from_bstr(key.as_bstr()).as_ref());
+            }
+            Format::Ssh => {
+                cmd.args(["-Y", "sign", "-n", "git", "-f"]);
+                let literal_key = key
+                    .strip_prefix(b"key::")
+                    .or_else(|| key.starts_with(b"ssh-").then_some(key.as_slice()));
+                match literal_key {
+                    Some(literal_key) => {
+                        let (file, path) = tempfile_with(literal_key).map_err(program::Error::SigningKeyFile)?;
+                        _key_file = Some(file);
+                        cmd.arg(path).arg("-U");
+                    }
+                    None => {
+                        cmd.arg(gix_path::from_bstr(key.as_bstr()).as_ref());
+                    }
+                }
+            }
+        }
+        let output = run(&mut cmd, data)?;
+        // Just like `git`, only trust `gpg` if it indicates that the signature was created.
+        let created = match format {
+            Format::OpenPgp | Format::X509 => output.stderr.contains_str("[GNUPG:] SIG_CREATED "),
+            Format::Ssh => true,
+        };
+        if !output.status.success() || !created || output.stdout.is_empty() {
+            return Err(program::Error::Failed {
+                program: self.program.clone(),
+                stderr: output.stderr.into(),
+            }
+            .into());
+        }
+        Ok(output.stdout.into())
+    }
+}
+
+/// Write `content` to a new temporary file and return it along with its path, which is valid as long as the file is kept.
+fn tempfile_with(content: &[u8]) -> std::io::Result<(gix_tempfile::Handle<gix_tempfile::handle::Writable>, PathBuf)> {
+    let mut file = gix_tempfile::new(
+        std::env::temp_dir(),
+        gix_tempfile::ContainingDirectory::Exists,
+        gix_tempfile::AutoRemove::Tempfile,
+    )?;
+    file.write_all(content)?;
+    let path = file.with_mut(|file| file.path().to_owned())?;
+    Ok((file, path))
+}
+
+/// Run `cmd` with `input` on stdin and return its output.
+fn run(cmd: &mut Command, input: &[u8]) -> Result<std::process::Output, program::Error> {
+    let program = PathBuf::from(cmd.get_program());
+    let spawn_error = |source| program::Error::Spawn {
+        source,
+        program: program.clone(),
+    };
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(spawn_error)?;
+    let mut stdin = child.stdin.take().expect("configured");
+    std::thread::scope(|scope| {
+        // Write the input while reading the output, as the program may not consume its input before writing output.
+        let writer = scope.spawn(move || stdin.write_all(input));
+        let output = child.wait_with_output().map_err(spawn_error)?;
+        match writer.join().expect("writing doesn't panic") {
+            // The program exited without reading all input, which is handled by looking at its output instead.
+            Err(err) if err.kind() != std::io::ErrorKind::BrokenPipe => Err(program::Error::WriteInput {
+                source: err,
+                program: program.clone(),
+            }),
+            _ => Ok(output),
+        }
+    })
+}
+
+/// Extract the key fingerprint from the `Good "git" signature … with <algorithm> key <fingerprint>` line of `ssh-keygen`.
+fn ssh_fingerprint(stdout: &[u8]) -> Option<&[u8]> {
+    let line = stdout
+        .lines()
+        .find(|line| line.starts_with(b"Good \"git\" signature"))?;
+    let pos = line.rfind(b" key ")?;
+    Some(&line[pos + b" key ".len()..])
+}
+
+/// Parse the machine-readable `status` lines of `gpg --status-fd`, along with its human-readable `output`.
+fn parse_status(status: &[u8], output: BString, min_trust_level: verify::Trust) -> verify::Outcome {
+    use verify::{Status, Trust};
+
+    let mut outcome = verify::Outcome {
+        status: Status::CannotCheck,
+        signer: None,
+        key: None,
+        fingerprint: None,
+        primary_key_fingerprint: None,
+        trust: None,
+        output,
+    };
+    let mut seen_status = false;
+    for line in status.lines() {
+        let line = match line.strip_prefix(b"[GNUPG:] ") {
+            Some(line) => line,
+            None => continue,
+        };
+        let (keyword, args) = line.split_once_str(" ").unwrap_or((line, b""));
+        let signature_status = match keyword {
+            b"GOODSIG" => Some(Status::Good),
+            b"BADSIG" => Some(Status::Bad),
+            b"EXPSIG" => Some(Status::ExpiredSignature),
+            b"EXPKEYSIG" => Some(Status::ExpiredKey),
+            b"REVKEYSIG" => Some(Status::RevokedKey),
+            b"ERRSIG" => Some(Status::CannotCheck),
+            _ => None,
+        };
+        if let Some(signature_status) = signature_status {
+            // Only the first status counts, just like in `git`, which treats multiple signatures as error.
+            if seen_status {
+                outcome.status = Status::CannotCheck;
+                continue;
+            }
+            seen_status = true;
+            outcome.status = signature_status;
+            let (key, signer) = args.split_once_str(" ").unwrap_or((args, b""));
+            outcome.key = Some(key.into());
+            if signature_status != Status::CannotCheck && !signer.is_empty() {
+                outcome.signer = Some(signer.into());
+            }
+            continue;
+        }
+        match keyword {
+            b"VALIDSIG" => {
+                let mut fields = args.split_str(" ");
+                outcome.fingerprint = fields.next().map(Into::into);
+                outcome.primary_key_fingerprint = fields.nth(8).map(Into::into);
+            }
+            b"TRUST_UNDEFINED" => outcome.trust = Some(Trust::Undefined),
+            b"TRUST_NEVER" => outcome.trust = Some(Trust::Never),
+            b"TRUST_MARGINAL" => outcome.trust = Some(Trust::Marginal),
+            b"TRUST_FULLY" => outcome.trust = Some(Trust::Fully),
+            b"TRUST_ULTIMATE" => outcome.trust = Some(Trust::Ultimate),
+            _ => {}
+        }
+    }
+    if outcome.status == Status::Good && outcome.trust.unwrap_or(Trust::Undefined) < min_trust_level {
+        outcome.status = Status::Untrusted;
+    }
+    outcome
+}
@@ -0,0 +1,122 @@
+//! Compute the ids of blobs from worktree files or in-memory data and optionally write them, similar to `git hash-object`.
+//!
+//! Use [`Repository::hash_object()`](crate::Repository::hash_object()) to obtain a [`Platform`]. By default, content is
+//! passed through the same filters that [staging](crate::Repository::stage()) would apply, so the resulting ids match
+//! those of the blobs that `git add` would produce.
+use std::io::Read;
+
+use crate::{bstr::BStr, Repository};
+
+/// A utility to hash files and data into blob ids, obtained with [`Repository::hash_object()`](crate::Repository::hash_object()).
+pub struct Platform<'repo> {
+    pub(crate) repo: &'repo Repository,
+    pub(crate) index: gix_index::File,
+    pub(crate) filter: crate::filter::Pipeline<'repo>,
+    pub(crate) buf: Vec<u8>,
+    pub(crate) write: bool,
+    pub(crate) apply_filters: bool,
+}
+
+///
+pub mod init {
+    /// The error returned by [`Repository::hash_object()`](crate::Repository::hash_object()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        AttributeStack(#[from] crate::config::attribute_stack::Error),
+        #[error(transparent)]
+        FilterPipeline(#[from] crate::filter::pipeline::options::Error),
+    }
+}
+
+/// The error returned by [`Platform::path()`] and [`Platform::bytes()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("A working tree is required to hash files from it")]
+    MissingWorkDir,
+    #[error("Could not read '{}' to hash it", path.display())]
+    Io {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error(transparent)]
+    ConvertToGit(#[from] Box<crate::filter::pipeline::convert_to_git::Error>),
+    #[error(transparent)]
+    WriteBlob(#[from] crate::object::write::Error),
+}
+
+/// Builder
+impl<'repo> Platform<'repo> {
+    /// If `toggle` is `true`, write all hashed blobs to the object database, like `git hash-object -w` does.
+    ///
+    /// By default, ids are only computed.
+    pub fn write_objects(mut self, toggle: bool) -> Self {
+        self.write = toggle;
+        self
+    }
+
+    /// If `toggle` is `false`, hash content as is without passing it through the filters configured in `.gitattributes`
+    /// and git configuration, like `git hash-object --no-filters` does.
+    ///
+    /// By default, filters are applied.
+    pub fn apply_filters(mut self, toggle: bool) -> Self {
+        self.apply_filters = toggle;
+        self
+    }
+}
+
+/// Hashing
+impl<'repo> Platform<'repo> {
+    /// Read the file at `rela_path` in the worktree and return the id of the blob it would be stored as, just like
+    /// `git hash-object <rela_path>`.
+    ///
+    /// Its content is passed through all filters that apply to `rela_path`, like `clean` filters and end-of-line conversions,
+    /// unless [filters are disabled](Self::apply_filters()). Note that symlinks are followed.
+    pub fn path(&mut self, rela_path: impl AsRef<BStr>) -> Result<gix_hash::ObjectId, Error> {
+        let rela_path = rela_path.as_ref();
+        let work_dir = self.repo.work_dir().ok_or(Error::MissingWorkDir)?;
+        let path = work_dir.join(gix_path::from_bstr(rela_path));
+        let file = std::fs::File::open(&path).map_err(|source| Error::Io { path, source })?;
+        self.hash(file, Some(rela_path))
+    }
+
+    /// Return the id of the blob that `data` would be stored as.
+    ///
+    /// If `rela_path` is set, `data` is passed through all filters that would apply to a file at `rela_path`, just like
+    /// `git hash-object --path <rela_path>` does, unless [filters are disabled](Self::apply_filters()).
+    /// Otherwise, it's hashed as is like `git hash-object --stdin` without `--path` would do.
+    pub fn bytes(&mut self, data: impl AsRef<[u8]>, rela_path: Option<&BStr>) -> Result<gix_hash::ObjectId, Error> {
+        self.hash(data.as_ref(), rela_path)
+    }
+
+    fn hash(&mut self, mut src: impl Read, rela_path: Option<&BStr>) -> Result<gix_hash::ObjectId, Error> {
+        let io_err = |source| Error::Io {
+            path: rela_path
+                .map(|p| gix_path::from_bstr(p).into_owned())
+                .unwrap_or_default(),
+            source,
+        };
+        self.buf.clear();
+        match rela_path.filter(|_| self.apply_filters) {
+            Some(rela_path) => {
+                let mut converted = self
+                    .filter
+                    .convert_to_git(src, gix_path::from_bstr(rela_path).as_ref(), &self.index)
+                    .map_err(Box::new)?;
+                converted.read_to_end(&mut self.buf).map_err(io_err)?;
+            }
+            None => {
+                src.read_to_end(&mut self.buf).map_err(io_err)?;
+            }
+        }
+        Ok(if self.write {
+            self.repo.write_blob(&self.buf)?.detach()
+        } else {
+            gix_object::compute_hash(self.repo.object_hash(), gix_object::Kind::Blob, &self.buf)
+        })
+    }
+}
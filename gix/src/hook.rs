@@ -0,0 +1,342 @@
+//! Hooks are executables in the [hooks directory](crate::Repository::hooks_dir()) which are run at certain points
+//! of git operations, and which may abort them.
+//!
+//! An [`Invocation`] describes the arguments, standard input and environment a hook expects, and can be created for
+//! all hooks that `git` runs when committing, merging, checking out, pushing and receiving pushes.
+//! It's run with [`Repository::invoke_hook()`](crate::Repository::invoke_hook()), whose [`Outcome`] allows to honor
+//! or surface a failing hook.
+use std::{
+    borrow::Cow,
+    ffi::{OsStr, OsString},
+    path::PathBuf,
+    process::ExitStatus,
+};
+
+use gix_hash::ObjectId;
+
+use crate::bstr::{BStr, BString, ByteVec};
+
+/// A reference update as passed to hooks that run when pushing or receiving a push.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RefUpdate {
+    /// The full name of the reference, like `refs/heads/main`.
+    pub name: BString,
+    /// The id the reference points to before the update, or the null id if it is created.
+    pub previous_id: ObjectId,
+    /// The id the reference points to after the update, or the null id if it is deleted.
+    pub new_id: ObjectId,
+}
+
+/// An update to a remote reference as passed to the `pre-push` hook.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PushUpdate {
+    /// The full name of the local reference that is pushed, or `(delete)` if the remote reference is deleted.
+    pub local_ref: BString,
+    /// The id of the local reference, or the null id if the remote reference is deleted.
+    pub local_id: ObjectId,
+    /// The full name of the remote reference that is updated.
+    pub remote_ref: BString,
+    /// The id the remote reference currently points to, or the null id if it doesn't exist yet.
+    pub remote_id: ObjectId,
+}
+
+/// Where the commit message passed to the `prepare-commit-msg` hook came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MessageSource {
+    /// The message was provided directly, like with `git commit -m`.
+    Message,
+    /// The message was read from a template.
+    Template,
+    /// The message is the one of a merge.
+    Merge,
+    /// The message is the one of a squash.
+    Squash,
+    /// The message was taken from the given commit, like with `git commit -c`.
+    Commit(ObjectId),
+}
+
+/// The description of how to run a hook, which is created for a particular hook and executed with
+/// [`Repository::invoke_hook()`](crate::Repository::invoke_hook()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Invocation {
+    pub(crate) name: Cow<'static, str>,
+    pub(crate) args: Vec<OsString>,
+    pub(crate) stdin: Option<Vec<u8>>,
+    pub(crate) env: Vec<(OsString, OsString)>,
+    pub(crate) in_git_dir: bool,
+    pub(crate) capture_output: bool,
+}
+
+/// Lifecycle
+impl Invocation {
+    /// Create a new invocation of the hook named `name`, like `pre-commit`, without arguments, standard input or
+    /// additional environment variables.
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Invocation {
+            name: name.into(),
+            args: Vec::new(),
+            stdin: None,
+            env: Vec::new(),
+            in_git_dir: false,
+            capture_output: false,
+        }
+    }
+
+    /// Run before a commit is created, without arguments.
+    pub fn pre_commit() -> Self {
+        Self::new("pre-commit").env("GIT_EDITOR", ":")
+    }
+
+    /// Run after the commit message was prepared in the file at `message_path`, and before an editor would be started,
+    /// with `source` indicating where the message came from, if known.
+    pub fn prepare_commit_msg(message_path: impl Into<OsString>, source: Option<MessageSource>) -> Self {
+        let mut out = Self::new("prepare-commit-msg").arg(message_path).env("GIT_EDITOR", ":");
+        if let Some(source) = source {
+            out = match source {
+                MessageSource::Message => out.arg("message"),
+                MessageSource::Template => out.arg("template"),
+                MessageSource::Merge => out.arg("merge"),
+                MessageSource::Squash => out.arg("squash"),
+                MessageSource::Commit(id) => out.arg("commit").arg(id.to_string()),
+            };
+        }
+        out
+    }
+
+    /// Run with the path to the file containing the final commit message, which the hook may alter.
+    pub fn commit_msg(message_path: impl Into<OsString>) -> Self {
+        Self::new("commit-msg").arg(message_path).env("GIT_EDITOR", ":")
+    }
+
+    /// Run after a commit was created, without arguments.
+    pub fn post_commit() -> Self {
+        Self::new("post-commit")
+    }
+
+    /// Run after a merge succeeded and before the merge commit is created, without arguments.
+    pub fn pre_merge_commit() -> Self {
+        Self::new("pre-merge-commit").env("GIT_EDITOR", ":")
+    }
+
+    /// Run after a merge was performed, with `is_squash` indicating if it was a squash merge.
+    pub fn post_merge(is_squash: bool) -> Self {
+        Self::new("post-merge").arg(if is_squash { "1" } else { "0" })
+    }
+
+    /// Run after the work tree was updated from the `previous_head` to the `new_head` commit, with `is_branch_checkout`
+    /// being `true` if `HEAD` changed, or `false` if only some files were checked out.
+    ///
+    /// Use the null id as `previous_head` after a clone.
+    pub fn post_checkout(previous_head: ObjectId, new_head: ObjectId, is_branch_checkout: bool) -> Self {
+        Self::new("post-checkout")
+            .arg(previous_head.to_string())
+            .arg(new_head.to_string())
+            .arg(if is_branch_checkout { "1" } else { "0" })
+    }
+
+    /// Run before pushing `updates` to the remote named `remote_name` at `url`.
+    ///
+    /// If the remote isn't named, `remote_name` should be the `url` as well.
+    pub fn pre_push<'a>(
+        remote_name: impl Into<OsString>,
+        url: impl Into<OsString>,
+        updates: impl IntoIterator<Item = &'a PushUpdate>,
+    ) -> Self {
+        let mut stdin = Vec::new();
+        for update in updates {
+            stdin.push_str(&update.local_ref);
+            stdin.push(b' ');
+            stdin.push_str(update.local_id.to_string());
+            stdin.push(b' ');
+            stdin.push_str(&update.remote_ref);
+            stdin.push(b' ');
+            stdin.push_str(update.remote_id.to_string());
+            stdin.push(b'\n');
+        }
+        Self::new("pre-push").arg(remote_name).arg(url).stdin(stdin)
+    }
+
+    /// Run once in the receiving repository before any of the `updates` of a push are applied.
+    pub fn pre_receive<'a>(updates: impl IntoIterator<Item = &'a RefUpdate>) -> Self {
+        Self::new("pre-receive")
+            .receiving()
+            .stdin(ref_updates_to_stdin(updates))
+    }
+
+    /// Run in the receiving repository for each reference named `ref_name` before it's updated
+    /// from `previous_id` to `new_id`.
+    pub fn update(ref_name: &BStr, previous_id: ObjectId, new_id: ObjectId) -> Self {
+        Self::new("update")
+            .receiving()
+            .arg(gix_path::from_bstr(ref_name).into_owned())
+            .arg(previous_id.to_string())
+            .arg(new_id.to_string())
+    }
+
+    /// Run once in the receiving repository after all `updates` of a push were applied.
+    pub fn post_receive<'a>(updates: impl IntoIterator<Item = &'a RefUpdate>) -> Self {
+        Self::new("post-receive")
+            .receiving()
+            .stdin(ref_updates_to_stdin(updates))
+    }
+
+    /// Run once in the receiving repository after the references named `ref_names` were updated.
+    pub fn post_update<'a>(ref_names: impl IntoIterator<Item = &'a BStr>) -> Self {
+        Self::new("post-update")
+            .receiving()
+            .args(ref_names.into_iter().map(|name| gix_path::from_bstr(name).into_owned()))
+    }
+
+    fn receiving(mut self) -> Self {
+        self.in_git_dir = true;
+        self
+    }
+}
+
+/// Builder
+impl Invocation {
+    /// Append `arg` to the arguments of the hook.
+    pub fn arg(mut self, arg: impl Into<OsString>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Append all `args` to the arguments of the hook.
+    pub fn args(mut self, args: impl IntoIterator<Item = impl Into<OsString>>) -> Self {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    /// Pass `input` to the hook on standard input, which otherwise is empty.
+    pub fn stdin(mut self, input: impl Into<Vec<u8>>) -> Self {
+        self.stdin = Some(input.into());
+        self
+    }
+
+    /// Set the environment variable `key` to `value` for the hook.
+    pub fn env(mut self, key: impl Into<OsString>, value: impl Into<OsString>) -> Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+
+    /// Pass the push options `options`, like `git push --push-option` would, to hooks that run when receiving a push.
+    pub fn push_options<'a>(mut self, options: impl IntoIterator<Item = &'a BStr>) -> Self {
+        let mut count = 0;
+        for (idx, option) in options.into_iter().enumerate() {
+            self = self.env(
+                format!("GIT_PUSH_OPTION_{idx}"),
+                gix_path::from_bstr(option).into_owned(),
+            );
+            count += 1;
+        }
+        self.env("GIT_PUSH_OPTION_COUNT", count.to_string())
+    }
+
+    /// If `toggle` is `true`, capture standard output and standard error of the hook into the [`Outcome`] instead
+    /// of letting them pass through to the standard output and standard error of this process.
+    pub fn capture_output(mut self, toggle: bool) -> Self {
+        self.capture_output = toggle;
+        self
+    }
+}
+
+/// Access
+impl Invocation {
+    /// The name of the hook to run, like `pre-commit`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The arguments to pass to the hook.
+    pub fn arguments(&self) -> impl Iterator<Item = &OsStr> {
+        self.args.iter().map(OsString::as_os_str)
+    }
+
+    /// The data to pass to the hook on standard input, if any.
+    pub fn input(&self) -> Option<&[u8]> {
+        self.stdin.as_deref()
+    }
+}
+
+fn ref_updates_to_stdin<'a>(updates: impl IntoIterator<Item = &'a RefUpdate>) -> Vec<u8> {
+    let mut out = Vec::new();
+    for update in updates {
+        out.push_str(update.previous_id.to_string());
+        out.push(b' ');
+        out.push_str(update.new_id.to_string());
+        out.push(b' ');
+        out.push_str(&update.name);
+        out.push(b'\n');
+    }
+    out
+}
+
+/// The result of [running a hook](crate::Repository::invoke_hook()).
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// The name of the hook that ran.
+    pub name: Cow<'static, str>,
+    /// The path to the executable of the hook.
+    pub path: PathBuf,
+    /// The exit status of the hook.
+    pub status: ExitStatus,
+    /// The standard output of the hook if it was [captured](Invocation::capture_output()), or empty otherwise.
+    pub stdout: Vec<u8>,
+    /// The standard error of the hook if it was [captured](Invocation::capture_output()), or empty otherwise.
+    pub stderr: Vec<u8>,
+}
+
+impl Outcome {
+    /// Return `true` if the hook succeeded, which allows the operation it guards to proceed.
+    pub fn is_success(&self) -> bool {
+        self.status.success()
+    }
+
+    /// Turn this outcome into an error if the hook failed, or return it unchanged otherwise.
+    pub fn into_result(self) -> Result<Self, rejected::Error> {
+        if self.is_success() {
+            Ok(self)
+        } else {
+            Err(rejected::Error {
+                name: self.name.into_owned(),
+                status: self.status,
+                stderr: self.stderr.into(),
+            })
+        }
+    }
+}
+
+///
+pub mod run {
+    use std::path::PathBuf;
+
+    /// The error returned by [`Repository::run_hook()`](crate::Repository::run_hook()) and
+    /// [`Repository::invoke_hook()`](crate::Repository::invoke_hook()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not interpolate the path at 'core.hooksPath'")]
+        HooksPath(#[from] gix_config::path::interpolate::Error),
+        #[error("Could not run the hook at '{}'", path.display())]
+        Spawn { source: std::io::Error, path: PathBuf },
+        #[error("Could not pass input to or read output from the hook at '{}'", path.display())]
+        Io { source: std::io::Error, path: PathBuf },
+    }
+}
+
+///
+pub mod rejected {
+    use crate::bstr::BString;
+
+    /// The error returned by [`Outcome::into_result()`](super::Outcome::into_result()) if the hook failed.
+    #[derive(Debug, thiserror::Error)]
+    #[error("The '{name}' hook failed with {status}")]
+    pub struct Error {
+        /// The name of the hook.
+        pub name: String,
+        /// The exit status of the hook.
+        pub status: std::process::ExitStatus,
+        /// The standard error of the hook if it was captured, or empty otherwise.
+        pub stderr: BString,
+    }
+}
@@ -0,0 +1,176 @@
+//! Detect [Git LFS](https://git-lfs.com) pointer files, which are stored in place of the content of large files, and resolve
+//! them to the content they point to.
+//!
+//! Use [`Pointer::from_bytes()`] to learn if a blob is a pointer, and a [`Store`] to obtain the content it refers to,
+//! for instance the one returned by [`Repository::lfs_store()`](crate::Repository::lfs_store()).
+//! [`Blob::resolve_lfs_pointer()`](crate::Blob::resolve_lfs_pointer()) replaces the data of a blob with the content it
+//! points to, and the [blob diff platform](crate::object::blob::diff::Platform) can do the same for both of its sides.
+use std::path::PathBuf;
+
+use crate::bstr::{BStr, BString, ByteSlice};
+
+/// The version line that starts each pointer file.
+const VERSION: &str = "https://git-lfs.github.com/spec/v1";
+/// The version used by pre-release versions of `git lfs`, which is still accepted when reading.
+const LEGACY_VERSION: &str = "https://hawser.github.com/spec/v1";
+/// Files larger than this can't be pointers, just like `git lfs` assumes.
+const MAX_POINTER_SIZE: usize = 1024;
+
+/// A pointer to a large file, stored in `git` instead of the file itself.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Pointer {
+    /// The lower-case, hex-encoded SHA-256 hash of the content.
+    pub oid: String,
+    /// The size of the content in bytes.
+    pub size: u64,
+    /// All other `key value` pairs of the pointer, like `ext-0-name sha256:…` for extensions, sorted by key.
+    pub extra: Vec<(BString, BString)>,
+}
+
+impl Pointer {
+    /// Parse `data` as pointer file, or return `None` if it isn't one according to the
+    /// [specification](https://github.com/git-lfs/git-lfs/blob/main/docs/spec.md).
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        if data.len() > MAX_POINTER_SIZE || !data.ends_with(b"\n") {
+            return None;
+        }
+        let mut lines = data[..data.len() - 1].split_str("\n").map(|line| {
+            line.split_once_str(" ")
+                .map(|(key, value)| (key.as_bstr(), value.as_bstr()))
+        });
+        match lines.next()?? {
+            (key, version) if key == "version" && (version == VERSION || version == LEGACY_VERSION) => {}
+            _ => return None,
+        }
+
+        let (mut oid, mut size, mut extra) = (None, None, Vec::new());
+        let mut previous_key: Option<&BStr> = None;
+        for line in lines {
+            let (key, value) = line?;
+            if previous_key.map_or(false, |previous| previous >= key) || !is_valid_key(key) {
+                return None;
+            }
+            previous_key = Some(key);
+            match key.as_bytes() {
+                b"oid" => {
+                    let hex = value.strip_prefix(b"sha256:")?;
+                    if hex.len() != 64 || !hex.iter().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(b)) {
+                        return None;
+                    }
+                    oid = Some(hex.to_str().ok()?.to_owned());
+                }
+                b"size" => {
+                    if value.is_empty() || !value.iter().all(u8::is_ascii_digit) {
+                        return None;
+                    }
+                    size = Some(value.to_str().ok()?.parse().ok()?);
+                }
+                _ => extra.push((key.to_owned(), value.to_owned())),
+            }
+        }
+        Some(Pointer {
+            oid: oid?,
+            size: size?,
+            extra,
+        })
+    }
+
+    /// Serialize this instance into the pointer file format, which can be read back with [`from_bytes()`](Self::from_bytes()).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let oid = format!("sha256:{}", self.oid);
+        let size = self.size.to_string();
+        let mut lines: Vec<(&[u8], &[u8])> = vec![(b"oid", oid.as_bytes()), (b"size", size.as_bytes())];
+        lines.extend(self.extra.iter().map(|(key, value)| (key.as_bytes(), value.as_bytes())));
+        lines.sort_by(|a, b| a.0.cmp(b.0));
+
+        let mut out = format!("version {VERSION}\n").into_bytes();
+        for (key, value) in lines {
+            out.extend_from_slice(key);
+            out.push(b' ');
+            out.extend_from_slice(value);
+            out.push(b'\n');
+        }
+        out
+    }
+}
+
+fn is_valid_key(key: &BStr) -> bool {
+    !key.is_empty()
+        && key
+            .iter()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || *b == b'.' || *b == b'-')
+}
+
+///
+pub mod find {
+    /// The error returned by [`Store::find()`](super::Store::find()).
+    pub type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
+}
+
+/// A store for the content of large files, which makes it available by [`Pointer`].
+pub trait Store {
+    /// Return the content `pointer` refers to, or `None` if it isn't available in this store.
+    fn find(&self, pointer: &Pointer) -> Result<Option<Vec<u8>>, find::Error>;
+}
+
+/// A [`Store`] that reads content from a local directory in the layout used by `git lfs`, which is
+/// `<objects-dir>/<oid[0..2]>/<oid[2..4]>/<oid>`.
+#[derive(Debug, Clone)]
+pub struct LocalStore {
+    /// The directory containing all objects.
+    pub objects_dir: PathBuf,
+}
+
+impl LocalStore {
+    /// Create a new instance to read from `objects_dir`.
+    pub fn at(objects_dir: impl Into<PathBuf>) -> Self {
+        LocalStore {
+            objects_dir: objects_dir.into(),
+        }
+    }
+
+    /// Return the path at which the content of `pointer` is stored.
+    pub fn path(&self, pointer: &Pointer) -> PathBuf {
+        let oid = &pointer.oid;
+        self.objects_dir
+            .join(oid.get(..2).unwrap_or_default())
+            .join(oid.get(2..4).unwrap_or_default())
+            .join(oid)
+    }
+}
+
+impl Store for LocalStore {
+    fn find(&self, pointer: &Pointer) -> Result<Option<Vec<u8>>, find::Error> {
+        let path = self.path(pointer);
+        let data = match std::fs::read(&path) {
+            Ok(data) => data,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        if data.len() as u64 != pointer.size {
+            return Err(format!(
+                "The object at '{}' has {} bytes, but {} bytes were expected",
+                path.display(),
+                data.len(),
+                pointer.size
+            )
+            .into());
+        }
+        Ok(Some(data))
+    }
+}
+
+/// Replace `data` with the content it points to if it's a pointer that can be found in `store`, and return `true` if this was the case.
+pub(crate) fn resolve_pointer(data: &mut Vec<u8>, store: &dyn Store) -> Result<bool, find::Error> {
+    let pointer = match Pointer::from_bytes(data) {
+        Some(pointer) => pointer,
+        None => return Ok(false),
+    };
+    Ok(match store.find(&pointer)? {
+        Some(content) => {
+            *data = content;
+            true
+        }
+        None => false,
+    })
+}
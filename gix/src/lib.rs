@@ -42,6 +42,8 @@
 //!
 //! In scenarios where the same objects are accessed multiple times, the object cache can be useful and is to be configured specifically
 //! using the [`object_cache_size(…)`][crate::Repository::object_cache_size()] method.
+//! Caches are created per handle, but can be shared between a [`Repository`] and its clones with
+//! [`shared_object_cache_size(…)`][crate::Repository::shared_object_cache_size()].
 //!
 //! Use the `cache-efficiency-debug` cargo feature to learn how efficient the cache actually is - it's easy to end up with lowered
 //! performance if the cache is not hit in 50% of the time.
@@ -129,8 +131,6 @@ pub use gix_ref as refs;
 pub use gix_refspec as refspec;
 pub use gix_revwalk as revwalk;
 pub use gix_sec as sec;
-#[cfg(feature = "status")]
-pub use gix_status as status;
 pub use gix_tempfile as tempfile;
 pub use gix_trace as trace;
 pub use gix_traverse as traverse;
@@ -172,7 +172,11 @@ pub use types::{Pathspec, PathspecDetached, Submodule};
 ///
 pub mod clone;
 pub mod commit;
+pub mod connectivity;
+pub mod error;
+pub mod gpg;
 pub mod head;
+pub mod hook;
 pub mod id;
 pub mod object;
 #[cfg(feature = "attributes")]
@@ -302,16 +306,73 @@ pub mod filter;
 ///
 pub mod remote;
 
+#[cfg(feature = "blocking-network-client")]
+pub mod promisor;
+
 ///
 pub mod init;
 
+pub mod lfs;
+
 /// Not to be confused with 'status'.
 pub mod state;
 
 ///
 pub mod shallow;
 
+pub mod compat;
+
+#[cfg(feature = "status")]
+pub mod status;
+
+#[cfg(feature = "revision")]
+pub mod cat_file;
+
+#[cfg(feature = "attributes")]
+pub mod check_attr;
+
+#[cfg(feature = "excludes")]
+pub mod check_ignore;
+
+#[cfg(feature = "attributes")]
+pub mod driver;
+
+#[cfg(feature = "status")]
+pub mod checkout;
+
+#[cfg(feature = "status")]
+pub mod clean;
+
+#[cfg(feature = "status")]
+pub mod ls_files;
+
+#[cfg(feature = "status")]
+pub mod sparse;
+
+#[cfg(feature = "maintenance")]
+pub mod maintenance;
+
+#[cfg(feature = "fsck")]
+pub mod fsck;
+
+#[cfg(feature = "revision")]
+pub mod replay;
+
+#[cfg(feature = "revision")]
+pub mod subtree;
+
+#[cfg(feature = "revision")]
+pub mod notes;
+
+#[cfg(feature = "attributes")]
+pub mod stage;
+
+#[cfg(feature = "attributes")]
+pub mod hash_object;
+
 ///
 pub mod discover;
 
+pub mod pool;
+
 pub mod env;
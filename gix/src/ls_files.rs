@@ -0,0 +1,130 @@
+//! Query tracked, unmerged, untracked and ignored files, similar to `git ls-files`.
+//!
+//! Use [`Repository::ls_files()`](crate::Repository::ls_files()) to obtain a [`Platform`] for queries.
+use crate::{
+    bstr::{BStr, BString},
+    PathspecDetached, Repository,
+};
+
+/// An entry of the index, as returned by [`Platform::cached()`] and [`Platform::unmerged()`].
+#[derive(Debug, Clone, Copy)]
+pub struct Tracked<'a> {
+    /// The path of the entry relative to the root of the worktree.
+    pub rela_path: &'a BStr,
+    /// The entry itself, with its id, mode and flags.
+    pub entry: &'a gix_index::Entry,
+}
+
+impl Tracked<'_> {
+    /// Return the stage of the entry, which is 0 for merged entries, or 1, 2 and 3 for the base, our and their side of a conflict.
+    pub fn stage(&self) -> u32 {
+        self.entry.stage()
+    }
+}
+
+/// A utility to query the files of a repository, obtained with [`Repository::ls_files()`](crate::Repository::ls_files()).
+///
+/// All queries are limited to the paths matching the pathspecs the platform was created with.
+pub struct Platform<'repo> {
+    pub(crate) repo: &'repo Repository,
+    pub(crate) index: crate::worktree::Index,
+    pub(crate) pathspec: Option<PathspecDetached>,
+}
+
+/// Queries
+impl<'repo> Platform<'repo> {
+    /// Return the index that is queried.
+    pub fn index(&self) -> &gix_index::State {
+        &self.index
+    }
+
+    /// Iterate over all entries of the index in order, including the ones of all stages of conflicting paths,
+    /// similar to `git ls-files --stage`.
+    pub fn cached(&mut self) -> impl Iterator<Item = Tracked<'_>> + '_ {
+        let index = &*self.index;
+        let pathspec = &mut self.pathspec;
+        index.entries().iter().filter_map(move |entry| {
+            let rela_path = entry.path(index);
+            pathspec
+                .as_mut()
+                .map_or(true, |spec| spec.is_included(rela_path, Some(false)))
+                .then_some(Tracked { rela_path, entry })
+        })
+    }
+
+    /// Iterate over all entries of the index that are part of a conflict, similar to `git ls-files --unmerged`.
+    pub fn unmerged(&mut self) -> impl Iterator<Item = Tracked<'_>> + '_ {
+        self.cached().filter(|tracked| tracked.stage() != 0)
+    }
+
+    /// Return all untracked files that aren't ignored, similar to `git ls-files --others --exclude-standard`.
+    ///
+    /// Nested repositories are listed as directory with trailing slash.
+    pub fn others(&mut self) -> Result<impl Iterator<Item = BString>, Error> {
+        Ok(self.walk(false)?.into_iter().filter_map(|item| match item {
+            crate::status::Item::Untracked { rela_path } => Some(rela_path),
+            _ => None,
+        }))
+    }
+
+    /// Return all ignored files that aren't tracked, similar to `git ls-files --others --ignored --exclude-standard --directory`.
+    ///
+    /// Ignored directories are listed as a whole with trailing slash.
+    pub fn ignored(&mut self) -> Result<impl Iterator<Item = BString>, Error> {
+        Ok(self.walk(true)?.into_iter().filter_map(|item| match item {
+            crate::status::Item::Ignored { rela_path } => Some(rela_path),
+            _ => None,
+        }))
+    }
+
+    fn walk(&mut self, ignored: bool) -> Result<Vec<crate::status::Item>, Error> {
+        let repo = self.repo;
+        let work_dir = repo.work_dir().ok_or(Error::MissingWorkDir)?;
+        let mut items = Vec::new();
+        crate::status::untracked::Walk {
+            work_dir,
+            index: &self.index,
+            excludes: repo.excludes(
+                &self.index,
+                None,
+                gix_worktree::stack::state::ignore::Source::WorktreeThenIdMappingIfNotSkipped,
+            )?,
+            pathspec: self.pathspec.as_mut(),
+            mode: crate::status::Untracked::All,
+            ignored,
+            icase: repo.config.ignore_case.then(|| self.index.prepare_icase_backing()),
+            precompose_unicode: repo.filesystem_options()?.precompose_unicode,
+        }
+        .directory(&mut BString::default(), &mut items)?;
+        Ok(items)
+    }
+}
+
+///
+pub mod init {
+    /// The error returned by [`Repository::ls_files()`](crate::Repository::ls_files()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        Pathspec(#[from] crate::pathspec::init::Error),
+        #[error("Could not prepare the pathspec for use")]
+        DetachPathspec(#[source] std::io::Error),
+    }
+}
+
+/// The error returned by [`Platform::others()`] and [`Platform::ignored()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("A working tree is required to list untracked files")]
+    MissingWorkDir,
+    #[error(transparent)]
+    ExcludeStack(#[from] crate::config::exclude_stack::Error),
+    #[error(transparent)]
+    FilesystemOptions(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    Status(#[from] crate::status::Error),
+}
@@ -0,0 +1,1304 @@
+//! Repack objects, expire reflogs, prune unreachable objects and remove stale tempfiles, similar to `git gc`.
+//!
+//! Use [`Repository::maintenance()`](crate::Repository::maintenance()) to obtain a [`Platform`] to run individual tasks,
+//! or all of them at once with [`Platform::run()`].
+//!
+//! Unreachable objects which didn't expire yet are kept in a _cruft pack_ along with their modification times, unless
+//! `gc.cruftPacks` is `false`, in which case they are written as loose objects.
+//!
+//! Unreachable loose objects and temporary files are only removed once they are older than the [prune expiry time](Platform::with_prune_expire()),
+//! which gives concurrent writers time to make their new objects reachable. Writers that skip writing an object as it
+//! already exists update its modification time instead, so it isn't pruned while new objects start to refer to it.
+//!
+//! When repacking all objects, a reachability bitmap is written for the new pack if `repack.writeBitmaps` is `true`,
+//! or if the repository is bare.
+//!
+//! If `repack.useDeltaIslands` is `true`, deltas are kept within the delta islands configured with `pack.island`
+//! when repacking all objects, so that the objects of one island never need objects of another island as delta base.
+//!
+//! Packs with a `.keep` file are left untouched, and unless `repack.packKeptObjects` is `true`, their objects aren't added to
+//! new packs either. Use [`Platform::keep_pack()`] and [`Platform::unkeep_pack()`] to protect packs from being repacked.
+//!
+//! ### Deviation
+//!
+//! * Objects of alternate object databases are packed as well, as if they were local objects.
+//! * Packs with a `.promisor` file are left untouched, but their objects are still added to new packs.
+//! * Commit-graphs aren't rewritten, and a multi-pack-index is removed when [repacking all objects](Platform::repack_all()).
+//!   Use [`Platform::write_multi_pack_index()`] to write a new one.
+//! * Only a single multi-pack-index can be expired and repacked, but not a multi-pack-index chain.
+//! * `pack.islandCore` isn't supported, and as no new deltas are computed, objects whose existing delta would cross
+//!   an island are stored as base objects.
+use std::{
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+    time::SystemTime,
+};
+
+use gix_hash::ObjectId;
+use gix_pack::data::output;
+
+use crate::Repository;
+
+///
+pub mod init {
+    /// The error returned by [`Repository::maintenance()`](crate::Repository::maintenance()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Expiry(#[from] crate::config::key::GenericErrorWithValue),
+        #[error(transparent)]
+        Boolean(#[from] crate::config::boolean::Error),
+        #[error(transparent)]
+        CompressionLevel(#[from] crate::config::key::GenericError),
+    }
+}
+
+/// The error returned by the tasks of the [`Platform`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    ReadOnly(#[from] crate::repository::read_only::Error),
+    #[error("Could not open packed references")]
+    PackedReferences(#[from] crate::reference::iter::Error),
+    #[error("Could not iterate references")]
+    ReferencesInit(#[from] crate::reference::iter::init::Error),
+    #[error("Could not iterate references")]
+    References(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error("Could not obtain the HEAD reference")]
+    Head(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    FindHeader(#[from] crate::object::find::Error),
+    #[error(transparent)]
+    PeelTag(#[from] crate::object::peel::to_kind::Error),
+    #[error("Could not open a linked worktree to learn about the objects it refers to")]
+    OpenWorktree(#[from] crate::open::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    Walk(#[from] crate::revision::walk::Error),
+    #[error(transparent)]
+    WalkIter(#[from] gix_traverse::commit::ancestors::Error),
+    #[error("Could not find all reachable objects")]
+    CountObjects(#[from] output::count::objects::Error),
+    #[error("Could not produce the entries of the new pack")]
+    WriteEntries(#[from] output::bytes::Error<output::entry::iter_from_counts::Error>),
+    #[error(transparent)]
+    WritePack(#[from] gix_pack::bundle::write::Error),
+    #[error(transparent)]
+    OpenPackIndex(#[from] gix_pack::index::init::Error),
+    #[error(transparent)]
+    OpenMultiPackIndex(#[from] gix_pack::multi_index::init::Error),
+    #[error(transparent)]
+    WriteMultiPackIndex(#[from] gix_pack::multi_index::write::Error),
+    #[error(transparent)]
+    OpenMultiPackIndexChain(#[from] gix_pack::multi_index::chain::Error),
+    #[error("Could not lock the multi-pack-index chain to append a layer")]
+    LockMultiPackIndexChain(#[source] gix_lock::acquire::Error),
+    #[error(transparent)]
+    OpenMtimes(#[from] gix_pack::mtimes::init::Error),
+    #[error(transparent)]
+    OpenPack(#[from] gix_pack::bundle::init::Error),
+    #[error(transparent)]
+    WriteBitmap(#[from] gix_pack::bitmap::write::Error),
+    #[error("The delta island pattern {pattern:?} is not a valid regular expression")]
+    IslandPattern {
+        pattern: crate::bstr::BString,
+        source: regex::Error,
+    },
+    #[error(transparent)]
+    Islands(#[from] gix_pack::data::output::islands::Error),
+    #[error(transparent)]
+    IterLooseObjects(#[from] gix_odb::loose::iter::Error),
+    #[error("Could not write unreachable object as loose object")]
+    WriteLooseObject(#[source] gix_odb::write::Error),
+    #[error(transparent)]
+    FindPackedObject(#[from] gix_object::find::Error),
+    #[error("Could not lock reflog at \"{}\" to rewrite it", path.display())]
+    LockReflog {
+        path: PathBuf,
+        source: gix_lock::acquire::Error,
+    },
+    #[error("There is no pack for the index at \"{}\"", path.display())]
+    MissingPack { path: PathBuf },
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("The operation was interrupted")]
+    Interrupted,
+}
+
+/// A utility to keep the object database and reflogs of a repository tidy, obtained with
+/// [`Repository::maintenance()`](crate::Repository::maintenance()).
+pub struct Platform<'repo> {
+    pub(crate) repo: &'repo Repository,
+    pub(crate) prune_expire: Option<SystemTime>,
+    pub(crate) reflog_expire: Option<SystemTime>,
+    pub(crate) cruft_packs: bool,
+    pub(crate) bitmaps: Option<gix_pack::bitmap::write::Selection>,
+    pub(crate) delta_islands: bool,
+    pub(crate) pack_kept_objects: bool,
+    pub(crate) compression_level: u32,
+}
+
+/// The outcome of [`Platform::pack_loose_objects()`] and [`Platform::repack_all()`].
+#[derive(Debug, Clone, Default)]
+pub struct Repack {
+    /// The newly written pack, or `None` if there was nothing to pack.
+    pub pack: Option<gix_pack::bundle::write::Outcome>,
+    /// The newly written cruft pack with all unreachable objects that didn't expire yet, or `None` if there were none or
+    /// if [cruft packs are disabled](Platform::with_cruft_packs()).
+    pub cruft_pack: Option<gix_pack::bundle::write::Outcome>,
+    /// The outcome of writing a reachability bitmap for the new pack, or `None` if
+    /// [bitmaps are disabled](Platform::with_bitmaps()), if there was nothing to pack, or if reachable objects
+    /// of kept packs [weren't added](Platform::with_pack_kept_objects()) to the new pack.
+    pub bitmap: Option<gix_pack::bitmap::write::Outcome>,
+    /// The amount of packs that were removed as all of their reachable objects are contained in the new pack.
+    pub removed_packs: usize,
+    /// The amount of loose objects that were removed as they are contained in a pack.
+    pub removed_loose_objects: usize,
+    /// The amount of unreachable objects of removed packs that were written as loose objects, as they didn't expire yet
+    /// and [cruft packs are disabled](Platform::with_cruft_packs()).
+    pub loosened_objects: usize,
+}
+
+/// The outcome of [`Platform::run()`].
+#[derive(Debug, Clone, Default)]
+pub struct Outcome {
+    /// The amount of reflog entries that were removed.
+    pub expired_reflog_entries: usize,
+    /// The outcome of repacking all objects.
+    pub repack: Repack,
+    /// The amount of unreachable loose objects that were removed.
+    pub pruned_objects: usize,
+    /// The amount of stale temporary files that were removed.
+    pub removed_tempfiles: usize,
+}
+
+/// Configuration
+impl Platform<'_> {
+    /// Unreachable objects that were last modified before `time` are removed, or never if `None`.
+    ///
+    /// Defaults to the value of `gc.pruneExpire`, or two weeks ago.
+    pub fn with_prune_expire(mut self, time: Option<SystemTime>) -> Self {
+        self.prune_expire = time;
+        self
+    }
+
+    /// Reflog entries which were created before `time` are removed, or never if `None`.
+    ///
+    /// Defaults to the value of `gc.reflogExpire`, or 90 days ago.
+    pub fn with_reflog_expire(mut self, time: Option<SystemTime>) -> Self {
+        self.reflog_expire = time;
+        self
+    }
+
+    /// If `true`, unreachable objects that didn't expire yet are written into a cruft pack when [repacking](Self::repack_all()),
+    /// or as loose objects otherwise.
+    ///
+    /// Defaults to the value of `gc.cruftPacks`, or `true`.
+    pub fn with_cruft_packs(mut self, toggle: bool) -> Self {
+        self.cruft_packs = toggle;
+        self
+    }
+
+    /// If `Some`, a reachability bitmap is written for the new pack when [repacking all objects](Self::repack_all()),
+    /// with `selection` determining which commits receive a bitmap besides the ones that references point to.
+    ///
+    /// Defaults to the value of `repack.writeBitmaps`, or `true` in bare repositories, using the default selection.
+    pub fn with_bitmaps(mut self, selection: Option<gix_pack::bitmap::write::Selection>) -> Self {
+        self.bitmaps = selection;
+        self
+    }
+
+    /// If `true`, deltas are kept within the delta islands configured with `pack.island` when
+    /// [repacking all objects](Self::repack_all()).
+    ///
+    /// Defaults to the value of `repack.useDeltaIslands`, or `false`.
+    pub fn with_delta_islands(mut self, toggle: bool) -> Self {
+        self.delta_islands = toggle;
+        self
+    }
+
+    /// If `true`, reachable objects of packs with a `.keep` file are added to the new pack when
+    /// [repacking all objects](Self::repack_all()), even though the kept packs themselves are left untouched.
+    ///
+    /// Note that a reachability bitmap can only be written if the new pack contains all reachable objects.
+    ///
+    /// Defaults to the value of `repack.packKeptObjects`, or `true` if [bitmaps are written](Self::with_bitmaps()).
+    pub fn with_pack_kept_objects(mut self, toggle: bool) -> Self {
+        self.pack_kept_objects = toggle;
+        self
+    }
+
+    /// Compress objects that aren't copied from existing packs with the zlib compression `level`, ranging from 0 for
+    /// no compression to 9 for the best compression.
+    ///
+    /// Defaults to the value of `pack.compression`, or `core.compression`, or 1.
+    pub fn with_compression_level(mut self, level: u32) -> Self {
+        self.compression_level = level;
+        self
+    }
+}
+
+/// Tasks
+impl Platform<'_> {
+    /// Perform all maintenance tasks like `git gc` would: expire reflogs, repack all reachable objects into a single pack,
+    /// prune unreachable loose objects and remove stale temporary files.
+    pub fn run<P>(&self, mut progress: P, should_interrupt: &AtomicBool) -> Result<Outcome, Error>
+    where
+        P: crate::NestedProgress,
+        P::SubProgress: 'static,
+    {
+        self.repo.assure_writable("perform maintenance")?;
+        let expired_reflog_entries = self.expire_reflogs()?;
+        let reachable = self.reachable_objects(should_interrupt)?;
+        let reachable_ids: gix_hashtable::HashSet<ObjectId> = reachable.iter().map(|count| count.id).collect();
+        let repack = self.repack_all_inner(reachable, &reachable_ids, &mut progress, should_interrupt)?;
+        let pruned_objects = self.prune_inner(&reachable_ids, should_interrupt)?;
+        let removed_tempfiles = self.remove_stale_tempfiles()?;
+        Ok(Outcome {
+            expired_reflog_entries,
+            repack,
+            pruned_objects,
+            removed_tempfiles,
+        })
+    }
+
+    /// Remove all entries of all reflogs which were created before the [reflog expiry time](Self::with_reflog_expire()),
+    /// and return the amount of removed entries.
+    pub fn expire_reflogs(&self) -> Result<usize, Error> {
+        self.repo.assure_writable("expire reflogs")?;
+        let cutoff = match self.reflog_expire {
+            Some(time) => seconds_since_epoch(time),
+            None => return Ok(0),
+        };
+        let mut removed = 0;
+        for path in self.reflog_paths()? {
+            // Lock before reading so entries appended in the meantime can't be lost when writing the remaining ones.
+            let mut lock =
+                gix_lock::File::acquire_to_update_resource(&path, gix_lock::acquire::Fail::Immediately, None).map_err(
+                    |source| Error::LockReflog {
+                        path: path.clone(),
+                        source,
+                    },
+                )?;
+            let log = std::fs::read(&path)?;
+            let mut kept = Vec::with_capacity(log.len());
+            for line in crate::bstr::ByteSlice::lines_with_terminator(log.as_slice()) {
+                let is_expired = gix_ref::file::log::LineRef::from_bytes(line)
+                    .map_or(false, |line| line.signature.time.seconds < cutoff);
+                if is_expired {
+                    removed += 1;
+                } else {
+                    kept.extend_from_slice(line);
+                }
+            }
+            if kept.len() != log.len() {
+                std::io::Write::write_all(&mut lock, &kept)?;
+                lock.commit().map_err(|err| err.error)?;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Write all reachable loose objects into a new pack and remove all loose objects that are contained in a pack,
+    /// similar to `git repack -d`.
+    pub fn pack_loose_objects<P>(&self, mut progress: P, should_interrupt: &AtomicBool) -> Result<Repack, Error>
+    where
+        P: crate::NestedProgress,
+        P::SubProgress: 'static,
+    {
+        self.repo.assure_writable("pack loose objects")?;
+        let reachable_ids: gix_hashtable::HashSet<ObjectId> = self
+            .reachable_objects(should_interrupt)?
+            .into_iter()
+            .map(|count| count.id)
+            .collect();
+        let mut loose = Vec::new();
+        for id in self.loose_objects().iter() {
+            let id = id?;
+            if reachable_ids.contains(&id) {
+                loose.push(output::Count::from_data(id, None));
+            }
+        }
+        let pack = self.write_pack(loose, None, &mut progress, should_interrupt)?;
+        Ok(Repack {
+            pack,
+            removed_loose_objects: self.remove_packed_loose_objects(should_interrupt)?,
+            ..Default::default()
+        })
+    }
+
+    /// Write all reachable objects into a new pack, remove all other packs along with all loose objects that are contained
+    /// in a pack, similar to `git repack -a -d`.
+    ///
+    /// Unreachable objects of removed packs and unreachable loose objects are written into a cruft pack, unless they
+    /// were last modified before the [prune expiry time](Self::with_prune_expire()), similar to `git repack --cruft`.
+    /// If [cruft packs are disabled](Self::with_cruft_packs()), unreachable objects of removed packs are written as
+    /// loose objects instead, similar to `git repack -A`.
+    pub fn repack_all<P>(&self, mut progress: P, should_interrupt: &AtomicBool) -> Result<Repack, Error>
+    where
+        P: crate::NestedProgress,
+        P::SubProgress: 'static,
+    {
+        self.repo.assure_writable("repack objects")?;
+        let reachable = self.reachable_objects(should_interrupt)?;
+        let reachable_ids = reachable.iter().map(|count| count.id).collect();
+        self.repack_all_inner(reachable, &reachable_ids, &mut progress, should_interrupt)
+    }
+
+    /// Remove all unreachable loose objects that were last modified before the [prune expiry time](Self::with_prune_expire()),
+    /// and return the amount of removed objects, similar to `git prune`.
+    pub fn prune(&self, should_interrupt: &AtomicBool) -> Result<usize, Error> {
+        self.repo.assure_writable("prune objects")?;
+        if self.prune_expire.is_none() {
+            return Ok(0);
+        }
+        let reachable_ids = self
+            .reachable_objects(should_interrupt)?
+            .into_iter()
+            .map(|count| count.id)
+            .collect();
+        self.prune_inner(&reachable_ids, should_interrupt)
+    }
+
+    /// Remove temporary files left behind by interrupted operations in the object database that were last modified before
+    /// the [prune expiry time](Self::with_prune_expire()), and return the amount of removed files.
+    pub fn remove_stale_tempfiles(&self) -> Result<usize, Error> {
+        self.repo.assure_writable("remove stale temporary files")?;
+        let cutoff = match self.prune_expire {
+            Some(time) => time,
+            None => return Ok(0),
+        };
+        let objects_dir = self.objects_dir();
+        let mut removed = 0;
+        for dir in [objects_dir.to_owned(), objects_dir.join("pack")] {
+            let entries = match std::fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err.into()),
+            };
+            for entry in entries {
+                let entry = entry?;
+                let name = entry.file_name();
+                let name = name.to_string_lossy();
+                let is_tempfile = name.starts_with("tmp_") || name.starts_with(".tmp");
+                if !is_tempfile || !entry.file_type()?.is_file() {
+                    continue;
+                }
+                if entry.metadata()?.modified()? < cutoff {
+                    std::fs::remove_file(entry.path())?;
+                    removed += 1;
+                }
+            }
+        }
+        Ok(removed)
+    }
+}
+
+/// Keep markers
+impl Platform<'_> {
+    /// Protect the pack whose index or data file is at `pack_path` from being removed or repacked by writing a `.keep` file
+    /// next to it, with `reason` as its content if not empty, and return the path to the `.keep` file.
+    ///
+    /// An existing `.keep` file is overwritten.
+    pub fn keep_pack(&self, pack_path: impl AsRef<Path>, reason: &crate::bstr::BStr) -> Result<PathBuf, Error> {
+        self.repo.assure_writable("keep a pack")?;
+        let pack_path = pack_path.as_ref();
+        if !pack_path.with_extension("pack").is_file() {
+            return Err(Error::MissingPack {
+                path: pack_path.to_owned(),
+            });
+        }
+        let keep_path = pack_path.with_extension("keep");
+        let mut content = reason.to_owned();
+        if !content.is_empty() && !content.ends_with(b"\n") {
+            content.push(b'\n');
+        }
+        std::fs::write(&keep_path, content)?;
+        Ok(keep_path)
+    }
+
+    /// Remove the `.keep` file of the pack whose index or data file is at `pack_path`, and return `true` if there was one.
+    pub fn unkeep_pack(&self, pack_path: impl AsRef<Path>) -> Result<bool, Error> {
+        self.repo.assure_writable("unkeep a pack")?;
+        match std::fs::remove_file(pack_path.as_ref().with_extension("keep")) {
+            Ok(()) => Ok(true),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Return the paths to the indices of all packs with a `.keep` file.
+    pub fn kept_packs(&self) -> Result<Vec<PathBuf>, Error> {
+        Ok(self
+            .pack_index_paths()?
+            .into_iter()
+            .filter(|path| is_kept(path))
+            .collect())
+    }
+}
+
+/// Multi-pack-index
+impl Platform<'_> {
+    /// Write a multi-pack-index for all packs to speed up object lookups in repositories with many packs, similar to
+    /// `git multi-pack-index write`.
+    ///
+    /// Return `None` if there was no pack to write it for, in which case an existing multi-pack-index is removed.
+    pub fn write_multi_pack_index<P>(
+        &self,
+        mut progress: P,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Option<gix_pack::multi_index::write::Outcome>, Error>
+    where
+        P: crate::NestedProgress,
+        P::SubProgress: 'static,
+    {
+        self.repo.assure_writable("write the multi-pack-index")?;
+        let index_paths = self.pack_index_paths()?;
+        self.write_multi_pack_index_from(index_paths, &mut progress, should_interrupt)
+    }
+
+    /// Remove all packs listed in the multi-pack-index which don't provide any object to it as all of their objects are
+    /// provided by other packs, and rewrite the multi-pack-index without them, similar to `git multi-pack-index expire`.
+    ///
+    /// Packs with a `.keep` file are never removed. Return the amount of removed packs.
+    pub fn expire_multi_pack_index<P>(&self, mut progress: P, should_interrupt: &AtomicBool) -> Result<usize, Error>
+    where
+        P: crate::NestedProgress,
+        P::SubProgress: 'static,
+    {
+        self.repo.assure_writable("expire the multi-pack-index")?;
+        let multi_index = match self.open_multi_pack_index()? {
+            Some(multi_index) => multi_index,
+            None => return Ok(0),
+        };
+        let mut num_provided_objects = vec![0_u32; multi_index.num_indices() as usize];
+        for entry in multi_index.iter() {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            num_provided_objects[entry.pack_index as usize] += 1;
+        }
+        let pack_dir = self.objects_dir().join("pack");
+        let (mut kept, mut expired) = (Vec::new(), Vec::new());
+        for (name, num_objects) in multi_index.index_names().iter().zip(num_provided_objects) {
+            let index_path = pack_dir.join(name);
+            if num_objects == 0 && !is_kept(&index_path) {
+                expired.push(index_path);
+            } else {
+                kept.push(index_path);
+            }
+        }
+        drop(multi_index);
+        if expired.is_empty() {
+            return Ok(0);
+        }
+        // Readers must never see a multi-pack-index that refers to packs that don't exist anymore.
+        self.write_multi_pack_index_from(kept, &mut progress, should_interrupt)?;
+        for index_path in &expired {
+            remove_pack(index_path)?;
+        }
+        Ok(expired.len())
+    }
+
+    /// Add all packs that aren't yet part of the multi-pack-index chain to a new layer on top of it, which is much cheaper than
+    /// rewriting a multi-pack-index for all packs, similar to `git multi-pack-index write --incremental`.
+    ///
+    /// A single multi-pack-index is removed as the chain takes precedence. Return `None` if there was no new pack to add.
+    pub fn append_multi_pack_index_layer<P>(
+        &self,
+        mut progress: P,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Option<gix_pack::multi_index::write::Outcome>, Error>
+    where
+        P: crate::NestedProgress,
+        P::SubProgress: 'static,
+    {
+        self.repo.assure_writable("append to the multi-pack-index chain")?;
+        use std::io::Write;
+
+        use gix_pack::multi_index::chain;
+        let pack_dir = self.objects_dir().join("pack");
+        let chain_path = chain::file_path(&pack_dir);
+        let base = if chain_path.is_file() {
+            gix_pack::multi_index::File::at_chain(&pack_dir)?
+        } else {
+            Vec::new()
+        };
+        let index_paths = self.pack_index_paths()?;
+        let has_new_packs = index_paths.iter().any(|path| {
+            let file_name = Path::new(path.file_name().expect("file name present"));
+            base.iter()
+                .all(|layer| layer.index_names().iter().all(|name| name != file_name))
+        });
+        if !has_new_packs {
+            return Ok(None);
+        }
+
+        let layers_dir = chain_path.parent().expect("chain file in directory");
+        std::fs::create_dir_all(layers_dir)?;
+        let mut out = gix_tempfile::new(
+            layers_dir,
+            gix_tempfile::ContainingDirectory::Exists,
+            gix_tempfile::AutoRemove::Tempfile,
+        )?;
+        let outcome = gix_pack::multi_index::File::write_layer_from_index_paths(
+            index_paths,
+            &base,
+            &mut out,
+            &mut progress,
+            should_interrupt,
+            gix_pack::multi_index::write::Options {
+                object_hash: self.repo.object_hash(),
+            },
+        )?;
+        out.persist(layers_dir.join(chain::layer_file_name(&outcome.multi_index_checksum)))
+            .map_err(|err| err.error)?;
+
+        let mut chain_file =
+            gix_lock::File::acquire_to_update_resource(&chain_path, gix_lock::acquire::Fail::Immediately, None)
+                .map_err(Error::LockMultiPackIndexChain)?;
+        for checksum in base
+            .iter()
+            .map(gix_pack::multi_index::File::checksum)
+            .chain(Some(outcome.multi_index_checksum))
+        {
+            writeln!(chain_file, "{checksum}")?;
+        }
+        chain_file.commit().map_err(|err| err.error)?;
+        remove_if_present(&self.multi_pack_index_path())?;
+        Ok(Some(outcome))
+    }
+
+    /// Write the objects provided by small packs listed in the multi-pack-index into a new pack, and add it to the rewritten
+    /// multi-pack-index, similar to `git multi-pack-index repack --batch-size=<batch_size>`.
+    ///
+    /// Packs are selected from oldest to newest if their expected size, i.e. the size of the objects they provide to the
+    /// multi-pack-index, is smaller than `batch_size`, until the selected packs have a total expected size of at
+    /// least `batch_size`. If `batch_size` is 0, all packs are selected.
+    /// Packs with a `.keep` file are never selected.
+    ///
+    /// Return the new pack, or `None` if less than two packs were selected. Packs whose objects are now provided by the new pack
+    /// can be removed with [`expire_multi_pack_index()`](Self::expire_multi_pack_index()).
+    pub fn repack_multi_pack_index<P>(
+        &self,
+        batch_size: u64,
+        mut progress: P,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Option<gix_pack::bundle::write::Outcome>, Error>
+    where
+        P: crate::NestedProgress,
+        P::SubProgress: 'static,
+    {
+        self.repo.assure_writable("repack the multi-pack-index")?;
+        let multi_index = match self.open_multi_pack_index()? {
+            Some(multi_index) => multi_index,
+            None => return Ok(None),
+        };
+        let mut num_provided_objects = vec![0_u64; multi_index.num_indices() as usize];
+        for entry in multi_index.iter() {
+            num_provided_objects[entry.pack_index as usize] += 1;
+        }
+        let pack_dir = self.objects_dir().join("pack");
+        let mut candidates = Vec::new();
+        for (pack_index, (name, num_provided)) in multi_index.index_names().iter().zip(num_provided_objects).enumerate()
+        {
+            let index_path = pack_dir.join(name);
+            if num_provided == 0 || is_kept(&index_path) {
+                continue;
+            }
+            let pack_metadata = std::fs::metadata(index_path.with_extension("pack"))?;
+            let num_objects = u64::from(gix_pack::index::File::at(&index_path, self.repo.object_hash())?.num_objects());
+            let expected_size = pack_metadata.len() * num_provided / num_objects.max(1);
+            candidates.push((pack_metadata.modified()?, expected_size, pack_index as u32));
+        }
+        candidates.sort_by_key(|(mtime, _, _)| *mtime);
+
+        let mut selected = vec![false; multi_index.num_indices() as usize];
+        let (mut num_selected, mut total_size) = (0, 0);
+        for (_mtime, expected_size, pack_index) in candidates {
+            if batch_size != 0 {
+                if expected_size >= batch_size {
+                    continue;
+                }
+                if total_size >= batch_size {
+                    break;
+                }
+            }
+            selected[pack_index as usize] = true;
+            num_selected += 1;
+            total_size += expected_size;
+        }
+        if num_selected < 2 || total_size < batch_size {
+            return Ok(None);
+        }
+
+        let mut counts = Vec::new();
+        for entry in multi_index.iter() {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            if selected[entry.pack_index as usize] {
+                counts.push(output::Count::from_data(entry.oid, None));
+            }
+        }
+        let mut index_paths: Vec<_> = multi_index
+            .index_names()
+            .iter()
+            .map(|name| pack_dir.join(name))
+            .collect();
+        drop(multi_index);
+
+        let pack = self.write_pack(counts, None, &mut progress, should_interrupt)?;
+        if let Some(index_path) = pack.as_ref().and_then(|pack| pack.index_path.clone()) {
+            if !index_paths.contains(&index_path) {
+                index_paths.push(index_path);
+            }
+        }
+        self.write_multi_pack_index_from(index_paths, &mut progress, should_interrupt)?;
+        Ok(pack)
+    }
+}
+
+/// Utilities
+impl Platform<'_> {
+    fn multi_pack_index_path(&self) -> PathBuf {
+        self.objects_dir().join("pack").join("multi-pack-index")
+    }
+
+    fn open_multi_pack_index(&self) -> Result<Option<gix_pack::multi_index::File>, Error> {
+        let path = self.multi_pack_index_path();
+        Ok(if path.is_file() {
+            Some(gix_pack::multi_index::File::at(path)?)
+        } else {
+            None
+        })
+    }
+
+    /// Write a multi-pack-index for the packs at `index_paths`, or remove it if there is none.
+    fn write_multi_pack_index_from(
+        &self,
+        index_paths: Vec<PathBuf>,
+        progress: &mut dyn crate::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Option<gix_pack::multi_index::write::Outcome>, Error> {
+        let path = self.multi_pack_index_path();
+        self.remove_multi_pack_index_chain()?;
+        if index_paths.is_empty() {
+            remove_if_present(&path)?;
+            return Ok(None);
+        }
+        let mut out = gix_tempfile::new(
+            path.parent().expect("pack directory"),
+            gix_tempfile::ContainingDirectory::Exists,
+            gix_tempfile::AutoRemove::Tempfile,
+        )?;
+        let outcome = gix_pack::multi_index::File::write_from_index_paths(
+            index_paths,
+            &mut out,
+            progress,
+            should_interrupt,
+            gix_pack::multi_index::write::Options {
+                object_hash: self.repo.object_hash(),
+            },
+        )?;
+        out.persist(path).map_err(|err| err.error)?;
+        Ok(Some(outcome))
+    }
+
+    /// Remove all layers of the multi-pack-index chain, if present.
+    fn remove_multi_pack_index_chain(&self) -> std::io::Result<()> {
+        let layers_dir = self
+            .objects_dir()
+            .join("pack")
+            .join(gix_pack::multi_index::chain::DIRECTORY);
+        match std::fs::remove_dir_all(layers_dir) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+
+    fn objects_dir(&self) -> &Path {
+        self.repo.objects.store_ref().path()
+    }
+
+    /// Return an object database handle suitable for creating packs, which keeps packs available even if they are removed
+    /// and doesn't apply replacements.
+    fn packing_db(&self) -> crate::OdbHandle {
+        let mut db = self.repo.objects.clone();
+        db.prevent_pack_unload();
+        db.ignore_replacements = true;
+        db
+    }
+
+    fn loose_objects(&self) -> gix_odb::loose::Store {
+        gix_odb::loose::Store::at(self.objects_dir(), self.repo.object_hash())
+            .with_compression_level(self.repo.objects.store_ref().loose_compression_level())
+    }
+
+    /// Return the paths to all reflogs of all worktrees.
+    fn reflog_paths(&self) -> Result<Vec<PathBuf>, Error> {
+        let mut out = Vec::new();
+        let mut dirs = vec![self.repo.common_dir().join("logs")];
+        for proxy in self.repo.worktrees()? {
+            dirs.push(proxy.git_dir().join("logs"));
+        }
+        if self.repo.git_dir() != self.repo.common_dir() {
+            dirs.push(self.repo.git_dir().join("logs"));
+        }
+        while let Some(dir) = dirs.pop() {
+            let entries = match std::fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err.into()),
+            };
+            for entry in entries {
+                let entry = entry?;
+                let file_type = entry.file_type()?;
+                if file_type.is_dir() {
+                    dirs.push(entry.path());
+                } else if file_type.is_file() && !entry.file_name().to_string_lossy().ends_with(".lock") {
+                    out.push(entry.path());
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    /// Find all objects that are reachable from references, reflogs, the `HEAD` of all worktrees and their indices.
+    ///
+    /// Replacements are ignored, just like `git` does, as otherwise objects only reachable through replaced objects would be lost.
+    fn reachable_objects(&self, should_interrupt: &AtomicBool) -> Result<Vec<output::Count>, Error> {
+        let mut repo = self.repo.clone();
+        repo.objects = self.packing_db();
+        let repo = &repo;
+        let mut roots = Vec::new();
+        for reference in repo.references()?.all()? {
+            let reference = reference.map_err(Error::References)?;
+            if let Some(id) = reference.target().try_id() {
+                roots.push(id.to_owned());
+            }
+        }
+        roots.extend(repo.head()?.id().map(crate::Id::detach));
+        let mut indices = vec![repo.index_or_empty()?];
+        for proxy in repo.worktrees()? {
+            let worktree = proxy.into_repo_with_possibly_inaccessible_worktree()?;
+            roots.extend(worktree.head()?.id().map(crate::Id::detach));
+            indices.push(worktree.index_or_empty()?);
+        }
+        let mut reflog_ids = Vec::new();
+        for path in self.reflog_paths()? {
+            let log = std::fs::read(path)?;
+            for line in gix_ref::file::log::iter::forward(&log).filter_map(Result::ok) {
+                reflog_ids.extend(
+                    [line.previous_oid(), line.new_oid()]
+                        .into_iter()
+                        .filter(|id| !id.is_null()),
+                );
+            }
+        }
+        for index in &indices {
+            roots.extend(
+                index
+                    .entries()
+                    .iter()
+                    .filter(|entry| entry.mode != gix_index::entry::Mode::COMMIT)
+                    .map(|entry| entry.id),
+            );
+        }
+
+        let mut commits = Vec::new();
+        let mut others = Vec::new();
+        let mut seen = gix_hashtable::HashSet::default();
+        // Reflogs may refer to objects that don't exist anymore, which is why these are ignored.
+        for (id, must_exist) in roots
+            .into_iter()
+            .map(|id| (id, true))
+            .chain(reflog_ids.into_iter().map(|id| (id, false)))
+        {
+            if !seen.insert(id) {
+                continue;
+            }
+            let kind = match repo.try_find_header(id)? {
+                Some(header) => header.kind(),
+                None if must_exist => return Err(crate::object::find::existing::Error::NotFound { oid: id }.into()),
+                None => continue,
+            };
+            match kind {
+                gix_object::Kind::Commit => commits.push(id),
+                gix_object::Kind::Tag => {
+                    others.push(id);
+                    let target = repo.find_object(id)?.peel_tags_to_end()?;
+                    if target.kind == gix_object::Kind::Commit {
+                        commits.push(target.id);
+                    }
+                }
+                gix_object::Kind::Tree | gix_object::Kind::Blob => others.push(id),
+            }
+        }
+
+        let mut ids = Vec::new();
+        if !commits.is_empty() {
+            for info in repo.rev_walk(commits).all()? {
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(Error::Interrupted);
+                }
+                ids.push(info?.id);
+            }
+        }
+        ids.extend(others);
+        let (counts, _outcome) = output::count::objects_unthreaded(
+            &self.packing_db(),
+            &mut ids.into_iter().map(Ok),
+            &gix_features::progress::Discard,
+            should_interrupt,
+            output::count::objects::ObjectExpansion::TreeContents,
+        )?;
+        Ok(counts)
+    }
+
+    fn repack_all_inner(
+        &self,
+        mut reachable: Vec<output::Count>,
+        reachable_ids: &gix_hashtable::HashSet<ObjectId>,
+        progress: &mut dyn crate::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Repack, Error> {
+        let old_packs = self.removable_packs()?;
+        let mut has_all_reachable_objects = true;
+        if !self.pack_kept_objects {
+            let kept = self
+                .kept_packs()?
+                .into_iter()
+                .map(|path| gix_pack::index::File::at(path, self.repo.object_hash()))
+                .collect::<Result<Vec<_>, _>>()?;
+            let num_reachable = reachable.len();
+            reachable.retain(|count| kept.iter().all(|index| index.lookup(count.id).is_none()));
+            has_all_reachable_objects = reachable.len() == num_reachable;
+        }
+        let islands = if self.delta_islands {
+            Some(self.islands(should_interrupt)?)
+        } else {
+            None
+        };
+        let pack = self.write_pack(reachable, islands, progress, should_interrupt)?;
+
+        let mut out = Repack::default();
+        let mut unreachable = self.unreachable_packed_objects(&old_packs, reachable_ids, should_interrupt)?;
+        if self.cruft_packs {
+            self.add_unreachable_loose_objects(&mut unreachable, reachable_ids, should_interrupt)?;
+            out.cruft_pack = self.write_cruft_pack(unreachable, progress, should_interrupt)?;
+        } else {
+            let loose = self.loose_objects();
+            let db = self.packing_db();
+            let mut buf = Vec::new();
+            for id in unreachable.keys() {
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(Error::Interrupted);
+                }
+                if loose.contains(id) {
+                    continue;
+                }
+                if let Some(object) = gix_object::Find::try_find(&db, id, &mut buf)? {
+                    gix_odb::Write::write_buf(&loose, object.kind, object.data).map_err(Error::WriteLooseObject)?;
+                    out.loosened_objects += 1;
+                }
+            }
+        }
+
+        let new_index_paths: Vec<_> = [&pack, &out.cruft_pack]
+            .into_iter()
+            .filter_map(|pack| pack.as_ref().and_then(|pack| pack.index_path.clone()))
+            .collect();
+        for index_path in old_packs.into_iter().filter(|path| !new_index_paths.contains(path)) {
+            remove_pack(&index_path)?;
+            out.removed_packs += 1;
+        }
+        if out.removed_packs != 0 {
+            remove_if_present(&self.multi_pack_index_path())?;
+            self.remove_multi_pack_index_chain()?;
+        }
+        out.removed_loose_objects = self.remove_packed_loose_objects(should_interrupt)?;
+        if let (Some(selection), Some(pack), true) = (self.bitmaps, &pack, has_all_reachable_objects) {
+            out.bitmap = Some(self.write_bitmap(pack, selection, progress, should_interrupt)?);
+        }
+        out.pack = pack;
+        Ok(out)
+    }
+
+    fn prune_inner(
+        &self,
+        reachable_ids: &gix_hashtable::HashSet<ObjectId>,
+        should_interrupt: &AtomicBool,
+    ) -> Result<usize, Error> {
+        let cutoff = match self.prune_expire {
+            Some(time) => time,
+            None => return Ok(0),
+        };
+        let loose = self.loose_objects();
+        let mut removed = 0;
+        for id in loose.iter() {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            let id = id?;
+            if reachable_ids.contains(&id) {
+                continue;
+            }
+            let path = loose.object_path(&id);
+            if std::fs::metadata(&path)?.modified()? < cutoff {
+                std::fs::remove_file(&path)?;
+                remove_empty_dir(path.parent().expect("fan-out directory"));
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Return all unreachable objects in the packs at `index_paths` which didn't expire yet, along with their modification
+    /// time in seconds since the unix epoch.
+    ///
+    /// The modification time is read from the `.mtimes` file of cruft packs, or is the one of the pack itself.
+    fn unreachable_packed_objects(
+        &self,
+        index_paths: &[PathBuf],
+        reachable_ids: &gix_hashtable::HashSet<ObjectId>,
+        should_interrupt: &AtomicBool,
+    ) -> Result<gix_hashtable::HashMap<ObjectId, u32>, Error> {
+        let mut out = gix_hashtable::HashMap::default();
+        for index_path in index_paths {
+            let index = gix_pack::index::File::at(index_path, self.repo.object_hash())?;
+            let mtimes_path = index_path.with_extension("mtimes");
+            let mtimes = if mtimes_path.is_file() {
+                Some(gix_pack::mtimes::File::at(mtimes_path)?)
+            } else {
+                None
+            };
+            let pack_mtime = mtime_seconds(std::fs::metadata(index_path.with_extension("pack"))?.modified()?);
+            for (entry_index, entry) in index.iter().enumerate() {
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(Error::Interrupted);
+                }
+                if reachable_ids.contains(&entry.oid) {
+                    continue;
+                }
+                let mtime = match &mtimes {
+                    Some(mtimes) if entry_index < mtimes.num_objects() as usize => {
+                        mtimes.mtime_at_index(entry_index as u32)
+                    }
+                    _ => pack_mtime,
+                };
+                self.add_unexpired(&mut out, entry.oid, mtime);
+            }
+        }
+        Ok(out)
+    }
+
+    /// Add all unreachable loose objects which didn't expire yet to `out`, along with their modification time.
+    fn add_unreachable_loose_objects(
+        &self,
+        out: &mut gix_hashtable::HashMap<ObjectId, u32>,
+        reachable_ids: &gix_hashtable::HashSet<ObjectId>,
+        should_interrupt: &AtomicBool,
+    ) -> Result<(), Error> {
+        let loose = self.loose_objects();
+        for id in loose.iter() {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            let id = id?;
+            if reachable_ids.contains(&id) {
+                continue;
+            }
+            let mtime = mtime_seconds(std::fs::metadata(loose.object_path(&id))?.modified()?);
+            self.add_unexpired(out, id, mtime);
+        }
+        Ok(())
+    }
+
+    /// Add `id` with `mtime` to `out` unless it expired, keeping the most recent modification time of duplicates.
+    fn add_unexpired(&self, out: &mut gix_hashtable::HashMap<ObjectId, u32>, id: ObjectId, mtime: u32) {
+        let is_expired = self
+            .prune_expire
+            .map_or(false, |cutoff| i64::from(mtime) < seconds_since_epoch(cutoff));
+        if !is_expired {
+            let previous = out.entry(id).or_insert(mtime);
+            *previous = (*previous).max(mtime);
+        }
+    }
+
+    /// Write all objects in `unreachable` into a new pack along with a `.mtimes` file that keeps their modification times.
+    fn write_cruft_pack(
+        &self,
+        unreachable: gix_hashtable::HashMap<ObjectId, u32>,
+        progress: &mut dyn crate::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Option<gix_pack::bundle::write::Outcome>, Error> {
+        let counts = unreachable
+            .keys()
+            .map(|id| output::Count::from_data(*id, None))
+            .collect();
+        let pack = match self.write_pack(counts, None, progress, should_interrupt)? {
+            Some(pack) => pack,
+            None => return Ok(None),
+        };
+        let index_path = pack.index_path.as_ref().expect("written to a directory");
+        let index = gix_pack::index::File::at(index_path, self.repo.object_hash())?;
+        let mut mtimes = gix_tempfile::new(
+            index_path.parent().expect("pack directory"),
+            gix_tempfile::ContainingDirectory::Exists,
+            gix_tempfile::AutoRemove::Tempfile,
+        )?;
+        gix_pack::mtimes::File::write_to(
+            index.iter().map(|entry| unreachable[&entry.oid]),
+            &pack.index.data_hash,
+            &mut mtimes,
+            self.repo.object_hash(),
+        )?;
+        mtimes
+            .persist(index_path.with_extension("mtimes"))
+            .map_err(|err| err.error)?;
+        Ok(Some(pack))
+    }
+
+    /// Write a reachability bitmap for `pack`, which must contain all objects reachable from references, with bitmaps
+    /// for the commits that references point to and the ones chosen by `selection`.
+    fn write_bitmap(
+        &self,
+        pack: &gix_pack::bundle::write::Outcome,
+        selection: gix_pack::bitmap::write::Selection,
+        progress: &mut dyn crate::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<gix_pack::bitmap::write::Outcome, Error> {
+        let index_path = pack.index_path.as_ref().expect("written to a directory");
+        let bundle = gix_pack::Bundle::at(index_path, self.repo.object_hash())?;
+        let mut tips = Vec::new();
+        for reference in self.repo.references()?.all()? {
+            let reference = reference.map_err(Error::References)?;
+            if let Some(id) = reference.target().try_id() {
+                tips.push(id.to_owned());
+            }
+        }
+        tips.extend(self.repo.head()?.id().map(crate::Id::detach));
+        let mut bitmap = gix_tempfile::new(
+            index_path.parent().expect("pack directory"),
+            gix_tempfile::ContainingDirectory::Exists,
+            gix_tempfile::AutoRemove::Tempfile,
+        )?;
+        let outcome = gix_pack::bitmap::File::write_to(
+            &bundle,
+            tips,
+            &mut bitmap,
+            progress,
+            should_interrupt,
+            gix_pack::bitmap::write::Options { selection },
+        )?;
+        bitmap
+            .persist(index_path.with_extension("bitmap"))
+            .map_err(|err| err.error)?;
+        Ok(outcome)
+    }
+
+    /// Return the paths to the indices of all packs.
+    fn pack_index_paths(&self) -> Result<Vec<PathBuf>, Error> {
+        let entries = match std::fs::read_dir(self.objects_dir().join("pack")) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+        let mut out = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().map_or(false, |ext| ext == "idx") && path.with_extension("pack").is_file() {
+                out.push(path);
+            }
+        }
+        Ok(out)
+    }
+
+    /// Return the paths to the indices of all packs that may be removed, i.e. which aren't kept or promised.
+    fn removable_packs(&self) -> Result<Vec<PathBuf>, Error> {
+        Ok(self
+            .pack_index_paths()?
+            .into_iter()
+            .filter(|path| !is_kept(path) && !path.with_extension("promisor").exists())
+            .collect())
+    }
+
+    /// Remove all loose objects that are also contained in one of our packs.
+    fn remove_packed_loose_objects(&self, should_interrupt: &AtomicBool) -> Result<usize, Error> {
+        let indices = self
+            .pack_index_paths()?
+            .into_iter()
+            .map(|path| gix_pack::index::File::at(path, self.repo.object_hash()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let loose = self.loose_objects();
+        let mut removed = 0;
+        for id in loose.iter() {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            let id = id?;
+            if indices.iter().any(|index| index.lookup(id).is_some()) {
+                let path = loose.object_path(&id);
+                std::fs::remove_file(&path)?;
+                remove_empty_dir(path.parent().expect("fan-out directory"));
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Compute the delta islands of all objects reachable from references that match the patterns in `pack.island`.
+    ///
+    /// The island of a reference is named after the capture groups of the last matching pattern, joined with `-`.
+    fn islands(&self, should_interrupt: &AtomicBool) -> Result<output::Islands, Error> {
+        use crate::{bstr::ByteSlice, config::tree::Key};
+        let patterns = self
+            .repo
+            .config_snapshot()
+            .strings_by_key(crate::config::tree::Pack::ISLAND.logical_name().as_str())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|pattern| {
+                pattern
+                    .to_str()
+                    .map_err(|_| regex::Error::Syntax("pattern is not valid UTF-8".into()))
+                    .and_then(regex::bytes::Regex::new)
+                    .map_err(|source| Error::IslandPattern {
+                        pattern: pattern.into_owned(),
+                        source,
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut tips = Vec::new();
+        for reference in self.repo.references()?.all()? {
+            let reference = reference.map_err(Error::References)?;
+            let id = match reference.target().try_id() {
+                Some(id) => id.to_owned(),
+                None => continue,
+            };
+            let name = reference.name().as_bstr();
+            if let Some(captures) = patterns.iter().rev().find_map(|pattern| pattern.captures(name)) {
+                let island = captures
+                    .iter()
+                    .skip(1)
+                    .flatten()
+                    .map(|group| group.as_bytes())
+                    .collect::<Vec<_>>()
+                    .join(&b'-');
+                tips.push((island.into(), id));
+            }
+        }
+        Ok(output::Islands::from_tips(tips, &self.packing_db(), should_interrupt)?)
+    }
+
+    /// Write all objects in `counts` into a new pack, along with its index, keeping deltas within `islands` if set.
+    fn write_pack(
+        &self,
+        counts: Vec<output::Count>,
+        islands: Option<output::Islands>,
+        progress: &mut dyn crate::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Option<gix_pack::bundle::write::Outcome>, Error> {
+        use std::io::Seek;
+        if counts.is_empty() {
+            return Ok(None);
+        }
+        let pack_dir = self.objects_dir().join("pack");
+        std::fs::create_dir_all(&pack_dir)?;
+        let num_entries = counts.len().try_into().expect("less than 2^32 objects");
+        let islands = islands.map(std::sync::Arc::new);
+        let entries = output::entry::iter_from_counts_with_delta_filter(
+            counts,
+            self.packing_db().into_arc()?,
+            Box::new(progress.add_child("creating entries".into())),
+            output::entry::iter_from_counts::Options {
+                compression_level: self.compression_level,
+                ..Default::default()
+            },
+            |_: &gix_hash::oid| true,
+            move |id: &gix_hash::oid, base_id: &gix_hash::oid| {
+                islands
+                    .as_ref()
+                    .map_or(true, |islands| islands.allows_delta(id, base_id))
+            },
+        );
+        let mut tempfile = gix_tempfile::new(
+            &pack_dir,
+            gix_tempfile::ContainingDirectory::Exists,
+            gix_tempfile::AutoRemove::Tempfile,
+        )?;
+        {
+            let mut writer = output::bytes::FromEntriesIter::new(
+                gix_features::parallel::InOrderIter::from(entries),
+                &mut tempfile,
+                num_entries,
+                gix_pack::data::Version::V2,
+                self.repo.object_hash(),
+            );
+            for written in &mut writer {
+                written?;
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(Error::Interrupted);
+                }
+            }
+        }
+        tempfile.rewind()?;
+        let mut outcome = gix_pack::Bundle::write_to_directory(
+            &mut std::io::BufReader::new(tempfile),
+            Some(&pack_dir),
+            progress,
+            should_interrupt,
+            None::<gix_object::find::Never>,
+            gix_pack::bundle::write::Options {
+                object_hash: self.repo.object_hash(),
+                ..Default::default()
+            },
+        )?;
+        // All objects are reachable already, so there is no need to protect the new pack from being collected.
+        if let Some(keep_path) = outcome.keep_path.take() {
+            std::fs::remove_file(keep_path)?;
+        }
+        Ok(Some(outcome))
+    }
+}
+
+fn seconds_since_epoch(time: SystemTime) -> gix_date::SecondsSinceUnixEpoch {
+    time.duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs().try_into().unwrap_or(i64::MAX))
+}
+
+/// Return `time` in seconds since the unix epoch as stored in `.mtimes` files.
+fn mtime_seconds(time: SystemTime) -> u32 {
+    seconds_since_epoch(time).try_into().unwrap_or(u32::MAX)
+}
+
+/// Remove the pack whose index is at `index_path`, along with all files that belong to it.
+fn remove_pack(index_path: &Path) -> std::io::Result<()> {
+    for extension in ["pack", "idx", "rev", "bitmap", "mtimes"] {
+        remove_if_present(&index_path.with_extension(extension))?;
+    }
+    Ok(())
+}
+
+fn remove_if_present(path: &Path) -> std::io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+/// Return `true` if the pack whose index is at `index_path` has a `.keep` file.
+fn is_kept(index_path: &Path) -> bool {
+    index_path.with_extension("keep").exists()
+}
+
+/// Remove `dir` if it's empty, ignoring all errors as it might not be.
+fn remove_empty_dir(dir: &Path) {
+    std::fs::remove_dir(dir).ok();
+}
@@ -0,0 +1,480 @@
+//! Attach notes to objects without changing them, similar to `git notes`.
+//!
+//! Notes are blobs in the tree of the commit that a notes reference like `refs/notes/commits` points to, named after
+//! the hexadecimal id of the object they annotate. Large notes trees split these names into a _fan-out_ of directories
+//! named after the first two hexadecimal characters, like `ab/cdef…`, which is supported when reading and followed when writing.
+//! Each change to the notes is recorded as a new commit, so the history of notes can be shared and [merged](Platform::merge()).
+//!
+//! Use [`Repository::notes()`](crate::Repository::notes()) to obtain a [`Platform`] for the notes reference configured with
+//! `core.notesRef` or `GIT_NOTES_REF`, or `refs/notes/commits` by default.
+//!
+//! ### Deviation
+//!
+//! * The fan-out isn't adjusted to the amount of notes when writing. Instead, new notes are placed into the fan-out that already exists.
+//! * Conflicting notes can't be resolved manually when merging, so one of the automatic [strategies](merge::Strategy) must be chosen.
+use std::collections::{BTreeMap, BTreeSet};
+
+use gix_hash::{oid, ObjectId};
+use gix_object::tree::EntryMode;
+use gix_ref::{transaction::PreviousValue, FullName, FullNameRef};
+
+use crate::{
+    bstr::{BString, ByteSlice, ByteVec},
+    object::tree::Editor,
+    Id, Repository,
+};
+
+///
+pub mod init {
+    /// The error returned by [`Repository::notes()`](crate::Repository::notes()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The notes reference configured in 'core.notesRef' is invalid")]
+        ReferenceName(#[from] gix_validate::reference::name::Error),
+    }
+}
+
+///
+pub mod merge {
+    use gix_hash::ObjectId;
+    use gix_ref::FullName;
+
+    /// The way to resolve notes which were changed differently on both sides of a [merge](super::Platform::merge()).
+    #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+    pub enum Strategy {
+        /// Keep our version of the note.
+        Ours,
+        /// Use their version of the note.
+        Theirs,
+        /// Concatenate our version of the note and their version, separated by an empty line.
+        Union,
+        /// Concatenate the lines of both versions, sort them and remove duplicate and empty lines.
+        CatSortUniq,
+    }
+
+    /// The outcome of [`Platform::merge()`](super::Platform::merge()).
+    #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+    pub enum Outcome {
+        /// All notes to merge are already contained in our notes, so nothing was done.
+        AlreadyUpToDate,
+        /// Our notes reference was moved to the notes commit that was merged, as it contains all of our notes.
+        FastForward {
+            /// The notes commit our notes reference now points to.
+            commit: ObjectId,
+        },
+        /// A merge commit with the merged notes was created, and our notes reference was moved to it.
+        Merged {
+            /// The newly created merge commit.
+            commit: ObjectId,
+        },
+    }
+
+    /// The error returned by [`Platform::merge()`](super::Platform::merge()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Notes(#[from] super::Error),
+        #[error(transparent)]
+        ReferenceName(#[from] gix_validate::reference::name::Error),
+        #[error("The notes reference '{name}' to merge doesn't exist", name = name.as_bstr())]
+        NotFound { name: FullName },
+        #[error(transparent)]
+        Walk(#[from] crate::revision::walk::Error),
+        #[error(transparent)]
+        Traverse(#[from] gix_traverse::commit::ancestors::Error),
+        #[error(transparent)]
+        ReferenceEdit(#[from] crate::reference::edit::Error),
+    }
+}
+
+/// The error returned by the methods of the [`Platform`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    FindReference(#[from] crate::reference::find::Error),
+    #[error(transparent)]
+    PeelReference(#[from] crate::reference::peel::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    ObjectKind(#[from] crate::object::try_into::Error),
+    #[error(transparent)]
+    Decode(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    OpenTree(#[from] crate::repository::edit_tree::Error),
+    #[error(transparent)]
+    EditTree(#[from] gix_object::tree::editor::Error),
+    #[error(transparent)]
+    WriteObject(#[from] crate::object::write::Error),
+    #[error(transparent)]
+    Commit(#[from] crate::commit::Error),
+    #[error("Object {target} already has a note")]
+    Exists { target: ObjectId },
+}
+
+/// A note attached to an object.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Note {
+    /// The object the note is attached to.
+    pub target: ObjectId,
+    /// The id of the blob with the note itself.
+    pub id: ObjectId,
+}
+
+/// A utility to read and write the notes of a single notes reference, obtained with
+/// [`Repository::notes()`](crate::Repository::notes()).
+pub struct Platform<'repo> {
+    pub(crate) repo: &'repo Repository,
+    pub(crate) notes_ref: FullName,
+}
+
+/// Builder
+impl Platform<'_> {
+    /// Read and write the notes of the notes reference `name`, like `refs/notes/review`.
+    ///
+    /// Defaults to the value of `core.notesRef`, or `refs/notes/commits`.
+    pub fn with_ref(mut self, name: FullName) -> Self {
+        self.notes_ref = name;
+        self
+    }
+}
+
+/// Access
+impl<'repo> Platform<'repo> {
+    /// Return the name of the notes reference we read from and write to.
+    pub fn notes_ref(&self) -> &FullNameRef {
+        self.notes_ref.as_ref()
+    }
+
+    /// Return the blob with the note attached to `target`, or `None` if there is none.
+    pub fn find(&self, target: impl Into<ObjectId>) -> Result<Option<crate::Blob<'repo>>, Error> {
+        let tree = match self.tip()? {
+            Some((_, tree)) => tree,
+            None => return Ok(None),
+        };
+        Ok(match self.locate(Some(tree), &target.into())? {
+            (_, Some(id)) => Some(self.repo.find_object(id)?.try_into_blob()?),
+            (_, None) => None,
+        })
+    }
+
+    /// Return all notes, sorted by the object they are attached to.
+    ///
+    /// Entries of the notes tree whose name isn't an object id are ignored.
+    pub fn iter(&self) -> Result<Vec<Note>, Error> {
+        Ok(self
+            .notes_in(self.tip()?.map(|(_, tree)| tree))?
+            .into_iter()
+            .map(|(target, id)| Note { target, id })
+            .collect())
+    }
+}
+
+/// Editing
+impl<'repo> Platform<'repo> {
+    /// Attach a note with `message` to `target`, and return the new notes commit.
+    ///
+    /// If `target` has a note already, it's replaced if `overwrite` is `true`, or an error is returned otherwise.
+    pub fn add(
+        &self,
+        target: impl Into<ObjectId>,
+        message: impl AsRef<[u8]>,
+        overwrite: bool,
+    ) -> Result<Id<'repo>, Error> {
+        let target = target.into();
+        let tip = self.tip()?;
+        let (path, existing) = self.locate(tip.map(|(_, tree)| tree), &target)?;
+        if existing.is_some() && !overwrite {
+            return Err(Error::Exists { target });
+        }
+        let note = self.repo.write_blob(message.as_ref())?.detach();
+        self.write(tip, None, "Notes added by 'git notes add'", |editor| {
+            editor.upsert(path, EntryMode::Blob, note)?;
+            Ok(())
+        })
+    }
+
+    /// Remove the note attached to `target`, and return the new notes commit, or `None` if `target` had no note.
+    pub fn remove(&self, target: impl Into<ObjectId>) -> Result<Option<Id<'repo>>, Error> {
+        let tip = match self.tip()? {
+            Some(tip) => tip,
+            None => return Ok(None),
+        };
+        let (path, existing) = self.locate(Some(tip.1), &target.into())?;
+        if existing.is_none() {
+            return Ok(None);
+        }
+        self.write(Some(tip), None, "Notes removed by 'git notes remove'", |editor| {
+            editor.remove(path)?;
+            Ok(())
+        })
+        .map(Some)
+    }
+
+    /// Merge the notes of the notes reference `other` into our notes, using `strategy` to resolve notes that were
+    /// changed differently on both sides since the notes histories diverged.
+    ///
+    /// Our notes reference is fast-forwarded if possible, or set to a new merge commit otherwise.
+    pub fn merge<Name, E>(&self, other: Name, strategy: merge::Strategy) -> Result<merge::Outcome, merge::Error>
+    where
+        Name: TryInto<FullName, Error = E>,
+        gix_validate::reference::name::Error: From<E>,
+    {
+        let other = other.try_into().map_err(gix_validate::reference::name::Error::from)?;
+        let theirs = Platform {
+            repo: self.repo,
+            notes_ref: other.clone(),
+        }
+        .tip()?;
+        let theirs = match theirs {
+            Some(theirs) => theirs,
+            None => return Err(merge::Error::NotFound { name: other }),
+        };
+        let ours = match self.tip()? {
+            Some(ours) => ours,
+            None => {
+                self.fast_forward(None, theirs.0)?;
+                return Ok(merge::Outcome::FastForward { commit: theirs.0 });
+            }
+        };
+        if ours.0 == theirs.0 {
+            return Ok(merge::Outcome::AlreadyUpToDate);
+        }
+        let base = self.repo.merge_base::<merge::Error>(ours.0, theirs.0)?;
+        if base == Some(theirs.0) {
+            return Ok(merge::Outcome::AlreadyUpToDate);
+        } else if base == Some(ours.0) {
+            self.fast_forward(Some(ours.0), theirs.0)?;
+            return Ok(merge::Outcome::FastForward { commit: theirs.0 });
+        }
+
+        let base_notes = match base {
+            Some(base) => self.notes_in(Some(self.commit_tree(base)?))?,
+            None => BTreeMap::new(),
+        };
+        let our_notes = self.notes_in(Some(ours.1))?;
+        let their_notes = self.notes_in(Some(theirs.1))?;
+        let mut changes = Vec::new();
+        for target in our_notes.keys().chain(their_notes.keys()).collect::<BTreeSet<_>>() {
+            let base = base_notes.get(target).copied();
+            let (ours, theirs) = (our_notes.get(target).copied(), their_notes.get(target).copied());
+            if ours == theirs || theirs == base {
+                continue;
+            }
+            let resolved = if ours == base {
+                theirs
+            } else {
+                match strategy {
+                    merge::Strategy::Ours => continue,
+                    merge::Strategy::Theirs => theirs,
+                    merge::Strategy::Union | merge::Strategy::CatSortUniq => {
+                        let combined = combine(
+                            self.note_data(ours)?,
+                            self.note_data(theirs)?,
+                            strategy == merge::Strategy::CatSortUniq,
+                        );
+                        if combined.is_empty() {
+                            None
+                        } else {
+                            Some(self.repo.write_blob(combined).map_err(Error::from)?.detach())
+                        }
+                    }
+                }
+            };
+            if resolved != ours {
+                changes.push((*target, resolved));
+            }
+        }
+
+        let message = format!(
+            "notes: Merged notes from {} into {}",
+            other.as_bstr(),
+            self.notes_ref.as_bstr()
+        );
+        let commit = self.write(Some(ours), Some(theirs.0), &message, |editor| {
+            for (target, note) in changes {
+                let (path, _) = self.locate(Some(ours.1), &target)?;
+                match note {
+                    Some(note) => editor.upsert(path, EntryMode::Blob, note)?,
+                    None => editor.remove(path)?,
+                };
+            }
+            Ok(())
+        })?;
+        Ok(merge::Outcome::Merged {
+            commit: commit.detach(),
+        })
+    }
+}
+
+/// Utilities
+impl<'repo> Platform<'repo> {
+    /// Return the commit our notes reference points to along with its tree, or `None` if there are no notes yet.
+    fn tip(&self) -> Result<Option<(ObjectId, ObjectId)>, Error> {
+        let mut reference = match self.repo.try_find_reference(self.notes_ref.as_ref())? {
+            Some(reference) => reference,
+            None => return Ok(None),
+        };
+        let commit = reference.peel_to_id_in_place()?.detach();
+        Ok(Some((commit, self.commit_tree(commit)?)))
+    }
+
+    fn commit_tree(&self, commit: ObjectId) -> Result<ObjectId, Error> {
+        Ok(self.repo.find_object(commit)?.try_into_commit()?.tree_id()?.detach())
+    }
+
+    fn tree(&self, id: ObjectId) -> Result<gix_object::Tree, Error> {
+        Ok(self.repo.find_object(id)?.try_into_tree()?.decode()?.into())
+    }
+
+    /// Return the path of the note for `target` in the notes `tree`, along with the note if it exists.
+    ///
+    /// If there is no note, the path follows the fan-out of the directories along the way.
+    fn locate(&self, tree: Option<ObjectId>, target: &oid) -> Result<(BString, Option<ObjectId>), Error> {
+        let hex = target.to_hex().to_string();
+        let mut rest = hex.as_str();
+        let mut path = BString::default();
+        let mut tree = match tree {
+            Some(id) => self.tree(id)?,
+            None => gix_object::Tree::empty(),
+        };
+        loop {
+            if let Some(entry) = tree
+                .entries
+                .iter()
+                .find(|entry| !entry.mode.is_tree() && entry.filename == rest)
+            {
+                path.push_str(rest);
+                return Ok((path, Some(entry.oid)));
+            }
+            if rest.len() <= 2 || !tree.entries.iter().any(is_fanout) {
+                path.push_str(rest);
+                return Ok((path, None));
+            }
+            let (dir, remainder) = rest.split_at(2);
+            tree = match tree
+                .entries
+                .iter()
+                .find(|entry| entry.mode.is_tree() && entry.filename == dir)
+            {
+                Some(entry) => self.tree(entry.oid)?,
+                None => gix_object::Tree::empty(),
+            };
+            path.push_str(dir);
+            path.push_byte(b'/');
+            rest = remainder;
+        }
+    }
+
+    /// Return all notes in the notes `tree` by the object they are attached to.
+    fn notes_in(&self, tree: Option<ObjectId>) -> Result<BTreeMap<ObjectId, ObjectId>, Error> {
+        let mut notes = BTreeMap::new();
+        let mut stack: Vec<_> = tree.map(|tree| (BString::default(), tree)).into_iter().collect();
+        while let Some((prefix, tree)) = stack.pop() {
+            for entry in self.tree(tree)?.entries {
+                let mut name = prefix.clone();
+                name.push_str(&entry.filename);
+                if entry.mode.is_tree() {
+                    if is_fanout(&entry) {
+                        stack.push((name, entry.oid));
+                    }
+                } else if let Ok(target) = ObjectId::from_hex(&name) {
+                    if target.kind() == self.repo.object_hash() {
+                        notes.insert(target, entry.oid);
+                    }
+                }
+            }
+        }
+        Ok(notes)
+    }
+
+    fn note_data(&self, note: Option<ObjectId>) -> Result<Vec<u8>, Error> {
+        Ok(match note {
+            Some(id) => self.repo.find_object(id)?.detach().data,
+            None => Vec::new(),
+        })
+    }
+
+    /// Apply `edit` to the tree of our notes commit `tip`, and commit the result with `message` on top of `tip` and `other_parent`.
+    fn write(
+        &self,
+        tip: Option<(ObjectId, ObjectId)>,
+        other_parent: Option<ObjectId>,
+        message: &str,
+        edit: impl FnOnce(&mut Editor<'repo>) -> Result<(), Error>,
+    ) -> Result<Id<'repo>, Error> {
+        let mut editor = match tip {
+            Some((_, tree)) => self.repo.edit_tree(tree)?,
+            None => self.repo.empty_tree().edit()?,
+        };
+        edit(&mut editor)?;
+        let tree = editor.write()?;
+        let parents = tip.map(|(commit, _)| commit).into_iter().chain(other_parent);
+        // Notes are never signed, just like in `git`, hence `commit.gpgSign` must not apply.
+        let author = self
+            .repo
+            .author()
+            .ok_or(crate::commit::Error::AuthorMissing)?
+            .map_err(crate::commit::Error::from)?;
+        let committer = self
+            .repo
+            .committer()
+            .ok_or(crate::commit::Error::CommitterMissing)?
+            .map_err(crate::commit::Error::from)?;
+        Ok(self
+            .repo
+            .commit_as(committer, author, self.notes_ref.as_bstr(), message, tree, parents)?)
+    }
+
+    /// Point our notes reference to `commit`, which is expected to be a descendant of its `previous` value.
+    fn fast_forward(&self, previous: Option<ObjectId>, commit: ObjectId) -> Result<(), merge::Error> {
+        self.repo.reference(
+            self.notes_ref.clone(),
+            commit,
+            match previous {
+                Some(previous) => PreviousValue::MustExistAndMatch(previous.into()),
+                None => PreviousValue::MustNotExist,
+            },
+            "notes: Fast-forward",
+        )?;
+        Ok(())
+    }
+}
+
+fn is_fanout(entry: &gix_object::tree::Entry) -> bool {
+    entry.mode.is_tree() && entry.filename.len() == 2 && entry.filename.iter().all(u8::is_ascii_hexdigit)
+}
+
+/// Combine the notes `ours` and `theirs` by concatenating them, or by sorting their lines and removing duplicates if `sort_uniq` is `true`.
+fn combine(ours: Vec<u8>, theirs: Vec<u8>, sort_uniq: bool) -> Vec<u8> {
+    if sort_uniq {
+        let mut lines: Vec<_> = ours
+            .lines()
+            .chain(theirs.lines())
+            .filter(|line| !line.is_empty())
+            .collect();
+        lines.sort();
+        lines.dedup();
+        let mut out = Vec::new();
+        for line in lines {
+            out.extend_from_slice(line);
+            out.push(b'\n');
+        }
+        out
+    } else if ours.is_empty() {
+        theirs
+    } else if theirs.is_empty() {
+        ours
+    } else {
+        let mut out = ours;
+        if !out.ends_with(b"\n") {
+            out.push(b'\n');
+        }
+        out.push(b'\n');
+        out.extend_from_slice(&theirs);
+        out
+    }
+}
@@ -147,6 +147,16 @@ pub mod diff {
             //       OK to just know how these objects are saved to know what constitutes a line.
             gix_diff::blob::intern::InternedInput::new(self.old.data.as_bytes(), self.new.data.as_bytes())
         }
+
+        /// Replace the data of the old and new blob with the content they point to if they are [LFS pointers](crate::lfs::Pointer)
+        /// and their content can be found in `store`, so that diffs are performed on the actual content.
+        ///
+        /// Return `true` if at least one side was replaced.
+        pub fn resolve_lfs_pointers(&mut self, store: &dyn crate::lfs::Store) -> Result<bool, crate::lfs::find::Error> {
+            let old = crate::lfs::resolve_pointer(&mut self.old.data, store)?;
+            let new = crate::lfs::resolve_pointer(&mut self.new.data, store)?;
+            Ok(old || new)
+        }
     }
 }
 
@@ -173,3 +183,20 @@ impl Blob<'_> {
         std::mem::take(&mut self.data)
     }
 }
+
+/// Large File Storage
+impl Blob<'_> {
+    /// Return the [LFS pointer](crate::lfs::Pointer) stored in this blob, or `None` if it isn't one.
+    pub fn lfs_pointer(&self) -> Option<crate::lfs::Pointer> {
+        crate::lfs::Pointer::from_bytes(&self.data)
+    }
+
+    /// If this blob is an [LFS pointer](crate::lfs::Pointer), replace its data with the content it points to
+    /// as found in `store`, and return `true`.
+    ///
+    /// Return `false` if it's no pointer, or if its content isn't available in `store`, leaving the data unchanged.
+    /// Note that the [id](Self::id) is not changed and still refers to the pointer.
+    pub fn resolve_lfs_pointer(&mut self, store: &dyn crate::lfs::Store) -> Result<bool, crate::lfs::find::Error> {
+        crate::lfs::resolve_pointer(&mut self.data, store)
+    }
+}
@@ -1,4 +1,9 @@
-use crate::{bstr, bstr::BStr, Commit, ObjectDetached, Tree};
+use crate::{
+    bstr,
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    ext::ObjectIdExt,
+    Commit, ObjectDetached, Tree,
+};
 
 mod error {
     use crate::object;
@@ -20,6 +25,42 @@ mod error {
 
 pub use error::Error;
 
+///
+pub mod pretty_format {
+    use crate::bstr::BString;
+
+    /// The error returned by [`Commit::pretty_format()`](crate::Commit::pretty_format()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The commit could not be decoded fully or partially")]
+        Decode(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        ShortId(#[from] crate::id::shorten::Error),
+        #[error(transparent)]
+        Head(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        OpenReferences(#[from] crate::reference::iter::Error),
+        #[error(transparent)]
+        IterReferencesInit(#[from] crate::reference::iter::init::Error),
+        #[error("Could not iterate or peel references")]
+        IterReferences(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+    }
+
+    /// All information needed to expand placeholders for a commit, independently of the repository it's contained in.
+    ///
+    /// It's obtained with [`Commit::pretty_format_context()`](crate::Commit::pretty_format_context()).
+    #[derive(Debug, Clone)]
+    pub struct Context {
+        pub(crate) id: gix_hash::ObjectId,
+        pub(crate) data: Vec<u8>,
+        pub(crate) short_id: String,
+        pub(crate) short_tree_id: String,
+        pub(crate) short_parent_ids: Vec<String>,
+        pub(crate) decorations: Vec<BString>,
+    }
+}
+
 /// Remove Lifetime
 impl<'repo> Commit<'repo> {
     /// Create an owned instance of this object, copying our data in the process.
@@ -158,13 +199,33 @@ impl<'repo> Commit<'repo> {
     }
 
     /// Extracts the PGP signature and the data that was used to create the signature, or `None` if it wasn't signed.
-    // TODO: make it possible to verify the signature, probably by wrapping `SignedData`. It's quite some work to do it properly.
     pub fn signature(
         &self,
     ) -> Result<Option<(std::borrow::Cow<'_, BStr>, gix_object::commit::SignedData<'_>)>, gix_object::decode::Error>
     {
         gix_object::CommitRefIter::signature(&self.data)
     }
+
+    /// Verify the signature of this commit with the [verifier as configured](crate::Repository::signature_verifier()),
+    /// or return `None` if it isn't signed.
+    pub fn verify_signature(&self) -> Result<Option<crate::gpg::verify::Outcome>, crate::gpg::verify::Error> {
+        self.verify_signature_with(&self.repo.signature_verifier()?)
+    }
+
+    /// Verify the signature of this commit with `verifier`, or return `None` if it isn't signed.
+    pub fn verify_signature_with(
+        &self,
+        verifier: &dyn crate::gpg::verify::Verifier,
+    ) -> Result<Option<crate::gpg::verify::Outcome>, crate::gpg::verify::Error> {
+        Ok(match self.signature()? {
+            Some((signature, signed_data)) => Some(
+                verifier
+                    .verify(signature.as_ref(), &signed_data.to_bstring())
+                    .map_err(crate::gpg::verify::Error::Verify)?,
+            ),
+            None => None,
+        })
+    }
 }
 
 impl<'r> std::fmt::Debug for Commit<'r> {
@@ -172,3 +233,164 @@ impl<'r> std::fmt::Debug for Commit<'r> {
         write!(f, "Commit({})", self.id)
     }
 }
+
+/// Formatting
+impl<'repo> Commit<'repo> {
+    /// Expand the placeholders in `format` for this commit like `git log --format=<format>` would, and return the result.
+    ///
+    /// See [`Context::format()`](pretty_format::Context::format()) for details.
+    pub fn pretty_format(&self, format: &BStr) -> Result<BString, pretty_format::Error> {
+        Ok(self.pretty_format_context()?.format(format))
+    }
+
+    /// Obtain all information needed to expand placeholders for this commit, to be able to do so later and without
+    /// access to the repository.
+    ///
+    /// Decorations are limited to branches, remote branches and tags, like `git log` does by default.
+    pub fn pretty_format_context(&self) -> Result<pretty_format::Context, pretty_format::Error> {
+        self.pretty_format_context_inner(false)
+    }
+
+    /// Like [`pretty_format_context()`](Self::pretty_format_context()), but if `decorate_all_refs` is `true`, references
+    /// outside of the well-known categories are used for decorations as well, like `git archive` does.
+    pub(crate) fn pretty_format_context_inner(
+        &self,
+        decorate_all_refs: bool,
+    ) -> Result<pretty_format::Context, pretty_format::Error> {
+        let commit = self.decode()?;
+        Ok(pretty_format::Context {
+            id: self.id,
+            short_id: self.id().shorten()?.to_string(),
+            short_tree_id: commit.tree().attach(self.repo).shorten()?.to_string(),
+            short_parent_ids: commit
+                .parents()
+                .map(|id| id.attach(self.repo).shorten().map(|id| id.to_string()))
+                .collect::<Result<_, _>>()?,
+            decorations: self.decorations(decorate_all_refs)?,
+            data: self.data.clone(),
+        })
+    }
+
+    /// Return the names of all branches, remote branches and tags pointing to this commit, along with `HEAD`, in the order
+    /// `git log --decorate` would use. If `all_refs` is `true`, all other references are included by their full name.
+    fn decorations(&self, all_refs: bool) -> Result<Vec<BString>, pretty_format::Error> {
+        let head = self.repo.head()?;
+        let head_branch = head
+            .id()
+            .filter(|id| *id == self.id)
+            .map(|_| head.referent_name().map(ToOwned::to_owned));
+
+        let mut names = Vec::new();
+        for reference in self.repo.references()?.all()?.peeled() {
+            let reference = reference.map_err(pretty_format::Error::IterReferences)?;
+            if reference.id() != self.id {
+                continue;
+            }
+            let name = reference.name();
+            if head_branch.as_ref().and_then(Option::as_ref).map(AsRef::as_ref) == Some(name) {
+                continue;
+            }
+            match name.category() {
+                Some(gix_ref::Category::Tag) => names.push(format!("tag: {}", name.shorten()).into()),
+                Some(gix_ref::Category::LocalBranch | gix_ref::Category::RemoteBranch) => {
+                    names.push(name.shorten().to_owned())
+                }
+                _ if all_refs => names.push(name.as_bstr().to_owned()),
+                _ => {}
+            }
+        }
+        names.reverse();
+        match head_branch {
+            Some(Some(branch)) => names.insert(0, format!("HEAD -> {}", branch.shorten()).into()),
+            Some(None) => names.insert(0, "HEAD".into()),
+            None => {}
+        }
+        Ok(names)
+    }
+}
+
+impl pretty_format::Context {
+    /// Expand the placeholders in `format` like `git log --format=<format>` would, and return the result.
+    ///
+    /// Supported are `%H`, `%h`, `%T`, `%t`, `%P`, `%p`, `%an`, `%ae`, `%ad`, `%aD`, `%ai`, `%aI`, `%at`, `%as` and their
+    /// committer counterparts starting with `%c`, as well as `%s`, `%b`, `%B`, `%d`, `%D`, `%n`, `%%` and `%x<hex>`.
+    /// Just like in `git`, unknown placeholders are kept as is.
+    ///
+    /// ### Deviation
+    ///
+    /// Dates are always formatted in the time zone of the signature, and `mailmap` isn't applied.
+    pub fn format(&self, format: &BStr) -> BString {
+        let commit = gix_object::CommitRef::from_bytes(&self.data).expect("decoded when the context was created");
+        let mut out = BString::default();
+        let mut rest = format.as_bytes();
+        while let Some(pos) = rest.find_byte(b'%') {
+            out.push_str(&rest[..pos]);
+            rest = &rest[pos + 1..];
+            match self.expand_placeholder(&commit, rest, &mut out) {
+                0 => out.push(b'%'),
+                consumed => rest = &rest[consumed..],
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+
+    /// Expand the placeholder at the beginning of `spec` into `out`, and return the amount of bytes it consumed,
+    /// or `0` if it's unknown.
+    fn expand_placeholder(&self, commit: &gix_object::CommitRef<'_>, spec: &[u8], out: &mut BString) -> usize {
+        use gix_date::time::format;
+        match spec {
+            [b'H', ..] => out.push_str(self.id.to_hex().to_string()),
+            [b'h', ..] => out.push_str(&self.short_id),
+            [b'T', ..] => out.push_str(commit.tree().to_hex().to_string()),
+            [b't', ..] => out.push_str(&self.short_tree_id),
+            [b'P', ..] => out.push_str(
+                commit
+                    .parents()
+                    .map(|id| id.to_hex().to_string())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            ),
+            [b'p', ..] => out.push_str(self.short_parent_ids.join(" ")),
+            [who @ (b'a' | b'c'), field, ..] => {
+                let signature = if *who == b'a' { commit.author } else { commit.committer };
+                match field {
+                    b'n' => out.push_str(signature.name),
+                    b'e' => out.push_str(signature.email),
+                    b'd' => out.push_str(signature.time.format(format::DEFAULT)),
+                    b'D' => out.push_str(signature.time.format(format::GIT_RFC2822)),
+                    b'i' => out.push_str(signature.time.format(format::ISO8601)),
+                    b'I' => out.push_str(signature.time.format(format::ISO8601_STRICT)),
+                    b't' => out.push_str(signature.time.format(format::UNIX)),
+                    b's' => out.push_str(signature.time.format(format::SHORT)),
+                    _ => return 0,
+                }
+                return 2;
+            }
+            [b's', ..] => out.push_str(commit.message().summary().as_ref()),
+            [b'b', ..] => out.push_str(commit.message().body.unwrap_or_default()),
+            [b'B', ..] => out.push_str(commit.message),
+            [kind @ (b'd' | b'D'), ..] => {
+                if !self.decorations.is_empty() {
+                    let decorations = self.decorations.join(", ".as_bytes());
+                    if *kind == b'd' {
+                        out.push_str(" (");
+                        out.push_str(decorations);
+                        out.push(b')');
+                    } else {
+                        out.push_str(decorations);
+                    }
+                }
+            }
+            [b'n', ..] => out.push(b'\n'),
+            [b'%', ..] => out.push(b'%'),
+            [b'x', hex @ ..] if hex.len() >= 2 && hex[..2].iter().all(u8::is_ascii_hexdigit) => {
+                let hex = std::str::from_utf8(&hex[..2]).expect("ascii");
+                out.push(u8::from_str_radix(hex, 16).expect("valid hex"));
+                return 3;
+            }
+            _ => return 0,
+        }
+        1
+    }
+}
@@ -13,6 +13,15 @@ pub mod conversion {
             actual: gix_object::Kind,
         },
     }
+
+    impl crate::error::Classify for Error {
+        fn kind(&self) -> crate::error::Kind {
+            match self {
+                Error::Decode(_) => crate::error::Kind::CorruptObject,
+                Error::UnexpectedType { .. } => crate::error::Kind::Other,
+            }
+        }
+    }
 }
 
 ///
@@ -22,6 +31,12 @@ pub mod find {
     #[error(transparent)]
     pub struct Error(#[from] pub gix_object::find::Error);
 
+    impl crate::error::Classify for Error {
+        fn kind(&self) -> crate::error::Kind {
+            crate::error::find_kind(&self.0)
+        }
+    }
+
     ///
     pub mod existing {
         /// An object could not be found in the database, or an error occurred when trying to obtain it.
@@ -2,14 +2,17 @@
 use crate::{
     object,
     object::{peel, Kind},
-    Object, Tree,
+    Commit, Object, Tree,
 };
 
 ///
 pub mod to_kind {
     mod error {
 
-        use crate::object;
+        use crate::{
+            error::{Classify, Kind},
+            object,
+        };
 
         /// The error returned by [`Object::peel_to_kind()`][crate::Object::peel_to_kind()].
         #[derive(Debug, thiserror::Error)]
@@ -24,12 +27,20 @@ pub mod to_kind {
                 expected: object::Kind,
             },
         }
+
+        impl Classify for Error {
+            fn kind(&self) -> Kind {
+                match self {
+                    Error::FindExistingObject(err) => err.kind(),
+                    Error::NotFound { .. } => Kind::NotFound,
+                }
+            }
+        }
     }
     pub use error::Error;
 }
 
 impl<'repo> Object<'repo> {
-    // TODO: tests
     /// Follow tags to their target and commits to trees until the given `kind` of object is encountered.
     ///
     /// Note that this object doesn't necessarily have to be the end of the chain.
@@ -72,7 +83,11 @@ impl<'repo> Object<'repo> {
         Ok(self.peel_to_kind(gix_object::Kind::Tree)?.into_tree())
     }
 
-    // TODO: tests
+    /// Follow tags to their target until a commit is reached and return it, if this is possible.
+    pub fn peel_to_commit(self) -> Result<Commit<'repo>, peel::to_kind::Error> {
+        Ok(self.peel_to_kind(gix_object::Kind::Commit)?.into_commit())
+    }
+
     /// Follow all tag object targets until a commit, tree or blob is reached.
     ///
     /// Note that this method is different from [`peel_to_kind(…)`][Object::peel_to_kind()] as it won't
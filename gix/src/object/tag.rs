@@ -23,6 +23,36 @@ impl<'repo> Tag<'repo> {
     pub fn tagger(&self) -> Result<Option<gix_actor::SignatureRef<'_>>, gix_object::decode::Error> {
         gix_object::TagRefIter::from_bytes(&self.data).tagger()
     }
+
+    /// Extract the signature of this tag and the data that was signed, or `None` if it isn't signed.
+    pub fn signature(&self) -> Result<Option<(&crate::bstr::BStr, &[u8])>, gix_object::decode::Error> {
+        let tag = self.decode()?;
+        Ok(tag.pgp_signature.map(|signature| {
+            let signed_data = &self.data[..self.data.len() - signature.len()];
+            (signature, signed_data)
+        }))
+    }
+
+    /// Verify the signature of this tag with the [verifier as configured](crate::Repository::signature_verifier()),
+    /// or return `None` if it isn't signed.
+    pub fn verify_signature(&self) -> Result<Option<crate::gpg::verify::Outcome>, crate::gpg::verify::Error> {
+        self.verify_signature_with(&self.repo.signature_verifier()?)
+    }
+
+    /// Verify the signature of this tag with `verifier`, or return `None` if it isn't signed.
+    pub fn verify_signature_with(
+        &self,
+        verifier: &dyn crate::gpg::verify::Verifier,
+    ) -> Result<Option<crate::gpg::verify::Outcome>, crate::gpg::verify::Error> {
+        Ok(match self.signature()? {
+            Some((signature, signed_data)) => Some(
+                verifier
+                    .verify(signature, signed_data)
+                    .map_err(crate::gpg::verify::Error::Verify)?,
+            ),
+            None => None,
+        })
+    }
 }
 
 /// Remove Lifetime
@@ -57,8 +57,12 @@ impl<'a, 'old> Platform<'a, 'old> {
             tracked: self.rewrites.map(|r| tracked::State::new(r, self.tracking)),
             err: None,
         };
-        match gix_diff::tree::Changes::from(TreeRefIter::from_bytes(&self.lhs.data)).needed_to_obtain(
-            TreeRefIter::from_bytes(&other.data),
+        match gix_diff::tree::Changes::from(TreeRefIter::from_bytes_with_hash(
+            &self.lhs.data,
+            self.lhs.repo.object_hash(),
+        ))
+        .needed_to_obtain(
+            TreeRefIter::from_bytes_with_hash(&other.data, other.repo.object_hash()),
             &mut self.state,
             &repo.objects,
             &mut delegate,
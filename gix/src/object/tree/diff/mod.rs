@@ -78,6 +78,9 @@ pub struct Rewrites {
     /// If the limit would not be enough to test the entire set of combinations, the algorithm will trade in precision and not
     /// run the fuzzy version of identity tests at all. That way results are never partial.
     pub limit: usize,
+    /// If `Some(…)`, modifications that rewrite most of a file are broken into a deletion and an addition first, similar to
+    /// `git diff -B`, so that both can be matched as part of renames or copies individually. `None` is the default.
+    pub breaks: Option<rewrites::Breaks>,
 }
 
 ///
@@ -113,5 +116,23 @@ impl<'a, 'repo> Platform<'a, 'repo> {
     }
 }
 
+/// Reuse
+impl<'a, 'repo> Platform<'a, 'repo> {
+    /// Diff `lhs` with `state` to reuse its allocations, with rewrites tracked according to `rewrites`.
+    pub(crate) fn with_state(lhs: &'a Tree<'repo>, state: gix_diff::tree::State, rewrites: Option<Rewrites>) -> Self {
+        Platform {
+            state,
+            lhs,
+            tracking: None,
+            rewrites,
+        }
+    }
+
+    /// Return the state to reuse it with the next diff.
+    pub(crate) fn into_state(self) -> gix_diff::tree::State {
+        self.state
+    }
+}
+
 ///
 pub mod for_each;
@@ -36,6 +36,60 @@ impl Default for Copies {
     }
 }
 
+/// How to break complete rewrites of files into a deletion and an addition, similar to `git diff -B<n>/<m>`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Breaks {
+    /// The amount of change at which a modification is considered a complete rewrite, counting removed and added content
+    /// relative to the larger version of the file. It's equivalent to `<n>` in `-B<n>/<m>` and defaults to `0.5`.
+    ///
+    /// Just like in `git`, files smaller than 400 bytes are never broken.
+    pub percentage: f32,
+    /// The amount of removed content, relative to the previous version of the file, at which a broken modification that
+    /// wasn't matched as part of a rename or copy is still reported as deletion and addition, instead of as modification.
+    /// It's equivalent to `<m>` in `-B<n>/<m>` and defaults to `0.6`.
+    pub split_percentage: f32,
+}
+
+impl Default for Breaks {
+    fn default() -> Self {
+        Breaks {
+            percentage: 0.5,
+            split_percentage: 0.6,
+        }
+    }
+}
+
+/// Parse a similarity score like `git` does for the `<n>` in `-M<n>`, `-C<n>` or `-B<n>/<m>`, and return it as value between
+/// `0.0` and `1.0`, or `None` if `score` isn't valid.
+///
+/// Unless `%` is used, digits are interpreted as decimal fraction, so `5` and `0.5` are `0.5` just like `50%` is, while `05` is `0.05`.
+pub fn parse_score(score: &[u8]) -> Option<f32> {
+    if score.is_empty() {
+        return None;
+    }
+    let (mut num, mut scale, mut dot) = (0u64, 1u64, false);
+    let mut bytes = score.iter().peekable();
+    while let Some(&byte) = bytes.next() {
+        match byte {
+            b'.' if !dot => {
+                scale = 1;
+                dot = true;
+            }
+            b'%' if bytes.peek().is_none() => {
+                scale = if dot { scale * 100 } else { 100 };
+            }
+            b'0'..=b'9' => {
+                if scale < 100_000 {
+                    scale *= 10;
+                    num = num * 10 + u64::from(byte - b'0');
+                }
+            }
+            _ => return None,
+        }
+    }
+    Some(if num >= scale { 1.0 } else { num as f32 / scale as f32 })
+}
+
 /// Information collected while handling rewrites of files which may be tracked.
 #[derive(Default, Clone, Copy, Debug, PartialEq)]
 pub struct Outcome {
@@ -66,6 +120,7 @@ impl Default for Rewrites {
             copies: None,
             percentage: Some(0.5),
             limit: 1000,
+            breaks: None,
         }
     }
 }
@@ -105,4 +160,55 @@ impl Rewrites {
         }
         .into())
     }
+
+    /// Create an instance for use by [`Repository::status()`](crate::Repository::status()) by reading `status.renames` and
+    /// `status.renameLimit` from `config`, falling back to `diff.renames` and `diff.renameLimit` respectively, while being `lenient` or not.
+    ///
+    /// Unlike [`try_from_config()`](Self::try_from_config()), this returns `Ok(None)` only if rename tracking is disabled,
+    /// as it's enabled by default just like in `git status`.
+    #[cfg(feature = "status")]
+    #[allow(clippy::result_large_err)]
+    pub fn try_from_status_config(config: &gix_config::File<'static>, lenient: bool) -> Result<Option<Self>, Error> {
+        use crate::config::tree::Status;
+
+        let tracking = match config
+            .boolean_by_key("status.renames")
+            .map(|value| Status::RENAMES.try_into_renames(value))
+            .transpose()
+            .with_leniency(lenient)?
+        {
+            Some(tracking) => Some(tracking),
+            None => config
+                .boolean_by_key("diff.renames")
+                .map(|value| Diff::RENAMES.try_into_renames(value))
+                .transpose()
+                .with_leniency(lenient)?,
+        };
+        let copies = match tracking.unwrap_or(Tracking::Renames) {
+            Tracking::Disabled => return Ok(None),
+            Tracking::Renames => None,
+            Tracking::RenamesAndCopies => Some(Copies::default()),
+        };
+
+        let limit = match config
+            .integer_by_key("status.renameLimit")
+            .map(|value| Status::RENAME_LIMIT.try_into_usize(value))
+            .transpose()
+            .with_leniency(lenient)?
+        {
+            Some(limit) => Some(limit),
+            None => config
+                .integer_by_key("diff.renameLimit")
+                .map(|value| Diff::RENAME_LIMIT.try_into_usize(value))
+                .transpose()
+                .with_leniency(lenient)?,
+        };
+        let default = Self::default();
+        Ok(Rewrites {
+            copies,
+            limit: limit.unwrap_or(default.limit),
+            ..default
+        }
+        .into())
+    }
 }
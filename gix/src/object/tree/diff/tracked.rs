@@ -8,7 +8,7 @@ use crate::{
     ext::ObjectIdExt,
     object::tree::diff::{
         change::DiffLineStats,
-        rewrites::{CopySource, Outcome},
+        rewrites::{Breaks, CopySource, Outcome},
         Rewrites,
     },
     Repository, Tree,
@@ -22,6 +22,9 @@ pub struct Item {
     location: Range<usize>,
     /// If true, this item was already emitted, i.e. seen by the caller.
     emitted: bool,
+    /// If `Some(removed)`, this item is one half of a modification that was broken into a deletion and an addition,
+    /// with `removed` being the fraction of the previous content that was removed.
+    broken: Option<f32>,
 }
 
 impl Item {
@@ -63,6 +66,8 @@ pub mod visit {
         pub kind: Kind,
         pub location: &'a BStr,
         pub diff: Option<DiffLineStats>,
+        /// The similarity of source and destination, from `0.0` to `1.0` for identical content.
+        pub similarity: f32,
     }
 
     #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -97,7 +102,7 @@ impl State {
         }
         let keep = match (self.rewrites.copies, &change) {
             (Some(_find_copies), _) => true,
-            (None, Change::Modification { .. }) => false,
+            (None, Change::Modification { .. }) => self.rewrites.breaks.is_some(),
             (None, _) => true,
         };
 
@@ -111,6 +116,7 @@ impl State {
             location: start..self.path_backing.len(),
             change,
             emitted: false,
+            broken: None,
         });
         None
     }
@@ -133,6 +139,9 @@ impl State {
                     .then(a.location.end.cmp(&b.location.end))
             })
         }
+        if let Some(breaks) = self.rewrites.breaks {
+            self.break_rewrites(breaks, src_tree.repo)?;
+        }
         self.items.sort_by(by_id_and_location);
 
         let mut out = Outcome {
@@ -175,23 +184,111 @@ impl State {
             }
         }
 
-        self.items
-            .sort_by(|a, b| a.location(&self.path_backing).cmp(b.location(&self.path_backing)));
-        for item in self.items.drain(..).filter(|item| !item.emitted) {
-            if cb(
-                visit::Destination {
-                    location: item.location(&self.path_backing),
-                    change: item.change,
-                },
-                None,
-            ) == gix_diff::tree::visit::Action::Cancel
-            {
+        // Deletions go first so both halves of broken modifications are next to each other.
+        self.items.sort_by(|a, b| {
+            a.location(&self.path_backing)
+                .cmp(b.location(&self.path_backing))
+                .then_with(|| {
+                    matches!(a.change, Change::Addition { .. }).cmp(&matches!(b.change, Change::Addition { .. }))
+                })
+        });
+        let split_percentage = self.rewrites.breaks.map(|breaks| breaks.split_percentage);
+        let mut items = self.items.drain(..).filter(|item| !item.emitted).peekable();
+        while let Some(item) = items.next() {
+            let location = item.location(&self.path_backing);
+            let change = match (&item.change, item.broken, items.peek()) {
+                (
+                    Change::Deletion {
+                        entry_mode: previous_entry_mode,
+                        oid: previous_oid,
+                    },
+                    Some(removed),
+                    Some(next),
+                ) if next.broken.is_some()
+                    && next.location == item.location
+                    && split_percentage.map_or(false, |split| removed < split) =>
+                {
+                    let next = items.next().expect("peeked");
+                    let (oid, entry_mode) = next.change.oid_and_entry_mode();
+                    Change::Modification {
+                        previous_entry_mode: *previous_entry_mode,
+                        previous_oid: *previous_oid,
+                        entry_mode,
+                        oid: oid.to_owned(),
+                    }
+                }
+                _ => item.change,
+            };
+            if cb(visit::Destination { location, change }, None) == gix_diff::tree::visit::Action::Cancel {
                 break;
             }
         }
         Ok(out)
     }
 
+    /// Split all modifications of blobs that are complete rewrites according to `breaks` into a deletion and an addition,
+    /// so that both can be matched with other changes individually.
+    fn break_rewrites(
+        &mut self,
+        breaks: Breaks,
+        repo: &Repository,
+    ) -> Result<(), crate::object::tree::diff::for_each::Error> {
+        /// Like `git`, we don't break small files.
+        const MINIMUM_BREAK_SIZE: usize = 400;
+        let algo = repo.config.diff_algorithm()?;
+        let mut additions = Vec::new();
+        for item in &mut self.items {
+            let (previous_entry_mode, previous_oid, entry_mode, oid) = match &item.change {
+                Change::Modification {
+                    previous_entry_mode,
+                    previous_oid,
+                    entry_mode,
+                    oid,
+                } if previous_entry_mode.is_blob() && entry_mode.is_blob() && previous_oid != oid => {
+                    (*previous_entry_mode, *previous_oid, *entry_mode, *oid)
+                }
+                _ => continue,
+            };
+            let old = previous_oid.attach(repo).object()?;
+            let new = oid.attach(repo).object()?;
+            let max_size = old.data.len().max(new.data.len());
+            if old.data.is_empty() || max_size < MINIMUM_BREAK_SIZE {
+                continue;
+            }
+            let tokens = gix_diff::blob::intern::InternedInput::new(
+                gix_diff::blob::sources::byte_lines_with_terminator(&old.data),
+                gix_diff::blob::sources::byte_lines_with_terminator(&new.data),
+            );
+            let removed = gix_diff::blob::diff(
+                algo,
+                &tokens,
+                diff::Statistics {
+                    removed_bytes: 0,
+                    input: &tokens,
+                },
+            );
+            // All content that wasn't removed is still present, so everything else was added.
+            let added = new.data.len() - (old.data.len() - removed);
+            if ((removed + added) as f32 / max_size as f32) < breaks.percentage {
+                continue;
+            }
+            let removed = removed as f32 / old.data.len() as f32;
+            item.change = Change::Deletion {
+                entry_mode: previous_entry_mode,
+                oid: previous_oid,
+            };
+            item.broken = Some(removed);
+            additions.push(Item {
+                change: Change::Addition { entry_mode, oid },
+                location: item.location.clone(),
+                emitted: false,
+                broken: Some(removed),
+            });
+        }
+        self.items.extend(additions);
+        Ok(())
+    }
+
     fn match_pairs_of_kind(
         &mut self,
         kind: visit::Kind,
@@ -244,8 +341,8 @@ impl State {
         }) {
             dest_idx += dest_ofs;
             dest_ofs = dest_idx + 1;
-            let src =
-                find_match(&self.items, dest, dest_idx, percentage, kind, repo, stats)?.map(|(src_idx, src, diff)| {
+            let src = find_match(&self.items, dest, dest_idx, percentage, kind, repo, stats)?.map(
+                |(src_idx, src, diff, similarity)| {
                     let (id, mode) = src.change.oid_and_entry_mode();
                     let id = id.to_owned();
                     let location = src.location(&self.path_backing);
@@ -256,10 +353,12 @@ impl State {
                             kind,
                             location,
                             diff,
+                            similarity,
                         },
                         src_idx,
                     )
-                });
+                },
+            );
             if src.is_none() {
                 continue;
             }
@@ -311,8 +410,8 @@ fn needs_exact_match(percentage: Option<f32>) -> bool {
     percentage.map_or(true, |p| p >= 1.0)
 }
 
-/// <`src_idx`, src, possibly diff stat>
-type SourceTuple<'a> = (usize, &'a Item, Option<DiffLineStats>);
+/// <`src_idx`, src, possibly diff stat, similarity>
+type SourceTuple<'a> = (usize, &'a Item, Option<DiffLineStats>, f32);
 
 /// Find `item` in our set of items ignoring `item_idx` to avoid finding ourselves, by similarity indicated by `percentage`.
 /// The latter can be `None` or `Some(x)` where `x>=1` for identity, and anything else for similarity.
@@ -348,7 +447,8 @@ fn find_match<'a>(
         }
         let res = items[range.clone()].iter().enumerate().find_map(|(mut src_idx, src)| {
             src_idx += range.start;
-            (src_idx != item_idx && src.is_source_for_destination_of(kind, item_mode)).then_some((src_idx, src, None))
+            (src_idx != item_idx && src.location != item.location && src.is_source_for_destination_of(kind, item_mode))
+                .then_some((src_idx, src, None, 1.0))
         });
         if let Some(src) = res {
             return Ok(Some(src));
@@ -361,46 +461,50 @@ fn find_match<'a>(
             "symlinks are matched exactly, and trees aren't used here"
         );
         let algo = repo.config.diff_algorithm()?;
-        for (can_idx, src) in items
-            .iter()
-            .enumerate()
-            .filter(|(src_idx, src)| *src_idx != item_idx && src.is_source_for_destination_of(kind, item_mode))
-        {
+        for (can_idx, src) in items.iter().enumerate().filter(|(src_idx, src)| {
+            // Both halves of a broken modification have the same location, and they are never matched with each other.
+            *src_idx != item_idx && src.location != item.location && src.is_source_for_destination_of(kind, item_mode)
+        }) {
             let old = src.change.oid().to_owned().attach(repo).object()?;
-            // TODO: make sure we get attribute handling and binary skips and filters right here. There is crate::object::blob::diff::Platform
-            //       which should have facilities for that one day, but we don't use it because we need newlines in our tokens.
-            let tokens = gix_diff::blob::intern::InternedInput::new(
-                gix_diff::blob::sources::byte_lines_with_terminator(&old.data),
-                gix_diff::blob::sources::byte_lines_with_terminator(&new.data),
-            );
-            let counts = gix_diff::blob::diff(
-                algo,
-                &tokens,
-                gix_diff::blob::sink::Counter::new(diff::Statistics {
-                    removed_bytes: 0,
-                    input: &tokens,
-                }),
-            );
-            let similarity = (old.data.len() - counts.wrapped) as f32 / old.data.len().max(new.data.len()) as f32;
+            let (similarity, diff) = similarity(algo, &old.data, &new.data);
             stats.num_similarity_checks += 1;
             if similarity >= percentage {
-                return Ok(Some((
-                    can_idx,
-                    src,
-                    DiffLineStats {
-                        removals: counts.removals,
-                        insertions: counts.insertions,
-                        before: tokens.before.len().try_into().expect("interner handles only u32"),
-                        after: tokens.after.len().try_into().expect("interner handles only u32"),
-                    }
-                    .into(),
-                )));
+                return Ok(Some((can_idx, src, diff.into(), similarity)));
             }
         }
     }
     Ok(None)
 }
 
+/// Return the similarity of `old` and `new` from `0.0` to `1.0`, which is the amount of bytes of `old` that are still
+/// present in `new` relative to the size of the larger one, along with statistics about the lines that changed.
+pub(crate) fn similarity(algo: gix_diff::blob::Algorithm, old: &[u8], new: &[u8]) -> (f32, DiffLineStats) {
+    // TODO: make sure we get attribute handling and binary skips and filters right here. There is crate::object::blob::diff::Platform
+    //       which should have facilities for that one day, but we don't use it because we need newlines in our tokens.
+    let tokens = gix_diff::blob::intern::InternedInput::new(
+        gix_diff::blob::sources::byte_lines_with_terminator(old),
+        gix_diff::blob::sources::byte_lines_with_terminator(new),
+    );
+    let counts = gix_diff::blob::diff(
+        algo,
+        &tokens,
+        gix_diff::blob::sink::Counter::new(diff::Statistics {
+            removed_bytes: 0,
+            input: &tokens,
+        }),
+    );
+    let similarity = (old.len() - counts.wrapped) as f32 / old.len().max(new.len()) as f32;
+    (
+        similarity,
+        DiffLineStats {
+            removals: counts.removals,
+            insertions: counts.insertions,
+            before: tokens.before.len().try_into().expect("interner handles only u32"),
+            after: tokens.after.len().try_into().expect("interner handles only u32"),
+        },
+    )
+}
+
 mod diff {
     use std::ops::Range;
 
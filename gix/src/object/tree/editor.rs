@@ -0,0 +1,48 @@
+use crate::{ext::ObjectIdExt, object, Id, Repository};
+
+/// A [tree editor](gix_object::tree::Editor) which writes changed trees into the object database of a repository.
+///
+/// Use it like the underlying editor to [insert or update](gix_object::tree::Editor::upsert()) and
+/// [remove](gix_object::tree::Editor::remove()) entries, and [write](Editor::write()) the changed trees afterwards.
+pub struct Editor<'repo> {
+    inner: gix_object::tree::Editor<'repo>,
+    repo: &'repo Repository,
+}
+
+/// Lifecycle
+impl<'repo> Editor<'repo> {
+    /// Create a new editor to change `root`, with trees being loaded from and written to the object database of `repo`.
+    pub fn new(root: gix_object::Tree, repo: &'repo Repository) -> Self {
+        Editor {
+            inner: gix_object::tree::Editor::new(root, &repo.objects, repo.object_hash()),
+            repo,
+        }
+    }
+}
+
+/// Writing
+impl<'repo> Editor<'repo> {
+    /// Write all changed trees into the object database and return the id of the new root tree.
+    ///
+    /// The editor can be used for more edits afterwards, which will then be based on the written root tree.
+    pub fn write(&mut self) -> Result<Id<'repo>, object::write::Error> {
+        let repo = self.repo;
+        self.inner
+            .write(|tree| repo.write_object(tree).map(Id::detach))
+            .map(|id| id.attach(repo))
+    }
+}
+
+impl<'repo> std::ops::Deref for Editor<'repo> {
+    type Target = gix_object::tree::Editor<'repo>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<'repo> std::ops::DerefMut for Editor<'repo> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
@@ -63,6 +63,7 @@ impl<'repo> Tree<'repo> {
     /// Return an iterator over tree entries to obtain information about files and directories this tree contains.
     pub fn iter(&self) -> impl Iterator<Item = Result<EntryRef<'repo, '_>, gix_object::decode::Error>> {
         let repo = self.repo;
-        gix_object::TreeRefIter::from_bytes(&self.data).map(move |e| e.map(|entry| EntryRef { inner: entry, repo }))
+        gix_object::TreeRefIter::from_bytes_with_hash(&self.data, repo.object_hash())
+            .map(move |e| e.map(|entry| EntryRef { inner: entry, repo }))
     }
 }
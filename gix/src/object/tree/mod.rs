@@ -27,12 +27,17 @@ impl<'repo> Tree<'repo> {
 
     /// Parse our tree data and return the parse tree for direct access to its entries.
     pub fn decode(&self) -> Result<gix_object::TreeRef<'_>, gix_object::decode::Error> {
-        gix_object::TreeRef::from_bytes(&self.data)
+        gix_object::TreeRef::from_bytes_with_hash(&self.data, self.repo.object_hash())
+    }
+
+    /// Return an [editor](Editor) to change this tree, which writes the changed trees into the object database.
+    pub fn edit(&self) -> Result<Editor<'repo>, gix_object::decode::Error> {
+        Ok(Editor::new(self.decode()?.into(), self.repo))
     }
 
     /// Find the entry named `name` by iteration, or return `None` if it wasn't found.
     pub fn find_entry(&self, name: impl PartialEq<BStr>) -> Option<EntryRef<'repo, '_>> {
-        TreeRefIter::from_bytes(&self.data)
+        TreeRefIter::from_bytes_with_hash(&self.data, self.repo.object_hash())
             .filter_map(Result::ok)
             .find(|entry| name.eq(entry.filename))
             .map(|entry| EntryRef {
@@ -60,7 +65,7 @@ impl<'repo> Tree<'repo> {
         buf.clear();
         buf.extend_from_slice(&self.data);
         while let Some(component) = path.next() {
-            match TreeRefIter::from_bytes(buf)
+            match TreeRefIter::from_bytes_with_hash(buf, self.repo.object_hash())
                 .filter_map(Result::ok)
                 .find(|entry| component.eq(entry.filename))
             {
@@ -102,7 +107,7 @@ impl<'repo> Tree<'repo> {
     {
         let mut path = path.into_iter().peekable();
         while let Some(component) = path.next() {
-            match TreeRefIter::from_bytes(&self.data)
+            match TreeRefIter::from_bytes_with_hash(&self.data, self.repo.object_hash())
                 .filter_map(Result::ok)
                 .find(|entry| component.eq(entry.filename))
             {
@@ -181,6 +186,9 @@ pub mod traverse;
 mod iter;
 pub use iter::EntryRef;
 
+mod editor;
+pub use editor::Editor;
+
 impl<'r> std::fmt::Debug for Tree<'r> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "Tree({})", self.id)
@@ -48,7 +48,7 @@ impl<'a, 'repo> Platform<'a, 'repo> {
     where
         V: gix_traverse::tree::Visit,
     {
-        let root = gix_object::TreeRefIter::from_bytes(&self.root.data);
+        let root = gix_object::TreeRefIter::from_bytes_with_hash(&self.root.data, self.root.repo.object_hash());
         let state = gix_traverse::tree::breadthfirst::State::default();
         gix_traverse::tree::breadthfirst(root, state, &self.root.repo.objects, delegate)
     }
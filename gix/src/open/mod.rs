@@ -17,13 +17,15 @@ pub struct Permissions {
 ///
 /// ### Replacement Objects for the object database
 ///
-/// The environment variables `GIT_REPLACE_REF_BASE` and `GIT_NO_REPLACE_OBJECTS` are mapped to `gitoxide.objects.replaceRefBase`
-/// and `gitoxide.objects.noReplace` respectively and then interpreted exactly as their environment variable counterparts.
+/// Replacement objects are used unless `core.useReplaceRefs` is `false`, which is also the case if the `GIT_NO_REPLACE_OBJECTS`
+/// environment variable is set. They are read from references below `refs/replace/`, or below `gitoxide.objects.replaceRefBase`
+/// to which the `GIT_REPLACE_REF_BASE` environment variable is mapped.
 ///
 /// Use [Permissions] to control which environment variables can be read, and config-overrides to control these values programmatically.
 #[derive(Clone)]
 pub struct Options {
     pub(crate) object_store_slots: gix_odb::store::init::Slots,
+    pub(crate) max_open_packs: Option<usize>,
     /// Define what is allowed while opening a repository.
     pub permissions: Permissions,
     pub(crate) git_dir_trust: Option<gix_sec::Trust>,
@@ -35,6 +37,9 @@ pub struct Options {
     pub(crate) api_config_overrides: Vec<BString>,
     pub(crate) cli_config_overrides: Vec<BString>,
     pub(crate) open_path_as_is: bool,
+    pub(crate) read_only: bool,
+    #[cfg(all(feature = "index", feature = "parallel"))]
+    pub(crate) index_cache: Option<crate::worktree::IndexCache>,
     /// Internal to pass an already obtained CWD on to where it may also be used. This avoids the CWD being queried more than once per repo.
     pub(crate) current_dir: Option<PathBuf>,
 }
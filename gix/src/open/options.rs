@@ -7,6 +7,7 @@ impl Default for Options {
     fn default() -> Self {
         Options {
             object_store_slots: Default::default(),
+            max_open_packs: None,
             permissions: Default::default(),
             git_dir_trust: None,
             filter_config_section: None,
@@ -14,6 +15,9 @@ impl Default for Options {
             lenient_config: true,
             bail_if_untrusted: false,
             open_path_as_is: false,
+            read_only: false,
+            #[cfg(all(feature = "index", feature = "parallel"))]
+            index_cache: None,
             api_config_overrides: Vec::new(),
             cli_config_overrides: Vec::new(),
             current_dir: None,
@@ -63,6 +67,15 @@ impl Options {
         self
     }
 
+    /// Set the maximum amount of pack data files the object database may keep open at a time, closing the least recently used ones
+    /// if there would be more, or `None` to not limit them which is the default.
+    ///
+    /// This should be set in long-running processes like servers to not run out of file handles or memory maps.
+    pub fn max_open_packs(mut self, max: Option<usize>) -> Self {
+        self.max_open_packs = max;
+        self
+    }
+
     // TODO: tests
     /// Set the given permissions, which are typically derived by a `Trust` level.
     pub fn permissions(mut self, permissions: Permissions) -> Self {
@@ -79,6 +92,29 @@ impl Options {
         self
     }
 
+    /// If `true`, default `false`, the repository will be opened in read-only mode which guarantees that no lock files,
+    /// temporary files, objects or packs are written to the repository.
+    ///
+    /// This is useful when operating on snapshots or read-only mounted filesystems. All operations that would
+    /// write to the repository, like [writing objects](crate::Repository::write_object()) or
+    /// [editing references](crate::Repository::edit_references()), will fail with a [read-only error](crate::repository::read_only::Error)
+    /// instead. Reference logs are disabled as well.
+    pub fn read_only(mut self, toggle: bool) -> Self {
+        self.read_only = toggle;
+        self
+    }
+
+    /// Use `cache` to obtain the index of the repository to open, so that all repositories opened with the same `cache`
+    /// share a single in-memory snapshot of the same index file, even if they were opened independently.
+    ///
+    /// Without it, each call to [`open()`](crate::open()) starts with its own snapshot and thus decodes the index once more
+    /// on first access. Note that [clones](crate::Repository::clone()) of a repository share their index snapshot either way.
+    #[cfg(all(feature = "index", feature = "parallel"))]
+    pub fn index_cache(mut self, cache: crate::worktree::IndexCache) -> Self {
+        self.index_cache = Some(cache);
+        self
+    }
+
     /// Set the trust level of the `.git` directory we are about to open.
     ///
     /// This can be set manually to force trust even though otherwise it might
@@ -160,6 +196,7 @@ impl gix_sec::trust::DefaultForLevel for Options {
         match level {
             gix_sec::Trust::Full => Options {
                 object_store_slots: Default::default(),
+                max_open_packs: None,
                 permissions: Permissions::default_for_level(level),
                 git_dir_trust: gix_sec::Trust::Full.into(),
                 filter_config_section: Some(config::section::is_trusted),
@@ -167,18 +204,25 @@ impl gix_sec::trust::DefaultForLevel for Options {
                 bail_if_untrusted: false,
                 lenient_config: true,
                 open_path_as_is: false,
+                read_only: false,
+                #[cfg(all(feature = "index", feature = "parallel"))]
+                index_cache: None,
                 api_config_overrides: Vec::new(),
                 cli_config_overrides: Vec::new(),
                 current_dir: None,
             },
             gix_sec::Trust::Reduced => Options {
                 object_store_slots: gix_odb::store::init::Slots::Given(32), // limit resource usage
+                max_open_packs: None,
                 permissions: Permissions::default_for_level(level),
                 git_dir_trust: gix_sec::Trust::Reduced.into(),
                 filter_config_section: Some(config::section::is_trusted),
                 bail_if_untrusted: false,
                 lenient_config: true,
                 open_path_as_is: false,
+                read_only: false,
+                #[cfg(all(feature = "index", feature = "parallel"))]
+                index_cache: None,
                 lossy_config: None,
                 api_config_overrides: Vec::new(),
                 cli_config_overrides: Vec::new(),
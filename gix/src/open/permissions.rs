@@ -25,7 +25,10 @@ pub struct Config {
     /// Whether to use the user configuration.
     /// This is usually `~/.gitconfig` on unix.
     pub user: bool,
-    /// Whether to use the configuration from environment variables.
+    /// Whether to use the configuration from environment variables, namely `GIT_CONFIG_COUNT` along with
+    /// `GIT_CONFIG_KEY_<n>` and `GIT_CONFIG_VALUE_<n>`.
+    ///
+    /// Note that these variables are also subject to the [`git_prefix`](Environment::git_prefix) permission.
     pub env: bool,
     /// Whether to follow include files are encountered in loaded configuration,
     /// via `include` and `includeIf` sections.
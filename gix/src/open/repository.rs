@@ -157,11 +157,15 @@ impl ThreadSafeRepository {
         let Options {
             git_dir_trust,
             object_store_slots,
+            max_open_packs,
             filter_config_section,
             lossy_config,
             lenient_config,
             bail_if_untrusted,
             open_path_as_is: _,
+            read_only,
+            #[cfg(all(feature = "index", feature = "parallel"))]
+            ref index_cache,
             permissions:
                 Permissions {
                     ref env,
@@ -272,7 +276,11 @@ impl ThreadSafeRepository {
             None => {}
         }
 
-        refs.write_reflog = config::cache::util::reflog_or_default(config.reflog, worktree_dir.is_some());
+        refs.write_reflog = if read_only {
+            gix_ref::store::WriteReflog::Disable
+        } else {
+            config::cache::util::reflog_or_default(config.reflog, worktree_dir.is_some())
+        };
         let replacements = replacement_objects_refs_prefix(&config.resolved, lenient_config, filter_config_section)?
             .and_then(|prefix| {
                 let _span = gix_trace::detail!("find replacement objects");
@@ -291,7 +299,15 @@ impl ThreadSafeRepository {
                 Some(replacements)
             })
             .unwrap_or_default();
+        let (max_mapped_pack_bytes, pack_mmap_advice) = config.pack_mmap_options()?;
 
+        #[cfg(all(feature = "index", feature = "parallel"))]
+        let index = index_cache.as_ref().map_or_else(
+            || gix_fs::SharedFileSnapshotMut::new().into(),
+            |cache| cache.storage_for(&git_dir),
+        );
+        #[cfg(all(feature = "index", not(feature = "parallel")))]
+        let index = gix_fs::SharedFileSnapshotMut::new().into();
         Ok(ThreadSafeRepository {
             objects: OwnShared::new(gix_odb::Store::at_opts(
                 common_dir_ref.join("objects"),
@@ -301,6 +317,13 @@ impl ThreadSafeRepository {
                     object_hash: config.object_hash,
                     use_multi_pack_index: config.use_multi_pack_index,
                     current_dir: current_dir.to_owned().into(),
+                    max_open_packs,
+                    max_mapped_pack_bytes,
+                    pack_mmap_advice,
+                    loose_compression_level: config
+                        .compression_level(&Core::LOOSE_COMPRESSION)
+                        .map_err(config::Error::from)?
+                        .unwrap_or(gix_features::zlib::stream::deflate::DEFAULT_LEVEL),
                 },
             )?),
             common_dir,
@@ -310,21 +333,21 @@ impl ThreadSafeRepository {
             // used when spawning new repositories off this one when following worktrees
             linked_worktree_options: options,
             #[cfg(feature = "index")]
-            index: gix_fs::SharedFileSnapshotMut::new().into(),
+            index,
             shallow_commits: gix_fs::SharedFileSnapshotMut::new().into(),
+            compat_map: gix_fs::SharedFileSnapshotMut::new().into(),
             #[cfg(feature = "attributes")]
             modules: gix_fs::SharedFileSnapshotMut::new().into(),
         })
     }
 }
 
-// TODO: tests
 fn replacement_objects_refs_prefix(
     config: &gix_config::File<'static>,
     lenient: bool,
     mut filter_config_section: fn(&gix_config::file::Metadata) -> bool,
 ) -> Result<Option<PathBuf>, Error> {
-    let is_disabled = config
+    let use_replace_refs = config
         .boolean_filter_by_key("core.useReplaceRefs", &mut filter_config_section)
         .map(|b| Core::USE_REPLACE_REFS.enrich_error(b))
         .transpose()
@@ -332,7 +355,7 @@ fn replacement_objects_refs_prefix(
         .map_err(config::Error::ConfigBoolean)?
         .unwrap_or(true);
 
-    if is_disabled {
+    if !use_replace_refs {
         return Ok(None);
     }
 
@@ -1,7 +1,28 @@
+#[cfg(feature = "attributes")]
+use std::borrow::Cow;
 use std::path::PathBuf;
 
 pub use gix_path::*;
 
+#[cfg(feature = "attributes")]
+use crate::bstr::{BStr, BString, ByteSlice};
+
+/// Return `path` with all of its decomposed unicode characters precomposed, which is what `git` does with paths read from
+/// the filesystem if `core.precomposeUnicode` is enabled.
+///
+/// This is needed on Apple platforms, where the filesystem returns decomposed paths even if they were created precomposed.
+/// Paths that aren't valid UTF-8 are returned unchanged.
+#[cfg(feature = "attributes")]
+pub fn precompose_unicode(path: &BStr) -> Cow<'_, BStr> {
+    use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+    match path.to_str() {
+        Ok(path) if !path.is_ascii() && is_nfc_quick(path.chars()) != IsNormalized::Yes => {
+            Cow::Owned(BString::from(path.nfc().collect::<String>()))
+        }
+        _ => Cow::Borrowed(path),
+    }
+}
+
 pub(crate) fn install_dir() -> std::io::Result<PathBuf> {
     std::env::current_exe().and_then(|exe| {
         exe.parent()
@@ -9,3 +30,8 @@ pub(crate) fn install_dir() -> std::io::Result<PathBuf> {
             .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::Other, "no parent for current executable"))
     })
 }
+
+/// Quote `path` so that it's passed as single argument by the shell.
+pub(crate) fn shell_quote(path: &std::path::Path) -> String {
+    format!("'{}'", path.to_string_lossy().replace('\'', r"'\''"))
+}
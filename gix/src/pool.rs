@@ -0,0 +1,188 @@
+//! Keep repositories open for reuse across threads, with limits on the resources they may use together.
+//!
+//! This is useful for applications like servers that access a lot of repositories, see [`Pool`].
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use crate::{open, ThreadSafeRepository};
+
+/// Options for use in [`Pool::new()`].
+#[derive(Clone, Default)]
+pub struct Options {
+    /// The maximum amount of repositories to keep open, with the least recently used ones being evicted first.
+    ///
+    /// If `None`, there is no limit.
+    pub max_repositories: Option<usize>,
+    /// The maximum amount of pack data files that may be open across all repositories in the pool, with the least recently used
+    /// repositories being evicted until the limit is respected. Each open pack also holds a file handle or memory map.
+    ///
+    /// The repository that is currently obtained is never evicted, which is why the limit may be exceeded if it uses more packs than that.
+    /// If `None`, there is no limit.
+    pub max_open_packs: Option<usize>,
+    /// The options to use when opening repositories.
+    pub open: open::Options,
+}
+
+/// A collection of open repositories keyed by their path, which can be shared across threads.
+///
+/// Repositories are opened on first access and returned as cheap clones afterwards, so all users share the caches and open
+/// packs of the same object database. Repositories that exceed the limits configured in [`Options`] are evicted from the pool,
+/// least recently used ones first. Note that their resources are only released once all clones handed out before are dropped as well.
+pub struct Pool {
+    options: Options,
+    state: parking_lot::Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    repos: HashMap<PathBuf, Entry>,
+    /// A counter to track the order of accesses.
+    clock: u64,
+}
+
+struct Entry {
+    repo: ThreadSafeRepository,
+    last_used: u64,
+}
+
+/// Lifecycle
+impl Pool {
+    /// Create a new empty pool that respects the limits of `options`.
+    pub fn new(options: Options) -> Self {
+        Pool {
+            options,
+            state: Default::default(),
+        }
+    }
+}
+
+/// Access
+impl Pool {
+    /// Return the repository at `path`, opening it if it isn't yet in the pool, and evict other repositories to respect the configured limits.
+    ///
+    /// `path` is canonicalized to assure each repository is only held once.
+    #[allow(clippy::result_large_err)]
+    pub fn get(&self, path: impl AsRef<Path>) -> Result<ThreadSafeRepository, open::Error> {
+        let path = key(path.as_ref());
+        let repo = match self.touch(&path) {
+            Some(repo) => repo,
+            None => {
+                // Open without holding the lock so other repositories can be obtained in the meantime.
+                let repo = ThreadSafeRepository::open_opts(path.clone(), self.options.open.clone())?;
+                let mut state = self.state.lock();
+                state.clock += 1;
+                let last_used = state.clock;
+                let entry = state.repos.entry(path.clone()).or_insert(Entry { repo, last_used });
+                // Another thread may have been faster, in which case we use its instance.
+                entry.last_used = last_used;
+                entry.repo.clone()
+            }
+        };
+        self.evict(&path);
+        Ok(repo)
+    }
+
+    /// Remove the repository at `path` from the pool and return it, if it was present.
+    pub fn remove(&self, path: impl AsRef<Path>) -> Option<ThreadSafeRepository> {
+        self.state
+            .lock()
+            .repos
+            .remove(&key(path.as_ref()))
+            .map(|entry| entry.repo)
+    }
+
+    /// Remove all repositories from the pool.
+    pub fn clear(&self) {
+        self.state.lock().repos.clear();
+    }
+
+    /// Return the amount of repositories in the pool.
+    pub fn len(&self) -> usize {
+        self.state.lock().repos.len()
+    }
+
+    /// Return `true` if there is no repository in the pool.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return `true` if the repository at `path` is in the pool.
+    pub fn contains(&self, path: impl AsRef<Path>) -> bool {
+        self.state.lock().repos.contains_key(&key(path.as_ref()))
+    }
+
+    /// Return the amount of pack data files that are currently open across all repositories in the pool.
+    pub fn open_packs(&self) -> usize {
+        self.state
+            .lock()
+            .repos
+            .values()
+            .map(|entry| open_packs(&entry.repo))
+            .sum()
+    }
+}
+
+/// Utilities
+impl Pool {
+    /// Mark the repository at `path` as used and return it, if present.
+    fn touch(&self, path: &Path) -> Option<ThreadSafeRepository> {
+        let mut state = self.state.lock();
+        state.clock += 1;
+        let clock = state.clock;
+        state.repos.get_mut(path).map(|entry| {
+            entry.last_used = clock;
+            entry.repo.clone()
+        })
+    }
+
+    /// Evict the least recently used repositories other than the one at `keep` until all limits are respected.
+    fn evict(&self, keep: &Path) {
+        let mut state = self.state.lock();
+        let mut open_packs_by_path: HashMap<PathBuf, usize> = match self.options.max_open_packs {
+            Some(_) => state
+                .repos
+                .iter()
+                .map(|(path, entry)| (path.clone(), open_packs(&entry.repo)))
+                .collect(),
+            None => HashMap::new(),
+        };
+        loop {
+            let too_many_repos = self
+                .options
+                .max_repositories
+                .map_or(false, |max| state.repos.len() > max);
+            let too_many_packs = self
+                .options
+                .max_open_packs
+                .map_or(false, |max| open_packs_by_path.values().sum::<usize>() > max);
+            if !(too_many_repos || too_many_packs) {
+                break;
+            }
+            let least_recently_used = state
+                .repos
+                .iter()
+                .filter(|(path, _)| path.as_path() != keep)
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(path, _)| path.clone());
+            match least_recently_used {
+                Some(path) => {
+                    state.repos.remove(&path);
+                    open_packs_by_path.remove(&path);
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+/// Return the path to use as key for the repository at `path`.
+fn key(path: &Path) -> PathBuf {
+    gix_path::realpath(path).unwrap_or_else(|_| path.to_owned())
+}
+
+fn open_packs(repo: &ThreadSafeRepository) -> usize {
+    let metrics = repo.objects.metrics();
+    metrics.open_reachable_packs + metrics.unreachable_packs
+}
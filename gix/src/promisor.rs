@@ -0,0 +1,46 @@
+//! Partial clones omit objects when fetching from their promisor remote, which promises to provide them later on demand.
+//!
+//! Once [enabled](crate::Repository::enable_lazy_fetch()), objects that are missing when looked up are fetched one at a
+//! time from the promisor remote. Callers that know they need many objects should [fetch them in a batch](crate::Repository::fetch_missing_objects())
+//! beforehand.
+//!
+//! ### Deviation
+//!
+//! Unlike git, lazy fetching is disabled by default and has to be enabled per repository.
+
+///
+pub mod fetch {
+    /// The error returned by [`Repository::fetch_missing_objects()`](crate::Repository::fetch_missing_objects()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The repository is not a partial clone as `extensions.partialClone` isn't set")]
+        NoPromisorRemote,
+        #[error(transparent)]
+        FindRemote(#[from] crate::remote::find::existing::Error),
+        #[error(transparent)]
+        Refspec(#[from] gix_refspec::parse::Error),
+        #[error(transparent)]
+        Connect(#[from] crate::remote::connect::Error),
+        #[error(transparent)]
+        PrepareFetch(#[from] crate::remote::fetch::prepare::Error),
+        #[error(transparent)]
+        Fetch(#[from] crate::remote::fetch::Error),
+    }
+}
+
+///
+pub mod enable {
+    /// The error returned by [`Repository::enable_lazy_fetch()`](crate::Repository::enable_lazy_fetch()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not open the repository to fetch missing objects with")]
+        Open(#[from] crate::open::Error),
+        #[error(transparent)]
+        Fetch(#[from] super::fetch::Error),
+    }
+}
+
+/// The filter to use when fetching missing objects, to only receive what's needed to access the wanted objects, just like git.
+pub(crate) const LAZY_FETCH_FILTER: &str = "blob:none";
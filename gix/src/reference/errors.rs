@@ -1,6 +1,9 @@
 ///
 pub mod edit {
-    use crate::config;
+    use crate::{
+        config,
+        error::{Classify, Kind},
+    };
 
     /// The error returned by [`edit_references(…)`][crate::Repository::edit_references()], and others
     /// which ultimately create a reference.
@@ -17,6 +20,24 @@ pub mod edit {
         LockTimeoutConfiguration(#[from] config::lock_timeout::Error),
         #[error(transparent)]
         ParseCommitterTime(#[from] crate::config::time::Error),
+        #[error(transparent)]
+        ReadOnly(#[from] crate::repository::read_only::Error),
+    }
+
+    impl Classify for Error {
+        fn kind(&self) -> Kind {
+            match self {
+                Error::FileTransactionPrepare(err) => err.kind(),
+                _ => Kind::Other,
+            }
+        }
+
+        fn is_retryable(&self) -> bool {
+            match self {
+                Error::FileTransactionPrepare(err) => err.is_retryable(),
+                _ => false,
+            }
+        }
     }
 }
 
@@ -86,6 +107,15 @@ pub mod find {
             #[error("The reference did not exist")]
             NotFound,
         }
+
+        impl crate::error::Classify for Error {
+            fn kind(&self) -> crate::error::Kind {
+                match self {
+                    Error::Find(_) => crate::error::Kind::Other,
+                    Error::NotFound => crate::error::Kind::NotFound,
+                }
+            }
+        }
     }
 
     /// The error returned by [`try_find_reference(…)`][crate::Repository::try_find_reference()].
@@ -4,6 +4,8 @@ use std::path::Path;
 use gix_macros::momo;
 use gix_ref::file::ReferenceExt;
 
+use crate::bstr::BStr;
+
 /// A platform to create iterators over references.
 #[must_use = "Iterators should be obtained from this iterator platform"]
 pub struct Platform<'r> {
@@ -11,17 +13,23 @@ pub struct Platform<'r> {
     pub(crate) repo: &'r crate::Repository,
 }
 
+type InnerIter<'r> =
+    dyn Iterator<Item = Result<gix_ref::Reference, gix_ref::file::iter::loose_then_packed::Error>> + 'r;
+
 /// An iterator over references, with or without filter.
 pub struct Iter<'r> {
-    inner: gix_ref::file::iter::LooseThenPacked<'r, 'r>,
+    inner: Box<InnerIter<'r>>,
     peel: bool,
     repo: &'r crate::Repository,
 }
 
 impl<'r> Iter<'r> {
-    fn new(repo: &'r crate::Repository, platform: gix_ref::file::iter::LooseThenPacked<'r, 'r>) -> Self {
+    fn new(
+        repo: &'r crate::Repository,
+        iter: impl Iterator<Item = Result<gix_ref::Reference, gix_ref::file::iter::loose_then_packed::Error>> + 'r,
+    ) -> Self {
         Iter {
-            inner: platform,
+            inner: Box::new(iter),
             peel: false,
             repo,
         }
@@ -47,6 +55,16 @@ impl<'r> Platform<'r> {
         Ok(Iter::new(self.repo, self.platform.prefixed(prefix.as_ref())?))
     }
 
+    /// Return an iterator over all references whose names match at least one of the `include` patterns, or all references if
+    /// there are none, but none of the `exclude` patterns.
+    ///
+    /// Patterns match like they do in `git for-each-ref`, so `refs/heads/feature/*` matches all branches directly in `feature/`,
+    /// and `refs/tags` matches all tags. Only the directory shared by the literal portions of all `include` patterns is traversed,
+    /// which makes this faster than filtering [all references](Self::all()).
+    pub fn matching(&self, include: &[&BStr], exclude: &[&BStr]) -> Result<Iter<'_>, init::Error> {
+        Ok(Iter::new(self.repo, self.platform.matching(include, exclude)?))
+    }
+
     // TODO: tests
     /// Return an iterator over all references that are tags.
     ///
@@ -110,7 +128,8 @@ impl<'r> Iterator for Iter<'r> {
 
 ///
 pub mod init {
-    /// The error returned by [`Platform::all()`][super::Platform::all()] or [`Platform::prefixed()`][super::Platform::prefixed()].
+    /// The error returned by [`Platform::all()`][super::Platform::all()], [`Platform::prefixed()`][super::Platform::prefixed()]
+    /// or [`Platform::matching()`][super::Platform::matching()].
     #[derive(Debug, thiserror::Error)]
     #[allow(missing_docs)]
     pub enum Error {
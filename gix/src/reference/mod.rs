@@ -15,6 +15,8 @@ use crate::ext::ObjectIdExt;
 
 pub mod log;
 
+pub mod watch;
+
 pub use gix_ref::{Category, Kind};
 
 /// Access
@@ -0,0 +1,324 @@
+//! Learn about references that change on disk, see [`Repository::watch_references()`](crate::Repository::watch_references()).
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+use gix_ref::FullName;
+
+/// Options for use in [`Repository::watch_references()`](crate::Repository::watch_references()).
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    /// The time to wait between checking references for changes when polling.
+    ///
+    /// It's only relevant if filesystem events aren't used.
+    pub poll_interval: Duration,
+    /// If `true`, and if the `watch-references` feature is enabled, use filesystem events to learn when to look for changes
+    /// instead of polling, falling back to polling if these events aren't available.
+    pub use_filesystem_events: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            poll_interval: Duration::from_secs(1),
+            use_filesystem_events: true,
+        }
+    }
+}
+
+/// A change to a reference as observed by a [`Watcher`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Change {
+    /// A loose reference was created.
+    Added(FullName),
+    /// The file of a loose reference was rewritten.
+    ///
+    /// Note that its value might still be the same.
+    Modified(FullName),
+    /// A loose reference was deleted, which may also happen if it was moved into the `packed-refs` file.
+    Removed(FullName),
+    /// The `packed-refs` file was created, changed or deleted, which may affect the value of any packed reference.
+    PackedRefs,
+}
+
+/// A utility to learn about changed references, created with [`Repository::watch_references()`](crate::Repository::watch_references()).
+///
+/// It compares the file metadata of all loose references and the `packed-refs` file with what it saw before. This is cheap enough
+/// to do repeatedly, but it's done only if filesystem events indicate a change if [these are used](Self::uses_filesystem_events()).
+///
+/// Top-level references in the `git` directory like `HEAD` or `FETCH_HEAD` are observed as well.
+pub struct Watcher {
+    git_dir: PathBuf,
+    common_dir: PathBuf,
+    snapshot: Snapshot,
+    options: Options,
+    #[cfg(feature = "watch-references")]
+    events: Option<events::Events>,
+}
+
+#[derive(Default, PartialEq)]
+struct Snapshot {
+    loose: BTreeMap<FullName, Stat>,
+    packed: Option<Stat>,
+}
+
+#[derive(PartialEq)]
+struct Stat {
+    modified: Option<SystemTime>,
+    len: u64,
+    #[cfg(unix)]
+    ino: u64,
+}
+
+impl Stat {
+    fn from_metadata(meta: &std::fs::Metadata) -> Self {
+        Stat {
+            modified: meta.modified().ok(),
+            len: meta.len(),
+            #[cfg(unix)]
+            ino: std::os::unix::fs::MetadataExt::ino(meta),
+        }
+    }
+}
+
+/// Lifecycle
+impl Watcher {
+    pub(crate) fn new(git_dir: &Path, common_dir: &Path, options: Options) -> std::io::Result<Self> {
+        let mut watcher = Watcher {
+            git_dir: git_dir.to_owned(),
+            common_dir: common_dir.to_owned(),
+            snapshot: Snapshot::default(),
+            options,
+            #[cfg(feature = "watch-references")]
+            events: None,
+        };
+        #[cfg(feature = "watch-references")]
+        if options.use_filesystem_events {
+            watcher.events = events::Events::new(git_dir, common_dir);
+        }
+        watcher.snapshot = watcher.scan()?;
+        Ok(watcher)
+    }
+}
+
+/// Access
+impl Watcher {
+    /// Return `true` if filesystem events are used to learn about changes, or `false` if polling is used.
+    pub fn uses_filesystem_events(&self) -> bool {
+        #[cfg(feature = "watch-references")]
+        {
+            self.events.is_some()
+        }
+        #[cfg(not(feature = "watch-references"))]
+        {
+            false
+        }
+    }
+}
+
+/// Observing changes
+impl Watcher {
+    /// Return all changes since the last call, or since this instance was created, without blocking.
+    ///
+    /// Note that each change is only reported once.
+    pub fn changes(&mut self) -> std::io::Result<Vec<Change>> {
+        let current = self.scan()?;
+        let previous = std::mem::replace(&mut self.snapshot, current);
+        let current = &self.snapshot;
+        if previous == *current {
+            return Ok(Vec::new());
+        }
+
+        let mut out = Vec::new();
+        for (name, stat) in &current.loose {
+            match previous.loose.get(name) {
+                None => out.push(Change::Added(name.clone())),
+                Some(previous_stat) if previous_stat != stat => out.push(Change::Modified(name.clone())),
+                Some(_) => {}
+            }
+        }
+        out.extend(
+            previous
+                .loose
+                .keys()
+                .filter(|name| !current.loose.contains_key(*name))
+                .map(|name| Change::Removed(name.clone())),
+        );
+        if previous.packed != current.packed {
+            out.push(Change::PackedRefs);
+        }
+        Ok(out)
+    }
+
+    /// Block until references change and return these changes, or return an empty list once `timeout` passed without a change.
+    /// Without `timeout`, block until a change was observed.
+    pub fn wait(&mut self, timeout: Option<Duration>) -> std::io::Result<Vec<Change>> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        loop {
+            let remaining = deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+            #[cfg(feature = "watch-references")]
+            if let Some(events) = self.events.as_ref() {
+                if !events.wait(remaining) {
+                    // the event source is gone, so we have to continue with polling.
+                    self.events = None;
+                }
+                let changes = self.changes()?;
+                if !changes.is_empty() || remaining.map_or(false, |remaining| remaining.is_zero()) {
+                    return Ok(changes);
+                }
+                continue;
+            }
+            std::thread::sleep(remaining.map_or(self.options.poll_interval, |remaining| {
+                remaining.min(self.options.poll_interval)
+            }));
+            let changes = self.changes()?;
+            if !changes.is_empty() || deadline.map_or(false, |deadline| Instant::now() >= deadline) {
+                return Ok(changes);
+            }
+        }
+    }
+}
+
+/// Utilities
+impl Watcher {
+    fn scan(&self) -> std::io::Result<Snapshot> {
+        let mut snapshot = Snapshot::default();
+        for entry in read_dir(&self.git_dir)? {
+            let entry = entry?;
+            let file_name = entry.file_name();
+            let is_pseudo_ref = file_name.to_str().map_or(false, |name| {
+                name.ends_with("HEAD") && name.bytes().all(|b| b.is_ascii_uppercase() || b == b'_')
+            });
+            if is_pseudo_ref {
+                record(&mut snapshot, &entry.path(), &self.git_dir)?;
+            }
+        }
+        scan_refs(&mut snapshot, &self.common_dir)?;
+        if self.git_dir != self.common_dir {
+            // worktree-private references like `refs/bisect`.
+            scan_refs(&mut snapshot, &self.git_dir)?;
+        }
+        snapshot.packed = metadata(&self.common_dir.join("packed-refs"))?.map(|meta| Stat::from_metadata(&meta));
+        Ok(snapshot)
+    }
+}
+
+fn scan_refs(snapshot: &mut Snapshot, base: &Path) -> std::io::Result<()> {
+    let mut dirs = vec![base.join("refs")];
+    while let Some(dir) = dirs.pop() {
+        for entry in read_dir(&dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            match entry.file_type() {
+                Ok(file_type) if file_type.is_dir() => dirs.push(path),
+                Ok(_) if path.extension().map_or(false, |ext| ext == "lock") => {}
+                Ok(_) => record(snapshot, &path, base)?,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Record the reference at `path` if it still exists and its path relative to `base` is a valid reference name.
+fn record(snapshot: &mut Snapshot, path: &Path, base: &Path) -> std::io::Result<()> {
+    let name = path
+        .strip_prefix(base)
+        .ok()
+        .and_then(|rela_path| gix_path::try_into_bstr(rela_path).ok())
+        .and_then(|name| FullName::try_from(gix_path::to_unix_separators_on_windows(name).into_owned()).ok());
+    if let (Some(name), Some(meta)) = (name, metadata(path)?) {
+        snapshot.loose.insert(name, Stat::from_metadata(&meta));
+    }
+    Ok(())
+}
+
+fn metadata(path: &Path) -> std::io::Result<Option<std::fs::Metadata>> {
+    match std::fs::metadata(path) {
+        Ok(meta) => Ok(meta.is_file().then_some(meta)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+fn read_dir(dir: &Path) -> std::io::Result<impl Iterator<Item = std::io::Result<std::fs::DirEntry>>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => Some(entries),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+        Err(err) => return Err(err),
+    };
+    Ok(entries
+        .into_iter()
+        .flatten()
+        .filter(|entry| !matches!(entry, Err(err) if err.kind() == std::io::ErrorKind::NotFound)))
+}
+
+#[cfg(feature = "watch-references")]
+mod events {
+    use std::{
+        path::{Path, PathBuf},
+        sync::mpsc,
+        time::Duration,
+    };
+
+    use notify::Watcher;
+
+    /// A source of notifications about changes in the directories that contain references.
+    pub(super) struct Events {
+        _watcher: notify::RecommendedWatcher,
+        rx: mpsc::Receiver<()>,
+    }
+
+    impl Events {
+        /// Return `None` if filesystem events aren't available.
+        pub(super) fn new(git_dir: &Path, common_dir: &Path) -> Option<Self> {
+            let refs_dirs: Vec<PathBuf> = std::iter::once(common_dir.join("refs"))
+                .chain((git_dir != common_dir).then(|| git_dir.join("refs")))
+                .collect();
+            let (tx, rx) = mpsc::channel();
+            let mut watcher = {
+                let refs_dirs = refs_dirs.clone();
+                notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                    let concerns_references = event.map_or(true, |event| {
+                        event.paths.iter().any(|path| {
+                            refs_dirs.iter().any(|dir| path.starts_with(dir))
+                                || path
+                                    .file_name()
+                                    .and_then(|name| name.to_str())
+                                    .map_or(false, |name| name == "packed-refs" || name.ends_with("HEAD"))
+                        })
+                    });
+                    if concerns_references {
+                        tx.send(()).ok();
+                    }
+                })
+                .ok()?
+            };
+            watcher.watch(git_dir, notify::RecursiveMode::NonRecursive).ok()?;
+            if git_dir != common_dir {
+                watcher.watch(common_dir, notify::RecursiveMode::NonRecursive).ok()?;
+            }
+            for dir in refs_dirs.iter().filter(|dir| dir.is_dir()) {
+                watcher.watch(dir, notify::RecursiveMode::Recursive).ok()?;
+            }
+            Some(Events { _watcher: watcher, rx })
+        }
+
+        /// Block until an event arrives or `timeout` passes, and consume all pending events.
+        /// Return `false` if no event can arrive anymore.
+        pub(super) fn wait(&self, timeout: Option<Duration>) -> bool {
+            let res = match timeout {
+                Some(timeout) => self.rx.recv_timeout(timeout),
+                None => self.rx.recv().map_err(|_| mpsc::RecvTimeoutError::Disconnected),
+            };
+            if let Err(mpsc::RecvTimeoutError::Disconnected) = res {
+                return false;
+            }
+            while self.rx.try_recv().is_ok() {}
+            true
+        }
+    }
+}
@@ -22,6 +22,17 @@ impl<'repo> Remote<'repo> {
         }
     }
 
+    /// Return the url used for the given `direction` as it was configured, before any rewrites from
+    /// `url.<base>.insteadOf|pushInsteadOf` were applied.
+    ///
+    /// Compare it with [`url()`](Self::url()) to learn if the url was rewritten, for example to display both.
+    pub fn url_as_configured(&self, direction: remote::Direction) -> Option<&gix_url::Url> {
+        match direction {
+            remote::Direction::Fetch => self.url.as_ref(),
+            remote::Direction::Push => self.push_url.as_ref().or(self.url.as_ref()),
+        }
+    }
+
     /// Return how we handle tags when fetching the remote.
     pub fn fetch_tags(&self) -> remote::fetch::Tags {
         self.fetch_tags
@@ -31,6 +42,8 @@ impl<'repo> Remote<'repo> {
     /// was created with one of the `_without_url_rewrite()` methods.
     /// For pushing, this is the `remote.<name>.pushUrl` or the `remote.<name>.url` used for fetching, and for fetching it's
     /// the `remote.<name>.url`.
+    /// Just like in `git`, `pushUrl` is only rewritten by `insteadOf`, while `pushInsteadOf` only applies to the `url`
+    /// if it's also used for pushing.
     /// Note that it's possible to only have the push url set, in which case there will be no way to fetch from the remote as
     /// the push-url isn't used for that.
     pub fn url(&self, direction: remote::Direction) -> Option<&gix_url::Url> {
@@ -60,7 +73,7 @@ impl Remote<'_> {
             Err(err) => err.into(),
         };
         let push_url_err =
-            match remote::init::rewrite_url(&self.repo.config, self.push_url.as_ref(), remote::Direction::Push) {
+            match remote::init::rewrite_push_url(&self.repo.config, self.url.as_ref(), self.push_url.as_ref()) {
                 Ok(url) => {
                     self.push_url_alias = url;
                     None
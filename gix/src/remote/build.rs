@@ -42,10 +42,10 @@ impl Remote<'_> {
     ) -> Result<Self, remote::init::Error> {
         self.push_url = push_url.into();
 
-        let (_, push_url_alias) = should_rewrite_urls
-            .then(|| remote::init::rewrite_urls(&self.repo.config, None, self.push_url.as_ref()))
-            .unwrap_or(Ok((None, None)))?;
-        self.push_url_alias = push_url_alias;
+        self.push_url_alias = should_rewrite_urls
+            .then(|| remote::init::rewrite_push_url(&self.repo.config, self.url.as_ref(), self.push_url.as_ref()))
+            .transpose()?
+            .flatten();
 
         Ok(self)
     }
@@ -6,7 +6,12 @@ use std::borrow::Cow;
 use crate::{remote::Connection, Remote};
 
 mod error {
-    use crate::{bstr::BString, config, remote};
+    use crate::{
+        bstr::BString,
+        config,
+        error::{Classify, Kind},
+        remote,
+    };
 
     /// The error returned by [connect()][crate::Remote::connect()].
     #[derive(Debug, thiserror::Error)]
@@ -44,6 +49,23 @@ mod error {
             }
         }
     }
+
+    impl Classify for Error {
+        fn kind(&self) -> Kind {
+            match self {
+                Error::Connect(err) => err.kind(),
+                Error::InvalidRemoteRepositoryPath { .. } | Error::FileUrl { .. } => Kind::NotFound,
+                _ => Kind::Other,
+            }
+        }
+
+        fn is_retryable(&self) -> bool {
+            match self {
+                Error::Connect(err) => err.is_retryable(),
+                _ => false,
+            }
+        }
+    }
 }
 pub use error::Error;
 
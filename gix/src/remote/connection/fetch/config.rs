@@ -1,6 +1,9 @@
 use super::Error;
 use crate::{
-    config::{cache::util::ApplyLeniency, tree::Pack},
+    config::{
+        cache::util::ApplyLeniency,
+        tree::{Fetch, Key, Pack, Transfer},
+    },
     Repository,
 };
 
@@ -24,3 +27,26 @@ pub fn pack_index_version(repo: &Repository) -> Result<gix_pack::index::Version,
         .with_leniency(repo.options.lenient_config)?
         .unwrap_or(gix_pack::index::Version::V2))
 }
+
+/// Return the amount of objects below which received packs are unpacked into loose objects, or `None` if packs should always be kept.
+pub fn unpack_limit(repo: &Repository) -> Result<Option<u64>, Error> {
+    for key in [&Fetch::UNPACK_LIMIT, &Transfer::UNPACK_LIMIT] {
+        let limit = repo
+            .config
+            .resolved
+            .integer_filter(
+                key.section().name(),
+                None,
+                key.name(),
+                &mut repo.filter_config_section(),
+            )
+            .map(|limit| key.try_into_u64(limit))
+            .transpose()
+            .with_leniency(repo.options.lenient_config)
+            .map_err(Error::UnpackLimit)?;
+        if limit.is_some() {
+            return Ok(limit);
+        }
+    }
+    Ok(None)
+}
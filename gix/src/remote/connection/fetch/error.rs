@@ -1,4 +1,9 @@
-use crate::config;
+use gix_protocol::transport::IsSpuriousError;
+
+use crate::{
+    config,
+    error::{Classify, Kind},
+};
 
 /// The error returned by [`receive()`](super::Prepare::receive()).
 #[derive(Debug, thiserror::Error)]
@@ -8,6 +13,14 @@ pub enum Error {
     PackThreads(#[from] config::unsigned_integer::Error),
     #[error("The value to configure the pack index version should be 1 or 2")]
     PackIndexVersion(#[from] config::key::GenericError),
+    #[error("The value to configure the unpack limit must be a positive number")]
+    UnpackLimit(#[source] config::unsigned_integer::Error),
+    #[error("Could not read the header of the received pack")]
+    ReadPackHeader(#[source] std::io::Error),
+    #[error(transparent)]
+    DecodePackHeader(#[from] gix_pack::data::header::decode::Error),
+    #[error(transparent)]
+    UnpackObjects(#[from] gix_pack::data::input::unpack::Error),
     #[error("Could not decode server reply")]
     FetchResponse(#[from] gix_protocol::fetch::response::Error),
     #[error("Cannot fetch from a remote that uses {remote} while local repository uses {local} for object hashes")]
@@ -21,6 +34,15 @@ pub enum Error {
     Client(#[from] gix_protocol::transport::client::Error),
     #[error(transparent)]
     WritePack(#[from] gix_pack::bundle::write::Error),
+    #[error("The received pack is incomplete")]
+    Connectivity(#[from] crate::connectivity::Error),
+    #[error("Could not open the index of the received pack to check its connectivity")]
+    OpenPackIndex(#[from] gix_pack::index::init::Error),
+    #[error("Failed to remove the incomplete pack at \"{}\"", path.display())]
+    RemoveIncompletePack {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
     #[error(transparent)]
     UpdateRefs(#[from] super::refs::update::Error),
     #[error("Failed to remove .keep file at \"{}\"", path.display())]
@@ -28,6 +50,11 @@ pub enum Error {
         path: std::path::PathBuf,
         source: std::io::Error,
     },
+    #[error("Failed to write .promisor file at \"{}\"", path.display())]
+    WritePromisorFile {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
     #[error(transparent)]
     ShallowOpen(#[from] crate::shallow::open::Error),
     #[error("Server lack feature {feature:?}: {description}")]
@@ -45,9 +72,23 @@ pub enum Error {
     RejectShallowRemote,
     #[error(transparent)]
     NegotiationAlgorithmConfig(#[from] config::key::GenericErrorWithValue),
+    #[error(transparent)]
+    ReadOnly(#[from] crate::repository::read_only::Error),
+    #[cfg(feature = "blocking-network-client")]
+    #[error(transparent)]
+    Submodules(#[from] crate::submodule::modules::Error),
+    #[cfg(feature = "blocking-network-client")]
+    #[error("Could not obtain configuration to learn if submodules should be fetched")]
+    SubmoduleRecurse(#[source] config::boolean::Error),
+    #[cfg(feature = "blocking-network-client")]
+    #[error("Failed to fetch submodule '{name}'")]
+    Submodule {
+        name: crate::bstr::BString,
+        source: Box<super::submodule::Error>,
+    },
 }
 
-impl gix_protocol::transport::IsSpuriousError for Error {
+impl IsSpuriousError for Error {
     fn is_spurious(&self) -> bool {
         match self {
             Error::FetchResponse(err) => err.is_spurious(),
@@ -56,3 +97,32 @@ impl gix_protocol::transport::IsSpuriousError for Error {
         }
     }
 }
+
+impl Classify for Error {
+    fn kind(&self) -> Kind {
+        match self {
+            Error::FetchResponse(err) => err.kind(),
+            Error::Client(err) => err.kind(),
+            Error::IncompatibleObjectHash { .. } | Error::MissingServerFeature { .. } => Kind::Network,
+            Error::Connectivity(crate::connectivity::Error::Interrupted) => Kind::Other,
+            Error::WritePack(_) | Error::DecodePackHeader(_) | Error::UnpackObjects(_) | Error::Connectivity(_) => {
+                Kind::CorruptObject
+            }
+            Error::UpdateRefs(err) => err.kind(),
+            #[cfg(feature = "blocking-network-client")]
+            Error::Submodule { source, .. } => match source.as_ref() {
+                super::submodule::Error::Fetch(err) => err.kind(),
+                _ => Kind::Other,
+            },
+            _ => Kind::Other,
+        }
+    }
+
+    fn is_retryable(&self) -> bool {
+        match self {
+            Error::LockShallowFile(err) => err.is_retryable(),
+            Error::UpdateRefs(err) => err.is_retryable(),
+            _ => self.is_spurious(),
+        }
+    }
+}
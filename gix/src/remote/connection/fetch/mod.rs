@@ -65,6 +65,16 @@ pub enum Status {
         /// Information collected while updating references.
         update_refs: refs::update::Outcome,
     },
+    /// There was at least one tip with a new object which we received, but as the pack had less objects than
+    /// configured with `fetch.unpackLimit` or `transfer.unpackLimit`, its objects were written as loose objects.
+    Unpacked {
+        /// Information about the pack negotiation phase.
+        negotiate: outcome::Negotiate,
+        /// Information about the objects that were written.
+        unpack: gix_pack::data::input::unpack::Outcome,
+        /// Information collected while updating references.
+        update_refs: refs::update::Outcome,
+    },
 }
 
 /// The outcome of receiving a pack via [`Prepare::receive()`].
@@ -74,6 +84,22 @@ pub struct Outcome {
     pub ref_map: RefMap,
     /// The status of the operation to indicate what happened.
     pub status: Status,
+    /// The outcome of fetching each submodule, in the order they were fetched, if [recursing into submodules](Prepare::with_recurse_submodules())
+    /// was enabled.
+    #[cfg(feature = "blocking-network-client")]
+    pub submodules: Vec<SubmoduleOutcome>,
+}
+
+/// The outcome of fetching a submodule as part of a fetch [recursing into submodules](Prepare::with_recurse_submodules()).
+#[cfg(feature = "blocking-network-client")]
+#[derive(Debug, Clone)]
+pub struct SubmoduleOutcome {
+    /// The name of the submodule.
+    pub name: BString,
+    /// The path of the submodule relative to the root of the worktree of the superproject.
+    pub rela_path: BString,
+    /// The outcome of fetching from the default remote of the submodule, which includes its own submodules.
+    pub outcome: Outcome,
 }
 
 /// Additional types related to the outcome of a fetch operation.
@@ -125,12 +151,15 @@ pub mod outcome {
 pub enum ProgressId {
     /// The progress name is defined by the remote and the progress messages it sets, along with their progress values and limits.
     RemoteProgress,
+    /// The amount of objects written as loose objects as the received pack had less objects than the unpack limit.
+    UnpackObjects,
 }
 
 impl From<ProgressId> for gix_features::progress::Id {
     fn from(v: ProgressId) -> Self {
         match v {
             ProgressId::RemoteProgress => *b"FERP",
+            ProgressId::UnpackObjects => *b"FEUO",
         }
     }
 }
@@ -139,6 +168,10 @@ pub(crate) mod negotiate;
 
 ///
 pub mod prepare {
+    use gix_protocol::transport::IsSpuriousError;
+
+    use crate::error::{Classify, Kind};
+
     /// The error returned by [`prepare_fetch()`][super::Connection::prepare_fetch()].
     #[derive(Debug, thiserror::Error)]
     #[allow(missing_docs)]
@@ -149,7 +182,7 @@ pub mod prepare {
         RefMap(#[from] crate::remote::ref_map::Error),
     }
 
-    impl gix_protocol::transport::IsSpuriousError for Error {
+    impl IsSpuriousError for Error {
         fn is_spurious(&self) -> bool {
             match self {
                 Error::RefMap(err) => err.is_spurious(),
@@ -157,6 +190,45 @@ pub mod prepare {
             }
         }
     }
+
+    impl Classify for Error {
+        fn kind(&self) -> Kind {
+            match self {
+                Error::MissingRefSpecs => Kind::Other,
+                Error::RefMap(err) => err.kind(),
+            }
+        }
+
+        fn is_retryable(&self) -> bool {
+            self.is_spurious()
+        }
+    }
+}
+
+///
+#[cfg(feature = "blocking-network-client")]
+pub mod submodule {
+    /// The error produced when fetching a submodule as part of a fetch [recursing into submodules](super::Prepare::with_recurse_submodules()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FetchRecurse(#[from] crate::submodule::fetch_recurse::Error),
+        #[error(transparent)]
+        Path(#[from] gix_submodule::config::path::Error),
+        #[error(transparent)]
+        Open(#[from] crate::submodule::open::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        FindRemote(#[from] crate::remote::find::existing::Error),
+        #[error(transparent)]
+        Connect(#[from] crate::remote::connect::Error),
+        #[error(transparent)]
+        PrepareFetch(#[from] super::prepare::Error),
+        #[error(transparent)]
+        Fetch(#[from] Box<super::Error>),
+    }
 }
 
 impl<'remote, 'repo, T> Connection<'remote, 'repo, T>
@@ -193,6 +265,10 @@ where
             reflog_message: None,
             write_packed_refs: WritePackedRefs::Never,
             shallow: Default::default(),
+            filter: None,
+            check_connectivity: true,
+            #[cfg(feature = "blocking-network-client")]
+            recurse_submodules: None,
         })
     }
 }
@@ -212,6 +288,8 @@ mod receive_pack;
 ///
 #[path = "update_refs/mod.rs"]
 pub mod refs;
+#[cfg(feature = "blocking-network-client")]
+mod submodules;
 
 /// A structure to hold the result of the handshake with the remote and configure the upcoming fetch operation.
 pub struct Prepare<'remote, 'repo, T>
@@ -224,6 +302,10 @@ where
     reflog_message: Option<RefLogMessage>,
     write_packed_refs: WritePackedRefs,
     shallow: remote::fetch::Shallow,
+    filter: Option<BString>,
+    check_connectivity: bool,
+    #[cfg(feature = "blocking-network-client")]
+    recurse_submodules: Option<gix_submodule::config::FetchRecurse>,
 }
 
 /// Builder
@@ -265,6 +347,45 @@ where
         self.shallow = shallow;
         self
     }
+
+    /// Ask the remote to omit objects from the pack according to the given filter `spec`, like `blob:none` or `tree:0`,
+    /// to obtain a partial clone.
+    ///
+    /// The received pack is marked as coming from a promisor remote, as is any pack received from a remote that is configured as such.
+    /// Note that objects that were filtered out won't be fetched on demand unless lazy fetching is enabled with
+    /// `Repository::enable_lazy_fetch()`, and that the remote must support filters.
+    pub fn with_filter(mut self, spec: Option<BString>) -> Self {
+        self.filter = spec;
+        self
+    }
+
+    /// If `enabled` (the default), assure that all objects reachable from the received tips are present after receiving a pack,
+    /// and refuse the pack if they are not, before any reference is updated.
+    ///
+    /// The check is skipped for partial clones as objects are omitted there on purpose.
+    pub fn with_connectivity_check(mut self, enabled: bool) -> Self {
+        self.check_connectivity = enabled;
+        self
+    }
+
+    /// Control if and how populated submodules are fetched from their default remote after the pack was received,
+    /// like `--recurse-submodules=<mode>`.
+    ///
+    /// If `None` (the default), each submodule is fetched as configured by `submodule.<name>.fetchRecurseSubmodules`,
+    /// falling back to `fetch.recurseSubmodules` and `submodule.recurse`.
+    /// With [`OnDemand`](gix_submodule::config::FetchRecurse::OnDemand), a submodule is only fetched if a commit it records
+    /// at the tips of the updated references is missing in it. Submodules of submodules are fetched in the same way.
+    ///
+    /// ### Deviation
+    ///
+    /// If none of the configuration is set, submodules aren't fetched, whereas `git` fetches them on demand.
+    /// Further, only the commits at the tips of updated references are checked for changed submodules,
+    /// instead of all newly received commits.
+    #[cfg(feature = "blocking-network-client")]
+    pub fn with_recurse_submodules(mut self, mode: Option<gix_submodule::config::FetchRecurse>) -> Self {
+        self.recurse_submodules = mode;
+        self
+    }
 }
 
 impl<'remote, 'repo, T> Drop for Prepare<'remote, 'repo, T>
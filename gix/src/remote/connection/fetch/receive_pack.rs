@@ -10,6 +10,7 @@ use gix_protocol::{
 };
 
 use crate::{
+    bstr::ByteSlice,
     config::{
         cache::util::ApplyLeniency,
         tree::{Clone, Fetch, Key},
@@ -53,6 +54,17 @@ where
     /// A known application for this behaviour is in `remote-helper` implementations which should send this path via `lock <path>` to stdout
     /// to inform git about the file that it will remove once it updated the refs accordingly.
     ///
+    /// ### Unpack limit
+    ///
+    /// If `fetch.unpackLimit`, or `transfer.unpackLimit` if it's unset, is set and the received pack has less objects than that,
+    /// its objects are written as loose objects and [`Status::Unpacked`] is returned. Unlike `git`, packs are always kept
+    /// if neither value is set.
+    ///
+    /// ### Connectivity
+    ///
+    /// Before updating refs, all objects reachable from the tips we asked for are checked for presence, and the received pack
+    /// is removed if it's incomplete. See [`with_connectivity_check()`](Self::with_connectivity_check()) to turn it off.
+    ///
     /// ### Deviation
     ///
     /// When **updating refs**, the `git-fetch` docs state that the following:
@@ -96,6 +108,9 @@ where
 
         let fetch = gix_protocol::Command::Fetch;
         let repo = con.remote.repo;
+        if matches!(self.dry_run, fetch::DryRun::No) {
+            repo.assure_writable("receive a pack")?;
+        }
         let fetch_features = {
             let mut f = fetch.default_features(protocol_version, &handshake.capabilities);
             f.push(repo.config.user_agent_tuple());
@@ -117,6 +132,15 @@ where
             arguments.use_include_tag();
         }
         let (shallow_commits, mut shallow_lock) = add_shallow_args(&mut arguments, &self.shallow, repo)?;
+        if let Some(spec) = self.filter.as_ref() {
+            if !arguments.can_use_filter() {
+                return Err(Error::MissingServerFeature {
+                    feature: "filter",
+                    description: "partial clones need the server to omit filtered objects from the pack",
+                });
+            }
+            arguments.filter(spec.to_str_lossy().as_ref());
+        }
 
         if self.ref_map.object_hash != repo.object_hash() {
             return Err(Error::IncompatibleObjectHash {
@@ -156,7 +180,7 @@ where
             negotiate::make_refmapping_ignore_predicate(con.remote.fetch_tags, &self.ref_map),
         )?;
         let mut previous_response = None::<gix_protocol::fetch::Response>;
-        let (mut write_pack_bundle, negotiate) = match &action {
+        let (mut received, negotiate) = match &action {
             negotiate::Action::NoChange | negotiate::Action::SkipToRefUpdate => {
                 gix_protocol::indicate_end_of_interaction(&mut con.transport, con.trace)
                     .await
@@ -265,20 +289,27 @@ where
                     object_hash: con.remote.repo.object_hash(),
                 };
 
-                let write_pack_bundle = if matches!(self.dry_run, fetch::DryRun::No) {
+                let from_promisor = self.filter.is_some()
+                    || con
+                        .remote
+                        .name()
+                        .map_or(false, |name| repo.is_promisor_remote(name.as_bstr()));
+                let received = if matches!(self.dry_run, fetch::DryRun::No) {
                     #[cfg(not(feature = "async-network-client"))]
                     let mut rd = reader;
                     #[cfg(feature = "async-network-client")]
                     let mut rd = gix_protocol::futures_lite::io::BlockOn::new(reader);
-                    let res = gix_pack::Bundle::write_to_directory(
+                    let res = write_or_unpack(
+                        repo,
                         &mut rd,
-                        Some(&repo.objects.store_ref().path().join("pack")),
+                        // Packs from promisor remotes are always kept to be able to mark them as such.
+                        if from_promisor {
+                            None
+                        } else {
+                            config::unpack_limit(repo)?
+                        },
                         progress,
                         should_interrupt,
-                        Some(Box::new({
-                            let repo = repo.clone();
-                            repo.objects
-                        })),
                         options,
                     )?;
                     #[cfg(feature = "async-network-client")]
@@ -306,7 +337,31 @@ where
                         crate::shallow::write(shallow_lock, shallow_commits, previous_response.shallow_updates())?;
                     }
                 }
-                (write_pack_bundle, Some(outcome::Negotiate { graph, rounds }))
+
+                if let Some(received) = received.as_ref() {
+                    if self.check_connectivity && self.filter.is_none() {
+                        let is_ignored =
+                            negotiate::make_refmapping_ignore_predicate(con.remote.fetch_tags, &self.ref_map);
+                        let tips = self
+                            .ref_map
+                            .mappings
+                            .iter()
+                            .zip(remote_ref_target_known)
+                            .filter(|(m, known)| !**known && !is_ignored(m))
+                            .filter_map(|(m, _)| m.remote.as_id().map(ToOwned::to_owned));
+                        check_connectivity(repo, received, tips, should_interrupt)?;
+                    }
+                    if from_promisor {
+                        if let Received::Pack(bundle) = received {
+                            if let Some(data_path) = bundle.data_path.as_ref() {
+                                let path = data_path.with_extension("promisor");
+                                std::fs::write(&path, promisor_file_content(&self.ref_map.mappings))
+                                    .map_err(|source| Error::WritePromisorFile { path, source })?;
+                            }
+                        }
+                    }
+                }
+                (received, Some(outcome::Negotiate { graph, rounds }))
             }
         };
 
@@ -323,7 +378,7 @@ where
             self.write_packed_refs,
         )?;
 
-        if let Some(bundle) = write_pack_bundle.as_mut() {
+        if let Some(Received::Pack(bundle)) = received.as_mut() {
             if !update_refs.edits.is_empty() || bundle.index.num_objects == 0 {
                 if let Some(path) = bundle.keep_path.take() {
                     std::fs::remove_file(&path).map_err(|err| Error::RemovePackKeepFile { path, source: err })?;
@@ -331,25 +386,158 @@ where
             }
         }
 
+        #[cfg(feature = "blocking-network-client")]
+        let submodules = match self.dry_run {
+            fetch::DryRun::No => super::submodules::fetch(
+                repo,
+                self.recurse_submodules,
+                &update_refs.edits,
+                progress,
+                should_interrupt,
+            )?,
+            fetch::DryRun::Yes => Vec::new(),
+        };
+
         let out = Outcome {
             ref_map: std::mem::take(&mut self.ref_map),
-            status: match write_pack_bundle {
-                Some(write_pack_bundle) => Status::Change {
+            status: match received {
+                Some(Received::Pack(write_pack_bundle)) => Status::Change {
                     write_pack_bundle,
                     update_refs,
                     negotiate: negotiate.expect("if we have a pack, we always negotiated it"),
                 },
+                Some(Received::Unpacked(unpack)) => Status::Unpacked {
+                    unpack,
+                    update_refs,
+                    negotiate: negotiate.expect("if we have a pack, we always negotiated it"),
+                },
                 None => Status::NoPackReceived {
                     dry_run: matches!(self.dry_run, fetch::DryRun::Yes),
                     negotiate,
                     update_refs,
                 },
             },
+            #[cfg(feature = "blocking-network-client")]
+            submodules,
         };
         Ok(out)
     }
 }
 
+/// List the tips we asked for, one per line along with the name of the remote reference if there is one, just like git.
+fn promisor_file_content(mappings: &[fetch::Mapping]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for mapping in mappings {
+        if let Some(id) = mapping.remote.as_id() {
+            out.extend_from_slice(id.to_hex().to_string().as_bytes());
+            if let Some(name) = mapping.remote.as_name() {
+                out.push(b' ');
+                out.extend_from_slice(name);
+            }
+            out.push(b'\n');
+        }
+    }
+    out
+}
+
+/// The objects received from the remote.
+enum Received {
+    /// The pack was written along with its index.
+    Pack(gix_pack::bundle::write::Outcome),
+    /// The pack had less objects than the unpack limit, so each object was written as loose object.
+    Unpacked(gix_pack::data::input::unpack::Outcome),
+}
+
+/// Write the pack in `pack` along with its index, or write all of its objects as loose objects if it has less objects
+/// than `unpack_limit`.
+fn write_or_unpack(
+    repo: &Repository,
+    pack: &mut dyn std::io::BufRead,
+    unpack_limit: Option<u64>,
+    progress: &mut dyn crate::DynNestedProgress,
+    should_interrupt: &AtomicBool,
+    options: gix_pack::bundle::write::Options,
+) -> Result<Received, Error> {
+    let unpack_options = gix_pack::data::input::unpack::Options {
+        iteration_mode: options.iteration_mode,
+        object_hash: options.object_hash,
+    };
+    let write_pack = |pack: &mut dyn std::io::BufRead, progress: &mut dyn crate::DynNestedProgress| {
+        gix_pack::Bundle::write_to_directory(
+            pack,
+            Some(&repo.objects.store_ref().path().join("pack")),
+            progress,
+            should_interrupt,
+            Some(Box::new({
+                let repo = repo.clone();
+                repo.objects
+            })),
+            options,
+        )
+        .map(Received::Pack)
+        .map_err(Error::from)
+    };
+    let unpack_limit = match unpack_limit {
+        Some(limit) => limit,
+        None => return write_pack(pack, progress),
+    };
+
+    let mut header = [0u8; 12];
+    std::io::Read::read_exact(pack, &mut header).map_err(Error::ReadPackHeader)?;
+    let (_version, num_objects) = gix_pack::data::header::decode(&header)?;
+    let mut pack = std::io::BufReader::new(std::io::Read::chain(&header[..], pack));
+    if u64::from(num_objects) >= unpack_limit {
+        return write_pack(&mut pack, progress);
+    }
+    let mut unpack_progress = progress.add_child_with_id("unpacking objects".into(), ProgressId::UnpackObjects.into());
+    let outcome = gix_pack::data::input::unpack(
+        pack,
+        &repo.objects,
+        &mut |kind, data| gix_odb::Write::write_buf(&repo.objects, kind, data),
+        &mut unpack_progress,
+        should_interrupt,
+        unpack_options,
+    )?;
+    Ok(Received::Unpacked(outcome))
+}
+
+/// Assure everything reachable from `tips` is present now that objects were `received`, or remove the pack
+/// so it can't be used.
+fn check_connectivity(
+    repo: &Repository,
+    received: &Received,
+    tips: impl Iterator<Item = gix_hash::ObjectId>,
+    should_interrupt: &AtomicBool,
+) -> Result<(), Error> {
+    let bundle = match received {
+        Received::Pack(bundle) => bundle,
+        // Loose objects are removed once they expire if they aren't reachable.
+        Received::Unpacked(_) => {
+            repo.check_connectivity(tips, None, should_interrupt)?;
+            return Ok(());
+        }
+    };
+    let index_path = match bundle.index_path.as_ref() {
+        Some(path) => path,
+        None => return Ok(()),
+    };
+    let index = gix_pack::index::File::at(index_path, bundle.object_hash)?;
+    if let Err(err) = repo.check_connectivity(tips, Some(&index), should_interrupt) {
+        drop(index);
+        for path in [bundle.data_path.as_ref(), Some(index_path), bundle.keep_path.as_ref()]
+            .into_iter()
+            .flatten()
+        {
+            std::fs::remove_file(path).map_err(|source| Error::RemoveIncompletePack {
+                path: path.to_owned(),
+                source,
+            })?;
+        }
+        return Err(err.into());
+    }
+    Ok(())
+}
+
 fn acquire_shallow_lock(repo: &Repository) -> Result<gix_lock::File, Error> {
     gix_lock::File::acquire_to_update_resource(repo.shallow_file(), gix_lock::acquire::Fail::Immediately, None)
         .map_err(Into::into)
@@ -0,0 +1,126 @@
+use std::sync::atomic::AtomicBool;
+
+use gix_submodule::config::FetchRecurse;
+
+use crate::{
+    remote,
+    remote::fetch::{submodule, Error, SubmoduleOutcome},
+    Repository, Submodule,
+};
+
+/// Fetch the populated submodules of `repo` from their default remote according to `mode`, or according to their configuration if
+/// `mode` is `None`. `edits` are the reference edits of the superproject, whose new tips are checked for missing submodule commits
+/// when fetching on demand.
+pub(super) fn fetch(
+    repo: &Repository,
+    mode: Option<FetchRecurse>,
+    edits: &[gix_ref::transaction::RefEdit],
+    progress: &mut dyn crate::DynNestedProgress,
+    should_interrupt: &AtomicBool,
+) -> Result<Vec<SubmoduleOutcome>, Error> {
+    // Only repositories with a checkout, unlike those that are still being cloned, can have populated submodules.
+    let has_checkout = repo.work_dir().is_some() && repo.head().map_or(false, |head| !head.is_unborn());
+    if mode == Some(FetchRecurse::Never) || !has_checkout {
+        return Ok(Vec::new());
+    }
+    let submodules = match repo.submodules()? {
+        Some(submodules) => submodules,
+        None => return Ok(Vec::new()),
+    };
+    let recurse = repo.submodule_recurse().map_err(Error::SubmoduleRecurse)?;
+    let tips: Vec<_> = edits
+        .iter()
+        .filter_map(|edit| match &edit.change {
+            gix_ref::transaction::Change::Update {
+                new: gix_ref::Target::Peeled(id),
+                ..
+            } => Some(*id),
+            _ => None,
+        })
+        .collect();
+
+    let mut out = Vec::new();
+    for sm in submodules {
+        let outcome =
+            fetch_one(repo, &sm, mode, recurse, &tips, progress, should_interrupt).map_err(|err| Error::Submodule {
+                name: sm.name().to_owned(),
+                source: err.into(),
+            })?;
+        out.extend(outcome);
+    }
+    Ok(out)
+}
+
+/// Fetch `sm` if it's populated and `mode`, or its configuration, indicates it should be fetched.
+fn fetch_one(
+    repo: &Repository,
+    sm: &Submodule<'_>,
+    mode: Option<FetchRecurse>,
+    recurse: Option<bool>,
+    tips: &[gix_hash::ObjectId],
+    progress: &mut dyn crate::DynNestedProgress,
+    should_interrupt: &AtomicBool,
+) -> Result<Option<SubmoduleOutcome>, submodule::Error> {
+    let effective_mode = match mode {
+        Some(mode) => mode,
+        None => match sm.fetch_recurse()? {
+            Some(mode) => mode,
+            None if recurse == Some(true) => FetchRecurse::Always,
+            None => FetchRecurse::Never,
+        },
+    };
+    if effective_mode == FetchRecurse::Never {
+        return Ok(None);
+    }
+    let sm_repo = match sm.open()? {
+        Some(sm_repo) => sm_repo,
+        None => return Ok(None),
+    };
+    let rela_path = sm.path()?.into_owned();
+    if effective_mode == FetchRecurse::OnDemand
+        && !tips_reference_missing_commit(repo, &sm_repo, rela_path.as_ref(), tips)?
+    {
+        return Ok(None);
+    }
+    let remote = match sm_repo.find_default_remote(remote::Direction::Fetch) {
+        Some(remote) => remote?,
+        None => return Ok(None),
+    };
+
+    let mut progress = progress.add_child(format!("submodule '{}'", sm.name()));
+    let outcome = remote
+        .connect(remote::Direction::Fetch)?
+        .prepare_fetch(&mut progress, Default::default())?
+        .with_recurse_submodules(mode)
+        .receive_inner(&mut progress, should_interrupt)
+        .map_err(Box::new)?;
+    Ok(Some(SubmoduleOutcome {
+        name: sm.name().to_owned(),
+        rela_path,
+        outcome,
+    }))
+}
+
+/// Return `true` if the tree of any of the commits in `tips` records a commit for the submodule at `rela_path`
+/// which isn't present in `sm_repo`.
+fn tips_reference_missing_commit(
+    repo: &Repository,
+    sm_repo: &Repository,
+    rela_path: &crate::bstr::BStr,
+    tips: &[gix_hash::ObjectId],
+) -> Result<bool, submodule::Error> {
+    for tip in tips {
+        // Tips may also be tags pointing to blobs, which can't record submodules.
+        let mut tree = match repo.find_object(*tip)?.peel_to_tree() {
+            Ok(tree) => tree,
+            Err(_) => continue,
+        };
+        let entry = tree.peel_to_entry_by_path(gix_path::from_bstr(rela_path))?;
+        if let Some(entry) = entry.filter(|entry| entry.mode() == gix_object::tree::EntryMode::Commit) {
+            if !sm_repo.has_object(entry.oid()) {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
@@ -24,6 +24,23 @@ mod error {
         #[error("Failed to follow a symbolic reference to assure worktree isn't affected")]
         FollowSymref(#[from] gix_ref::file::find::existing::Error),
     }
+
+    impl crate::error::Classify for Error {
+        fn kind(&self) -> crate::error::Kind {
+            match self {
+                Error::EditReferences(err) => err.kind(),
+                Error::FindCommit(err) => err.kind(),
+                _ => crate::error::Kind::Other,
+            }
+        }
+
+        fn is_retryable(&self) -> bool {
+            match self {
+                Error::EditReferences(err) => err.is_retryable(),
+                _ => false,
+            }
+        }
+    }
 }
 
 pub use error::Error;
@@ -113,6 +130,23 @@ impl std::fmt::Display for Mode {
     }
 }
 
+impl Mode {
+    /// Return the class of failure if this update was rejected, or `None` if it was performed or wasn't needed.
+    pub fn rejection_kind(&self) -> Option<crate::error::Kind> {
+        use crate::error::Kind;
+        Some(match self {
+            Mode::NoChangeNeeded | Mode::FastForward | Mode::Forced | Mode::New | Mode::ImplicitTagNotSentByRemote => {
+                return None
+            }
+            Mode::RejectedSourceObjectNotFound { .. } => Kind::NotFound,
+            Mode::RejectedNonFastForward => Kind::NonFastForward,
+            Mode::RejectedTagUpdate | Mode::RejectedToReplaceWithUnborn | Mode::RejectedCurrentlyCheckedOut { .. } => {
+                Kind::WouldOverwrite
+            }
+        })
+    }
+}
+
 /// Indicates that a ref changes its type.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Hash)]
 pub enum TypeChange {
@@ -1,11 +1,12 @@
 use std::collections::HashSet;
 
 use gix_features::progress::Progress;
-use gix_protocol::transport::client::Transport;
+use gix_protocol::transport::{client::Transport, IsSpuriousError};
 
 use crate::{
     bstr,
     bstr::{BString, ByteVec},
+    error::{Classify, Kind},
     remote::{connection::HandshakeWithRefs, fetch, fetch::SpecIndex, Connection, Direction},
 };
 
@@ -34,7 +35,7 @@ pub enum Error {
     MappingValidation(#[from] gix_refspec::match_group::validate::Error),
 }
 
-impl gix_protocol::transport::IsSpuriousError for Error {
+impl IsSpuriousError for Error {
     fn is_spurious(&self) -> bool {
         match self {
             Error::Transport(err) => err.is_spurious(),
@@ -45,6 +46,25 @@ impl gix_protocol::transport::IsSpuriousError for Error {
     }
 }
 
+impl Classify for Error {
+    fn kind(&self) -> Kind {
+        match self {
+            Error::Handshake(err) => err.kind(),
+            Error::ListRefs(err) => err.kind(),
+            Error::Transport(err) => err.kind(),
+            Error::ConfigureCredentials(_) => Kind::Authentication,
+            Error::UnknownObjectFormat { .. } => Kind::Network,
+            Error::GatherTransportConfig { .. } | Error::ConfigureTransport(_) | Error::MappingValidation(_) => {
+                Kind::Other
+            }
+        }
+    }
+
+    fn is_retryable(&self) -> bool {
+        self.is_spurious()
+    }
+}
+
 /// For use in [`Connection::ref_map()`].
 #[derive(Debug, Clone)]
 pub struct Options {
@@ -266,6 +286,7 @@ fn extract_object_format(
             })?;
             match object_format {
                 "sha1" => gix_hash::Kind::Sha1,
+                "sha256" => gix_hash::Kind::Sha256,
                 unknown => return Err(Error::UnknownObjectFormat { format: unknown.into() }),
             }
         } else {
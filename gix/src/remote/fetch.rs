@@ -15,6 +15,8 @@ pub mod negotiate {
 pub use super::connection::fetch::{
     outcome, prepare, refs, Error, Outcome, Prepare, ProgressId, RefLogMessage, Status,
 };
+#[cfg(feature = "blocking-network-client")]
+pub use super::connection::fetch::{submodule, SubmoduleOutcome};
 
 /// If `Yes`, don't really make changes but do as much as possible to get an idea of what would be done.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -42,9 +42,11 @@ impl<'repo> Remote<'repo> {
             url.is_some() || push_url.is_some(),
             "BUG: fetch or push url must be set at least"
         );
-        let (url_alias, push_url_alias) = should_rewrite_urls
-            .then(|| rewrite_urls(&repo.config, url.as_ref(), push_url.as_ref()))
-            .unwrap_or(Ok((None, None)))?;
+        let (url_alias, push_url_alias) = if should_rewrite_urls {
+            rewrite_urls(&repo.config, url.as_ref(), push_url.as_ref())?
+        } else {
+            (None, None)
+        };
         Ok(Remote {
             name: name_or_url.map(Into::into),
             url,
@@ -79,15 +81,17 @@ impl<'repo> Remote<'repo> {
         should_rewrite_urls: bool,
         repo: &'repo Repository,
     ) -> Result<Self, Error> {
-        let (url_alias, _) = should_rewrite_urls
-            .then(|| rewrite_urls(&repo.config, Some(&url), None))
-            .unwrap_or(Ok((None, None)))?;
+        let (url_alias, push_url_alias) = if should_rewrite_urls {
+            rewrite_urls(&repo.config, Some(&url), None)?
+        } else {
+            (None, None)
+        };
         Ok(Remote {
             name: None,
             url: Some(url),
             url_alias,
             push_url: None,
-            push_url_alias: None,
+            push_url_alias,
             fetch_specs: Vec::new(),
             push_specs: Vec::new(),
             fetch_tags: Default::default(),
@@ -96,12 +100,42 @@ impl<'repo> Remote<'repo> {
     }
 }
 
+/// Rewrite `url` with the `url.<base>.insteadOf` rules, or with the `url.<base>.pushInsteadOf` rules if `direction` is `Push`.
 pub(crate) fn rewrite_url(
     config: &config::Cache,
     url: Option<&gix_url::Url>,
     direction: remote::Direction,
 ) -> Result<Option<gix_url::Url>, Error> {
-    url.and_then(|url| config.url_rewrite().longest(url, direction))
+    rewrite_url_inner(config, url, direction, direction)
+}
+
+/// Rewrite the url to use for pushing, which is `push_url` if set, or `url` otherwise.
+///
+/// Just like `git`, an explicit `push_url` is only subject to `url.<base>.insteadOf` rules, whereas the `url.<base>.pushInsteadOf`
+/// rules apply to `url` only. If none of these match, `None` is returned as the (rewritten) `url` is used for pushing.
+pub(crate) fn rewrite_push_url(
+    config: &config::Cache,
+    url: Option<&gix_url::Url>,
+    push_url: Option<&gix_url::Url>,
+) -> Result<Option<gix_url::Url>, Error> {
+    match push_url {
+        Some(push_url) => rewrite_url_inner(
+            config,
+            Some(push_url),
+            remote::Direction::Fetch,
+            remote::Direction::Push,
+        ),
+        None => rewrite_url(config, url, remote::Direction::Push),
+    }
+}
+
+fn rewrite_url_inner(
+    config: &config::Cache,
+    url: Option<&gix_url::Url>,
+    rules: remote::Direction,
+    direction: remote::Direction,
+) -> Result<Option<gix_url::Url>, Error> {
+    url.and_then(|url| config.url_rewrite().longest(url, rules))
         .map(|url| {
             gix_url::parse(url.as_ref()).map_err(|err| Error::RewrittenUrlInvalid {
                 kind: match direction {
@@ -121,7 +155,7 @@ pub(crate) fn rewrite_urls(
     push_url: Option<&gix_url::Url>,
 ) -> Result<(Option<gix_url::Url>, Option<gix_url::Url>), Error> {
     let url_alias = rewrite_url(config, url, remote::Direction::Fetch)?;
-    let push_url_alias = rewrite_url(config, push_url, remote::Direction::Push)?;
+    let push_url_alias = rewrite_push_url(config, url, push_url)?;
 
     Ok((url_alias, push_url_alias))
 }
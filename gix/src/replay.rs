@@ -0,0 +1,53 @@
+//! Replay commits onto a new base without a worktree, similar to `git replay`.
+//!
+//! Use [`Repository::replay()`](crate::Repository::replay()) to rebase a range of commits entirely within the object database,
+//! for instance to implement a server-side "rebase and merge".
+use crate::bstr::BString;
+
+/// The outcome of [`Repository::replay()`](crate::Repository::replay()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// All commits were replayed.
+    Replayed {
+        /// The replayed commit that corresponds to the tip of the range, or the new base if nothing had to be replayed.
+        tip: gix_hash::ObjectId,
+        /// The original commits in the order they were replayed, along with the commit they were replayed as.
+        ///
+        /// Commits whose changes are already present in the new base are dropped, and map to the commit they were dropped onto.
+        commits: Vec<(gix_hash::ObjectId, gix_hash::ObjectId)>,
+    },
+    /// A commit couldn't be replayed as its changes conflict with the new base. Nothing is replayed in this case,
+    /// even though objects for the commits before it might already have been written.
+    Conflicts {
+        /// The original commit that couldn't be replayed.
+        commit: gix_hash::ObjectId,
+        /// The paths that were changed on both sides.
+        paths: Vec<BString>,
+    },
+}
+
+/// The error returned by [`Repository::replay()`](crate::Repository::replay()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Committer identity is not configured")]
+    CommitterMissing,
+    #[error(transparent)]
+    ParseTime(#[from] crate::config::time::Error),
+    #[error("Commit {id} has more than one parent, and merge commits can't be replayed")]
+    MergeCommit { id: gix_hash::ObjectId },
+    #[error(transparent)]
+    Walk(#[from] crate::revision::walk::Error),
+    #[error(transparent)]
+    Traverse(#[from] gix_traverse::commit::ancestors::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    PeelToCommit(#[from] crate::object::peel::to_kind::Error),
+    #[error(transparent)]
+    ObjectKind(#[from] crate::object::try_into::Error),
+    #[error(transparent)]
+    Decode(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    WriteObject(#[from] crate::object::write::Error),
+}
@@ -27,4 +27,58 @@ impl crate::Repository {
             self.object_cache_size(bytes)
         }
     }
+
+    /// Sets the amount of space used at most for caching decoded delta-base objects when reading from packs to `Some(bytes)`,
+    /// or `None` to deactivate it entirely.
+    ///
+    /// By default, it is configured with `core.deltaBaseCacheLimit`, which may be overridden with the `GITOXIDE_PACK_CACHE_MEMORY`
+    /// environment variable, and set to a fixed-size cache otherwise.
+    #[cfg(feature = "pack-cache-lru-dynamic")]
+    pub fn pack_cache_size(&mut self, bytes: impl Into<Option<usize>>) {
+        let bytes = bytes.into();
+        match bytes {
+            Some(0) | None => self.objects.unset_pack_cache(),
+            Some(bytes) => self.objects.set_pack_cache(move || -> Box<gix_odb::cache::PackCache> {
+                Box::new(gix_pack::cache::lru::MemoryCappedHashmap::new(bytes))
+            }),
+        }
+    }
+
+    /// Like [`object_cache_size()`](Self::object_cache_size()), but makes the object cache shared between this instance and
+    /// all of its clones created afterwards, instead of giving each clone its own cache.
+    ///
+    /// This is useful if multiple handles are working on the same objects, possibly on different threads,
+    /// as any of them benefits from objects that were already decoded by any other.
+    pub fn shared_object_cache_size(&mut self, bytes: impl Into<Option<usize>>) {
+        let bytes = bytes.into();
+        match bytes {
+            Some(0) | None => self.objects.unset_object_cache(),
+            Some(bytes) => {
+                let cache = gix_odb::cache::Shared::new(crate::object::cache::MemoryCappedHashmap::new(bytes));
+                self.objects.set_object_cache(move || Box::new(cache.clone()))
+            }
+        }
+    }
+
+    /// Like [`pack_cache_size()`](Self::pack_cache_size()), but makes the pack cache shared between this instance and
+    /// all of its clones created afterwards, instead of giving each clone its own cache.
+    #[cfg(feature = "pack-cache-lru-dynamic")]
+    pub fn shared_pack_cache_size(&mut self, bytes: impl Into<Option<usize>>) {
+        let bytes = bytes.into();
+        match bytes {
+            Some(0) | None => self.objects.unset_pack_cache(),
+            Some(bytes) => {
+                let cache = gix_odb::cache::Shared::new(gix_pack::cache::lru::MemoryCappedHashmap::new(bytes));
+                self.objects.set_pack_cache(move || Box::new(cache.clone()))
+            }
+        }
+    }
+
+    /// Configure the pack and object caches of this instance according to `core.deltaBaseCacheLimit`, `gitoxide.objects.cacheLimit`
+    /// and their environment overrides, just like it's done when opening a repository.
+    ///
+    /// Use this to undo changes done with any of the other cache-configuration methods.
+    pub fn reset_caches_from_configuration(&mut self) {
+        crate::repository::init::setup_objects(&mut self.objects, &self.config);
+    }
 }
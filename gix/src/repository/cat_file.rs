@@ -0,0 +1,11 @@
+use crate::cat_file;
+
+impl crate::Repository {
+    /// Obtain a platform to look up many objects by id or revision one after another, similar to `git cat-file --batch`.
+    pub fn cat_file(&self) -> cat_file::Platform<'_> {
+        cat_file::Platform {
+            repo: self,
+            buf: Vec::new(),
+        }
+    }
+}
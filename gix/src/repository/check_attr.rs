@@ -0,0 +1,29 @@
+use gix_worktree::stack::state::attributes;
+
+use crate::check_attr;
+
+impl crate::Repository {
+    /// Obtain a platform to look up the attributes of paths, similar to `git check-attr`, with `.gitattributes` files
+    /// read from `source`.
+    ///
+    /// Attributes are also read from `$GIT_DIR/info/attributes` and `core.attributesFile`, see [`check_attr::Platform`]
+    /// for details on their precedence.
+    pub fn check_attr(&self, source: check_attr::Source) -> Result<check_attr::Platform<'_>, check_attr::Error> {
+        let stack = match source {
+            check_attr::Source::WorktreeThenIndex => {
+                let index = self.index_or_empty()?;
+                self.attributes_only(&index, attributes::Source::WorktreeThenIdMapping)?
+            }
+            check_attr::Source::Index => {
+                let index = self.index_or_empty()?;
+                self.attributes_only(&index, attributes::Source::IdMapping)?
+            }
+            check_attr::Source::Tree(tree) => {
+                let index = self.index_from_tree(&tree)?;
+                self.attributes_only(&index, attributes::Source::IdMapping)?
+            }
+        };
+        let all = stack.attribute_matches();
+        Ok(check_attr::Platform { stack, all })
+    }
+}
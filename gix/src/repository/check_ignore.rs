@@ -0,0 +1,18 @@
+use crate::check_ignore;
+
+impl crate::Repository {
+    /// Obtain a platform to learn whether paths are ignored and which pattern caused it, similar to `git check-ignore`.
+    ///
+    /// Patterns are read from `.gitignore` files in the worktree or the index, `$GIT_DIR/info/exclude` and `core.excludesFile`,
+    /// see [`check_ignore::Platform`] for details on their precedence.
+    /// Note that no worktree is required, but without one `.gitignore` files can only be read from the index.
+    pub fn check_ignore(&self) -> Result<check_ignore::Platform<'_>, check_ignore::Error> {
+        let index = self.index_or_empty()?;
+        let stack = self.excludes(
+            &index,
+            None,
+            gix_worktree::stack::state::ignore::Source::WorktreeThenIdMappingIfNotSkipped,
+        )?;
+        Ok(check_ignore::Platform { stack, index })
+    }
+}
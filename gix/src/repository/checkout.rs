@@ -0,0 +1,692 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+};
+
+use gix_diff::tree::recorder;
+use gix_object::tree::EntryMode;
+use gix_ref::{
+    transaction::{LogChange, PreviousValue, RefEdit, RefLog},
+    PartialNameRef, Target,
+};
+
+use super::status::to_entry_mode;
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    checkout::{plan, switch, tree, Conflict, ConflictReason, Operation, Plan},
+    status::{self, Change, Item},
+};
+
+/// An entry of a tree that isn't itself a tree.
+type Entry = (gix_hash::ObjectId, EntryMode);
+
+/// All paths that differ between two trees, with their entry in the source and the destination tree, if present.
+type Changes = BTreeMap<BString, (Option<Entry>, Option<Entry>)>;
+
+impl crate::Repository {
+    /// Compute the filesystem operations that are needed to change the worktree and the index from the tree of `HEAD` to
+    /// the tree `id` peels to, without performing them, similar to what `git checkout <tree-ish>` would do.
+    ///
+    /// Only paths that differ between both trees are considered. These are checked for staged changes, changes in the worktree
+    /// and untracked files that are in the way, which are reported as [conflicts](Plan::conflicts) just like `git` would refuse
+    /// to overwrite them. Local changes to all other paths are carried over.
+    /// Unmerged entries in the index are always reported as conflicts, just like changed paths that would only differ in case
+    /// from other paths if `core.ignoreCase` is enabled.
+    pub fn checkout_plan(
+        &self,
+        id: impl Into<gix_hash::ObjectId>,
+        options: plan::Options,
+    ) -> Result<Plan, plan::Error> {
+        let _span = gix_trace::coarse!("gix::checkout_plan()");
+        let work_dir = self.work_dir().ok_or(plan::Error::MissingWorkDir)?;
+        let to_tree = self.find_object(id)?.peel_to_tree()?;
+        let mut head = self.head()?;
+        let from_tree = if head.is_unborn() {
+            None
+        } else {
+            Some(head.peel_to_commit_in_place()?.tree_id()?.detach())
+        };
+        let changes = self.tree_changes(from_tree, &to_tree)?;
+
+        let index = self.index_or_empty()?;
+        let (worktree_items, _) =
+            self.index_worktree_items(&index, None, status::Submodule::Ignore, options.thread_limit)?;
+        let mut conflicts = Vec::new();
+        let mut worktree_changes = BTreeMap::new();
+        for item in worktree_items {
+            match item {
+                Item::Conflict { rela_path, .. } => conflicts.push(Conflict {
+                    rela_path,
+                    reason: ConflictReason::Unmerged,
+                }),
+                Item::Changed {
+                    rela_path,
+                    worktree: Some(change),
+                    ..
+                } => {
+                    worktree_changes.insert(rela_path, change);
+                }
+                _ => {}
+            }
+        }
+
+        let mut walk = status::untracked::Walk {
+            work_dir,
+            index: &index,
+            excludes: self.excludes(
+                &index,
+                None,
+                gix_worktree::stack::state::ignore::Source::WorktreeThenIdMappingIfNotSkipped,
+            )?,
+            pathspec: None,
+            mode: status::Untracked::All,
+            ignored: true,
+            icase: self.config.ignore_case.then(|| index.prepare_icase_backing()),
+            precompose_unicode: self.filesystem_options()?.precompose_unicode,
+        };
+        if self.config.ignore_case {
+            conflicts.extend(case_collisions(&index, &changes));
+        }
+        let mut operations = Vec::new();
+        for (rela_path, (from, to)) in &changes {
+            let is_unmerged = index.entry_range(rela_path.as_bstr()).map_or(false, |range| {
+                index.entries()[range].iter().any(|entry| entry.stage() != 0)
+            });
+            if is_unmerged {
+                continue;
+            }
+            let current = index
+                .entry_by_path_and_stage(rela_path.as_bstr(), 0)
+                .map(|entry| (entry.id, to_entry_mode(entry.mode)));
+            let conflict = |reason| Conflict {
+                rela_path: rela_path.clone(),
+                reason,
+            };
+            if current != *from {
+                if current != *to {
+                    conflicts.push(conflict(ConflictReason::StagedChanges));
+                }
+                continue;
+            }
+            let worktree_change = worktree_changes.get(rela_path).copied();
+            let operation = match *to {
+                None => match worktree_change {
+                    Some(Change::Modified | Change::TypeChange) => {
+                        conflicts.push(conflict(ConflictReason::WorktreeChanges));
+                        continue;
+                    }
+                    _ => Operation::Delete {
+                        rela_path: rela_path.clone(),
+                    },
+                },
+                Some((id, mode)) => {
+                    let rela_path = rela_path.clone();
+                    if current.is_some() {
+                        match worktree_change {
+                            Some(Change::Deleted) => Operation::Create { rela_path, id, mode },
+                            Some(_) if mode != EntryMode::Commit => {
+                                conflicts.push(conflict(ConflictReason::WorktreeChanges));
+                                continue;
+                            }
+                            _ => Operation::Overwrite { rela_path, id, mode },
+                        }
+                    } else if mode == EntryMode::Commit {
+                        Operation::Create { rela_path, id, mode }
+                    } else {
+                        match obstacle(&mut walk, rela_path.as_bstr(), &changes)? {
+                            Obstacle::Nothing => Operation::Create { rela_path, id, mode },
+                            Obstacle::Ignored(conflict) if options.protect_ignored => {
+                                conflicts.push(conflict);
+                                continue;
+                            }
+                            Obstacle::Ignored(_) => Operation::Overwrite { rela_path, id, mode },
+                            Obstacle::Untracked(conflict) => {
+                                conflicts.push(conflict);
+                                continue;
+                            }
+                        }
+                    }
+                }
+            };
+            operations.push(operation);
+        }
+
+        conflicts.sort_by(|a, b| a.rela_path.cmp(&b.rela_path));
+        conflicts.dedup();
+        Ok(Plan {
+            from_tree,
+            to_tree: to_tree.id,
+            operations,
+            conflicts,
+        })
+    }
+
+    /// Change the worktree and the index from the tree of `HEAD` to the tree `id` peels to, without changing `HEAD`,
+    /// and return the plan that was performed.
+    ///
+    /// This is done by applying the changes between both trees, which leaves all other files untouched, along with their
+    /// modification time and local changes. If local changes or untracked files would be overwritten, nothing is changed
+    /// and all [conflicts](Plan::conflicts) are returned in [`tree::Error::Conflicts`].
+    /// Use [`checkout_plan()`](Self::checkout_plan()) to learn about them beforehand.
+    pub fn checkout_tree(
+        &self,
+        id: impl Into<gix_hash::ObjectId>,
+        options: plan::Options,
+    ) -> Result<Plan, tree::Error> {
+        let _span = gix_trace::coarse!("gix::checkout_tree()");
+        self.assure_writable("check out a tree")?;
+        let plan = self.checkout_plan(id, options)?;
+        if plan.is_conflicted() {
+            return Err(tree::Error::Conflicts {
+                conflicts: plan.conflicts,
+            });
+        }
+        self.apply_checkout_plan(&plan)?;
+        Ok(plan)
+    }
+
+    /// Switch to the branch `name` by [checking out](Self::checkout_tree()) the tree of the commit it points to and attaching
+    /// `HEAD` to it, similar to `git switch <branch>`, and return the plan that was performed.
+    /// If `name` isn't a local branch, or if [`options.detach`](switch::Options::detach) is set, `HEAD` is detached at the commit instead.
+    ///
+    /// Only paths that differ between the tree of `HEAD` and the new tree are changed in the worktree and the index, so local changes
+    /// to all other paths are carried over. If these changes, or untracked files, would be overwritten, nothing is changed.
+    pub fn switch<'a, Name, E>(&self, name: Name, options: switch::Options) -> Result<Plan, switch::Error>
+    where
+        Name: TryInto<&'a PartialNameRef, Error = E>,
+        gix_ref::file::find::Error: From<E>,
+    {
+        let _span = gix_trace::coarse!("gix::switch()");
+        self.assure_writable("switch branches")?;
+        let mut reference = self.find_reference(name)?;
+        let commit_id = self
+            .find_object(reference.peel_to_id_in_place()?)?
+            .peel_to_kind(gix_object::Kind::Commit)?
+            .id;
+        let head = self.head()?;
+        let (previous, expected) = match head.kind {
+            crate::head::Kind::Symbolic(reference) => {
+                (reference.name.shorten().to_owned(), Target::Symbolic(reference.name))
+            }
+            crate::head::Kind::Unborn(name) => (name.shorten().to_owned(), Target::Symbolic(name)),
+            crate::head::Kind::Detached { target, .. } => (target.to_string().into(), Target::Peeled(target)),
+        };
+        let plan = self.checkout_tree(commit_id, options.plan)?;
+
+        let is_branch = !options.detach && reference.name().category() == Some(gix_ref::Category::LocalBranch);
+        let (new, next) = if is_branch {
+            (
+                Target::Symbolic(reference.name().to_owned()),
+                reference.name().shorten().to_owned(),
+            )
+        } else {
+            (Target::Peeled(commit_id), commit_id.to_string().into())
+        };
+        self.edit_reference(RefEdit {
+            change: gix_ref::transaction::Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: format!("checkout: moving from {previous} to {next}").into(),
+                },
+                expected: PreviousValue::MustExistAndMatch(expected),
+                new,
+            },
+            name: "HEAD".try_into().expect("valid"),
+            deref: false,
+        })?;
+        Ok(plan)
+    }
+
+    /// Perform all operations of `plan` in the worktree and the index, without checking for conflicts, and write the index.
+    fn apply_checkout_plan(&self, plan: &Plan) -> Result<(), tree::Error> {
+        let work_dir = self.work_dir().ok_or(plan::Error::MissingWorkDir)?;
+        let mut stage = self.stage()?;
+        for operation in &plan.operations {
+            match operation {
+                Operation::Delete { rela_path } => {
+                    stage.remove(rela_path.as_bstr());
+                }
+                Operation::Create { rela_path, id, mode } | Operation::Overwrite { rela_path, id, mode } => {
+                    stage.upsert(rela_path.as_bstr(), *id, to_index_mode(*mode), Default::default());
+                }
+            }
+        }
+
+        // Filters are configured by the attributes of the index we are moving to, just like `git` does.
+        let mut filter = crate::filter::Pipeline::new(
+            self,
+            self.attributes_only(stage.index(), gix_worktree::stack::state::attributes::Source::IdMapping)?
+                .detach(),
+        )?;
+        let ctx = filter.driver_context_mut();
+        ctx.ref_name = self
+            .head()
+            .ok()
+            .and_then(|head| head.referent_name().map(|name| name.as_bstr().to_owned()));
+        ctx.treeish = Some(plan.to_tree);
+        let delay = self.config.filter_process_delay()?;
+        let fs = self.filesystem_options()?;
+        let mut delayed = Vec::new();
+        for operation in &plan.operations {
+            if let Operation::Delete { rela_path } = operation {
+                remove_entry(work_dir, rela_path.as_bstr())?;
+            }
+        }
+        for operation in &plan.operations {
+            let (rela_path, id, mode) = match operation {
+                Operation::Create { rela_path, id, mode } | Operation::Overwrite { rela_path, id, mode } => {
+                    (rela_path.as_bstr(), *id, *mode)
+                }
+                Operation::Delete { .. } => continue,
+            };
+            match self.write_entry(work_dir, rela_path, id, mode, &mut filter, delay, &fs)? {
+                Written::Entry(metadata) => update_stat(&mut stage.index, rela_path, metadata)?,
+                Written::Submodule => {}
+                Written::Delayed(key) => delayed.push((rela_path.to_owned(), key, mode)),
+            }
+        }
+        self.write_delayed_entries(work_dir, delayed, &mut filter, &fs, |rela_path, metadata| {
+            update_stat(&mut stage.index, rela_path, metadata)
+        })?;
+        stage.write()?;
+        Ok(())
+    }
+
+    /// Write the object `id` to `rela_path` as `mode`, replacing whatever is in the way, and return its metadata
+    /// unless it's a submodule.
+    ///
+    /// If `delay` allows it, long-running filter processes may delay the content of files, which then has to be obtained
+    /// with [`write_delayed_entries()`](Self::write_delayed_entries()) later.
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn write_entry(
+        &self,
+        work_dir: &Path,
+        rela_path: &BStr,
+        id: gix_hash::ObjectId,
+        mode: EntryMode,
+        filter: &mut crate::filter::Pipeline<'_>,
+        delay: gix_filter::driver::apply::Delay,
+        fs: &gix_fs::Capabilities,
+    ) -> Result<Written, tree::Error> {
+        let io_err = |source| tree::Error::Io {
+            rela_path: rela_path.to_owned(),
+            source,
+        };
+        let path = work_dir.join(gix_path::from_bstr(rela_path));
+        make_room(work_dir, rela_path, mode == EntryMode::Commit)?;
+        if mode == EntryMode::Commit {
+            std::fs::create_dir_all(&path).map_err(io_err)?;
+            return Ok(Written::Submodule);
+        }
+
+        let object = self.find_object(id)?;
+        if mode == EntryMode::Link && fs.symlink {
+            let target = gix_path::from_bstr(object.data.as_bstr());
+            gix_fs::symlink::create(&target, &path).map_err(io_err)?;
+        } else if mode == EntryMode::Link {
+            // Like `git`, write the path the symlink points to into a file without filtering it.
+            write_file(&path, mode, fs, &mut object.data.as_slice()).map_err(io_err)?;
+        } else {
+            use gix_filter::{driver::apply::MaybeDelayed, pipeline::convert::ToWorktreeOutcome};
+            let converted = filter.convert_to_worktree(&object.data, rela_path, delay)?;
+            let mut read: Box<dyn std::io::Read + '_> = match converted {
+                ToWorktreeOutcome::Process(MaybeDelayed::Delayed(key)) => return Ok(Written::Delayed(key)),
+                ToWorktreeOutcome::Process(MaybeDelayed::Immediate(read)) => read,
+                ToWorktreeOutcome::Unchanged(buf) | ToWorktreeOutcome::Buffer(buf) => Box::new(buf),
+            };
+            write_file(&path, mode, fs, &mut read).map_err(io_err)?;
+        }
+        Ok(Written::Entry(std::fs::symlink_metadata(&path).map_err(io_err)?))
+    }
+
+    /// Obtain the content of all `delayed` files, as `(rela_path, key, mode)` of entries previously returned as
+    /// [`Written::Delayed`], from their long-running filter processes, write it to the worktree and call `on_written`
+    /// with the path and metadata of each written file.
+    ///
+    /// Just like `git`, it's an error if a process doesn't produce all of its delayed files, or lists files it never received.
+    pub(super) fn write_delayed_entries(
+        &self,
+        work_dir: &Path,
+        delayed: Vec<(BString, gix_filter::driver::Key, EntryMode)>,
+        filter: &mut crate::filter::Pipeline<'_>,
+        fs: &gix_fs::Capabilities,
+        mut on_written: impl FnMut(&BStr, std::fs::Metadata) -> Result<(), tree::Error>,
+    ) -> Result<(), tree::Error> {
+        let keys: BTreeSet<_> = delayed.iter().map(|(_, key, _)| key.clone()).collect();
+        let mut delayed: BTreeMap<_, _> = delayed
+            .into_iter()
+            .map(|(rela_path, key, mode)| (rela_path, (key, mode)))
+            .collect();
+        for key in keys {
+            loop {
+                let rela_paths = filter.driver_state_mut().list_delayed_paths(&key)?;
+                if rela_paths.is_empty() {
+                    break;
+                }
+                for rela_path in rela_paths {
+                    let mode = match delayed.remove(&rela_path) {
+                        Some((_, mode)) => mode,
+                        None => return Err(tree::Error::FilterPathUnknown { rela_path }),
+                    };
+                    let io_err = |source| tree::Error::Io {
+                        rela_path: rela_path.clone(),
+                        source,
+                    };
+                    let path = work_dir.join(gix_path::from_bstr(rela_path.as_bstr()));
+                    let mut read = filter.driver_state_mut().fetch_delayed(
+                        &key,
+                        rela_path.as_ref(),
+                        gix_filter::driver::Operation::Smudge,
+                    )?;
+                    write_file(&path, mode, fs, &mut read).map_err(io_err)?;
+                    on_written(rela_path.as_ref(), std::fs::symlink_metadata(&path).map_err(io_err)?)?;
+                }
+            }
+        }
+        if !delayed.is_empty() {
+            return Err(tree::Error::FilterPathsUnprocessed {
+                rela_paths: delayed.into_keys().collect(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Return all non-tree entries that differ between the trees `from` and `to`, with `from` being the empty tree if `None`.
+    fn tree_changes(&self, from: Option<gix_hash::ObjectId>, to: &crate::Tree<'_>) -> Result<Changes, plan::Error> {
+        let from = match from {
+            Some(id) => self.find_object(id)?.into_tree(),
+            None => self.empty_tree(),
+        };
+        let mut recorder = gix_diff::tree::Recorder::default();
+        gix_diff::tree::Changes::from(gix_object::TreeRefIter::from_bytes_with_hash(
+            &from.data,
+            self.object_hash(),
+        ))
+        .needed_to_obtain(
+            gix_object::TreeRefIter::from_bytes_with_hash(&to.data, self.object_hash()),
+            gix_diff::tree::State::default(),
+            &self.objects,
+            &mut recorder,
+        )?;
+
+        let non_tree = |id, mode: EntryMode| (!mode.is_tree()).then_some((id, mode));
+        let mut changes = Changes::new();
+        for change in recorder.records {
+            let (path, from, to) = match change {
+                recorder::Change::Addition { entry_mode, oid, path } => (path, None, non_tree(oid, entry_mode)),
+                recorder::Change::Deletion { entry_mode, oid, path } => (path, non_tree(oid, entry_mode), None),
+                recorder::Change::Modification {
+                    previous_entry_mode,
+                    previous_oid,
+                    entry_mode,
+                    oid,
+                    path,
+                } => (
+                    path,
+                    non_tree(previous_oid, previous_entry_mode),
+                    non_tree(oid, entry_mode),
+                ),
+            };
+            if from.is_none() && to.is_none() {
+                continue;
+            }
+            // A tree turning into a blob or vice versa may be reported as deletion and addition of the same path.
+            let entry = changes.entry(path).or_default();
+            entry.0 = entry.0.or(from);
+            entry.1 = entry.1.or(to);
+        }
+        Ok(changes)
+    }
+}
+
+/// The result of [writing an entry](crate::Repository::write_entry()) to the worktree.
+pub(super) enum Written {
+    /// A file or symlink was written, with the given metadata.
+    Entry(std::fs::Metadata),
+    /// The directory of a submodule was created.
+    Submodule,
+    /// The content of the file was delayed by the long-running filter process identified by this key.
+    Delayed(gix_filter::driver::Key),
+}
+
+/// Set the stat information of the entry at `rela_path` in `index` to `metadata`, if it exists.
+pub(super) fn update_stat(
+    index: &mut gix_index::File,
+    rela_path: &BStr,
+    metadata: std::fs::Metadata,
+) -> Result<(), tree::Error> {
+    let stat = gix_index::entry::Stat::from_fs(&metadata).map_err(|source| tree::Error::Stat {
+        rela_path: rela_path.to_owned(),
+        source,
+    })?;
+    if let Some(entry) = index.entry_mut_by_path_and_stage(rela_path, 0) {
+        entry.stat = stat;
+    }
+    Ok(())
+}
+
+/// Create the file at `path` with the content of `read`, and make it executable if `mode` and `fs` allow it.
+fn write_file(
+    path: &Path,
+    mode: EntryMode,
+    fs: &gix_fs::Capabilities,
+    read: &mut dyn std::io::Read,
+) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    std::io::copy(read, &mut file)?;
+    #[cfg(unix)]
+    if mode == EntryMode::BlobExecutable && fs.executable_bit {
+        use std::os::unix::fs::PermissionsExt;
+        let mut permissions = file.metadata()?.permissions();
+        // Make executable for everyone who can read it.
+        permissions.set_mode(permissions.mode() | ((permissions.mode() & 0o444) >> 2));
+        file.set_permissions(permissions)?;
+    }
+    #[cfg(not(unix))]
+    let _ = (mode, fs);
+    Ok(())
+}
+
+/// Delete the file, symlink or empty directory at `rela_path`, along with all leading directories that are empty afterwards.
+pub(super) fn remove_entry(work_dir: &Path, rela_path: &BStr) -> Result<(), tree::Error> {
+    let io_err = |source| tree::Error::Io {
+        rela_path: rela_path.to_owned(),
+        source,
+    };
+    let path = work_dir.join(gix_path::from_bstr(rela_path));
+    match std::fs::symlink_metadata(&path) {
+        // Like `git`, leave populated submodules in place.
+        Ok(metadata) if metadata.is_dir() => {
+            std::fs::remove_dir(&path).ok();
+        }
+        Ok(_) => std::fs::remove_file(&path).map_err(io_err)?,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+        Err(err) => return Err(io_err(err)),
+    }
+    let mut dir = path.parent();
+    while let Some(parent) = dir.filter(|dir| *dir != work_dir) {
+        if std::fs::remove_dir(parent).is_err() {
+            break;
+        }
+        dir = parent.parent();
+    }
+    Ok(())
+}
+
+/// Remove everything that is in the way of writing an entry at `rela_path` and create its leading directories.
+/// Directories at `rela_path` are kept if `is_submodule` is `true`.
+///
+/// The caller has to assure that nothing of value is lost, which is what the checkout plan is for.
+fn make_room(work_dir: &Path, rela_path: &BStr, is_submodule: bool) -> Result<(), tree::Error> {
+    let io_err = |source| tree::Error::Io {
+        rela_path: rela_path.to_owned(),
+        source,
+    };
+    for pos in rela_path.find_iter("/") {
+        let leading = work_dir.join(gix_path::from_bstr(rela_path[..pos].as_bstr()));
+        match std::fs::symlink_metadata(&leading) {
+            Ok(metadata) if metadata.is_dir() => {}
+            Ok(_) => std::fs::remove_file(&leading).map_err(io_err)?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => break,
+            Err(err) => return Err(io_err(err)),
+        }
+    }
+    let path = work_dir.join(gix_path::from_bstr(rela_path));
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(io_err)?;
+    }
+    match std::fs::symlink_metadata(&path) {
+        Ok(metadata) if metadata.is_dir() => {
+            if !is_submodule {
+                std::fs::remove_dir_all(&path).map_err(io_err)?;
+            }
+        }
+        Ok(_) => std::fs::remove_file(&path).map_err(io_err)?,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+        Err(err) => return Err(io_err(err)),
+    }
+    Ok(())
+}
+
+fn to_index_mode(mode: EntryMode) -> gix_index::entry::Mode {
+    use gix_index::entry::Mode;
+    match mode {
+        EntryMode::Tree => Mode::DIR,
+        EntryMode::BlobExecutable => Mode::FILE_EXECUTABLE,
+        EntryMode::Link => Mode::SYMLINK,
+        EntryMode::Commit => Mode::COMMIT,
+        EntryMode::Blob => Mode::FILE,
+    }
+}
+
+/// Whatever is in the way of writing an untracked path.
+enum Obstacle {
+    /// The path and its leading directories are free to use.
+    Nothing,
+    /// Ignored files are in the way, which cause `conflict` only if they are protected.
+    Ignored(Conflict),
+    /// Untracked files are in the way.
+    Untracked(Conflict),
+}
+
+/// Find what's in the way of writing an entry at `rela_path`, which isn't tracked in the index, while knowing that
+/// all `changes` will be applied.
+fn obstacle(
+    walk: &mut status::untracked::Walk<'_, '_>,
+    rela_path: &BStr,
+    changes: &Changes,
+) -> Result<Obstacle, plan::Error> {
+    let conflict = |rela_path: &BStr, reason| Conflict {
+        rela_path: rela_path.to_owned(),
+        reason,
+    };
+    for pos in rela_path.find_iter("/") {
+        let leading = rela_path[..pos].as_bstr();
+        let path = walk.work_dir.join(gix_path::from_bstr(leading));
+        match std::fs::symlink_metadata(&path) {
+            Ok(metadata) if metadata.is_dir() => continue,
+            Ok(_) => {
+                let is_tracked = walk.index.entry_by_path_and_stage(leading, 0).is_some();
+                let is_deleted = changes.get(leading).map_or(false, |(_, to)| to.is_none());
+                return Ok(if is_tracked && is_deleted {
+                    Obstacle::Nothing
+                } else if is_tracked {
+                    Obstacle::Untracked(conflict(leading, ConflictReason::StagedChanges))
+                } else if is_excluded(walk, leading, false)? {
+                    Obstacle::Ignored(conflict(leading, ConflictReason::UntrackedOverwritten))
+                } else {
+                    Obstacle::Untracked(conflict(leading, ConflictReason::UntrackedOverwritten))
+                });
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Obstacle::Nothing),
+            Err(source) => return Err(plan::Error::Io { path, source }),
+        }
+    }
+
+    let path = walk.work_dir.join(gix_path::from_bstr(rela_path));
+    let metadata = match std::fs::symlink_metadata(&path) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Obstacle::Nothing),
+        Err(source) => return Err(plan::Error::Io { path, source }),
+    };
+    if let Some(entry) = walk.tracked_icase(rela_path) {
+        // On a case-insensitive filesystem, this is the file of a tracked path that only differs in case.
+        let tracked_path = entry.path(walk.index);
+        let is_deleted = changes.get(tracked_path).map_or(false, |(_, to)| to.is_none());
+        return Ok(if is_deleted {
+            Obstacle::Nothing
+        } else {
+            Obstacle::Untracked(conflict(rela_path, ConflictReason::CaseCollision))
+        });
+    }
+    if !metadata.is_dir() {
+        let conflict = conflict(rela_path, ConflictReason::UntrackedOverwritten);
+        return Ok(if is_excluded(walk, rela_path, false)? {
+            Obstacle::Ignored(conflict)
+        } else {
+            Obstacle::Untracked(conflict)
+        });
+    }
+
+    // Tracked files in the directory are deleted as the tree has a file here, so only untracked ones are in the way.
+    let mut items = Vec::new();
+    walk.directory(&mut rela_path.to_owned(), &mut items)?;
+    let conflict = conflict(rela_path, ConflictReason::UntrackedRemoved);
+    Ok(if items.iter().any(|item| matches!(item, Item::Untracked { .. })) {
+        Obstacle::Untracked(conflict)
+    } else if items.is_empty() {
+        Obstacle::Nothing
+    } else {
+        Obstacle::Ignored(conflict)
+    })
+}
+
+/// Return a conflict for each path that is changed to exist alongside another path that only differs in case,
+/// after all `changes` were applied to `index`.
+fn case_collisions(index: &gix_index::State, changes: &Changes) -> Vec<Conflict> {
+    let is_deleted = |rela_path: &BStr| changes.get(rela_path).map_or(false, |(_, to)| to.is_none());
+    let paths: BTreeSet<&BStr> = index
+        .entries()
+        .iter()
+        .map(|entry| entry.path(index))
+        .filter(|rela_path| !is_deleted(rela_path))
+        .chain(
+            changes
+                .iter()
+                .filter_map(|(rela_path, (_, to))| to.is_some().then_some(rela_path.as_bstr())),
+        )
+        .collect();
+    let mut by_folded_path = BTreeMap::<BString, Vec<&BStr>>::new();
+    for rela_path in paths {
+        by_folded_path
+            .entry(rela_path.to_ascii_lowercase().into())
+            .or_default()
+            .push(rela_path);
+    }
+    by_folded_path
+        .into_values()
+        .filter(|rela_paths| rela_paths.len() > 1)
+        .flatten()
+        .filter(|rela_path| changes.contains_key(*rela_path))
+        .map(|rela_path| Conflict {
+            rela_path: rela_path.to_owned(),
+            reason: ConflictReason::CaseCollision,
+        })
+        .collect()
+}
+
+fn is_excluded(
+    walk: &mut status::untracked::Walk<'_, '_>,
+    rela_path: &BStr,
+    is_dir: bool,
+) -> Result<bool, plan::Error> {
+    Ok(walk
+        .excludes
+        .at_entry(rela_path, Some(is_dir))
+        .map_err(|source| plan::Error::Io {
+            path: walk.work_dir.join(gix_path::from_bstr(rela_path)),
+            source,
+        })?
+        .is_excluded())
+}
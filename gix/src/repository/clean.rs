@@ -0,0 +1,193 @@
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    clean::{self, Entry, Ignored, Kind},
+    status::{self, Item},
+};
+
+/// An untracked or ignored path in the worktree, without trailing slash.
+struct Candidate {
+    rela_path: BString,
+    is_dir: bool,
+    is_ignored: bool,
+    is_repository: bool,
+    /// If `true`, the path matches the pathspecs, if there are any.
+    is_included: bool,
+}
+
+impl crate::Repository {
+    /// Find untracked and optionally ignored files in the worktree and remove them, similar to `git clean`, as configured by `options`.
+    ///
+    /// By default, this is a dry-run that only returns what would be removed.
+    /// Nested repositories are only removed if [`options.repositories`](clean::Options::repositories) is set,
+    /// and directories are only removed as a whole if [`options.directories`](clean::Options::directories) is set,
+    /// which also requires that nothing in them is tracked or has to be kept.
+    pub fn clean(&self, options: clean::Options) -> Result<clean::Outcome, clean::Error> {
+        let _span = gix_trace::coarse!("gix::clean()");
+        let work_dir = self.work_dir().ok_or(clean::Error::MissingWorkDir)?;
+        if !options.dry_run {
+            self.assure_writable("clean the worktree")?;
+        }
+        let index = self.index_or_empty()?;
+        let mut pathspec = if options.patterns.is_empty() {
+            None
+        } else {
+            Some(
+                self.pathspec(
+                    &options.patterns,
+                    true,
+                    &index,
+                    gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping,
+                )?
+                .detach()
+                .map_err(clean::Error::DetachPathspec)?,
+            )
+        };
+
+        let mut items = Vec::new();
+        status::untracked::Walk {
+            work_dir,
+            index: &index,
+            excludes: self.excludes(
+                &index,
+                None,
+                gix_worktree::stack::state::ignore::Source::WorktreeThenIdMappingIfNotSkipped,
+            )?,
+            // Pathspecs are applied later to not remove directories with files that don't match.
+            pathspec: None,
+            mode: status::Untracked::All,
+            // We have to know about ignored files in any case to not remove directories that contain them.
+            ignored: true,
+            icase: self.config.ignore_case.then(|| index.prepare_icase_backing()),
+            precompose_unicode: self.filesystem_options()?.precompose_unicode,
+        }
+        .directory(&mut BString::default(), &mut items)?;
+
+        let mut candidates: Vec<_> = items
+            .into_iter()
+            .map(|item| {
+                let is_ignored = matches!(item, Item::Ignored { .. });
+                let mut rela_path = item.rela_path().clone();
+                let is_dir = rela_path.ends_with(b"/");
+                if is_dir {
+                    rela_path.pop();
+                }
+                let is_repository = is_dir
+                    && work_dir
+                        .join(gix_path::from_bstr(rela_path.as_bstr()))
+                        .join(".git")
+                        .exists();
+                let is_included = pathspec
+                    .as_mut()
+                    .map_or(true, |spec| spec.is_included(rela_path.as_bstr(), Some(is_dir)));
+                Candidate {
+                    rela_path,
+                    is_dir,
+                    is_ignored,
+                    is_repository,
+                    is_included,
+                }
+            })
+            .collect();
+        candidates.sort_by(|a, b| a.rela_path.cmp(&b.rela_path));
+
+        let is_selected = |candidate: &Candidate| {
+            let by_ignored = match options.ignored {
+                Ignored::Keep => !candidate.is_ignored,
+                Ignored::Also => true,
+                Ignored::Only => candidate.is_ignored,
+            };
+            by_ignored
+                && candidate.is_included
+                && (!candidate.is_dir || options.directories)
+                && (!candidate.is_repository || options.repositories)
+        };
+        let has_tracked_entries_in = |rela_dir: &BStr| {
+            let mut prefix = rela_dir.to_owned();
+            prefix.push(b'/');
+            index
+                .prefixed_entries(prefix.as_bstr())
+                .map_or(false, |entries| !entries.is_empty())
+        };
+
+        // For each directory containing candidates, learn if all of them are selected and if all of them are ignored.
+        let mut dirs = std::collections::HashMap::<&BStr, (bool, bool)>::new();
+        if options.directories {
+            for candidate in &candidates {
+                let rela_path = candidate.rela_path.as_bstr();
+                let is_selected = is_selected(candidate);
+                for pos in rela_path.find_iter("/") {
+                    let (all_selected, all_ignored) = dirs.entry(rela_path[..pos].as_bstr()).or_insert((true, true));
+                    *all_selected &= is_selected;
+                    *all_ignored &= candidate.is_ignored;
+                }
+            }
+        }
+
+        let mut entries: Vec<Entry> = Vec::new();
+        for candidate in candidates.iter().filter(|candidate| is_selected(candidate)) {
+            if entries.last().map_or(false, |last| {
+                last.kind != Kind::File && candidate.rela_path.starts_with(&last.rela_path)
+            }) {
+                // Contained in a directory that is removed as a whole.
+                continue;
+            }
+            let rela_path = candidate.rela_path.as_bstr();
+            let parent_dirs = rela_path.find_iter("/").map(|pos| rela_path[..pos].as_bstr());
+            let entry = if options.directories {
+                let removable_dir = parent_dirs.filter(|dir| !has_tracked_entries_in(dir)).find_map(|dir| {
+                    let (all_selected, all_ignored) = dirs[dir];
+                    all_selected.then_some((dir, all_ignored))
+                });
+                match removable_dir {
+                    Some((dir, is_ignored)) => entry(dir, Kind::Directory, is_ignored),
+                    None => entry(rela_path, kind(candidate), candidate.is_ignored),
+                }
+            } else {
+                if rela_path
+                    .rfind_byte(b'/')
+                    .map_or(false, |pos| !has_tracked_entries_in(rela_path[..pos].as_bstr()))
+                {
+                    // Like `git`, don't go into directories without tracked files unless these can be removed.
+                    continue;
+                }
+                entry(rela_path, kind(candidate), candidate.is_ignored)
+            };
+            entries.push(entry);
+        }
+
+        if !options.dry_run {
+            for entry in &entries {
+                let rela_path = entry.rela_path.strip_suffix(b"/").unwrap_or(&entry.rela_path);
+                let path = work_dir.join(gix_path::from_byte_slice(rela_path));
+                match entry.kind {
+                    Kind::File => std::fs::remove_file(&path),
+                    Kind::Directory | Kind::Repository => std::fs::remove_dir_all(&path),
+                }
+                .map_err(|source| clean::Error::Io { path, source })?;
+            }
+        }
+        Ok(clean::Outcome { entries })
+    }
+}
+
+fn kind(candidate: &Candidate) -> Kind {
+    if candidate.is_repository {
+        Kind::Repository
+    } else if candidate.is_dir {
+        Kind::Directory
+    } else {
+        Kind::File
+    }
+}
+
+fn entry(rela_path: &BStr, kind: Kind, is_ignored: bool) -> Entry {
+    let mut rela_path = rela_path.to_owned();
+    if kind != Kind::File {
+        rela_path.push(b'/');
+    }
+    Entry {
+        rela_path,
+        kind,
+        is_ignored,
+    }
+}
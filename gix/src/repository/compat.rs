@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+
+use gix_hash::ObjectId;
+
+use crate::compat::{open, record};
+
+/// Compatibility hash
+impl crate::Repository {
+    /// Return the kind of hash that object ids are additionally maintained in as configured with `extensions.compatObjectFormat`,
+    /// or `None` if there is no such hash.
+    pub fn compat_object_hash(&self) -> Option<gix_hash::Kind> {
+        self.config.compat_object_hash
+    }
+
+    /// Return a shared mapping of object ids to their ids in the [compatibility hash](Self::compat_object_hash()) and back,
+    /// which is updated automatically if the in-memory snapshot has become stale as the underlying file on disk has changed.
+    ///
+    /// It's `None` if there is no compatibility hash, or if no object was mapped yet.
+    /// Note that only loose objects written by this or another implementation are mapped.
+    pub fn compat_map(&self) -> Result<Option<crate::compat::Map>, open::Error> {
+        let compat_hash = match self.compat_object_hash() {
+            Some(kind) => kind,
+            None => return Ok(None),
+        };
+        let path = self.compat_map_path();
+        self.compat_map.recent_snapshot(
+            || path.metadata().ok().and_then(|m| m.modified().ok()),
+            || Ok(Some(gix_odb::compat::Map::at(&path, self.object_hash(), compat_hash)?)),
+        )
+    }
+
+    /// Return the id of the object with `id` in the [compatibility hash](Self::compat_object_hash()), or `None` if it isn't known.
+    pub fn to_compat_id(&self, id: impl AsRef<gix_hash::oid>) -> Result<Option<ObjectId>, open::Error> {
+        Ok(self.compat_map()?.and_then(|map| map.to_compat(id.as_ref())))
+    }
+
+    /// Return the id of the object whose id in the [compatibility hash](Self::compat_object_hash()) is `compat_id`,
+    /// or `None` if it isn't known.
+    pub fn from_compat_id(&self, compat_id: impl AsRef<gix_hash::oid>) -> Result<Option<ObjectId>, open::Error> {
+        Ok(self.compat_map()?.and_then(|map| map.from_compat(compat_id.as_ref())))
+    }
+
+    /// If a compatibility hash is configured, compute the compatibility id of the object `id` of `kind` with `data`
+    /// and add it to the mapping unless it's already present.
+    pub(crate) fn record_compat_id(
+        &self,
+        id: &gix_hash::oid,
+        kind: gix_object::Kind,
+        data: &[u8],
+    ) -> Result<(), record::Error> {
+        self.record_compat_id_with(id, |map| Ok(map.compat_id(kind, data)?))
+    }
+
+    /// Like [`record_compat_id()`](Self::record_compat_id()), but obtain the compatibility id of `id` from `compute`,
+    /// which is only called if a compatibility hash is configured and `id` isn't mapped yet.
+    pub(crate) fn record_compat_id_with(
+        &self,
+        id: &gix_hash::oid,
+        compute: impl FnOnce(&gix_odb::compat::Map) -> Result<ObjectId, record::Error>,
+    ) -> Result<(), record::Error> {
+        let compat_hash = match self.compat_object_hash() {
+            Some(kind) => kind,
+            None => return Ok(()),
+        };
+        let map = self.compat_map()?;
+        let compat_id = match map.as_deref() {
+            Some(map) if map.to_compat(id).is_some() => return Ok(()),
+            Some(map) => compute(map)?,
+            None => compute(&gix_odb::compat::Map::new(self.object_hash(), compat_hash))?,
+        };
+        gix_odb::compat::Map::append_to(self.compat_map_path(), &[(id.to_owned(), compat_id)])?;
+        Ok(())
+    }
+
+    fn compat_map_path(&self) -> PathBuf {
+        self.objects.store_ref().path().join(gix_odb::compat::LOOSE_OBJECT_MAP)
+    }
+}
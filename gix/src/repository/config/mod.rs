@@ -22,6 +22,73 @@ impl crate::Repository {
         }
     }
 
+    /// Set each of the `values` in the configuration file of the current worktree as returned by
+    /// [`worktree_config_location()`](Self::worktree_config_location()), and in the configuration of this instance.
+    ///
+    /// The file is rewritten while holding a lock, and keys are expected to not have a subsection.
+    #[cfg(feature = "status")]
+    pub(crate) fn persist_worktree_config(
+        &mut self,
+        values: &[(&'static dyn config::tree::Key, &crate::bstr::BStr)],
+    ) -> Result<(), config::persist::Error> {
+        self.assure_writable("write the configuration")?;
+        let (path, source) = self.worktree_config_location()?;
+        let mut file = if path.is_file() {
+            gix_config::File::from_path_no_includes(path.clone(), source)?
+        } else {
+            gix_config::File::new(gix_config::file::Metadata::from(source).at(&path))
+        };
+        for (key, value) in values {
+            key.validate(value).map_err(config::set_value::Error::from)?;
+            file.set_raw_value(key.section().name(), None, key.name(), *value)
+                .map_err(config::set_value::Error::from)?;
+        }
+        let io_err = |source| config::persist::Error::Io {
+            path: path.clone(),
+            source,
+        };
+        let mut lock = gix_lock::File::acquire_to_update_resource(&path, gix_lock::acquire::Fail::Immediately, None)?;
+        file.write_to(&mut lock).map_err(io_err)?;
+        lock.commit().map_err(|err| io_err(err.error))?;
+
+        let mut snapshot = self.config_snapshot_mut();
+        for (key, value) in values {
+            snapshot.set_value(*key, *value)?;
+        }
+        snapshot.commit()?;
+        Ok(())
+    }
+
+    /// Return the path to the configuration file that receives the configuration specific to the current worktree, along with its source.
+    ///
+    /// Just like in `git`, it's `config.worktree` in the private git directory of the worktree if `extensions.worktreeConfig`
+    /// is enabled, or the shared configuration file of the repository otherwise.
+    pub(crate) fn worktree_config_location(
+        &self,
+    ) -> Result<(std::path::PathBuf, gix_config::Source), config::boolean::Error> {
+        Ok(if self.config_boolean(&config::tree::Extensions::WORKTREE_CONFIG)? {
+            (self.git_dir().join("config.worktree"), gix_config::Source::Worktree)
+        } else {
+            (self.common_dir().join("config"), gix_config::Source::Local)
+        })
+    }
+
+    /// Return the value of `key`, which defaults to `false`.
+    pub(crate) fn config_boolean(
+        &self,
+        key: &'static config::tree::keys::Boolean,
+    ) -> Result<bool, config::boolean::Error> {
+        use crate::config::{cache::util::ApplyLeniencyDefault, tree::Key};
+        Ok(self
+            .config
+            .resolved
+            .boolean(key.section().name(), None, key.name())
+            .map(|res| key.enrich_error(res))
+            .transpose()
+            .with_lenient_default(self.config.lenient_config)?
+            .unwrap_or_default())
+    }
+
     /// Return filesystem options as retrieved from the repository configuration.
     ///
     /// Note that these values have not been [probed](gix_fs::Capabilities::probe()).
@@ -142,7 +142,8 @@ impl crate::Repository {
                     }
 
                     let mut opts = http::Options::default();
-                    let config = &self.config.resolved;
+                    let config = url_matched_http_config(&self.config.resolved, &url);
+                    let config = &*config;
                     let mut trusted_only = self.filter_config_section();
                     let lenient = self.config.lenient_config;
                     opts.extra_headers = {
@@ -405,6 +406,18 @@ impl crate::Repository {
                         }
                     }
 
+                    {
+                        let key = "http.sslVerify";
+                        debug_assert_eq!(key, config::tree::Http::SSL_VERIFY.logical_name());
+                        opts.ssl_verify = config
+                            .boolean_filter_by_key(key, &mut trusted_only)
+                            .map(|value| config::tree::Http::SSL_VERIFY.enrich_error(value))
+                            .transpose()
+                            .with_leniency(lenient)
+                            .map_err(config::transport::http::Error::from)?
+                            .unwrap_or(true);
+                    }
+
                     #[cfg(feature = "blocking-http-transport-curl")]
                     {
                         let key = "http.schannelCheckRevoke";
@@ -426,3 +439,118 @@ impl crate::Repository {
         }
     }
 }
+
+/// Return `config` with the values of all `[http "<url>"]` sections whose url matches `url` appended as `http.*` values,
+/// ordered by how specific the match is, so that the values of the best match take precedence.
+///
+/// This emulates the url-matching of `git config --get-urlmatch`: scheme, host and port must match, with `*` matching any single
+/// label of the host name, the path of the pattern must be a prefix of the path of `url` ending on a `/` boundary, and if a user name
+/// is given it must match as well. Matches with a longer host name, then with a longer path, then those with a user name are more specific.
+#[cfg(any(
+    feature = "blocking-http-transport-reqwest",
+    feature = "blocking-http-transport-curl"
+))]
+fn url_matched_http_config<'a>(
+    config: &'a gix_config::File<'static>,
+    url: &gix_url::Url,
+) -> std::borrow::Cow<'a, gix_config::File<'static>> {
+    use std::borrow::Cow;
+
+    let mut matches: Vec<_> = config
+        .sections_by_name("http")
+        .into_iter()
+        .flatten()
+        .filter_map(|section| {
+            let pattern = section.header().subsection_name()?;
+            url_match_specificity(pattern, url).map(|specificity| (specificity, section))
+        })
+        .collect();
+    if matches.is_empty() {
+        return Cow::Borrowed(config);
+    }
+    matches.sort_by_key(|(specificity, _)| *specificity);
+
+    let mut out = config.clone();
+    for (_, section) in matches {
+        let body = section.body();
+        let mut new_section = out.push_section(
+            gix_config::file::Section::new("http", None, section.meta().clone())
+                .expect("'http' is a valid section name"),
+        );
+        let mut seen = Vec::new();
+        for key in body.keys() {
+            if seen.contains(&key) {
+                continue;
+            }
+            seen.push(key);
+            let values = body.values(key.as_ref());
+            if values.is_empty() {
+                new_section.push(key.clone(), None);
+            }
+            for value in values {
+                new_section.push(key.clone(), Some(value.as_ref()));
+            }
+        }
+    }
+    Cow::Owned(out)
+}
+
+/// Return the specificity of the match of `url` against `pattern` as tuple of the length of the matched host,
+/// the length of the matched path and whether or not a user name was matched, or `None` if it doesn't match.
+#[cfg(any(
+    feature = "blocking-http-transport-reqwest",
+    feature = "blocking-http-transport-curl"
+))]
+fn url_match_specificity(pattern: &BStr, url: &gix_url::Url) -> Option<(usize, usize, bool)> {
+    use crate::bstr::ByteSlice;
+
+    let pattern = pattern.to_str().ok()?;
+    let (scheme, rest) = pattern.split_once("://")?;
+    if !scheme.eq_ignore_ascii_case(url.scheme.as_str()) {
+        return None;
+    }
+    let (authority, path) = rest.find('/').map_or((rest, ""), |pos| rest.split_at(pos));
+    let (user, host_and_port) = match authority.rsplit_once('@') {
+        Some((user, host_and_port)) => (Some(user.split_once(':').map_or(user, |t| t.0)), host_and_port),
+        None => (None, authority),
+    };
+    let (host, port) = match host_and_port.rsplit_once(':') {
+        Some((host, port)) if !port.contains(']') => (host, (!port.is_empty()).then_some(port)),
+        _ => (host_and_port, None),
+    };
+
+    if let Some(user) = user {
+        if url.user() != Some(user) {
+            return None;
+        }
+    }
+
+    let url_host = url.host()?;
+    if host.split('.').count() != url_host.split('.').count()
+        || !host
+            .split('.')
+            .zip(url_host.split('.'))
+            .all(|(pattern, label)| pattern == "*" || pattern.eq_ignore_ascii_case(label))
+    {
+        return None;
+    }
+
+    let port = match port {
+        Some(port) => Some(port.parse::<u16>().ok()?),
+        None => {
+            let mut url = url.clone();
+            url.port = None;
+            url.port_or_default()
+        }
+    };
+    if port != url.port_or_default() {
+        return None;
+    }
+
+    let path = path.trim_end_matches('/');
+    let url_path = url.path.trim_end_with(|c| c == '/');
+    let path_matches = path.is_empty()
+        || url_path == path.as_bytes()
+        || (url_path.starts_with(path.as_bytes()) && url_path.get(path.len()) == Some(&b'/'));
+    path_matches.then_some((host.len(), path.len(), user.is_some()))
+}
@@ -0,0 +1,99 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use gix_hash::ObjectId;
+
+use crate::{connectivity, connectivity::Error};
+
+impl crate::Repository {
+    /// Assure that all objects reachable from `tips` are present in the object database, similar to
+    /// `git rev-list --objects <tips> --not --all`, which is useful to refuse incomplete packs before updating references
+    /// to point to their objects.
+    ///
+    /// If `new_objects` is set, it is the index of the pack that was just received, and only the objects in it are traversed
+    /// while all other objects merely have to exist. Otherwise, all objects reachable from `tips` are traversed until
+    /// reaching an object that a reference points to.
+    ///
+    /// Commits listed in the `shallow` file don't need their parents to be present.
+    /// See the [module documentation](connectivity) for deviations from `git`.
+    pub fn check_connectivity(
+        &self,
+        tips: impl IntoIterator<Item = impl Into<ObjectId>>,
+        new_objects: Option<&gix_pack::index::File>,
+        should_interrupt: &AtomicBool,
+    ) -> Result<connectivity::Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::check_connectivity()");
+        let mut connected = gix_hashtable::HashSet::default();
+        let platform = self.references()?;
+        for reference in platform.all()? {
+            let reference = reference.map_err(Error::References)?;
+            if let Some(id) = reference.inner.target.try_id() {
+                connected.insert(id.to_owned());
+            }
+        }
+        let shallow = self.shallow_commits()?;
+        let is_shallow = |id: &ObjectId| {
+            shallow
+                .as_ref()
+                .map_or(false, |commits| commits.binary_search(id).is_ok())
+        };
+
+        let mut out = connectivity::Outcome {
+            num_traversed: 0,
+            num_boundary: 0,
+        };
+        let mut seen = gix_hashtable::HashSet::default();
+        let mut queue: Vec<(ObjectId, Option<ObjectId>)> = tips.into_iter().map(|id| (id.into(), None)).collect();
+        while let Some((id, referenced_by)) = queue.pop() {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            if !seen.insert(id) {
+                continue;
+            }
+            let missing = || match referenced_by {
+                Some(referenced_by) => Error::Missing { id, referenced_by },
+                None => Error::MissingTip { id },
+            };
+            let is_new = new_objects.map_or(!connected.contains(&id), |index| index.lookup(id).is_some());
+            if !is_new {
+                if !self.has_object(id) {
+                    return Err(missing());
+                }
+                out.num_boundary += 1;
+                continue;
+            }
+
+            let object = self.try_find_object(id)?.ok_or_else(missing)?;
+            out.num_traversed += 1;
+            let decode_err = |source| Error::Decode {
+                id,
+                kind: object.kind,
+                source,
+            };
+            match object.kind {
+                gix_object::Kind::Blob => {}
+                gix_object::Kind::Commit => {
+                    let commit = gix_object::CommitRef::from_bytes(&object.data).map_err(decode_err)?;
+                    queue.push((commit.tree(), Some(id)));
+                    if !is_shallow(&id) {
+                        queue.extend(commit.parents().map(|parent| (parent, Some(id))));
+                    }
+                }
+                gix_object::Kind::Tag => {
+                    let tag = gix_object::TagRef::from_bytes(&object.data).map_err(decode_err)?;
+                    queue.push((tag.target(), Some(id)));
+                }
+                gix_object::Kind::Tree => {
+                    for entry in gix_object::TreeRefIter::from_bytes_with_hash(&object.data, self.object_hash()) {
+                        let entry = entry.map_err(decode_err)?;
+                        if entry.mode == gix_object::tree::EntryMode::Commit {
+                            continue;
+                        }
+                        queue.push((entry.oid.to_owned(), Some(id)));
+                    }
+                }
+            }
+        }
+        Ok(out)
+    }
+}
@@ -0,0 +1,25 @@
+use crate::driver;
+
+impl crate::Repository {
+    /// Obtain a platform to run the diff and merge drivers named in the `diff` and `merge` attributes of paths, preferring
+    /// the in-process drivers in `registry` over external commands configured for the same name.
+    ///
+    /// `.gitattributes` files are read from the worktree, or from the index if they don't exist there.
+    pub fn drivers<'registry>(
+        &self,
+        registry: &'registry driver::Registry,
+    ) -> Result<driver::Platform<'_, 'registry>, driver::InitError> {
+        let index = self.index_or_empty()?;
+        let stack = self.attributes_only(
+            &index,
+            gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping,
+        )?;
+        let attrs = stack.selected_attribute_matches(["diff", "merge", "conflict-marker-size"]);
+        Ok(driver::Platform {
+            repo: self,
+            registry,
+            stack,
+            attrs,
+        })
+    }
+}
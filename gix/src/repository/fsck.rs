@@ -0,0 +1,27 @@
+use crate::fsck;
+
+impl crate::Repository {
+    /// Obtain a platform to check the consistency of objects, references and reflogs of this repository, similar to
+    /// `git fsck`.
+    ///
+    /// The severity of each [kind of finding](fsck::MessageId) is read from `fsck.<msgId>`, like `fsck.hasDotgit = error`.
+    pub fn fsck(&self) -> Result<fsck::Platform<'_>, fsck::init::Error> {
+        let mut severities = Vec::new();
+        for id in fsck::MessageId::ALL {
+            if let Some(value) = self.config.resolved.string("fsck", None, id.as_str()) {
+                let severity =
+                    fsck::Severity::from_bytes(value.as_ref()).ok_or_else(|| fsck::init::Error::InvalidSeverity {
+                        id: *id,
+                        value: value.into_owned(),
+                    })?;
+                severities.push((*id, severity));
+            }
+        }
+        Ok(fsck::Platform {
+            repo: self,
+            severities,
+            dangling: true,
+            unreachable: false,
+        })
+    }
+}
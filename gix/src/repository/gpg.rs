@@ -0,0 +1,120 @@
+use std::{borrow::Cow, path::Path};
+
+use crate::{
+    bstr::BStr,
+    config::{
+        cache::util::ApplyLeniency,
+        tree::{gpg, Gpg, Key, Section, User},
+    },
+    gpg::{signer, verifier, Format, ProgramSigner, Programs},
+};
+
+impl crate::Repository {
+    /// Return a verifier for signatures of commits and tags which runs the programs configured with `gpg.openpgp.program`
+    /// or `gpg.program`, `gpg.x509.program` and `gpg.ssh.program`, like `git` does.
+    /// SSH signatures are checked against the signers in `gpg.ssh.allowedSignersFile`, while keys in `gpg.ssh.revocationFile`
+    /// are rejected.
+    ///
+    /// Good signatures are only considered valid if their key is trusted at least as much as configured with `gpg.minTrustLevel`.
+    pub fn signature_verifier(&self) -> Result<Programs, verifier::Error> {
+        let mut programs = Programs::default();
+        if let Some(path) = self.signature_program(Format::OpenPgp)? {
+            programs.openpgp = path.into_owned();
+        }
+        if let Some(path) = self.signature_program(Format::X509)? {
+            programs.x509 = path.into_owned();
+        }
+        if let Some(path) = self.signature_program(Format::Ssh)? {
+            programs.ssh = path.into_owned();
+        }
+        let section = Gpg::PROGRAM.section().name();
+        let ssh = Some(BStr::new(Gpg::SSH.name()));
+        programs.ssh_allowed_signers = self
+            .config
+            .trusted_file_path(section, ssh, gpg::Ssh::ALLOWED_SIGNERS_FILE.name())
+            .transpose()?
+            .map(Cow::into_owned);
+        programs.ssh_revocation_file = self
+            .config
+            .trusted_file_path(section, ssh, gpg::Ssh::REVOCATION_FILE.name())
+            .transpose()?
+            .map(Cow::into_owned);
+        if let Some(level) = self
+            .config
+            .resolved
+            .string_filter_by_key(
+                Gpg::MIN_TRUST_LEVEL.logical_name().as_str(),
+                &mut self.filter_config_section(),
+            )
+            .map(|value| Gpg::MIN_TRUST_LEVEL.try_into_trust(value))
+            .transpose()
+            .with_leniency(self.config.lenient_config)?
+        {
+            programs.min_trust_level = level;
+        }
+        Ok(programs)
+    }
+
+    /// Return a signer to create signatures of commits and tags in the format configured with `gpg.format`, which defaults
+    /// to OpenPGP, by running the program configured for it just like `git` does.
+    ///
+    /// The key to sign with is configured with `user.signingKey`, and defaults to the committer identity like `Name <email>`
+    /// for OpenPGP and X.509 signatures. For SSH signatures, it must be configured.
+    pub fn signer(&self) -> Result<ProgramSigner, signer::Error> {
+        let format = self
+            .config
+            .resolved
+            .string_filter_by_key(Gpg::FORMAT.logical_name().as_str(), &mut self.filter_config_section())
+            .map(|value| Gpg::FORMAT.try_into_format(value))
+            .transpose()
+            .with_leniency(self.config.lenient_config)?
+            .unwrap_or(Format::OpenPgp);
+        let program = match self.signature_program(format)? {
+            Some(path) => path.into_owned(),
+            None => {
+                let default = Programs::default();
+                match format {
+                    Format::OpenPgp => default.openpgp,
+                    Format::X509 => default.x509,
+                    Format::Ssh => default.ssh,
+                }
+            }
+        };
+        let key = match self.config.resolved.string_filter_by_key(
+            User::SIGNING_KEY.logical_name().as_str(),
+            &mut self.filter_config_section(),
+        ) {
+            Some(key) => Some(key.into_owned()),
+            None if format != Format::Ssh => self
+                .committer()
+                .transpose()?
+                .map(|committer| format!("{} <{}>", committer.name, committer.email).into()),
+            None => None,
+        };
+        Ok(ProgramSigner { format, program, key })
+    }
+
+    /// Return the program configured to create and verify signatures in `format`, or `None` if the default should be used.
+    fn signature_program(&self, format: Format) -> Result<Option<Cow<'_, Path>>, gix_config::path::interpolate::Error> {
+        let section = Gpg::PROGRAM.section().name();
+        match format {
+            Format::OpenPgp => self
+                .config
+                .trusted_file_path(
+                    section,
+                    Some(BStr::new(Gpg::OPENPGP.name())),
+                    gpg::Openpgp::PROGRAM.name(),
+                )
+                .or_else(|| self.config.trusted_file_path(section, None, Gpg::PROGRAM.name())),
+            Format::X509 => {
+                self.config
+                    .trusted_file_path(section, Some(BStr::new(Gpg::X509.name())), gpg::X509::PROGRAM.name())
+            }
+            Format::Ssh => {
+                self.config
+                    .trusted_file_path(section, Some(BStr::new(Gpg::SSH.name())), gpg::Ssh::PROGRAM.name())
+            }
+        }
+        .transpose()
+    }
+}
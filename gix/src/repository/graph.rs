@@ -6,7 +6,8 @@ impl crate::Repository {
     /// it will just not be used.
     ///
     /// Note that a commitgraph is only allowed to be used if `core.commitGraph` is true (the default), and that configuration errors are
-    /// ignored as well.
+    /// ignored as well. It's also not used if there are [replacement objects](crate::Repository::replace_object()), as the
+    /// commitgraph doesn't know about them.
     ///
     /// ### Performance
     ///
@@ -15,9 +16,7 @@ impl crate::Repository {
     pub fn revision_graph<T>(&self) -> gix_revwalk::Graph<'_, T> {
         gix_revwalk::Graph::new(
             &self.objects,
-            self.config
-                .may_use_commit_graph()
-                .unwrap_or(true)
+            (self.config.may_use_commit_graph().unwrap_or(true) && !self.has_replacements())
                 .then(|| gix_commitgraph::at(self.objects.store_ref().path().join("info")).ok())
                 .flatten(),
         )
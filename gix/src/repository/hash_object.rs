@@ -0,0 +1,27 @@
+use crate::hash_object;
+
+impl crate::Repository {
+    /// Obtain a platform to compute the ids of blobs from worktree files or in-memory data, like `git hash-object` does.
+    ///
+    /// Content is passed through the filters that apply to its path as configured in `.gitattributes` and the git configuration,
+    /// which uses the current index or an empty one if there is none yet.
+    pub fn hash_object(&self) -> Result<hash_object::Platform<'_>, hash_object::init::Error> {
+        let index = gix_index::File::clone(&**self.index_or_empty()?);
+        let filter = crate::filter::Pipeline::new(
+            self,
+            self.attributes_only(
+                &index,
+                gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping,
+            )?
+            .detach(),
+        )?;
+        Ok(hash_object::Platform {
+            repo: self,
+            index,
+            filter,
+            buf: Vec::new(),
+            write: false,
+            apply_filters: true,
+        })
+    }
+}
@@ -0,0 +1,150 @@
+use std::{
+    ffi::OsStr,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{ExitStatus, Stdio},
+};
+
+use crate::{
+    config::tree::{Core, Key},
+    hook,
+};
+
+impl crate::Repository {
+    /// Return the directory in which hooks are looked up, which is `core.hooksPath` if set, or the `hooks` directory
+    /// in the [common directory](Self::common_dir()) otherwise.
+    ///
+    /// A relative `core.hooksPath` is relative to the directory hooks run in, which is the work tree if there is one,
+    /// or the git directory otherwise.
+    pub fn hooks_dir(&self) -> Result<PathBuf, gix_config::path::interpolate::Error> {
+        Ok(
+            match self
+                .config
+                .trusted_file_path(Core::HOOKS_PATH.section().name(), None, Core::HOOKS_PATH.name())
+                .transpose()?
+            {
+                Some(path) => self.hook_cwd().join(path),
+                None => self.common_dir().join("hooks"),
+            },
+        )
+    }
+
+    /// Return the path to the hook named `name`, like `pre-commit`, or `None` if it isn't installed.
+    ///
+    /// Just like in `git`, hooks which aren't executable are ignored.
+    /// Hooks are also ignored if the [git directory isn't fully trusted](Self::git_dir_trust()), as they
+    /// would allow whoever owns it to run arbitrary programs.
+    pub fn hook_path(&self, name: &str) -> Result<Option<PathBuf>, gix_config::path::interpolate::Error> {
+        if self.git_dir_trust() != gix_sec::Trust::Full {
+            return Ok(None);
+        }
+        let path = self.hooks_dir()?.join(name);
+        Ok(std::fs::metadata(&path)
+            .ok()
+            .filter(|md| md.is_file() && (!cfg!(unix) || gix_fs::is_executable(md)))
+            .map(|_| path))
+    }
+
+    /// Run the hook named `name` with `args` if it is installed, and return its exit status, or `None` if there is no such hook.
+    ///
+    /// The hook runs in the work tree, or in the git directory if there is none, without standard input.
+    /// `GIT_INDEX_FILE` is set to the [index of the work tree](Self::index_path()) if there is one.
+    /// Use [`invoke_hook()`](Self::invoke_hook()) for more control.
+    pub fn run_hook(
+        &self,
+        name: &str,
+        args: impl IntoIterator<Item = impl AsRef<OsStr>>,
+    ) -> Result<Option<ExitStatus>, hook::run::Error> {
+        let invocation =
+            hook::Invocation::new(name.to_owned()).args(args.into_iter().map(|arg| arg.as_ref().to_owned()));
+        Ok(self.invoke_hook(invocation)?.map(|outcome| outcome.status))
+    }
+
+    /// Run the hook described by `invocation` if it is installed, and return its [outcome](hook::Outcome), or `None`
+    /// if there is no such hook or if it [is ignored](Self::hook_path()) due to reduced trust.
+    ///
+    /// Just like in `git`, the hook runs in the work tree, or in the git directory if there is none or if it's one of the
+    /// hooks that run when receiving a push. `GIT_DIR` is set to the [git directory](Self::git_dir()), and `GIT_INDEX_FILE`
+    /// to the [index of the work tree](Self::index_path()) unless the hook runs in the git directory.
+    /// On Windows, hooks are run through `sh` as they are typically shell scripts.
+    ///
+    /// Note that it's up to the caller to decide how to deal with a failing hook, for instance by turning the outcome
+    /// [into an error](hook::Outcome::into_result()).
+    pub fn invoke_hook(&self, invocation: hook::Invocation) -> Result<Option<hook::Outcome>, hook::run::Error> {
+        let path = match self.hook_path(&invocation.name)? {
+            Some(path) => path,
+            None => return Ok(None),
+        };
+        let cwd = if invocation.in_git_dir {
+            self.git_dir()
+        } else {
+            self.hook_cwd()
+        };
+        // Hooks are typically shell scripts, which can't be executed directly on Windows, so they are run by `sh` there.
+        let prepare = if cfg!(windows) {
+            gix_command::prepare(crate::path::shell_quote(&path)).with_shell()
+        } else {
+            gix_command::prepare(path.as_os_str())
+        };
+        let output = || {
+            if invocation.capture_output {
+                Stdio::piped()
+            } else {
+                Stdio::inherit()
+            }
+        };
+        let mut cmd: std::process::Command = prepare
+            .args(&invocation.args)
+            .stdin(if invocation.stdin.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(output())
+            .stderr(output())
+            .into();
+        cmd.current_dir(cwd).env("GIT_DIR", self.git_dir());
+        if self.work_dir().is_some() && !invocation.in_git_dir {
+            cmd.env("GIT_INDEX_FILE", self.index_path());
+        }
+        cmd.envs(invocation.env.iter().map(|(key, value)| (key, value)));
+
+        let mut child = match cmd.spawn() {
+            Ok(child) => child,
+            Err(source) => return Err(hook::run::Error::Spawn { source, path }),
+        };
+        let io_err = |source, path: &Path| hook::run::Error::Io {
+            source,
+            path: path.to_owned(),
+        };
+        let output = std::thread::scope(|scope| {
+            // Write the input while reading the output, as hooks may produce more output than fits into the pipe
+            // before they are done reading.
+            let writer = child
+                .stdin
+                .take()
+                .zip(invocation.stdin.as_deref())
+                .map(|(mut stdin, input)| scope.spawn(move || stdin.write_all(input)));
+            let output = child.wait_with_output().map_err(|err| io_err(err, &path))?;
+            if let Some(writer) = writer {
+                // Hooks don't have to read their input, just like in `git`.
+                match writer.join().expect("writing doesn't panic") {
+                    Err(err) if err.kind() != std::io::ErrorKind::BrokenPipe => return Err(io_err(err, &path)),
+                    _ => {}
+                }
+            }
+            Ok(output)
+        })?;
+        Ok(Some(hook::Outcome {
+            name: invocation.name,
+            path,
+            status: output.status,
+            stdout: output.stdout,
+            stderr: output.stderr,
+        }))
+    }
+
+    fn hook_cwd(&self) -> &Path {
+        self.work_dir().unwrap_or_else(|| self.git_dir())
+    }
+}
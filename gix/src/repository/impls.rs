@@ -10,6 +10,7 @@ impl Clone for crate::Repository {
             #[cfg(feature = "index")]
             self.index.clone(),
             self.shallow_commits.clone(),
+            self.compat_map.clone(),
             #[cfg(feature = "attributes")]
             self.modules.clone(),
         )
@@ -36,9 +37,11 @@ impl PartialEq<crate::Repository> for crate::Repository {
 
 impl From<&crate::ThreadSafeRepository> for crate::Repository {
     fn from(repo: &crate::ThreadSafeRepository) -> Self {
+        let mut objects = repo.objects.to_handle().into();
+        crate::repository::init::setup_objects(&mut objects, &repo.config);
         crate::Repository::from_refs_and_objects(
             repo.refs.clone(),
-            repo.objects.to_handle().into(),
+            objects,
             repo.work_tree.clone(),
             repo.common_dir.clone(),
             repo.config.clone(),
@@ -46,6 +49,7 @@ impl From<&crate::ThreadSafeRepository> for crate::Repository {
             #[cfg(feature = "index")]
             repo.index.clone(),
             repo.shallow_commits.clone(),
+            repo.compat_map.clone(),
             #[cfg(feature = "attributes")]
             repo.modules.clone(),
         )
@@ -54,9 +58,11 @@ impl From<&crate::ThreadSafeRepository> for crate::Repository {
 
 impl From<crate::ThreadSafeRepository> for crate::Repository {
     fn from(repo: crate::ThreadSafeRepository) -> Self {
+        let mut objects = repo.objects.to_handle().into();
+        crate::repository::init::setup_objects(&mut objects, &repo.config);
         crate::Repository::from_refs_and_objects(
             repo.refs,
-            repo.objects.to_handle().into(),
+            objects,
             repo.work_tree,
             repo.common_dir,
             repo.config,
@@ -64,6 +70,7 @@ impl From<crate::ThreadSafeRepository> for crate::Repository {
             #[cfg(feature = "index")]
             repo.index,
             repo.shallow_commits,
+            repo.compat_map,
             #[cfg(feature = "attributes")]
             repo.modules.clone(),
         )
@@ -84,6 +91,7 @@ impl From<crate::Repository> for crate::ThreadSafeRepository {
             #[cfg(feature = "attributes")]
             modules: r.modules,
             shallow_commits: r.shallow_commits,
+            compat_map: r.compat_map,
         }
     }
 }
@@ -1,4 +1,13 @@
-use crate::{config::cache::util::ApplyLeniencyDefault, repository::IndexPersistedOrInMemory, worktree};
+use std::collections::BTreeMap;
+
+use gix_index::entry;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    config::cache::util::ApplyLeniencyDefault,
+    repository::{read_tree, IndexPersistedOrInMemory},
+    worktree,
+};
 
 /// Index access
 impl crate::Repository {
@@ -14,14 +23,7 @@ impl crate::Repository {
             .map(|value| crate::config::tree::Index::THREADS.try_into_index_threads(value))
             .transpose()
             .with_lenient_default(self.config.lenient_config)?;
-        let skip_hash = self
-            .config
-            .resolved
-            .boolean("index", None, "skipHash")
-            .map(|res| crate::config::tree::Index::SKIP_HASH.enrich_error(res))
-            .transpose()
-            .with_lenient_default(self.config.lenient_config)?
-            .unwrap_or_default();
+        let skip_hash = self.index_skip_hash()?;
 
         let index = gix_index::File::at(
             self.index_path(),
@@ -37,6 +39,18 @@ impl crate::Repository {
         Ok(index)
     }
 
+    /// Return `true` if the index trailer hash should neither be computed nor verified, as configured by `index.skipHash`.
+    pub(crate) fn index_skip_hash(&self) -> Result<bool, crate::config::boolean::Error> {
+        Ok(self
+            .config
+            .resolved
+            .boolean("index", None, "skipHash")
+            .map(|res| crate::config::tree::Index::SKIP_HASH.enrich_error(res))
+            .transpose()
+            .with_lenient_default(self.config.lenient_config)?
+            .unwrap_or_default())
+    }
+
     /// Return a shared worktree index which is updated automatically if the in-memory snapshot has become stale as the underlying file
     /// on disk has changed.
     ///
@@ -117,6 +131,257 @@ impl crate::Repository {
     }
 }
 
+/// Reading trees into the index
+impl crate::Repository {
+    /// Return a new index with the entries of the tree `id` peels to, similar to `git read-tree <tree-ish>`, or if
+    /// [`options.prefix`](read_tree::Options::prefix) is set, the current index with the entries of the tree added below
+    /// that directory, like `git read-tree --prefix=<prefix> <tree-ish>`.
+    ///
+    /// Entries that didn't change compared to the current index retain their stat information, so they aren't
+    /// considered modified in the worktree afterwards.
+    /// The returned index would live at the correct location, but it's not written.
+    pub fn read_tree(
+        &self,
+        id: impl Into<gix_hash::ObjectId>,
+        options: read_tree::Options,
+    ) -> Result<gix_index::File, read_tree::Error> {
+        let _span = gix_trace::coarse!("gix::read_tree()");
+        let prefix = options.prefix.as_ref().map(|prefix| prefix.trim_with(|c| c == '/'));
+        if prefix.map_or(false, |prefix| prefix.is_empty()) {
+            return Err(read_tree::Error::EmptyPrefix);
+        }
+        let index = self.index_or_empty()?;
+        let mut tree = gix_index::State::from_tree(&self.peel_to_tree_id(id.into())?, &self.objects)?;
+        let state = match prefix {
+            None => {
+                for (entry, rela_path) in tree.entries_mut_with_paths() {
+                    if let Some(current) = index
+                        .entry_by_path_and_stage(rela_path, 0)
+                        .filter(|current| current.id == entry.id && current.mode == entry.mode)
+                    {
+                        entry.stat = current.stat;
+                        entry.flags = current.flags - entry::Flags::INTENT_TO_ADD;
+                    }
+                }
+                tree
+            }
+            Some(prefix) => {
+                let is_in_use = |rela_path: &BStr| {
+                    rela_path == prefix
+                        || (rela_path.starts_with(prefix) && rela_path.get(prefix.len()) == Some(&b'/'))
+                        || (prefix.starts_with(rela_path) && prefix.get(rela_path.len()) == Some(&b'/'))
+                };
+                if index.entries().iter().any(|entry| is_in_use(entry.path(&index))) {
+                    return Err(read_tree::Error::PrefixInUse {
+                        prefix: prefix.as_bstr().to_owned(),
+                    });
+                }
+                let mut state = gix_index::State::clone(&index);
+                let mut rela_path = BString::default();
+                for entry in tree.entries() {
+                    rela_path.clear();
+                    rela_path.extend_from_slice(prefix);
+                    rela_path.push(b'/');
+                    rela_path.extend_from_slice(entry.path(&tree));
+                    state.dangerously_push_entry(entry.stat, entry.id, entry.flags, entry.mode, rela_path.as_ref());
+                }
+                state.sort_entries();
+                state.remove_tree();
+                state
+            }
+        };
+        Ok(gix_index::File::from_state(state, self.index_path()))
+    }
+
+    /// Return a new index that moves the current index from the tree `current` peels to towards the one of `target`,
+    /// similar to `git read-tree -m <current> <target>`.
+    ///
+    /// Paths that are the same in both trees keep their index entry, including staged changes. All other paths are
+    /// changed to match `target`, which is only allowed if the index has no staged changes for them, or if it
+    /// already matches `target`. Otherwise, all paths with staged changes that would be lost are returned in
+    /// [`read_tree::Error::LocalChanges`]. If the index is empty, it's populated from `target` like in an initial checkout.
+    ///
+    /// Note that the worktree isn't checked for local changes, use [`checkout_plan()`](Self::checkout_plan()) for that.
+    /// The returned index would live at the correct location, but it's not written.
+    pub fn read_tree_two_way(
+        &self,
+        current: impl Into<gix_hash::ObjectId>,
+        target: impl Into<gix_hash::ObjectId>,
+    ) -> Result<gix_index::File, read_tree::Error> {
+        let _span = gix_trace::coarse!("gix::read_tree_two_way()");
+        let trees = [
+            self.peel_to_tree_id(current.into())?,
+            self.peel_to_tree_id(target.into())?,
+        ];
+        let is_initial_checkout = self.index_or_empty()?.entries().is_empty();
+        self.merge_trees_into_index(&trees, |index, sides| {
+            let (current, target) = (sides[0], sides[1]);
+            match (index, current, target) {
+                (None, Some(current), Some(target)) if current != target && !is_initial_checkout => Resolution::Reject,
+                (None, Some(_), Some(_)) if !is_initial_checkout => Resolution::KeepIndex,
+                (None, _, target) => Resolution::Take(target),
+                (Some(index), None, target) => {
+                    if target.map_or(true, |target| target == index) {
+                        Resolution::KeepIndex
+                    } else {
+                        Resolution::Reject
+                    }
+                }
+                (Some(index), Some(current), None) => {
+                    if index == current {
+                        Resolution::Take(None)
+                    } else {
+                        Resolution::Reject
+                    }
+                }
+                (Some(index), Some(current), Some(target)) => {
+                    if current == target || index == target {
+                        Resolution::KeepIndex
+                    } else if index == current {
+                        Resolution::Take(Some(target))
+                    } else {
+                        Resolution::Reject
+                    }
+                }
+            }
+        })
+    }
+
+    /// Return a new index with the result of merging the trees `ours` and `theirs` peel to, using `base` as their
+    /// common ancestor, similar to `git read-tree -m --aggressive <base> <ours> <theirs>`.
+    ///
+    /// Trivial merges are resolved right away, which is when both sides are the same, or when only one side changed
+    /// compared to `base`, including deletions. All other paths are conflicted, and `base`, `ours` and `theirs`
+    /// are placed into stage 1, 2 and 3 of the index respectively, as far as they exist.
+    ///
+    /// The index is expected to match `ours` for all paths that change, otherwise all paths with staged changes that
+    /// would be lost are returned in [`read_tree::Error::LocalChanges`]. Paths that keep their version of `ours`
+    /// keep their index entry, including staged changes.
+    /// The returned index would live at the correct location, but it's not written.
+    pub fn read_tree_three_way(
+        &self,
+        base: impl Into<gix_hash::ObjectId>,
+        ours: impl Into<gix_hash::ObjectId>,
+        theirs: impl Into<gix_hash::ObjectId>,
+    ) -> Result<gix_index::File, read_tree::Error> {
+        let _span = gix_trace::coarse!("gix::read_tree_three_way()");
+        let trees = [
+            self.peel_to_tree_id(base.into())?,
+            self.peel_to_tree_id(ours.into())?,
+            self.peel_to_tree_id(theirs.into())?,
+        ];
+        self.merge_trees_into_index(&trees, |index, sides| {
+            let (base, ours, theirs) = (sides[0], sides[1], sides[2]);
+            let merged = if ours == theirs || base == theirs {
+                Resolution::Take(ours)
+            } else if base == ours {
+                Resolution::Take(theirs)
+            } else {
+                Resolution::Conflict { base, ours, theirs }
+            };
+            match merged {
+                Resolution::Take(merged) if merged == ours || merged == index => Resolution::KeepIndex,
+                merged if index == ours => merged,
+                _ => Resolution::Reject,
+            }
+        })
+    }
+
+    fn peel_to_tree_id(&self, id: gix_hash::ObjectId) -> Result<gix_hash::ObjectId, read_tree::Error> {
+        Ok(self.find_object(id)?.peel_to_kind(gix_object::Kind::Tree)?.id)
+    }
+
+    /// Merge all `trees` with the current index by calling `resolve` for each path that is in at least one of them,
+    /// with the stage 0 entry of the index and the entry of each tree. Paths that are only in the index are kept.
+    fn merge_trees_into_index(
+        &self,
+        trees: &[gix_hash::ObjectId],
+        mut resolve: impl FnMut(Option<Side>, &[Option<Side>]) -> Resolution,
+    ) -> Result<gix_index::File, read_tree::Error> {
+        let index = self.index_or_empty()?;
+        if let Some(entry) = index.entries().iter().find(|entry| entry.stage() != 0) {
+            return Err(read_tree::Error::Unmerged {
+                rela_path: entry.path(&index).to_owned(),
+            });
+        }
+        let mut paths = BTreeMap::<BString, Vec<Option<Side>>>::new();
+        for (tree_index, tree) in trees.iter().enumerate() {
+            let tree = gix_index::State::from_tree(tree, &self.objects)?;
+            for entry in tree.entries() {
+                paths
+                    .entry(entry.path(&tree).to_owned())
+                    .or_insert_with(|| vec![None; trees.len()])[tree_index] = Some((entry.id, entry.mode));
+            }
+        }
+
+        let mut state = gix_index::State::new(self.object_hash());
+        for entry in index.entries() {
+            let rela_path = entry.path(&index);
+            if !paths.contains_key(rela_path) {
+                state.dangerously_push_entry(entry.stat, entry.id, entry.flags, entry.mode, rela_path);
+            }
+        }
+        let mut rejected = Vec::new();
+        for (rela_path, sides) in paths {
+            let current = index.entry_by_path_and_stage(rela_path.as_ref(), 0);
+            match resolve(current.map(|entry| (entry.id, entry.mode)), &sides) {
+                Resolution::KeepIndex => {
+                    if let Some(entry) = current {
+                        state.dangerously_push_entry(entry.stat, entry.id, entry.flags, entry.mode, rela_path.as_ref());
+                    }
+                }
+                Resolution::Take(None) => {}
+                Resolution::Take(Some((id, mode))) => {
+                    let (stat, flags) = current
+                        .filter(|entry| entry.id == id && entry.mode == mode)
+                        .map_or((entry::Stat::default(), entry::Flags::empty()), |entry| {
+                            (entry.stat, entry.flags - entry::Flags::INTENT_TO_ADD)
+                        });
+                    state.dangerously_push_entry(stat, id, flags, mode, rela_path.as_ref());
+                }
+                Resolution::Conflict { base, ours, theirs } => {
+                    for (stage, side) in [(1, base), (2, ours), (3, theirs)] {
+                        if let Some((id, mode)) = side {
+                            state.dangerously_push_entry(
+                                entry::Stat::default(),
+                                id,
+                                entry::Flags::from_bits_retain(stage << 12),
+                                mode,
+                                rela_path.as_ref(),
+                            );
+                        }
+                    }
+                }
+                Resolution::Reject => rejected.push(rela_path),
+            }
+        }
+        if !rejected.is_empty() {
+            return Err(read_tree::Error::LocalChanges { rela_paths: rejected });
+        }
+        state.sort_entries();
+        Ok(gix_index::File::from_state(state, self.index_path()))
+    }
+}
+
+/// The id and mode of an entry in the index or in a tree.
+type Side = (gix_hash::ObjectId, entry::Mode);
+
+/// What to do with a path when merging trees into the index.
+enum Resolution {
+    /// Keep the stage 0 entry of the index, if there is one.
+    KeepIndex,
+    /// Place the given entry into stage 0, or remove the path if there is none.
+    Take(Option<Side>),
+    /// Place each side into its own stage.
+    Conflict {
+        base: Option<Side>,
+        ours: Option<Side>,
+        theirs: Option<Side>,
+    },
+    /// The path has staged changes that would be lost.
+    Reject,
+}
+
 impl std::ops::Deref for IndexPersistedOrInMemory {
     type Target = gix_index::File;
 
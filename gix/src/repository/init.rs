@@ -4,16 +4,16 @@ impl crate::Repository {
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn from_refs_and_objects(
         refs: crate::RefStore,
-        mut objects: crate::OdbHandle,
+        objects: crate::OdbHandle,
         work_tree: Option<std::path::PathBuf>,
         common_dir: Option<std::path::PathBuf>,
         config: crate::config::Cache,
         linked_worktree_options: crate::open::Options,
         #[cfg(feature = "index")] index: crate::worktree::IndexStorage,
         shallow_commits: crate::shallow::CommitsStorage,
+        compat_map: crate::compat::MapStorage,
         #[cfg(feature = "attributes")] modules: crate::submodule::ModulesFileStorage,
     ) -> Self {
-        setup_objects(&mut objects, &config);
         crate::Repository {
             bufs: RefCell::new(Vec::with_capacity(4)),
             work_tree,
@@ -25,6 +25,7 @@ impl crate::Repository {
             #[cfg(feature = "index")]
             index,
             shallow_commits,
+            compat_map,
             #[cfg(feature = "attributes")]
             modules,
         }
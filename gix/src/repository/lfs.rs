@@ -0,0 +1,9 @@
+impl crate::Repository {
+    /// Return a [store](crate::lfs::Store) for the content of large files that `git lfs` keeps locally,
+    /// in the `lfs/objects` directory of the [common directory](Self::common_dir()).
+    ///
+    /// Note that the `lfs.storage` configuration isn't respected yet.
+    pub fn lfs_store(&self) -> crate::lfs::LocalStore {
+        crate::lfs::LocalStore::at(self.common_dir().join("lfs").join("objects"))
+    }
+}
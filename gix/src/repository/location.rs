@@ -1,7 +1,12 @@
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 
 use gix_path::realpath::MAX_SYMLINKS;
 
+use crate::{
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    repository::repo_relative_path,
+};
+
 impl crate::Repository {
     /// Return the path to the repository itself, containing objects, references, configuration, and more.
     ///
@@ -63,6 +68,115 @@ impl crate::Repository {
         Ok(current_dir.strip_prefix(&root).ok())
     }
 
+    /// Convert `path`, which is absolute or relative to the current working directory, into a path relative to the root of
+    /// the worktree with slashes as separators, just like `git` does with paths passed on the command-line.
+    /// The root of the worktree itself is returned as empty path.
+    ///
+    /// `.` and `..` components are resolved without accessing the filesystem, and paths that reach the worktree through
+    /// symbolic links are supported as well. If `core.symlinks` is enabled, paths that lead through a symbolic link within
+    /// the worktree are rejected like `git` does, as only the link itself can be tracked.
+    ///
+    /// Note that the current working directory is obtained once upon instantiation of the repository.
+    pub fn repo_relative_path(&self, path: impl AsRef<Path>) -> Result<BString, repo_relative_path::Error> {
+        use repo_relative_path::Error;
+        let path = path.as_ref();
+        let current_dir = self.options.current_dir_or_empty();
+        let work_dir = self.work_dir().ok_or(Error::MissingWorkDir)?;
+        let work_dir = normalize(&current_dir.join(work_dir)).ok_or_else(|| Error::BeyondRoot {
+            path: work_dir.to_owned(),
+        })?;
+        let absolute = normalize(&current_dir.join(path)).ok_or_else(|| Error::BeyondRoot { path: path.to_owned() })?;
+        let rela_path = match absolute.strip_prefix(&work_dir) {
+            Ok(rela_path) => rela_path.to_owned(),
+            Err(_) => {
+                // The path may reach the worktree through a symlink, so compare the real paths of what exists.
+                let work_dir = gix_path::realpath_opts(&work_dir, current_dir, MAX_SYMLINKS)?;
+                let existing = absolute
+                    .ancestors()
+                    .find(|ancestor| ancestor.symlink_metadata().is_ok())
+                    .unwrap_or(&absolute);
+                let mut real = gix_path::realpath_opts(existing, current_dir, MAX_SYMLINKS)?;
+                real.push(absolute.strip_prefix(existing).expect("ancestor"));
+                real.strip_prefix(&work_dir)
+                    .map_err(|_| Error::OutsideWorktree { path: path.to_owned() })?
+                    .to_owned()
+            }
+        };
+
+        let mut out = BString::default();
+        let check_symlinks = self.config.fs_capabilities()?.symlink;
+        let mut components = rela_path.components().peekable();
+        while let Some(component) = components.next() {
+            let component = gix_path::os_str_into_bstr(component.as_os_str())
+                .map_err(|_| Error::IllformedUtf8 { path: path.to_owned() })?;
+            if !out.is_empty() {
+                out.push(b'/');
+            }
+            out.push_str(component);
+            let is_leading = components.peek().is_some();
+            if is_leading
+                && check_symlinks
+                && work_dir
+                    .join(gix_path::from_bstr(out.as_bstr()))
+                    .symlink_metadata()
+                    .map_or(false, |metadata| metadata.is_symlink())
+            {
+                return Err(Error::BeyondSymlink {
+                    path: path.to_owned(),
+                    symlink: out,
+                });
+            }
+        }
+        Ok(out)
+    }
+
+    /// Return the absolute path to `rela_path` in the worktree, with `rela_path` being relative to the root of the worktree and
+    /// using slashes as separators, or `None` if there is no worktree.
+    ///
+    /// Separators are converted to the ones native to the platform, which makes the path usable on Windows as well.
+    pub fn worktree_path(&self, rela_path: impl AsRef<BStr>) -> Option<PathBuf> {
+        let work_dir = self.options.current_dir_or_empty().join(self.work_dir()?);
+        Some(work_dir.join(gix_path::to_native_path_on_windows(rela_path.as_ref())))
+    }
+
+    /// Return `rela_path`, which is relative to the root of the worktree and uses slashes as separators, as path relative to the
+    /// current working directory, just like `git` displays paths, or `None` if there is no worktree.
+    ///
+    /// If the current working directory isn't inside of the worktree, the absolute [`worktree_path()`](Self::worktree_path())
+    /// is returned instead. The current working directory itself is returned as `.`.
+    pub fn prefix_relative_path(
+        &self,
+        rela_path: impl AsRef<BStr>,
+    ) -> Result<Option<PathBuf>, gix_path::realpath::Error> {
+        let rela_path = rela_path.as_ref();
+        let prefix = match self.prefix()? {
+            Some(prefix) => prefix,
+            None => return Ok(self.worktree_path(rela_path)),
+        };
+        let mut prefix = prefix
+            .components()
+            .filter(|c| matches!(c, Component::Normal(_)))
+            .peekable();
+        let mut rela_path = rela_path
+            .split(|b| *b == b'/')
+            .filter(|c| !c.is_empty())
+            .map(|c| gix_path::from_byte_slice(c).as_os_str())
+            .peekable();
+        while let (Some(a), Some(b)) = (prefix.peek(), rela_path.peek()) {
+            if a.as_os_str() != *b {
+                break;
+            }
+            prefix.next();
+            rela_path.next();
+        }
+        let mut out: PathBuf = prefix.map(|_| Component::ParentDir).collect();
+        out.extend(rela_path);
+        if out.as_os_str().is_empty() {
+            out.push(Component::CurDir);
+        }
+        Ok(Some(out))
+    }
+
     /// Return the kind of repository, either bare or one with a work tree.
     pub fn kind(&self) -> crate::repository::Kind {
         match self.worktree() {
@@ -79,3 +193,21 @@ impl crate::Repository {
         }
     }
 }
+
+/// Resolve `.` and `..` components in `path` without accessing the filesystem, or return `None` if a `..` component
+/// leads above the root, or above the beginning of a relative path.
+fn normalize(path: &Path) -> Option<PathBuf> {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !out.pop() {
+                    return None;
+                }
+            }
+            component => out.push(component),
+        }
+    }
+    Some(out)
+}
@@ -0,0 +1,34 @@
+use crate::{bstr::BStr, ls_files};
+
+impl crate::Repository {
+    /// Obtain a platform to query tracked, unmerged, untracked and ignored files, similar to `git ls-files`,
+    /// limited to paths matching `patterns`, or all paths if there is none.
+    ///
+    /// Note that the index is loaded once and reflects the state at the time of this call.
+    pub fn ls_files(
+        &self,
+        patterns: impl IntoIterator<Item = impl AsRef<BStr>>,
+    ) -> Result<ls_files::Platform<'_>, ls_files::init::Error> {
+        let index = self.index_or_empty()?;
+        let patterns: Vec<_> = patterns.into_iter().map(|p| p.as_ref().to_owned()).collect();
+        let pathspec = if patterns.is_empty() {
+            None
+        } else {
+            Some(
+                self.pathspec(
+                    &patterns,
+                    true,
+                    &index,
+                    gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping,
+                )?
+                .detach()
+                .map_err(ls_files::init::Error::DetachPathspec)?,
+            )
+        };
+        Ok(ls_files::Platform {
+            repo: self,
+            index,
+            pathspec,
+        })
+    }
+}
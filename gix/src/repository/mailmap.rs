@@ -1,5 +1,15 @@
 impl crate::Repository {
-    // TODO: tests
+    /// Load the mailmap from all locations [`open_mailmap_into()`][crate::Repository::open_mailmap_into()] considers
+    /// and return it, or the first error that occurred.
+    ///
+    /// Use it to canonicalize the names and emails of authors and committers, for instance
+    /// [during a revision walk](crate::revision::walk::Platform::with_mailmap()).
+    pub fn mailmap(&self) -> Result<gix_mailmap::Snapshot, crate::mailmap::load::Error> {
+        let mut out = gix_mailmap::Snapshot::default();
+        self.open_mailmap_into(&mut out)?;
+        Ok(out)
+    }
+
     /// Similar to [`open_mailmap_into()`][crate::Repository::open_mailmap_into()], but ignores all errors and returns at worst
     /// an empty mailmap, e.g. if there is no mailmap or if there were errors loading them.
     ///
@@ -11,7 +21,6 @@ impl crate::Repository {
         out
     }
 
-    // TODO: tests
     /// Try to merge mailmaps from the following locations into `target`:
     ///
     /// - read the `.mailmap` file without following symlinks from the working tree, if present
@@ -0,0 +1,96 @@
+use std::time::{Duration, SystemTime};
+
+use crate::{
+    config::{
+        cache::util::ApplyLeniencyDefault,
+        tree::{Gc, Key, Pack, Repack},
+    },
+    maintenance,
+};
+
+impl crate::Repository {
+    /// Obtain a platform to tidy up the object database and reflogs of this repository, similar to `git gc`.
+    ///
+    /// Expiry times are read from `gc.pruneExpire` and `gc.reflogExpire`, relative to the current time, and
+    /// `gc.cruftPacks` controls whether unreachable objects are kept in a cruft pack or as loose objects.
+    /// `repack.writeBitmaps` (or the deprecated `pack.writeBitmaps`) controls whether a reachability bitmap is written when
+    /// repacking, which is the default in bare repositories, and `repack.useDeltaIslands` controls whether deltas are kept within the islands of `pack.island`.
+    /// `repack.packKeptObjects` controls whether objects of packs with a `.keep` file are added to new packs, which is
+    /// the default if bitmaps are written.
+    /// Objects that are compressed anew are compressed with the level of `pack.compression` or `core.compression`.
+    pub fn maintenance(&self) -> Result<maintenance::Platform<'_>, maintenance::init::Error> {
+        let now = SystemTime::now();
+        let config = self.config_snapshot();
+        let expiry = |key: &'static crate::config::tree::gc::Expiry, default: Duration| {
+            Ok::<_, maintenance::init::Error>(match config.string_by_key(key.logical_name().as_str()) {
+                Some(value) => key.try_into_expiry(value, now)?,
+                None => now.checked_sub(default),
+            })
+        };
+        let cruft_packs = self
+            .config
+            .resolved
+            .boolean(Gc::CRUFT_PACKS.section().name(), None, Gc::CRUFT_PACKS.name())
+            .map(|res| Gc::CRUFT_PACKS.enrich_error(res))
+            .transpose()
+            .with_lenient_default(self.config.lenient_config)?
+            .unwrap_or(true);
+        let write_bitmaps = self
+            .config
+            .resolved
+            .boolean(
+                Repack::WRITE_BITMAPS.section().name(),
+                None,
+                Repack::WRITE_BITMAPS.name(),
+            )
+            .map(|res| Repack::WRITE_BITMAPS.enrich_error(res))
+            .or_else(|| {
+                self.config
+                    .resolved
+                    .boolean(Pack::WRITE_BITMAPS.section().name(), None, Pack::WRITE_BITMAPS.name())
+                    .map(|res| Pack::WRITE_BITMAPS.enrich_error(res))
+            })
+            .transpose()
+            .with_lenient_default(self.config.lenient_config)?
+            .unwrap_or_else(|| self.is_bare());
+        let delta_islands = self
+            .config
+            .resolved
+            .boolean(
+                Repack::USE_DELTA_ISLANDS.section().name(),
+                None,
+                Repack::USE_DELTA_ISLANDS.name(),
+            )
+            .map(|res| Repack::USE_DELTA_ISLANDS.enrich_error(res))
+            .transpose()
+            .with_lenient_default(self.config.lenient_config)?
+            .unwrap_or(false);
+        let pack_kept_objects = self
+            .config
+            .resolved
+            .boolean(
+                Repack::PACK_KEPT_OBJECTS.section().name(),
+                None,
+                Repack::PACK_KEPT_OBJECTS.name(),
+            )
+            .map(|res| Repack::PACK_KEPT_OBJECTS.enrich_error(res))
+            .transpose()
+            .with_lenient_default(self.config.lenient_config)?
+            .unwrap_or(write_bitmaps);
+        let compression_level = self
+            .config
+            .compression_level(&Pack::COMPRESSION)?
+            .unwrap_or(gix_features::zlib::stream::deflate::DEFAULT_LEVEL);
+        const DAY: u64 = 24 * 60 * 60;
+        Ok(maintenance::Platform {
+            repo: self,
+            prune_expire: expiry(&Gc::PRUNE_EXPIRE, Duration::from_secs(14 * DAY))?,
+            reflog_expire: expiry(&Gc::REFLOG_EXPIRE, Duration::from_secs(90 * DAY))?,
+            cruft_packs,
+            bitmaps: write_bitmaps.then(Default::default),
+            delta_islands,
+            pack_kept_objects,
+            compression_level,
+        })
+    }
+}
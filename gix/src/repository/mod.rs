@@ -38,31 +38,72 @@ impl crate::Repository {
 #[cfg(any(feature = "attributes", feature = "excludes"))]
 pub mod attributes;
 mod cache;
+#[cfg(feature = "revision")]
+mod cat_file;
+#[cfg(feature = "attributes")]
+mod check_attr;
+#[cfg(feature = "excludes")]
+mod check_ignore;
+#[cfg(feature = "status")]
+mod checkout;
+#[cfg(feature = "status")]
+mod clean;
+mod compat;
 mod config;
+mod connectivity;
+#[cfg(feature = "attributes")]
+mod driver;
 ///
 #[cfg(feature = "attributes")]
 pub mod filter;
+#[cfg(feature = "fsck")]
+mod fsck;
+mod gpg;
 mod graph;
+#[cfg(feature = "attributes")]
+mod hash_object;
+mod hook;
 pub(crate) mod identity;
 mod impls;
 #[cfg(feature = "index")]
 mod index;
 pub(crate) mod init;
 mod kind;
+mod lfs;
 mod location;
+#[cfg(feature = "status")]
+mod ls_files;
 #[cfg(feature = "mailmap")]
 mod mailmap;
+#[cfg(feature = "maintenance")]
+mod maintenance;
+#[cfg(feature = "revision")]
+mod notes;
 mod object;
 #[cfg(feature = "attributes")]
 mod pathspec;
+mod promisor;
+///
+pub mod read_only;
 mod reference;
 mod remote;
+mod replace;
+#[cfg(feature = "revision")]
+mod replay;
 #[cfg(feature = "revision")]
 mod revision;
 mod shallow;
+#[cfg(feature = "status")]
+mod sparse;
+#[cfg(feature = "attributes")]
+mod stage;
 mod state;
+#[cfg(feature = "status")]
+mod status;
 #[cfg(feature = "attributes")]
 mod submodule;
+#[cfg(feature = "revision")]
+mod subtree;
 mod thread_safe;
 mod worktree;
 
@@ -77,6 +118,142 @@ pub enum IndexPersistedOrInMemory {
     InMemory(gix_index::File),
 }
 
+///
+pub mod objects_stats {
+    use std::path::PathBuf;
+
+    /// The error returned by [`Repository::objects_stats()`](crate::Repository::objects_stats()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+        #[error(transparent)]
+        OpenPackIndex(#[from] gix_pack::index::init::Error),
+    }
+
+    /// Statistics about the object database as returned by [`Repository::objects_stats()`](crate::Repository::objects_stats()),
+    /// similar to `git count-objects -v`.
+    #[derive(Debug, Default, Clone, PartialEq, Eq)]
+    pub struct Outcome {
+        /// The amount of loose objects.
+        pub loose_objects: usize,
+        /// The size of all loose objects on disk in bytes.
+        pub loose_size: u64,
+        /// The amount of loose objects which are also in a pack, and which could be removed.
+        pub prune_packable: usize,
+        /// The amount of packs which have an index.
+        pub packs: usize,
+        /// The amount of objects in all packs, possibly counting objects more than once if they are in multiple packs.
+        pub packed_objects: u64,
+        /// The size of all packs and their indices on disk in bytes.
+        pub packs_size: u64,
+        /// The amount of packs with a reachability bitmap.
+        pub packs_with_bitmap: usize,
+        /// If `true`, there is a multi-pack-index or a multi-pack-index chain.
+        pub has_multi_pack_index: bool,
+        /// Files which aren't loose objects, packs or the files that accompany them, like packs without index or leftovers
+        /// of interrupted operations.
+        pub garbage: Vec<PathBuf>,
+        /// The size of all [garbage](Self::garbage) files in bytes.
+        pub garbage_size: u64,
+    }
+}
+
+///
+pub mod replace_object {
+    /// The error returned by [`Repository::replace_object()`](crate::Repository::replace_object()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ReferenceName(#[from] gix_validate::reference::name::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error("Object {id} can't replace itself")]
+        SameObject { id: gix_hash::ObjectId },
+        #[error("The {original_kind} {original} can't be replaced by a {replacement_kind}")]
+        KindMismatch {
+            original: gix_hash::ObjectId,
+            original_kind: gix_object::Kind,
+            replacement_kind: gix_object::Kind,
+        },
+        #[error(transparent)]
+        EditReference(#[from] crate::reference::edit::Error),
+    }
+}
+
+///
+pub mod remove_replacement {
+    /// The error returned by [`Repository::remove_replacement()`](crate::Repository::remove_replacement()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ReferenceName(#[from] gix_validate::reference::name::Error),
+        #[error(transparent)]
+        FindReference(#[from] crate::reference::find::Error),
+        #[error(transparent)]
+        EditReference(#[from] crate::reference::edit::Error),
+    }
+}
+
+///
+pub mod quarantine_objects {
+    /// The error returned by [`Repository::quarantine_objects()`](crate::Repository::quarantine_objects()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ReadOnly(#[from] crate::repository::read_only::Error),
+        #[error(transparent)]
+        Init(#[from] gix_odb::quarantine::init::Error),
+        #[error("Could not open the object database in the quarantine directory")]
+        OpenStore(#[from] std::io::Error),
+    }
+}
+
+///
+pub mod edit_alternates {
+    /// The error returned by [`Repository::add_alternate()`](crate::Repository::add_alternate()) and
+    /// [`Repository::remove_alternate()`](crate::Repository::remove_alternate()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ReadOnly(#[from] crate::repository::read_only::Error),
+        #[error(transparent)]
+        Edit(#[from] gix_odb::alternate::edit::Error),
+    }
+}
+
+///
+pub mod repo_relative_path {
+    use std::path::PathBuf;
+
+    use crate::bstr::BString;
+
+    /// The error returned by [`Repository::repo_relative_path()`](crate::Repository::repo_relative_path()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("A working tree is required to turn paths into paths relative to it")]
+        MissingWorkDir,
+        #[error("'{}' is outside of the working tree", path.display())]
+        OutsideWorktree { path: PathBuf },
+        #[error("'{}' has more '..' components than there are directories to leave", path.display())]
+        BeyondRoot { path: PathBuf },
+        #[error("'{}' is beyond the symbolic link at '{symlink}'", path.display())]
+        BeyondSymlink { path: PathBuf, symlink: BString },
+        #[error("'{}' can't be represented as path in the repository as it isn't valid UTF-8", path.display())]
+        IllformedUtf8 { path: PathBuf },
+        #[error(transparent)]
+        RealPath(#[from] gix_path::realpath::Error),
+        #[error(transparent)]
+        FilesystemOptions(#[from] crate::config::boolean::Error),
+    }
+}
+
 ///
 #[cfg(feature = "attributes")]
 pub mod pathspec_defaults_ignore_case {
@@ -91,6 +268,21 @@ pub mod pathspec_defaults_ignore_case {
     }
 }
 
+///
+pub mod edit_tree {
+    /// The error returned by [`Repository::edit_tree()`][crate::Repository::edit_tree()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        IntoTree(#[from] crate::object::try_into::Error),
+        #[error(transparent)]
+        Decode(#[from] gix_object::decode::Error),
+    }
+}
+
 ///
 #[cfg(feature = "index")]
 pub mod index_or_load_from_head {
@@ -109,6 +301,47 @@ pub mod index_or_load_from_head {
     }
 }
 
+///
+#[cfg(feature = "index")]
+pub mod read_tree {
+    use crate::bstr::BString;
+
+    /// Options for use with [`Repository::read_tree()`](crate::Repository::read_tree()).
+    #[derive(Debug, Default, Clone)]
+    pub struct Options {
+        /// If set, keep all entries of the current index and add the entries of the tree below this directory, similar to
+        /// `git read-tree --prefix=<prefix>`.
+        ///
+        /// Entries that are already at or below `prefix` are never overwritten, and an error is returned instead.
+        /// The same is true if `prefix` is empty or consists only of slashes.
+        pub prefix: Option<BString>,
+    }
+
+    /// The error returned by [`Repository::read_tree()`](crate::Repository::read_tree()),
+    /// [`Repository::read_tree_two_way()`](crate::Repository::read_tree_two_way())
+    /// and [`Repository::read_tree_three_way()`](crate::Repository::read_tree_three_way()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        PeelToTree(#[from] crate::object::peel::to_kind::Error),
+        #[error(transparent)]
+        TraverseTree(#[from] gix_traverse::tree::breadthfirst::Error),
+        #[error("The prefix to read the tree into must not be empty")]
+        EmptyPrefix,
+        #[error("The index already has entries at or below '{prefix}'")]
+        PrefixInUse { prefix: BString },
+        #[error("The index has unresolved conflicts at '{rela_path}' which have to be resolved first")]
+        Unmerged { rela_path: BString },
+        #[error("Staged changes at {} path(s) would be overwritten by the merge", rela_paths.len())]
+        LocalChanges { rela_paths: Vec<BString> },
+    }
+}
+
 ///
 #[cfg(feature = "worktree-stream")]
 pub mod worktree_stream {
@@ -124,6 +357,10 @@ pub mod worktree_stream {
         AttributesCache(#[from] crate::config::attribute_stack::Error),
         #[error(transparent)]
         FilterPipeline(#[from] crate::filter::pipeline::options::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        PrettyFormat(#[from] crate::object::commit::pretty_format::Error),
         #[error("Needed {id} to be a tree to turn into a workspace stream, got {actual}")]
         NotATree {
             id: gix_hash::ObjectId,
@@ -138,3 +375,21 @@ pub mod worktree_archive {
     /// The error returned by [`Repository::worktree_archive()`][crate::Repository::worktree_archive()].
     pub type Error = gix_archive::Error;
 }
+
+///
+#[cfg(feature = "worktree-archive")]
+pub mod archive {
+    /// The error returned by [`Repository::archive()`][crate::Repository::archive()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        PeelToCommit(#[from] crate::object::peel::to_kind::Error),
+        #[error(transparent)]
+        WorktreeStream(#[from] crate::repository::worktree_stream::Error),
+        #[error(transparent)]
+        Archive(#[from] gix_archive::Error),
+    }
+}
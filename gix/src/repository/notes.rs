@@ -0,0 +1,25 @@
+use std::borrow::Cow;
+
+use crate::{
+    config::tree::{Core, Key},
+    notes,
+};
+
+impl crate::Repository {
+    /// Obtain a platform to read, write and merge the notes attached to objects, similar to `git notes`.
+    ///
+    /// The notes reference is read from `core.notesRef`, which can be overridden with `GIT_NOTES_REF`,
+    /// and defaults to `refs/notes/commits`.
+    pub fn notes(&self) -> Result<notes::Platform<'_>, notes::init::Error> {
+        let notes_ref = self
+            .config
+            .resolved
+            .string_filter_by_key(
+                Core::NOTES_REF.logical_name().as_str(),
+                &mut self.filter_config_section(),
+            )
+            .map_or_else(|| "refs/notes/commits".into(), Cow::into_owned)
+            .try_into()?;
+        Ok(notes::Platform { repo: self, notes_ref })
+    }
+}
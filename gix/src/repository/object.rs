@@ -11,7 +11,174 @@ use gix_ref::{
 };
 use smallvec::SmallVec;
 
-use crate::{commit, ext::ObjectIdExt, object, tag, Blob, Id, Object, Reference, Tree};
+use crate::{
+    bstr::BString,
+    commit,
+    config::{
+        cache::util::ApplyLeniencyDefault,
+        tree::{Commit, Key},
+    },
+    ext::ObjectIdExt,
+    gpg::sign::Signer,
+    object, tag, Blob, Id, Object, Reference, Tree,
+};
+
+/// Statistics
+impl crate::Repository {
+    /// Count loose objects and packs along with the space they take on disk, and find files that don't belong into the
+    /// object database, similar to `git count-objects -v`.
+    ///
+    /// This is useful to decide if maintenance is needed. Note that only the local object database is considered,
+    /// but not its alternates, and that sizes are the lengths of files instead of the disk space they use.
+    pub fn objects_stats(
+        &self,
+    ) -> Result<crate::repository::objects_stats::Outcome, crate::repository::objects_stats::Error> {
+        let objects_dir = self.objects.store_ref().path();
+        let mut out = crate::repository::objects_stats::Outcome::default();
+        let mut loose_ids = Vec::new();
+        for entry in std::fs::read_dir(objects_dir)? {
+            let entry = entry?;
+            let fan_out = entry.file_name();
+            let is_fan_out = fan_out.len() == 2
+                && fan_out
+                    .to_str()
+                    .map_or(false, |name| name.bytes().all(|b| b.is_ascii_hexdigit()));
+            if !is_fan_out || !entry.file_type()?.is_dir() {
+                continue;
+            }
+            for entry in std::fs::read_dir(entry.path())? {
+                let entry = entry?;
+                let len = entry.metadata()?.len();
+                let name = entry.file_name();
+                let id = name
+                    .to_str()
+                    .filter(|name| name.len() == self.object_hash().len_in_hex() - 2)
+                    .and_then(|name| {
+                        ObjectId::from_hex(format!("{}{name}", fan_out.to_string_lossy()).as_bytes()).ok()
+                    });
+                match id {
+                    Some(id) => {
+                        out.loose_objects += 1;
+                        out.loose_size += len;
+                        loose_ids.push(id);
+                    }
+                    None => {
+                        out.garbage.push(entry.path());
+                        out.garbage_size += len;
+                    }
+                }
+            }
+        }
+
+        let pack_dir = objects_dir.join("pack");
+        let mut indices = Vec::new();
+        let entries = match std::fs::read_dir(&pack_dir) {
+            Ok(entries) => entries.collect::<Result<Vec<_>, _>>()?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err.into()),
+        };
+        for entry in entries {
+            let path = entry.path();
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with("multi-pack-index") {
+                out.has_multi_pack_index = true;
+                continue;
+            }
+            let len = entry.metadata()?.len();
+            let has_sibling = |extension: &str| path.with_extension(extension).is_file();
+            let is_accounted_for = name.starts_with("pack-")
+                && match path.extension().and_then(|ext| ext.to_str()) {
+                    Some("pack") if has_sibling("idx") => {
+                        out.packs += 1;
+                        out.packs_size += len;
+                        true
+                    }
+                    Some("idx") if has_sibling("pack") => {
+                        out.packs_size += len;
+                        indices.push(gix_pack::index::File::at(&path, self.object_hash())?);
+                        true
+                    }
+                    Some("bitmap") if has_sibling("pack") => {
+                        out.packs_with_bitmap += 1;
+                        true
+                    }
+                    Some("keep" | "promisor" | "mtimes" | "rev") => has_sibling("pack"),
+                    _ => false,
+                };
+            if !is_accounted_for {
+                out.garbage.push(path);
+                out.garbage_size += len;
+            }
+        }
+        out.packed_objects = indices.iter().map(|index| u64::from(index.num_objects())).sum();
+        out.prune_packable = loose_ids
+            .iter()
+            .filter(|id| indices.iter().any(|index| index.lookup(id).is_some()))
+            .count();
+        out.garbage.sort();
+        Ok(out)
+    }
+}
+
+/// Quarantine
+impl crate::Repository {
+    /// Create a new quarantine directory within our objects directory and return it along with a clone of this repository
+    /// which writes all new objects into it, while still seeing all objects of this repository.
+    ///
+    /// Objects written through the returned repository, which includes packs received when fetching, remain invisible
+    /// to this repository until the quarantine is [migrated](gix_odb::Quarantine::migrate()), which typically happens
+    /// once they were validated. Dropping the quarantine discards all objects in it.
+    ///
+    /// Note that only objects are quarantined, and references of both repositories remain shared.
+    pub fn quarantine_objects(
+        &self,
+    ) -> Result<(crate::Repository, gix_odb::Quarantine), crate::repository::quarantine_objects::Error> {
+        self.assure_writable("create an object quarantine")?;
+        let quarantine = gix_odb::Quarantine::new(self.objects.store_ref().path())?;
+        let store = quarantine.open_store_like(self.objects.store_ref())?;
+        let mut repo = self.clone();
+        repo.objects = gix_features::threading::OwnShared::new(store).to_handle().into();
+        crate::repository::init::setup_objects(&mut repo.objects, &repo.config);
+        Ok((repo, quarantine))
+    }
+}
+
+/// Alternates
+impl crate::Repository {
+    /// Add `alternate` to the `info/alternates` file of our objects directory, and return `true` if it was added or `false`
+    /// if it was listed already. Relative paths are relative to our objects directory.
+    ///
+    /// See [`gix_odb::alternate::add()`] for details, and note that the new alternate is only seen once the object database
+    /// is refreshed.
+    pub fn add_alternate(
+        &self,
+        alternate: impl AsRef<std::path::Path>,
+    ) -> Result<bool, crate::repository::edit_alternates::Error> {
+        self.assure_writable("add an alternate object database")?;
+        Ok(gix_odb::alternate::add(
+            self.objects.store_ref().path(),
+            alternate.as_ref(),
+            self.options.current_dir_or_empty(),
+        )?)
+    }
+
+    /// Remove `alternate` from the `info/alternates` file of our objects directory, and return `true` if it was removed or `false`
+    /// if it wasn't listed.
+    ///
+    /// See [`gix_odb::alternate::remove()`] for details.
+    pub fn remove_alternate(
+        &self,
+        alternate: impl AsRef<std::path::Path>,
+    ) -> Result<bool, crate::repository::edit_alternates::Error> {
+        self.assure_writable("remove an alternate object database")?;
+        Ok(gix_odb::alternate::remove(
+            self.objects.store_ref().path(),
+            alternate.as_ref(),
+            self.options.current_dir_or_empty(),
+        )?)
+    }
+}
 
 /// Methods related to object creation.
 impl crate::Repository {
@@ -140,15 +307,23 @@ impl crate::Repository {
     }
 
     fn write_object_inner(&self, buf: &[u8], kind: gix_object::Kind) -> Result<Id<'_>, object::write::Error> {
+        self.assure_writable("write objects")
+            .map_err(|err| object::write::Error(err.into()))?;
         let oid = gix_object::compute_hash(self.object_hash(), kind, buf);
-        if self.objects.exists(&oid) {
-            return Ok(oid.attach(self));
+        if !self.freshen_existing_object(&oid) {
+            self.objects.write_buf(kind, buf)?;
         }
+        self.record_compat_id(&oid, kind, buf)
+            .map_err(|err| object::write::Error(err.into()))?;
+        Ok(oid.attach(self))
+    }
 
-        self.objects
-            .write_buf(kind, buf)
-            .map(|oid| oid.attach(self))
-            .map_err(Into::into)
+    /// Return `true` if the object with `oid` exists and doesn't have to be written, after setting the modification time of
+    /// its loose object or pack to now so that concurrent pruning doesn't remove it before it becomes reachable.
+    ///
+    /// Objects that can't be freshened, like those in cruft packs, are written again as loose objects, which has the same effect.
+    fn freshen_existing_object(&self, oid: &gix_hash::oid) -> bool {
+        self.objects.exists(oid) && self.objects.freshen(oid).unwrap_or(false)
     }
 
     /// Write a blob from the given `bytes`.
@@ -157,43 +332,84 @@ impl crate::Repository {
     /// pre-hashing the data, and checking if the object is already present.
     #[momo]
     pub fn write_blob(&self, bytes: impl AsRef<[u8]>) -> Result<Id<'_>, object::write::Error> {
+        self.assure_writable("write objects")
+            .map_err(|err| object::write::Error(err.into()))?;
         let bytes = bytes.as_ref();
-        let oid = gix_object::compute_hash(self.object_hash(), gix_object::Kind::Blob, bytes);
-        if self.objects.exists(&oid) {
-            return Ok(oid.attach(self));
-        }
-        self.objects
-            .write_buf(gix_object::Kind::Blob, bytes)
-            .map_err(Into::into)
-            .map(|oid| oid.attach(self))
+        self.write_object_inner(bytes, gix_object::Kind::Blob)
     }
 
-    /// Write a blob from the given `Read` implementation.
+    /// Write a blob from the given `Read` implementation, starting at its current position.
     ///
-    /// Note that we hash the object in memory to avoid storing objects that are already present. That way,
+    /// Note that we hash the object while reading it a first time to avoid storing objects that are already present. That way,
     /// we avoid writing duplicate objects using slow disks that will eventually have to be garbage collected.
+    /// Then the stream is rewound to write it, so the data is never held in memory in its entirety.
     ///
-    /// If that is prohibitive, use the object database directly.
+    /// If reading the data twice is prohibitive, use [`write_blob_stream_with_size()`](Self::write_blob_stream_with_size()).
     pub fn write_blob_stream(
         &self,
         mut bytes: impl std::io::Read + std::io::Seek,
     ) -> Result<Id<'_>, object::write::Error> {
-        let mut buf = self.shared_empty_buf();
-        std::io::copy(&mut bytes, buf.deref_mut()).expect("write to memory works");
+        use std::io::SeekFrom;
+        self.assure_writable("write objects")
+            .map_err(|err| object::write::Error(err.into()))?;
+        let io_err = |err: std::io::Error| object::write::Error(err.into());
+        let start = bytes.stream_position().map_err(io_err)?;
+        let size = bytes.seek(SeekFrom::End(0)).map_err(io_err)? - start;
+        bytes.seek(SeekFrom::Start(start)).map_err(io_err)?;
 
-        self.write_blob_stream_inner(&buf)
+        let oid = gix_object::compute_stream_hash(
+            self.object_hash(),
+            gix_object::Kind::Blob,
+            &mut bytes,
+            size,
+            &mut gix_features::progress::Discard,
+            &std::sync::atomic::AtomicBool::default(),
+        )
+        .map_err(io_err)?;
+        if !self.freshen_existing_object(&oid) {
+            bytes.seek(SeekFrom::Start(start)).map_err(io_err)?;
+            self.objects.write_stream(gix_object::Kind::Blob, size, &mut bytes)?;
+        }
+        self.record_compat_id_with(&oid, |map| {
+            bytes.seek(SeekFrom::Start(start))?;
+            Ok(gix_object::compute_stream_hash(
+                map.compat_hash(),
+                gix_object::Kind::Blob,
+                &mut bytes,
+                size,
+                &mut gix_features::progress::Discard,
+                &std::sync::atomic::AtomicBool::default(),
+            )?)
+        })
+        .map_err(|err| object::write::Error(err.into()))?;
+        Ok(oid.attach(self))
     }
 
-    fn write_blob_stream_inner(&self, buf: &[u8]) -> Result<Id<'_>, object::write::Error> {
-        let oid = gix_object::compute_hash(self.object_hash(), gix_object::Kind::Blob, buf);
-        if self.objects.exists(&oid) {
-            return Ok(oid.attach(self));
+    /// Write a blob from `bytes`, which must yield exactly `size` bytes, hashing and compressing it while reading it only once.
+    ///
+    /// This is the way to write blobs of any size from streams that can't be rewound, but as the object id isn't known
+    /// upfront, the blob is written even if it is already present.
+    pub fn write_blob_stream_with_size(
+        &self,
+        bytes: impl std::io::Read,
+        size: u64,
+    ) -> Result<Id<'_>, object::write::Error> {
+        self.assure_writable("write objects")
+            .map_err(|err| object::write::Error(err.into()))?;
+        let mut bytes = CompatHashingReader {
+            inner: bytes,
+            hasher: self.compat_object_hash().map(|kind| {
+                let mut hasher = gix_features::hash::hasher(kind);
+                hasher.update(&gix_object::encode::loose_header(gix_object::Kind::Blob, size));
+                hasher
+            }),
+        };
+        let oid = self.objects.write_stream(gix_object::Kind::Blob, size, &mut bytes)?;
+        if let Some(hasher) = bytes.hasher {
+            self.record_compat_id_with(&oid, |_| Ok(hasher.digest()))
+                .map_err(|err| object::write::Error(err.into()))?;
         }
-
-        self.objects
-            .write_buf(gix_object::Kind::Blob, buf)
-            .map_err(Into::into)
-            .map(|oid| oid.attach(self))
+        Ok(oid.attach(self))
     }
 
     /// Create a tag reference named `name` (without `refs/tags/` prefix) pointing to a newly created tag object
@@ -223,6 +439,77 @@ impl crate::Repository {
         self.tag_reference(name, tag_id, constraint).map_err(Into::into)
     }
 
+    /// Create a tag named `name` (without `refs/tags/` prefix) pointing to `target` with `message`, configured by `options`,
+    /// and return the newly created reference.
+    ///
+    /// Unless the tag is [lightweight](tag::create::Options::lightweight), a tag object is written first, with its target kind
+    /// looked up in the object database and the tagger defaulting to the configured committer.
+    /// The name of the tag and whether it already exists are checked before any object is written, so failures don't leave
+    /// objects behind unnecessarily, while the reference is only created if it's still in the expected state.
+    pub fn create_tag(
+        &self,
+        name: impl AsRef<str>,
+        target: impl Into<ObjectId>,
+        message: impl AsRef<str>,
+        options: tag::create::Options<'_>,
+    ) -> Result<Reference<'_>, tag::create::Error> {
+        let name = name.as_ref();
+        let target = target.into();
+        let full_name: FullName = format!("refs/tags/{name}").try_into()?;
+        let constraint = if options.force {
+            PreviousValue::Any
+        } else {
+            if self.try_find_reference(full_name.as_ref())?.is_some() {
+                return Err(tag::create::Error::Exists { name: name.into() });
+            }
+            PreviousValue::MustNotExist
+        };
+        if options.lightweight {
+            return Ok(self.tag_reference(name, target, constraint)?);
+        }
+
+        let target_kind = self.find_header(target)?.kind();
+        let tagger = match options.tagger {
+            Some(tagger) => tagger,
+            None => self.committer().ok_or(tag::create::Error::TaggerMissing)??,
+        };
+        Ok(match options.sign {
+            Some(sign) => self.tag_signed(name, target, target_kind, Some(tagger), message, constraint, sign)?,
+            None => self.tag(name, target, target_kind, Some(tagger), message, constraint)?,
+        })
+    }
+
+    /// Like [`tag(…)`][crate::Repository::tag()], but calls `sign` with the exact bytes to sign and
+    /// appends the signature it returns to the tag object.
+    ///
+    /// This allows to produce signatures with external programs or signing services, like the [configured signer](crate::Repository::signer()),
+    /// while any error they return aborts the operation before anything is written.
+    #[momo]
+    #[allow(clippy::too_many_arguments)]
+    pub fn tag_signed(
+        &self,
+        name: impl AsRef<str>,
+        target: impl AsRef<gix_hash::oid>,
+        target_kind: gix_object::Kind,
+        tagger: Option<gix_actor::SignatureRef<'_>>,
+        message: impl AsRef<str>,
+        constraint: PreviousValue,
+        sign: impl FnOnce(&[u8]) -> Result<BString, Box<dyn std::error::Error + Send + Sync + 'static>>,
+    ) -> Result<Reference<'_>, tag::Error> {
+        let mut tag = gix_object::Tag {
+            target: target.as_ref().into(),
+            target_kind,
+            name: name.as_ref().into(),
+            tagger: tagger.map(|t| t.to_owned()),
+            message: message.as_ref().into(),
+            pgp_signature: None,
+        };
+        let signature = sign(&tag.signing_payload()?).map_err(tag::Error::Sign)?;
+        tag.set_signature(signature);
+        let tag_id = self.write_object(&tag)?;
+        self.tag_reference(name, tag_id, constraint).map_err(Into::into)
+    }
+
     /// Similar to [`commit(…)`][crate::Repository::commit()], but allows to create the commit with `committer` and `author` specified.
     ///
     /// This forces setting the commit time and author time by hand. Note that typically, committer and author are the same.
@@ -246,9 +533,42 @@ impl crate::Repository {
             message.as_ref(),
             tree.into(),
             parents.into_iter().map(Into::into).collect(),
+            None,
+        )
+    }
+
+    /// Like [`commit_as(…)`][crate::Repository::commit_as()], but calls `sign` with the exact bytes to sign
+    /// and stores the signature it returns in the `gpgsig` header of the commit.
+    ///
+    /// This allows to produce signatures with external programs or signing services, like the [configured signer](crate::Repository::signer()),
+    /// while any error they return aborts the operation before anything is written.
+    #[allow(clippy::too_many_arguments)]
+    pub fn commit_as_signed<'a, 'c, Name, E>(
+        &self,
+        committer: impl Into<gix_actor::SignatureRef<'c>>,
+        author: impl Into<gix_actor::SignatureRef<'a>>,
+        reference: Name,
+        message: impl AsRef<str>,
+        tree: impl Into<ObjectId>,
+        parents: impl IntoIterator<Item = impl Into<ObjectId>>,
+        sign: impl FnOnce(&[u8]) -> Result<BString, Box<dyn std::error::Error + Send + Sync + 'static>>,
+    ) -> Result<Id<'_>, commit::Error>
+    where
+        Name: TryInto<FullName, Error = E>,
+        commit::Error: From<E>,
+    {
+        self.commit_as_inner(
+            committer.into(),
+            author.into(),
+            reference.try_into()?,
+            message.as_ref(),
+            tree.into(),
+            parents.into_iter().map(Into::into).collect(),
+            Some(Box::new(sign)),
         )
     }
 
+    #[allow(clippy::too_many_arguments, clippy::type_complexity)]
     fn commit_as_inner(
         &self,
         committer: gix_actor::SignatureRef<'_>,
@@ -257,6 +577,9 @@ impl crate::Repository {
         message: &str,
         tree: ObjectId,
         parents: SmallVec<[ObjectId; 1]>,
+        sign: Option<
+            Box<dyn FnOnce(&[u8]) -> Result<BString, Box<dyn std::error::Error + Send + Sync + 'static>> + '_>,
+        >,
     ) -> Result<Id<'_>, commit::Error> {
         use gix_ref::{
             transaction::{Change, RefEdit},
@@ -265,7 +588,7 @@ impl crate::Repository {
 
         // TODO: possibly use CommitRef to save a few allocations (but will have to allocate for object ids anyway.
         //       This can be made vastly more efficient though if we wanted to, so we lie in the API
-        let commit = gix_object::Commit {
+        let mut commit = gix_object::Commit {
             message: message.into(),
             tree,
             author: author.into(),
@@ -274,6 +597,10 @@ impl crate::Repository {
             parents,
             extra_headers: Default::default(),
         };
+        if let Some(sign) = sign {
+            let signature = sign(&commit.signing_payload()).map_err(commit::Error::Sign)?;
+            commit.set_signature(signature);
+        }
 
         let commit_id = self.write_object(&commit)?;
         self.edit_reference(RefEdit {
@@ -314,6 +641,8 @@ impl crate::Repository {
     /// If there is no parent, the `reference` is expected to not exist yet.
     ///
     /// The method fails immediately if a `reference` lock can't be acquired.
+    ///
+    /// If `commit.gpgSign` is `true`, the commit is signed with the [configured signer](crate::Repository::signer()).
     pub fn commit<Name, E>(
         &self,
         reference: Name,
@@ -327,9 +656,85 @@ impl crate::Repository {
     {
         let author = self.author().ok_or(commit::Error::AuthorMissing)??;
         let committer = self.committer().ok_or(commit::Error::CommitterMissing)??;
+        let sign = self
+            .config
+            .resolved
+            .boolean(Commit::GPG_SIGN.section().name(), None, Commit::GPG_SIGN.name())
+            .map(|res| Commit::GPG_SIGN.enrich_error(res))
+            .transpose()
+            .with_lenient_default(self.config.lenient_config)?
+            .unwrap_or_default();
+        if sign {
+            let mut signer = self.signer()?;
+            return self.commit_as_signed(committer, author, reference, message, tree, parents, |data| {
+                signer.sign(data)
+            });
+        }
         self.commit_as(committer, author, reference, message, tree, parents)
     }
 
+    /// Like [`commit(…)`][crate::Repository::commit()], but runs the `pre-commit`, `prepare-commit-msg`, `commit-msg` and
+    /// `post-commit` [hooks](crate::Repository::invoke_hook()) if they are installed, like `git commit -m <message>` would.
+    ///
+    /// `pre-commit` runs first, and the `prepare-commit-msg` and `commit-msg` hooks receive the path to a file with `message`
+    /// which they may alter to change the message of the commit. If either of them fails, the commit isn't created.
+    /// Once the commit was created and `reference` was updated, `post-commit` runs, but it can't affect the outcome.
+    ///
+    /// Note that `pre-commit` typically validates the index, which isn't used to create the commit.
+    pub fn commit_with_hooks<Name, E>(
+        &self,
+        reference: Name,
+        message: impl AsRef<str>,
+        tree: impl Into<ObjectId>,
+        parents: impl IntoIterator<Item = impl Into<ObjectId>>,
+    ) -> Result<Id<'_>, commit::Error>
+    where
+        Name: TryInto<FullName, Error = E>,
+        commit::Error: From<E>,
+    {
+        fn run_hook(
+            repo: &crate::Repository,
+            name: &'static str,
+            invocation: crate::hook::Invocation,
+        ) -> Result<(), commit::Error> {
+            match repo.invoke_hook(invocation)? {
+                Some(outcome) if !outcome.is_success() => Err(commit::Error::HookRejected {
+                    name,
+                    status: outcome.status,
+                }),
+                _ => Ok(()),
+            }
+        }
+        self.assure_writable("commit")?;
+        run_hook(self, "pre-commit", crate::hook::Invocation::pre_commit())?;
+
+        let mut message = message.as_ref().to_owned();
+        let mut has_message_hook = false;
+        for name in ["prepare-commit-msg", "commit-msg"] {
+            has_message_hook |= self.hook_path(name).map_err(crate::hook::run::Error::from)?.is_some();
+        }
+        if has_message_hook {
+            let message_path = self.git_dir().join("COMMIT_EDITMSG");
+            let message_file_err = |source| commit::Error::CommitMessageFile {
+                source,
+                path: message_path.clone(),
+            };
+            std::fs::write(&message_path, &message).map_err(message_file_err)?;
+            run_hook(
+                self,
+                "prepare-commit-msg",
+                crate::hook::Invocation::prepare_commit_msg(&message_path, Some(crate::hook::MessageSource::Message)),
+            )?;
+            run_hook(self, "commit-msg", crate::hook::Invocation::commit_msg(&message_path))?;
+            message = std::fs::read_to_string(&message_path).map_err(message_file_err)?;
+        }
+
+        let id = self.commit(reference, message, tree, parents)?;
+        // Just like in `git`, the outcome of this hook doesn't affect the commit.
+        self.invoke_hook(crate::hook::Invocation::post_commit()).ok();
+        Ok(id)
+    }
+
     /// Return an empty tree object, suitable for [getting changes](Tree::changes()).
     ///
     /// Note that the returned object is special and doesn't necessarily physically exist in the object database.
@@ -340,6 +745,15 @@ impl crate::Repository {
             .into_tree()
     }
 
+    /// Return an [editor](crate::object::tree::Editor) to change the tree with `id`, which is the starting point to write
+    /// new trees with entries inserted, updated or removed at arbitrary paths.
+    pub fn edit_tree(
+        &self,
+        id: impl Into<ObjectId>,
+    ) -> Result<crate::object::tree::Editor<'_>, crate::repository::edit_tree::Error> {
+        Ok(self.find_object(id)?.try_into_tree()?.edit()?)
+    }
+
     /// Return an empty blob object.
     ///
     /// Note that the returned object is special and doesn't necessarily physically exist in the object database.
@@ -352,3 +766,19 @@ impl crate::Repository {
         }
     }
 }
+
+/// A reader which hashes everything it reads with the compatibility hash, if there is one.
+struct CompatHashingReader<R> {
+    inner: R,
+    hasher: Option<gix_features::hash::Hasher>,
+}
+
+impl<R: std::io::Read> std::io::Read for CompatHashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let bytes_read = self.inner.read(buf)?;
+        if let Some(hasher) = self.hasher.as_mut() {
+            hasher.update(&buf[..bytes_read]);
+        }
+        Ok(bytes_read)
+    }
+}
@@ -0,0 +1,114 @@
+use std::borrow::Cow;
+
+use crate::{
+    bstr::BStr,
+    config::tree::{Extensions, Remote},
+};
+
+/// Partial clones
+impl crate::Repository {
+    /// Return the name of the remote that promises to provide objects missing in this partial clone, as configured
+    /// in `extensions.partialClone`, or `None` if this isn't a partial clone.
+    pub fn promisor_remote_name(&self) -> Option<Cow<'_, BStr>> {
+        self.config.resolved.string_filter(
+            "extensions",
+            None,
+            Extensions::PARTIAL_CLONE.name,
+            &mut self.filter_config_section(),
+        )
+    }
+
+    /// Return `true` if the remote named `name` is a promisor remote, either because it's the
+    /// [promisor remote of this partial clone](Self::promisor_remote_name()) or as `remote.<name>.promisor` is set.
+    pub fn is_promisor_remote(&self, name: &BStr) -> bool {
+        self.promisor_remote_name()
+            .map_or(false, |promisor| promisor.as_ref() == name)
+            || self
+                .config
+                .resolved
+                .boolean_filter(
+                    "remote",
+                    Some(name),
+                    Remote::PROMISOR.name,
+                    &mut self.filter_config_section(),
+                )
+                .and_then(Result::ok)
+                .unwrap_or(false)
+    }
+}
+
+#[cfg(feature = "blocking-network-client")]
+mod lazy_fetch {
+    use std::sync::atomic::AtomicBool;
+
+    use gix_hash::ObjectId;
+
+    use crate::{promisor, remote};
+
+    /// Partial clones
+    impl crate::Repository {
+        /// Fetch all objects in `ids` that don't exist locally from the [promisor remote](Self::promisor_remote_name())
+        /// in a single round-trip, and return the amount of objects that were requested.
+        ///
+        /// Nothing happens if all objects exist, otherwise the resulting pack is marked as coming from a promisor remote.
+        pub fn fetch_missing_objects(
+            &self,
+            ids: impl IntoIterator<Item = ObjectId>,
+        ) -> Result<usize, promisor::fetch::Error> {
+            let mut missing: Vec<_> = ids.into_iter().filter(|id| !self.has_object(id)).collect();
+            missing.sort();
+            missing.dedup();
+            if missing.is_empty() {
+                return Ok(0);
+            }
+            self.fetch_from_promisor(&missing)?;
+            Ok(missing.len())
+        }
+
+        /// Have all objects that are missing when looked up be fetched from the [promisor remote](Self::promisor_remote_name())
+        /// on demand, for all handles to the object database of this repository. Return `false` if this isn't a partial clone,
+        /// so there is nothing to do.
+        ///
+        /// Note that each missing object is fetched individually, and that existence checks like [`has_object()`](Self::has_object())
+        /// don't fetch objects.
+        pub fn enable_lazy_fetch(&self) -> Result<bool, promisor::enable::Error> {
+            if self.promisor_remote_name().is_none() {
+                return Ok(false);
+            }
+            // Fetch through a separate instance to not look up missing objects through our own object database,
+            // which would fetch recursively, and to not keep our object database alive forever.
+            let git_dir = self.git_dir().to_owned();
+            let options = self.open_options().clone();
+            self.objects.store_ref().set_promisor(move |ids| {
+                let repo = crate::open_opts(&git_dir, options.clone()).map_err(promisor::enable::Error::from)?;
+                repo.fetch_from_promisor(ids)
+                    .map_err(|err| Box::new(promisor::enable::Error::from(err)) as _)
+            });
+            Ok(true)
+        }
+
+        /// Stop [lazy fetching](Self::enable_lazy_fetch()) of missing objects.
+        pub fn disable_lazy_fetch(&self) {
+            self.objects.store_ref().unset_promisor();
+        }
+
+        fn fetch_from_promisor(&self, ids: &[ObjectId]) -> Result<(), promisor::fetch::Error> {
+            let name = self
+                .promisor_remote_name()
+                .ok_or(promisor::fetch::Error::NoPromisorRemote)?;
+            let mut remote = self
+                .find_remote(name.as_ref())?
+                .with_fetch_tags(remote::fetch::Tags::None);
+            remote.replace_refspecs(
+                ids.iter().map(|id| crate::bstr::BString::from(id.to_string())),
+                remote::Direction::Fetch,
+            )?;
+            remote
+                .connect(remote::Direction::Fetch)?
+                .prepare_fetch(crate::progress::Discard, Default::default())?
+                .with_filter(Some(promisor::LAZY_FETCH_FILTER.into()))
+                .receive(crate::progress::Discard, &AtomicBool::default())?;
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use std::path::PathBuf;
+
+/// The error returned when trying to write to a repository that was [opened read-only](crate::open::Options::read_only()).
+#[derive(Debug, thiserror::Error)]
+#[error("Refusing to {action} as the repository at \"{}\" was opened in read-only mode", git_dir.display())]
+pub struct Error {
+    /// A description of the operation that would have written to the repository.
+    pub action: &'static str,
+    /// The `.git` directory of the repository.
+    pub git_dir: PathBuf,
+}
+
+impl crate::Repository {
+    /// Return `true` if this repository was [opened in read-only mode](crate::open::Options::read_only()), which means
+    /// no operation is permitted to write to it.
+    pub fn is_read_only(&self) -> bool {
+        self.options.read_only
+    }
+
+    /// Fail with an error describing `action` if this repository is read-only.
+    pub(crate) fn assure_writable(&self, action: &'static str) -> Result<(), Error> {
+        if self.is_read_only() {
+            return Err(Error {
+                action,
+                git_dir: self.git_dir().to_owned(),
+            });
+        }
+        Ok(())
+    }
+}
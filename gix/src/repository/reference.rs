@@ -147,6 +147,7 @@ impl crate::Repository {
         &self,
         edits: impl IntoIterator<Item = RefEdit>,
     ) -> Result<Vec<RefEdit>, reference::edit::Error> {
+        self.assure_writable("edit references")?;
         let (file_lock_fail, packed_refs_lock_fail) = self.config.lock_timeout()?;
         self.refs
             .transaction()
@@ -244,6 +245,14 @@ impl crate::Repository {
         })
     }
 
+    /// Return a watcher to learn about references that change on disk, for instance to invalidate caches of long-running services
+    /// without having to iterate all references repeatedly.
+    ///
+    /// With the `watch-references` feature, filesystem events are used as configured in `options`, otherwise references are polled.
+    pub fn watch_references(&self, options: reference::watch::Options) -> std::io::Result<reference::watch::Watcher> {
+        reference::watch::Watcher::new(self.git_dir(), self.common_dir(), options)
+    }
+
     /// Try to find the reference named `name`, like `main`, `heads/branch`, `HEAD` or `origin/other`, and return it.
     ///
     /// Otherwise return `None` if the reference wasn't found.
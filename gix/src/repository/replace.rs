@@ -0,0 +1,97 @@
+use std::borrow::Cow;
+
+use gix_hash::ObjectId;
+use gix_ref::transaction::PreviousValue;
+
+use crate::{
+    bstr::{BString, ByteVec},
+    config::tree::{gitoxide, Key},
+    repository::{remove_replacement, replace_object},
+    Reference,
+};
+
+/// Replacement objects
+impl crate::Repository {
+    /// Return all replacements as pairs of `(original, replacement)`, sorted by the original id, which are used when looking up
+    /// the original object.
+    ///
+    /// Note that replacements are read when opening the repository, so [new](Self::replace_object()) or
+    /// [removed](Self::remove_replacement()) replacements only take effect in repositories opened afterwards.
+    pub fn replacements(&self) -> impl Iterator<Item = (ObjectId, ObjectId)> + '_ {
+        self.objects.store_ref().replacements()
+    }
+
+    /// Return `true` if there is at least one [replacement](Self::replacements()) in use.
+    pub fn has_replacements(&self) -> bool {
+        self.replacements().next().is_some()
+    }
+
+    /// Make `replacement` stand in for `original` whenever `original` is looked up, similar to `git replace`, and return
+    /// the reference that records the replacement. It's named after `original` and placed below `refs/replace/`, or
+    /// below `gitoxide.objects.replaceRefBase` if set.
+    ///
+    /// `constraint` controls what happens if `original` was already replaced. Both objects must exist and be of the same kind.
+    ///
+    /// Note that the replacement only takes effect in repositories opened afterwards, and only if `core.useReplaceRefs` isn't `false`.
+    pub fn replace_object(
+        &self,
+        original: impl Into<ObjectId>,
+        replacement: impl Into<ObjectId>,
+        constraint: PreviousValue,
+    ) -> Result<Reference<'_>, replace_object::Error> {
+        let (original, replacement) = (original.into(), replacement.into());
+        if original == replacement {
+            return Err(replace_object::Error::SameObject { id: original });
+        }
+        let original_kind = self.find_header(original)?.kind();
+        let replacement_kind = self.find_header(replacement)?.kind();
+        if original_kind != replacement_kind {
+            return Err(replace_object::Error::KindMismatch {
+                original,
+                original_kind,
+                replacement_kind,
+            });
+        }
+        Ok(self.reference(
+            self.replacement_ref_name(&original)?,
+            replacement,
+            constraint,
+            format!("replace: {original} with {replacement}"),
+        )?)
+    }
+
+    /// Remove the replacement of `original`, similar to `git replace --delete`, and return the id of the object that replaced it,
+    /// or `None` if there was no replacement.
+    pub fn remove_replacement(
+        &self,
+        original: impl Into<ObjectId>,
+    ) -> Result<Option<ObjectId>, remove_replacement::Error> {
+        let name = self.replacement_ref_name(&original.into())?;
+        let reference = match self.try_find_reference(name.as_ref())? {
+            Some(reference) => reference,
+            None => return Ok(None),
+        };
+        let replacement = reference.target().try_id().map(ToOwned::to_owned);
+        reference.delete()?;
+        Ok(replacement)
+    }
+
+    fn replacement_ref_name(
+        &self,
+        original: &gix_hash::oid,
+    ) -> Result<gix_ref::FullName, gix_validate::reference::name::Error> {
+        let mut name: BString = self
+            .config
+            .resolved
+            .string_filter_by_key(
+                gitoxide::Objects::REPLACE_REF_BASE.logical_name().as_str(),
+                &mut self.filter_config_section(),
+            )
+            .map_or_else(|| "refs/replace/".into(), Cow::into_owned);
+        if !name.ends_with(b"/") {
+            name.push_byte(b'/');
+        }
+        name.push_str(original.to_hex().to_string());
+        name.try_into()
+    }
+}
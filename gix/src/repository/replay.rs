@@ -0,0 +1,92 @@
+use std::collections::HashSet;
+
+use gix_hash::ObjectId;
+use gix_object::tree::EntryMode;
+
+use crate::replay::{Error, Outcome};
+
+impl crate::Repository {
+    /// Replay all commits reachable from `tip` but not from `upstream` onto `onto`, oldest first, similar to
+    /// `git replay --onto <onto> <upstream>..<tip>`, without touching the worktree, the index or any reference.
+    ///
+    /// Each commit is replayed by merging the changes it introduced relative to its parent into the tree of the previously replayed
+    /// commit, which conflicts if the same path was changed differently on both sides as the content of files isn't merged.
+    /// The replayed commits retain their author and message, while the committer is set to the configured identity.
+    /// Signatures are removed as they would be invalid.
+    ///
+    /// Use [`edit_reference()`](Self::edit_reference()) with the returned tip to update a branch if the commits could be replayed.
+    pub fn replay(
+        &self,
+        onto: impl Into<ObjectId>,
+        upstream: impl Into<ObjectId>,
+        tip: impl Into<ObjectId>,
+    ) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::replay()");
+        let committer: gix_actor::Signature = self.committer().ok_or(Error::CommitterMissing)??.into();
+        let onto = self.find_object(onto)?.peel_to_kind(gix_object::Kind::Commit)?.id;
+        let upstream = self.find_object(upstream)?.peel_to_kind(gix_object::Kind::Commit)?.id;
+        let tip = self.find_object(tip)?.peel_to_kind(gix_object::Kind::Commit)?.id;
+
+        let mut hidden = HashSet::new();
+        for info in self.rev_walk(Some(upstream)).all()? {
+            hidden.insert(info?.id);
+        }
+        let mut to_replay = Vec::new();
+        for info in self.rev_walk(Some(tip)).all()? {
+            let info = info?;
+            if hidden.contains(&info.id) {
+                continue;
+            }
+            if info.parent_ids.len() > 1 {
+                return Err(Error::MergeCommit { id: info.id });
+            }
+            to_replay.push(info.id);
+        }
+
+        let mut current = onto;
+        let mut current_tree = self.find_object(onto)?.try_into_commit()?.tree_id()?.detach();
+        let mut commits = Vec::with_capacity(to_replay.len());
+        for id in to_replay.into_iter().rev() {
+            let commit = gix_object::Commit::from(self.find_object(id)?.try_into_commit()?.decode()?);
+            let base_tree = match commit.parents.first() {
+                Some(parent) => Some(self.find_object(*parent)?.try_into_commit()?.tree_id()?.detach()),
+                None => None,
+            };
+            let mut conflicts = Vec::new();
+            let merged = self
+                .merge_subtrees::<Error>(
+                    base_tree.map(|id| (id, EntryMode::Tree)),
+                    Some((current_tree, EntryMode::Tree)),
+                    Some((commit.tree, EntryMode::Tree)),
+                    &mut Default::default(),
+                    &mut conflicts,
+                )?
+                .map_or_else(|| ObjectId::empty_tree(self.object_hash()), |(id, _)| id);
+            if !conflicts.is_empty() {
+                return Ok(Outcome::Conflicts {
+                    commit: id,
+                    paths: conflicts,
+                });
+            }
+            if merged == current_tree {
+                commits.push((id, current));
+                continue;
+            }
+            let replayed = gix_object::Commit {
+                tree: merged,
+                parents: [current].into_iter().collect(),
+                committer: committer.clone(),
+                extra_headers: commit
+                    .extra_headers
+                    .into_iter()
+                    .filter(|(name, _)| name != "gpgsig" && name != "gpgsig-sha256")
+                    .collect(),
+                ..commit
+            };
+            current = self.write_object(&replayed)?.detach();
+            current_tree = merged;
+            commits.push((id, current));
+        }
+        Ok(Outcome::Replayed { tip: current, commits })
+    }
+}
@@ -47,6 +47,49 @@ impl Repository {
         )
     }
 
+    /// Return the parents that commits are assumed to have as defined by the `info/grafts` file, or `None` if there is no such file.
+    ///
+    /// Each non-empty line of the file that isn't a comment lists a commit followed by the parents it should be considered to have,
+    /// which may be none at all.
+    /// Note that [revision walks](crate::revision::walk::Platform) apply these, along with the [shallow commits](Self::shallow_commits())
+    /// which are treated as commits without parents.
+    pub fn grafts(&self) -> Result<Option<gix_traverse::commit::Grafts>, crate::shallow::grafts::Error> {
+        use crate::shallow::grafts::Error;
+        let buf = match std::fs::read(self.grafts_file()) {
+            Ok(buf) => buf,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut grafts = gix_traverse::commit::Grafts::default();
+        for (line_number, line) in buf.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(b"#") {
+                continue;
+            }
+            let mut ids = line
+                .fields_with(|b| b == ' ')
+                .filter(|field| !field.is_empty())
+                .map(|hex| {
+                    gix_hash::ObjectId::from_hex(hex).map_err(|source| Error::DecodeHash {
+                        line_number: line_number + 1,
+                        source,
+                    })
+                });
+            let commit = ids.next().expect("non-empty line has one field")?;
+            let parents = ids.collect::<Result<_, _>>()?;
+            grafts.insert(commit, parents);
+        }
+        Ok(Some(grafts))
+    }
+
+    /// Return the path to the `info/grafts` file which overrides the parents of commits.
+    ///
+    /// Note that it usually doesn't exist.
+    pub fn grafts_file(&self) -> PathBuf {
+        self.common_dir().join("info").join("grafts")
+    }
+
     /// Return the path to the `shallow` file which contains hashes, one per line, that describe commits that don't have their
     /// parents within this repository.
     ///
@@ -0,0 +1,147 @@
+use std::io::Write;
+
+use gix_index::entry::Flags;
+
+use super::checkout::Written;
+use crate::{bstr::BString, config::tree::Core, sparse, status};
+
+/// Sparse checkouts
+impl crate::Repository {
+    /// Return the path to the sparse-checkout file, which belongs to the current worktree.
+    pub fn sparse_checkout_path(&self) -> std::path::PathBuf {
+        self.git_dir().join("info").join("sparse-checkout")
+    }
+
+    /// Return the definition of the sparse checkout if `core.sparseCheckout` is enabled, in cone mode if `core.sparseCheckoutCone` is set.
+    ///
+    /// `None` is returned if sparse checkouts are disabled, or if there is no sparse-checkout file, which makes `git`
+    /// check out all files as well.
+    pub fn sparse_checkout(&self) -> Result<Option<sparse::Definition>, sparse::read::Error> {
+        if !self.config_boolean(&Core::SPARSE_CHECKOUT)? {
+            return Ok(None);
+        }
+        let path = self.sparse_checkout_path();
+        let data = match std::fs::read(&path) {
+            Ok(data) => data,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(source) => return Err(sparse::read::Error::Io { path, source }),
+        };
+        Ok(Some(sparse::Definition::from_bytes(
+            &data,
+            self.config_boolean(&Core::SPARSE_CHECKOUT_CONE)?,
+        )))
+    }
+
+    /// Write `definition` to the sparse-checkout file, enable sparse checkouts along with cone mode if `definition` uses it,
+    /// and apply it to the index and the worktree, similar to `git sparse-checkout set`.
+    ///
+    /// The configuration is written to `config.worktree` if `extensions.worktreeConfig` is enabled, or to the local configuration
+    /// file of the repository otherwise, and is also applied to this instance.
+    /// See [`reapply_sparse_checkout()`](Self::reapply_sparse_checkout()) for details on how it's applied.
+    pub fn set_sparse_checkout(
+        &mut self,
+        definition: &sparse::Definition,
+    ) -> Result<sparse::Outcome, sparse::set::Error> {
+        let _span = gix_trace::coarse!("gix::set_sparse_checkout()");
+        self.assure_writable("set the sparse checkout")?;
+        let path = self.sparse_checkout_path();
+        let io_err = |source| sparse::set::Error::Io {
+            path: path.clone(),
+            source,
+        };
+        std::fs::create_dir_all(path.parent().expect("in info directory")).map_err(io_err)?;
+        let mut lock = gix_lock::File::acquire_to_update_resource(&path, gix_lock::acquire::Fail::Immediately, None)?;
+        lock.write_all(&definition.to_bytes()).map_err(io_err)?;
+        lock.commit().map_err(|err| io_err(err.error))?;
+
+        let cone = if definition.is_cone() { "true" } else { "false" };
+        self.persist_worktree_config(&[
+            (&Core::SPARSE_CHECKOUT, "true".into()),
+            (&Core::SPARSE_CHECKOUT_CONE, cone.into()),
+        ])?;
+        Ok(self.reapply_sparse_checkout()?)
+    }
+
+    /// Disable sparse checkouts and check out all files that were excluded, similar to `git sparse-checkout disable`.
+    ///
+    /// The sparse-checkout file is left in place so that it can be enabled again later.
+    pub fn disable_sparse_checkout(&mut self) -> Result<sparse::Outcome, sparse::set::Error> {
+        let _span = gix_trace::coarse!("gix::disable_sparse_checkout()");
+        self.assure_writable("disable the sparse checkout")?;
+        self.persist_worktree_config(&[(&Core::SPARSE_CHECKOUT, "false".into())])?;
+        Ok(self.reapply_sparse_checkout()?)
+    }
+
+    /// Apply the current [sparse checkout definition](Self::sparse_checkout()) to the index and the worktree,
+    /// similar to `git sparse-checkout reapply`.
+    ///
+    /// Files that are excluded are removed from the worktree and marked with the
+    /// [`SKIP_WORKTREE`](gix_index::entry::Flags::SKIP_WORKTREE) flag in the index, unless they have local changes
+    /// or merge conflicts. Files that were excluded and are included now are written to the worktree, unless something is
+    /// in the way, which is kept and will show up as modification instead.
+    /// If sparse checkouts are disabled, all files are included.
+    pub fn reapply_sparse_checkout(&self) -> Result<sparse::Outcome, sparse::apply::Error> {
+        let _span = gix_trace::coarse!("gix::reapply_sparse_checkout()");
+        self.assure_writable("apply the sparse checkout")?;
+        let work_dir = self.work_dir().ok_or(sparse::apply::Error::MissingWorkDir)?;
+        let search = self.sparse_checkout()?.map(|definition| definition.search());
+        let mut stage = self.stage()?;
+        let dirty: std::collections::BTreeSet<BString> = self
+            .index_worktree_items(stage.index(), None, status::Submodule::Ignore, None)?
+            .0
+            .into_iter()
+            .map(|item| item.rela_path().clone())
+            .collect();
+
+        let mut outcome = sparse::Outcome::default();
+        let mut filter = stage.filter.clone();
+        let delay = self.config.filter_process_delay()?;
+        let fs = self.filesystem_options()?;
+        let mut delayed = Vec::new();
+        for (entry, rela_path) in stage.index.entries_mut_with_paths() {
+            let is_included =
+                entry.stage() != 0 || search.as_ref().map_or(true, |search| search.is_included(rela_path));
+            let is_skipped = entry.flags.contains(Flags::SKIP_WORKTREE);
+            if is_included && is_skipped {
+                entry.flags.remove(Flags::SKIP_WORKTREE);
+                if !entry.flags.intersects(Flags::INTENT_TO_ADD) {
+                    entry.flags.remove(Flags::EXTENDED);
+                }
+                let path = work_dir.join(gix_path::from_bstr(rela_path));
+                if std::fs::symlink_metadata(path).is_ok() {
+                    continue;
+                }
+                let mode = super::status::to_entry_mode(entry.mode);
+                match self.write_entry(work_dir, rela_path, entry.id, mode, &mut filter, delay, &fs)? {
+                    Written::Entry(metadata) => {
+                        entry.stat = gix_index::entry::Stat::from_fs(&metadata).map_err(|source| {
+                            crate::checkout::tree::Error::Stat {
+                                rela_path: rela_path.to_owned(),
+                                source,
+                            }
+                        })?;
+                    }
+                    Written::Submodule => {}
+                    Written::Delayed(key) => delayed.push((rela_path.to_owned(), key, mode)),
+                }
+                outcome.added.push(rela_path.to_owned());
+            } else if !is_included && !is_skipped {
+                if dirty.contains(rela_path) {
+                    outcome.kept.push(rela_path.to_owned());
+                    continue;
+                }
+                // The flag is only written to indices of version 3 and later, which requires extended flags.
+                entry.flags.insert(Flags::SKIP_WORKTREE | Flags::EXTENDED);
+                super::checkout::remove_entry(work_dir, rela_path)?;
+                outcome.removed.push(rela_path.to_owned());
+            }
+        }
+        self.write_delayed_entries(work_dir, delayed, &mut filter, &fs, |rela_path, metadata| {
+            super::checkout::update_stat(&mut stage.index, rela_path, metadata)
+        })?;
+        if !outcome.added.is_empty() || !outcome.removed.is_empty() {
+            stage.write()?;
+        }
+        Ok(outcome)
+    }
+}
@@ -0,0 +1,26 @@
+use crate::stage;
+
+impl crate::Repository {
+    /// Obtain a platform to stage files from the worktree or in-memory contents into a copy of the current index,
+    /// or into a new empty index if there is none yet.
+    ///
+    /// Nothing is persisted until [`stage::Platform::write()`] is called.
+    pub fn stage(&self) -> Result<stage::Platform<'_>, stage::init::Error> {
+        let index = gix_index::File::clone(&**self.index_or_empty()?);
+        let filter = crate::filter::Pipeline::new(
+            self,
+            self.attributes_only(
+                &index,
+                gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping,
+            )?
+            .detach(),
+        )?;
+        Ok(stage::Platform {
+            repo: self,
+            index,
+            filter,
+            buf: Vec::new(),
+            warnings: Vec::new(),
+        })
+    }
+}
@@ -0,0 +1,431 @@
+use std::{
+    collections::{btree_map::Entry, BTreeMap},
+    sync::atomic::AtomicBool,
+};
+
+use gix_diff::tree::visit;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    object::tree::diff::tracked,
+    status::{self, plumbing::index_as_worktree, Change, Item},
+    PathspecDetached,
+};
+
+impl crate::Repository {
+    /// Return options for use with [`status()`](Self::status()) that are configured like `git status` would be.
+    ///
+    /// This enables rename tracking according to `status.renames` and `status.renameLimit`, which fall back to their `diff.*`
+    /// counterparts, and recursing into submodules according to `submodule.recurse`.
+    pub fn status_options(&self) -> Result<status::Options, status::options::Error> {
+        Ok(status::Options {
+            rewrites: crate::object::tree::diff::Rewrites::try_from_status_config(
+                &self.config.resolved,
+                self.config.lenient_config,
+            )?,
+            recurse_submodules: self.submodule_recurse()?.unwrap_or_default(),
+            ..Default::default()
+        })
+    }
+
+    /// Compute the status of all paths in the worktree similar to `git status --porcelain=v2`, configured by `options`.
+    ///
+    /// This compares `HEAD^{tree}` with the index to learn about staged changes, and the index with the worktree to
+    /// find unstaged changes and conflicts. Untracked and ignored files are found by traversing the worktree, and submodules
+    /// are opened and queried recursively depending on [`options.submodules`](status::Options::submodules).
+    ///
+    /// Note that the index isn't refreshed on disk, even if stat information could be updated.
+    #[doc(alias = "git2")]
+    pub fn status(&self, options: status::Options) -> Result<status::Outcome, status::Error> {
+        let _span = gix_trace::coarse!("gix::status()");
+        let work_dir = self.work_dir().ok_or(status::Error::MissingWorkDir)?;
+        let index = self.index_or_empty()?;
+        let mut pathspec = if options.patterns.is_empty() {
+            None
+        } else {
+            Some(
+                self.pathspec(
+                    &options.patterns,
+                    true,
+                    &index,
+                    gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping,
+                )?
+                .detach()?,
+            )
+        };
+
+        let (mut changes, rewrites) = self.head_index_changes(&index, pathspec.as_mut(), options.rewrites)?;
+
+        let (worktree_items, outcome) =
+            self.index_worktree_items(&index, pathspec.clone(), options.submodules, options.thread_limit)?;
+        for item in worktree_items {
+            let (rela_path, change, submodule_status) = match item {
+                Item::Changed {
+                    rela_path,
+                    worktree,
+                    submodule,
+                    ..
+                } => (rela_path, worktree, submodule),
+                item => {
+                    changes.insert(item.rela_path().clone(), item);
+                    continue;
+                }
+            };
+            match changes.entry(rela_path) {
+                Entry::Occupied(mut entry) => match entry.get_mut() {
+                    Item::Changed {
+                        worktree, submodule, ..
+                    } => {
+                        *worktree = change;
+                        *submodule = submodule_status;
+                    }
+                    Item::Rewritten { worktree, .. } => *worktree = change,
+                    Item::Conflict { .. } | Item::Untracked { .. } | Item::Ignored { .. } => {}
+                },
+                Entry::Vacant(entry) => {
+                    let rela_path = entry.key().clone();
+                    entry.insert(Item::Changed {
+                        rela_path,
+                        index: None,
+                        worktree: change,
+                        submodule: submodule_status,
+                    });
+                }
+            }
+        }
+
+        let mut items: Vec<_> = changes.into_values().collect();
+        if options.untracked != status::Untracked::No {
+            let mut walk = status::untracked::Walk {
+                work_dir,
+                index: &index,
+                excludes: self.excludes(
+                    &index,
+                    None,
+                    gix_worktree::stack::state::ignore::Source::WorktreeThenIdMappingIfNotSkipped,
+                )?,
+                pathspec: pathspec.as_mut(),
+                mode: options.untracked,
+                ignored: options.ignored,
+                icase: self.config.ignore_case.then(|| index.prepare_icase_backing()),
+                precompose_unicode: self.filesystem_options()?.precompose_unicode,
+            };
+            walk.directory(&mut BString::default(), &mut items)?;
+        }
+        let index_worktree_rewrites = options
+            .rewrites
+            .map(|rewrites| status::worktree_rewrites::track(self, &index, &mut items, rewrites))
+            .transpose()?;
+
+        items.sort_by(|a, b| a.rela_path().cmp(b.rela_path()));
+        let submodules = if options.recurse_submodules && options.submodules != status::Submodule::Ignore {
+            self.submodule_outcomes(&options, pathspec.as_mut())?
+        } else {
+            Vec::new()
+        };
+        Ok(status::Outcome {
+            items,
+            index_worktree: outcome,
+            rewrites,
+            index_worktree_rewrites,
+            submodules,
+        })
+    }
+
+    /// Obtain the status of all submodules with a worktree checkout that are included in `pathspec`, using `options` of the
+    /// superproject without its pathspecs.
+    fn submodule_outcomes(
+        &self,
+        options: &status::Options,
+        mut pathspec: Option<&mut PathspecDetached>,
+    ) -> Result<Vec<status::SubmoduleOutcome>, status::Error> {
+        let mut out = Vec::new();
+        for submodule in self.submodules()?.into_iter().flatten() {
+            let outcome = submodule_outcome(&submodule, options, pathspec.as_deref_mut()).map_err(|err| {
+                status::Error::Submodule {
+                    name: submodule.name().to_owned(),
+                    source: err.into(),
+                }
+            })?;
+            out.extend(outcome);
+        }
+        out.sort_by(|a, b| a.rela_path.cmp(&b.rela_path));
+        Ok(out)
+    }
+
+    /// Compare `index` with the worktree and return an item for each tracked path that changed or is conflicting,
+    /// along with statistics about the comparison.
+    ///
+    /// Changed paths are always returned as [`Item::Changed`] without information about the index.
+    pub(crate) fn index_worktree_items(
+        &self,
+        index: &gix_index::State,
+        pathspec: Option<PathspecDetached>,
+        submodules: status::Submodule,
+        thread_limit: Option<usize>,
+    ) -> Result<(Vec<Item>, index_as_worktree::Outcome), status::Error> {
+        let work_dir = self.work_dir().ok_or(status::Error::MissingWorkDir)?;
+        let (attributes, _) = self.config.assemble_attribute_globals(
+            self.git_dir(),
+            gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping,
+            self.options.permissions.attributes,
+        )?;
+        let filter = crate::filter::Pipeline::new(
+            self,
+            self.attributes_only(
+                index,
+                gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping,
+            )?
+            .detach(),
+        )?
+        .into_parts()
+        .0;
+        let mut recorder = index_as_worktree::Recorder::default();
+        let outcome = status::plumbing::index_as_worktree(
+            index,
+            work_dir,
+            &mut recorder,
+            index_as_worktree::traits::FastEq,
+            status::SubmoduleCheck {
+                work_dir: work_dir.to_owned(),
+                options: self.options.clone(),
+                mode: submodules,
+            },
+            self.objects.clone().into_arc()?,
+            &mut gix_features::progress::Discard,
+            MaybePathspec(pathspec),
+            filter,
+            &AtomicBool::default(),
+            index_as_worktree::Options {
+                fs: self.filesystem_options()?,
+                thread_limit,
+                stat: self.stat_options()?,
+                attributes,
+            },
+        )?;
+        let items = recorder
+            .records
+            .into_iter()
+            .filter_map(|record| {
+                let rela_path = record.relative_path.to_owned();
+                let (change, submodule) = match record.status {
+                    index_as_worktree::EntryStatus::Conflict(conflict) => {
+                        return Some(Item::Conflict { rela_path, conflict })
+                    }
+                    index_as_worktree::EntryStatus::Change(change) => match change {
+                        index_as_worktree::Change::Removed => (Change::Deleted, None),
+                        index_as_worktree::Change::Type => (Change::TypeChange, None),
+                        index_as_worktree::Change::Modification { .. } => (Change::Modified, None),
+                        index_as_worktree::Change::SubmoduleModification(status) => (Change::Modified, Some(status)),
+                    },
+                    index_as_worktree::EntryStatus::IntentToAdd => (Change::Added, None),
+                    index_as_worktree::EntryStatus::NeedsUpdate(_) => return None,
+                };
+                Some(Item::Changed {
+                    rela_path,
+                    index: None,
+                    worktree: Some(change),
+                    submodule,
+                })
+            })
+            .collect();
+        Ok((items, outcome))
+    }
+
+    /// Compare `HEAD^{tree}` with `index` and return all paths that changed, skipping conflicting entries.
+    /// If `rewrites` is set, additions and deletions are matched up to find renames and copies.
+    fn head_index_changes(
+        &self,
+        index: &gix_index::State,
+        mut pathspec: Option<&mut PathspecDetached>,
+        rewrites: Option<crate::object::tree::diff::Rewrites>,
+    ) -> Result<
+        (
+            BTreeMap<BString, Item>,
+            Option<crate::object::tree::diff::rewrites::Outcome>,
+        ),
+        status::Error,
+    > {
+        let mut head = self.head()?;
+        let head_tree = if head.is_unborn() {
+            None
+        } else {
+            Some(head.peel_to_commit_in_place()?.tree_id()?)
+        };
+        let head_state = match head_tree {
+            None => gix_index::State::new(self.object_hash()),
+            Some(tree) => gix_index::State::from_tree(&tree, &self.objects)?,
+        };
+        let mut tracker = head_tree
+            .and(rewrites)
+            .map(|rewrites| tracked::State::new(rewrites, None));
+        let mut is_included = |rela_path: &BStr| {
+            pathspec
+                .as_mut()
+                .map_or(true, |spec| spec.is_included(rela_path, Some(false)))
+        };
+
+        let mut changes = BTreeMap::new();
+        for entry in index.entries() {
+            let rela_path = entry.path(index);
+            if entry.stage() != 0
+                || entry.flags.contains(gix_index::entry::Flags::INTENT_TO_ADD)
+                || !is_included(rela_path)
+            {
+                continue;
+            }
+            let change = match head_state.entry_by_path_and_stage(rela_path, 0) {
+                None => Change::Added,
+                Some(head_entry)
+                    if head_entry.mode.is_submodule() != entry.mode.is_submodule()
+                        || (head_entry.mode == gix_index::entry::Mode::SYMLINK)
+                            != (entry.mode == gix_index::entry::Mode::SYMLINK) =>
+                {
+                    Change::TypeChange
+                }
+                Some(head_entry) if head_entry.id != entry.id || head_entry.mode != entry.mode => Change::Modified,
+                Some(_) => continue,
+            };
+            if let Some(tracker) = tracker.as_mut() {
+                let change = match change {
+                    Change::Added => visit::Change::Addition {
+                        entry_mode: to_entry_mode(entry.mode),
+                        oid: entry.id,
+                    },
+                    _ => {
+                        let head_entry = head_state
+                            .entry_by_path_and_stage(rela_path, 0)
+                            .expect("present for modifications");
+                        visit::Change::Modification {
+                            previous_entry_mode: to_entry_mode(head_entry.mode),
+                            previous_oid: head_entry.id,
+                            entry_mode: to_entry_mode(entry.mode),
+                            oid: entry.id,
+                        }
+                    }
+                };
+                tracker.try_push_change(change, rela_path);
+            }
+            changes.insert(
+                rela_path.to_owned(),
+                Item::Changed {
+                    rela_path: rela_path.to_owned(),
+                    index: Some(change),
+                    worktree: None,
+                    submodule: None,
+                },
+            );
+        }
+        for head_entry in head_state.entries() {
+            let rela_path = head_entry.path(&head_state);
+            if index.entry_range(rela_path).is_none() && is_included(rela_path) {
+                if let Some(tracker) = tracker.as_mut() {
+                    tracker.try_push_change(
+                        visit::Change::Deletion {
+                            entry_mode: to_entry_mode(head_entry.mode),
+                            oid: head_entry.id,
+                        },
+                        rela_path,
+                    );
+                }
+                changes.insert(
+                    rela_path.to_owned(),
+                    Item::Changed {
+                        rela_path: rela_path.to_owned(),
+                        index: Some(Change::Deleted),
+                        worktree: None,
+                        submodule: None,
+                    },
+                );
+            }
+        }
+
+        let outcome = match (tracker, head_tree) {
+            (Some(mut tracker), Some(head_tree)) => Some(tracker.emit(
+                |dest, source| {
+                    if let Some(source) = source {
+                        let copy = source.kind == tracked::visit::Kind::CopyDestination;
+                        if !copy {
+                            changes.remove(source.location);
+                        }
+                        changes.insert(
+                            dest.location.to_owned(),
+                            Item::Rewritten {
+                                source_rela_path: source.location.to_owned(),
+                                rela_path: dest.location.to_owned(),
+                                copy,
+                                score: (source.similarity * 100.0) as u8,
+                                in_worktree: false,
+                                worktree: None,
+                            },
+                        );
+                    }
+                    visit::Action::Continue
+                },
+                &self.find_object(head_tree)?.into_tree(),
+            )?),
+            _ => None,
+        };
+        Ok((changes, outcome))
+    }
+}
+
+/// Obtain the status of `submodule` with `options` if it's included in `pathspec` and has a worktree checkout,
+/// or return `None`.
+fn submodule_outcome(
+    submodule: &crate::Submodule<'_>,
+    options: &status::Options,
+    pathspec: Option<&mut PathspecDetached>,
+) -> Result<Option<status::SubmoduleOutcome>, status::submodule::Error> {
+    let rela_path = submodule.path()?.into_owned();
+    if !pathspec.map_or(true, |spec| spec.is_included(rela_path.as_bstr(), Some(true))) {
+        return Ok(None);
+    }
+    let repo = match submodule.open()? {
+        Some(repo) if repo.work_dir().is_some() && submodule.state()?.worktree_checkout => repo,
+        _ => return Ok(None),
+    };
+    let outcome = repo
+        .status(status::Options {
+            patterns: Vec::new(),
+            ..options.clone()
+        })
+        .map_err(Box::new)?;
+    Ok(Some(status::SubmoduleOutcome {
+        name: submodule.name().to_owned(),
+        index_id: submodule.index_id()?,
+        checked_out_id: repo.head()?.id().map(crate::Id::detach),
+        rela_path,
+        outcome,
+    }))
+}
+
+pub(super) fn to_entry_mode(mode: gix_index::entry::Mode) -> gix_object::tree::EntryMode {
+    use gix_index::entry::Mode;
+    use gix_object::tree::EntryMode;
+    match mode {
+        Mode::DIR => EntryMode::Tree,
+        Mode::FILE_EXECUTABLE => EntryMode::BlobExecutable,
+        Mode::SYMLINK => EntryMode::Link,
+        Mode::COMMIT => EntryMode::Commit,
+        _ => EntryMode::Blob,
+    }
+}
+
+/// Allow to pass an optional pathspec where an implementation of [`gix_status::Pathspec`] is required.
+#[derive(Clone)]
+struct MaybePathspec(Option<PathspecDetached>);
+
+impl gix_status::Pathspec for MaybePathspec {
+    fn common_prefix(&self) -> &BStr {
+        self.0
+            .as_ref()
+            .map_or("".into(), |spec| gix_status::Pathspec::common_prefix(spec))
+    }
+
+    fn is_included(&mut self, relative_path: &BStr, is_dir: Option<bool>) -> bool {
+        self.0
+            .as_mut()
+            .map_or(true, |spec| spec.is_included(relative_path, is_dir))
+    }
+}
@@ -93,4 +93,17 @@ impl Repository {
                 }),
         ))
     }
+
+    /// Return the value of `submodule.recurse`, or `None` if it's unset, to learn if commands should recurse into submodules
+    /// by default.
+    #[cfg(any(feature = "status", feature = "blocking-network-client"))]
+    pub(crate) fn submodule_recurse(&self) -> Result<Option<bool>, crate::config::boolean::Error> {
+        use crate::config::cache::util::ApplyLeniencyDefault;
+        self.config
+            .resolved
+            .boolean("submodule", None, crate::config::tree::Submodule::RECURSE.name)
+            .map(|res| crate::config::tree::Submodule::RECURSE.enrich_error(res))
+            .transpose()
+            .with_lenient_default(self.config.lenient_config)
+    }
 }
@@ -0,0 +1,457 @@
+use std::collections::HashMap;
+
+use gix_hash::ObjectId;
+use gix_object::tree::{self, EntryMode};
+
+use crate::{
+    bstr::{BStr, ByteSlice},
+    subtree::split,
+};
+
+/// The id and mode of a tree entry.
+pub(super) type Side = (ObjectId, EntryMode);
+
+/// Subtrees
+impl crate::Repository {
+    /// Extract the history of the directory at `prefix` from the history of commit `tip` into an independent lineage of
+    /// commits with the directory as their root, similar to `git subtree split --prefix=<prefix> <tip>`, and return
+    /// the commit corresponding to `tip`.
+    ///
+    /// Each commit that changes the directory is rewritten to a commit with its tree, keeping the author, the committer
+    /// and the message, while all other commits are skipped. This makes the result stable, so splitting the same directory again
+    /// after more commits were made only adds the new commits on top of the previous result.
+    /// `None` is returned if the directory doesn't exist in the history of `tip`.
+    pub fn subtree_split(
+        &self,
+        tip: impl Into<ObjectId>,
+        prefix: impl AsRef<BStr>,
+    ) -> Result<Option<crate::Id<'_>>, split::Error> {
+        let _span = gix_trace::coarse!("gix::subtree_split()");
+        let tip = tip.into();
+        let components = components(prefix.as_ref());
+        let mut parents_by_commit = HashMap::new();
+        for info in self.rev_walk(Some(tip)).all()? {
+            let info = info?;
+            parents_by_commit.insert(info.id, info.parent_ids.to_vec());
+        }
+
+        // Parents are rewritten before their children, so each commit can be mapped to the rewritten commits of its parents.
+        let mut rewritten: HashMap<ObjectId, Option<ObjectId>> = HashMap::new();
+        let mut lineage = Lineage::default();
+        let mut stack = vec![(tip, false)];
+        while let Some((id, parents_done)) = stack.pop() {
+            if rewritten.contains_key(&id) {
+                continue;
+            }
+            let parents = parents_by_commit.get(&id).map(Vec::as_slice).unwrap_or_default();
+            if !parents_done {
+                stack.push((id, true));
+                stack.extend(
+                    parents
+                        .iter()
+                        .filter(|parent| !rewritten.contains_key(*parent) && parents_by_commit.contains_key(*parent))
+                        .map(|parent| (*parent, false)),
+                );
+                continue;
+            }
+            let mut new_parents = Vec::new();
+            for parent in parents {
+                if let Some(Some(new_parent)) = rewritten.get(parent) {
+                    if !new_parents.contains(new_parent) {
+                        new_parents.push(*new_parent);
+                    }
+                }
+            }
+            let commit = self.find_object(id)?.try_into_commit()?;
+            let commit = commit.decode()?;
+            let new_id = match self.subtree_id::<split::Error>(commit.tree(), &components)? {
+                Some(tree) => Some(self.rewrite_subtree_commit(commit.into(), tree, new_parents, &mut lineage)?),
+                None => new_parents.first().copied(),
+            };
+            rewritten.insert(id, new_id);
+        }
+        Ok(rewritten
+            .get(&tip)
+            .copied()
+            .flatten()
+            .map(|id| crate::Id::from_id(id, self)))
+    }
+
+    /// Merge the commit `theirs` into `HEAD` with its root tree placed at the directory at `prefix`, similar to
+    /// `git merge -Xsubtree=<prefix> <theirs>` or `git subtree merge --prefix=<prefix> <theirs>`, and create a merge
+    /// commit to which `HEAD` is moved.
+    ///
+    /// The common ancestor of `HEAD` and `theirs` is placed at `prefix` as well if its tree resembles the one of `theirs`,
+    /// which makes this work for lineages created with [`subtree_split()`](Self::subtree_split()) as well as for histories
+    /// that were added with a subtree merge before. If it resembles the tree of `HEAD` instead, its directory at `prefix` is used.
+    /// Merges are resolved at the level of files, and files that were changed on both sides in different ways are considered
+    /// conflicting as their content isn't merged. In that case [`Conflicts`](crate::subtree::merge::Error::Conflicts)
+    /// are returned and nothing is changed.
+    ///
+    /// The merged tree is checked out with [`checkout_tree()`](Self::checkout_tree()), which protects local changes
+    /// according to `options`, before the merge commit is created.
+    #[cfg(feature = "status")]
+    pub fn subtree_merge(
+        &self,
+        theirs: impl Into<ObjectId>,
+        prefix: impl AsRef<BStr>,
+        options: crate::subtree::merge::Options,
+    ) -> Result<crate::subtree::merge::Outcome, crate::subtree::merge::Error> {
+        use crate::subtree::merge::{Error, Outcome};
+        let _span = gix_trace::coarse!("gix::subtree_merge()");
+        self.assure_writable("merge a subtree")?;
+        // Fail before changing the worktree if the merge commit can't be created.
+        let signatures = || -> Result<_, crate::commit::Error> {
+            let committer = self.committer().ok_or(crate::commit::Error::CommitterMissing)??;
+            let author = self.author().ok_or(crate::commit::Error::AuthorMissing)??;
+            Ok((committer, author))
+        };
+        let (committer, author) = signatures()?;
+        let components = components(prefix.as_ref());
+        let theirs = self.find_object(theirs)?.peel_to_kind(gix_object::Kind::Commit)?.id;
+        let ours = self.head_commit()?;
+        let base = self.merge_base::<Error>(ours.id, theirs)?;
+        if base == Some(theirs) {
+            return Ok(Outcome::AlreadyUpToDate);
+        }
+
+        let ours_tree = ours.tree_id()?.detach();
+        let theirs_tree = self.find_object(theirs)?.try_into_commit()?.tree_id()?.detach();
+        let base_tree = match base {
+            Some(base) => {
+                let base_tree = self.find_object(base)?.try_into_commit()?.tree_id()?.detach();
+                // Like `git`, shift the base tree up if it has the layout of `ours`, which happens if `theirs`
+                // descends from a commit of our own history.
+                let shifted = match self.subtree_id::<Error>(base_tree, &components)? {
+                    Some(shifted)
+                        if self.num_common_entries::<Error>(base_tree, ours_tree)?
+                            > self.num_common_entries::<Error>(base_tree, theirs_tree)? =>
+                    {
+                        Some(shifted)
+                    }
+                    _ => None,
+                };
+                Some((shifted.unwrap_or(base_tree), EntryMode::Tree))
+            }
+            None => None,
+        };
+        let ours_subtree = self.subtree_id::<Error>(ours_tree, &components)?;
+        let mut conflicts = Vec::new();
+        let merged = self.merge_subtrees::<Error>(
+            base_tree,
+            ours_subtree.map(|id| (id, EntryMode::Tree)),
+            Some((theirs_tree, EntryMode::Tree)),
+            &mut prefix.as_ref().trim_with(|c| c == '/').as_bstr().to_owned(),
+            &mut conflicts,
+        )?;
+        if !conflicts.is_empty() {
+            return Err(Error::Conflicts { paths: conflicts });
+        }
+        let tree = self.replace_subtree::<Error>(Some(ours_tree), &components, merged.map(|(id, _)| id))?;
+
+        self.checkout_tree(tree, options.plan)?;
+        let message = options.message.unwrap_or_else(|| format!("Merge commit '{theirs}'"));
+        let commit = self
+            .commit_as(committer, author, "HEAD", message, tree, [ours.id, theirs])?
+            .detach();
+        Ok(Outcome::Merged { commit, tree })
+    }
+}
+
+/// Utilities
+impl crate::Repository {
+    /// Return the id of the tree at the path made of `components` within the tree `id`, or `id` if there are no components.
+    fn subtree_id<E: TreeError>(&self, id: ObjectId, components: &[&BStr]) -> Result<Option<ObjectId>, E> {
+        let mut id = id;
+        for component in components {
+            match self
+                .tree_entries::<E>(id)?
+                .into_iter()
+                .find(|entry| entry.mode == EntryMode::Tree && entry.filename == *component)
+            {
+                Some(entry) => id = entry.oid,
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(id))
+    }
+
+    /// Write a copy of the tree `id` with the tree at the path made of `components` replaced by `subtree`, or removed if it's `None`,
+    /// and return its id. Leading trees are created as needed, and removed if they are empty afterwards.
+    #[cfg(feature = "status")]
+    fn replace_subtree<E: TreeError>(
+        &self,
+        id: Option<ObjectId>,
+        components: &[&BStr],
+        subtree: Option<ObjectId>,
+    ) -> Result<ObjectId, E> {
+        let (component, rest) = match components.split_first() {
+            Some(split) => split,
+            None => return Ok(subtree.unwrap_or_else(|| ObjectId::empty_tree(self.object_hash()))),
+        };
+        let mut entries = match id {
+            Some(id) => self.tree_entries::<E>(id)?,
+            None => Vec::new(),
+        };
+        let existing = entries.iter().position(|entry| entry.filename == *component);
+        let current = existing
+            .map(|pos| &entries[pos])
+            .filter(|entry| entry.mode == EntryMode::Tree)
+            .map(|entry| entry.oid);
+        let new = if rest.is_empty() {
+            subtree
+        } else {
+            Some(self.replace_subtree::<E>(current, rest, subtree)?)
+        }
+        .filter(|id| !id.is_empty_tree());
+        if let Some(pos) = existing {
+            entries.remove(pos);
+        }
+        if let Some(oid) = new {
+            entries.push(tree::Entry {
+                mode: EntryMode::Tree,
+                filename: (*component).to_owned(),
+                oid,
+            });
+            entries.sort();
+        }
+        Ok(self.write_object(&gix_object::Tree { entries })?.detach())
+    }
+
+    /// Merge `ours` and `theirs` using `base` as their common ancestor, with `path` being the location of all of them, and return
+    /// the result. Paths that can't be merged are added to `conflicts`, and `ours` is used for them.
+    pub(super) fn merge_subtrees<E: TreeError>(
+        &self,
+        base: Option<Side>,
+        ours: Option<Side>,
+        theirs: Option<Side>,
+        path: &mut crate::bstr::BString,
+        conflicts: &mut Vec<crate::bstr::BString>,
+    ) -> Result<Option<Side>, E> {
+        use crate::bstr::ByteVec;
+        if ours == theirs || base == theirs {
+            return Ok(ours);
+        }
+        if base == ours {
+            return Ok(theirs);
+        }
+        let (ours_id, theirs_id) = match (ours, theirs) {
+            (Some((ours, EntryMode::Tree)), Some((theirs, EntryMode::Tree))) => (ours, theirs),
+            _ => {
+                conflicts.push(path.clone());
+                return Ok(ours);
+            }
+        };
+        let base_entries = match base {
+            Some((id, EntryMode::Tree)) => self.tree_entries::<E>(id)?,
+            _ => Vec::new(),
+        };
+        let ours_entries = self.tree_entries::<E>(ours_id)?;
+        let theirs_entries = self.tree_entries::<E>(theirs_id)?;
+        let names: std::collections::BTreeSet<&crate::bstr::BString> = base_entries
+            .iter()
+            .chain(&ours_entries)
+            .chain(&theirs_entries)
+            .map(|entry| &entry.filename)
+            .collect();
+        let side = |entries: &[tree::Entry], name: &crate::bstr::BString| {
+            entries
+                .iter()
+                .find(|entry| entry.filename == *name)
+                .map(|entry| (entry.oid, entry.mode))
+        };
+        let mut entries = Vec::new();
+        for name in names {
+            let len = path.len();
+            if !path.is_empty() {
+                path.push(b'/');
+            }
+            path.push_str(name);
+            let merged = self.merge_subtrees::<E>(
+                side(&base_entries, name),
+                side(&ours_entries, name),
+                side(&theirs_entries, name),
+                path,
+                conflicts,
+            )?;
+            path.truncate(len);
+            if let Some((oid, mode)) = merged {
+                if !(mode == EntryMode::Tree && oid.is_empty_tree()) {
+                    entries.push(tree::Entry {
+                        mode,
+                        filename: name.clone(),
+                        oid,
+                    });
+                }
+            }
+        }
+        entries.sort();
+        Ok(match entries.is_empty() {
+            true => None,
+            false => Some((
+                self.write_object(&gix_object::Tree { entries })?.detach(),
+                EntryMode::Tree,
+            )),
+        })
+    }
+
+    /// Return the common ancestor of `a` and `b` with the most recent commit time, or `None` if they have no common history.
+    pub(crate) fn merge_base<E>(&self, a: ObjectId, b: ObjectId) -> Result<Option<ObjectId>, E>
+    where
+        E: From<crate::revision::walk::Error> + From<gix_traverse::commit::ancestors::Error>,
+    {
+        let mut ancestors_of_b = std::collections::HashSet::new();
+        for info in self.rev_walk(Some(b)).all()? {
+            ancestors_of_b.insert(info?.id);
+        }
+        for info in self
+            .rev_walk(Some(a))
+            .sorting(gix_traverse::commit::Sorting::ByCommitTimeNewestFirst)
+            .all()?
+        {
+            let id = info?.id;
+            if ancestors_of_b.contains(&id) {
+                return Ok(Some(id));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Return the amount of entries that are the same in the trees `a` and `b`, without recursing.
+    #[cfg(feature = "status")]
+    fn num_common_entries<E: TreeError>(&self, a: ObjectId, b: ObjectId) -> Result<usize, E> {
+        let b = self.tree_entries::<E>(b)?;
+        Ok(self
+            .tree_entries::<E>(a)?
+            .iter()
+            .filter(|entry| b.binary_search(entry).is_ok())
+            .count())
+    }
+
+    /// Write `commit` with `tree` and `parents`, or return the parent that can be used instead if it has the same tree and
+    /// there is no other history that would be lost, just like `git subtree split` does.
+    /// All `parents` must be part of `lineage`, to which the written commit is added.
+    fn rewrite_subtree_commit(
+        &self,
+        commit: gix_object::Commit,
+        tree: ObjectId,
+        parents: Vec<ObjectId>,
+        lineage: &mut Lineage,
+    ) -> Result<ObjectId, split::Error> {
+        let mut identical = None;
+        let mut non_identical = None;
+        let mut needs_copy = false;
+        for parent in &parents {
+            let parent = *parent;
+            if self.find_object(parent)?.try_into_commit()?.tree_id()? != tree {
+                non_identical = Some(parent);
+                continue;
+            }
+            identical = match identical {
+                None => Some(parent),
+                Some(previous) if lineage.is_ancestor(previous, parent) => Some(parent),
+                Some(previous) => {
+                    needs_copy |= !lineage.is_ancestor(parent, previous);
+                    Some(previous)
+                }
+            };
+        }
+        if let (Some(identical), Some(non_identical)) = (identical, non_identical) {
+            needs_copy |= !lineage.is_ancestor(non_identical, identical);
+        }
+        match identical {
+            Some(identical) if !needs_copy => Ok(identical),
+            _ => {
+                let id = self
+                    .write_object(&gix_object::Commit {
+                        tree,
+                        parents: parents.clone().into(),
+                        extra_headers: Vec::new(),
+                        ..commit
+                    })?
+                    .detach();
+                lineage.insert(id, parents);
+                Ok(id)
+            }
+        }
+    }
+
+    fn tree_entries<E: TreeError>(&self, id: ObjectId) -> Result<Vec<tree::Entry>, E> {
+        let tree = self.find_object(id)?.try_into_tree()?;
+        let entries = gix_object::Tree::from(tree.decode()?).entries;
+        Ok(entries)
+    }
+}
+
+/// The commits written by a split, along with their parents and generation, to answer ancestry queries without traversing
+/// the object database.
+#[derive(Default)]
+struct Lineage {
+    commits: HashMap<ObjectId, (Vec<ObjectId>, u32)>,
+}
+
+impl Lineage {
+    /// Add the commit `id` with `parents`, all of which must already be known.
+    fn insert(&mut self, id: ObjectId, parents: Vec<ObjectId>) {
+        let generation = parents.iter().map(|parent| self.generation(parent)).max().unwrap_or(0) + 1;
+        self.commits.insert(id, (parents, generation));
+    }
+
+    fn generation(&self, id: &ObjectId) -> u32 {
+        self.commits.get(id).map_or(0, |(_, generation)| *generation)
+    }
+
+    /// Return `true` if `ancestor` is reachable from `id`, or if both are the same.
+    ///
+    /// Only commits with a higher generation than `ancestor` can lead to it, which bounds the traversal.
+    fn is_ancestor(&self, ancestor: ObjectId, id: ObjectId) -> bool {
+        if ancestor == id {
+            return true;
+        }
+        let min_generation = self.generation(&ancestor);
+        let mut seen = std::collections::HashSet::new();
+        let mut stack = vec![id];
+        while let Some(id) = stack.pop() {
+            for parent in self
+                .commits
+                .get(&id)
+                .map(|(parents, _)| parents.as_slice())
+                .unwrap_or_default()
+            {
+                if *parent == ancestor {
+                    return true;
+                }
+                if self.generation(parent) > min_generation && seen.insert(*parent) {
+                    stack.push(*parent);
+                }
+            }
+        }
+        false
+    }
+}
+
+/// All errors that may occur when reading and writing trees.
+pub(super) trait TreeError:
+    From<crate::object::find::existing::Error>
+    + From<crate::object::try_into::Error>
+    + From<gix_object::decode::Error>
+    + From<crate::object::write::Error>
+{
+}
+
+impl<T> TreeError for T where
+    T: From<crate::object::find::existing::Error>
+        + From<crate::object::try_into::Error>
+        + From<gix_object::decode::Error>
+        + From<crate::object::write::Error>
+{
+}
+
+/// Split `prefix` into its non-empty components.
+fn components(prefix: &BStr) -> Vec<&BStr> {
+    prefix
+        .split(|b| *b == b'/')
+        .filter(|component| !component.is_empty())
+        .map(ByteSlice::as_bstr)
+        .collect()
+}
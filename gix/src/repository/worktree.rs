@@ -1,3 +1,5 @@
+#[cfg(feature = "worktree-mutation")]
+use crate::bstr::{BString, ByteSlice, ByteVec};
 use crate::{worktree, Worktree};
 
 /// Interact with individual worktrees and their information.
@@ -25,6 +27,298 @@ impl crate::Repository {
         res.sort_by(|a, b| a.git_dir.cmp(&b.git_dir));
         Ok(res)
     }
+    /// Create a new linked worktree at `path` with `head` checked out, like `git worktree add`, and return it as repository.
+    ///
+    /// The private git directory of the worktree is created within the `worktrees` directory of the
+    /// [common directory](Self::common_dir()), named after the last component of `path` and made unique if needed.
+    /// `path` must not exist or be an empty directory, and relative paths are interpreted relative to the current working directory.
+    ///
+    /// Just like `git`, a branch can only be checked out in one worktree at a time.
+    #[cfg(feature = "worktree-mutation")]
+    pub fn add_worktree(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        head: worktree::add::Head,
+        options: worktree::add::Options,
+    ) -> Result<crate::Repository, worktree::add::Error> {
+        use worktree::add::{Error, Head};
+        let _span = gix_trace::coarse!("gix::add_worktree()");
+        self.assure_writable("add a worktree")?;
+        let base = self.options.current_dir_or_empty().join(path.as_ref());
+        let is_empty_dir = std::fs::read_dir(&base).map_or(false, |mut entries| entries.next().is_none());
+        if base.exists() && !is_empty_dir {
+            return Err(Error::DestinationExists { path: base });
+        }
+
+        let (branch, commit, new_branch) = match head {
+            Head::Branch(name) => {
+                let name = local_branch_name(name)?;
+                let id = self.find_reference(name.as_ref())?.peel_to_id_in_place()?.detach();
+                (Some(name), id, false)
+            }
+            Head::NewBranch { name, commit } => {
+                let name = local_branch_name(name)?;
+                if self.try_find_reference(name.as_ref())?.is_some() {
+                    return Err(Error::BranchExists {
+                        name: name.as_bstr().to_owned(),
+                    });
+                }
+                (Some(name), commit, true)
+            }
+            Head::Detached(commit) => (None, commit, false),
+        };
+        let commit = self.find_object(commit)?.peel_to_kind(gix_object::Kind::Commit)?;
+        let (commit, tree) = (commit.id, commit.peel_to_tree()?.id);
+        if let Some(name) = branch.as_ref().filter(|_| !new_branch) {
+            self.assure_branch_is_not_checked_out(name)?;
+        }
+
+        let io_err = |path: &std::path::Path| {
+            let path = path.to_owned();
+            move |source| Error::Io { path, source }
+        };
+        let worktrees = self.common_dir().join("worktrees");
+        std::fs::create_dir_all(&worktrees).map_err(io_err(&worktrees))?;
+        let name: BString = base
+            .file_name()
+            .and_then(|name| gix_path::os_str_into_bstr(name).ok())
+            .map_or_else(|| "worktree".into(), ToOwned::to_owned);
+        let mut git_dir = worktrees.join(gix_path::from_bstr(name.as_bstr()));
+        let mut counter = 0;
+        loop {
+            match std::fs::create_dir(&git_dir) {
+                Ok(()) => break,
+                Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {
+                    counter += 1;
+                    git_dir = worktrees.join(gix_path::from_bstr(format!("{name}{counter}").as_bytes().as_bstr()));
+                }
+                Err(err) => return Err(io_err(&git_dir)(err)),
+            }
+        }
+        // Like `git`, keep the worktree locked until it's fully set up so it can't be pruned.
+        let locked = git_dir.join("locked");
+        std::fs::write(&locked, "initializing").map_err(io_err(&locked))?;
+
+        std::fs::create_dir_all(&base).map_err(io_err(&base))?;
+        let base = gix_path::realpath(&base)
+            .map_err(|err| io_err(&base)(std::io::Error::new(std::io::ErrorKind::Other, err)))?;
+        let private_git_dir = gix_path::realpath(&git_dir)
+            .map_err(|err| io_err(&git_dir)(std::io::Error::new(std::io::ErrorKind::Other, err)))?;
+        worktree::proxy::write_dot_git_file(&base, &private_git_dir).map_err(io_err(&base))?;
+        worktree::proxy::write_gitdir_file(&git_dir, &base).map_err(io_err(&git_dir))?;
+        let commondir = git_dir.join("commondir");
+        std::fs::write(&commondir, "../..\n").map_err(io_err(&commondir))?;
+
+        if let Some(name) = branch.as_ref().filter(|_| new_branch) {
+            self.reference(
+                name.clone(),
+                commit,
+                gix_ref::transaction::PreviousValue::MustNotExist,
+                format!("branch: Created from {commit}"),
+            )?;
+        }
+        let head = git_dir.join("HEAD");
+        let head_content = match &branch {
+            Some(name) => format!("ref: {}\n", name.as_bstr()),
+            None => format!("{commit}\n"),
+        };
+        std::fs::write(&head, head_content).map_err(io_err(&head))?;
+
+        let repo: crate::Repository = crate::ThreadSafeRepository::open_opts(&base, self.options.clone())?.into();
+        let index = gix_index::State::from_tree(&tree, &repo.objects)?;
+        let mut index = gix_index::File::from_state(index, repo.index_path());
+        let mut opts = repo
+            .config
+            .checkout_options(&repo, gix_worktree::stack::state::attributes::Source::IdMapping)?;
+        opts.destination_is_initially_empty = true;
+        gix_worktree_state::checkout(
+            &mut index,
+            &base,
+            repo.objects.clone().into_arc().map_err(io_err(&base))?,
+            &gix_features::progress::Discard,
+            &gix_features::progress::Discard,
+            &std::sync::atomic::AtomicBool::default(),
+            opts,
+        )?;
+        index.write(Default::default())?;
+
+        match options.lock_reason {
+            Some(reason) => std::fs::write(&locked, reason),
+            None => std::fs::remove_file(&locked),
+        }
+        .map_err(io_err(&locked))?;
+        Ok(repo)
+    }
+
+    /// Remove the administrative files of linked worktrees whose checkout doesn't exist anymore, like `git worktree prune`,
+    /// and return the worktrees that were pruned sorted by their id.
+    ///
+    /// Locked worktrees are never pruned, and with [`options.dry_run`](worktree::prune::Options::dry_run) nothing is removed.
+    /// Entries whose names can't be represented as worktree id, which can only happen with ill-formed UTF-16 on Windows,
+    /// are skipped.
+    pub fn prune_worktrees(
+        &self,
+        options: worktree::prune::Options,
+    ) -> Result<Vec<worktree::prune::Pruned>, worktree::prune::Error> {
+        use worktree::prune::{Error, Pruned, Reason};
+        let _span = gix_trace::coarse!("gix::prune_worktrees()");
+        self.assure_writable("prune worktrees")?;
+        let worktrees = self.common_dir().join("worktrees");
+        let io_err = |path: &std::path::Path| {
+            let path = path.to_owned();
+            move |source| Error::Io { path, source }
+        };
+        let iter = match std::fs::read_dir(&worktrees) {
+            Ok(iter) => iter,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(io_err(&worktrees)(err)),
+        };
+        let mut pruned = Vec::new();
+        for entry in iter {
+            let entry = entry.map_err(io_err(&worktrees))?;
+            let id = match gix_path::os_str_into_bstr(&entry.file_name()) {
+                Ok(id) => id.to_owned(),
+                Err(_) => continue,
+            };
+            let git_dir = entry.path();
+            let reason = if !git_dir.is_dir() {
+                Reason::NotADirectory
+            } else if git_dir.join("locked").is_file() {
+                continue;
+            } else {
+                let gitdir_file = git_dir.join("gitdir");
+                match gix_discover::path::from_plain_file(&gitdir_file) {
+                    None => Reason::MissingGitdirFile,
+                    Some(Err(_)) => Reason::InvalidGitdirFile,
+                    Some(Ok(dot_git)) if dot_git.as_os_str().is_empty() => Reason::InvalidGitdirFile,
+                    Some(Ok(dot_git)) => {
+                        if git_dir.join(dot_git).exists() {
+                            continue;
+                        }
+                        // Like `git`, use the private index to learn when the worktree was last used.
+                        let is_expired = options.expire.map_or(true, |expire| {
+                            std::fs::metadata(git_dir.join("index"))
+                                .and_then(|metadata| metadata.modified())
+                                .map_or(true, |modified| modified <= expire)
+                        });
+                        if !is_expired {
+                            continue;
+                        }
+                        Reason::MissingWorktree
+                    }
+                }
+            };
+            if !options.dry_run {
+                if reason == Reason::NotADirectory {
+                    std::fs::remove_file(&git_dir)
+                } else {
+                    std::fs::remove_dir_all(&git_dir)
+                }
+                .map_err(io_err(&git_dir))?;
+            }
+            pruned.push(Pruned { id, reason });
+        }
+        if !options.dry_run && !pruned.is_empty() {
+            // Like `git`, remove the `worktrees` directory once it's empty, which fails if it isn't.
+            std::fs::remove_dir(&worktrees).ok();
+        }
+        pruned.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(pruned)
+    }
+
+    /// Repair the connection between linked worktrees and this repository in both directions, like `git worktree repair <paths>…`,
+    /// and return all files that were rewritten.
+    ///
+    /// The `.git` file of each linked worktree whose checkout exists is made to point to its private git directory again,
+    /// which is needed after the repository was moved.
+    /// Each of `paths` is expected to be the checkout of a linked worktree that was moved manually, and the private git directory
+    /// it belongs to is made to point to it again. If the repository was moved as well, the private git directory is found
+    /// by its id instead.
+    /// Relative paths are interpreted relative to the current working directory.
+    pub fn repair_worktrees(
+        &self,
+        paths: impl IntoIterator<Item = impl AsRef<std::path::Path>>,
+    ) -> Result<Vec<worktree::repair::Repaired>, worktree::repair::Error> {
+        use worktree::repair::{Error, Kind, Repaired};
+        let _span = gix_trace::coarse!("gix::repair_worktrees()");
+        self.assure_writable("repair worktrees")?;
+        let io_err = |path: &std::path::Path| {
+            let path = path.to_owned();
+            move |source| Error::Io { path, source }
+        };
+        let realpath = |path: &std::path::Path| gix_path::realpath(path).ok();
+        let worktrees_dir = self.common_dir().join("worktrees");
+        let mut repaired = Vec::new();
+
+        for proxy in self.worktrees().map_err(io_err(&worktrees_dir))? {
+            let base = match proxy.base() {
+                Ok(base) if base.is_dir() => base,
+                _ => continue,
+            };
+            let dot_git = base.join(gix_discover::DOT_GIT_DIR);
+            if dot_git.is_dir() {
+                continue;
+            }
+            let private_git_dir = realpath(proxy.git_dir());
+            let current = gix_discover::path::from_gitdir_file(&dot_git)
+                .ok()
+                .and_then(|path| realpath(&path));
+            if current.is_none() || current != private_git_dir {
+                let private_git_dir = private_git_dir.unwrap_or_else(|| proxy.git_dir().to_owned());
+                worktree::proxy::write_dot_git_file(&base, &private_git_dir).map_err(io_err(&dot_git))?;
+                repaired.push(Repaired {
+                    id: proxy.id().to_owned(),
+                    kind: Kind::DotGitFile,
+                    path: dot_git,
+                });
+            }
+        }
+
+        for path in paths {
+            let path = self.options.current_dir_or_empty().join(path.as_ref());
+            let dot_git = path.join(gix_discover::DOT_GIT_DIR);
+            if !dot_git.is_file() {
+                return Err(Error::NotALinkedWorktree { path });
+            }
+            let target = gix_discover::path::from_gitdir_file(&dot_git).map_err(|source| Error::InvalidDotGitFile {
+                path: dot_git.clone(),
+                source,
+            })?;
+            // Like `git`, infer the private git directory from the last component of the path in the `.git` file
+            // if it doesn't point to a worktree of this repository anymore.
+            let git_dir = match target.file_name() {
+                Some(id) => worktrees_dir.join(id),
+                None => return Err(Error::UnknownWorktree { path }),
+            };
+            if !git_dir.join("gitdir").is_file() {
+                return Err(Error::UnknownWorktree { path });
+            }
+            let proxy = worktree::Proxy::new(self, git_dir);
+            let private_git_dir = realpath(proxy.git_dir()).unwrap_or_else(|| proxy.git_dir().to_owned());
+            if realpath(&target).as_ref() != Some(&private_git_dir) {
+                worktree::proxy::write_dot_git_file(&path, &private_git_dir).map_err(io_err(&dot_git))?;
+                repaired.push(Repaired {
+                    id: proxy.id().to_owned(),
+                    kind: Kind::DotGitFile,
+                    path: dot_git,
+                });
+            }
+
+            let base = gix_path::realpath(&path)
+                .map_err(|err| io_err(&path)(std::io::Error::new(std::io::ErrorKind::Other, err)))?;
+            let current_base = proxy.base().ok().and_then(|base| realpath(&base));
+            if current_base.as_ref() != Some(&base) {
+                worktree::proxy::write_gitdir_file(proxy.git_dir(), &base).map_err(io_err(proxy.git_dir()))?;
+                repaired.push(Repaired {
+                    id: proxy.id().to_owned(),
+                    kind: Kind::GitdirFile,
+                    path: proxy.git_dir().join("gitdir"),
+                });
+            }
+        }
+        Ok(repaired)
+    }
+
     /// Return the repository owning the main worktree, typically from a linked worktree.
     ///
     /// Note that it might be the one that is currently open if this repository doesn't point to a linked worktree.
@@ -61,9 +355,31 @@ impl crate::Repository {
     pub fn worktree_stream(
         &self,
         id: impl Into<gix_hash::ObjectId>,
+    ) -> Result<(gix_worktree_stream::Stream, gix_index::File), crate::repository::worktree_stream::Error> {
+        self.worktree_stream_inner(id.into(), None)
+    }
+
+    /// Like [`worktree_stream()`](Self::worktree_stream()), but produces the stream for the tree of `commit` and
+    /// [expands](crate::object::commit::pretty_format::Context::format()) `$Format:<format>$` placeholders in blobs with the
+    /// `export-subst` attribute for it, just like `git archive` does.
+    #[cfg(feature = "worktree-stream")]
+    pub fn worktree_stream_with_substitution(
+        &self,
+        commit: &crate::Commit<'_>,
+    ) -> Result<(gix_worktree_stream::Stream, gix_index::File), crate::repository::worktree_stream::Error> {
+        self.worktree_stream_inner(
+            commit.tree_id()?.detach(),
+            Some(commit.pretty_format_context_inner(true)?),
+        )
+    }
+
+    #[cfg(feature = "worktree-stream")]
+    fn worktree_stream_inner(
+        &self,
+        id: gix_hash::ObjectId,
+        commit: Option<crate::object::commit::pretty_format::Context>,
     ) -> Result<(gix_worktree_stream::Stream, gix_index::File), crate::repository::worktree_stream::Error> {
         use gix_odb::HeaderExt;
-        let id = id.into();
         let header = self.objects.header(id)?;
         if !header.kind().is_tree() {
             return Err(crate::repository::worktree_stream::Error::NotATree {
@@ -82,16 +398,31 @@ impl crate::Repository {
         let pipeline =
             gix_filter::Pipeline::new(cache.attributes_collection(), crate::filter::Pipeline::options(self)?);
         let objects = self.objects.clone().into_arc().expect("TBD error handling");
-        let stream = gix_worktree_stream::from_tree(
-            id,
-            objects.clone(),
-            pipeline,
-            move |path, mode, attrs| -> std::io::Result<()> {
+        let attributes = {
+            let objects = objects.clone();
+            move |path: &crate::bstr::BStr,
+                  mode: gix_object::tree::EntryMode,
+                  attrs: &mut gix_attributes::search::Outcome|
+                  -> std::io::Result<()> {
                 let entry = cache.at_entry(path, Some(mode.is_tree()), &objects)?;
                 entry.matching_attributes(attrs);
                 Ok(())
-            },
-        );
+            }
+        };
+        let stream = match commit {
+            Some(context) => gix_worktree_stream::from_tree_with_substitution(
+                id,
+                objects,
+                pipeline,
+                attributes,
+                move |format, out| -> Result<(), std::convert::Infallible> {
+                    use crate::bstr::ByteVec;
+                    out.push_str(context.format(format));
+                    Ok(())
+                },
+            ),
+            None => gix_worktree_stream::from_tree(id, objects, pipeline, attributes),
+        };
         Ok((stream, index))
     }
 
@@ -139,4 +470,105 @@ impl crate::Repository {
         )?;
         Ok(())
     }
+
+    /// Write an archive of the tree-ish `id` to `out` according to `options`, just like `git archive <id>` would, querying
+    /// `should_interrupt` between each entry.
+    ///
+    /// If `id` is a commit or a tag pointing to one, the archive contains the tree of the commit, and the id of the commit
+    /// is recorded in the archive unless [`options.commit_id`](gix_archive::Options::commit_id) is already set.
+    /// Further, `$Format:<format>$` placeholders in files with the `export-subst` attribute are replaced with `<format>`
+    /// [expanded](crate::Commit::pretty_format()) for the commit, while files with the `export-ignore` attribute are omitted.
+    /// To produce the same archive every time, set [`options.modification_time`](gix_archive::Options::modification_time)
+    /// to the time of the commit, which is what `git` does.
+    ///
+    /// As `out` doesn't have to be seekable, this is suitable for streaming `tar` archives, but `zip` archives
+    /// require [`worktree_archive()`](Self::worktree_archive()) instead.
+    ///
+    /// ### Performance
+    ///
+    /// Be sure that `out` is able to handle a lot of write calls. Otherwise wrap it in a [`BufWriter`][std::io::BufWriter].
+    #[cfg(feature = "worktree-archive")]
+    #[gix_macros::momo]
+    pub fn archive(
+        &self,
+        id: impl Into<gix_hash::ObjectId>,
+        out: impl std::io::Write,
+        should_interrupt: &std::sync::atomic::AtomicBool,
+        mut options: gix_archive::Options,
+    ) -> Result<(), crate::repository::archive::Error> {
+        let object = self.find_object(id)?;
+        let (mut stream, _index) = match object.kind {
+            gix_object::Kind::Tree => self.worktree_stream(object.id)?,
+            _ => {
+                let commit = object.peel_to_kind(gix_object::Kind::Commit)?.into_commit();
+                options.commit_id.get_or_insert(commit.id);
+                self.worktree_stream_with_substitution(&commit)?
+            }
+        };
+        let mut out = gix_features::interrupt::Write {
+            inner: out,
+            should_interrupt,
+        };
+        if options.format == gix_archive::Format::InternalTransientNonPersistable {
+            std::io::copy(&mut stream.into_read(), &mut out).map_err(gix_archive::Error::from)?;
+            return Ok(());
+        }
+        gix_archive::write_stream(
+            &mut stream,
+            |stream| {
+                if should_interrupt.load(std::sync::atomic::Ordering::Relaxed) {
+                    return Err(std::io::Error::new(std::io::ErrorKind::Other, "Cancelled by user").into());
+                }
+                stream.next_entry()
+            },
+            out,
+            options,
+        )?;
+        Ok(())
+    }
+}
+
+/// Utilities
+#[cfg(feature = "worktree-mutation")]
+impl crate::Repository {
+    /// Fail if the local branch `name` is the `HEAD` of the main worktree, unless it's bare, or of any linked worktree.
+    fn assure_branch_is_not_checked_out(&self, name: &gix_ref::FullName) -> Result<(), worktree::add::Error> {
+        let main = (!self.config.is_bare).then(|| {
+            let base = self
+                .common_dir()
+                .parent()
+                .map_or_else(Default::default, ToOwned::to_owned);
+            (self.common_dir().to_owned(), base)
+        });
+        let linked = self.worktrees().map_err(|source| worktree::add::Error::Io {
+            path: self.common_dir().join("worktrees"),
+            source,
+        })?;
+        let linked = linked
+            .iter()
+            .map(|proxy| (proxy.git_dir().to_owned(), proxy.base().unwrap_or_default()));
+        for (git_dir, base) in main.into_iter().chain(linked) {
+            if let gix_ref::Target::Symbolic(head) = worktree::proxy::read_head(&git_dir)? {
+                if head == *name {
+                    return Err(worktree::add::Error::BranchCheckedOut {
+                        name: name.as_bstr().to_owned(),
+                        path: base,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Turn `name` into the full name of a local branch, unless it's a full name already.
+#[cfg(feature = "worktree-mutation")]
+fn local_branch_name(name: BString) -> Result<gix_ref::FullName, gix_validate::reference::name::Error> {
+    if name.starts_with(b"refs/heads/") {
+        name.try_into()
+    } else {
+        let mut full = BString::from("refs/heads/");
+        full.push_str(&name);
+        full.try_into()
+    }
 }
@@ -31,11 +31,7 @@ impl<'repo> delegate::Revision for Delegate<'repo> {
         }
     }
 
-    fn disambiguate_prefix(
-        &mut self,
-        prefix: gix_hash::Prefix,
-        _must_be_commit: Option<delegate::PrefixHint<'_>>,
-    ) -> Option<()> {
+    fn disambiguate_prefix(&mut self, prefix: gix_hash::Prefix, hint: Option<delegate::PrefixHint<'_>>) -> Option<()> {
         self.last_call_was_disambiguate_prefix[self.idx] = true;
         let mut candidates = Some(HashSet::default());
         self.prefix[self.idx] = Some(prefix);
@@ -59,7 +55,22 @@ impl<'repo> delegate::Revision for Delegate<'repo> {
             }
             Ok(Some(Ok(_) | Err(()))) => {
                 assert!(self.objs[self.idx].is_none(), "BUG: cannot set the same prefix twice");
-                let candidates = candidates.expect("set above");
+                let mut candidates = candidates.expect("set above");
+                if hint.is_some() && candidates.len() > 1 {
+                    // Prefixes from the output of `git describe` always refer to commits, which `git` uses to disambiguate them.
+                    let commits: HashSet<_> = candidates
+                        .iter()
+                        .filter(|id| {
+                            self.repo
+                                .find_header(**id)
+                                .map_or(false, |header| header.kind() == gix_object::Kind::Commit)
+                        })
+                        .copied()
+                        .collect();
+                    if !commits.is_empty() {
+                        candidates = commits;
+                    }
+                }
                 match self.opts.refs_hint {
                     RefsHint::PreferObjectOnFullLengthHexShaUseRefOtherwise
                         if prefix.hex_len() == candidates.iter().next().expect("at least one").kind().len_in_hex() =>
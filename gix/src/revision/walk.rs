@@ -1,5 +1,4 @@
 use gix_hash::ObjectId;
-use gix_object::FindExt;
 
 use crate::{ext::ObjectIdExt, revision, Repository};
 
@@ -12,11 +11,53 @@ pub enum Error {
     #[error(transparent)]
     ShallowCommits(#[from] crate::shallow::open::Error),
     #[error(transparent)]
+    Grafts(#[from] crate::shallow::grafts::Error),
+    #[error(transparent)]
     ConfigBoolean(#[from] crate::config::boolean::Error),
 }
 
+pub use gix_traverse::commit::ancestors::Checkpoint;
+
+///
+#[cfg(feature = "blob-diff")]
+pub mod with_diffs {
+    /// The error returned by [`Platform::walk_with_diffs()`](super::Platform::walk_with_diffs()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Walk(#[from] super::Error),
+        #[error(transparent)]
+        Traverse(#[from] gix_traverse::commit::ancestors::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        ObjectKind(#[from] crate::object::try_into::Error),
+        #[error(transparent)]
+        CommitTree(#[from] crate::object::commit::Error),
+        #[error(transparent)]
+        Rewrites(#[from] crate::object::tree::diff::rewrites::Error),
+        #[error(transparent)]
+        Diff(#[from] crate::object::tree::diff::for_each::Error),
+    }
+}
+
+///
+#[cfg(feature = "mailmap")]
+pub mod info {
+    /// The error returned by [`Info::author()`](super::Info::author()) and [`Info::committer()`](super::Info::committer()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        Decode(#[from] gix_object::decode::Error),
+    }
+}
+
 /// Information about a commit that we obtained naturally as part of the iteration.
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct Info<'repo> {
     /// The detached id of the commit.
     pub id: gix_hash::ObjectId,
@@ -27,6 +68,19 @@ pub struct Info<'repo> {
     pub commit_time: Option<gix_date::SecondsSinceUnixEpoch>,
 
     repo: &'repo Repository,
+    #[cfg(feature = "mailmap")]
+    mailmap: Option<&'repo gix_mailmap::Snapshot>,
+}
+
+impl std::fmt::Debug for Info<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Info")
+            .field("id", &self.id)
+            .field("parent_ids", &self.parent_ids)
+            .field("commit_time", &self.commit_time)
+            .field("repo", &self.repo)
+            .finish_non_exhaustive()
+    }
 }
 
 /// Access
@@ -57,6 +111,34 @@ impl<'repo> Info<'repo> {
     pub fn commit_time(&self) -> gix_date::SecondsSinceUnixEpoch {
         self.commit_time.expect("traversal involving date caused it to be set")
     }
+
+    /// Read the commit and return its author, with name and email canonicalized by the [mailmap](Platform::with_mailmap())
+    /// if one was set.
+    ///
+    /// Note that this reads the whole object, just like [`object()`](Self::object()).
+    #[cfg(feature = "mailmap")]
+    pub fn author(&self) -> Result<gix_actor::Signature, info::Error> {
+        let commit = self.object()?;
+        Ok(self.resolve(commit.author()?))
+    }
+
+    /// Read the commit and return its committer, with name and email canonicalized by the [mailmap](Platform::with_mailmap())
+    /// if one was set.
+    ///
+    /// Note that this reads the whole object, just like [`object()`](Self::object()).
+    #[cfg(feature = "mailmap")]
+    pub fn committer(&self) -> Result<gix_actor::Signature, info::Error> {
+        let commit = self.object()?;
+        Ok(self.resolve(commit.committer()?))
+    }
+
+    #[cfg(feature = "mailmap")]
+    fn resolve(&self, signature: gix_actor::SignatureRef<'_>) -> gix_actor::Signature {
+        match self.mailmap {
+            Some(mailmap) => mailmap.resolve(signature),
+            None => signature.to_owned(),
+        }
+    }
 }
 
 /// Initialization and detachment
@@ -68,6 +150,8 @@ impl<'repo> Info<'repo> {
             parent_ids: info.parent_ids,
             commit_time: info.commit_time,
             repo,
+            #[cfg(feature = "mailmap")]
+            mailmap: None,
         }
     }
     /// Consume this instance and remove the reference to the underlying repository.
@@ -95,6 +179,9 @@ pub struct Platform<'repo> {
     pub(crate) parents: gix_traverse::commit::Parents,
     pub(crate) use_commit_graph: Option<bool>,
     pub(crate) commit_graph: Option<gix_commitgraph::Graph>,
+    pub(crate) checkpoint: Option<Checkpoint>,
+    #[cfg(feature = "mailmap")]
+    pub(crate) mailmap: Option<&'repo gix_mailmap::Snapshot>,
 }
 
 impl<'repo> Platform<'repo> {
@@ -106,6 +193,9 @@ impl<'repo> Platform<'repo> {
             parents: Default::default(),
             use_commit_graph: None,
             commit_graph: None,
+            checkpoint: None,
+            #[cfg(feature = "mailmap")]
+            mailmap: None,
         }
     }
 }
@@ -128,6 +218,7 @@ impl<'repo> Platform<'repo> {
     /// control over this to the configuration of `core.commitGraph` (the default).
     ///
     /// Errors when loading the graph lead to falling back to the object database, it's treated as optional cache.
+    /// It's never used if there are replacement objects, as these would be ignored otherwise.
     pub fn use_commit_graph(mut self, toggle: impl Into<Option<bool>>) -> Self {
         self.use_commit_graph = toggle.into();
         self
@@ -143,6 +234,27 @@ impl<'repo> Platform<'repo> {
         self.commit_graph = graph;
         self
     }
+
+    /// Continue the traversal where the one that produced `checkpoint` with [`Walk::checkpoint()`](revision::Walk::checkpoint())
+    /// left off, instead of starting from scratch.
+    ///
+    /// Commits that were returned already won't be returned again, and tips are only traversed if they weren't seen yet,
+    /// which allows to process new commits incrementally. Sorting and parent handling should be configured just like
+    /// they were for the traversal that produced `checkpoint`.
+    pub fn resume_from(mut self, checkpoint: Checkpoint) -> Self {
+        self.checkpoint = Some(checkpoint);
+        self
+    }
+
+    /// Canonicalize the [author](Info::author()) and [committer](Info::committer()) of each traversed commit with `mailmap`,
+    /// like `git log --use-mailmap` does.
+    ///
+    /// Use [`Repository::mailmap()`](crate::Repository::mailmap()) to obtain the mailmap as configured.
+    #[cfg(feature = "mailmap")]
+    pub fn with_mailmap(mut self, mailmap: &'repo gix_mailmap::Snapshot) -> Self {
+        self.mailmap = Some(mailmap);
+        self
+    }
 }
 
 /// Produce the iterator
@@ -151,10 +263,7 @@ impl<'repo> Platform<'repo> {
     /// if the traversal should exclude it and its ancestry entirely.
     ///
     /// If `filter` is None, no pruning of the graph will be performed which is the default.
-    pub fn selected(
-        self,
-        mut filter: impl FnMut(&gix_hash::oid) -> bool + 'repo,
-    ) -> Result<revision::Walk<'repo>, Error> {
+    pub fn selected(self, filter: impl FnMut(&gix_hash::oid) -> bool + 'repo) -> Result<revision::Walk<'repo>, Error> {
         let Platform {
             repo,
             tips,
@@ -162,53 +271,34 @@ impl<'repo> Platform<'repo> {
             parents,
             use_commit_graph,
             commit_graph,
+            checkpoint,
+            #[cfg(feature = "mailmap")]
+            mailmap,
         } = self;
+        let mut grafts = repo.grafts()?.unwrap_or_default();
+        if let Some(commits) = repo.shallow_commits()? {
+            // Commits at the shallow boundary don't have parents, even if they are grafted.
+            grafts.extend(commits.iter().map(|id| (*id, Default::default())));
+        }
+        let state = checkpoint.map(Into::into).unwrap_or_default();
+        let filter: iter::Filter<'repo> = Box::new(filter);
         Ok(revision::Walk {
             repo,
-            inner: Box::new(
-                gix_traverse::commit::Ancestors::filtered(
-                    tips,
-                    gix_traverse::commit::ancestors::State::default(),
-                    &repo.objects,
-                    {
-                        // Note that specific shallow handling for commit-graphs isn't needed as these contain
-                        // all information there is, and exclude shallow parents to be structurally consistent.
-                        let shallow_commits = repo.shallow_commits()?;
-                        let mut grafted_parents_to_skip = Vec::new();
-                        let mut buf = Vec::new();
-                        move |id| {
-                            if !filter(id) {
-                                return false;
-                            }
-                            match shallow_commits.as_ref() {
-                                Some(commits) => {
-                                    let id = id.to_owned();
-                                    if let Ok(idx) = grafted_parents_to_skip.binary_search(&id) {
-                                        grafted_parents_to_skip.remove(idx);
-                                        return false;
-                                    };
-                                    if commits.binary_search(&id).is_ok() {
-                                        if let Ok(commit) = repo.objects.find_commit_iter(&id, &mut buf) {
-                                            grafted_parents_to_skip.extend(commit.parent_ids());
-                                            grafted_parents_to_skip.sort();
-                                        }
-                                    };
-                                    true
-                                }
-                                None => true,
-                            }
-                        }
-                    },
-                )
+            #[cfg(feature = "mailmap")]
+            mailmap,
+            // A default state is empty, so resuming from it is the same as starting a new traversal.
+            inner: gix_traverse::commit::Ancestors::resume(tips, state, &repo.objects, filter)
                 .sorting(sorting)?
                 .parents(parents)
+                .grafts(grafts)
                 .commit_graph(
-                    commit_graph.or(use_commit_graph
-                        .map_or_else(|| self.repo.config.may_use_commit_graph(), Ok)?
+                    commit_graph.or(
+                        (use_commit_graph.map_or_else(|| self.repo.config.may_use_commit_graph(), Ok)?
+                            && !self.repo.has_replacements())
                         .then(|| self.repo.commit_graph().ok())
-                        .flatten()),
+                        .flatten(),
+                    ),
                 ),
-            ),
         })
     }
     /// Return an iterator to traverse all commits reachable as configured by the [Platform].
@@ -222,22 +312,127 @@ impl<'repo> Platform<'repo> {
     }
 }
 
+/// Produce diffs along with the traversal
+#[cfg(feature = "blob-diff")]
+impl<'repo> Platform<'repo> {
+    /// Traverse [all](Self::all()) commits and call `for_each` with each commit and every change that is needed to obtain its tree
+    /// from the tree of its first parent, or from the empty tree if it has no parent, similar to `git log -p --diff-merges=first-parent`.
+    ///
+    /// Paths of changes are always tracked, and rewrites are tracked according to the configuration, just like
+    /// [`Tree::changes()`](crate::Tree::changes()) would do it. Return [`Action::Cancel`](crate::object::tree::diff::Action::Cancel)
+    /// to stop the traversal.
+    ///
+    /// # Performance
+    ///
+    /// Compared to diffing each commit against its parent individually, the tree of a parent is kept until the parent itself
+    /// is traversed, so each tree along a line of history is only decoded once, and the diff state is reused for all commits.
+    /// When sorting by commit time, trees of parents that are older than the current commit are forgotten as they won't be
+    /// traversed anymore, which happens for parents beyond a cutoff date for instance.
+    /// Setting an [object cache](Repository::object_cache_size()) helps further as changed subtrees of a parent will be needed
+    /// again when diffing it against its own parent.
+    pub fn walk_with_diffs<E>(
+        self,
+        mut for_each: impl FnMut(
+            &Info<'repo>,
+            crate::object::tree::diff::Change<'_, 'repo, 'repo>,
+        ) -> Result<crate::object::tree::diff::Action, E>,
+    ) -> Result<(), with_diffs::Error>
+    where
+        E: std::error::Error + Sync + Send + 'static,
+    {
+        use crate::object::tree::diff;
+
+        let repo = self.repo;
+        let rewrites = repo.config.diff_renames()?.unwrap_or_default().into();
+        let empty_tree = repo.empty_tree();
+        // The trees of parents that are yet to be traversed along with their commit time, keyed by the id of their commit.
+        let mut parent_trees =
+            std::collections::HashMap::<ObjectId, (crate::Tree<'repo>, gix_date::SecondsSinceUnixEpoch)>::new();
+        let mut state = gix_diff::tree::State::default();
+        for info in self.all()? {
+            let info = info?;
+            let tree = match parent_trees.remove(&info.id) {
+                Some((tree, _)) => tree,
+                None => info.object()?.tree()?,
+            };
+            if let Some(commit_time) = info.commit_time {
+                // Newer commits are traversed first, so a newer parent we still have is hidden, and all of its children were seen.
+                parent_trees.retain(|_, (_, parent_time)| *parent_time <= commit_time);
+            }
+            let parent_tree = match info.parent_ids.first() {
+                Some(parent_id) => {
+                    if !parent_trees.contains_key(parent_id) {
+                        let parent = repo.find_object(*parent_id)?.try_into_commit()?;
+                        let parent_time = parent.time()?.seconds;
+                        parent_trees.insert(*parent_id, (parent.tree()?, parent_time));
+                    }
+                    &parent_trees[parent_id].0
+                }
+                None => &empty_tree,
+            };
+
+            let mut platform = diff::Platform::with_state(parent_tree, std::mem::take(&mut state), rewrites);
+            platform.track_path();
+            let mut cancelled = false;
+            let res = platform.for_each_to_obtain_tree(&tree, |change| {
+                let action = for_each(&info, change)?;
+                cancelled |= action == diff::Action::Cancel;
+                Ok::<_, E>(action)
+            });
+            state = platform.into_state();
+            match res {
+                Ok(_) => {}
+                Err(diff::for_each::Error::Diff(gix_diff::tree::changes::Error::Cancelled)) if cancelled => {}
+                Err(err) => return Err(err.into()),
+            }
+            if cancelled {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
 pub(crate) mod iter {
+    pub(crate) type Filter<'repo> = Box<dyn FnMut(&gix_hash::oid) -> bool + 'repo>;
+
     /// The iterator returned by [`crate::revision::walk::Platform::all()`].
     pub struct Walk<'repo> {
         pub(crate) repo: &'repo crate::Repository,
-        pub(crate) inner: Box<
-            dyn Iterator<Item = Result<gix_traverse::commit::Info, gix_traverse::commit::ancestors::Error>> + 'repo,
+        #[cfg(feature = "mailmap")]
+        pub(crate) mailmap: Option<&'repo gix_mailmap::Snapshot>,
+        pub(crate) inner: gix_traverse::commit::Ancestors<
+            &'repo crate::OdbHandle,
+            Filter<'repo>,
+            gix_traverse::commit::ancestors::State,
         >,
     }
 
+    impl<'repo> Walk<'repo> {
+        /// Capture the state of the traversal to be able to [resume](super::Platform::resume_from()) it later,
+        /// possibly after persisting it.
+        ///
+        /// Note that commits that were already returned won't be returned again when resuming.
+        pub fn checkpoint(&self) -> super::Checkpoint {
+            self.inner.state().checkpoint()
+        }
+    }
+
     impl<'repo> Iterator for Walk<'repo> {
         type Item = Result<super::Info<'repo>, gix_traverse::commit::ancestors::Error>;
 
         fn next(&mut self) -> Option<Self::Item> {
-            self.inner
-                .next()
-                .map(|res| res.map(|info| super::Info::new(info, self.repo)))
+            self.inner.next().map(|res| {
+                res.map(|info| {
+                    #[allow(unused_mut)]
+                    let mut info = super::Info::new(info, self.repo);
+                    #[cfg(feature = "mailmap")]
+                    {
+                        info.mailmap = self.mailmap;
+                    }
+                    info
+                })
+            })
         }
     }
 }
@@ -90,3 +90,19 @@ pub mod open {
         DecodeHash(#[from] gix_hash::decode::Error),
     }
 }
+
+///
+pub mod grafts {
+    /// The error returned by [`Repository::grafts()`][crate::Repository::grafts()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not open grafts file for reading")]
+        Io(#[from] std::io::Error),
+        #[error("Could not decode line {line_number} in grafts file as hex-encoded object hashes")]
+        DecodeHash {
+            line_number: usize,
+            source: gix_hash::decode::Error,
+        },
+    }
+}
@@ -0,0 +1,273 @@
+//! Restrict the worktree to a subset of the tracked files, similar to `git sparse-checkout`.
+//!
+//! The [`Definition`] of which files to check out is stored in `$GIT_DIR/info/sparse-checkout`, and it's active only if
+//! `core.sparseCheckout` is enabled. Index entries of all other files are marked with the
+//! [`SKIP_WORKTREE`](gix_index::entry::Flags::SKIP_WORKTREE) flag, and they aren't present in the worktree.
+//!
+//! Use [`Repository::sparse_checkout()`](crate::Repository::sparse_checkout()) to read the current definition,
+//! and [`Repository::set_sparse_checkout()`](crate::Repository::set_sparse_checkout()) to change it and apply it right away.
+use std::collections::BTreeSet;
+
+use crate::bstr::{BStr, BString, ByteSlice, ByteVec};
+
+/// The definition of the files to check out, as stored in the sparse-checkout file.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Definition {
+    /// Check out all files in the root of the worktree, everything below each of `directories`, and all files directly within
+    /// the leading directories of these, like `git sparse-checkout set --cone`.
+    ///
+    /// This is also known as *cone mode*, and it's what `git` uses by default as it's much faster to match.
+    Cone {
+        /// Directories relative to the root of the worktree, without leading or trailing slash.
+        directories: Vec<BString>,
+    },
+    /// Check out all files matching the given patterns, which work like `.gitignore` patterns with a matching path
+    /// being included instead of being ignored, like `git sparse-checkout set --no-cone`.
+    Patterns {
+        /// One pattern per line, just like in the sparse-checkout file.
+        patterns: Vec<BString>,
+    },
+}
+
+impl Definition {
+    /// Parse the contents of a sparse-checkout file in `data`, interpreting it as [cone mode](Definition::Cone) if `cone` is `true`.
+    ///
+    /// Just like `git`, patterns that don't follow the cone mode format are used as [patterns](Definition::Patterns) instead.
+    pub fn from_bytes(data: &[u8], cone: bool) -> Self {
+        let lines: Vec<BString> = data
+            .lines()
+            .map(ByteSlice::trim)
+            .filter(|line| !line.is_empty() && line[0] != b'#')
+            .map(|line| line.as_bstr().to_owned())
+            .collect();
+        match cone.then(|| parse_cone(&lines)).flatten() {
+            Some(directories) => Definition::Cone { directories },
+            None => Definition::Patterns { patterns: lines },
+        }
+    }
+
+    /// Return `true` if this is a definition in [cone mode](Definition::Cone).
+    pub fn is_cone(&self) -> bool {
+        matches!(self, Definition::Cone { .. })
+    }
+
+    /// Serialize this instance into the format of the sparse-checkout file, which can be read back with
+    /// [`from_bytes()`](Self::from_bytes()).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        match self {
+            Definition::Cone { directories } => {
+                let (recursive, parents) = cone_sets(directories);
+                out.push_str("/*\n!/*/\n");
+                for parent in &parents {
+                    out.push_str(format!("/{parent}/\n!/{parent}/*/\n"));
+                }
+                for dir in &recursive {
+                    out.push_str(format!("/{dir}/\n"));
+                }
+            }
+            Definition::Patterns { patterns } => {
+                for pattern in patterns {
+                    out.push_str(pattern);
+                    out.push(b'\n');
+                }
+            }
+        }
+        out
+    }
+
+    /// Return a utility to efficiently learn which paths are included by this definition.
+    pub fn search(&self) -> Search {
+        Search {
+            inner: match self {
+                Definition::Cone { directories } => {
+                    let (recursive, parents) = cone_sets(directories);
+                    Inner::Cone { recursive, parents }
+                }
+                Definition::Patterns { patterns } => Inner::Patterns(
+                    patterns
+                        .iter()
+                        .filter_map(|pattern| gix_ignore::parse(pattern).next().map(|(pattern, _)| pattern))
+                        .collect(),
+                ),
+            },
+        }
+    }
+}
+
+/// A utility to match paths against a sparse-checkout [`Definition`], as obtained by [`Definition::search()`].
+#[derive(Debug, Clone)]
+pub struct Search {
+    inner: Inner,
+}
+
+#[derive(Debug, Clone)]
+enum Inner {
+    Cone {
+        recursive: BTreeSet<BString>,
+        parents: BTreeSet<BString>,
+    },
+    Patterns(Vec<gix_glob::Pattern>),
+}
+
+impl Search {
+    /// Return `true` if the file at `rela_path`, relative to the root of the worktree, is included in the sparse checkout.
+    pub fn is_included(&self, rela_path: &BStr) -> bool {
+        match &self.inner {
+            Inner::Cone { recursive, parents } => match rela_path.rfind_byte(b'/') {
+                None => true,
+                Some(pos) => {
+                    parents.contains(rela_path[..pos].as_bstr())
+                        || rela_path
+                            .find_iter("/")
+                            .any(|pos| recursive.contains(rela_path[..pos].as_bstr()))
+                }
+            },
+            Inner::Patterns(patterns) => {
+                // Leading directories are matched first so that files within them inherit their result,
+                // unless a more specific pattern matches.
+                let mut is_included = false;
+                let ends = rela_path
+                    .find_iter("/")
+                    .map(|pos| (pos, true))
+                    .chain(Some((rela_path.len(), false)));
+                for (end, is_dir) in ends {
+                    let path = rela_path[..end].as_bstr();
+                    let basename_start = path.rfind_byte(b'/').map(|pos| pos + 1);
+                    if let Some(pattern) = patterns.iter().rev().find(|pattern| {
+                        pattern.matches_repo_relative_path(
+                            path,
+                            basename_start,
+                            Some(is_dir),
+                            gix_glob::pattern::Case::Sensitive,
+                            gix_glob::wildmatch::Mode::NO_MATCH_SLASH_LITERAL,
+                        )
+                    }) {
+                        is_included = !pattern.is_negative();
+                    }
+                }
+                is_included
+            }
+        }
+    }
+}
+
+/// The outcome of applying a sparse-checkout [`Definition`] to the index and the worktree.
+#[derive(Debug, Default, Clone)]
+pub struct Outcome {
+    /// Files that were written to the worktree as they are included now.
+    pub added: Vec<BString>,
+    /// Files that were removed from the worktree as they are excluded now.
+    pub removed: Vec<BString>,
+    /// Files that are excluded, but were kept in the worktree along with their index entry as they have local changes,
+    /// just like `git` does.
+    pub kept: Vec<BString>,
+}
+
+///
+pub mod read {
+    /// The error returned by [`Repository::sparse_checkout()`](crate::Repository::sparse_checkout()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Config(#[from] crate::config::boolean::Error),
+        #[error("Could not read the sparse-checkout file at '{}'", path.display())]
+        Io {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+    }
+}
+
+///
+pub mod apply {
+    /// The error returned by [`Repository::reapply_sparse_checkout()`](crate::Repository::reapply_sparse_checkout()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ReadOnly(#[from] crate::repository::read_only::Error),
+        #[error("A working tree is required to apply a sparse checkout")]
+        MissingWorkDir,
+        #[error(transparent)]
+        Read(#[from] super::read::Error),
+        #[error(transparent)]
+        Stage(#[from] crate::stage::init::Error),
+        #[error(transparent)]
+        Status(#[from] crate::status::Error),
+        #[error(transparent)]
+        FilesystemOptions(#[from] crate::config::boolean::Error),
+        #[error(transparent)]
+        Worktree(#[from] crate::checkout::tree::Error),
+        #[error(transparent)]
+        WriteIndex(#[from] crate::stage::write::Error),
+    }
+}
+
+///
+pub mod set {
+    /// The error returned by [`Repository::set_sparse_checkout()`](crate::Repository::set_sparse_checkout())
+    /// and [`Repository::disable_sparse_checkout()`](crate::Repository::disable_sparse_checkout()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ReadOnly(#[from] crate::repository::read_only::Error),
+        #[error(transparent)]
+        Lock(#[from] gix_lock::acquire::Error),
+        #[error("Could not write the sparse-checkout file at '{}'", path.display())]
+        Io {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+        #[error(transparent)]
+        Config(#[from] crate::config::persist::Error),
+        #[error(transparent)]
+        Apply(#[from] super::apply::Error),
+    }
+}
+
+/// Parse `lines` in the cone mode format and return the directories that are included recursively,
+/// or `None` if the format isn't followed.
+fn parse_cone(lines: &[BString]) -> Option<Vec<BString>> {
+    let mut lines = lines.iter();
+    if lines.next()? != "/*" || lines.next()? != "!/*/" {
+        return None;
+    }
+    let mut included = Vec::new();
+    let mut parents = BTreeSet::new();
+    for line in lines {
+        if let Some(parent) = line.strip_prefix(b"!/").and_then(|line| line.strip_suffix(b"/*/")) {
+            parents.insert(parent.as_bstr().to_owned());
+        } else if let Some(dir) = line.strip_prefix(b"/").and_then(|line| line.strip_suffix(b"/")) {
+            if dir.is_empty() || dir.contains(&b'*') {
+                return None;
+            }
+            included.push(dir.as_bstr().to_owned());
+        } else {
+            return None;
+        }
+    }
+    Some(included.into_iter().filter(|dir| !parents.contains(dir)).collect())
+}
+
+/// Return all `directories` that are included recursively, without those that are contained in others, along with all of their
+/// leading directories.
+fn cone_sets(directories: &[BString]) -> (BTreeSet<BString>, BTreeSet<BString>) {
+    let all: BTreeSet<_> = directories
+        .iter()
+        .map(|dir| dir.trim_with(|c| c == '/').as_bstr().to_owned())
+        .filter(|dir| !dir.is_empty())
+        .collect();
+    let recursive: BTreeSet<BString> = all
+        .iter()
+        .filter(|dir| !dir.find_iter("/").any(|pos| all.contains(dir[..pos].as_bstr())))
+        .cloned()
+        .collect();
+    let parents = recursive
+        .iter()
+        .flat_map(|dir| dir.find_iter("/").map(move |pos| dir[..pos].as_bstr().to_owned()))
+        .collect();
+    (recursive, parents)
+}
@@ -0,0 +1,209 @@
+use std::ops::Range;
+
+use gix_diff::blob::{intern::InternedInput, sources::byte_lines_with_terminator};
+
+use super::{add, Hunk, Platform};
+use crate::bstr::{BStr, BString, ByteSlice};
+
+/// The error returned by [`Platform::worktree_hunks()`], [`Platform::stage_hunks()`], [`Platform::index_hunks()`]
+/// and [`Platform::unstage_hunks()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("'{rela_path}' isn't tracked and can't have hunks staged or unstaged")]
+    NotInIndex { rela_path: BString },
+    #[error("'{rela_path}' isn't a file and can't be staged or unstaged by hunks")]
+    NotAFile { rela_path: BString },
+    #[error("Lines {:?} to replace with {:?} are not part of a single hunk of '{rela_path}', or overlap with other lines", hunk.before, hunk.after)]
+    InvalidHunk { rela_path: BString, hunk: Hunk },
+    #[error(transparent)]
+    Read(#[from] add::Error),
+    #[error(transparent)]
+    DiffAlgorithm(#[from] crate::config::diff::algorithm::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    FindHead(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    PeelHead(#[from] crate::head::peel::to_commit::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+}
+
+/// Partial staging
+impl<'repo> Platform<'repo> {
+    /// Return all hunks that differ between the index and the worktree version of the file at `rela_path`, after passing it
+    /// through the filters that would apply when [staging it](Self::add_path()).
+    ///
+    /// Any selection of these can be staged with [`stage_hunks()`](Self::stage_hunks()).
+    pub fn worktree_hunks(&mut self, rela_path: impl AsRef<BStr>) -> Result<Vec<Hunk>, Error> {
+        let rela_path = rela_path.as_ref();
+        let (index, _) = self.index_data(rela_path)?;
+        let worktree = self.worktree_data(rela_path)?;
+        self.hunks(&index, &worktree)
+    }
+
+    /// Stage only the `hunks` of the changes between the index and the worktree version of the file at `rela_path`, similar to
+    /// `git add -p`, and return the id of the blob that is now referenced by the index.
+    ///
+    /// `hunks` are typically obtained by [`worktree_hunks()`](Self::worktree_hunks()), but can also be narrowed down to stage
+    /// only some lines of a hunk. To do that, each of the `hunks` has to be within a single hunk, and they must be sorted
+    /// without overlapping each other.
+    pub fn stage_hunks(&mut self, rela_path: impl AsRef<BStr>, hunks: &[Hunk]) -> Result<gix_hash::ObjectId, Error> {
+        let rela_path = rela_path.as_ref();
+        let (index, mode) = self.index_data(rela_path)?;
+        let worktree = self.worktree_data(rela_path)?;
+        let all_hunks = self.hunks(&index, &worktree)?;
+        verify(rela_path, hunks, &all_hunks)?;
+        let data = apply(
+            &index,
+            &worktree,
+            hunks.iter().map(|hunk| (hunk.before.clone(), hunk.after.clone())),
+        );
+        self.upsert_data(rela_path, &data, mode)
+    }
+
+    /// Return all hunks that differ between the version of the file at `rela_path` in `HEAD` and the one in the index,
+    /// which is considered empty if it's not in `HEAD`.
+    ///
+    /// Any selection of these can be unstaged with [`unstage_hunks()`](Self::unstage_hunks()).
+    pub fn index_hunks(&mut self, rela_path: impl AsRef<BStr>) -> Result<Vec<Hunk>, Error> {
+        let rela_path = rela_path.as_ref();
+        let (index, _) = self.index_data(rela_path)?;
+        let head = self.head_data(rela_path)?;
+        self.hunks(&head, &index)
+    }
+
+    /// Unstage only the `hunks` of the changes between `HEAD` and the index version of the file at `rela_path`, similar to
+    /// `git reset -p`, and return the id of the blob that is now referenced by the index.
+    ///
+    /// `hunks` are typically obtained by [`index_hunks()`](Self::index_hunks()), and the same rules as for
+    /// [`stage_hunks()`](Self::stage_hunks()) apply when narrowing them down to unstage only some lines.
+    pub fn unstage_hunks(&mut self, rela_path: impl AsRef<BStr>, hunks: &[Hunk]) -> Result<gix_hash::ObjectId, Error> {
+        let rela_path = rela_path.as_ref();
+        let (index, mode) = self.index_data(rela_path)?;
+        let head = self.head_data(rela_path)?;
+        let all_hunks = self.hunks(&head, &index)?;
+        verify(rela_path, hunks, &all_hunks)?;
+        // Revert the hunks by replacing the new lines in the index with the old ones from `HEAD`.
+        let data = apply(
+            &index,
+            &head,
+            hunks.iter().map(|hunk| (hunk.after.clone(), hunk.before.clone())),
+        );
+        self.upsert_data(rela_path, &data, mode)
+    }
+}
+
+/// Utilities
+impl<'repo> Platform<'repo> {
+    fn hunks(&self, before: &[u8], after: &[u8]) -> Result<Vec<Hunk>, Error> {
+        let input = InternedInput::new(byte_lines_with_terminator(before), byte_lines_with_terminator(after));
+        let mut hunks = Vec::new();
+        gix_diff::blob::diff(
+            self.repo.config.diff_algorithm()?,
+            &input,
+            |before: Range<u32>, after: Range<u32>| hunks.push(Hunk { before, after }),
+        );
+        Ok(hunks)
+    }
+
+    /// Return the content of the file at `rela_path` in the index, along with its mode.
+    fn index_data(&self, rela_path: &BStr) -> Result<(Vec<u8>, gix_index::entry::Mode), Error> {
+        let entry = self
+            .index
+            .entry_by_path_and_stage(rela_path, 0)
+            .ok_or_else(|| Error::NotInIndex {
+                rela_path: rela_path.to_owned(),
+            })?;
+        if !matches!(
+            entry.mode,
+            gix_index::entry::Mode::FILE | gix_index::entry::Mode::FILE_EXECUTABLE
+        ) {
+            return Err(Error::NotAFile {
+                rela_path: rela_path.to_owned(),
+            });
+        }
+        let mode = entry.mode;
+        Ok((self.repo.find_object(entry.id)?.detach().data, mode))
+    }
+
+    /// Return the content of the file at `rela_path` in the worktree, converted to what would be stored in `git`.
+    fn worktree_data(&mut self, rela_path: &BStr) -> Result<Vec<u8>, add::Error> {
+        let work_dir = self.repo.work_dir().ok_or(add::Error::MissingWorkDir)?;
+        let path = work_dir.join(gix_path::from_bstr(rela_path));
+        let file = std::fs::File::open(&path).map_err(|source| add::Error::Io { path, source })?;
+        self.convert_to_git(file, rela_path)?;
+        Ok(std::mem::take(&mut self.buf))
+    }
+
+    /// Return the content of the file at `rela_path` in the tree of `HEAD`, or an empty buffer if there is none.
+    fn head_data(&self, rela_path: &BStr) -> Result<Vec<u8>, Error> {
+        let mut head = self.repo.head()?;
+        if head.is_unborn() {
+            return Ok(Vec::new());
+        }
+        let tree = self
+            .repo
+            .find_object(head.peel_to_commit_in_place()?.tree_id()?)?
+            .into_tree();
+        let mut buf = Vec::new();
+        Ok(match tree.lookup_entry(rela_path.split_str("/"), &mut buf)? {
+            Some(entry) if entry.mode().is_blob() => entry.object()?.detach().data,
+            _ => Vec::new(),
+        })
+    }
+
+    fn upsert_data(
+        &mut self,
+        rela_path: &BStr,
+        data: &[u8],
+        mode: gix_index::entry::Mode,
+    ) -> Result<gix_hash::ObjectId, Error> {
+        let id = self.repo.write_blob(data).map_err(add::Error::from)?.detach();
+        self.upsert(rela_path, id, mode, gix_index::entry::Stat::default());
+        Ok(id)
+    }
+}
+
+/// Assure that each of `selected` lies within one of `all` hunks, and that they are sorted without overlap.
+fn verify(rela_path: &BStr, selected: &[Hunk], all: &[Hunk]) -> Result<(), Error> {
+    let contains = |outer: &Range<u32>, inner: &Range<u32>| outer.start <= inner.start && inner.end <= outer.end;
+    let mut previous: Option<&Hunk> = None;
+    for hunk in selected {
+        let is_valid = all
+            .iter()
+            .any(|candidate| contains(&candidate.before, &hunk.before) && contains(&candidate.after, &hunk.after))
+            && previous.map_or(true, |previous| {
+                previous.before.end <= hunk.before.start && previous.after.end <= hunk.after.start
+            });
+        if !is_valid {
+            return Err(Error::InvalidHunk {
+                rela_path: rela_path.to_owned(),
+                hunk: hunk.clone(),
+            });
+        }
+        previous = Some(hunk);
+    }
+    Ok(())
+}
+
+/// Produce a copy of `base` in which each range of lines in `base` is replaced by the corresponding range of lines in `other`.
+fn apply(base: &[u8], other: &[u8], replacements: impl Iterator<Item = (Range<u32>, Range<u32>)>) -> Vec<u8> {
+    let base: Vec<_> = byte_lines_with_terminator(base).collect();
+    let other: Vec<_> = byte_lines_with_terminator(other).collect();
+    let mut out = Vec::with_capacity(base.iter().map(|line| line.len()).sum());
+    let mut pos = 0;
+    for (base_range, other_range) in replacements {
+        out.extend(base[pos..base_range.start as usize].iter().copied().flatten());
+        out.extend(
+            other[other_range.start as usize..other_range.end as usize]
+                .iter()
+                .copied()
+                .flatten(),
+        );
+        pos = base_range.end as usize;
+    }
+    out.extend(base[pos..].iter().copied().flatten());
+    out
+}
@@ -0,0 +1,398 @@
+//! Stage files from the worktree or in-memory contents into the index, similar to `git add` and `git rm --cached`.
+//!
+//! Use [`Repository::stage()`](crate::Repository::stage()) to obtain a [`Platform`] which holds a copy of the index
+//! to change, and [write](Platform::write()) it back atomically once done.
+use std::io::Read;
+
+use gix_index::entry;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    Repository,
+};
+
+/// A utility to change the entries of an in-memory copy of the index before writing it back.
+///
+/// All content is passed through the filters configured in `.gitattributes` and git configuration before it is written to the
+/// object database, just like `git add` would.
+pub struct Platform<'repo> {
+    pub(crate) repo: &'repo Repository,
+    pub(crate) index: gix_index::File,
+    pub(crate) filter: crate::filter::Pipeline<'repo>,
+    pub(crate) buf: Vec<u8>,
+    pub(crate) warnings: Vec<Warning>,
+}
+
+/// A warning about a file whose line endings wouldn't be the same if it was checked out again after staging it,
+/// as produced if `core.safecrlf` is set to `warn`, which is the default.
+///
+/// Obtain them with [`Platform::take_warnings()`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Warning {
+    /// The repository-relative path of the file that was converted.
+    pub rela_path: BString,
+    /// The change to its line endings.
+    pub kind: gix_filter::eol::convert_to_git::RoundTripWarning,
+}
+
+/// A contiguous change of lines between two versions of a file, with lines counted from zero.
+///
+/// It's used to stage or unstage only parts of a file, see [`Platform::stage_hunks()`] and [`Platform::unstage_hunks()`].
+#[cfg(feature = "blob-diff")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Hunk {
+    /// The lines of the old version that are replaced, which is empty if lines are only added.
+    pub before: std::ops::Range<u32>,
+    /// The lines of the new version that replace them, which is empty if lines are only removed.
+    pub after: std::ops::Range<u32>,
+}
+
+///
+#[cfg(feature = "blob-diff")]
+pub mod hunks;
+
+///
+pub mod init {
+    /// The error returned by [`Repository::stage()`](crate::Repository::stage()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        AttributeStack(#[from] crate::config::attribute_stack::Error),
+        #[error(transparent)]
+        FilterPipeline(#[from] crate::filter::pipeline::options::Error),
+    }
+}
+
+///
+pub mod add {
+    use crate::bstr::BString;
+
+    /// The error returned by [`Platform::add_path()`](super::Platform::add_path()) and
+    /// [`Platform::add_bytes()`](super::Platform::add_bytes()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("A working tree is required to stage files from it")]
+        MissingWorkDir,
+        #[error("Could not read '{}' to stage it", path.display())]
+        Io {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+        #[error("'{rela_path}' is a directory which cannot be staged directly")]
+        IsDirectory { rela_path: BString },
+        #[error("Could not open the repository at '{rela_path}' to stage its current commit")]
+        OpenNestedRepository {
+            rela_path: BString,
+            source: Box<crate::open::Error>,
+        },
+        #[error("The repository at '{rela_path}' has no commit that could be staged")]
+        NestedRepositoryHead {
+            rela_path: BString,
+            source: crate::reference::head_id::Error,
+        },
+        #[error(transparent)]
+        FilesystemOptions(#[from] crate::config::boolean::Error),
+        #[error(transparent)]
+        ConvertToGit(#[from] crate::filter::pipeline::convert_to_git::Error),
+        #[error(transparent)]
+        WriteBlob(#[from] crate::object::write::Error),
+        #[error("Could not obtain the modification time of '{}'", path.display())]
+        Stat {
+            path: std::path::PathBuf,
+            source: std::time::SystemTimeError,
+        },
+    }
+}
+
+///
+pub mod write {
+    /// The error returned by [`Platform::write()`](super::Platform::write()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ReadOnly(#[from] crate::repository::read_only::Error),
+        #[error(transparent)]
+        SkipHash(#[from] crate::config::boolean::Error),
+        #[error(transparent)]
+        WriteIndex(#[from] gix_index::file::write::Error),
+    }
+}
+
+/// Access
+impl<'repo> Platform<'repo> {
+    /// Return the index with all changes applied so far.
+    pub fn index(&self) -> &gix_index::File {
+        &self.index
+    }
+
+    /// Return all warnings produced while converting files for storage in `git` since the last call, leaving none behind.
+    ///
+    /// Just like `git add`, which prints these, staging isn't prevented by them.
+    pub fn take_warnings(&mut self) -> Vec<Warning> {
+        std::mem::take(&mut self.warnings)
+    }
+
+    /// Return the index with all changes applied so far, without writing it.
+    pub fn into_index(self) -> gix_index::File {
+        self.index
+    }
+}
+
+/// Staging
+impl<'repo> Platform<'repo> {
+    /// Read the file, symlink or nested repository at `rela_path` in the worktree and stage it, just like `git add <rela_path>`,
+    /// returning the id of the object that is now referenced by the index.
+    ///
+    /// Files are passed through all filters that apply to them before they are written to the object database,
+    /// and the stat information of the file is recorded to speed up future comparisons.
+    /// Nested repositories are staged as submodule with their currently checked out commit.
+    ///
+    /// Conflicting entries at `rela_path` are resolved by the newly added entry, and entries that are in the way,
+    /// like a file at a leading directory of `rela_path`, are removed.
+    ///
+    /// If `core.precomposeUnicode` is enabled, `rela_path` is stored with precomposed unicode in the index.
+    pub fn add_path(&mut self, rela_path: impl AsRef<BStr>) -> Result<gix_hash::ObjectId, add::Error> {
+        let rela_path = rela_path.as_ref();
+        let work_dir = self.repo.work_dir().ok_or(add::Error::MissingWorkDir)?;
+        let path = work_dir.join(gix_path::from_bstr(rela_path));
+        let io_err = |source| add::Error::Io {
+            path: path.clone(),
+            source,
+        };
+        let metadata = std::fs::symlink_metadata(&path).map_err(io_err)?;
+        let fs = self.repo.filesystem_options()?;
+        let rela_path = if fs.precompose_unicode {
+            crate::path::precompose_unicode(rela_path)
+        } else {
+            rela_path.into()
+        };
+        let rela_path = rela_path.as_ref();
+        let (id, mode) = if metadata.is_dir() {
+            if !path.join(gix_discover::DOT_GIT_DIR).exists() {
+                return Err(add::Error::IsDirectory {
+                    rela_path: rela_path.to_owned(),
+                });
+            }
+            let repo =
+                crate::open_opts(&path, self.repo.options.clone()).map_err(|err| add::Error::OpenNestedRepository {
+                    rela_path: rela_path.to_owned(),
+                    source: Box::new(err),
+                })?;
+            let id = repo
+                .head_id()
+                .map_err(|err| add::Error::NestedRepositoryHead {
+                    rela_path: rela_path.to_owned(),
+                    source: err,
+                })?
+                .detach();
+            (id, entry::Mode::COMMIT)
+        } else if metadata.is_symlink() {
+            let target = std::fs::read_link(&path).map_err(io_err)?;
+            let target = gix_path::into_bstr(target);
+            let id = self.repo.write_blob(target.as_bytes())?.detach();
+            (id, entry::Mode::SYMLINK)
+        } else if !fs.symlink
+            && self
+                .index
+                .entry_by_path(rela_path)
+                .map_or(false, |entry| entry.mode == entry::Mode::SYMLINK)
+        {
+            // Without symlink support, symlinks are checked out as files containing their target, which remain symlinks.
+            let target = std::fs::read(&path).map_err(io_err)?;
+            let id = self.repo.write_blob(target)?.detach();
+            (id, entry::Mode::SYMLINK)
+        } else {
+            let mode = if fs.executable_bit {
+                if gix_fs::is_executable(&metadata) {
+                    entry::Mode::FILE_EXECUTABLE
+                } else {
+                    entry::Mode::FILE
+                }
+            } else {
+                // Without a reliable executable bit, retain what's already recorded.
+                self.index
+                    .entry_by_path(rela_path)
+                    .map(|entry| entry.mode)
+                    .filter(|mode| *mode == entry::Mode::FILE_EXECUTABLE)
+                    .unwrap_or(entry::Mode::FILE)
+            };
+            let file = std::fs::File::open(&path).map_err(io_err)?;
+            let id = self.write_filtered_blob(file, rela_path)?;
+            (id, mode)
+        };
+        let stat = entry::Stat::from_fs(&metadata).map_err(|source| add::Error::Stat { path, source })?;
+        self.upsert(rela_path, id, mode, stat);
+        Ok(id)
+    }
+
+    /// Stage `data` as content of the entry at `rela_path` with `mode`, without touching the worktree, and return the id
+    /// of the object that is now referenced by the index.
+    ///
+    /// Unless `mode` is a symlink or a submodule, `data` is passed through all filters that would apply to a file at `rela_path`,
+    /// just like `git update-index --add --cacheinfo` would after `git hash-object -w --path <rela_path>`.
+    /// For submodules, `data` is expected to be the hexadecimal id of the commit to record.
+    ///
+    /// As there is no file to obtain stat information from, the entry will always be considered modified in the worktree
+    /// until it's staged from there.
+    pub fn add_bytes(
+        &mut self,
+        rela_path: impl AsRef<BStr>,
+        data: impl AsRef<[u8]>,
+        mode: entry::Mode,
+    ) -> Result<gix_hash::ObjectId, add::Error> {
+        let rela_path = rela_path.as_ref();
+        let data = data.as_ref();
+        let id = if mode == entry::Mode::SYMLINK {
+            self.repo.write_blob(data)?.detach()
+        } else if mode == entry::Mode::COMMIT {
+            gix_hash::ObjectId::from_hex(data.trim()).map_err(|err| add::Error::Io {
+                path: gix_path::from_bstr(rela_path).into_owned(),
+                source: std::io::Error::new(std::io::ErrorKind::InvalidData, err),
+            })?
+        } else {
+            self.write_filtered_blob(data, rela_path)?
+        };
+        self.upsert(rela_path, id, mode, entry::Stat::default());
+        Ok(id)
+    }
+
+    /// Remove all entries at `rela_path` from the index, including conflicting ones, and return `true` if at least one was removed.
+    ///
+    /// This is what `git rm --cached <rela_path>` does, and it leaves the worktree untouched.
+    pub fn remove(&mut self, rela_path: impl AsRef<BStr>) -> bool {
+        let rela_path = rela_path.as_ref();
+        let num_entries = self.index.entries().len();
+        self.index.remove_entries(|_, path, _| path == rela_path);
+        let removed = self.index.entries().len() != num_entries;
+        if removed {
+            self.index.remove_tree();
+        }
+        removed
+    }
+
+    /// Write the index back to where it was read from while holding a lock, which makes the change atomic.
+    ///
+    /// Fails if the repository was opened [read-only](crate::open::Options::read_only()).
+    pub fn write(&mut self) -> Result<(), write::Error> {
+        self.repo.assure_writable("write the index")?;
+        let skip_hash = self.repo.index_skip_hash()?;
+        self.index.write(gix_index::write::Options {
+            skip_hash,
+            ..Default::default()
+        })?;
+        Ok(())
+    }
+}
+
+/// Flags
+impl<'repo> Platform<'repo> {
+    /// Return `true` if the entry at `rela_path` is marked to be assumed unchanged, or `None` if there is no such entry.
+    pub fn is_assume_unchanged(&self, rela_path: impl AsRef<BStr>) -> Option<bool> {
+        self.flags(rela_path.as_ref())
+            .map(|flags| flags.contains(entry::Flags::ASSUME_VALID))
+    }
+
+    /// Set or clear the flag that marks the entry at `rela_path` as assumed unchanged, similar to
+    /// `git update-index --[no-]assume-unchanged`, and return `false` if there is no such entry.
+    ///
+    /// The worktree file of such entries isn't checked for changes in a [status](crate::Repository::status()), which
+    /// saves time if it's known that the file wasn't changed.
+    pub fn set_assume_unchanged(&mut self, rela_path: impl AsRef<BStr>, value: bool) -> bool {
+        self.update_flags(rela_path.as_ref(), |flags| flags.set(entry::Flags::ASSUME_VALID, value))
+    }
+
+    /// Return `true` if the entry at `rela_path` is marked to skip the worktree, or `None` if there is no such entry.
+    pub fn is_skip_worktree(&self, rela_path: impl AsRef<BStr>) -> Option<bool> {
+        self.flags(rela_path.as_ref())
+            .map(|flags| flags.contains(entry::Flags::SKIP_WORKTREE))
+    }
+
+    /// Set or clear the flag that marks the entry at `rela_path` to skip the worktree, similar to
+    /// `git update-index --[no-]skip-worktree`, and return `false` if there is no such entry.
+    ///
+    /// Such entries are treated as if they matched the worktree by [status](crate::Repository::status()) and aren't
+    /// written by checkouts, even if the file is missing. Note that [sparse checkouts](crate::Repository::reapply_sparse_checkout())
+    /// set and clear this flag according to their patterns.
+    pub fn set_skip_worktree(&mut self, rela_path: impl AsRef<BStr>, value: bool) -> bool {
+        self.update_flags(rela_path.as_ref(), |flags| {
+            flags.set(entry::Flags::SKIP_WORKTREE, value);
+            // The flag is only written to indices of version 3 and later, which requires extended flags.
+            let needs_extended = flags.intersects(entry::Flags::SKIP_WORKTREE | entry::Flags::INTENT_TO_ADD);
+            flags.set(entry::Flags::EXTENDED, needs_extended);
+        })
+    }
+
+    fn flags(&self, rela_path: &BStr) -> Option<entry::Flags> {
+        self.index
+            .entry_by_path_and_stage(rela_path, 0)
+            .map(|entry| entry.flags)
+    }
+
+    fn update_flags(&mut self, rela_path: &BStr, update: impl FnOnce(&mut entry::Flags)) -> bool {
+        match self.index.entry_mut_by_path_and_stage(rela_path, 0) {
+            Some(entry) => {
+                update(&mut entry.flags);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Utilities
+impl<'repo> Platform<'repo> {
+    fn write_filtered_blob(&mut self, src: impl Read, rela_path: &BStr) -> Result<gix_hash::ObjectId, add::Error> {
+        self.convert_to_git(src, rela_path)?;
+        Ok(self.repo.write_blob(&self.buf)?.detach())
+    }
+
+    /// Pass `src` through all filters that apply to `rela_path` and place the result into our buffer.
+    fn convert_to_git(&mut self, src: impl Read, rela_path: &BStr) -> Result<(), add::Error> {
+        let mut converted = self
+            .filter
+            .convert_to_git(src, gix_path::from_bstr(rela_path).as_ref(), &self.index)?;
+        self.buf.clear();
+        converted.read_to_end(&mut self.buf).map_err(|source| add::Error::Io {
+            path: gix_path::from_bstr(rela_path).into_owned(),
+            source,
+        })?;
+        drop(converted);
+        if let Some(kind) = self.filter.round_trip_warning() {
+            self.warnings.push(Warning {
+                rela_path: rela_path.to_owned(),
+                kind,
+            });
+        }
+        Ok(())
+    }
+
+    /// Place an entry at `rela_path` on stage 0 and remove everything it would conflict with.
+    pub(crate) fn upsert(&mut self, rela_path: &BStr, id: gix_hash::ObjectId, mode: entry::Mode, stat: entry::Stat) {
+        let mut dir = BString::from(rela_path);
+        dir.push(b'/');
+        self.index.remove_entries(|_, path, entry| {
+            (path == rela_path && entry.stage() != 0)
+                || path.starts_with(dir.as_slice())
+                || (rela_path.starts_with(path.as_bytes()) && rela_path.get(path.len()) == Some(&b'/'))
+        });
+        match self.index.entry_mut_by_path_and_stage(rela_path, 0) {
+            Some(entry) => {
+                entry.id = id;
+                entry.mode = mode;
+                entry.stat = stat;
+                entry.flags.remove(entry::Flags::INTENT_TO_ADD);
+            }
+            None => {
+                self.index
+                    .dangerously_push_entry(stat, id, entry::Flags::empty(), mode, rela_path);
+                self.index.sort_entries();
+            }
+        }
+        self.index.remove_tree();
+    }
+}
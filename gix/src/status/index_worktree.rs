@@ -0,0 +1,51 @@
+use std::path::PathBuf;
+
+use crate::{
+    bstr::BStr,
+    status::{self, plumbing::index_as_worktree::traits, Item, SubmoduleStatus},
+};
+
+/// Determine the state of submodules by opening them as repositories and recursively obtaining their status.
+#[derive(Clone)]
+pub(crate) struct SubmoduleCheck {
+    pub(crate) work_dir: PathBuf,
+    pub(crate) options: crate::open::Options,
+    pub(crate) mode: status::Submodule,
+}
+
+impl traits::SubmoduleStatus for SubmoduleCheck {
+    type Output = SubmoduleStatus;
+    type Error = status::submodule::Error;
+
+    fn status(&mut self, entry: &gix_index::Entry, rela_path: &BStr) -> Result<Option<Self::Output>, Self::Error> {
+        if self.mode == status::Submodule::Ignore {
+            return Ok(None);
+        }
+        let repo = match crate::open_opts(self.work_dir.join(gix_path::from_bstr(rela_path)), self.options.clone()) {
+            Ok(repo) => repo,
+            // Submodules that aren't checked out are not considered changed.
+            Err(crate::open::Error::NotARepository { .. }) => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let mut out = SubmoduleStatus {
+            commit_changed: repo.head_id().ok().map(crate::Id::detach) != Some(entry.id),
+            ..Default::default()
+        };
+        if matches!(self.mode, status::Submodule::Modifications | status::Submodule::All) {
+            let outcome = repo
+                .status(status::Options {
+                    untracked: if self.mode == status::Submodule::All {
+                        status::Untracked::Normal
+                    } else {
+                        status::Untracked::No
+                    },
+                    submodules: self.mode,
+                    ..Default::default()
+                })
+                .map_err(Box::new)?;
+            out.has_modifications = outcome.is_dirty();
+            out.has_untracked = outcome.items.iter().any(|item| matches!(item, Item::Untracked { .. }));
+        }
+        Ok((out != SubmoduleStatus::default()).then_some(out))
+    }
+}
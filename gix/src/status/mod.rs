@@ -0,0 +1,345 @@
+//! Obtain information similar to `git status`, comparing `HEAD` with the index, and the index with the worktree.
+//!
+//! Use [`Repository::status()`](crate::Repository::status()) to obtain a list of [items](Item) that describe
+//! how each path differs, with the same semantics as `git status --porcelain=v2`.
+use crate::bstr::BString;
+
+pub use gix_status as plumbing;
+
+mod index_worktree;
+pub(crate) use index_worktree::SubmoduleCheck;
+
+pub(crate) mod untracked;
+
+pub(crate) mod worktree_rewrites;
+
+/// How to handle untracked files.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Untracked {
+    /// Do not look for untracked files at all, like `--untracked-files=no`.
+    No,
+    /// Show untracked files, but collapse directories that contain only untracked files into the directory itself,
+    /// like `--untracked-files=normal`.
+    #[default]
+    Normal,
+    /// Show every untracked file individually, like `--untracked-files=all`.
+    All,
+}
+
+/// How to handle submodules, similar to `--ignore-submodules=<when>`.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Submodule {
+    /// Don't look into submodules at all, like `--ignore-submodules=all`.
+    Ignore,
+    /// Only see if the checked out commit differs from the one recorded in the superproject, like `--ignore-submodules=dirty`.
+    RefChange,
+    /// Also check for modifications of tracked files in the submodule worktree, like `--ignore-submodules=untracked`.
+    Modifications,
+    /// Check for changes to the checked out commit, modifications and untracked files, like `--ignore-submodules=none`.
+    #[default]
+    All,
+}
+
+/// Options for use with [`Repository::status()`](crate::Repository::status()).
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+    /// Pathspecs to limit the status to, or an empty list to see all paths.
+    pub patterns: Vec<BString>,
+    /// Control if and how untracked files are reported.
+    pub untracked: Untracked,
+    /// If `true`, ignored files are reported as well, like `--ignored`.
+    pub ignored: bool,
+    /// Control how much work is done to determine the status of submodules.
+    pub submodules: Submodule,
+    /// If `true`, obtain the full status of each submodule with a checked out worktree as well, which is reported
+    /// in [`Outcome::submodules`]. Submodules of submodules are recursed into as well.
+    ///
+    /// It's configured by `submodule.recurse` when using [`Repository::status_options()`](crate::Repository::status_options()),
+    /// and has no effect if [submodules are ignored](Submodule::Ignore).
+    pub recurse_submodules: bool,
+    /// If set, don't use more than this amount of threads when comparing the index with the worktree.
+    pub thread_limit: Option<usize>,
+    /// If `Some(…)`, find renames and possibly copies between `HEAD` and the index, as well as between the index and the worktree,
+    /// which are reported as [`Item::Rewritten`].
+    ///
+    /// In the worktree, files deleted from it are matched with untracked files and files added with `git add --intent-to-add`.
+    /// Note that untracked files are only seen individually if they aren't collapsed into their [untracked directory](Untracked::Normal).
+    ///
+    /// Use [`Repository::status_options()`](crate::Repository::status_options()) to obtain options that are configured like `git status`.
+    pub rewrites: Option<crate::object::tree::diff::Rewrites>,
+}
+
+/// Describes a change of a tracked path, either between `HEAD` and the index, or between the index and the worktree.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Change {
+    /// The path was added, or marked with intent-to-add when seen from the worktree.
+    Added,
+    /// The path was deleted.
+    Deleted,
+    /// The content or the executable bit of the path changed.
+    Modified,
+    /// The kind of the entry changed, for instance a file became a symlink.
+    TypeChange,
+}
+
+impl Change {
+    /// Return the character `git status --porcelain` uses to display this change.
+    pub fn to_porcelain_char(&self) -> char {
+        match self {
+            Change::Added => 'A',
+            Change::Deleted => 'D',
+            Change::Modified => 'M',
+            Change::TypeChange => 'T',
+        }
+    }
+}
+
+/// The state of a submodule with changes in its worktree or in the commit it has checked out.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct SubmoduleStatus {
+    /// The commit checked out in the submodule differs from the one recorded in the superproject.
+    pub commit_changed: bool,
+    /// The submodule has modifications to tracked files.
+    pub has_modifications: bool,
+    /// The submodule has untracked files.
+    pub has_untracked: bool,
+}
+
+/// A single path as reported by [`Repository::status()`](crate::Repository::status()).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Item {
+    /// A tracked path with changes between `HEAD` and the index, between the index and the worktree, or both.
+    Changed {
+        /// The path relative to the root of the worktree.
+        rela_path: BString,
+        /// How the index differs from `HEAD`, if at all.
+        index: Option<Change>,
+        /// How the worktree differs from the index, if at all.
+        worktree: Option<Change>,
+        /// If the path is a submodule that has changes, this is its detailed state.
+        submodule: Option<SubmoduleStatus>,
+    },
+    /// A path in the index that was renamed or copied from another path in `HEAD`, or a path in the worktree that was renamed
+    /// or copied from another path in the index.
+    Rewritten {
+        /// The path in `HEAD` or in the index that is the source of the rename or copy.
+        source_rela_path: BString,
+        /// The path of the renamed or copied entry in the index or in the worktree, relative to the root of the worktree.
+        rela_path: BString,
+        /// If `true`, the source still exists and this is a copy, otherwise it's a rename.
+        copy: bool,
+        /// The similarity of source and destination in percent, which is `100` if their content is the same.
+        score: u8,
+        /// If `true`, the rename or copy is between the index and the worktree, otherwise it's between `HEAD` and the index.
+        in_worktree: bool,
+        /// How the worktree differs from the index, if at all. It's always `None` if the rewrite is `in_worktree`.
+        worktree: Option<Change>,
+    },
+    /// A path with unresolved merge conflicts.
+    Conflict {
+        /// The path relative to the root of the worktree.
+        rela_path: BString,
+        /// The kind of conflict.
+        conflict: plumbing::index_as_worktree::Conflict,
+    },
+    /// An untracked path, with directories ending in a slash.
+    Untracked {
+        /// The path relative to the root of the worktree.
+        rela_path: BString,
+    },
+    /// An ignored path, with directories ending in a slash.
+    Ignored {
+        /// The path relative to the root of the worktree.
+        rela_path: BString,
+    },
+}
+
+impl Item {
+    /// Return the path of this item relative to the root of the worktree.
+    pub fn rela_path(&self) -> &BString {
+        match self {
+            Item::Changed { rela_path, .. }
+            | Item::Rewritten { rela_path, .. }
+            | Item::Conflict { rela_path, .. }
+            | Item::Untracked { rela_path }
+            | Item::Ignored { rela_path } => rela_path,
+        }
+    }
+
+    /// Return the two-letter status code `XY` as shown by `git status --porcelain=v2`, where `X` is the state of the index
+    /// and `Y` the state of the worktree, using `.` for no change.
+    ///
+    /// Renames and copies are `R` and `C` respectively, untracked items are `??` and ignored items are `!!`.
+    /// The similarity score that `git` prints along with renames and copies, like `R100`, is [available separately](Item::Rewritten::score).
+    pub fn porcelain_v2_code(&self) -> [char; 2] {
+        use plumbing::index_as_worktree::Conflict;
+        let to_char = |c: &Option<Change>| c.as_ref().map_or('.', Change::to_porcelain_char);
+        match self {
+            Item::Changed { index, worktree, .. } => [to_char(index), to_char(worktree)],
+            Item::Rewritten {
+                copy,
+                in_worktree,
+                worktree,
+                ..
+            } => {
+                let rewrite = if *copy { 'C' } else { 'R' };
+                if *in_worktree {
+                    ['.', rewrite]
+                } else {
+                    [rewrite, to_char(worktree)]
+                }
+            }
+            Item::Conflict { conflict, .. } => match conflict {
+                Conflict::BothDeleted => ['D', 'D'],
+                Conflict::AddedByUs => ['A', 'U'],
+                Conflict::DeletedByThem => ['U', 'D'],
+                Conflict::AddedByThem => ['U', 'A'],
+                Conflict::DeletedByUs => ['D', 'U'],
+                Conflict::BothAdded => ['A', 'A'],
+                Conflict::BothModified => ['U', 'U'],
+            },
+            Item::Untracked { .. } => ['?', '?'],
+            Item::Ignored { .. } => ['!', '!'],
+        }
+    }
+}
+
+/// The outcome of [`Repository::status()`](crate::Repository::status()).
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// All paths with a status, sorted by path.
+    pub items: Vec<Item>,
+    /// Statistics about the comparison of the index with the worktree.
+    pub index_worktree: plumbing::index_as_worktree::Outcome,
+    /// Information about the rename tracking between `HEAD` and the index, if [enabled](Options::rewrites).
+    pub rewrites: Option<crate::object::tree::diff::rewrites::Outcome>,
+    /// Information about the rename tracking between the index and the worktree, if [enabled](Options::rewrites).
+    pub index_worktree_rewrites: Option<crate::object::tree::diff::rewrites::Outcome>,
+    /// The status of each checked out submodule, sorted by path, if [enabled](Options::recurse_submodules).
+    pub submodules: Vec<SubmoduleOutcome>,
+}
+
+/// The status of a submodule as obtained when [recursing into submodules](Options::recurse_submodules).
+#[derive(Debug, Clone)]
+pub struct SubmoduleOutcome {
+    /// The name of the submodule.
+    pub name: BString,
+    /// The path of the submodule relative to the root of the worktree of the superproject.
+    pub rela_path: BString,
+    /// The commit recorded for the submodule in the index of the superproject, or `None` if there is none.
+    pub index_id: Option<gix_hash::ObjectId>,
+    /// The commit checked out in the submodule, or `None` if its `HEAD` is unborn.
+    pub checked_out_id: Option<gix_hash::ObjectId>,
+    /// The status of the submodule itself, with paths relative to the root of its worktree.
+    pub outcome: Outcome,
+}
+
+impl SubmoduleOutcome {
+    /// Return `true` if the submodule has another commit checked out than the one recorded in the superproject.
+    pub fn is_commit_changed(&self) -> bool {
+        self.index_id != self.checked_out_id
+    }
+}
+
+impl Outcome {
+    /// Return `true` if there are tracked changes or conflicts, ignoring untracked and ignored files.
+    pub fn is_dirty(&self) -> bool {
+        self.items.iter().any(|item| {
+            matches!(
+                item,
+                Item::Changed { .. } | Item::Rewritten { .. } | Item::Conflict { .. }
+            )
+        })
+    }
+}
+
+/// The error returned by [`Repository::status()`](crate::Repository::status()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("A working tree is required to compute the status")]
+    MissingWorkDir,
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    Pathspec(#[from] crate::pathspec::init::Error),
+    #[error(transparent)]
+    FindHead(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    PeelHead(#[from] crate::head::peel::to_commit::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    TraverseHeadTree(#[from] gix_traverse::tree::breadthfirst::Error),
+    #[error(transparent)]
+    AttributeStack(#[from] crate::config::attribute_stack::Error),
+    #[error(transparent)]
+    ExcludeStack(#[from] crate::config::exclude_stack::Error),
+    #[error(transparent)]
+    FilterPipeline(#[from] crate::filter::pipeline::options::Error),
+    #[error(transparent)]
+    FilesystemOptions(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    StatOptions(#[from] crate::config::stat_options::Error),
+    #[error(transparent)]
+    FindHeadTree(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    TrackRewrites(#[from] crate::object::tree::diff::for_each::Error),
+    #[error(transparent)]
+    IndexAsWorktree(#[from] plumbing::index_as_worktree::Error),
+    #[error("Could not read directory or file at '{}' while looking for untracked files", path.display())]
+    UntrackedIo {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Could not read file at '{}' while looking for renames in the worktree", path.display())]
+    WorktreeRewritesIo {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error(transparent)]
+    ConvertToGit(#[from] Box<crate::filter::pipeline::convert_to_git::Error>),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Submodules(#[from] crate::submodule::modules::Error),
+    #[error("Could not obtain the status of submodule '{name}'")]
+    Submodule {
+        name: BString,
+        source: Box<submodule::Error>,
+    },
+}
+
+///
+pub mod options {
+    /// The error returned by [`Repository::status_options()`](crate::Repository::status_options()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Rewrites(#[from] crate::object::tree::diff::rewrites::Error),
+        #[error(transparent)]
+        RecurseSubmodules(#[from] crate::config::boolean::Error),
+    }
+}
+
+///
+pub mod submodule {
+    /// The error produced when obtaining the status of a submodule.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Open(#[from] crate::open::Error),
+        #[error(transparent)]
+        Status(#[from] Box<super::Error>),
+        #[error(transparent)]
+        Path(#[from] gix_submodule::config::path::Error),
+        #[error(transparent)]
+        IndexId(#[from] crate::submodule::index_id::Error),
+        #[error(transparent)]
+        OpenSubmodule(#[from] crate::submodule::open::Error),
+        #[error(transparent)]
+        Head(#[from] crate::reference::find::existing::Error),
+    }
+}
@@ -0,0 +1,164 @@
+use std::path::Path;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    status::{Error, Item, Untracked},
+    AttributeStack, PathspecDetached,
+};
+
+/// State needed to find untracked and ignored files in the worktree.
+pub(crate) struct Walk<'a, 'repo> {
+    pub(crate) work_dir: &'a Path,
+    pub(crate) index: &'a gix_index::State,
+    pub(crate) excludes: AttributeStack<'repo>,
+    pub(crate) pathspec: Option<&'a mut PathspecDetached>,
+    pub(crate) mode: Untracked,
+    pub(crate) ignored: bool,
+    /// A lookup table to find tracked paths while ignoring their case, set if `core.ignoreCase` is enabled.
+    pub(crate) icase: Option<gix_index::AccelerateLookup<'a>>,
+    /// If `true`, precompose the unicode of all names read from the filesystem, as configured by `core.precomposeUnicode`.
+    pub(crate) precompose_unicode: bool,
+}
+
+impl<'a> Walk<'a, '_> {
+    /// Recursively visit the directory at `rela_path` (empty for the worktree root) and collect untracked or ignored paths into `out`.
+    pub(crate) fn directory(&mut self, rela_path: &mut BString, out: &mut Vec<Item>) -> Result<(), Error> {
+        let dir = self.work_dir.join(gix_path::from_bstr(rela_path.as_bstr()));
+        self.directory_at(&dir, rela_path, out)
+    }
+
+    /// Like [`directory()`](Self::directory()), but read the directory at `dir` on disk, as `rela_path` may be precomposed.
+    fn directory_at(&mut self, dir: &Path, rela_path: &mut BString, out: &mut Vec<Item>) -> Result<(), Error> {
+        let mut entries = std::fs::read_dir(dir)
+            .and_then(Iterator::collect::<Result<Vec<_>, _>>)
+            .map_err(|source| Error::UntrackedIo {
+                path: dir.to_owned(),
+                source,
+            })?;
+        entries.sort_by_key(std::fs::DirEntry::file_name);
+
+        for entry in entries {
+            let file_name = gix_path::os_string_into_bstring(entry.file_name()).map_err(|_| Error::UntrackedIo {
+                path: entry.path(),
+                source: std::io::Error::new(std::io::ErrorKind::InvalidData, "illformed UTF-8"),
+            })?;
+            if file_name == ".git" {
+                continue;
+            }
+            let file_name = if self.precompose_unicode {
+                crate::path::precompose_unicode(file_name.as_bstr()).into_owned()
+            } else {
+                file_name
+            };
+            let prev_len = rela_path.len();
+            if !rela_path.is_empty() {
+                rela_path.push(b'/');
+            }
+            rela_path.push_str(&file_name);
+            let res = self.entry(&entry, rela_path, out);
+            rela_path.truncate(prev_len);
+            res?;
+        }
+        Ok(())
+    }
+
+    fn entry(&mut self, entry: &std::fs::DirEntry, rela_path: &mut BString, out: &mut Vec<Item>) -> Result<(), Error> {
+        let is_dir = entry
+            .file_type()
+            .map_err(|source| Error::UntrackedIo {
+                path: entry.path(),
+                source,
+            })?
+            .is_dir();
+        if self.index.entry_range(rela_path.as_bstr()).is_some() || self.tracked_icase(rela_path.as_bstr()).is_some() {
+            // Tracked files and submodules are handled elsewhere.
+            return Ok(());
+        }
+        if is_dir && self.has_tracked_entries_in(rela_path.as_bstr()) {
+            return self.directory_at(&entry.path(), rela_path, out);
+        }
+
+        let is_excluded = self.excludes.at_entry(rela_path.as_bstr(), Some(is_dir))?.is_excluded();
+        if is_excluded {
+            if self.ignored && self.is_included(rela_path, is_dir) {
+                out.push(Item::Ignored {
+                    rela_path: with_trailing_slash(rela_path, is_dir),
+                });
+            }
+            return Ok(());
+        }
+
+        if !is_dir {
+            if self.is_included(rela_path, is_dir) {
+                out.push(Item::Untracked {
+                    rela_path: rela_path.clone(),
+                });
+            }
+            return Ok(());
+        }
+
+        if entry.path().join(".git").exists() {
+            // Nested repositories are always reported as a whole.
+            if self.is_included(rela_path, is_dir) {
+                out.push(Item::Untracked {
+                    rela_path: with_trailing_slash(rela_path, is_dir),
+                });
+            }
+            return Ok(());
+        }
+
+        let start = out.len();
+        self.directory_at(&entry.path(), rela_path, out)?;
+        if self.mode == Untracked::Normal && out.len() > start {
+            let has_untracked = out[start..].iter().any(|item| matches!(item, Item::Untracked { .. }));
+            let all_ignored = out[start..].iter().all(|item| matches!(item, Item::Ignored { .. }));
+            if has_untracked || all_ignored {
+                out.truncate(start);
+                let rela_path = with_trailing_slash(rela_path, is_dir);
+                out.push(if has_untracked {
+                    Item::Untracked { rela_path }
+                } else {
+                    Item::Ignored { rela_path }
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Return the entry that is tracked at a path that only differs from `rela_path` in case, if `core.ignoreCase` is enabled.
+    pub(crate) fn tracked_icase(&self, rela_path: &BStr) -> Option<&'a gix_index::Entry> {
+        self.icase
+            .as_ref()
+            .and_then(|lookup| self.index.entry_by_path_icase(rela_path, true, lookup))
+    }
+
+    fn has_tracked_entries_in(&self, rela_dir: &BStr) -> bool {
+        match self.icase.as_ref() {
+            Some(lookup) => self
+                .index
+                .entry_closest_to_directory_icase(rela_dir, true, lookup)
+                .is_some(),
+            None => {
+                let mut prefix = rela_dir.to_owned();
+                prefix.push(b'/');
+                self.index
+                    .prefixed_entries(prefix.as_bstr())
+                    .map_or(false, |entries| !entries.is_empty())
+            }
+        }
+    }
+
+    fn is_included(&mut self, rela_path: &BString, is_dir: bool) -> bool {
+        self.pathspec
+            .as_mut()
+            .map_or(true, |spec| spec.is_included(rela_path.as_bstr(), Some(is_dir)))
+    }
+}
+
+fn with_trailing_slash(rela_path: &BString, is_dir: bool) -> BString {
+    let mut out = rela_path.clone();
+    if is_dir {
+        out.push(b'/');
+    }
+    out
+}
@@ -0,0 +1,289 @@
+use std::io::Read;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    object::tree::diff::{
+        for_each,
+        rewrites::{CopySource, Outcome},
+        tracked, Rewrites,
+    },
+    status::{Change, Error, Item},
+    Repository,
+};
+
+/// A tracked file whose content in the index may be the source of a rename or copy.
+struct Source {
+    /// The index of the item that represents the source, or `None` if it's unchanged and only used for finding copies.
+    item_idx: Option<usize>,
+    rela_path: BString,
+    id: gix_hash::ObjectId,
+}
+
+/// An untracked or intent-to-add file in the worktree that may be the destination of a rename or copy.
+struct Destination {
+    item_idx: usize,
+    id: gix_hash::ObjectId,
+    /// The content of the file as it would be stored in `git`.
+    data: Vec<u8>,
+    /// The path of the source, the similarity in percent and whether it's a copy, once a match was found.
+    source: Option<(BString, u8, bool)>,
+}
+
+/// Match tracked files deleted from the worktree with untracked or intent-to-add files in `items`, and replace matching pairs
+/// with [`Item::Rewritten`] configured by `rewrites`. If copies are tracked as well, the sources are tracked files
+/// that were modified in the worktree, and possibly all files in `index`.
+///
+/// The content of the index is compared to the content of worktree files after it was passed through all filters, just like
+/// `git add` would see it.
+pub(crate) fn track(
+    repo: &Repository,
+    index: &gix_index::State,
+    items: &mut Vec<Item>,
+    rewrites: Rewrites,
+) -> Result<Outcome, Error> {
+    let mut out = Outcome {
+        options: rewrites,
+        ..Default::default()
+    };
+    let index_file = |rela_path: &BStr| {
+        index.entry_by_path_and_stage(rela_path, 0).filter(|entry| {
+            matches!(
+                entry.mode,
+                gix_index::entry::Mode::FILE | gix_index::entry::Mode::FILE_EXECUTABLE
+            )
+        })
+    };
+    let mut sources = Vec::new();
+    let mut destination_items = Vec::new();
+    for (item_idx, item) in items.iter().enumerate() {
+        match item {
+            Item::Changed {
+                rela_path,
+                index: None,
+                worktree: Some(Change::Deleted),
+                submodule: None,
+            } => {
+                if let Some(entry) = index_file(rela_path.as_bstr()) {
+                    sources.push(Source {
+                        item_idx: Some(item_idx),
+                        rela_path: rela_path.clone(),
+                        id: entry.id,
+                    });
+                }
+            }
+            Item::Changed {
+                rela_path,
+                index: None,
+                worktree: Some(Change::Added),
+                submodule: None,
+            } => destination_items.push((item_idx, rela_path)),
+            Item::Untracked { rela_path } if !rela_path.ends_with(b"/") => {
+                destination_items.push((item_idx, rela_path))
+            }
+            _ => {}
+        }
+    }
+    if destination_items.is_empty() || (sources.is_empty() && rewrites.copies.is_none()) {
+        return Ok(out);
+    }
+
+    let work_dir = repo.work_dir().ok_or(Error::MissingWorkDir)?;
+    let mut filter = crate::filter::Pipeline::new(
+        repo,
+        repo.attributes_only(
+            index,
+            gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping,
+        )?
+        .detach(),
+    )?;
+    let mut destinations = Vec::new();
+    for (item_idx, rela_path) in destination_items {
+        let path = work_dir.join(gix_path::from_bstr(rela_path.as_bstr()));
+        let io_err = |source| Error::WorktreeRewritesIo {
+            path: path.clone(),
+            source,
+        };
+        let metadata = std::fs::symlink_metadata(&path).map_err(io_err)?;
+        if !metadata.is_file() || metadata.len() == 0 {
+            continue;
+        }
+        let file = std::fs::File::open(&path).map_err(io_err)?;
+        let mut data = Vec::new();
+        filter
+            .convert_to_git(file, gix_path::from_bstr(rela_path.as_bstr()).as_ref(), index)
+            .map_err(Box::new)?
+            .read_to_end(&mut data)
+            .map_err(io_err)?;
+        destinations.push(Destination {
+            item_idx,
+            id: gix_object::compute_hash(repo.object_hash(), gix_object::Kind::Blob, &data),
+            data,
+            source: None,
+        });
+    }
+
+    let algo = repo.config.diff_algorithm().map_err(for_each::Error::from)?;
+    let mut matched_sources = vec![false; sources.len()];
+    let skipped = match_pairs(
+        repo,
+        algo,
+        &sources,
+        Some(&mut matched_sources),
+        &mut destinations,
+        rewrites.percentage,
+        rewrites.limit,
+        &mut out,
+    )?;
+    out.num_similarity_checks_skipped_for_rename_tracking_due_to_limit = skipped;
+
+    if let Some(copies) = rewrites.copies {
+        let mut copy_sources: Vec<_> = items
+            .iter()
+            .enumerate()
+            .filter_map(|(item_idx, item)| match item {
+                Item::Changed {
+                    rela_path,
+                    worktree: Some(Change::Modified),
+                    submodule: None,
+                    ..
+                } => index_file(rela_path.as_bstr()).map(|entry| Source {
+                    item_idx: Some(item_idx),
+                    rela_path: rela_path.clone(),
+                    id: entry.id,
+                }),
+                _ => None,
+            })
+            .collect();
+        if copies.source == CopySource::FromSetOfModifiedFilesAndSourceTree {
+            let modified = copy_sources.len();
+            for entry in index.entries() {
+                let rela_path = entry.path(index);
+                if entry.stage() != 0
+                    || !matches!(
+                        entry.mode,
+                        gix_index::entry::Mode::FILE | gix_index::entry::Mode::FILE_EXECUTABLE
+                    )
+                    || entry.flags.contains(gix_index::entry::Flags::INTENT_TO_ADD)
+                    || copy_sources[..modified]
+                        .iter()
+                        .any(|source| source.rela_path == rela_path)
+                {
+                    continue;
+                }
+                copy_sources.push(Source {
+                    item_idx: None,
+                    rela_path: rela_path.to_owned(),
+                    id: entry.id,
+                });
+            }
+        }
+        let skipped = match_pairs(
+            repo,
+            algo,
+            &copy_sources,
+            None,
+            &mut destinations,
+            copies.percentage,
+            rewrites.limit,
+            &mut out,
+        )?;
+        out.num_similarity_checks_skipped_for_copy_tracking_due_to_limit = skipped;
+    }
+
+    // Sources were collected in order, so these are sorted.
+    let renamed: Vec<_> = sources
+        .iter()
+        .zip(matched_sources)
+        .filter_map(|(source, matched)| matched.then_some(source.item_idx).flatten())
+        .collect();
+    for dest in destinations {
+        if let Some((source_rela_path, score, copy)) = dest.source {
+            let rela_path = items[dest.item_idx].rela_path().clone();
+            items[dest.item_idx] = Item::Rewritten {
+                source_rela_path,
+                rela_path,
+                copy,
+                score,
+                in_worktree: true,
+                worktree: None,
+            };
+        }
+    }
+    let mut item_idx = 0;
+    items.retain(|_| {
+        item_idx += 1;
+        renamed.binary_search(&(item_idx - 1)).is_err()
+    });
+    Ok(out)
+}
+
+/// Match all unmatched `destinations` with `sources`, first by identity and then by similarity if `percentage` is below `1.0`.
+/// If `matched_sources` is set, pairs are renames and each source is used only once, otherwise they are copies.
+/// Return the amount of permutations that weren't checked for similarity as there were more than `limit * limit` of them.
+#[allow(clippy::too_many_arguments)]
+fn match_pairs(
+    repo: &Repository,
+    algo: gix_diff::blob::Algorithm,
+    sources: &[Source],
+    mut matched_sources: Option<&mut Vec<bool>>,
+    destinations: &mut [Destination],
+    percentage: Option<f32>,
+    limit: usize,
+    out: &mut Outcome,
+) -> Result<usize, Error> {
+    let copy = matched_sources.is_none();
+    let is_available = |matched_sources: &Option<&mut Vec<bool>>, src_idx: usize| {
+        matched_sources.as_ref().map_or(true, |matched| !matched[src_idx])
+    };
+    for dest in destinations.iter_mut().filter(|dest| dest.source.is_none()) {
+        if let Some(src_idx) = (0..sources.len())
+            .find(|&src_idx| is_available(&matched_sources, src_idx) && sources[src_idx].id == dest.id)
+        {
+            dest.source = Some((sources[src_idx].rela_path.clone(), 100, copy));
+            if let Some(matched) = matched_sources.as_mut() {
+                matched[src_idx] = true;
+            }
+        }
+    }
+
+    let percentage = match percentage {
+        Some(percentage) if percentage < 1.0 => percentage,
+        _ => return Ok(0),
+    };
+    let num_sources = (0..sources.len())
+        .filter(|&src_idx| is_available(&matched_sources, src_idx))
+        .count();
+    let num_destinations = destinations.iter().filter(|dest| dest.source.is_none()).count();
+    let permutations = num_sources * num_destinations;
+    if limit != 0 && permutations > limit * limit {
+        return Ok(permutations);
+    }
+    for dest in destinations.iter_mut().filter(|dest| dest.source.is_none()) {
+        for src_idx in 0..sources.len() {
+            if !is_available(&matched_sources, src_idx) {
+                continue;
+            }
+            let source = &sources[src_idx];
+            let old = repo.find_object(source.id).map_err(for_each::Error::from)?;
+            // The similarity can't be higher than the ratio of both sizes, so there is no need to compute it.
+            let (min, max) = if old.data.len() < dest.data.len() {
+                (old.data.len(), dest.data.len())
+            } else {
+                (dest.data.len(), old.data.len())
+            };
+            if old.data.is_empty() || (min as f32 / max as f32) < percentage {
+                continue;
+            }
+            let (similarity, _) = tracked::similarity(algo, &old.data, &dest.data);
+            out.num_similarity_checks += 1;
+            if similarity >= percentage {
+                dest.source = Some((source.rela_path.clone(), (similarity * 100.0) as u8, copy));
+                if let Some(matched) = matched_sources.as_mut() {
+                    matched[src_idx] = true;
+                }
+                break;
+            }
+        }
+    }
+    Ok(0)
+}
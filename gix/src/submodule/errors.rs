@@ -104,3 +104,141 @@ pub mod head_id {
         PathConfiguration(#[from] gix_submodule::config::path::Error),
     }
 }
+
+///
+pub mod checked_out_id {
+    /// The error returned by [Submodule::checked_out_id()](crate::Submodule::checked_out_id()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Open(#[from] crate::submodule::open::Error),
+        #[error(transparent)]
+        Head(#[from] crate::reference::find::existing::Error),
+    }
+}
+
+///
+pub mod resolved_url {
+    /// The error returned by [Submodule::resolved_url()](crate::Submodule::resolved_url()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Url(#[from] gix_submodule::config::url::Error),
+        #[error(transparent)]
+        FindRemote(#[from] crate::remote::find::existing::Error),
+        #[error(
+            "Could not determine the absolute path of the superproject to resolve relative submodule urls against"
+        )]
+        Realpath(#[from] gix_path::realpath::Error),
+        #[error(transparent)]
+        Parse(#[from] gix_url::parse::Error),
+    }
+}
+
+///
+pub mod init {
+    /// The error returned by [Submodule::init()](crate::Submodule::init()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ReadOnly(#[from] crate::repository::read_only::Error),
+        #[error(transparent)]
+        ResolvedUrl(#[from] crate::submodule::resolved_url::Error),
+        #[error(transparent)]
+        IsActive(#[from] crate::submodule::is_active::Error),
+        #[error(transparent)]
+        SetValue(#[from] gix_config::file::set_raw_value::Error),
+        #[error(transparent)]
+        Persist(#[from] crate::config::persist::Error),
+    }
+}
+
+///
+#[cfg(all(
+    feature = "blocking-network-client",
+    feature = "status",
+    feature = "worktree-mutation"
+))]
+pub mod perform_update {
+    use crate::bstr::BString;
+
+    /// The error returned by [Submodule::perform_update()](crate::Submodule::perform_update()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ReadOnly(#[from] crate::repository::read_only::Error),
+        #[error(transparent)]
+        Update(#[from] gix_submodule::config::update::Error),
+        #[error("Submodule '{name}' can't be updated with '{mode:?}', only 'checkout' is supported")]
+        UnsupportedMode {
+            name: BString,
+            mode: gix_submodule::config::Update,
+        },
+        #[error("Submodule '{name}' isn't recorded in the index of the superproject")]
+        MissingIndexEntry { name: BString },
+        #[error(transparent)]
+        IndexId(#[from] crate::submodule::index_id::Error),
+        #[error(transparent)]
+        ResolvedUrl(#[from] crate::submodule::resolved_url::Error),
+        #[error(transparent)]
+        Path(#[from] gix_submodule::config::path::Error),
+        #[error(transparent)]
+        Open(#[from] crate::submodule::open::Error),
+        #[error(transparent)]
+        OpenClone(#[from] crate::open::Error),
+        #[error(transparent)]
+        PrepareClone(#[from] crate::clone::Error),
+        #[error(transparent)]
+        Clone(#[from] crate::clone::fetch::Error),
+        #[error(transparent)]
+        SetValue(#[from] crate::config::set_value::Error),
+        #[error(transparent)]
+        Persist(#[from] crate::config::persist::Error),
+        #[error("Could not determine the absolute path of the submodule checkout")]
+        Realpath(#[from] gix_path::realpath::Error),
+        #[error("Could not create '{}' for the submodule", path.display())]
+        Io {
+            source: std::io::Error,
+            path: std::path::PathBuf,
+        },
+        #[error("Submodule '{name}' has no remote to fetch from")]
+        NoRemote { name: BString },
+        #[error(transparent)]
+        FindRemote(#[from] crate::remote::find::existing::Error),
+        #[error(transparent)]
+        ReplaceRefSpecs(#[from] gix_refspec::parse::Error),
+        #[error(transparent)]
+        Connect(#[from] crate::remote::connect::Error),
+        #[error(transparent)]
+        PrepareFetch(#[from] crate::remote::fetch::prepare::Error),
+        #[error(transparent)]
+        Fetch(#[from] crate::remote::fetch::Error),
+        #[error("Commit {id} recorded for submodule '{name}' could not be found, not even after fetching")]
+        MissingCommit { name: BString, id: gix_hash::ObjectId },
+        #[error(transparent)]
+        Head(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        EditHead(#[from] crate::reference::edit::Error),
+        #[error(transparent)]
+        Checkout(#[from] crate::clone::checkout::main_worktree::Error),
+        #[error(transparent)]
+        CheckoutTree(#[from] crate::checkout::tree::Error),
+    }
+
+    /// The outcome of [Submodule::perform_update()](crate::Submodule::perform_update()).
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Outcome {
+        /// The commit recorded in the index of the superproject, which is now checked out on a detached `HEAD`.
+        pub id: gix_hash::ObjectId,
+        /// The commit that was checked out previously, or `None` if the submodule was cloned.
+        pub previous_id: Option<gix_hash::ObjectId>,
+        /// If `true`, the submodule repository didn't exist yet and was cloned.
+        pub cloned: bool,
+        /// If `true`, the recorded commit wasn't present in the submodule repository and was fetched.
+        pub fetched: bool,
+    }
+}
@@ -9,7 +9,11 @@ use std::{
 
 pub use gix_submodule::*;
 
-use crate::{bstr::BStr, repository::IndexPersistedOrInMemory, Repository, Submodule};
+use crate::{
+    bstr::{BStr, ByteSlice},
+    repository::IndexPersistedOrInMemory,
+    Repository, Submodule,
+};
 
 pub(crate) type ModulesFileStorage = gix_features::threading::OwnShared<gix_fs::SharedFileSnapshotMut<File>>;
 /// A lazily loaded and auto-updated worktree index.
@@ -267,6 +271,303 @@ impl<'repo> Submodule<'repo> {
     }
 }
 
+/// Operations
+impl<'repo> Submodule<'repo> {
+    /// Return the commit currently checked out in the submodule, i.e. what its `HEAD` points to, or `None` if the submodule
+    /// repository doesn't exist yet or if its `HEAD` is unborn.
+    ///
+    /// Compare it to [`index_id()`](Self::index_id()) to learn if the submodule is checked out at the commit recorded
+    /// in the superproject.
+    pub fn checked_out_id(&self) -> Result<Option<gix_hash::ObjectId>, checked_out_id::Error> {
+        Ok(match self.open()? {
+            Some(repo) => repo.head()?.id().map(crate::Id::detach),
+            None => None,
+        })
+    }
+
+    /// Return the [url](Self::url()) from which to clone or update the submodule, with relative urls like `../other`
+    /// resolved against the url of the default remote of the superproject, or against its working tree if there is no remote,
+    /// just like `git submodule init` does.
+    pub fn resolved_url(&self) -> Result<gix_url::Url, resolved_url::Error> {
+        let url = self.url()?;
+        if url.scheme != gix_url::Scheme::File || !(url.path.starts_with(b"./") || url.path.starts_with(b"../")) {
+            return Ok(url);
+        }
+        let repo = self.state.repo;
+        let base = match repo
+            .find_default_remote(crate::remote::Direction::Fetch)
+            .transpose()?
+            .and_then(|remote| remote.url(crate::remote::Direction::Fetch).cloned())
+        {
+            Some(base) => base,
+            None => {
+                let dir = gix_path::realpath(repo.work_dir().unwrap_or_else(|| repo.git_dir()))?;
+                gix_url::parse(gix_path::into_bstr(dir).as_ref())?
+            }
+        };
+        Ok(resolve_relative_url(&base, url))
+    }
+
+    /// Register the submodule in the local configuration of the superproject, like `git submodule init <path>` does,
+    /// and return `true` if its url wasn't registered before.
+    ///
+    /// This writes the [resolved url](Self::resolved_url()) to `submodule.<name>.url` and copies the `update` field unless
+    /// it's a command, but only if the superproject doesn't configure them yet. Further, `submodule.<name>.active` is set
+    /// if the submodule isn't already [active](Self::is_active()).
+    ///
+    /// Note that the configuration in memory isn't updated, re-open the superproject to see the changes.
+    pub fn init(&self) -> Result<bool, init::Error> {
+        let repo = self.state.repo;
+        repo.assure_writable("initialize a submodule")?;
+        let name = self.name();
+        let is_configured = |key: &str| repo.config.resolved.string("submodule", Some(name), key).is_some();
+        let newly_registered = !is_configured("url");
+        let url = newly_registered.then(|| self.resolved_url()).transpose()?;
+        let update = if is_configured("update") {
+            None
+        } else {
+            self.state
+                .modules
+                .config()
+                .string("submodule", Some(name), "update")
+                .filter(|value| {
+                    matches!(
+                        config::Update::try_from(value.as_ref()),
+                        Ok(config::Update::Checkout
+                            | config::Update::Rebase
+                            | config::Update::Merge
+                            | config::Update::None)
+                    )
+                })
+                .map(std::borrow::Cow::into_owned)
+        };
+        let activate = !self.is_active()?;
+        if url.is_none() && update.is_none() && !activate {
+            return Ok(false);
+        }
+
+        let mut repo = repo.clone();
+        let mut config = repo.config_snapshot_mut();
+        if let Some(url) = url {
+            config.set_raw_value("submodule", Some(name), "url", url.to_bstring().as_bstr())?;
+        }
+        if let Some(update) = update {
+            config.set_raw_value("submodule", Some(name), "update", update.as_bstr())?;
+        }
+        if activate {
+            config.set_raw_value("submodule", Some(name), "active", "true")?;
+        }
+        config.commit_to(gix_config::Source::Local)?;
+        Ok(newly_registered)
+    }
+
+    /// Check out the commit recorded for the submodule in the index of the superproject on a detached `HEAD`, similar to
+    /// `git submodule update <path>`, and return what was done, or `None` if the `update` field of the submodule is `none`.
+    ///
+    /// If the submodule repository doesn't exist yet, it's cloned from its [resolved url](Self::resolved_url()) into the
+    /// [`git_dir()`](Self::git_dir()), with its working tree at the [`work_dir()`](Self::work_dir()).
+    /// Otherwise, the recorded commit is fetched from the default remote of the submodule if it's missing, and changes between
+    /// the currently checked out commit and the recorded one are applied to its working tree, which fails if local changes
+    /// would be overwritten.
+    ///
+    /// Note that only the `checkout` update mode is supported, and that submodules of the submodule aren't updated.
+    /// Use [`init()`](Self::init()) beforehand to make the submodule known to the superproject.
+    #[cfg(all(
+        feature = "blocking-network-client",
+        feature = "status",
+        feature = "worktree-mutation"
+    ))]
+    pub fn perform_update(
+        &self,
+        mut progress: impl crate::NestedProgress + 'static,
+        should_interrupt: &std::sync::atomic::AtomicBool,
+    ) -> Result<Option<perform_update::Outcome>, perform_update::Error> {
+        self.perform_update_inner(&mut progress, should_interrupt)
+    }
+
+    #[cfg(all(
+        feature = "blocking-network-client",
+        feature = "status",
+        feature = "worktree-mutation"
+    ))]
+    fn perform_update_inner(
+        &self,
+        progress: &mut dyn crate::DynNestedProgress,
+        should_interrupt: &std::sync::atomic::AtomicBool,
+    ) -> Result<Option<perform_update::Outcome>, perform_update::Error> {
+        use gix_ref::transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog};
+
+        let superproject = self.state.repo;
+        superproject.assure_writable("update a submodule")?;
+        match self.update()?.unwrap_or_default() {
+            config::Update::Checkout => {}
+            config::Update::None => return Ok(None),
+            mode => {
+                return Err(perform_update::Error::UnsupportedMode {
+                    name: self.name().to_owned(),
+                    mode,
+                })
+            }
+        }
+        let id = self
+            .index_id()?
+            .ok_or_else(|| perform_update::Error::MissingIndexEntry {
+                name: self.name().to_owned(),
+            })?;
+        let detach_head = |repo: &Repository, message: String| {
+            repo.edit_reference(RefEdit {
+                change: Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        message: message.into(),
+                    },
+                    expected: PreviousValue::Any,
+                    new: gix_ref::Target::Peeled(id),
+                },
+                name: "HEAD".try_into().expect("valid"),
+                deref: false,
+            })
+        };
+
+        let mut outcome = perform_update::Outcome {
+            id,
+            previous_id: None,
+            cloned: false,
+            fetched: false,
+        };
+        match self.open()? {
+            Some(repo) => {
+                if !repo.has_object(id) {
+                    self.fetch_commit(&repo, id, progress, should_interrupt)?;
+                    outcome.fetched = true;
+                }
+                let head = repo.head()?;
+                outcome.previous_id = head.id().map(crate::Id::detach);
+                if outcome.previous_id != Some(id) {
+                    let previous = match &head.kind {
+                        crate::head::Kind::Symbolic(reference) => reference.name.shorten().to_owned(),
+                        crate::head::Kind::Unborn(name) => name.shorten().to_owned(),
+                        crate::head::Kind::Detached { target, .. } => target.to_string().into(),
+                    };
+                    repo.checkout_tree(id, Default::default())?;
+                    detach_head(&repo, format!("checkout: moving from {previous} to {id}"))?;
+                }
+            }
+            None => {
+                let git_dir = self.git_dir();
+                let modules_dir = git_dir.parent().expect("git dir is in the modules directory");
+                std::fs::create_dir_all(modules_dir).map_err(|source| perform_update::Error::Io {
+                    source,
+                    path: modules_dir.to_owned(),
+                })?;
+                let mut prepare = crate::clone::PrepareFetch::new(
+                    self.resolved_url()?,
+                    &git_dir,
+                    crate::create::Kind::Bare,
+                    Default::default(),
+                    superproject.options.clone(),
+                )?;
+                let (mut repo, _) = prepare.fetch_only_inner(&mut *progress, should_interrupt)?;
+                outcome.cloned = true;
+                if !repo.has_object(id) {
+                    self.fetch_commit(&repo, id, progress, should_interrupt)?;
+                    outcome.fetched = true;
+                }
+
+                let work_dir = self.work_dir()?;
+                std::fs::create_dir_all(&work_dir).map_err(|source| perform_update::Error::Io {
+                    source,
+                    path: work_dir.clone(),
+                })?;
+                let (work_dir, git_dir) = (gix_path::realpath(work_dir)?, gix_path::realpath(git_dir)?);
+                let mut config = repo.config_snapshot_mut();
+                config.set_value(&crate::config::tree::Core::BARE, "false")?;
+                config.set_value(
+                    &crate::config::tree::Core::WORKTREE,
+                    gix_path::into_bstr(&work_dir).as_ref(),
+                )?;
+                config.commit_to(gix_config::Source::Local)?;
+                crate::worktree::proxy::write_dot_git_file(&work_dir, &git_dir).map_err(|source| {
+                    perform_update::Error::Io {
+                        source,
+                        path: work_dir.join(gix_discover::DOT_GIT_DIR),
+                    }
+                })?;
+
+                let repo = crate::open_opts(git_dir, superproject.options.clone())?;
+                detach_head(&repo, "submodule: checkout recorded commit".into())?;
+                crate::clone::PrepareCheckout { repo: Some(repo) }.main_worktree_inner(progress, should_interrupt)?;
+            }
+        }
+        Ok(Some(outcome))
+    }
+
+    /// Fetch from the default remote of the submodule `repo`, and if that doesn't provide the commit `id`, fetch it directly.
+    #[cfg(all(
+        feature = "blocking-network-client",
+        feature = "status",
+        feature = "worktree-mutation"
+    ))]
+    fn fetch_commit(
+        &self,
+        repo: &Repository,
+        id: gix_hash::ObjectId,
+        progress: &mut dyn crate::DynNestedProgress,
+        should_interrupt: &std::sync::atomic::AtomicBool,
+    ) -> Result<(), perform_update::Error> {
+        let direction = crate::remote::Direction::Fetch;
+        let remote = repo
+            .find_default_remote(direction)
+            .ok_or_else(|| perform_update::Error::NoRemote {
+                name: self.name().to_owned(),
+            })??;
+        let mut by_id = remote.clone().with_fetch_tags(crate::remote::fetch::Tags::None);
+        remote
+            .connect(direction)?
+            .prepare_fetch(&mut *progress, Default::default())?
+            .receive_inner(&mut *progress, should_interrupt)?;
+        if repo.has_object(id) {
+            return Ok(());
+        }
+        by_id.replace_refspecs(Some(crate::bstr::BString::from(id.to_string())), direction)?;
+        by_id
+            .connect(direction)?
+            .prepare_fetch(&mut *progress, Default::default())?
+            .receive_inner(&mut *progress, should_interrupt)?;
+        if !repo.has_object(id) {
+            return Err(perform_update::Error::MissingCommit {
+                name: self.name().to_owned(),
+                id,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Resolve `url` against the `base` url of the superproject if it's relative, like `../other`, or return it unchanged.
+pub(crate) fn resolve_relative_url(base: &gix_url::Url, url: gix_url::Url) -> gix_url::Url {
+    if url.scheme != gix_url::Scheme::File || !(url.path.starts_with(b"./") || url.path.starts_with(b"../")) {
+        return url;
+    }
+    let base_path: &BStr = base.path.as_ref();
+    let mut components: Vec<&[u8]> = base_path.trim_end_with(|c| c == '/').split_str("/").collect();
+    for component in url.path.split_str("/") {
+        match component {
+            b"" | b"." => {}
+            b".." => {
+                if components.len() > 1 {
+                    components.pop();
+                }
+            }
+            component => components.push(component),
+        }
+    }
+    let mut resolved = base.clone();
+    resolved.path = components.join(&b'/').into();
+    resolved
+}
+
 /// A summary of the state of all parts forming a submodule, which allows to answer various questions about it.
 ///
 /// Note that expensive questions about its presence in the `HEAD` or the `index` are left to the caller.
@@ -0,0 +1,86 @@
+//! Work with the history of a subdirectory as if it was a repository of its own, similar to `git subtree`.
+//!
+//! Use [`Repository::subtree_split()`](crate::Repository::subtree_split()) to extract the history of a subdirectory into
+//! an independent lineage of commits, which can be pushed to another repository, and
+//! [`Repository::subtree_merge()`](crate::Repository::subtree_merge()) to merge such a lineage back into the subdirectory,
+//! like `git merge -Xsubtree=<prefix>` would.
+
+///
+pub mod split {
+    /// The error returned by [`Repository::subtree_split()`](crate::Repository::subtree_split()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Walk(#[from] crate::revision::walk::Error),
+        #[error(transparent)]
+        Traverse(#[from] gix_traverse::commit::ancestors::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        ObjectKind(#[from] crate::object::try_into::Error),
+        #[error(transparent)]
+        Decode(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        WriteObject(#[from] crate::object::write::Error),
+    }
+}
+
+///
+#[cfg(feature = "status")]
+pub mod merge {
+    use crate::bstr::BString;
+
+    /// Options for use with [`Repository::subtree_merge()`](crate::Repository::subtree_merge()).
+    #[derive(Debug, Default, Clone)]
+    pub struct Options {
+        /// Options to configure how the checkout of the merged tree is planned.
+        pub plan: crate::checkout::plan::Options,
+        /// The message of the merge commit, which defaults to `Merge commit '<theirs>'` just like `git subtree merge` would use.
+        pub message: Option<String>,
+    }
+
+    /// The outcome of [`Repository::subtree_merge()`](crate::Repository::subtree_merge()).
+    #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+    pub enum Outcome {
+        /// The commit to merge is already contained in the history of `HEAD`, so nothing was done.
+        AlreadyUpToDate,
+        /// A merge commit was created and `HEAD` was moved to it.
+        Merged {
+            /// The newly created merge commit.
+            commit: gix_hash::ObjectId,
+            /// The tree of the merge commit.
+            tree: gix_hash::ObjectId,
+        },
+    }
+
+    /// The error returned by [`Repository::subtree_merge()`](crate::Repository::subtree_merge()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ReadOnly(#[from] crate::repository::read_only::Error),
+        #[error(transparent)]
+        HeadCommit(#[from] crate::reference::head_commit::Error),
+        #[error(transparent)]
+        Walk(#[from] crate::revision::walk::Error),
+        #[error(transparent)]
+        Traverse(#[from] gix_traverse::commit::ancestors::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        PeelToCommit(#[from] crate::object::peel::to_kind::Error),
+        #[error(transparent)]
+        ObjectKind(#[from] crate::object::try_into::Error),
+        #[error(transparent)]
+        Decode(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        WriteObject(#[from] crate::object::write::Error),
+        #[error("The merge has conflicts at {} path(s) that can't be resolved automatically", paths.len())]
+        Conflicts { paths: Vec<BString> },
+        #[error(transparent)]
+        Checkout(#[from] crate::checkout::tree::Error),
+        #[error(transparent)]
+        Commit(#[from] crate::commit::Error),
+    }
+}
@@ -1,7 +1,7 @@
 //!
 mod error {
 
-    /// The error returned by [`tag(…)`][crate::Repository::tag()].
+    /// The error returned by [`tag(…)`][crate::Repository::tag()] and [`tag_signed(…)`][crate::Repository::tag_signed()].
     #[derive(Debug, thiserror::Error)]
     #[allow(missing_docs)]
     pub enum Error {
@@ -11,6 +11,61 @@ mod error {
         WriteObject(#[from] crate::object::write::Error),
         #[error(transparent)]
         ReferenceEdit(#[from] crate::reference::edit::Error),
+        #[error(transparent)]
+        SigningPayload(#[from] gix_object::tag::write::Error),
+        #[error("Could not sign the tag")]
+        Sign(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
     }
 }
 pub use error::Error;
+
+///
+pub mod create {
+    use crate::bstr::BString;
+
+    /// A function to produce a signature over the bytes it receives, see [`Options::sign`].
+    pub type SignFn<'a> =
+        Box<dyn FnOnce(&[u8]) -> Result<BString, Box<dyn std::error::Error + Send + Sync + 'static>> + 'a>;
+
+    /// Options for use in [`Repository::create_tag()`](crate::Repository::create_tag()).
+    #[derive(Default)]
+    pub struct Options<'a> {
+        /// If `true`, an existing tag of the same name will be overwritten, similar to `git tag --force`.
+        /// Otherwise, creating the tag fails if it already exists.
+        pub force: bool,
+        /// If `true`, create a lightweight tag, which is just a reference pointing to the target, without a tag object.
+        /// The `message`, `tagger` and `sign` are ignored in this case.
+        pub lightweight: bool,
+        /// The identity of the tagger, or `None` to use the [committer](crate::Repository::committer()) as configured,
+        /// just like `git` does.
+        pub tagger: Option<gix_actor::SignatureRef<'a>>,
+        /// If set, it's called with the exact bytes to sign, and the signature it returns is appended to the tag object.
+        /// Any error it returns aborts the operation before anything is written.
+        ///
+        /// Any [`Signer`](crate::gpg::sign::Signer), like the [configured one](crate::Repository::signer()), can be used
+        /// by calling it from the function.
+        pub sign: Option<SignFn<'a>>,
+    }
+
+    /// The error returned by [`Repository::create_tag()`](crate::Repository::create_tag()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ReferenceNameValidation(#[from] gix_ref::name::Error),
+        #[error(transparent)]
+        FindReference(#[from] crate::reference::find::Error),
+        #[error("The tag '{name}' already exists")]
+        Exists { name: BString },
+        #[error("Could not find the object to tag")]
+        FindTarget(#[from] crate::object::find::existing::Error),
+        #[error("Tagger identity is not configured")]
+        TaggerMissing,
+        #[error(transparent)]
+        ParseTime(#[from] crate::config::time::Error),
+        #[error(transparent)]
+        Tag(#[from] super::Error),
+        #[error(transparent)]
+        ReferenceEdit(#[from] crate::reference::edit::Error),
+    }
+}
@@ -20,6 +20,112 @@ pub(crate) type IndexStorage = gix_features::threading::OwnShared<gix_fs::Shared
 #[cfg(feature = "index")]
 pub type Index = gix_fs::SharedFileSnapshot<gix_index::File>;
 
+/// A cache to share decoded index files among repositories that were opened independently, for use with
+/// [`open::Options::index_cache()`](crate::open::Options::index_cache()).
+///
+/// What's shared is the fully decoded [index](gix_index::File) of each repository, keyed by its git directory,
+/// so the index file is read and decoded only once for all repositories using the same cache. The index file itself isn't
+/// memory-mapped.
+///
+/// Each index is decoded lazily on first access and kept in memory for as long as the modification time of the index file
+/// on disk doesn't change, which is checked each time the index is [obtained](crate::Repository::index()).
+/// Once it changes, the next access decodes the index again and all handles will see the new snapshot,
+/// while previously obtained snapshots remain valid for as long as they are held.
+///
+/// At most [`capacity`](Self::with_capacity()) indices are tracked, and the least recently used one is forgotten once
+/// the index of another repository is added. By default, [`DEFAULT_CAPACITY`](Self::DEFAULT_CAPACITY) indices are tracked.
+///
+/// Clones of this instance refer to the same cache, and it's cheap to clone.
+/// It's only available with the `parallel` feature, as without it the cache couldn't be shared across threads.
+#[cfg(all(feature = "index", feature = "parallel"))]
+#[derive(Clone)]
+pub struct IndexCache {
+    state: gix_features::threading::OwnShared<gix_features::threading::Mutable<IndexCacheState>>,
+}
+
+#[cfg(all(feature = "index", feature = "parallel"))]
+struct IndexCacheState {
+    /// The storage for each git directory, along with the value of `use_count` when it was last used.
+    by_git_dir: std::collections::HashMap<PathBuf, (IndexStorage, u64)>,
+    /// The git directories in `by_git_dir` by the value of `use_count` when they were last used, least recently used first.
+    by_last_use: std::collections::BTreeMap<u64, PathBuf>,
+    capacity: usize,
+    use_count: u64,
+}
+
+#[cfg(all(feature = "index", feature = "parallel"))]
+impl Default for IndexCache {
+    fn default() -> Self {
+        Self::with_capacity(Self::DEFAULT_CAPACITY)
+    }
+}
+
+#[cfg(all(feature = "index", feature = "parallel"))]
+impl IndexCache {
+    /// The amount of indices tracked by a [default](Default) instance.
+    pub const DEFAULT_CAPACITY: usize = 64;
+
+    /// Create a new instance which tracks the indices of at most `capacity` repositories, forgetting the least recently used
+    /// one once the capacity is exceeded.
+    pub fn with_capacity(capacity: usize) -> Self {
+        IndexCache {
+            state: gix_features::threading::OwnShared::new(gix_features::threading::Mutable::new(IndexCacheState {
+                by_git_dir: Default::default(),
+                by_last_use: Default::default(),
+                capacity,
+                use_count: 0,
+            })),
+        }
+    }
+
+    /// Return the storage for the index of the repository at `git_dir`, creating it if it didn't exist yet.
+    pub(crate) fn storage_for(&self, git_dir: &std::path::Path) -> IndexStorage {
+        let git_dir = gix_path::realpath(git_dir).unwrap_or_else(|_| git_dir.to_owned());
+        let mut state = gix_features::threading::lock(&self.state);
+        let state = &mut *state;
+        state.use_count += 1;
+        let use_count = state.use_count;
+        if let Some((storage, last_used)) = state.by_git_dir.get_mut(&git_dir) {
+            let git_dir = state.by_last_use.remove(last_used).expect("in sync");
+            state.by_last_use.insert(use_count, git_dir);
+            *last_used = use_count;
+            return storage.clone();
+        }
+
+        let storage: IndexStorage = gix_fs::SharedFileSnapshotMut::new().into();
+        if state.capacity == 0 {
+            return storage;
+        }
+        while state.by_git_dir.len() >= state.capacity {
+            let least_recently_used = *state.by_last_use.keys().next().expect("not empty");
+            let git_dir = state.by_last_use.remove(&least_recently_used).expect("present");
+            state.by_git_dir.remove(&git_dir);
+        }
+        state.by_last_use.insert(use_count, git_dir.clone());
+        state.by_git_dir.insert(git_dir, (storage.clone(), use_count));
+        storage
+    }
+
+    /// Return the amount of repositories whose index is tracked by this cache.
+    pub fn len(&self) -> usize {
+        gix_features::threading::lock(&self.state).by_git_dir.len()
+    }
+
+    /// Return `true` if no index is tracked by this cache yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Forget all cached indices, releasing their memory once no repository refers to them anymore.
+    ///
+    /// Repositories that were opened before will keep sharing their index with each other, but not with repositories opened afterwards.
+    pub fn clear(&self) {
+        let mut state = gix_features::threading::lock(&self.state);
+        state.by_git_dir.clear();
+        state.by_last_use.clear();
+    }
+}
+
 /// A stand-in to a worktree as result of a worktree iteration.
 ///
 /// It provides access to typical worktree state, but may not actually point to a valid checkout as the latter has been moved or
@@ -85,6 +191,179 @@ pub(crate) fn id(git_dir: &std::path::Path, has_common_dir: bool) -> Option<&BSt
 ///
 pub mod proxy;
 
+///
+#[cfg(feature = "worktree-mutation")]
+pub mod add {
+    use std::path::PathBuf;
+
+    use crate::bstr::BString;
+
+    /// What to check out in a new worktree, for use with [`Repository::add_worktree()`](crate::Repository::add_worktree()).
+    #[derive(Debug, Clone)]
+    pub enum Head {
+        /// Check out the existing local branch with the given name, like `main` or `refs/heads/main`, which must not be
+        /// checked out in another worktree.
+        Branch(BString),
+        /// Create a new local branch with the given `name` at `commit` and check it out, like `git worktree add -b <name>`.
+        NewBranch {
+            /// The name of the branch, like `feature` or `refs/heads/feature`.
+            name: BString,
+            /// The commit the new branch should point to.
+            commit: gix_hash::ObjectId,
+        },
+        /// Detach `HEAD` at the given commit, like `git worktree add --detach`.
+        Detached(gix_hash::ObjectId),
+    }
+
+    /// Options for use with [`Repository::add_worktree()`](crate::Repository::add_worktree()).
+    #[derive(Debug, Default, Clone)]
+    pub struct Options {
+        /// If set, lock the new worktree with the given reason, which may be empty, like `git worktree add --lock --reason <reason>`.
+        pub lock_reason: Option<BString>,
+    }
+
+    /// The error returned by [`Repository::add_worktree()`](crate::Repository::add_worktree()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ReadOnly(#[from] crate::repository::read_only::Error),
+        #[error("The worktree location '{}' already exists and isn't an empty directory", path.display())]
+        DestinationExists { path: PathBuf },
+        #[error("The branch '{name}' already exists")]
+        BranchExists { name: BString },
+        #[error("The branch '{name}' is already checked out at '{}'", path.display())]
+        BranchCheckedOut { name: BString, path: PathBuf },
+        #[error(transparent)]
+        BranchName(#[from] gix_validate::reference::name::Error),
+        #[error(transparent)]
+        FindReference(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        TryFindReference(#[from] crate::reference::find::Error),
+        #[error(transparent)]
+        PeelReference(#[from] crate::reference::peel::Error),
+        #[error(transparent)]
+        CreateBranch(#[from] crate::reference::edit::Error),
+        #[error(transparent)]
+        ReadHead(#[from] crate::worktree::proxy::head::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        PeelToCommit(#[from] crate::object::peel::to_kind::Error),
+        #[error("Could not set up the worktree at '{}'", path.display())]
+        Io { path: PathBuf, source: std::io::Error },
+        #[error(transparent)]
+        Open(#[from] crate::open::Error),
+        #[error(transparent)]
+        IndexFromTree(#[from] gix_traverse::tree::breadthfirst::Error),
+        #[error(transparent)]
+        CheckoutOptions(#[from] crate::config::checkout_options::Error),
+        #[error(transparent)]
+        Checkout(#[from] gix_worktree_state::checkout::Error),
+        #[error(transparent)]
+        WriteIndex(#[from] gix_index::file::write::Error),
+    }
+}
+
+///
+pub mod prune {
+    use std::path::PathBuf;
+
+    use crate::bstr::BString;
+
+    /// Options for use with [`Repository::prune_worktrees()`](crate::Repository::prune_worktrees()).
+    #[derive(Debug, Default, Copy, Clone)]
+    pub struct Options {
+        /// If `true`, only report what would be pruned without removing anything, like `git worktree prune --dry-run`.
+        pub dry_run: bool,
+        /// If set, only prune worktrees whose checkout went missing if their private index wasn't modified after
+        /// this time, like `git worktree prune --expire <time>`.
+        ///
+        /// Administrative directories that are invalid or that have no index are pruned regardless.
+        pub expire: Option<std::time::SystemTime>,
+    }
+
+    /// The reason for a worktree to be pruned.
+    #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+    pub enum Reason {
+        /// The entry in the `worktrees` directory isn't a directory.
+        NotADirectory,
+        /// The `gitdir` file, which points to the checkout, doesn't exist.
+        MissingGitdirFile,
+        /// The `gitdir` file couldn't be read or is empty.
+        InvalidGitdirFile,
+        /// The `gitdir` file points to a location that doesn't exist anymore.
+        MissingWorktree,
+    }
+
+    /// A worktree that was pruned by [`Repository::prune_worktrees()`](crate::Repository::prune_worktrees()).
+    #[derive(Debug, Clone, Eq, PartialEq)]
+    pub struct Pruned {
+        /// The name of the worktree within the `worktrees` directory.
+        pub id: BString,
+        /// Why the worktree was pruned.
+        pub reason: Reason,
+    }
+
+    /// The error returned by [`Repository::prune_worktrees()`](crate::Repository::prune_worktrees()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ReadOnly(#[from] crate::repository::read_only::Error),
+        #[error("Could not prune the worktree administrative files at '{}'", path.display())]
+        Io { path: PathBuf, source: std::io::Error },
+    }
+}
+
+///
+pub mod repair {
+    use std::path::PathBuf;
+
+    use crate::bstr::BString;
+
+    /// The file that was rewritten to repair the connection between a linked worktree and its repository.
+    #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+    pub enum Kind {
+        /// The `.git` file in the checkout was rewritten to point to the private git directory of the worktree,
+        /// which is needed after the repository was moved.
+        DotGitFile,
+        /// The `gitdir` file in the private git directory was rewritten to point to the checkout,
+        /// which is needed after the checkout was moved manually.
+        GitdirFile,
+    }
+
+    /// A repair performed by [`Repository::repair_worktrees()`](crate::Repository::repair_worktrees()).
+    #[derive(Debug, Clone, Eq, PartialEq)]
+    pub struct Repaired {
+        /// The name of the worktree within the `worktrees` directory.
+        pub id: BString,
+        /// The kind of file that was rewritten.
+        pub kind: Kind,
+        /// The path to the file that was rewritten.
+        pub path: PathBuf,
+    }
+
+    /// The error returned by [`Repository::repair_worktrees()`](crate::Repository::repair_worktrees()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ReadOnly(#[from] crate::repository::read_only::Error),
+        #[error("'{}' is not a linked worktree as it has no .git file", path.display())]
+        NotALinkedWorktree { path: PathBuf },
+        #[error("Could not read the .git file at '{}'", path.display())]
+        InvalidDotGitFile {
+            path: PathBuf,
+            source: gix_discover::path::from_gitdir_file::Error,
+        },
+        #[error("The worktree at '{}' isn't known to this repository", path.display())]
+        UnknownWorktree { path: PathBuf },
+        #[error("Could not repair the worktree at '{}'", path.display())]
+        Io { path: PathBuf, source: std::io::Error },
+    }
+}
+
 ///
 #[cfg(feature = "index")]
 pub mod open_index {
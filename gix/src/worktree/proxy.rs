@@ -24,6 +24,75 @@ pub mod into_repo {
     }
 }
 
+///
+pub mod head {
+    use std::path::PathBuf;
+
+    /// The error returned by [`Proxy::head()`][super::Proxy::head()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not read the HEAD file at '{}'", path.display())]
+        Io { path: PathBuf, source: std::io::Error },
+        #[error(transparent)]
+        Decode(#[from] gix_ref::file::loose::reference::decode::Error),
+    }
+}
+
+///
+pub mod move_to {
+    use std::path::PathBuf;
+
+    use crate::bstr::BString;
+
+    /// The error returned by [`Proxy::move_to()`][super::Proxy::move_to()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ReadOnly(#[from] crate::repository::read_only::Error),
+        #[error("The worktree is locked and cannot be moved: '{reason}'")]
+        Locked { reason: BString },
+        #[error("The destination '{}' already exists", path.display())]
+        DestinationExists { path: PathBuf },
+        #[error("Could not move the worktree to '{}'", path.display())]
+        Io { path: PathBuf, source: std::io::Error },
+    }
+}
+
+///
+#[cfg(feature = "status")]
+pub mod remove {
+    use std::path::PathBuf;
+
+    use crate::bstr::BString;
+
+    /// Options for use with [`Proxy::remove()`][super::Proxy::remove()].
+    #[derive(Debug, Default, Copy, Clone)]
+    pub struct Options {
+        /// If `true`, remove the worktree even if it has local changes or untracked files, like `git worktree remove --force`.
+        pub force: bool,
+    }
+
+    /// The error returned by [`Proxy::remove()`][super::Proxy::remove()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ReadOnly(#[from] crate::repository::read_only::Error),
+        #[error("The worktree is locked and cannot be removed: '{reason}'")]
+        Locked { reason: BString },
+        #[error("The worktree at '{}' has local changes or untracked files", base.display())]
+        Dirty { base: PathBuf },
+        #[error(transparent)]
+        Open(#[from] super::into_repo::Error),
+        #[error(transparent)]
+        Status(#[from] crate::status::Error),
+        #[error("Could not remove '{}'", path.display())]
+        Io { path: PathBuf, source: std::io::Error },
+    }
+}
+
 impl<'repo> Proxy<'repo> {
     pub(crate) fn new(parent: &'repo Repository, git_dir: impl Into<PathBuf>) -> Self {
         Proxy {
@@ -74,6 +143,95 @@ impl<'repo> Proxy<'repo> {
             .map(|contents| contents.trim().into())
     }
 
+    /// Return `true` if the worktree can be pruned as its checkout doesn't exist anymore, and it isn't [locked](Self::is_locked()),
+    /// similar to what `git worktree prune` would remove.
+    pub fn is_prunable(&self) -> bool {
+        !self.is_locked()
+            && self
+                .base()
+                .map_or(true, |base| !base.join(gix_discover::DOT_GIT_DIR).is_file())
+    }
+
+    /// Read the `HEAD` of the worktree, which is either a symbolic reference to the checked out branch, or the
+    /// id of the commit it is detached at.
+    pub fn head(&self) -> Result<gix_ref::Target, head::Error> {
+        read_head(&self.git_dir)
+    }
+
+    /// Lock the worktree with the given `reason`, which may be empty, to prevent it from being pruned, moved or removed,
+    /// like `git worktree lock --reason <reason>`.
+    ///
+    /// It's an error of kind [`AlreadyExists`](std::io::ErrorKind::AlreadyExists) if the worktree is locked already, and of kind
+    /// [`PermissionDenied`](std::io::ErrorKind::PermissionDenied) if the repository is [read-only](crate::open::Options::read_only()).
+    pub fn lock(&self, reason: &BStr) -> std::io::Result<()> {
+        use std::io::Write;
+        self.assure_writable("lock a worktree")?;
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(self.git_dir.join("locked"))?;
+        file.write_all(reason)
+    }
+
+    /// Unlock the worktree, like `git worktree unlock`.
+    ///
+    /// It's an error of kind [`NotFound`](std::io::ErrorKind::NotFound) if the worktree isn't locked, and of kind
+    /// [`PermissionDenied`](std::io::ErrorKind::PermissionDenied) if the repository is [read-only](crate::open::Options::read_only()).
+    pub fn unlock(&self) -> std::io::Result<()> {
+        self.assure_writable("unlock a worktree")?;
+        std::fs::remove_file(self.git_dir.join("locked"))
+    }
+
+    /// Move the checkout of this worktree to `new_base`, which must not exist yet, and update the private git directory
+    /// to point to it, like `git worktree move`.
+    ///
+    /// Relative paths are interpreted relative to the current working directory, and locked worktrees can't be moved.
+    pub fn move_to(&self, new_base: impl AsRef<Path>) -> Result<(), move_to::Error> {
+        self.parent.assure_writable("move a worktree")?;
+        if let Some(reason) = self.lock_reason() {
+            return Err(move_to::Error::Locked { reason });
+        }
+        let new_base = self.parent.options.current_dir_or_empty().join(new_base.as_ref());
+        if new_base.exists() {
+            return Err(move_to::Error::DestinationExists { path: new_base });
+        }
+        let io_err = |source| move_to::Error::Io {
+            path: new_base.clone(),
+            source,
+        };
+        let base = self.base().map_err(io_err)?;
+        std::fs::rename(base, &new_base).map_err(io_err)?;
+        let new_base =
+            gix_path::realpath(&new_base).map_err(|err| io_err(std::io::Error::new(std::io::ErrorKind::Other, err)))?;
+        write_gitdir_file(&self.git_dir, &new_base).map_err(io_err)
+    }
+
+    /// Remove the checkout of this worktree along with its private git directory, like `git worktree remove`.
+    ///
+    /// Unless [`options.force`](remove::Options::force) is set, the worktree must not have local changes or untracked files.
+    /// Locked worktrees can't be removed, and it's not an error if the checkout doesn't exist anymore.
+    #[cfg(feature = "status")]
+    pub fn remove(self, options: remove::Options) -> Result<(), remove::Error> {
+        self.parent.assure_writable("remove a worktree")?;
+        if let Some(reason) = self.lock_reason() {
+            return Err(remove::Error::Locked { reason });
+        }
+        let base = self.base().ok().filter(|base| base.is_dir());
+        if let Some(base) = base {
+            if !options.force {
+                let repo = Proxy::new(self.parent, self.git_dir.clone()).into_repo()?;
+                if !repo.status(Default::default())?.items.is_empty() {
+                    return Err(remove::Error::Dirty { base });
+                }
+            }
+            std::fs::remove_dir_all(&base).map_err(|source| remove::Error::Io { path: base, source })?;
+        }
+        std::fs::remove_dir_all(&self.git_dir).map_err(|source| remove::Error::Io {
+            path: self.git_dir,
+            source,
+        })
+    }
+
     /// Transform this proxy into a [`Repository`] while ignoring issues reading `base()` and ignoring that it might not exist.
     ///
     /// Most importantly, the `Repository` might be initialized with a non-existing work tree directory as the checkout
@@ -99,3 +257,33 @@ impl<'repo> Proxy<'repo> {
         Ok(repo.into())
     }
 }
+
+impl Proxy<'_> {
+    fn assure_writable(&self, action: &'static str) -> std::io::Result<()> {
+        self.parent
+            .assure_writable(action)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::PermissionDenied, err))
+    }
+}
+
+/// Read the `HEAD` file in `git_dir` without resolving it.
+pub(crate) fn read_head(git_dir: &Path) -> Result<gix_ref::Target, head::Error> {
+    let path = git_dir.join("HEAD");
+    let data = std::fs::read(&path).map_err(|source| head::Error::Io { path, source })?;
+    Ok(gix_ref::file::loose::Reference::try_from_path("HEAD".try_into().expect("valid"), &data)?.target)
+}
+
+/// Write the `gitdir` file into the private `git_dir` of a linked worktree to point to the `.git` file in `base`.
+pub(crate) fn write_gitdir_file(git_dir: &Path, base: &Path) -> std::io::Result<()> {
+    let mut content = gix_path::into_bstr(base.join(gix_discover::DOT_GIT_DIR)).into_owned();
+    content.push(b'\n');
+    std::fs::write(git_dir.join("gitdir"), content)
+}
+
+/// Write the `.git` file into the checkout at `base` of a linked worktree to point to its `private_git_dir`.
+pub(crate) fn write_dot_git_file(base: &Path, private_git_dir: &Path) -> std::io::Result<()> {
+    let mut content = BString::from("gitdir: ");
+    content.extend_from_slice(&gix_path::into_bstr(private_git_dir));
+    content.push(b'\n');
+    std::fs::write(base.join(gix_discover::DOT_GIT_DIR), content)
+}
@@ -491,6 +491,35 @@ mod blocking_io {
         Ok(())
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn fetch_and_checkout_runs_post_checkout_hook() -> crate::Result {
+        use std::os::unix::fs::PermissionsExt;
+        let hooks = gix_testtools::tempfile::TempDir::new()?;
+        let hook_path = hooks.path().join("post-checkout");
+        std::fs::write(&hook_path, "#!/bin/sh\necho \"$@\" > \"$GIT_DIR/post-checkout-args\"\n")?;
+        std::fs::set_permissions(&hook_path, std::fs::Permissions::from_mode(0o755))?;
+
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let mut prepare = gix::clone::PrepareFetch::new(
+            remote::repo("base").path(),
+            tmp.path(),
+            gix::create::Kind::WithWorktree,
+            Default::default(),
+            restricted().config_overrides(Some(format!("core.hooksPath={}", hooks.path().display()))),
+        )?;
+        let (mut checkout, _out) =
+            prepare.fetch_then_checkout(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+        let (repo, _) = checkout.main_worktree(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+
+        assert_eq!(
+            std::fs::read_to_string(repo.git_dir().join("post-checkout-args"))?,
+            format!("{} {} 1\n", gix::ObjectId::null(repo.object_hash()), repo.head_id()?),
+            "just like in `git`, the hook sees a checkout from the null id to the new HEAD"
+        );
+        Ok(())
+    }
+
     #[test]
     fn fetch_and_checkout_empty_remote_repo() -> crate::Result {
         for version in [
@@ -565,6 +594,228 @@ mod blocking_io {
         }
         Ok(())
     }
+
+    mod builder {
+        use std::sync::atomic::AtomicBool;
+
+        use gix::bstr::ByteSlice;
+
+        use crate::util::restricted;
+
+        fn repo(name: &str) -> crate::Result<std::path::PathBuf> {
+            Ok(gix_testtools::scripted_fixture_read_only("make_clone_repos.sh")?.join(name))
+        }
+
+        fn builder(tmp: &gix_testtools::tempfile::TempDir) -> crate::Result<gix::clone::Builder> {
+            Ok(gix::clone::Builder::new(repo("super")?, tmp.path().join("clone"))?.with_open_options(restricted()))
+        }
+
+        #[test]
+        fn mirror_is_bare_and_maps_all_refs() -> crate::Result {
+            let tmp = gix_testtools::tempfile::TempDir::new()?;
+            let out = builder(&tmp)?
+                .with_mirror(true)
+                .run(gix::progress::Discard, &AtomicBool::default())?;
+            let repo = out.repo;
+            assert!(repo.is_bare());
+            assert!(out.checkout.is_none());
+            assert_eq!(repo.config_snapshot().boolean("remote.origin.mirror"), Some(true));
+            for name in ["refs/heads/main", "refs/heads/dev", "refs/tags/v1"] {
+                assert!(repo.try_find_reference(name)?.is_some(), "{name} is mirrored");
+            }
+            assert!(repo.try_find_reference("refs/remotes/origin/main")?.is_none());
+            Ok(())
+        }
+
+        #[test]
+        fn single_branch_with_depth() -> crate::Result {
+            let tmp = gix_testtools::tempfile::TempDir::new()?;
+            let out = builder(&tmp)?
+                .with_branch("dev")
+                .with_single_branch(true)
+                .with_depth(1.try_into().expect("non-zero"))
+                .run(gix::progress::Discard, &AtomicBool::default())?;
+            let repo = out.repo;
+            assert_eq!(
+                repo.head_name()?.expect("not detached").as_bstr(),
+                "refs/heads/dev",
+                "HEAD points to the chosen branch"
+            );
+            assert!(repo.try_find_reference("refs/remotes/origin/dev")?.is_some());
+            assert!(
+                repo.try_find_reference("refs/remotes/origin/main")?.is_none(),
+                "other branches aren't fetched"
+            );
+            assert_eq!(repo.shallow_commits()?.expect("shallow").len(), 1);
+            assert!(repo.work_dir().expect("non-bare").join("d").is_file());
+            assert!(out.checkout.is_some());
+            Ok(())
+        }
+
+        #[test]
+        fn branch_can_be_a_tag() -> crate::Result {
+            let tmp = gix_testtools::tempfile::TempDir::new()?;
+            let out = builder(&tmp)?
+                .with_branch("v1")
+                .run(gix::progress::Discard, &AtomicBool::default())?;
+            let repo = out.repo;
+            assert!(
+                repo.head()?.is_detached(),
+                "tags can only be checked out as detached HEAD"
+            );
+            assert_eq!(
+                repo.head_id()?,
+                repo.find_reference("refs/tags/v1")?.peel_to_id_in_place()?
+            );
+            assert!(!repo.work_dir().expect("non-bare").join("d").exists());
+            Ok(())
+        }
+
+        #[test]
+        fn missing_branch_is_an_error() -> crate::Result {
+            let tmp = gix_testtools::tempfile::TempDir::new()?;
+            let err = builder(&tmp)?
+                .with_branch("does-not-exist")
+                .run(gix::progress::Discard, &AtomicBool::default())
+                .err()
+                .expect("the branch doesn't exist");
+            use gix::error::Classify;
+            assert_eq!(err.kind(), gix::error::Kind::NotFound);
+            assert!(!tmp.path().join("clone").exists(), "the clone is removed on failure");
+            Ok(())
+        }
+
+        #[test]
+        fn without_checkout() -> crate::Result {
+            let tmp = gix_testtools::tempfile::TempDir::new()?;
+            let out = builder(&tmp)?
+                .with_checkout(false)
+                .run(gix::progress::Discard, &AtomicBool::default())?;
+            assert!(out.checkout.is_none());
+            let work_dir = out.repo.work_dir().expect("non-bare");
+            assert!(!work_dir.join("a").exists(), "nothing was checked out");
+            assert!(!out.repo.index_path().is_file());
+            Ok(())
+        }
+
+        #[test]
+        fn with_reference_writes_alternates() -> crate::Result {
+            let tmp = gix_testtools::tempfile::TempDir::new()?;
+            let out = builder(&tmp)?
+                .with_bare(true)
+                .with_reference(repo("super")?)
+                .run(gix::progress::Discard, &AtomicBool::default())?;
+            let alternates = std::fs::read(out.repo.git_dir().join("objects").join("info").join("alternates"))?;
+            let expected = gix_path::into_bstr(std::fs::canonicalize(repo("super")?.join(".git").join("objects"))?);
+            assert_eq!(alternates.trim(), expected.as_ref());
+            assert!(out.repo.find_object(out.repo.head_id()?).is_ok());
+            Ok(())
+        }
+
+        #[test]
+        fn with_filter_configures_a_promisor_remote() -> crate::Result {
+            let tmp = gix_testtools::tempfile::TempDir::new()?;
+            let out = builder(&tmp)?
+                .with_bare(true)
+                .with_filter("blob:none")
+                .run(gix::progress::Discard, &AtomicBool::default())?;
+            let config = out.repo.config_snapshot();
+            assert_eq!(config.boolean("remote.origin.promisor"), Some(true));
+            assert_eq!(
+                config.string("remote.origin.partialclonefilter").as_deref(),
+                Some("blob:none".into())
+            );
+            assert_eq!(
+                config.string("extensions.partialClone").as_deref(),
+                Some("origin".into())
+            );
+            let tree = out.repo.head_commit()?.tree()?;
+            let blob = tree.find_entry("a").expect("present").oid().to_owned();
+            assert!(!out.repo.has_object(blob), "blobs were filtered out");
+            Ok(())
+        }
+
+        #[test]
+        fn with_filter_fetches_missing_objects_on_demand() -> crate::Result {
+            let tmp = gix_testtools::tempfile::TempDir::new()?;
+            let out = builder(&tmp)?
+                .with_bare(true)
+                .with_filter("blob:none")
+                .run(gix::progress::Discard, &AtomicBool::default())?;
+            let repo = out.repo;
+            let pack_dir = repo.objects.store_ref().path().join("pack");
+            let num_promisor_files = || -> std::io::Result<usize> {
+                Ok(std::fs::read_dir(&pack_dir)?
+                    .filter_map(Result::ok)
+                    .filter(|entry| entry.path().extension().map_or(false, |ext| ext == "promisor"))
+                    .count())
+            };
+            assert_eq!(num_promisor_files()?, 1, "packs from promisor remotes are marked");
+
+            let tree = repo.head_commit()?.tree()?;
+            let a = tree.find_entry("a").expect("present").oid().to_owned();
+            let gitmodules = tree.find_entry(".gitmodules").expect("present").oid().to_owned();
+            assert!(!repo.has_object(a) && !repo.has_object(gitmodules));
+            assert!(
+                repo.find_object(gitmodules).is_err(),
+                "lazy fetching is disabled by default"
+            );
+
+            assert_eq!(
+                repo.fetch_missing_objects([a, a, tree.id])?,
+                1,
+                "only missing objects are fetched"
+            );
+            assert!(repo.has_object(a));
+            assert_eq!(num_promisor_files()?, 2);
+
+            assert!(repo.enable_lazy_fetch()?);
+            assert_eq!(repo.find_object(gitmodules)?.kind, gix::object::Kind::Blob);
+            assert_eq!(num_promisor_files()?, 3);
+
+            repo.disable_lazy_fetch();
+            let d = repo
+                .find_reference("refs/remotes/origin/dev")?
+                .peel_to_id_in_place()?
+                .object()?
+                .into_commit()
+                .tree()?
+                .find_entry("d")
+                .expect("present")
+                .oid()
+                .to_owned();
+            assert!(repo.find_object(d).is_err());
+            Ok(())
+        }
+
+        #[test]
+        fn recurse_submodules() -> crate::Result {
+            let tmp = gix_testtools::tempfile::TempDir::new()?;
+            let out = builder(&tmp)?
+                .with_recurse_submodules(true)
+                .run(gix::progress::Discard, &AtomicBool::default())?;
+            assert_eq!(out.submodules.len(), 1);
+            let sm = &out.submodules[0];
+            assert_eq!(sm.name, "lib");
+            assert_eq!(sm.path, "lib");
+            let sm_repo = &sm.outcome.repo;
+            assert_eq!(
+                sm_repo.work_dir().expect("non-bare").canonicalize()?,
+                out.repo.work_dir().expect("non-bare").join("lib").canonicalize()?
+            );
+            assert!(
+                sm_repo.head()?.is_detached(),
+                "submodules are checked out at their recorded commit"
+            );
+            assert!(sm_repo.work_dir().expect("non-bare").join("sub-file").is_file());
+
+            let superproject = gix::open_opts(out.repo.work_dir().expect("non-bare"), restricted())?;
+            let config = superproject.config_snapshot();
+            assert_eq!(config.boolean("submodule.lib.active"), Some(true));
+            assert!(config.string("submodule.lib.url").expect("set").ends_with(b"sub"));
+            Ok(())
+        }
+    }
 }
 
 #[test]
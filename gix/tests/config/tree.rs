@@ -145,6 +145,47 @@ mod ssh {
     }
 }
 
+mod gpg {
+    use gix::{
+        config::tree::{Gpg, Key},
+        gpg::verify::Trust,
+    };
+
+    use crate::config::tree::bcow;
+
+    #[test]
+    fn format() -> crate::Result {
+        for (actual, expected) in [
+            ("openpgp", gix::gpg::Format::OpenPgp),
+            ("x509", gix::gpg::Format::X509),
+            ("ssh", gix::gpg::Format::Ssh),
+        ] {
+            assert_eq!(Gpg::FORMAT.try_into_format(bcow(actual))?, expected);
+            assert!(Gpg::FORMAT.validate(actual.into()).is_ok());
+        }
+        assert_eq!(
+            Gpg::FORMAT.try_into_format(bcow("gpg")).unwrap_err().to_string(),
+            "The key \"gpg.format=gpg\" was invalid"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn min_trust_level() -> crate::Result {
+        for (actual, expected) in [
+            ("undefined", Trust::Undefined),
+            ("never", Trust::Never),
+            ("marginal", Trust::Marginal),
+            ("fully", Trust::Fully),
+            ("ultimate", Trust::Ultimate),
+        ] {
+            assert_eq!(Gpg::MIN_TRUST_LEVEL.try_into_trust(bcow(actual))?, expected);
+        }
+        assert!(Gpg::MIN_TRUST_LEVEL.validate("full".into()).is_err());
+        Ok(())
+    }
+}
+
 mod fetch {
 
     #[test]
@@ -206,6 +247,43 @@ mod fetch {
     }
 }
 
+#[cfg(feature = "maintenance")]
+mod gc {
+    use std::time::{Duration, SystemTime};
+
+    use gix::config::tree::{Gc, Key};
+
+    use crate::config::tree::bcow;
+
+    #[test]
+    fn prune_expire() -> crate::Result {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        for (actual, expected) in [
+            ("never", None),
+            ("false", None),
+            ("now", Some(now)),
+            ("all", Some(now)),
+            ("2.weeks.ago", Some(now - Duration::from_secs(14 * 24 * 60 * 60))),
+            ("1 hour ago", Some(now - Duration::from_secs(60 * 60))),
+        ] {
+            assert_eq!(
+                Gc::PRUNE_EXPIRE.try_into_expiry(bcow(actual), now)?,
+                expected,
+                "{actual}"
+            );
+            assert!(Gc::PRUNE_EXPIRE.validate(actual.into()).is_ok());
+        }
+        assert_eq!(
+            Gc::REFLOG_EXPIRE
+                .try_into_expiry(bcow("foo"), now)
+                .unwrap_err()
+                .to_string(),
+            "The key \"gc.reflogExpire=foo\" was invalid"
+        );
+        Ok(())
+    }
+}
+
 #[cfg(feature = "blob-diff")]
 mod diff {
     use gix::{
@@ -573,6 +651,10 @@ mod extensions {
             gix_hash::Kind::Sha1,
             "case-insensitive"
         );
+        assert_eq!(
+            Extensions::OBJECT_FORMAT.try_into_object_format(bcow("sha256"))?,
+            gix_hash::Kind::Sha256
+        );
         assert_eq!(
             Extensions::OBJECT_FORMAT
                 .try_into_object_format(bcow("invalid"))
@@ -581,9 +663,28 @@ mod extensions {
             "The key \"extensions.objectFormat=invalid\" was invalid"
         );
         assert!(Extensions::OBJECT_FORMAT.validate("sha1".into()).is_ok());
+        assert!(Extensions::OBJECT_FORMAT.validate("sha256".into()).is_ok());
         assert!(Extensions::OBJECT_FORMAT.validate("invalid".into()).is_err());
         Ok(())
     }
+
+    #[test]
+    fn compat_object_format() -> crate::Result {
+        assert_eq!(
+            Extensions::COMPAT_OBJECT_FORMAT.try_into_object_format(bcow("sha1"))?,
+            gix_hash::Kind::Sha1
+        );
+        assert_eq!(
+            Extensions::COMPAT_OBJECT_FORMAT
+                .try_into_object_format(bcow("invalid"))
+                .unwrap_err()
+                .to_string(),
+            "The key \"extensions.compatObjectFormat=invalid\" was invalid"
+        );
+        assert!(Extensions::COMPAT_OBJECT_FORMAT.validate("sha256".into()).is_ok());
+        assert!(Extensions::COMPAT_OBJECT_FORMAT.validate("invalid".into()).is_err());
+        Ok(())
+    }
 }
 
 mod checkout {
@@ -632,6 +733,28 @@ mod pack {
         assert!(Pack::INDEX_VERSION.validate("-1".into()).is_err());
         Ok(())
     }
+    #[test]
+    fn compression() -> crate::Result {
+        assert_eq!(
+            Pack::COMPRESSION.try_into_compression_level(Ok(-1))?,
+            6,
+            "the zlib default"
+        );
+        assert_eq!(Pack::COMPRESSION.try_into_compression_level(Ok(0))?, 0);
+        assert_eq!(Pack::COMPRESSION.try_into_compression_level(Ok(9))?, 9);
+        assert_eq!(
+            Pack::COMPRESSION
+                .try_into_compression_level(Ok(10))
+                .unwrap_err()
+                .to_string(),
+            "The value of key \"pack.compression\" was invalid"
+        );
+        assert!(Pack::COMPRESSION.validate("-1".into()).is_ok());
+        assert!(Pack::COMPRESSION.validate("9".into()).is_ok());
+        assert!(Pack::COMPRESSION.validate("10".into()).is_err());
+        assert!(Pack::COMPRESSION.validate("-2".into()).is_err());
+        Ok(())
+    }
 }
 
 mod protocol {
@@ -736,6 +859,36 @@ mod gitoxide {
             assert!(gitoxide::Allow::PROTOCOL_FROM_USER.validate("0".into()).is_err());
         }
     }
+    mod objects {
+        use gix::config::tree::{gitoxide, Key};
+        use gix_odb::pack::mmap::Advice;
+
+        use crate::config::tree::bcow;
+
+        #[test]
+        fn pack_mmap_advice() -> crate::Result {
+            for (value, expected) in [
+                ("normal", Advice::Normal),
+                ("sequential", Advice::Sequential),
+                ("random", Advice::Random),
+                ("willneed", Advice::WillNeed),
+            ] {
+                assert_eq!(
+                    gitoxide::Objects::PACK_MMAP_ADVICE.try_into_advice(bcow(value))?,
+                    expected
+                );
+                assert!(gitoxide::Objects::PACK_MMAP_ADVICE.validate(value.into()).is_ok());
+            }
+            assert_eq!(
+                gitoxide::Objects::PACK_MMAP_ADVICE
+                    .try_into_advice(bcow("dontneed"))
+                    .unwrap_err()
+                    .to_string(),
+                "The key \"gitoxide.objects.packMmapAdvice=dontneed\" was invalid"
+            );
+            Ok(())
+        }
+    }
     mod commit {
         use gix::config::tree::{gitoxide, Key};
 
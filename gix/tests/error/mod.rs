@@ -0,0 +1,66 @@
+use gix::error::{Classify, Kind};
+
+#[test]
+fn missing_objects_and_references_are_not_found() -> crate::Result {
+    let repo = crate::basic_repo()?;
+
+    let err = repo
+        .find_object(gix::ObjectId::null(repo.object_hash()))
+        .expect_err("the null id doesn't exist");
+    assert_eq!(err.kind(), Kind::NotFound);
+    assert!(!err.is_retryable());
+
+    let err = repo.find_reference("does-not-exist").expect_err("missing");
+    assert_eq!(err.kind(), Kind::NotFound);
+
+    let err = repo
+        .find_object(repo.head_id()?)?
+        .peel_to_kind(gix::object::Kind::Tag)
+        .expect_err("commits can't be peeled to tags");
+    assert_eq!(err.kind(), Kind::NotFound);
+    Ok(())
+}
+
+#[test]
+fn reference_edits_that_would_overwrite() -> crate::Result {
+    let (repo, _tmp) = crate::basic_rw_repo()?;
+    let parent_id = repo.head_commit()?.parent_ids().next().expect("c2 has a parent");
+    let err = repo
+        .reference(
+            "refs/heads/main",
+            parent_id,
+            gix::refs::transaction::PreviousValue::MustNotExist,
+            "must not overwrite",
+        )
+        .expect_err("main exists");
+    assert_eq!(err.kind(), Kind::WouldOverwrite);
+    assert!(!err.is_retryable());
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "blocking-network-client")]
+fn connecting_to_a_missing_local_repository() -> crate::Result {
+    let repo = crate::basic_repo()?;
+    let remote = repo.remote_at(repo.work_dir().expect("non-bare").join("does-not-exist"))?;
+    let err = remote
+        .connect(gix::remote::Direction::Fetch)
+        .err()
+        .expect("there is no repository to connect to");
+    assert_eq!(err.kind(), Kind::NotFound);
+    assert!(!err.is_retryable());
+    Ok(())
+}
+
+#[test]
+#[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
+fn rejected_ref_updates() {
+    use gix::remote::fetch::refs::update::Mode;
+    assert_eq!(Mode::FastForward.rejection_kind(), None);
+    assert_eq!(Mode::NoChangeNeeded.rejection_kind(), None);
+    assert_eq!(
+        Mode::RejectedNonFastForward.rejection_kind(),
+        Some(Kind::NonFastForward)
+    );
+    assert_eq!(Mode::RejectedTagUpdate.rejection_kind(), Some(Kind::WouldOverwrite));
+}
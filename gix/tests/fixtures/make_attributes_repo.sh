@@ -0,0 +1,25 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+cat <<EOF_ATTRS > .gitattributes
+[attr]custom text diff=custom
+*.txt text eol=lf
+*.bin binary
+*.rs diff=rust
+*.md custom
+EOF_ATTRS
+mkdir sub
+echo '*.txt -text eol=crlf' > sub/.gitattributes
+echo '*.rs diff=info' > .git/info/attributes
+cat <<EOF_ATTRS > global-attributes
+*.txt global
+*.rs diff=global
+EOF_ATTRS
+
+git add .gitattributes sub/.gitattributes global-attributes
+git commit -q -m "init"
+
+echo '*.txt staged' >> .gitattributes
+git add .gitattributes
+echo '*.txt worktree' >> .gitattributes
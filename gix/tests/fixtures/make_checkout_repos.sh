@@ -0,0 +1,49 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q plan
+(cd plan
+  mkdir dir
+  echo content > unchanged
+  echo content > modified
+  echo content > deleted
+  echo content > dir/file
+  echo "*.ignored" > .gitignore
+  git add . && git commit -q -m "init"
+
+  git checkout -q -b other
+  echo change >> modified && git add modified
+  git rm -q deleted
+  echo new > added && git add added
+  echo new > replaces.ignored && git add -f replaces.ignored
+  git rm -q -r dir && echo "now a file" > dir && git add dir
+  git commit -q -m "changes"
+  git checkout -q @{-1}
+)
+
+git init -q modes
+(cd modes
+  echo content > file
+  git add . && git commit -q -m "init"
+  # filters must never apply to symlinks, even if they are written as files.
+  echo 'link ident' > .git/info/attributes
+
+  git checkout -q -b other
+  ln -s '$Id$' link
+  printf '#!/bin/sh\n' > script && chmod +x script
+  git add . && git commit -q -m "symlink and executable"
+  git checkout -q @{-1}
+)
+
+git init -q case
+(cd case
+  echo content > readme
+  echo content > old
+  git add . && git commit -q -m "init"
+
+  git checkout -q -b other
+  echo other > README
+  git mv old OLD
+  git add . && git commit -q -m "paths that only differ in case"
+  git checkout -q @{-1}
+)
@@ -0,0 +1,28 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q sub
+(cd sub
+  git checkout -q -b main
+  echo sub > sub-file
+  git add sub-file && git commit -q -m "sub init"
+)
+
+git init -q super
+(cd super
+  git checkout -q -b main
+  git config uploadpack.allowFilter true
+  echo a > a
+  git add a && git commit -q -m "init"
+  echo a2 > a
+  git commit -q -am "change a"
+  git tag v1
+
+  git checkout -q -b dev
+  echo d > d
+  git add d && git commit -q -m "add d"
+  git checkout -q main
+
+  git -c protocol.file.allow=always submodule add -q ../sub lib
+  git commit -q -m "add submodule"
+)
@@ -0,0 +1,30 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+cat <<EOF_ATTRS > .gitattributes
+*.ipynb diff=jupyter merge=jupyter
+*.ext diff=external merge=external conflict-marker-size=10
+*.txt diff merge
+*.unknown diff=unknown merge=unknown
+EOF_ATTRS
+
+cat <<'EOF_SCRIPT' > diff.sh
+#!/bin/sh
+echo "$1 $3 $4 $6 $7"
+cat "$2" "$5"
+EOF_SCRIPT
+cat <<'EOF_SCRIPT' > merge.sh
+#!/bin/sh
+{ cat "$1" "$2" "$3"; echo "$4 $5"; } > "$2.tmp"
+mv "$2.tmp" "$2"
+exit 1
+EOF_SCRIPT
+chmod +x diff.sh merge.sh
+
+git config diff.external.command ./diff.sh
+git config diff.jupyter.command false
+git config merge.external.driver './merge.sh %O %A %B %L %P'
+
+git add .gitattributes diff.sh merge.sh
+git commit -q -m "init"
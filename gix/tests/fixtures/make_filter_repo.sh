@@ -0,0 +1,23 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git config filter.arrow.clean "sed -e 's/^>//'"
+git config filter.arrow.smudge "sed -e 's/^/>/'"
+git config filter.broken.clean false
+git config filter.broken.smudge false
+
+cat <<EOF >.gitattributes
+*.txt filter=arrow
+*.bin filter=broken
+EOF
+echo a >a.txt
+echo b >b.bin
+git add . 2>/dev/null && git commit -q -m "init"
+
+git branch other
+git checkout -q other 2>/dev/null
+echo c >a.txt
+echo d >b.bin
+git add . 2>/dev/null && git commit -q -m "change"
+git checkout -q - 2>/dev/null
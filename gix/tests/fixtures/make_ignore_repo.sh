@@ -0,0 +1,21 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+cat <<EOF_IGNORE > .gitignore
+*.log
+!keep.log
+build/
+!special.tmp
+EOF_IGNORE
+mkdir sub
+echo '!x.log' > sub/.gitignore
+cat <<EOF_IGNORE > .git/info/exclude
+*.tmp
+!info.bak
+EOF_IGNORE
+echo '*.bak' > global-ignore
+
+echo content > tracked.log
+git add .gitignore sub/.gitignore global-ignore && git add -f tracked.log
+git commit -q -m "init"
@@ -0,0 +1,29 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+cat <<EOF_IGNORE > .gitignore
+*.log
+ignored-dir/
+EOF_IGNORE
+mkdir dir
+echo a > a
+echo b > dir/b
+echo base > conflict
+git add . && git commit -q -m "init"
+
+git checkout -q -b other
+echo theirs > conflict
+git commit -q -am "theirs"
+
+git checkout -q main
+echo ours > conflict
+git commit -q -am "ours"
+git merge -q other >/dev/null || :
+
+echo untracked > untracked
+echo untracked > dir/untracked
+echo log > dir/x.log
+mkdir ignored-dir
+touch ignored-dir/file
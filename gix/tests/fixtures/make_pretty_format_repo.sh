@@ -0,0 +1,40 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+echo content >file
+git add file
+git commit -q -m "first"
+
+cat <<EOF2 >.gitattributes
+subst.txt export-subst
+EOF2
+cat <<'EOF2' >subst.txt
+commit $Format:%H$ ($Format:%h$) on $Format:%T$
+by $Format:%an <%ae>$ at $Format:%ai$$Format:%d$
+unterminated $Format:%H
+EOF2
+git add .gitattributes subst.txt
+git commit -q -m "second" -m "with a body"
+
+git tag v1.0
+git tag -a -m "annotated" v1.1
+git branch other
+git update-ref refs/remotes/origin/main HEAD
+git update-ref refs/other/ignored HEAD
+
+mkdir baseline
+formats=(
+  "%H %h %T %t %P %p"
+  "%an <%ae> %ad|%aD|%ai|%aI|%at|%as"
+  "%cn <%ce> %cd|%cD|%ci|%cI|%ct|%cs"
+  "%s%n%b%n%B"
+  "%d|%D"
+  "%%|%x41|%q|%aX|%"
+)
+for i in "${!formats[@]}"; do
+  git log -1 --format="format:${formats[$i]}" >baseline/$i
+done
+
+git archive --format=tar main subst.txt | tar -xO >baseline/subst.txt
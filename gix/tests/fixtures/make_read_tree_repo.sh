@@ -0,0 +1,27 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+mkdir dir
+echo base > changed-by-both
+echo base > changed-by-ours
+echo base > changed-by-theirs
+echo base > conflicting
+echo base > deleted-by-theirs
+echo content > dir/file
+git add . && git commit -q -m "base"
+git branch base
+
+git checkout -q -b theirs
+echo same > changed-by-both
+echo theirs > changed-by-theirs
+echo theirs > conflicting
+git rm -q deleted-by-theirs
+echo new > added-by-theirs
+git add . && git commit -q -m "theirs"
+
+git checkout -q @{-1}
+echo same > changed-by-both
+echo ours > changed-by-ours
+echo ours > conflicting
+git add . && git commit -q -m "ours"
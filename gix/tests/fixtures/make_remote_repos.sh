@@ -173,11 +173,16 @@ git init --bare url-rewriting
 [url "https://github.com/byron/"]
   insteadOf = https://github.com/foobar/
   pushInsteadOf = ssh://example.com/
+
+[remote "push-rewrite"]
+  url = "file://host/repo"
 EOF
 
   {
     git remote get-url origin
     git remote get-url origin --push
+    git remote get-url push-rewrite
+    git remote get-url push-rewrite --push
   } > baseline.git
 )
 
@@ -187,11 +192,8 @@ git init --bare bad-url-rewriting
   git remote add origin https://github.com/foobar/gitoxide
   cat <<EOF >> config
 
-[remote "origin"]
-  pushUrl = "file://dev/null"
-
 [url "invalid:://"]
-  pushInsteadOf = "file://"
+  pushInsteadOf = "https://github.com/"
 
 [url "https://github.com/byron/"]
   insteadOf = https://github.com/foobar/
@@ -0,0 +1,29 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+echo a > a
+echo b > b
+git add . && git commit -q -m "init"
+
+git checkout -q -b topic
+echo t > t
+git add t && git commit -q -m "add t"
+echo a2 > a
+git commit -q -am "change a"
+
+git checkout -q -b applied main
+echo b2 > b
+git commit -q -am "change b to b2"
+
+git checkout -q -b conflicting main
+echo b3 > b
+git commit -q -am "change b to b3"
+
+git checkout -q -b merge main
+git merge -q --no-ff -m "merge topic" topic
+
+git checkout -q main
+echo b2 > b
+git commit -q -am "change b on main"
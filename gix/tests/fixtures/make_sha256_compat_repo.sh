@@ -0,0 +1,13 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q --object-format=sha256
+
+echo a > a
+git add . && git commit -q -m "first"
+
+# Git versions without support for it refuse to open the repository, so it's set last.
+cat >>.git/config <<EOT
+[extensions]
+	compatObjectFormat = sha1
+EOT
@@ -0,0 +1,15 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q --object-format=sha256
+
+mkdir dir
+echo a > a
+echo b > dir/b
+git add . && git commit -q -m "first"
+
+git gc -q
+
+echo c > dir/c
+git add . && git commit -q -m "second"
+git tag -a -m "annotated" v1
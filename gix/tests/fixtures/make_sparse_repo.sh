@@ -0,0 +1,10 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+mkdir -p dir1/sub dir2
+echo content > a
+echo content > dir1/file
+echo content > dir1/sub/file
+echo content > dir2/file
+git add . && git commit -q -m "init"
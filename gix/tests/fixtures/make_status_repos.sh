@@ -0,0 +1,68 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q changes
+(cd changes
+  mkdir dir untracked-dir
+  echo content > unchanged
+  echo content > modified
+  echo content > staged
+  echo content > deleted-in-index
+  echo content > deleted-in-worktree
+  echo content > dir/tracked
+  echo "*.ignored" > .gitignore
+  git add . && git commit -q -m "init"
+
+  echo change >> modified
+  echo change >> staged && git add staged
+  echo new > added && git add added
+  git rm -q --cached deleted-in-index
+  rm deleted-in-worktree
+  echo untracked > untracked
+  echo untracked > dir/untracked
+  echo untracked > untracked-dir/a
+  echo untracked > untracked-dir/b
+  echo ignored > file.ignored
+)
+
+git init -q renames
+(cd renames
+  seq 10 > exact
+  seq 20 > similar
+  seq 30 > unrelated
+  git add . && git commit -q -m "init"
+
+  git mv exact exact-renamed
+  git mv similar similar-renamed
+  echo 21 >> similar-renamed && git add similar-renamed
+  git rm -q --cached unrelated && rm unrelated
+  echo other > added && git add added
+  echo change >> exact-renamed
+)
+
+git init -q worktree-renames
+(cd worktree-renames
+  git config status.renames true
+  seq 10 > exact
+  seq 20 > similar
+  echo unrelated > unrelated
+  seq 40 > intent-to-add
+  seq 50 > modified
+  git add . && git commit -q -m "init"
+
+  mv exact exact-renamed
+  mv similar similar-renamed && echo 21 >> similar-renamed
+  mv intent-to-add intent-to-add-renamed && git add --intent-to-add intent-to-add-renamed
+  rm unrelated && echo other > untracked
+  cp modified modified-copy && echo change >> modified
+)
+
+git init -q renames-disabled
+(cd renames-disabled
+  git config status.renames false
+  git config diff.renames true
+  seq 10 > exact
+  git add . && git commit -q -m "init"
+
+  git mv exact exact-renamed
+)
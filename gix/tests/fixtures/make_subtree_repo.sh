@@ -0,0 +1,37 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+mkdir lib
+echo a > lib/a
+echo main > main
+git add . && git commit -q -m "add lib"
+echo changed > main && git commit -q -am "change main only"
+echo b > lib/b && git add lib/b && git commit -q -m "add lib/b"
+
+git checkout -q -b side
+echo c > lib/c && git add lib/c && git commit -q -m "add lib/c on side"
+git checkout -q main
+echo d > other && git add other && git commit -q -m "add other"
+git merge -q --no-ff -m "merge side" side
+
+git checkout -q --orphan upstream
+git rm -q -rf .
+echo 1 > a && echo 1 > b && git add a b && git commit -q -m "u1"
+echo 2 > a && git commit -q -am "u2"
+echo upstream > b && git commit -q -am "u3"
+
+git checkout -q main
+git merge -q -s ours --allow-unrelated-histories --no-commit upstream~2 >/dev/null 2>&1
+git read-tree --prefix=vendor/ -u upstream~2
+git commit -q -m "add vendor"
+echo local > vendor/b && git commit -q -am "change vendor/b"
+
+git checkout -q -b lib-at-root
+git rm -q -rf .
+git checkout -q main -- lib
+git mv lib/* .
+echo "changed at root" > a && git add a && git commit -q -m "move lib to the root and change a"
+git checkout -q main
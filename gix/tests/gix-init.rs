@@ -30,6 +30,7 @@ mod with_overrides {
             .set("GIT_PROTOCOL_FROM_USER", "file-allowed")
             .set("GIT_REPLACE_REF_BASE", "refs/replace-mine")
             .set("GIT_NO_REPLACE_OBJECTS", "no-replace")
+            .set("GIT_NOTES_REF", "refs/notes/mine")
             .set("GIT_COMMITTER_NAME", "committer name")
             .set("GIT_COMMITTER_EMAIL", "committer email")
             .set("GIT_COMMITTER_DATE", default_date)
@@ -220,8 +221,9 @@ mod with_overrides {
         for (key, expected) in [
             ("gitoxide.http.verbose", "true"),
             ("gitoxide.allow.protocolFromUser", "file-allowed"),
-            ("core.useReplaceRefs", "no-replace"),
+            ("core.useReplaceRefs", "false"),
             ("gitoxide.objects.replaceRefBase", "refs/replace-mine"),
+            ("core.notesRef", "refs/notes/mine"),
             ("gitoxide.committer.nameFallback", "committer name"),
             ("gitoxide.committer.emailFallback", "committer email"),
             ("gitoxide.author.nameFallback", "author name"),
@@ -4,10 +4,12 @@ use util::*;
 mod clone;
 mod commit;
 mod config;
+mod error;
 mod head;
 mod id;
 mod init;
 mod object;
+mod pool;
 mod reference;
 mod remote;
 mod repository;
@@ -55,6 +55,25 @@ fn prefix() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn prefix_is_extended_until_unambiguous() -> crate::Result {
+    let base = gix_testtools::scripted_fixture_read_only("make_rev_spec_parse_repos.sh")?;
+    let repo = gix::open(base.join("ambiguous_blob_tree_commit"))?;
+    let id = hex_to_id("0000000000e4f9fbd19cf1e932319e5ad0d1d00b").attach(&repo);
+    let prefix = id.shorten()?;
+    assert_eq!(
+        prefix.to_string(),
+        "0000000000e",
+        "a tree and a blob share the first 10 characters, so more than the default of 7 are needed"
+    );
+    assert_eq!(
+        repo.objects.lookup_prefix(prefix, None)?,
+        Some(Ok(id.detach())),
+        "the shortened id can be resolved again"
+    );
+    Ok(())
+}
+
 #[test]
 fn display_and_debug() -> crate::Result {
     let repo = crate::basic_repo()?;
@@ -114,6 +133,56 @@ mod ancestors {
         Ok(())
     }
 
+    #[test]
+    fn resumed_from_checkpoint() -> crate::Result {
+        let repo = crate::repo("make_repo_with_fork_and_dates.sh")?.to_thread_local();
+        let head = repo.head()?.into_peeled_id()?;
+        for use_commit_graph in [false, true] {
+            for sorting in [commit::Sorting::BreadthFirst, commit::Sorting::ByCommitTimeNewestFirst] {
+                let ancestors = || head.ancestors().sorting(sorting).use_commit_graph(use_commit_graph);
+                let expected = ancestors()
+                    .all()?
+                    .map(|c| c.map(|c| c.id))
+                    .collect::<Result<Vec<_>, _>>()?;
+                for split in 0..=expected.len() {
+                    let mut walk = ancestors().all()?;
+                    let mut actual = walk
+                        .by_ref()
+                        .take(split)
+                        .map(|c| c.map(|c| c.id))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    let checkpoint = walk.checkpoint();
+                    for info in ancestors().resume_from(checkpoint).all()? {
+                        actual.push(info?.id);
+                    }
+                    assert_eq!(actual, expected, "{sorting:?}: resuming after {split} commits");
+                }
+            }
+        }
+
+        let parent = repo
+            .find_object(head)?
+            .into_commit()
+            .parent_ids()
+            .next()
+            .expect("present");
+        let mut walk = parent.ancestors().all()?;
+        let seen = walk.by_ref().count();
+        let new_commits = head
+            .ancestors()
+            .resume_from(walk.checkpoint())
+            .all()?
+            .map(|c| c.map(|c| c.id))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            new_commits.len(),
+            4 - seen,
+            "only commits that weren't seen before are returned"
+        );
+        assert_eq!(new_commits[0], head);
+        Ok(())
+    }
+
     #[test]
     fn pre_epoch() -> crate::Result {
         let repo = crate::repo("make_pre_epoch_repo.sh")?.to_thread_local();
@@ -166,3 +235,48 @@ mod ancestors {
         Ok(())
     }
 }
+
+#[cfg(feature = "revision")]
+mod peel {
+    use gix::object::Kind;
+    use gix_ref::transaction::PreviousValue;
+
+    #[test]
+    fn through_tag_chains() -> crate::Result {
+        let (repo, _keep) = crate::repo_rw("make_basic_repo.sh")?;
+        let head = repo.head_id()?.detach();
+        let tree = repo.head_tree_id()?.detach();
+        let inner = repo
+            .tag("inner", head, Kind::Commit, None, "inner", PreviousValue::MustNotExist)?
+            .id()
+            .detach();
+        let outer = repo
+            .tag("outer", inner, Kind::Tag, None, "outer", PreviousValue::MustNotExist)?
+            .id();
+
+        assert_eq!(outer.peel_to_commit()?.id, head, "tags are followed to their end");
+        assert_eq!(outer.peel_to_tree()?.id, tree, "commits are followed to their tree");
+        let object = outer.peel_tags_to_end()?;
+        assert_eq!((object.id, object.kind), (head, Kind::Commit), "commits aren't peeled");
+        assert_eq!(
+            outer.peel_to_kind(Kind::Tag)?.id,
+            outer.detach(),
+            "objects of the desired kind are returned as is"
+        );
+
+        let blob = repo.rev_parse_single("HEAD:this")?;
+        assert_eq!(blob.peel_tags_to_end()?.id, blob.detach());
+        assert!(
+            matches!(
+                blob.peel_to_commit(),
+                Err(gix::object::peel::to_kind::Error::NotFound {
+                    actual: Kind::Blob,
+                    expected: Kind::Commit,
+                    ..
+                })
+            ),
+            "blobs can't be peeled any further"
+        );
+        Ok(())
+    }
+}
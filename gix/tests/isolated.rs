@@ -0,0 +1,25 @@
+//! Tests that change the current working directory of the process, which is why they must not run alongside other tests.
+use serial_test::serial;
+
+#[test]
+#[serial]
+fn prefix_relative_path_within_worktree() -> gix_testtools::Result {
+    let work_dir = std::env::current_dir()?.join(gix_testtools::scripted_fixture_read_only("make_sparse_repo.sh")?);
+    let _keep = gix_testtools::set_current_dir(work_dir.join("dir1"))?;
+    let repo = gix::discover(".")?;
+    assert_eq!(repo.prefix()?, Some(std::path::Path::new("dir1")));
+
+    let rela_path = repo.repo_relative_path("sub/file")?;
+    assert_eq!(rela_path, "dir1/sub/file");
+    assert_eq!(repo.prefix_relative_path(&rela_path)?, Some("sub/file".into()));
+    assert_eq!(repo.prefix_relative_path("dir1")?, Some(".".into()));
+    assert_eq!(repo.repo_relative_path(".")?, "dir1");
+
+    let toplevel = repo.repo_relative_path("../dir2/file")?;
+    assert_eq!(toplevel, "dir2/file");
+    assert_eq!(
+        repo.prefix_relative_path(&toplevel)?,
+        Some(std::path::Path::new("..").join("dir2").join("file"))
+    );
+    Ok(())
+}
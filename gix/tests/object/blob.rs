@@ -1,2 +1,140 @@
 // TODO: needs repos with specific known objects for proper testing
 mod diff {}
+
+mod lfs {
+    use gix::{bstr::BString, lfs::Pointer};
+
+    const OID: &str = "4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393";
+
+    fn pointer_bytes(size: u64) -> Vec<u8> {
+        format!("version https://git-lfs.github.com/spec/v1\noid sha256:{OID}\nsize {size}\n").into_bytes()
+    }
+
+    #[test]
+    fn pointer_parsing_and_round_trip() {
+        let pointer = Pointer::from_bytes(&pointer_bytes(12345)).expect("valid");
+        assert_eq!(pointer.oid, OID);
+        assert_eq!(pointer.size, 12345);
+        assert!(pointer.extra.is_empty());
+        assert_eq!(pointer.to_bytes(), pointer_bytes(12345));
+
+        let with_extension =
+            format!("version https://hawser.github.com/spec/v1\next-0-foo sha256:{OID}\noid sha256:{OID}\nsize 5\n");
+        let pointer = Pointer::from_bytes(with_extension.as_bytes()).expect("legacy version and extensions are fine");
+        assert_eq!(
+            pointer.extra,
+            vec![(BString::from("ext-0-foo"), BString::from(format!("sha256:{OID}")))]
+        );
+        assert_eq!(
+            Pointer::from_bytes(&pointer.to_bytes()),
+            Some(pointer),
+            "serialization always uses the current version"
+        );
+    }
+
+    #[test]
+    fn invalid_pointers_are_rejected() {
+        for input in [
+            String::new(),
+            "hello world\n".into(),
+            format!("version https://git-lfs.github.com/spec/v1\noid sha256:{OID}\nsize 5"),
+            format!("version https://git-lfs.github.com/spec/v2\noid sha256:{OID}\nsize 5\n"),
+            format!("version https://git-lfs.github.com/spec/v1\nsize 5\noid sha256:{OID}\n"),
+            format!("version https://git-lfs.github.com/spec/v1\noid sha256:{OID}\n"),
+            format!("version https://git-lfs.github.com/spec/v1\noid sha256:{OID}\nsize -5\n"),
+            format!("version https://git-lfs.github.com/spec/v1\noid sha1:{OID}\nsize 5\n"),
+            format!(
+                "version https://git-lfs.github.com/spec/v1\noid sha256:{}\nsize 5\n",
+                OID.to_uppercase()
+            ),
+            format!("version https://git-lfs.github.com/spec/v1\noid sha256:{OID}\nsize 5\nsize 6\n"),
+            format!("oid sha256:{OID}\nsize 5\n"),
+        ] {
+            assert_eq!(Pointer::from_bytes(input.as_bytes()), None, "{input:?}");
+        }
+        let mut too_large = pointer_bytes(5);
+        too_large.extend(std::iter::repeat(b'x').take(1024));
+        assert_eq!(Pointer::from_bytes(&too_large), None);
+    }
+
+    #[test]
+    fn blobs_resolve_through_a_store() -> crate::Result {
+        let tmp = gix_testtools::tempfile::tempdir()?;
+        let repo = gix::init_bare(tmp.path())?;
+        let content = b"large content";
+        let id = repo.write_blob(pointer_bytes(content.len() as u64))?;
+
+        let mut blob = id.object()?.into_blob();
+        assert_eq!(blob.lfs_pointer().map(|p| p.oid), Some(OID.to_owned()));
+
+        let store = repo.lfs_store();
+        assert!(
+            !blob.resolve_lfs_pointer(&store)?,
+            "content that isn't present leaves the pointer untouched"
+        );
+        assert_eq!(blob.data, pointer_bytes(content.len() as u64));
+
+        let pointer = blob.lfs_pointer().expect("still a pointer");
+        let path = store.path(&pointer);
+        assert_eq!(
+            path,
+            repo.common_dir().join("lfs/objects/4d/7a").join(OID),
+            "git lfs uses a two-level fan-out"
+        );
+        std::fs::create_dir_all(path.parent().expect("parent"))?;
+        std::fs::write(&path, content)?;
+        assert!(blob.resolve_lfs_pointer(&store)?);
+        assert_eq!(blob.data, content);
+        assert_eq!(blob.id, id, "the id still refers to the pointer");
+        assert!(
+            !blob.resolve_lfs_pointer(&store)?,
+            "resolved content isn't a pointer anymore"
+        );
+
+        std::fs::write(&path, b"truncated")?;
+        let mut blob = id.object()?.into_blob();
+        assert!(blob.resolve_lfs_pointer(&store).is_err(), "a size mismatch is an error");
+        Ok(())
+    }
+
+    #[test]
+    fn custom_stores_can_be_used() -> crate::Result {
+        struct InMemory;
+        impl gix::lfs::Store for InMemory {
+            fn find(&self, pointer: &Pointer) -> Result<Option<Vec<u8>>, gix::lfs::find::Error> {
+                Ok((pointer.oid == OID).then(|| b"from memory".to_vec()))
+            }
+        }
+        let tmp = gix_testtools::tempfile::tempdir()?;
+        let repo = gix::init_bare(tmp.path())?;
+        let mut blob = repo.write_blob(pointer_bytes(11))?.object()?.into_blob();
+        assert!(blob.resolve_lfs_pointer(&InMemory)?);
+        assert_eq!(blob.data, b"from memory");
+
+        let mut blob = repo.write_blob(b"no pointer")?.object()?.into_blob();
+        assert!(!blob.resolve_lfs_pointer(&InMemory)?);
+        assert_eq!(blob.data, b"no pointer");
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "blob-diff")]
+    fn blob_diffs_can_use_resolved_content() -> crate::Result {
+        struct InMemory;
+        impl gix::lfs::Store for InMemory {
+            fn find(&self, pointer: &Pointer) -> Result<Option<Vec<u8>>, gix::lfs::find::Error> {
+                Ok((pointer.oid == OID).then(|| b"a\nb\nc\n".to_vec()))
+            }
+        }
+        let tmp = gix_testtools::tempfile::tempdir()?;
+        let repo = gix::init_bare(tmp.path())?;
+        let old = repo.write_blob(b"a\n")?;
+        let new = repo.write_blob(pointer_bytes(6))?;
+
+        let mut platform = gix::object::blob::diff::Platform::from_ids(&old, &new)?;
+        assert!(platform.resolve_lfs_pointers(&InMemory)?);
+        let counts = platform.line_counts();
+        assert_eq!((counts.removals, counts.insertions), (0, 2));
+        Ok(())
+    }
+}
@@ -43,3 +43,46 @@ fn decode() -> crate::Result {
     assert_eq!(commit.decode()?.message, "c2\n");
     Ok(())
 }
+
+mod pretty_format {
+    use gix::bstr::ByteSlice;
+
+    #[test]
+    fn placeholders_are_expanded_like_git_does() -> crate::Result {
+        let repo = crate::named_repo("make_pretty_format_repo.sh")?;
+        let commit = repo.head_commit()?;
+        let formats = [
+            "%H %h %T %t %P %p",
+            "%an <%ae> %ad|%aD|%ai|%aI|%at|%as",
+            "%cn <%ce> %cd|%cD|%ci|%cI|%ct|%cs",
+            "%s%n%b%n%B",
+            "%d|%D",
+            "%%|%x41|%q|%aX|%",
+        ];
+        let baseline_dir = repo.work_dir().expect("non-bare").join("baseline");
+        for (index, format) in formats.iter().enumerate() {
+            let expected = std::fs::read(baseline_dir.join(index.to_string()))?;
+            assert_eq!(
+                commit.pretty_format(format.as_bytes().as_bstr())?,
+                expected.as_bstr(),
+                "{format}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn context_can_be_used_without_repository() -> crate::Result {
+        let (context, short_id) = {
+            let repo = crate::named_repo("make_pretty_format_repo.sh")?;
+            let commit = repo.head_commit()?;
+            (commit.pretty_format_context()?, commit.short_id()?.to_string())
+        };
+        assert_eq!(
+            context.format("%h: %s".into()),
+            format!("{short_id}: second"),
+            "all information is captured in the context"
+        );
+        Ok(())
+    }
+}
@@ -2,7 +2,10 @@ use std::convert::Infallible;
 
 use gix::{
     bstr::BString,
-    object::{blob::diff::line::Change, tree::diff::change::Event},
+    object::{
+        blob::diff::line::Change,
+        tree::diff::{change::Event, Action},
+    },
 };
 use gix_object::{bstr::ByteSlice, tree::EntryMode};
 
@@ -100,6 +103,57 @@ fn tree_named(repo: &gix::Repository, rev_spec: impl AsRef<str>) -> gix::Tree {
         .into_tree()
 }
 
+#[test]
+fn walk_with_diffs_matches_diffs_of_each_commit_with_its_first_parent() -> crate::Result {
+    let repo = named_repo("make_diff_repo.sh")?;
+    let head = repo.head_id()?;
+    let mut expected = Vec::new();
+    for info in head.ancestors().all()? {
+        let commit = info?.object()?;
+        let parent_tree = match commit.parent_ids().next() {
+            Some(id) => id.object()?.into_commit().tree()?,
+            None => repo.empty_tree(),
+        };
+        parent_tree
+            .changes()?
+            .track_path()
+            .for_each_to_obtain_tree(&commit.tree()?, |change| {
+                expected.push((commit.id, change.location.to_owned(), format!("{:?}", change.event)));
+                Ok::<_, Infallible>(Action::Continue)
+            })?;
+    }
+
+    let mut actual = Vec::new();
+    head.ancestors().walk_with_diffs(|info, change| {
+        actual.push((info.id, change.location.to_owned(), format!("{:?}", change.event)));
+        Ok::<_, Infallible>(Action::Continue)
+    })?;
+    assert!(
+        expected.len() > 10,
+        "there are enough changes to make the comparison meaningful"
+    );
+    assert_eq!(actual, expected);
+
+    let mut by_time = Vec::new();
+    head.ancestors()
+        .sorting(gix::traverse::commit::Sorting::ByCommitTimeNewestFirst)
+        .walk_with_diffs(|info, change| {
+            by_time.push((info.id, change.location.to_owned(), format!("{:?}", change.event)));
+            Ok::<_, Infallible>(Action::Continue)
+        })?;
+    by_time.sort();
+    expected.sort();
+    assert_eq!(by_time, expected, "trees of parents are kept for as long as needed");
+
+    let mut count = 0;
+    head.ancestors().walk_with_diffs(|_info, _change| {
+        count += 1;
+        Ok::<_, Infallible>(if count == 3 { Action::Cancel } else { Action::Continue })
+    })?;
+    assert_eq!(count, 3, "cancelling stops the traversal");
+    Ok(())
+}
+
 mod track_rewrites {
     use std::convert::Infallible;
 
@@ -1123,6 +1177,134 @@ rename to gix-sec/tests/sec.rs
 
         Ok(())
     }
+
+    #[test]
+    fn parse_score() {
+        use gix::object::tree::diff::rewrites::parse_score;
+        for (input, expected) in [
+            ("5", Some(0.5)),
+            ("50", Some(0.5)),
+            ("05", Some(0.05)),
+            ("0.5", Some(0.5)),
+            (".5", Some(0.5)),
+            ("50%", Some(0.5)),
+            ("12.5%", Some(0.125)),
+            ("100%", Some(1.0)),
+            ("150%", Some(1.0)),
+            ("", None),
+            ("5x", None),
+            ("%5", None),
+            ("5%%", None),
+        ] {
+            assert_eq!(parse_score(input.as_bytes()), expected, "{input:?}");
+        }
+    }
+
+    mod breaks {
+        use std::convert::Infallible;
+
+        use gix::{
+            bstr::BString,
+            object::tree::diff::{rewrites::Breaks, Rewrites},
+        };
+        use gix_object::tree::EntryMode;
+
+        use crate::object::tree::diff::{added, deleted, modified, store};
+
+        #[test]
+        fn complete_rewrites_are_split_unless_too_much_remains() -> crate::Result {
+            let tmp = gix_testtools::tempfile::tempdir()?;
+            let repo = gix::init_bare(tmp.path())?;
+            let from = tree_with(&repo, &[("a", &lines("a", 50))])?;
+            let to = tree_with(&repo, &[("a", &lines("c", 50))])?;
+
+            assert_eq!(changes(&from, &to, None)?, vec![modified("a")]);
+            assert_eq!(
+                changes(&from, &to, Some(Breaks::default()))?,
+                vec![deleted("a"), added("a")],
+                "all content was replaced"
+            );
+            assert_eq!(
+                changes(
+                    &from,
+                    &to,
+                    Some(Breaks {
+                        split_percentage: 1.1,
+                        ..Default::default()
+                    })
+                )?,
+                vec![modified("a")],
+                "broken pairs that aren't used for renames are merged back if not enough was removed"
+            );
+
+            let small = tree_with(&repo, &[("a", "a\n")])?;
+            let small_rewritten = tree_with(&repo, &[("a", "c\n")])?;
+            assert_eq!(
+                changes(&small, &small_rewritten, Some(Breaks::default()))?,
+                vec![modified("a")],
+                "small files aren't broken"
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn broken_modifications_can_be_rename_destinations() -> crate::Result {
+            let tmp = gix_testtools::tempfile::tempdir()?;
+            let repo = gix::init_bare(tmp.path())?;
+            let b = lines("b", 50);
+            let from = tree_with(&repo, &[("a", &lines("a", 50)), ("b", &b)])?;
+            let to = tree_with(&repo, &[("a", &b)])?;
+
+            assert_eq!(changes(&from, &to, None)?, vec![modified("a"), deleted("b")]);
+            assert_eq!(
+                changes(&from, &to, Some(Breaks::default()))?,
+                vec![('R', "a".into()), deleted("a")],
+                "'b' was moved to 'a', whose previous content was deleted"
+            );
+            Ok(())
+        }
+
+        fn lines(prefix: &str, count: usize) -> String {
+            (0..count).map(|n| format!("{prefix} line {n}\n")).collect()
+        }
+
+        fn tree_with<'repo>(repo: &'repo gix::Repository, files: &[(&str, &str)]) -> crate::Result<gix::Tree<'repo>> {
+            let mut entries = files
+                .iter()
+                .map(|(name, content)| {
+                    Ok(gix::objs::tree::Entry {
+                        mode: EntryMode::Blob,
+                        filename: (*name).into(),
+                        oid: repo.write_blob(content)?.detach(),
+                    })
+                })
+                .collect::<crate::Result<Vec<_>>>()?;
+            entries.sort();
+            Ok(repo.write_object(&gix::objs::Tree { entries })?.object()?.into_tree())
+        }
+
+        fn changes(
+            from: &gix::Tree<'_>,
+            to: &gix::Tree<'_>,
+            breaks: Option<Breaks>,
+        ) -> crate::Result<Vec<(char, BString)>> {
+            let mut out = Vec::new();
+            from.changes()?
+                .track_path()
+                .track_rewrites(
+                    Rewrites {
+                        breaks,
+                        ..Default::default()
+                    }
+                    .into(),
+                )
+                .for_each_to_obtain_tree(to, |change| {
+                    out.push(store(&change));
+                    Ok::<_, Infallible>(Default::default())
+                })?;
+            Ok(out)
+        }
+    }
 }
 fn store(change: &gix::object::tree::diff::Change<'_, '_, '_>) -> (char, BString) {
     (shorthand(&change.event), change.location.to_owned())
@@ -25,3 +25,34 @@ fn lookup_entry_by_path() -> crate::Result {
     );
     Ok(())
 }
+
+#[test]
+fn edit() -> crate::Result {
+    let (repo, _keep) = crate::repo_rw("make_basic_repo.sh")?;
+    let tree = repo.head_commit()?.tree()?;
+    let blob = repo.write_blob("content")?.detach();
+
+    let mut editor = tree.edit()?;
+    editor
+        .upsert("new/dir/file", gix::object::tree::EntryMode::Blob, blob)?
+        .remove("this")?;
+    let new_tree = editor.write()?.object()?.into_tree();
+    assert!(new_tree.find_entry("this").is_none(), "the entry was removed");
+    assert_eq!(
+        new_tree
+            .lookup_entry_by_path("new/dir/file", &mut Vec::new())?
+            .expect("present")
+            .object_id(),
+        blob,
+        "intermediate trees were created"
+    );
+
+    let mut editor = repo.edit_tree(new_tree.id)?;
+    editor.remove("new/dir/file")?;
+    assert_eq!(
+        editor.write()?,
+        repo.empty_tree().id(),
+        "trees that become empty are removed, and 'this' was the only other entry"
+    );
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use gix::pool::{Options, Pool};
+
+fn options() -> Options {
+    Options {
+        open: crate::restricted(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn repositories_are_shared_and_least_recently_used_ones_are_evicted() -> crate::Result {
+    let base = gix_testtools::scripted_fixture_read_only("make_checkout_repos.sh")?;
+    let pool = Pool::new(Options {
+        max_repositories: Some(2),
+        ..options()
+    });
+    assert!(pool.is_empty());
+
+    let plan = pool.get(base.join("plan"))?;
+    assert!(
+        std::ptr::eq(&*plan.objects, &*pool.get(base.join("plan"))?.objects),
+        "the same instance is returned for the same path"
+    );
+    pool.get(base.join("modes"))?;
+    pool.get(base.join("plan"))?;
+    pool.get(base.join("case"))?;
+    assert_eq!(pool.len(), 2);
+    assert!(
+        !pool.contains(base.join("modes")),
+        "the least recently used repository was evicted"
+    );
+    assert!(pool.contains(base.join("plan")) && pool.contains(base.join("case")));
+
+    assert!(pool.remove(base.join("plan")).is_some());
+    assert!(pool.remove(base.join("plan")).is_none());
+    pool.clear();
+    assert!(pool.is_empty());
+    Ok(())
+}
+
+#[test]
+fn repositories_with_open_packs_are_evicted_to_respect_the_limit() -> crate::Result {
+    let packed = gix_testtools::scripted_fixture_read_only("make_packed_and_loose.sh")?;
+    let base = gix_testtools::scripted_fixture_read_only("make_checkout_repos.sh")?;
+    let pool = Pool::new(Options {
+        max_open_packs: Some(0),
+        ..options()
+    });
+
+    let repo = pool.get(&packed)?.to_thread_local();
+    repo.head_commit()?.parent_ids().next().expect("parent").object()?;
+    assert_eq!(pool.open_packs(), 1, "the packed commit was read");
+    assert!(
+        pool.contains(&packed),
+        "the limit is only enforced when repositories are obtained"
+    );
+
+    pool.get(base.join("plan"))?;
+    assert!(!pool.contains(&packed), "the repository with the open pack was evicted");
+    assert_eq!(pool.open_packs(), 0);
+    Ok(())
+}
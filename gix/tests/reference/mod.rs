@@ -104,3 +104,62 @@ fn set_target_id() {
 }
 
 mod remote;
+
+mod watch {
+    use std::time::Duration;
+
+    use gix::reference::watch::{Change, Options};
+    use gix_ref::transaction::PreviousValue;
+
+    fn changes_are_observed(options: Options) -> crate::Result {
+        let (repo, _tmp) = crate::repo_rw("make_references_repo.sh")?;
+        let mut watcher = repo.watch_references(options)?;
+        if !options.use_filesystem_events {
+            assert!(!watcher.uses_filesystem_events());
+        }
+        assert!(watcher.changes()?.is_empty(), "nothing changed yet");
+        let timeout = Some(Duration::from_secs(10));
+
+        let head_id = repo.head_id()?.detach();
+        repo.reference("refs/heads/new", head_id, PreviousValue::MustNotExist, "create")?;
+        assert_eq!(watcher.wait(timeout)?, [Change::Added("refs/heads/new".try_into()?)]);
+
+        let tag_id = repo
+            .find_reference("refs/tags/dt1")?
+            .target()
+            .try_id()
+            .expect("peeled")
+            .to_owned();
+        repo.reference("refs/heads/new", tag_id, PreviousValue::Any, "update")?;
+        assert_eq!(watcher.wait(timeout)?, [Change::Modified("refs/heads/new".try_into()?)]);
+
+        repo.find_reference("refs/heads/new")?.delete()?;
+        assert_eq!(watcher.wait(timeout)?, [Change::Removed("refs/heads/new".try_into()?)]);
+
+        std::fs::remove_file(repo.common_dir().join("packed-refs"))?;
+        assert_eq!(watcher.wait(timeout)?, [Change::PackedRefs]);
+
+        assert_eq!(
+            watcher.wait(Some(Duration::from_millis(50)))?,
+            [],
+            "without changes, nothing is returned once the timeout passed"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn polling() -> crate::Result {
+        changes_are_observed(Options {
+            poll_interval: Duration::from_millis(10),
+            use_filesystem_events: false,
+        })
+    }
+
+    #[test]
+    fn with_default_options() -> crate::Result {
+        changes_are_observed(Options {
+            poll_interval: Duration::from_millis(10),
+            ..Default::default()
+        })
+    }
+}
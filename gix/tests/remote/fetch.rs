@@ -157,6 +157,105 @@ mod blocking_and_async_io {
         Ok(())
     }
 
+    #[test]
+    #[cfg(feature = "blocking-network-client")]
+    fn fetch_below_unpack_limit_writes_loose_objects() -> crate::Result<()> {
+        for (limit, expect_unpacked) in [("1000", true), ("66", false)] {
+            let tmp = gix_testtools::tempfile::TempDir::new()?;
+            let (repo, out) = gix::clone::PrepareFetch::new(
+                remote::repo("multi_round/server").path(),
+                tmp.path(),
+                gix::create::Kind::Bare,
+                Default::default(),
+                gix::open::Options::isolated().config_overrides([format!("transfer.unpackLimit={limit}")]),
+            )?
+            .fetch_only(gix::progress::Discard, &AtomicBool::default())?;
+
+            let num_loose = gix::odb::loose::Store::at(repo.objects.store_ref().path(), repo.object_hash())
+                .iter()
+                .count();
+            match out.status {
+                Status::Unpacked {
+                    unpack, update_refs, ..
+                } => {
+                    assert!(expect_unpacked);
+                    assert_eq!(unpack.ids.len(), 66, "all objects of the pack were written");
+                    assert_eq!(num_loose, 66);
+                    assert!(!update_refs.edits.is_empty());
+                }
+                Status::Change { write_pack_bundle, .. } => {
+                    assert!(!expect_unpacked, "the limit must be exceeded to keep the pack");
+                    assert_eq!(write_pack_bundle.index.num_objects, 66);
+                    assert_eq!(num_loose, 0);
+                }
+                Status::NoPackReceived { .. } => unreachable!("the repository is empty"),
+            }
+            assert!(repo.head_commit().is_ok(), "references point to the received objects");
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(
+        feature = "blocking-network-client",
+        feature = "status",
+        feature = "worktree-mutation"
+    ))]
+    fn fetch_recurses_into_populated_submodules_if_configured() -> crate::Result {
+        use gix::submodule::config::FetchRecurse;
+
+        let tmp = gix_testtools::scripted_fixture_writable("make_submodules.sh")?;
+        let open = |overrides: &[&str]| {
+            gix::open_opts(
+                tmp.path().join("with-submodules-after-clone"),
+                crate::restricted().config_overrides(overrides.iter().copied()),
+            )
+        };
+        let fetch = |repo: &gix::Repository, mode: Option<FetchRecurse>| -> crate::Result<fetch::Outcome> {
+            Ok(repo
+                .find_remote("origin")?
+                .connect(Fetch)?
+                .prepare_fetch(progress::Discard, Default::default())?
+                .with_recurse_submodules(mode)
+                .receive(progress::Discard, &AtomicBool::default())?)
+        };
+
+        let repo = open(&[])?;
+        let sm = repo.submodules()?.into_iter().flatten().next().expect("one submodule");
+        sm.perform_update(progress::Discard, &AtomicBool::default())?;
+
+        assert!(
+            fetch(&repo, None)?.submodules.is_empty(),
+            "submodules aren't fetched if not configured"
+        );
+        assert!(
+            fetch(&repo, Some(FetchRecurse::OnDemand))?.submodules.is_empty(),
+            "the submodule has all commits recorded by the superproject"
+        );
+        let outcome = fetch(&repo, Some(FetchRecurse::Always))?;
+        assert_eq!(outcome.submodules.len(), 1);
+        let sm_outcome = &outcome.submodules[0];
+        assert_eq!(sm_outcome.name, "m1");
+        assert_eq!(sm_outcome.rela_path, "m1");
+        assert!(
+            matches!(sm_outcome.outcome.status, Status::NoPackReceived { .. }),
+            "the submodule is up to date"
+        );
+        assert!(sm_outcome.outcome.submodules.is_empty());
+
+        let repo = open(&["submodule.recurse=true"])?;
+        assert_eq!(
+            fetch(&repo, None)?.submodules.len(),
+            1,
+            "`submodule.recurse` makes fetches recurse into all submodules"
+        );
+        assert!(
+            fetch(&repo, Some(FetchRecurse::Never))?.submodules.is_empty(),
+            "the mode overrides the configuration"
+        );
+        Ok(())
+    }
+
     #[maybe_async::test(
         feature = "blocking-network-client",
         async(feature = "async-network-client-async-std", async_std::test)
@@ -596,6 +695,7 @@ mod blocking_and_async_io {
                         );
                         update_refs
                     }
+                    fetch::Status::Unpacked { .. } => unreachable!("packs are kept by default"),
                 };
 
                 assert_eq!(
@@ -0,0 +1,65 @@
+use gix::{bstr::BStr, cat_file};
+
+#[test]
+fn header_and_contents() -> crate::Result {
+    let repo = crate::basic_repo()?;
+    let head = repo.head_id()?.detach();
+    let blob = repo.rev_parse_single("HEAD:this")?.detach();
+    let mut cat = repo.cat_file();
+
+    let header = cat.header("HEAD")?.expect("present");
+    assert_eq!(header.id, head, "revisions are resolved");
+    assert_eq!(header.kind, gix_object::Kind::Commit);
+    assert_eq!(
+        cat.header(head.to_string().as_str())?,
+        Some(header),
+        "full ids are used as is"
+    );
+
+    let contents = cat.contents("HEAD:this")?.expect("present");
+    assert_eq!(contents.id, blob);
+    assert_eq!(contents.kind, gix_object::Kind::Blob);
+    assert_eq!(contents.data, b"hello\n");
+    assert_eq!(cat.header("HEAD:this")?.expect("present").size, 6);
+
+    let empty_tree = gix_hash::ObjectId::empty_tree(repo.object_hash()).to_string();
+    assert_eq!(
+        cat.contents(empty_tree.as_str())?.expect("always present").data,
+        b"",
+        "the empty tree is always available"
+    );
+
+    assert_eq!(cat.header("HEAD:missing")?, None, "unresolvable inputs are missing");
+    assert_eq!(
+        cat.contents(gix_hash::Kind::Sha1.null().to_string().as_str())?,
+        None,
+        "ids of objects that don't exist are missing"
+    );
+    Ok(())
+}
+
+#[test]
+fn write_batch() -> crate::Result {
+    let repo = crate::basic_repo()?;
+    let blob = repo.rev_parse_single("HEAD:this")?.detach();
+    let inputs: [&BStr; 3] = ["HEAD:this".into(), "does-not-exist".into(), "HEAD:this".into()];
+
+    let mut out = Vec::new();
+    repo.cat_file()
+        .write_batch(inputs.iter().copied(), cat_file::Mode::Header, &mut out)?;
+    assert_eq!(
+        out,
+        format!("{blob} blob 6\ndoes-not-exist missing\n{blob} blob 6\n").as_bytes(),
+        "the output is in input order, like `git cat-file --batch-check`"
+    );
+
+    out.clear();
+    repo.cat_file()
+        .write_batch(inputs.iter().copied(), cat_file::Mode::Contents, &mut out)?;
+    assert_eq!(
+        out,
+        format!("{blob} blob 6\nhello\n\ndoes-not-exist missing\n{blob} blob 6\nhello\n\n").as_bytes(),
+        "the data follows the header and a newline, like `git cat-file --batch`"
+    );
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use gix::check_attr::Source;
+
+fn repo() -> crate::Result<gix::Repository> {
+    let mut repo = crate::named_repo("make_attributes_repo.sh")?;
+    let global_attributes = repo.work_dir().expect("present").join("global-attributes");
+    repo.config_snapshot_mut().set_raw_value(
+        "core",
+        None,
+        "attributesFile",
+        gix::path::into_bstr(global_attributes).as_ref(),
+    )?;
+    Ok(repo)
+}
+
+fn sorted(assignments: Vec<gix::attrs::Assignment>) -> Vec<String> {
+    let mut out: Vec<_> = assignments.iter().map(|a| a.as_ref().to_string()).collect();
+    out.sort();
+    out
+}
+
+#[test]
+fn all_attributes_respect_precedence_and_expand_macros() -> crate::Result {
+    let repo = repo()?;
+    let mut platform = repo.check_attr(Source::default())?;
+
+    for (path, expected) in [
+        ("a.txt", &["eol=lf", "global", "staged", "text", "worktree"][..]),
+        ("sub/a.txt", &["-text", "eol=crlf", "global", "staged", "worktree"]),
+        ("a.bin", &["-diff", "-merge", "-text", "binary"]),
+        ("lib.rs", &["diff=info"]),
+        ("README.md", &["custom", "diff=custom", "text"]),
+        ("other", &[]),
+    ] {
+        assert_eq!(sorted(platform.all(path, None)?), expected, "{path}");
+    }
+    Ok(())
+}
+
+#[test]
+fn selected_attributes_are_returned_in_order_even_if_unspecified() -> crate::Result {
+    let repo = repo()?;
+    let mut platform = repo.check_attr(Source::default())?;
+    let actual: Vec<_> = platform
+        .selected("sub/a.txt", None, ["eol", "missing", "text"])?
+        .iter()
+        .map(|a| a.as_ref().to_string())
+        .collect();
+    assert_eq!(actual, ["eol=crlf", "!missing", "-text"]);
+    Ok(())
+}
+
+#[test]
+fn gitattributes_can_be_read_from_the_index_or_a_tree() -> crate::Result {
+    let repo = repo()?;
+    let mut platform = repo.check_attr(Source::Index)?;
+    assert_eq!(
+        sorted(platform.all("a.txt", None)?),
+        ["eol=lf", "global", "staged", "text"],
+        "only the staged version of the file is used"
+    );
+
+    let tree = repo.head_commit()?.tree_id()?.detach();
+    let mut platform = repo.check_attr(Source::Tree(tree))?;
+    assert_eq!(sorted(platform.all("a.txt", None)?), ["eol=lf", "global", "text"]);
+    Ok(())
+}
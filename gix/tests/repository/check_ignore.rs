@@ -0,0 +1,94 @@
+use std::path::PathBuf;
+
+fn repo() -> crate::Result<gix::Repository> {
+    let mut repo = crate::named_repo("make_ignore_repo.sh")?;
+    let global_ignore = repo.work_dir().expect("present").join("global-ignore");
+    repo.config_snapshot_mut().set_raw_value(
+        "core",
+        None,
+        "excludesFile",
+        gix::path::into_bstr(global_ignore).as_ref(),
+    )?;
+    Ok(repo)
+}
+
+fn pattern_and_location(
+    platform: &mut gix::check_ignore::Platform<'_>,
+    path: &str,
+    is_dir: Option<bool>,
+) -> crate::Result<Option<(String, PathBuf, usize)>> {
+    Ok(platform.matching_pattern(path, is_dir)?.map(|m| {
+        (
+            m.pattern.to_string(),
+            m.source.expect("all patterns are read from files"),
+            m.line_number,
+        )
+    }))
+}
+
+#[test]
+fn patterns_are_matched_in_order_of_precedence() -> crate::Result {
+    let repo = repo()?;
+    let work_dir = repo.work_dir().expect("present").to_owned();
+    let mut platform = repo.check_ignore()?;
+
+    for (path, is_dir, expected) in [
+        ("a.log", None, Some(("*.log", work_dir.join(".gitignore"), 1))),
+        ("keep.log", None, Some(("!keep.log", work_dir.join(".gitignore"), 2))),
+        ("build", Some(true), Some(("build/", work_dir.join(".gitignore"), 3))),
+        ("build/", None, Some(("build/", work_dir.join(".gitignore"), 3))),
+        ("build", Some(false), None),
+        ("sub/x.log", None, Some(("!x.log", work_dir.join("sub/.gitignore"), 1))),
+        ("sub/y.log", None, Some(("*.log", work_dir.join(".gitignore"), 1))),
+        ("x.tmp", None, Some(("*.tmp", repo.git_dir().join("info/exclude"), 1))),
+        (
+            "special.tmp",
+            None,
+            Some(("!special.tmp", work_dir.join(".gitignore"), 4)),
+        ),
+        ("file.bak", None, Some(("*.bak", work_dir.join("global-ignore"), 1))),
+        (
+            "info.bak",
+            None,
+            Some(("!info.bak", repo.git_dir().join("info/exclude"), 2)),
+        ),
+        ("a.txt", None, None),
+    ] {
+        assert_eq!(
+            pattern_and_location(&mut platform, path, is_dir)?,
+            expected.map(|(pattern, source, line)| (pattern.to_owned(), source, line)),
+            "{path}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn is_ignored_respects_negations_and_tracked_files() -> crate::Result {
+    let repo = repo()?;
+    let mut platform = repo.check_ignore()?;
+
+    for (path, expected) in [
+        ("a.log", true),
+        ("keep.log", false),
+        ("build/", true),
+        ("sub/x.log", false),
+        ("x.tmp", true),
+        ("special.tmp", false),
+        ("file.bak", true),
+        ("info.bak", false),
+        ("a.txt", false),
+    ] {
+        assert_eq!(platform.is_ignored(path, None)?, expected, "{path}");
+    }
+
+    assert!(
+        platform.matching_pattern("tracked.log", None)?.is_some(),
+        "tracked files still match patterns"
+    );
+    assert!(
+        !platform.is_ignored("tracked.log", None)?,
+        "but like in `git`, tracked files are never ignored"
+    );
+    Ok(())
+}
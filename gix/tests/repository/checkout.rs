@@ -0,0 +1,320 @@
+use gix::checkout::{plan, ConflictReason, Operation};
+
+fn other_tree(repo: &gix::Repository) -> crate::Result<gix::ObjectId> {
+    Ok(repo.rev_parse_single("other^{tree}")?.detach())
+}
+
+fn operations(plan: &gix::checkout::Plan) -> Vec<(&'static str, String)> {
+    plan.operations
+        .iter()
+        .map(|op| {
+            let kind = match op {
+                Operation::Create { .. } => "create",
+                Operation::Overwrite { .. } => "overwrite",
+                Operation::Delete { .. } => "delete",
+            };
+            (kind, op.rela_path().to_string())
+        })
+        .collect()
+}
+
+fn conflicts(plan: &gix::checkout::Plan) -> Vec<(ConflictReason, String)> {
+    plan.conflicts
+        .iter()
+        .map(|c| (c.reason, c.rela_path.to_string()))
+        .collect()
+}
+
+#[test]
+fn only_changed_paths_are_affected_in_a_clean_worktree() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_checkout_repos.sh", "plan")?;
+    let plan = repo.checkout_plan(other_tree(&repo)?, Default::default())?;
+    assert!(!plan.is_conflicted());
+    assert_eq!(plan.from_tree, Some(repo.head_tree_id()?.detach()));
+    assert_eq!(
+        operations(&plan),
+        [
+            ("create", "added"),
+            ("delete", "deleted"),
+            ("create", "dir"),
+            ("delete", "dir/file"),
+            ("overwrite", "modified"),
+            ("create", "replaces.ignored"),
+        ]
+        .into_iter()
+        .map(|(kind, path)| (kind, path.to_owned()))
+        .collect::<Vec<_>>()
+    );
+    Ok(())
+}
+
+#[test]
+fn local_changes_and_untracked_files_in_the_way_are_conflicts() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_checkout_repos.sh", "plan")?;
+    let work_dir = repo.work_dir().expect("non-bare");
+    std::fs::write(work_dir.join("modified"), "local change")?;
+    std::fs::write(work_dir.join("unchanged"), "carried over")?;
+    std::fs::write(work_dir.join("added"), "untracked")?;
+    std::fs::write(work_dir.join("replaces.ignored"), "ignored")?;
+    std::fs::write(work_dir.join("dir/untracked"), "untracked")?;
+    let mut stage = repo.stage()?;
+    stage.add_bytes("deleted", "staged\n", gix::index::entry::Mode::FILE)?;
+    stage.write()?;
+
+    let plan = repo.checkout_plan(other_tree(&repo)?, Default::default())?;
+    assert_eq!(
+        conflicts(&plan),
+        [
+            (ConflictReason::UntrackedOverwritten, "added"),
+            (ConflictReason::StagedChanges, "deleted"),
+            (ConflictReason::UntrackedRemoved, "dir"),
+            (ConflictReason::WorktreeChanges, "modified"),
+        ]
+        .into_iter()
+        .map(|(reason, path)| (reason, path.to_owned()))
+        .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        operations(&plan),
+        [("delete", "dir/file"), ("overwrite", "replaces.ignored"),]
+            .into_iter()
+            .map(|(kind, path)| (kind, path.to_owned()))
+            .collect::<Vec<_>>(),
+        "ignored files are overwritten, and local changes to unaffected paths are carried over"
+    );
+
+    let plan = repo.checkout_plan(
+        other_tree(&repo)?,
+        plan::Options {
+            protect_ignored: true,
+            ..Default::default()
+        },
+    )?;
+    assert!(plan
+        .conflicts
+        .iter()
+        .any(|c| c.rela_path == "replaces.ignored" && c.reason == ConflictReason::UntrackedOverwritten));
+    Ok(())
+}
+
+#[test]
+fn staged_changes_matching_the_target_are_no_conflict() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_checkout_repos.sh", "plan")?;
+    let mut stage = repo.stage()?;
+    stage.add_bytes("modified", "content\nchange\n", gix::index::entry::Mode::FILE)?;
+    stage.remove("deleted");
+    stage.write()?;
+
+    let plan = repo.checkout_plan(other_tree(&repo)?, Default::default())?;
+    assert!(!plan.is_conflicted());
+    assert!(
+        plan.operations
+            .iter()
+            .all(|op| op.rela_path() != "modified" && op.rela_path() != "deleted"),
+        "the index already matches the target"
+    );
+    Ok(())
+}
+
+#[test]
+fn checkout_tree_only_touches_changed_paths() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_checkout_repos.sh", "plan")?;
+    let work_dir = repo.work_dir().expect("non-bare").to_owned();
+    let unchanged_mtime = std::fs::metadata(work_dir.join("unchanged"))?.modified()?;
+    let head = repo.head_id()?.detach();
+
+    let plan = repo.checkout_tree(other_tree(&repo)?, Default::default())?;
+    assert_eq!(plan.operations.len(), 6);
+    assert_eq!(std::fs::read(work_dir.join("modified"))?, b"content\nchange\n");
+    assert!(!work_dir.join("deleted").exists());
+    assert_eq!(
+        std::fs::metadata(work_dir.join("unchanged"))?.modified()?,
+        unchanged_mtime,
+        "files that are the same in both trees aren't written"
+    );
+    assert_eq!(repo.head_id()?, head, "HEAD doesn't move");
+    assert_eq!(
+        repo.index()?.entry_by_path("modified".into()).expect("present").id,
+        repo.rev_parse_single("other:modified")?.detach(),
+        "the index is updated as well"
+    );
+    Ok(())
+}
+
+#[test]
+fn symlinks_and_executable_bits_follow_the_configuration() -> crate::Result {
+    use gix::index::entry::Mode;
+
+    let (mut repo, _tmp) = crate::named_subrepo_rw("make_checkout_repos.sh", "modes")?;
+    {
+        let mut config = repo.config_snapshot_mut();
+        config.set_raw_value("core", None, "symlinks", "false")?;
+        config.set_raw_value("core", None, "fileMode", "false")?;
+    }
+    let work_dir = repo.work_dir().expect("non-bare").to_owned();
+    repo.checkout_tree(other_tree(&repo)?, Default::default())?;
+
+    let link = work_dir.join("link");
+    assert!(
+        !link.symlink_metadata()?.file_type().is_symlink(),
+        "symlinks are written as files"
+    );
+    assert_eq!(
+        std::fs::read(&link)?,
+        b"$Id$",
+        "the file contains the path the symlink points to, unfiltered"
+    );
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(
+            std::fs::metadata(work_dir.join("script"))?.permissions().mode() & 0o111,
+            0,
+            "the executable bit isn't set"
+        );
+    }
+
+    let status = repo.status(Default::default())?;
+    assert_eq!(
+        status
+            .items
+            .iter()
+            .filter(|item| item.porcelain_v2_code()[1] != '.')
+            .count(),
+        0,
+        "the worktree is considered unchanged"
+    );
+
+    let index = repo.index()?;
+    let expected: Vec<_> = ["link", "script"]
+        .into_iter()
+        .map(|path| index.entry_by_path(path.into()).map(|e| (e.id, e.mode)))
+        .collect();
+    let mut stage = repo.stage()?;
+    stage.add_path("link")?;
+    stage.add_path("script")?;
+    let index = stage.index();
+    assert_eq!(
+        ["link", "script"]
+            .into_iter()
+            .map(|path| index.entry_by_path(path.into()).map(|e| (e.id, e.mode)))
+            .collect::<Vec<_>>(),
+        expected,
+        "staging from the worktree retains symlinks and executable bits"
+    );
+    assert_eq!(
+        expected.iter().map(|e| e.expect("present").1).collect::<Vec<_>>(),
+        [Mode::SYMLINK, Mode::FILE_EXECUTABLE]
+    );
+    Ok(())
+}
+
+#[test]
+fn paths_differing_only_in_case_are_conflicts_if_case_is_ignored() -> crate::Result {
+    let (mut repo, _tmp) = crate::named_subrepo_rw("make_checkout_repos.sh", "case")?;
+    let plan = repo.checkout_plan(other_tree(&repo)?, Default::default())?;
+    assert!(!plan.is_conflicted(), "by default, case matters");
+
+    repo.config_snapshot_mut()
+        .set_raw_value("core", None, "ignoreCase", "true")?;
+    let plan = repo.checkout_plan(other_tree(&repo)?, Default::default())?;
+    assert_eq!(
+        conflicts(&plan),
+        [(ConflictReason::CaseCollision, "README".into())],
+        "the new file would be written into the tracked one, but renames that only change case are fine"
+    );
+    assert_eq!(
+        operations(&plan),
+        [
+            ("create", "OLD".into()),
+            ("create", "README".into()),
+            ("delete", "old".into())
+        ]
+    );
+    Ok(())
+}
+
+mod switch {
+    use gix::checkout::{switch, tree, ConflictReason};
+
+    #[test]
+    fn changed_paths_are_updated_and_head_is_attached() -> crate::Result {
+        let (repo, _tmp) = crate::named_subrepo_rw("make_checkout_repos.sh", "plan")?;
+        let work_dir = repo.work_dir().expect("non-bare").to_owned();
+        std::fs::write(work_dir.join("unchanged"), "carried over")?;
+
+        let plan = repo.switch("other", Default::default())?;
+        assert_eq!(plan.operations.len(), 6);
+        assert_eq!(
+            repo.head_name()?.expect("attached").as_bstr(),
+            "refs/heads/other",
+            "local branches are attached to"
+        );
+        assert_eq!(std::fs::read(work_dir.join("dir"))?, b"now a file\n");
+        assert_eq!(std::fs::read(work_dir.join("modified"))?, b"content\nchange\n");
+        assert!(!work_dir.join("deleted").exists());
+        assert_eq!(std::fs::read(work_dir.join("unchanged"))?, b"carried over");
+
+        let repo = gix::open_opts(&work_dir, crate::restricted())?;
+        let status = repo.status(Default::default())?;
+        assert_eq!(
+            status
+                .items
+                .iter()
+                .map(|item| (
+                    item.porcelain_v2_code().iter().collect::<String>(),
+                    item.rela_path().to_string()
+                ))
+                .collect::<Vec<_>>(),
+            [(".M".to_string(), "unchanged".to_string())],
+            "the index matches the new tree and the local change was carried over"
+        );
+        let index = repo.index()?;
+        for path in ["added", "dir", "modified"] {
+            let entry = index.entry_by_path(path.into()).expect("present");
+            assert_ne!(
+                entry.stat,
+                Default::default(),
+                "stat information of written files is recorded"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn detached_head_for_non_branches_or_on_request() -> crate::Result {
+        let (repo, _tmp) = crate::named_subrepo_rw("make_checkout_repos.sh", "plan")?;
+        let other = repo.rev_parse_single("other")?.detach();
+        repo.switch(
+            "other",
+            switch::Options {
+                detach: true,
+                ..Default::default()
+            },
+        )?;
+        assert!(repo.head()?.is_detached());
+        assert_eq!(repo.head_id()?, other);
+        Ok(())
+    }
+
+    #[test]
+    fn local_changes_that_would_be_overwritten_prevent_the_switch() -> crate::Result {
+        let (repo, _tmp) = crate::named_subrepo_rw("make_checkout_repos.sh", "plan")?;
+        let work_dir = repo.work_dir().expect("non-bare").to_owned();
+        std::fs::write(work_dir.join("modified"), "local change")?;
+        let head = repo.head_id()?.detach();
+
+        match repo.switch("other", Default::default()) {
+            Err(switch::Error::Checkout(tree::Error::Conflicts { conflicts })) => {
+                assert_eq!(conflicts.len(), 1);
+                assert_eq!(conflicts[0].rela_path, "modified");
+                assert_eq!(conflicts[0].reason, ConflictReason::WorktreeChanges);
+            }
+            res => panic!("expected conflicts, got {res:?}"),
+        }
+        assert_eq!(repo.head_id()?, head, "nothing changed");
+        assert!(work_dir.join("deleted").exists());
+        assert_eq!(std::fs::read(work_dir.join("modified"))?, b"local change");
+        Ok(())
+    }
+}
@@ -0,0 +1,154 @@
+use gix::clean::{Entry, Ignored, Kind, Options};
+
+fn paths(entries: &[Entry]) -> Vec<(String, Kind)> {
+    entries
+        .iter()
+        .map(|entry| (entry.rela_path.to_string(), entry.kind))
+        .collect()
+}
+
+fn expected(paths: &[(&str, Kind)]) -> Vec<(String, Kind)> {
+    paths.iter().map(|(path, kind)| (path.to_string(), *kind)).collect()
+}
+
+#[test]
+fn dry_run_is_the_default_and_skips_directories_without_tracked_files() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_status_repos.sh", "changes")?;
+    let outcome = repo.clean(Default::default())?;
+    assert_eq!(
+        paths(&outcome.entries),
+        expected(&[
+            ("deleted-in-index", Kind::File),
+            ("dir/untracked", Kind::File),
+            ("untracked", Kind::File),
+        ])
+    );
+    assert!(
+        repo.work_dir().expect("present").join("untracked").is_file(),
+        "nothing is removed in a dry-run"
+    );
+    Ok(())
+}
+
+#[test]
+fn directories_are_removed_as_a_whole_if_nothing_in_them_is_tracked() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_status_repos.sh", "changes")?;
+    let outcome = repo.clean(Options {
+        directories: true,
+        ..Default::default()
+    })?;
+    assert_eq!(
+        paths(&outcome.entries),
+        expected(&[
+            ("deleted-in-index", Kind::File),
+            ("dir/untracked", Kind::File),
+            ("untracked", Kind::File),
+            ("untracked-dir/", Kind::Directory),
+        ])
+    );
+    Ok(())
+}
+
+#[test]
+fn ignored_files_can_be_included_or_selected_exclusively() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_status_repos.sh", "changes")?;
+    let outcome = repo.clean(Options {
+        ignored: Ignored::Only,
+        ..Default::default()
+    })?;
+    assert_eq!(outcome.entries.len(), 1);
+    assert_eq!(outcome.entries[0].rela_path, "file.ignored");
+    assert!(outcome.entries[0].is_ignored);
+
+    let outcome = repo.clean(Options {
+        ignored: Ignored::Also,
+        ..Default::default()
+    })?;
+    assert_eq!(
+        paths(&outcome.entries),
+        expected(&[
+            ("deleted-in-index", Kind::File),
+            ("dir/untracked", Kind::File),
+            ("file.ignored", Kind::File),
+            ("untracked", Kind::File),
+        ])
+    );
+    Ok(())
+}
+
+#[test]
+fn pathspecs_limit_what_is_removed() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_status_repos.sh", "changes")?;
+    let outcome = repo.clean(Options {
+        directories: true,
+        patterns: vec!["untracked-dir/a".into(), "dir".into()],
+        ..Default::default()
+    })?;
+    assert_eq!(
+        paths(&outcome.entries),
+        expected(&[("dir/untracked", Kind::File), ("untracked-dir/a", Kind::File)]),
+        "directories are only removed as a whole if all of their content matches"
+    );
+    Ok(())
+}
+
+#[test]
+fn nested_repositories_are_only_removed_if_enabled() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_status_repos.sh", "changes")?;
+    let work_dir = repo.work_dir().expect("present").to_owned();
+    gix::init(work_dir.join("nested"))?;
+    std::fs::write(work_dir.join("nested").join("file"), b"content")?;
+
+    let options = Options {
+        directories: true,
+        ..Default::default()
+    };
+    let outcome = repo.clean(options.clone())?;
+    assert!(
+        !outcome
+            .entries
+            .iter()
+            .any(|entry| entry.rela_path.starts_with(b"nested")),
+        "nested repositories are kept by default"
+    );
+
+    let outcome = repo.clean(Options {
+        repositories: true,
+        ..options
+    })?;
+    assert!(outcome.entries.contains(&Entry {
+        rela_path: "nested/".into(),
+        kind: Kind::Repository,
+        is_ignored: false,
+    }));
+    Ok(())
+}
+
+#[test]
+fn removal_without_dry_run() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_status_repos.sh", "changes")?;
+    let outcome = repo.clean(Options {
+        dry_run: false,
+        directories: true,
+        ignored: Ignored::Also,
+        ..Default::default()
+    })?;
+    assert_eq!(outcome.entries.len(), 5);
+    let work_dir = repo.work_dir().expect("present");
+    for entry in &outcome.entries {
+        assert!(
+            !work_dir.join(gix::path::from_bstr(entry.rela_path.as_ref())).exists(),
+            "{} was removed",
+            entry.rela_path
+        );
+    }
+    assert!(work_dir.join("dir/tracked").is_file(), "tracked files are kept");
+
+    let outcome = repo.clean(Options {
+        ignored: Ignored::Also,
+        directories: true,
+        ..Default::default()
+    })?;
+    assert!(outcome.entries.is_empty(), "there is nothing left to remove");
+    Ok(())
+}
@@ -56,6 +56,544 @@ fn snapshot_mut_commit_and_forget() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn snapshot_mut_commit_to_scope() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_basic_repo.sh")?;
+    let config_path = repo.git_dir().join("config");
+    let mut original = std::fs::read_to_string(&config_path)?;
+    original.push_str("# a comment\n[user]\n\tname = first\n\tname = second # inline\n");
+    std::fs::write(&config_path, &original)?;
+    let mut repo = gix::open_opts(repo.git_dir(), repo.open_options().clone())?;
+
+    let mut config = repo.config_snapshot_mut();
+    config.set_value(&Core::ABBREV, "5")?;
+    config.set_subsection_value(&Branch::MERGE, "feature", "refs/heads/feature")?;
+    let repo = config.commit_to(gix_config::Source::Local)?;
+    assert_eq!(
+        repo.config_snapshot().integer("core.abbrev"),
+        Some(5),
+        "changes are applied"
+    );
+
+    let actual = std::fs::read_to_string(&config_path)?;
+    assert!(
+        actual.ends_with(
+            "# a comment\n\tabbrev = 5\n[user]\n\tname = first\n\tname = second # inline\n\
+             [branch \"feature\"]\n\tmerge = refs/heads/feature\n"
+        ),
+        "comments and untouched sections are preserved, new values are added to existing sections if possible:\n{actual}"
+    );
+
+    let mut config = repo.config_snapshot_mut();
+    config.set_raw_value("core", None, "abbrev", "6")?;
+    let repo = config.commit_to(gix_config::Source::Worktree)?;
+    assert!(
+        !repo.git_dir().join("config.worktree").exists(),
+        "without extensions.worktreeConfig and with a single worktree, the local configuration is used instead"
+    );
+    assert_eq!(
+        std::fs::read_to_string(&config_path)?,
+        actual.replace("abbrev = 5", "abbrev = 6"),
+        "existing values are replaced"
+    );
+
+    let mut config = repo.config_snapshot_mut();
+    config.set_raw_value("core", None, "abbrev", "7")?;
+    assert!(
+        matches!(
+            config.commit_to(gix_config::Source::Cli),
+            Err(gix::config::persist::Error::MissingLocation {
+                scope: gix_config::Source::Cli
+            })
+        ),
+        "some scopes can't be written to"
+    );
+    assert_eq!(
+        repo.config_snapshot().integer("core.abbrev"),
+        Some(6),
+        "nothing is applied on failure"
+    );
+    Ok(())
+}
+
+#[test]
+fn snapshot_mut_commit_to_fails_in_read_only_repositories() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_basic_repo.sh")?;
+    let config_path = repo.git_dir().join("config");
+    let original = std::fs::read(&config_path)?;
+    let mut repo = gix::open_opts(repo.git_dir(), crate::restricted().read_only(true))?;
+
+    let mut config = repo.config_snapshot_mut();
+    config.set_raw_value("core", None, "abbrev", "7")?;
+    assert!(matches!(
+        config.commit_to(gix_config::Source::Local),
+        Err(gix::config::persist::Error::ReadOnly(_))
+    ));
+    assert_ne!(
+        repo.config_snapshot().integer("core.abbrev"),
+        Some(7),
+        "nothing is applied"
+    );
+    assert_eq!(std::fs::read(&config_path)?, original, "nothing is written");
+    Ok(())
+}
+
+#[test]
+fn snapshot_mut_commit_to_worktree_scope() -> crate::Result {
+    let tmp = gix_testtools::scripted_fixture_writable("make_worktree_repo_with_configs.sh")?;
+    let mut repo = gix::open_opts(tmp.path().join("repo"), crate::restricted())?;
+    let mut config = repo.config_snapshot_mut();
+    config.set_raw_value("worktree", None, "setting", "changed in the main worktree")?;
+    config.set_raw_value("user", None, "name", "worktree user")?;
+    let repo = config.commit_to(gix_config::Source::Worktree)?;
+    assert_eq!(
+        std::fs::read_to_string(repo.git_dir().join("config.worktree"))?,
+        "[worktree]\n\tsetting = changed in the main worktree\n[user]\n\tname = worktree user\n",
+        "with extensions.worktreeConfig, config.worktree receives the changes"
+    );
+
+    let repo = gix::open_opts(repo.work_dir().expect("non-bare"), crate::restricted())?;
+    let config = repo.config_snapshot();
+    assert_eq!(
+        config.string("worktree.setting").expect("present").as_ref(),
+        "changed in the main worktree"
+    );
+    assert_eq!(
+        config.string("override.setting").expect("present").as_ref(),
+        "set in the shared config"
+    );
+
+    let tmp = gix_testtools::scripted_fixture_writable("make_worktree_repo.sh")?;
+    let mut repo = gix::open_opts(tmp.path().join("repo"), crate::restricted())?;
+    let mut config = repo.config_snapshot_mut();
+    config.set_raw_value("user", None, "name", "worktree user")?;
+    assert!(
+        matches!(
+            config.commit_to(gix_config::Source::Worktree),
+            Err(gix::config::persist::Error::WorktreeConfigDisabled)
+        ),
+        "just like git, without extensions.worktreeConfig, multiple worktrees can't have their own configuration"
+    );
+    Ok(())
+}
+
+#[test]
+fn typed_values() -> crate::Result {
+    let mut repo: gix::Repository = named_repo("make_config_repo.sh")?;
+    let mut config = repo.config_snapshot_mut();
+    config.set_raw_value("color", Some("diff".into()), "meta", "bold red ul")?;
+    config.set_raw_value("color", Some("diff".into()), "frag", "no-such-color")?;
+    config.set_raw_value("pack", None, "windowMemory", "10m")?;
+    config.set_raw_value("pack", None, "deltaCacheSize", "-1")?;
+    config.set_raw_value("core", None, "filesRefLockTimeout", "1k")?;
+    config.set_raw_value("core", None, "packedRefsTimeout", "-1")?;
+    config.set_raw_value("core", None, "autocrlf", "input")?;
+    config.set_raw_value("push", None, "default", "everything")?;
+    config.set_raw_value("core", None, "excludesFile", "~/ignore")?;
+    config.set_raw_value("core", None, "hooksPath", "/absolute/hooks")?;
+    let repo = config.commit()?;
+    let config = repo.config_snapshot();
+
+    let color = config.color("color.diff.meta").expect("valid");
+    assert_eq!(color.to_string(), "red bold ul");
+    let err = config.try_color("color.diff.frag").expect("present").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        r#"The color at key "color.diff.frag=no-such-color" could not be parsed"#
+    );
+    assert!(config.color("color.diff.frag").is_none(), "errors degenerate to None");
+    assert!(
+        config.try_color("color.diff.old").is_none(),
+        "missing values aren't errors"
+    );
+
+    assert_eq!(config.byte_size("pack.windowMemory"), Some(10 * 1024 * 1024));
+    let err = config
+        .try_byte_size("pack.deltaCacheSize")
+        .expect("present")
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        r#"The value of key "pack.deltaCacheSize=-1" could not be parsed as unsigned integer"#
+    );
+
+    assert_eq!(
+        config.duration("core.filesRefLockTimeout"),
+        Some(std::time::Duration::from_millis(1024))
+    );
+    assert_eq!(
+        config.duration("core.packedRefsTimeout"),
+        Some(std::time::Duration::from_secs(u64::MAX)),
+        "negative durations are infinite"
+    );
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    enum AutoCrlf {
+        True,
+        False,
+        Input,
+    }
+    let variants = [
+        ("true", AutoCrlf::True),
+        ("false", AutoCrlf::False),
+        ("input", AutoCrlf::Input),
+    ];
+    assert_eq!(config.one_of("core.autocrlf", &variants), Some(AutoCrlf::Input));
+    let err = config
+        .try_one_of("push.default", &[("simple", 0), ("current", 1)])
+        .expect("present")
+        .unwrap_err();
+    assert_eq!(err.to_string(), r#"The key "push.default=everything" was invalid"#);
+
+    assert_eq!(
+        config.path("core.hooksPath").expect("present")?,
+        std::path::Path::new("/absolute/hooks")
+    );
+    assert!(
+        matches!(
+            config.path("core.excludesFile").expect("present"),
+            Err(gix_config::path::interpolate::Error::Missing { .. })
+        ),
+        "the home directory isn't accessible in isolated mode"
+    );
+    Ok(())
+}
+
+#[test]
+fn aliases() -> crate::Result {
+    use gix::config::alias::{Alias, Error};
+    let mut repo: gix::Repository = named_repo("make_config_repo.sh")?;
+    let mut config = repo.config_snapshot_mut();
+    config.set_raw_value("alias", None, "co", "checkout")?;
+    config.set_raw_value("alias", None, "lg", r#"log --format='%h %s' "--grep=a b" c\ d"#)?;
+    config.set_raw_value("alias", None, "last", "lg -1")?;
+    config.set_raw_value("alias", None, "sh", "!echo \"$1\" | cat")?;
+    config.set_raw_value("alias", None, "via-sh", "sh first")?;
+    config.set_raw_value("alias", None, "empty", "")?;
+    config.set_raw_value("alias", None, "unclosed", "log 'oops")?;
+    config.set_raw_value("alias", None, "ping", "pong")?;
+    config.set_raw_value("alias", None, "pong", "ping")?;
+    let repo = config.commit()?;
+    let config = repo.config_snapshot();
+
+    let args = |args: &[&str]| args.iter().map(|a| gix::bstr::BString::from(*a)).collect::<Vec<_>>();
+    assert_eq!(
+        config.alias("lg")?,
+        Some(Alias::Command(args(&["log", "--format=%h %s", "--grep=a b", "c d"]))),
+        "values are split like git would, with quotes and escapes"
+    );
+    assert_eq!(config.alias("LG")?, config.alias("lg")?, "names are case-insensitive");
+    assert_eq!(config.alias("unset")?, None);
+    assert_eq!(
+        config.alias("sh")?,
+        Some(Alias::Shell {
+            command: "echo \"$1\" | cat".into(),
+            args: Vec::new()
+        })
+    );
+
+    assert_eq!(
+        config.expand_alias(&args(&["last", "--stat"]))?,
+        Some(Alias::Command(args(&[
+            "log",
+            "--format=%h %s",
+            "--grep=a b",
+            "c d",
+            "-1",
+            "--stat"
+        ]))),
+        "aliases are expanded recursively, keeping all arguments"
+    );
+    assert_eq!(
+        config.expand_alias(&args(&["co", "main"]))?,
+        Some(Alias::Command(args(&["checkout", "main"])))
+    );
+    assert_eq!(
+        config.expand_alias(&args(&["via-sh", "second"]))?,
+        Some(Alias::Shell {
+            command: "echo \"$1\" | cat".into(),
+            args: args(&["first", "second"])
+        }),
+        "shell aliases receive all remaining arguments"
+    );
+    assert_eq!(config.expand_alias(&args(&["status"]))?, None, "not an alias");
+    assert_eq!(config.expand_alias(&[])?, None);
+
+    assert!(matches!(config.alias("empty"), Err(Error::Empty { .. })));
+    assert!(matches!(config.alias("unclosed"), Err(Error::UnclosedQuote { .. })));
+    let err = config.expand_alias(&args(&["ping"])).unwrap_err();
+    assert_eq!(err.to_string(), "Alias loop detected: ping -> pong -> ping");
+    Ok(())
+}
+
+#[test]
+fn validate() -> crate::Result {
+    use gix::config::validate::Kind;
+    let tmp = gix_testtools::scripted_fixture_writable("make_basic_repo.sh")?;
+    std::fs::write(
+        tmp.path().join(".git").join("config"),
+        r#"[core]
+	repositoryFormatVersion = 0
+	bare = maybe
+	fileMode
+	noSuchKey = 1
+# a comment
+[pack]
+	writeBitmaps = \
+true
+[no-such-section]
+	key = value
+[remote "origin"]
+	url = https://example.com/repo
+[http "https://example.com"]
+	sslVerify = false
+[gitoxide "core"]
+	useNsec = true
+"#,
+    )?;
+    let repo = gix::open_opts(
+        tmp.path(),
+        crate::restricted().config_overrides(["core.unknownOverride=x"]),
+    )?;
+    let diagnostics = repo.config_snapshot().validate()?;
+    let actual: Vec<_> = diagnostics
+        .iter()
+        .map(|d| {
+            let kind = match &d.kind {
+                Kind::UnknownSection => "unknown section",
+                Kind::UnknownKey => "unknown key",
+                Kind::InvalidValue(_) => "invalid value",
+                Kind::Deprecated(_) => "deprecated",
+            };
+            (d.key.to_string(), kind, d.source, d.line)
+        })
+        .collect();
+    assert_eq!(
+        actual,
+        [
+            ("core.bare".into(), "invalid value", gix::config::Source::Local, Some(3)),
+            (
+                "core.noSuchKey".into(),
+                "unknown key",
+                gix::config::Source::Local,
+                Some(5)
+            ),
+            (
+                "pack.writeBitmaps".into(),
+                "deprecated",
+                gix::config::Source::Local,
+                Some(8)
+            ),
+            (
+                "no-such-section.key".into(),
+                "unknown section",
+                gix::config::Source::Local,
+                Some(11)
+            ),
+            (
+                "core.unknownOverride".into(),
+                "unknown key",
+                gix::config::Source::Api,
+                None
+            ),
+        ],
+        "implicit values, multi-line values, url subsections and statically known subsections are handled"
+    );
+    assert_eq!(
+        diagnostics[0].path.as_deref(),
+        Some(tmp.path().join(".git").join("config").as_path())
+    );
+    assert_eq!(
+        diagnostics[2].value.as_ref().map(ToString::to_string),
+        Some("true".into())
+    );
+
+    let schema = gix::config::Tree.schema();
+    let entry = schema
+        .iter()
+        .find(|e| e.name == "pack.writeBitmaps")
+        .expect("deprecated keys are known");
+    assert_eq!(entry.value_type, "Boolean");
+    assert!(entry.deprecation.is_some());
+    let entry = schema
+        .iter()
+        .find(|e| e.name == "repack.writeBitmaps")
+        .expect("present");
+    assert_eq!(entry.fallback.as_deref(), Some("pack.writeBitmaps"));
+    Ok(())
+}
+
+#[test]
+fn values_with_origin() -> crate::Result {
+    let tmp = gix_testtools::scripted_fixture_writable("make_basic_repo.sh")?;
+    let git_dir = tmp.path().join(".git");
+    std::fs::write(
+        git_dir.join("config"),
+        "[core]\n\trepositoryFormatVersion = 0\n\tbare = false\n[include]\n\tpath = first.config\n[user]\n\tname = local\n",
+    )?;
+    std::fs::write(
+        git_dir.join("first.config"),
+        "# comment\n[user]\n\tname = first\n[include]\n\tpath = second.config\n",
+    )?;
+    std::fs::write(git_dir.join("second.config"), "[user]\n\temail = a \\\n  b\n\tname\n")?;
+    let mut opts = crate::restricted().config_overrides(["user.name=override"]);
+    opts.permissions.config.includes = true;
+    let repo = gix::open_opts(tmp.path(), opts)?;
+
+    let config = repo.config_snapshot();
+    let actual: Vec<_> = config
+        .values_with_origin_by_key("user.name")?
+        .into_iter()
+        .map(|v| {
+            (
+                v.value.map(|v| v.to_string()),
+                v.origin.scope(),
+                v.origin
+                    .path
+                    .map(|p| p.file_name().expect("present").to_string_lossy().into_owned()),
+                v.origin.line,
+                v.origin.included_from.len(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        actual,
+        [
+            (Some("first".into()), "local", Some("first.config".into()), Some(3), 1),
+            (None, "local", Some("second.config".into()), Some(4), 2),
+            (Some("local".into()), "local", Some("config".into()), Some(7), 0),
+            (Some("override".into()), "command", None, None, 0),
+        ],
+        "included values are placed after the section including them, and multi-line values are accounted for in line numbers"
+    );
+
+    let values = config.values_with_origin()?;
+    let nested = values
+        .iter()
+        .find(|v| v.key == "user.email")
+        .expect("included value is present");
+    assert_eq!(nested.value.as_ref().map(ToString::to_string), Some("a   b".into()));
+    assert_eq!(
+        nested.origin.included_from,
+        [git_dir.join("config"), git_dir.join("first.config")],
+        "the include chain starts with the file that was loaded directly"
+    );
+    assert_eq!(
+        nested.origin.to_string(),
+        format!("file:{}", git_dir.join("second.config").display())
+    );
+    assert!(
+        values.iter().any(|v| v.key == "core.bare" && v.origin.line == Some(3)),
+        "keys are listed with section names as written"
+    );
+    assert!(config.values_with_origin_by_key("no-such.key")?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn multi_valued_keys() -> crate::Result {
+    use gix::config::{tree::Remote, ValuePattern};
+
+    let mut repo = named_repo("make_config_repo.sh")?;
+    let mut config = repo.config_snapshot_mut();
+    let origin = Some("origin".into());
+    let fetch = |config: &gix::config::SnapshotMut<'_>| -> Vec<String> {
+        config
+            .strings_by_key("remote.origin.fetch")
+            .unwrap_or_default()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    };
+
+    for spec in [
+        "+refs/heads/*:refs/remotes/origin/*",
+        "refs/tags/*:refs/tags/*",
+        "+refs/heads/main",
+    ] {
+        config.append_value(&Remote::FETCH, origin, spec)?;
+    }
+    assert_eq!(fetch(&config).len(), 3, "values are appended");
+    assert!(
+        matches!(
+            config.append_value(&Remote::FETCH, None, "refs/heads/main"),
+            Err(gix::config::set_value::Error::SubSectionRequired)
+        ),
+        "the subsection requirement is checked"
+    );
+    assert!(
+        config.append_value(&Remote::FETCH, origin, "a:b:c").is_err(),
+        "values are validated"
+    );
+
+    assert_eq!(
+        config.replace_values(
+            &Remote::FETCH,
+            origin,
+            "refs/tags/v1:refs/tags/v1",
+            &ValuePattern::fixed("refs/tags/*:refs/tags/*")
+        )?,
+        1
+    );
+    assert_eq!(
+        fetch(&config),
+        [
+            "+refs/heads/*:refs/remotes/origin/*",
+            "refs/tags/v1:refs/tags/v1",
+            "+refs/heads/main"
+        ],
+        "the replacement takes the place of the match"
+    );
+
+    assert_eq!(
+        config.replace_values(
+            &Remote::FETCH,
+            origin,
+            "refs/heads/dev",
+            &ValuePattern::fixed("nothing")
+        )?,
+        0
+    );
+    assert_eq!(
+        fetch(&config).last().map(String::as_str),
+        Some("refs/heads/dev"),
+        "without a match, the value is appended"
+    );
+
+    #[cfg(feature = "regex")]
+    {
+        assert!(ValuePattern::regex("(").is_err());
+        assert_eq!(
+            config.remove_values(&Remote::FETCH, origin, &ValuePattern::regex("!^\\+")?)?,
+            2,
+            "negated patterns remove values that don't match"
+        );
+        assert_eq!(
+            fetch(&config),
+            ["+refs/heads/*:refs/remotes/origin/*", "+refs/heads/main"]
+        );
+        assert_eq!(
+            config.replace_values(
+                &Remote::FETCH,
+                origin,
+                "+refs/heads/*:refs/remotes/upstream/*",
+                &ValuePattern::regex("heads")?
+            )?,
+            2,
+            "patterns are unanchored"
+        );
+        assert_eq!(fetch(&config), ["+refs/heads/*:refs/remotes/upstream/*"]);
+    }
+
+    let num_values = fetch(&config).len();
+    assert_eq!(
+        config.remove_values(&Remote::FETCH, origin, &ValuePattern::all())?,
+        num_values
+    );
+    assert!(fetch(&config).is_empty());
+    Ok(())
+}
+
 #[test]
 fn values_are_set_in_memory_only() {
     let mut repo = named_repo("make_config_repo.sh").unwrap();
@@ -0,0 +1,66 @@
+use gix_sec::Permission;
+use gix_testtools::Env;
+use serial_test::serial;
+
+use crate::named_repo;
+
+#[test]
+#[serial]
+fn config_count_and_file_overrides() -> crate::Result {
+    let repo = named_repo("make_config_repo.sh")?;
+    let tmp = gix_testtools::tempfile::tempdir()?;
+    let global_path = tmp.path().join("global.config");
+    std::fs::write(&global_path, "[global]\n\tkey = from-global-file\n")?;
+    let _env = Env::new()
+        .set("GIT_CONFIG_GLOBAL", global_path.display().to_string())
+        .set("GIT_CONFIG_NOSYSTEM", "1")
+        .set("GIT_CONFIG_COUNT", "2")
+        .set("GIT_CONFIG_KEY_0", "env.key")
+        .set("GIT_CONFIG_VALUE_0", "from-env")
+        .set("GIT_CONFIG_KEY_1", "global.key")
+        .set("GIT_CONFIG_VALUE_1", "from-env");
+
+    let open = |git_prefix: Permission| -> crate::Result<gix::Repository> {
+        Ok(gix::open_opts(
+            repo.git_dir(),
+            repo.open_options().clone().permissions(gix::open::Permissions {
+                env: gix::open::permissions::Environment {
+                    xdg_config_home: Permission::Deny,
+                    home: Permission::Deny,
+                    git_prefix,
+                    ..gix::open::permissions::Environment::all()
+                },
+                ..Default::default()
+            }),
+        )?)
+    };
+
+    let repo = open(Permission::Allow)?;
+    let config = repo.config_snapshot();
+    assert_eq!(config.string("env.key").as_deref(), Some("from-env".into()));
+    assert_eq!(
+        config.plumbing().strings_by_key("global.key"),
+        Some(vec![
+            gix::bstr::BStr::new("from-global-file").into(),
+            gix::bstr::BStr::new("from-env").into()
+        ]),
+        "the global file is read only once, and environment values are applied after it"
+    );
+    assert!(
+        config
+            .plumbing()
+            .sections()
+            .all(|section| section.meta().source != gix_config::Source::System),
+        "the system configuration isn't loaded"
+    );
+
+    let repo = open(Permission::Deny)?;
+    let config = repo.config_snapshot();
+    assert_eq!(
+        config.string("env.key"),
+        None,
+        "GIT_* environment variables are ignored without permission"
+    );
+    assert_eq!(config.string("global.key"), None);
+    Ok(())
+}
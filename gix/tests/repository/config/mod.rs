@@ -1,4 +1,5 @@
 mod config_snapshot;
+mod environment;
 mod identity;
 mod remote;
 
@@ -55,6 +55,7 @@ mod http {
             verbose,
             ssl_ca_info,
             ssl_version,
+            ssl_verify,
             http_version,
             backend,
         } = http_options(&repo, None, "https://example.com/does/not/matter");
@@ -77,6 +78,7 @@ mod http {
         assert_eq!(no_proxy, None);
         assert!(!verbose, "verbose is disabled by default");
         assert_eq!(ssl_ca_info.as_deref(), Some(std::path::Path::new("./CA.pem")));
+        assert!(ssl_verify, "peers are verified by default");
         #[cfg(feature = "blocking-http-transport-reqwest")]
         {
             assert!(
@@ -109,6 +111,80 @@ mod http {
         assert_eq!(http_version, Some(HttpVersion::V1_1));
     }
 
+    #[test]
+    fn url_specific_configuration_uses_the_most_specific_match() {
+        let repo = repo_opts("http-config", |opts| {
+            opts.config_overrides([
+                "http.https://example.com.userAgent=host",
+                "http.https://*.example.com/org.userAgent=wildcard-host-and-path",
+                "http.https://example.com/org/.userAgent=path",
+                "http.https://user@example.com/org.userAgent=user",
+                "http.https://example.com/org.sslVerify=false",
+                "http.http://example.com.proxy=other-scheme",
+                "http.https://example.com:8443.version=HTTP/2",
+            ])
+        });
+
+        let opts = http_options(&repo, None, "https://example.com/org/repo");
+        assert_eq!(
+            opts.user_agent.as_deref(),
+            Some("path"),
+            "longer paths win, and trailing slashes don't matter"
+        );
+        assert!(!opts.ssl_verify);
+        assert_eq!(
+            opts.proxy.as_deref(),
+            Some("http://localhost:9090"),
+            "the scheme has to match"
+        );
+        assert_eq!(
+            opts.http_version,
+            Some(HttpVersion::V1_1),
+            "the port has to match, with the default port of the scheme being implied"
+        );
+        assert_eq!(
+            opts.extra_headers,
+            &["ExtraHeader: value2", "ExtraHeader: value3"],
+            "values that aren't overridden are taken from the `http` section"
+        );
+
+        let opts = http_options(&repo, None, "https://user@example.com/org/repo");
+        assert_eq!(
+            opts.user_agent.as_deref(),
+            Some("user"),
+            "at equal host and path, a matching user is more specific"
+        );
+
+        let opts = http_options(&repo, None, "https://example.com/organization");
+        assert_eq!(
+            opts.user_agent.as_deref(),
+            Some("host"),
+            "paths only match at component boundaries"
+        );
+        assert!(opts.ssl_verify);
+
+        let opts = http_options(&repo, None, "https://sub.example.com/org/repo");
+        assert_eq!(
+            opts.user_agent.as_deref(),
+            Some("wildcard-host-and-path"),
+            "wildcards match a single label of the host"
+        );
+
+        let opts = http_options(&repo, None, "https://sub.sub.example.com/org/repo");
+        assert_eq!(opts.user_agent.as_deref(), Some("agentJustForHttp"));
+
+        let opts = http_options(&repo, None, "https://example.com:8443/org/repo");
+        assert_eq!(opts.http_version, Some(HttpVersion::V2));
+        assert_eq!(
+            opts.user_agent.as_deref(),
+            Some("agentJustForHttp"),
+            "a different port doesn't match the default port"
+        );
+
+        let opts = http_options(&repo, None, "http://example.com/org/repo");
+        assert_eq!(opts.proxy.as_deref(), Some("http://other-scheme"));
+    }
+
     #[test]
     fn http_ssl_cainfo_suppressed_by_() {
         let repo = repo("http-disabled-cainfo");
@@ -0,0 +1,81 @@
+use std::sync::atomic::AtomicBool;
+
+use gix::connectivity::{Error, Outcome};
+use gix_odb::Write;
+
+use crate::util::basic_rw_repo;
+
+#[test]
+fn objects_pointed_to_by_references_are_connected() -> crate::Result {
+    let (repo, _tmp) = basic_rw_repo()?;
+    let head = repo.head_id()?.detach();
+    let outcome = repo.check_connectivity(Some(head), None, &AtomicBool::default())?;
+    assert_eq!(
+        outcome,
+        Outcome {
+            num_traversed: 0,
+            num_boundary: 1
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn new_objects_are_traversed_until_reaching_references() -> crate::Result {
+    let (repo, _tmp) = basic_rw_repo()?;
+    let head = repo.head_commit()?;
+    let mut tree: gix::objs::Tree = head.tree()?.decode()?.into();
+    tree.entries.push(gix::objs::tree::Entry {
+        mode: gix::objs::tree::EntryMode::Blob,
+        filename: "zz-new".into(),
+        oid: repo.write_blob("new")?.detach(),
+    });
+    let tree = repo.write_object(&tree)?.detach();
+    let commit = write_commit(&repo, tree, head.id)?;
+
+    let outcome = repo.check_connectivity(Some(commit), None, &AtomicBool::default())?;
+    assert_eq!(
+        outcome,
+        Outcome {
+            num_traversed: 4,
+            num_boundary: 1
+        },
+        "the new commit, its tree and both blobs are traversed, and the parent is connected"
+    );
+    Ok(())
+}
+
+#[test]
+fn missing_objects_are_detected() -> crate::Result {
+    let (repo, _tmp) = basic_rw_repo()?;
+    let missing = gix::ObjectId::from_hex(b"1111111111111111111111111111111111111111")?;
+    let err = repo
+        .check_connectivity(Some(missing), None, &AtomicBool::default())
+        .unwrap_err();
+    assert!(matches!(err, Error::MissingTip { id } if id == missing));
+
+    let mut tree = Vec::new();
+    tree.extend_from_slice(b"100644 file\0");
+    tree.extend_from_slice(missing.as_slice());
+    let tree = repo.objects.write_buf(gix::object::Kind::Tree, &tree)?;
+    let commit = write_commit(&repo, tree, repo.head_id()?.detach())?;
+    let err = repo
+        .check_connectivity(Some(commit), None, &AtomicBool::default())
+        .unwrap_err();
+    assert!(matches!(err, Error::Missing { id, referenced_by } if id == missing && referenced_by == tree));
+    Ok(())
+}
+
+fn write_commit(repo: &gix::Repository, tree: gix::ObjectId, parent: gix::ObjectId) -> crate::Result<gix::ObjectId> {
+    let signature = gix::actor::Signature::default();
+    let commit = gix::objs::Commit {
+        tree,
+        parents: Some(parent).into_iter().collect(),
+        author: signature.clone(),
+        committer: signature,
+        encoding: None,
+        message: "new".into(),
+        extra_headers: Vec::new(),
+    };
+    Ok(repo.write_object(&commit)?.detach())
+}
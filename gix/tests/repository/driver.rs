@@ -0,0 +1,128 @@
+use gix::{
+    bstr::{BStr, ByteSlice},
+    driver::{diff, merge, Registry},
+};
+
+fn resource<'a>(id: &'a gix::oid, data: &'a [u8]) -> diff::Resource<'a> {
+    diff::Resource {
+        id,
+        mode: gix::object::tree::EntryMode::Blob,
+        data,
+    }
+}
+
+#[test]
+fn in_process_drivers_take_precedence_over_commands() -> crate::Result {
+    let repo = crate::named_repo("make_driver_repo.sh")?;
+    let mut registry = Registry::default();
+    registry
+        .register_diff("jupyter", |ctx: diff::Context<'_>| {
+            Ok(format!("{} {}", ctx.rela_path, ctx.new.expect("present").data.as_bstr()).into_bytes())
+        })
+        .register_merge("jupyter", |ctx: merge::Context<'_>| {
+            let mut content = [ctx.base, ctx.ours, ctx.theirs].concat();
+            content.extend_from_slice(ctx.conflict_marker_size.to_string().as_bytes());
+            Ok(merge::Resolution {
+                content,
+                is_conflicted: false,
+            })
+        });
+    let mut drivers = repo.drivers(&registry)?;
+
+    let id = repo.object_hash().null();
+    let outcome = drivers
+        .diff("dir/a.ipynb".into(), None, Some(resource(&id, b"new")))?
+        .expect("driver is registered");
+    assert_eq!(outcome.driver, "jupyter");
+    assert_eq!(
+        outcome.output.as_bstr(),
+        "dir/a.ipynb new",
+        "the configured command would fail, but isn't used"
+    );
+
+    let outcome = drivers
+        .merge("a.ipynb".into(), b"b", b"o", b"t")?
+        .expect("driver is registered");
+    assert_eq!(outcome.driver, "jupyter");
+    assert_eq!(
+        outcome.resolution,
+        merge::Resolution {
+            content: b"bot7".to_vec(),
+            is_conflicted: false
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn configured_commands_are_used_without_in_process_driver() -> crate::Result {
+    let repo = crate::named_repo("make_driver_repo.sh")?;
+    let registry = Registry::default();
+    let mut drivers = repo.drivers(&registry)?;
+
+    let id = repo.object_hash().null();
+    let outcome = drivers
+        .diff("a.ext".into(), Some(resource(&id, b"old\n")), None)?
+        .expect("command is configured");
+    assert_eq!(outcome.driver, "external");
+    assert_eq!(
+        outcome.output.as_bstr(),
+        format!("a.ext {id} 100644 . .\nold\n"),
+        "the missing side is passed as /dev/null"
+    );
+
+    let outcome = drivers
+        .merge("a.ext".into(), b"base\n", b"ours\n", b"theirs\n")?
+        .expect("command is configured");
+    assert_eq!(outcome.driver, "external");
+    assert_eq!(
+        outcome.resolution,
+        merge::Resolution {
+            content: b"base\nours\ntheirs\n10 a.ext\n".to_vec(),
+            is_conflicted: true
+        },
+        "the content of %A is the result, and the exit code indicates conflicts"
+    );
+
+    let err = drivers
+        .diff("a.ipynb".into(), None, Some(resource(&id, b"new")))
+        .unwrap_err();
+    assert!(matches!(err, gix::driver::Error::CommandFailed { .. }));
+    Ok(())
+}
+
+#[test]
+fn builtin_algorithms_are_used_without_driver() -> crate::Result {
+    let mut repo = crate::named_repo("make_driver_repo.sh")?;
+    let mut registry = Registry::default();
+    registry.register_merge("fallback", |_ctx: merge::Context<'_>| {
+        Ok(merge::Resolution {
+            content: b"default".to_vec(),
+            is_conflicted: false,
+        })
+    });
+
+    let id = repo.object_hash().null();
+    {
+        let mut drivers = repo.drivers(&registry)?;
+        for path in ["a.txt", "a.unknown", "unspecified"] {
+            let path: &BStr = path.into();
+            assert_eq!(drivers.diff(path, None, Some(resource(&id, b"new")))?, None, "{path}");
+            assert_eq!(drivers.merge(path, b"", b"", b"")?, None, "{path}");
+        }
+    }
+
+    repo.config_snapshot_mut()
+        .set_raw_value("merge", None, "default", "fallback")?;
+    let mut drivers = repo.drivers(&registry)?;
+    assert_eq!(
+        drivers
+            .merge("unspecified".into(), b"", b"", b"")?
+            .expect("default")
+            .driver,
+        "fallback",
+        "merge.default applies only if the attribute isn't specified"
+    );
+    assert_eq!(drivers.merge("a.txt".into(), b"", b"", b"")?, None);
+    Ok(())
+}
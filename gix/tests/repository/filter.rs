@@ -53,3 +53,159 @@ fn pipeline_with_autocrlf() -> crate::Result {
     }
     Ok(())
 }
+
+mod drivers {
+    use std::path::PathBuf;
+
+    use gix::bstr::ByteSlice;
+    use gix_testtools::once_cell::sync::Lazy;
+
+    static DRIVER: Lazy<PathBuf> = Lazy::new(|| {
+        let mut cargo = std::process::Command::new(env!("CARGO"));
+        let res = cargo
+            .args(["build", "-p=gix-filter", "--example", "arrow"])
+            .status()
+            .expect("cargo should run fine");
+        assert!(res.success(), "cargo invocation should be successful");
+
+        let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR"))
+            .ancestors()
+            .nth(1)
+            .expect("first parent in target dir")
+            .join("debug")
+            .join("examples")
+            .join(if cfg!(windows) { "arrow.exe" } else { "arrow" });
+        assert!(path.is_file(), "Expecting driver to be located at {path:?}");
+        path
+    });
+
+    fn driver_exe() -> String {
+        let mut exe = DRIVER.to_string_lossy().into_owned();
+        if cfg!(windows) {
+            exe = exe.replace('\\', "/");
+        }
+        exe
+    }
+
+    fn repo_rw(required: bool) -> crate::Result<(gix::Repository, gix_testtools::tempfile::TempDir)> {
+        let opts = if required {
+            crate::restricted().config_overrides([
+                "user.name=gitoxide",
+                "user.email=gitoxide@localhost",
+                "filter.broken.required=true",
+            ])
+        } else {
+            crate::restricted()
+        };
+        crate::repo_rw_opts("make_filter_repo.sh", opts)
+    }
+
+    #[test]
+    fn clean_when_staging() -> crate::Result {
+        for required in [false, true] {
+            let (repo, _tmp) = repo_rw(required)?;
+            let work_dir = repo.work_dir().expect("non-bare");
+            std::fs::write(work_dir.join("a.txt"), ">x\n")?;
+            std::fs::write(work_dir.join("b.bin"), "y\n")?;
+
+            let mut stage = repo.stage()?;
+            let id = stage.add_path("a.txt")?;
+            assert_eq!(
+                repo.find_object(id)?.data.as_bstr(),
+                "x\n",
+                "the clean filter removed the prefix"
+            );
+            let res = stage.add_path("b.bin");
+            if required {
+                assert!(res.is_err(), "required filters must succeed");
+            } else {
+                assert_eq!(
+                    repo.find_object(res?)?.data.as_bstr(),
+                    "y\n",
+                    "the filter failed, so the content is used as is"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "status")]
+    fn smudge_when_checking_out() -> crate::Result {
+        for required in [false, true] {
+            let (repo, _tmp) = repo_rw(required)?;
+            let work_dir = repo.work_dir().expect("non-bare");
+            assert_eq!(std::fs::read(work_dir.join("a.txt"))?.as_bstr(), ">a\n");
+
+            let other = repo.find_reference("other")?.peel_to_id_in_place()?.detach();
+            let res = repo.checkout_tree(other, Default::default());
+            if required {
+                assert!(res.is_err(), "required filters must succeed");
+            } else {
+                res?;
+                assert_eq!(
+                    std::fs::read(work_dir.join("a.txt"))?.as_bstr(),
+                    ">c\n",
+                    "the smudge filter added the prefix"
+                );
+                assert_eq!(
+                    std::fs::read(work_dir.join("b.bin"))?.as_bstr(),
+                    "d\n",
+                    "the filter failed, so the content is written as is"
+                );
+                let worktree_changes = repo
+                    .status(Default::default())?
+                    .items
+                    .into_iter()
+                    .filter(|item| item.porcelain_v2_code()[1] != '.')
+                    .count();
+                assert_eq!(
+                    worktree_changes, 0,
+                    "filters are applied when comparing the index with the worktree as well"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "status")]
+    fn long_running_process_when_checking_out() -> crate::Result {
+        for delay in [true, false] {
+            let (repo, _tmp) = crate::repo_rw_opts(
+                "make_filter_repo.sh",
+                crate::restricted().config_overrides([
+                    "user.name=gitoxide".to_string(),
+                    "user.email=gitoxide@localhost".into(),
+                    format!("filter.arrow.process={} process", driver_exe()),
+                    format!("gitoxide.core.filterProcessDelay={delay}"),
+                ]),
+            )?;
+            let work_dir = repo.work_dir().expect("non-bare");
+            std::fs::write(work_dir.join("a.txt"), "➡a\n")?;
+            let mut stage = repo.stage()?;
+            stage.add_path("a.txt")?;
+            stage.write()?;
+
+            let other = repo.find_reference("other")?.peel_to_id_in_place()?.detach();
+            repo.checkout_tree(other, Default::default())?;
+            assert_eq!(
+                std::fs::read(work_dir.join("a.txt"))?.as_bstr(),
+                "➡c\n",
+                "the process is preferred over the clean and smudge programs, and may delay its output"
+            );
+
+            let worktree_changes = repo
+                .status(Default::default())?
+                .items
+                .into_iter()
+                .filter(|item| item.porcelain_v2_code()[1] != '.')
+                .count();
+            assert_eq!(
+                worktree_changes, 0,
+                "the process is used for cleaning as well, and delayed files have their stat information updated"
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,218 @@
+use std::sync::atomic::AtomicBool;
+
+use gix::{
+    fsck::{MessageId, Severity, Subject},
+    refs::transaction::PreviousValue,
+};
+use gix_odb::Write;
+
+use crate::util::{basic_rw_repo, restricted};
+
+fn ids(outcome: &gix::fsck::Outcome) -> Vec<MessageId> {
+    let mut ids: Vec<_> = outcome.findings.iter().map(|finding| finding.id).collect();
+    ids.sort();
+    ids
+}
+
+fn tree(entries: &[(&str, &str, gix::ObjectId)]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for (mode, name, id) in entries {
+        out.extend_from_slice(format!("{mode} {name}\0").as_bytes());
+        out.extend_from_slice(id.as_slice());
+    }
+    out
+}
+
+#[test]
+fn a_consistent_repository_has_no_findings() -> crate::Result {
+    let (repo, _tmp) = basic_rw_repo()?;
+    let outcome = repo.fsck()?.run(gix::progress::Discard, &AtomicBool::default())?;
+    assert_eq!(outcome.findings, Vec::new());
+    assert!(!outcome.has_errors());
+    assert_eq!(outcome.num_objects, 6, "two commits, two trees and two blobs");
+    assert_eq!(outcome.dangling, Vec::new(), "everything is reachable");
+    assert_eq!(outcome.unreachable, Vec::new(), "not enabled by default");
+    Ok(())
+}
+
+#[test]
+fn unreachable_and_dangling_objects() -> crate::Result {
+    let (repo, _tmp) = basic_rw_repo()?;
+    let blob = repo.write_blob("unreachable")?.detach();
+    let tree = repo
+        .objects
+        .write_buf(gix::object::Kind::Tree, &tree(&[("100644", "file", blob)]))?;
+
+    let outcome = repo
+        .fsck()?
+        .with_unreachable(true)
+        .run(gix::progress::Discard, &AtomicBool::default())?;
+    assert_eq!(outcome.findings, Vec::new());
+    assert_eq!(
+        outcome.dangling,
+        [(tree, gix::object::Kind::Tree)],
+        "the blob is referred to by the unreachable tree"
+    );
+    let mut expected = vec![(blob, gix::object::Kind::Blob), (tree, gix::object::Kind::Tree)];
+    expected.sort();
+    assert_eq!(outcome.unreachable, expected);
+
+    let outcome = repo
+        .fsck()?
+        .with_dangling(false)
+        .run(gix::progress::Discard, &AtomicBool::default())?;
+    assert_eq!(outcome.dangling, Vec::new());
+    Ok(())
+}
+
+#[test]
+fn replaced_objects_are_checked_as_stored() -> crate::Result {
+    let (repo, _tmp) = basic_rw_repo()?;
+    let head = repo.head_commit()?;
+    let mut root: gix::objs::Commit = head.decode()?.into();
+    root.parents.clear();
+    root.message = "replaced root".into();
+    let root = repo.write_object(&root)?.detach();
+    repo.replace_object(head.id, root, PreviousValue::MustNotExist)?;
+    let repo = gix::open_opts(repo.git_dir(), restricted())?;
+    assert!(repo.has_replacements());
+
+    let outcome = repo
+        .fsck()?
+        .with_unreachable(true)
+        .run(gix::progress::Discard, &AtomicBool::default())?;
+    assert_eq!(outcome.findings, Vec::new());
+    assert_eq!(
+        outcome.num_objects, 7,
+        "the original objects along with the replacement"
+    );
+    assert_eq!(
+        outcome.unreachable,
+        Vec::new(),
+        "the parent of the replaced commit is still reachable, and the replacement is reachable through its reference"
+    );
+    Ok(())
+}
+
+#[test]
+fn corrupt_trees_and_broken_links() -> crate::Result {
+    let (repo, _tmp) = basic_rw_repo()?;
+    let blob = repo.write_blob("content")?.detach();
+    let missing = gix::ObjectId::from_hex(b"1111111111111111111111111111111111111111")?;
+    let tree = repo.objects.write_buf(
+        gix::object::Kind::Tree,
+        &tree(&[
+            ("100644", ".GIT", blob),
+            ("100644", "b", blob),
+            ("100644", "a", missing),
+            ("100644", "a", blob),
+        ]),
+    )?;
+
+    let outcome = repo.fsck()?.run(gix::progress::Discard, &AtomicBool::default())?;
+    assert_eq!(
+        ids(&outcome),
+        [
+            MessageId::TreeNotSorted,
+            MessageId::DuplicateEntries,
+            MessageId::HasDotgit,
+            MessageId::BrokenLink
+        ]
+    );
+    assert!(outcome.has_errors());
+    for finding in &outcome.findings {
+        assert_eq!(
+            finding.subject,
+            Subject::Object {
+                id: tree,
+                kind: Some(gix::object::Kind::Tree)
+            }
+        );
+    }
+    let broken_link = outcome
+        .findings
+        .iter()
+        .find(|finding| finding.id == MessageId::BrokenLink)
+        .expect("present");
+    assert_eq!(broken_link.severity, Severity::Error);
+    assert_eq!(broken_link.message, format!("broken link from {tree} to {missing}"));
+
+    let outcome = repo
+        .fsck()?
+        .with_severity(MessageId::HasDotgit, Severity::Ignore)
+        .with_severity(MessageId::TreeNotSorted, Severity::Warn)
+        .run(gix::progress::Discard, &AtomicBool::default())?;
+    assert_eq!(
+        ids(&outcome),
+        [
+            MessageId::TreeNotSorted,
+            MessageId::DuplicateEntries,
+            MessageId::BrokenLink
+        ]
+    );
+    let not_sorted = outcome
+        .findings
+        .iter()
+        .find(|finding| finding.id == MessageId::TreeNotSorted)
+        .expect("present");
+    assert_eq!(not_sorted.severity, Severity::Warn);
+    Ok(())
+}
+
+#[test]
+fn broken_references_and_reflogs() -> crate::Result {
+    let (repo, _tmp) = basic_rw_repo()?;
+    let heads = repo.git_dir().join("refs").join("heads");
+    std::fs::write(heads.join("missing"), "1111111111111111111111111111111111111111\n")?;
+    std::fs::write(heads.join("dangling"), "ref: refs/heads/does-not-exist\n")?;
+    let reflog = repo.git_dir().join("logs").join("refs").join("heads").join("main");
+    let mut log = std::fs::read(&reflog)?;
+    let num_lines = log.iter().filter(|b| **b == b'\n').count();
+    log.extend_from_slice(b"this is not a reflog line\n");
+    std::fs::write(&reflog, log)?;
+
+    let outcome = repo.fsck()?.run(gix::progress::Discard, &AtomicBool::default())?;
+    assert_eq!(
+        ids(&outcome),
+        [
+            MessageId::BadRefTarget,
+            MessageId::DanglingSymref,
+            MessageId::BadReflogEntry
+        ]
+    );
+    let subjects: Vec<_> = outcome.findings.iter().map(|finding| finding.subject.clone()).collect();
+    assert!(subjects.contains(&Subject::Reference("refs/heads/missing".try_into()?)));
+    assert!(subjects.contains(&Subject::Reference("refs/heads/dangling".try_into()?)));
+    assert!(subjects.contains(&Subject::Reflog {
+        reference: "refs/heads/main".try_into()?,
+        line: num_lines,
+    }));
+    Ok(())
+}
+
+#[test]
+fn severities_are_configurable() -> crate::Result {
+    let (mut repo, _tmp) = basic_rw_repo()?;
+    let blob = repo.write_blob("content")?.detach();
+    repo.objects
+        .write_buf(gix::object::Kind::Tree, &tree(&[("100644", ".git", blob)]))?;
+
+    repo.config_snapshot_mut()
+        .set_raw_value("fsck", None, "hasDotgit", "error")?;
+    let outcome = repo.fsck()?.run(gix::progress::Discard, &AtomicBool::default())?;
+    assert_eq!(ids(&outcome), [MessageId::HasDotgit]);
+    assert_eq!(outcome.findings[0].severity, Severity::Error);
+
+    repo.config_snapshot_mut()
+        .set_raw_value("fsck", None, "hasDotgit", "IGNORE")?;
+    let outcome = repo.fsck()?.run(gix::progress::Discard, &AtomicBool::default())?;
+    assert_eq!(outcome.findings, Vec::new());
+
+    repo.config_snapshot_mut()
+        .set_raw_value("fsck", None, "hasDotgit", "sometimes")?;
+    assert_eq!(
+        repo.fsck().err().expect("invalid").to_string(),
+        "The value of fsck.hasDotgit must be one of 'error', 'warn', 'info' or 'ignore', got \"sometimes\""
+    );
+    Ok(())
+}
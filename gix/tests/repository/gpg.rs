@@ -0,0 +1,421 @@
+use gix::{
+    bstr::{BStr, BString},
+    gpg::{
+        sign::Signer,
+        verify::{Outcome, Status, Trust},
+        Format,
+    },
+};
+
+const SIGNATURE: &str = "-----BEGIN PGP SIGNATURE-----\n\nc2lnbmF0dXJl\n-----END PGP SIGNATURE-----\n";
+
+fn signed_commit(repo: &gix::Repository) -> crate::Result<(gix::Id<'_>, Vec<u8>)> {
+    let actor = gix::actor::SignatureRef {
+        name: "name".into(),
+        email: "name@example.com".into(),
+        time: gix::date::Time::new(1, 1800),
+    };
+    let mut payload = Vec::new();
+    let id = repo.commit_as_signed(
+        actor,
+        actor,
+        "refs/heads/signed",
+        "signed",
+        repo.head_tree_id()?,
+        gix::commit::NO_PARENT_IDS,
+        |data| {
+            payload = data.to_owned();
+            Ok(SIGNATURE.into())
+        },
+    )?;
+    Ok((id, payload))
+}
+
+fn outcome(status: Status) -> Outcome {
+    Outcome {
+        status,
+        signer: None,
+        key: None,
+        fingerprint: None,
+        primary_key_fingerprint: None,
+        trust: None,
+        output: BString::default(),
+    }
+}
+
+#[test]
+fn commits_and_tags_with_custom_verifier() -> crate::Result {
+    let (repo, _keep) = crate::repo_rw("make_basic_repo.sh")?;
+    let (commit_id, payload) = signed_commit(&repo)?;
+
+    let verifier = |expected_payload: Vec<u8>| {
+        move |signature: &BStr, signed_data: &[u8]| -> Result<Outcome, Box<dyn std::error::Error + Send + Sync>> {
+            assert_eq!(signature, SIGNATURE);
+            assert_eq!(signed_data, expected_payload, "the exact signed data is passed");
+            Ok(outcome(Status::Good))
+        }
+    };
+    let commit = commit_id.object()?.into_commit();
+    let res = commit.verify_signature_with(&verifier(payload))?.expect("signed");
+    assert!(res.is_valid());
+    assert_eq!(
+        repo.head_commit()?.verify_signature_with(&verifier(Vec::new()))?,
+        None,
+        "unsigned commits have nothing to verify"
+    );
+
+    let mut payload = Vec::new();
+    let tag = repo.tag_signed(
+        "signed",
+        commit_id,
+        gix_object::Kind::Commit,
+        None,
+        "message",
+        gix_ref::transaction::PreviousValue::MustNotExist,
+        |data| {
+            payload = data.to_owned();
+            Ok(SIGNATURE.into())
+        },
+    )?;
+    let tag = tag.id().object()?.into_tag();
+    let (signature, signed_data) = tag.signature()?.expect("signed");
+    assert_eq!(signature, SIGNATURE);
+    assert_eq!(signed_data, payload, "the signed data of tags is what was signed");
+    assert!(tag
+        .verify_signature_with(&verifier(payload))?
+        .expect("signed")
+        .is_valid());
+
+    let failing = |_: &BStr, _: &[u8]| -> Result<Outcome, Box<dyn std::error::Error + Send + Sync>> {
+        Err("verifier unavailable".into())
+    };
+    assert!(matches!(
+        commit.verify_signature_with(&failing),
+        Err(gix::gpg::verify::Error::Verify(_))
+    ));
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+fn commits_with_configured_programs() -> crate::Result {
+    use std::os::unix::fs::PermissionsExt;
+
+    let (mut repo, tmp) = crate::repo_rw("make_basic_repo.sh")?;
+    let verifier = repo.signature_verifier()?;
+    assert_eq!(
+        verifier.openpgp,
+        std::path::Path::new("gpg"),
+        "defaults are like in git"
+    );
+    assert_eq!(verifier.x509, std::path::Path::new("gpgsm"));
+    assert_eq!(verifier.min_trust_level, Trust::Undefined);
+
+    let commit_id = signed_commit(&repo)?.0.detach();
+    let write_program = |name: &str, status: &str| -> crate::Result<String> {
+        let path = tmp.path().join(name);
+        std::fs::write(
+            &path,
+            format!(
+                "#!/bin/sh\ngrep -q 'BEGIN PGP SIGNATURE' \"$4\" || exit 2\ncat >/dev/null\n{status}\necho 'gpg: output' >&2\n"
+            ),
+        )?;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+        Ok(path.to_str().expect("valid UTF-8").to_owned())
+    };
+    let good = write_program(
+        "good-gpg",
+        "echo '[GNUPG:] NEWSIG'\n\
+         echo '[GNUPG:] GOODSIG 0123456789ABCDEF Signer <signer@example.com>'\n\
+         echo '[GNUPG:] VALIDSIG FINGERPRINT 2024-01-01 1 0 4 0 22 8 01 PRIMARY'\n\
+         echo '[GNUPG:] TRUST_MARGINAL 0 pgp'",
+    )?;
+    let bad = write_program(
+        "bad-gpg",
+        "echo '[GNUPG:] BADSIG 0123456789ABCDEF Signer <signer@example.com>'",
+    )?;
+
+    repo.config_snapshot_mut()
+        .set_raw_value("gpg", None, "program", good.as_str())?;
+    let res = repo
+        .find_object(commit_id)?
+        .into_commit()
+        .verify_signature()?
+        .expect("signed");
+    assert_eq!(
+        res,
+        Outcome {
+            status: Status::Good,
+            signer: Some("Signer <signer@example.com>".into()),
+            key: Some("0123456789ABCDEF".into()),
+            fingerprint: Some("FINGERPRINT".into()),
+            primary_key_fingerprint: Some("PRIMARY".into()),
+            trust: Some(Trust::Marginal),
+            output: "gpg: output\n".into(),
+        }
+    );
+
+    repo.config_snapshot_mut()
+        .set_raw_value("gpg", None, "minTrustLevel", "fully")?;
+    let res = repo
+        .find_object(commit_id)?
+        .into_commit()
+        .verify_signature()?
+        .expect("signed");
+    assert_eq!(
+        res.status,
+        Status::Untrusted,
+        "good signatures need to be trusted enough to be valid"
+    );
+    assert!(!res.is_valid());
+
+    repo.config_snapshot_mut()
+        .set_raw_value("gpg", Some("openpgp".into()), "program", bad.as_str())?;
+    let res = repo
+        .find_object(commit_id)?
+        .into_commit()
+        .verify_signature()?
+        .expect("signed");
+    assert_eq!(res.status, Status::Bad, "gpg.openpgp.program has precedence");
+    assert_eq!(res.signer, Some("Signer <signer@example.com>".into()));
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+fn ssh_signatures_with_allowed_signers() -> crate::Result {
+    use std::process::Command;
+
+    let (mut repo, tmp) = crate::repo_rw("make_basic_repo.sh")?;
+    let key = tmp.path().join("key");
+    if Command::new("ssh-keygen")
+        .args(["-q", "-t", "ed25519", "-N", "", "-C", "", "-f"])
+        .arg(&key)
+        .status()
+        .map_or(true, |status| !status.success())
+    {
+        eprintln!("Skipping test as ssh-keygen isn't available");
+        return Ok(());
+    }
+    let public_key = std::fs::read_to_string(key.with_extension("pub"))?;
+
+    let actor = gix::actor::SignatureRef {
+        name: "name".into(),
+        email: "name@example.com".into(),
+        time: gix::date::Time::new(1, 1800),
+    };
+    let commit_id = repo
+        .commit_as_signed(
+            actor,
+            actor,
+            "refs/heads/signed",
+            "signed",
+            repo.head_tree_id()?,
+            gix::commit::NO_PARENT_IDS,
+            |data| {
+                let payload = tmp.path().join("payload");
+                std::fs::write(&payload, data)?;
+                let status = Command::new("ssh-keygen")
+                    .args(["-q", "-Y", "sign", "-n", "git", "-f"])
+                    .arg(&key)
+                    .arg(&payload)
+                    .status()?;
+                assert!(status.success());
+                Ok(std::fs::read(payload.with_extension("sig"))?.into())
+            },
+        )?
+        .detach();
+    let verify = |repo: &gix::Repository| -> crate::Result<Outcome> {
+        Ok(repo
+            .find_object(commit_id)?
+            .into_commit()
+            .verify_signature()?
+            .expect("signed"))
+    };
+
+    assert!(
+        verify(&repo).is_err(),
+        "without allowed signers, SSH signatures can't be verified"
+    );
+
+    let allowed_signers = tmp.path().join("allowed_signers");
+    std::fs::write(&allowed_signers, format!("signer@example.com {public_key}"))?;
+    repo.config_snapshot_mut().set_raw_value(
+        "gpg",
+        Some("ssh".into()),
+        "allowedSignersFile",
+        allowed_signers.to_str().expect("valid UTF-8"),
+    )?;
+    let res = verify(&repo)?;
+    assert_eq!(res.status, Status::Good);
+    assert_eq!(res.signer, Some("signer@example.com".into()));
+    assert_eq!(res.trust, Some(Trust::Fully));
+    let fingerprint = res.fingerprint.expect("parsed from the output");
+    assert!(fingerprint.starts_with(b"SHA256:"));
+    assert_eq!(res.key, Some(fingerprint));
+
+    repo.config_snapshot_mut().set_raw_value(
+        "gpg",
+        Some("ssh".into()),
+        "revocationFile",
+        key.with_extension("pub").to_str().expect("valid UTF-8"),
+    )?;
+    assert_eq!(verify(&repo)?.status, Status::Bad, "revoked keys are rejected");
+
+    std::fs::write(&allowed_signers, "")?;
+    let res = verify(&repo)?;
+    assert_eq!(
+        res.status,
+        Status::Untrusted,
+        "good signatures of unknown signers aren't trusted"
+    );
+    assert_eq!(res.signer, None);
+    assert!(!res.is_valid());
+    Ok(())
+}
+
+#[test]
+fn signer_from_configuration() -> crate::Result {
+    let (mut repo, _keep) = crate::repo_rw("make_basic_repo.sh")?;
+    let signer = repo.signer()?;
+    assert_eq!(signer.format, Format::OpenPgp, "the default format is like in git");
+    assert_eq!(signer.program, std::path::Path::new("gpg"));
+    let committer = repo.committer().expect("configured")?;
+    assert_eq!(
+        signer.key,
+        Some(format!("{} <{}>", committer.name, committer.email).into()),
+        "the committer identity is the default key"
+    );
+
+    let mut config = repo.config_snapshot_mut();
+    config.set_raw_value("gpg", None, "format", "ssh")?;
+    config.set_raw_value("gpg", Some("ssh".into()), "program", "custom-ssh-keygen")?;
+    drop(config);
+    let signer = repo.signer()?;
+    assert_eq!(signer.format, Format::Ssh);
+    assert_eq!(signer.program, std::path::Path::new("custom-ssh-keygen"));
+    assert_eq!(signer.key, None, "SSH keys must be configured");
+
+    repo.config_snapshot_mut()
+        .set_raw_value("user", None, "signingKey", "~/.ssh/id_ed25519.pub")?;
+    assert_eq!(repo.signer()?.key, Some("~/.ssh/id_ed25519.pub".into()));
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+fn commits_and_tags_signed_by_configured_program() -> crate::Result {
+    use std::os::unix::fs::PermissionsExt;
+
+    let (mut repo, tmp) = crate::repo_rw("make_basic_repo.sh")?;
+    let program = tmp.path().join("signing-gpg");
+    std::fs::write(
+        &program,
+        "#!/bin/sh\n\
+         test \"$1 $2 $3\" = '--status-fd=2 -bsau key-id' || exit 2\n\
+         cat >/dev/null\n\
+         echo '[GNUPG:] SIG_CREATED D 1 8 00 1700000000 FINGERPRINT' >&2\n\
+         echo '-----BEGIN PGP SIGNATURE-----'\n\
+         echo\n\
+         echo 'c2lnbmF0dXJl'\n\
+         echo '-----END PGP SIGNATURE-----'\n",
+    )?;
+    std::fs::set_permissions(&program, std::fs::Permissions::from_mode(0o755))?;
+    let mut config = repo.config_snapshot_mut();
+    config.set_raw_value("gpg", None, "program", program.to_str().expect("valid UTF-8"))?;
+    config.set_raw_value("user", None, "signingKey", "key-id")?;
+    drop(config);
+
+    let head = repo.head_id()?.detach();
+    let tree = repo.head_tree_id()?.detach();
+    let unsigned = repo.commit("HEAD", "unsigned", tree, Some(head))?.detach();
+    assert_eq!(
+        repo.find_object(unsigned)?.into_commit().signature()?,
+        None,
+        "commits aren't signed by default"
+    );
+
+    repo.config_snapshot_mut()
+        .set_raw_value("commit", None, "gpgSign", "true")?;
+    let signed = repo.commit("HEAD", "signed", tree, Some(unsigned))?.detach();
+    {
+        let commit = repo.find_object(signed)?.into_commit();
+        let (signature, _signed_data) = commit.signature()?.expect("signed as configured");
+        assert_eq!(signature.as_ref(), SIGNATURE);
+    }
+
+    let tag = repo.tag_signed(
+        "signed",
+        signed,
+        gix_object::Kind::Commit,
+        None,
+        "message",
+        gix_ref::transaction::PreviousValue::MustNotExist,
+        {
+            let mut signer = repo.signer()?;
+            move |data: &[u8]| signer.sign(data)
+        },
+    )?;
+    assert_eq!(tag.id().object()?.into_tag().signature()?.expect("signed").0, SIGNATURE);
+    drop(tag);
+
+    repo.config_snapshot_mut()
+        .set_raw_value("user", None, "signingKey", "other-key")?;
+    assert!(
+        matches!(
+            repo.commit("HEAD", "failed", tree, Some(signed)),
+            Err(gix::commit::Error::Sign(_))
+        ),
+        "programs need to indicate success"
+    );
+    assert_eq!(repo.head_id()?, signed, "nothing changes if signing fails");
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+fn commits_signed_with_ssh_keygen() -> crate::Result {
+    use std::process::Command;
+
+    let (mut repo, tmp) = crate::repo_rw("make_basic_repo.sh")?;
+    let key = tmp.path().join("key");
+    if Command::new("ssh-keygen")
+        .args(["-q", "-t", "ed25519", "-N", "", "-C", "", "-f"])
+        .arg(&key)
+        .status()
+        .map_or(true, |status| !status.success())
+    {
+        eprintln!("Skipping test as ssh-keygen isn't available");
+        return Ok(());
+    }
+    let allowed_signers = tmp.path().join("allowed_signers");
+    std::fs::write(
+        &allowed_signers,
+        format!(
+            "signer@example.com {}",
+            std::fs::read_to_string(key.with_extension("pub"))?
+        ),
+    )?;
+    let mut config = repo.config_snapshot_mut();
+    config.set_raw_value("commit", None, "gpgSign", "true")?;
+    config.set_raw_value("gpg", None, "format", "ssh")?;
+    config.set_raw_value("user", None, "signingKey", key.to_str().expect("valid UTF-8"))?;
+    config.set_raw_value(
+        "gpg",
+        Some("ssh".into()),
+        "allowedSignersFile",
+        allowed_signers.to_str().expect("valid UTF-8"),
+    )?;
+    drop(config);
+
+    let head = repo.head_id()?.detach();
+    let id = repo.commit("HEAD", "signed", repo.head_tree_id()?, Some(head))?;
+    let res = id
+        .object()?
+        .into_commit()
+        .verify_signature()?
+        .expect("signed as configured");
+    assert_eq!(res.status, Status::Good);
+    assert_eq!(res.signer, Some("signer@example.com".into()));
+    Ok(())
+}
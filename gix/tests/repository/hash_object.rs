@@ -0,0 +1,57 @@
+use gix::bstr::BStr;
+
+#[test]
+#[cfg(feature = "revision")]
+fn worktree_files_hash_like_their_staged_blobs() -> crate::Result {
+    let repo = crate::basic_repo()?;
+    let mut hash = repo.hash_object()?;
+    let id = hash.path("this")?;
+    assert_eq!(id, repo.rev_parse_single("HEAD:this")?, "the file is unchanged");
+    assert!(hash.path("does-not-exist").is_err(), "missing files can't be hashed");
+    assert_eq!(
+        hash.bytes("", None)?,
+        gix::ObjectId::empty_blob(repo.object_hash()),
+        "the empty blob is computed as well"
+    );
+    Ok(())
+}
+
+#[test]
+fn objects_are_only_written_if_configured() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_basic_repo.sh")?;
+    let id = repo.hash_object()?.bytes("not yet written\n", None)?;
+    assert!(!repo.has_object(id), "by default, ids are only computed");
+
+    let written = repo
+        .hash_object()?
+        .write_objects(true)
+        .bytes("not yet written\n", None)?;
+    assert_eq!(written, id);
+    assert!(repo.has_object(id));
+    Ok(())
+}
+
+#[test]
+fn filters_apply_only_with_path() -> crate::Result {
+    let (mut repo, _tmp) = crate::repo_rw("make_basic_repo.sh")?;
+    repo.config_snapshot_mut()
+        .set_raw_value("core", None, "autocrlf", "true")?;
+    let lf = repo.hash_object()?.bytes("a\nb\n", None)?;
+
+    let mut hash = repo.hash_object()?;
+    let path = Some(BStr::new("file.txt"));
+    assert_eq!(
+        hash.bytes("a\r\nb\r\n", path)?,
+        lf,
+        "CRLF is converted to LF like on `git add`"
+    );
+    assert_ne!(hash.bytes("a\r\nb\r\n", None)?, lf, "without a path, no filters apply");
+
+    let mut hash = repo.hash_object()?.apply_filters(false);
+    assert_ne!(
+        hash.bytes("a\r\nb\r\n", path)?,
+        lf,
+        "filters can be disabled, like with `--no-filters`"
+    );
+    Ok(())
+}
@@ -0,0 +1,199 @@
+#[cfg(unix)]
+mod invoke_hook {
+    use gix::hook::{Invocation, RefUpdate};
+    use gix_testtools::tempfile;
+
+    use crate::{freeze_time, util::restricted_and_git};
+
+    fn write_hook(dir: &std::path::Path, name: &str, script: &str) -> crate::Result {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(name);
+        std::fs::write(&path, format!("#!/bin/sh\n{script}\n"))?;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+        Ok(())
+    }
+
+    fn repo(kind: gix::create::Kind) -> crate::Result<(tempfile::TempDir, gix::Repository)> {
+        let tmp = tempfile::tempdir()?;
+        let repo = gix::ThreadSafeRepository::init_opts(&tmp, kind, Default::default(), restricted_and_git())?
+            .to_thread_local();
+        Ok((tmp, repo))
+    }
+
+    #[test]
+    fn missing_hooks_are_not_run() -> crate::Result {
+        let (_tmp, repo) = repo(gix::create::Kind::WithWorktree)?;
+        assert!(repo.invoke_hook(Invocation::pre_commit())?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn hooks_are_not_run_in_repositories_with_reduced_trust() -> crate::Result {
+        let (_tmp, repo) = repo(gix::create::Kind::WithWorktree)?;
+        write_hook(&repo.hooks_dir()?, "pre-commit", "exit 1")?;
+        assert!(
+            repo.hook_path("pre-commit")?.is_some(),
+            "the hook is found in a fully trusted repository"
+        );
+
+        let repo = gix::open_opts(
+            repo.git_dir(),
+            repo.open_options().clone().with(gix_sec::Trust::Reduced),
+        )?;
+        assert_eq!(repo.git_dir_trust(), gix_sec::Trust::Reduced);
+        assert!(repo.hook_path("pre-commit")?.is_none());
+        assert!(
+            repo.invoke_hook(Invocation::pre_commit())?.is_none(),
+            "hooks aren't run as they are controlled by whoever owns the repository"
+        );
+        let empty_tree_id = repo.write_object(gix::objs::Tree::empty())?;
+        repo.commit_with_hooks("HEAD", "initial\n", empty_tree_id, gix::commit::NO_PARENT_IDS)?;
+        Ok(())
+    }
+
+    #[test]
+    fn receiving_hooks_get_updates_on_stdin_and_run_in_the_git_dir() -> crate::Result {
+        let (_tmp, repo) = repo(gix::create::Kind::Bare)?;
+        write_hook(
+            &repo.hooks_dir()?,
+            "pre-receive",
+            r#"echo "$(pwd -P) $GIT_DIR $GIT_PUSH_OPTION_COUNT $GIT_PUSH_OPTION_0"; cat; echo "error" >&2; exit 3"#,
+        )?;
+
+        let null = gix::ObjectId::null(repo.object_hash());
+        let id = gix::ObjectId::empty_tree(repo.object_hash());
+        let updates = [
+            RefUpdate {
+                name: "refs/heads/main".into(),
+                previous_id: null,
+                new_id: id,
+            },
+            RefUpdate {
+                name: "refs/tags/v1".into(),
+                previous_id: id,
+                new_id: null,
+            },
+        ];
+        let outcome = repo
+            .invoke_hook(
+                Invocation::pre_receive(&updates)
+                    .push_options(Some("ci.skip".into()))
+                    .capture_output(true),
+            )?
+            .expect("hook is installed");
+        assert!(!outcome.is_success());
+        assert_eq!(outcome.path, repo.hooks_dir()?.join("pre-receive"));
+        assert_eq!(
+            outcome.stdout.as_slice(),
+            format!(
+                "{git_dir} {git_dir} 1 ci.skip\n{null} {id} refs/heads/main\n{id} {null} refs/tags/v1\n",
+                git_dir = gix::path::realpath(repo.git_dir())?.display()
+            )
+            .as_bytes(),
+            "the working directory is the git dir, and push options are passed as environment variables"
+        );
+
+        let err = outcome.into_result().unwrap_err();
+        assert_eq!(err.to_string(), "The 'pre-receive' hook failed with exit status: 3");
+        assert_eq!(err.stderr, "error\n", "standard error is available if it was captured");
+        Ok(())
+    }
+
+    #[test]
+    fn hooks_of_all_flows_receive_their_arguments_unchanged() -> crate::Result {
+        let (tmp, repo) = repo(gix::create::Kind::WithWorktree)?;
+        let hooks_dir = tmp.path().join("hooks with 'quotes' and spaces");
+        let repo = gix::open_opts(
+            repo.git_dir(),
+            restricted_and_git().config_overrides([format!("core.hooksPath={}", hooks_dir.display())]),
+        )?;
+        assert_eq!(repo.hooks_dir()?, hooks_dir);
+        for name in ["post-checkout", "post-merge", "pre-push", "update"] {
+            write_hook(&hooks_dir, name, r#"printf '%s|' "$@""#)?;
+        }
+
+        let id = gix::ObjectId::empty_tree(repo.object_hash());
+        let null = gix::ObjectId::null(repo.object_hash());
+        for (invocation, expected) in [
+            (Invocation::post_checkout(null, id, true), format!("{null}|{id}|1|")),
+            (Invocation::post_merge(false), "0|".into()),
+            (
+                Invocation::pre_push("a remote", "file:///path with spaces", None),
+                "a remote|file:///path with spaces|".into(),
+            ),
+            (
+                Invocation::update("refs/heads/with 'quotes'".into(), null, id),
+                format!("refs/heads/with 'quotes'|{null}|{id}|"),
+            ),
+        ] {
+            let outcome = repo
+                .invoke_hook(invocation.capture_output(true))?
+                .expect("hook is installed")
+                .into_result()?;
+            assert_eq!(outcome.stdout.as_slice(), expected.as_bytes());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn hooks_may_ignore_their_input() -> crate::Result {
+        let (_tmp, repo) = repo(gix::create::Kind::WithWorktree)?;
+        write_hook(&repo.hooks_dir()?, "pre-push", r#"echo "$1 $2""#)?;
+
+        let outcome = repo
+            .invoke_hook(
+                Invocation::pre_push("origin", "https://example.com/repo", None)
+                    .stdin(vec![b'x'; 1024 * 1024])
+                    .capture_output(true),
+            )?
+            .expect("hook is installed")
+            .into_result()?;
+        assert_eq!(outcome.stdout.as_slice(), b"origin https://example.com/repo\n");
+        Ok(())
+    }
+
+    #[test]
+    fn hooks_may_write_output_before_reading_their_input() -> crate::Result {
+        let (_tmp, repo) = repo(gix::create::Kind::WithWorktree)?;
+        write_hook(
+            &repo.hooks_dir()?,
+            "pre-push",
+            "head -c 1048576 /dev/zero; wc -c | tr -d ' '",
+        )?;
+
+        let outcome = repo
+            .invoke_hook(
+                Invocation::pre_push("origin", "https://example.com/repo", None)
+                    .stdin(vec![b'x'; 1024 * 1024])
+                    .capture_output(true),
+            )?
+            .expect("hook is installed")
+            .into_result()?;
+        assert_eq!(outcome.stdout.len(), 1024 * 1024 + b"1048576\n".len());
+        assert!(outcome.stdout.ends_with(b"1048576\n"), "all input was received");
+        Ok(())
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn commit_flow_passes_the_message_source() -> crate::Result {
+        let _env = freeze_time();
+        let (_tmp, repo) = repo(gix::create::Kind::WithWorktree)?;
+        let hooks_dir = repo.hooks_dir()?;
+        write_hook(
+            &hooks_dir,
+            "prepare-commit-msg",
+            r#"echo "source: $2 $GIT_EDITOR" >> "$1""#,
+        )?;
+
+        let empty_tree_id = repo.write_object(gix::objs::Tree::empty())?;
+        let commit_id = repo.commit_with_hooks("HEAD", "initial\n", empty_tree_id, gix::commit::NO_PARENT_IDS)?;
+        assert_eq!(
+            commit_id.object()?.into_commit().message_raw()?,
+            "initial\nsource: message :\n",
+            "the editor is disabled as the message is provided"
+        );
+        Ok(())
+    }
+}
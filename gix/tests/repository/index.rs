@@ -0,0 +1,216 @@
+use gix::repository::read_tree;
+
+fn entries(index: &gix::index::State) -> Vec<(String, u32)> {
+    index
+        .entries()
+        .iter()
+        .map(|entry| (entry.path(index).to_string(), entry.stage()))
+        .collect()
+}
+
+fn id(repo: &gix::Repository, spec: &str) -> crate::Result<gix::ObjectId> {
+    Ok(repo.rev_parse_single(spec)?.detach())
+}
+
+/// Open `repo` again to be sure the index that was just written is picked up, even if its modification time didn't change.
+#[cfg(feature = "attributes")]
+fn reopen(repo: &gix::Repository) -> crate::Result<gix::Repository> {
+    Ok(gix::open_opts(repo.work_dir().expect("non-bare"), crate::restricted())?)
+}
+
+fn expected(paths: &[(&str, u32)]) -> Vec<(String, u32)> {
+    paths.iter().map(|(path, stage)| (path.to_string(), *stage)).collect()
+}
+
+#[test]
+fn read_tree_replaces_all_entries_but_keeps_stat_of_unchanged_ones() -> crate::Result {
+    let repo = crate::named_repo("make_read_tree_repo.sh")?;
+    let index = repo.read_tree(id(&repo, "theirs")?, Default::default())?;
+    assert_eq!(
+        entries(&index),
+        expected(&[
+            ("added-by-theirs", 0),
+            ("changed-by-both", 0),
+            ("changed-by-ours", 0),
+            ("changed-by-theirs", 0),
+            ("conflicting", 0),
+            ("dir/file", 0),
+        ])
+    );
+    assert_eq!(
+        index.path(),
+        repo.index_path(),
+        "it would be written to the right place"
+    );
+    let stat = |path: &str| index.entry_by_path(path.into()).expect("present").stat;
+    assert_ne!(
+        stat("dir/file"),
+        Default::default(),
+        "unchanged entries keep their stat"
+    );
+    assert_eq!(
+        stat("conflicting"),
+        Default::default(),
+        "changed entries have to be refreshed"
+    );
+    Ok(())
+}
+
+#[test]
+fn read_tree_with_prefix() -> crate::Result {
+    let repo = crate::named_repo("make_read_tree_repo.sh")?;
+    let index = repo.read_tree(
+        id(&repo, "base")?,
+        read_tree::Options {
+            prefix: Some("sub/dir/".into()),
+        },
+    )?;
+    assert_eq!(index.entries().len(), 12, "existing entries are kept");
+    assert_eq!(
+        index.entry_by_path("sub/dir/dir/file".into()).expect("present").id,
+        id(&repo, "base:dir/file")?
+    );
+
+    for prefix in ["dir", "dir/file/sub"] {
+        let err = repo
+            .read_tree(
+                id(&repo, "base")?,
+                read_tree::Options {
+                    prefix: Some(prefix.into()),
+                },
+            )
+            .unwrap_err();
+        assert!(
+            matches!(err, read_tree::Error::PrefixInUse { .. }),
+            "entries at or leading up to {prefix:?} are never overwritten"
+        );
+    }
+
+    for prefix in ["", "/", "//"] {
+        let err = repo
+            .read_tree(
+                id(&repo, "base")?,
+                read_tree::Options {
+                    prefix: Some(prefix.into()),
+                },
+            )
+            .unwrap_err();
+        assert!(
+            matches!(err, read_tree::Error::EmptyPrefix),
+            "{prefix:?} would produce absolute paths and is rejected"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn two_way_moves_the_index_to_the_target() -> crate::Result {
+    let repo = crate::named_repo("make_read_tree_repo.sh")?;
+    let index = repo.read_tree_two_way(id(&repo, "HEAD")?, id(&repo, "theirs")?)?;
+    assert_eq!(index.entries().len(), 6);
+    for path in ["added-by-theirs", "changed-by-ours", "conflicting"] {
+        assert_eq!(
+            index.entry_by_path(path.into()).expect("present").id,
+            id(&repo, &format!("theirs:{path}"))?
+        );
+    }
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "attributes")]
+fn two_way_keeps_staged_changes_unless_they_would_be_overwritten() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_read_tree_repo.sh")?;
+    let mut stage = repo.stage()?;
+    stage.add_bytes("dir/file", "staged\n", gix::index::entry::Mode::FILE)?;
+    let staged = stage.add_bytes("added-by-theirs", "new\n", gix::index::entry::Mode::FILE)?;
+    stage.write()?;
+    let repo = reopen(&repo)?;
+
+    let index = repo.read_tree_two_way(id(&repo, "HEAD")?, id(&repo, "theirs")?)?;
+    assert_eq!(
+        index.entry_by_path("dir/file".into()).expect("present").id,
+        repo.index()?.entry_by_path("dir/file".into()).expect("present").id,
+        "paths that don't change keep their staged changes"
+    );
+    assert_eq!(
+        index.entry_by_path("added-by-theirs".into()).expect("present").id,
+        staged,
+        "staged changes that match the target are fine"
+    );
+
+    let mut stage = repo.stage()?;
+    stage.add_bytes("conflicting", "staged\n", gix::index::entry::Mode::FILE)?;
+    stage.remove("deleted-by-theirs");
+    stage.write()?;
+    let repo = reopen(&repo)?;
+    match repo.read_tree_two_way(id(&repo, "HEAD")?, id(&repo, "theirs")?) {
+        Err(read_tree::Error::LocalChanges { rela_paths }) => {
+            assert_eq!(
+                rela_paths,
+                ["conflicting"],
+                "staged deletions that match the target are fine"
+            );
+        }
+        res => panic!("expected local changes to be detected, got {res:?}"),
+    }
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "attributes")]
+fn three_way_merges_trivially_or_leaves_conflicts_in_stages() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_read_tree_repo.sh")?;
+    let index = repo.read_tree_three_way(id(&repo, "base")?, id(&repo, "HEAD")?, id(&repo, "theirs")?)?;
+    assert_eq!(
+        entries(&index),
+        expected(&[
+            ("added-by-theirs", 0),
+            ("changed-by-both", 0),
+            ("changed-by-ours", 0),
+            ("changed-by-theirs", 0),
+            ("conflicting", 1),
+            ("conflicting", 2),
+            ("conflicting", 3),
+            ("dir/file", 0),
+        ])
+    );
+    assert_eq!(
+        index.entry_by_path("changed-by-theirs".into()).expect("present").id,
+        id(&repo, "theirs:changed-by-theirs")?
+    );
+
+    let mut stage = repo.stage()?;
+    stage.add_bytes("changed-by-theirs", "staged\n", gix::index::entry::Mode::FILE)?;
+    stage.add_bytes("changed-by-ours", "staged\n", gix::index::entry::Mode::FILE)?;
+    stage.write()?;
+    let repo = reopen(&repo)?;
+    match repo.read_tree_three_way(id(&repo, "base")?, id(&repo, "HEAD")?, id(&repo, "theirs")?) {
+        Err(read_tree::Error::LocalChanges { rela_paths }) => {
+            assert_eq!(
+                rela_paths,
+                ["changed-by-theirs"],
+                "staged changes to paths that don't change are kept"
+            );
+        }
+        res => panic!("expected local changes to be detected, got {res:?}"),
+    }
+
+    let mut index = repo.open_index()?;
+    index.remove_entries(|_, path, _| path == "conflicting");
+    index.dangerously_push_entry(
+        Default::default(),
+        id(&repo, "base:conflicting")?,
+        gix::index::entry::Flags::from_bits_retain(1 << 12),
+        gix::index::entry::Mode::FILE,
+        "conflicting".into(),
+    );
+    index.sort_entries();
+    index.write(Default::default())?;
+    let repo = reopen(&repo)?;
+    assert!(matches!(
+        repo.read_tree_three_way(id(&repo, "base")?, id(&repo, "HEAD")?, id(&repo, "theirs")?),
+        Err(read_tree::Error::Unmerged { rela_path }) if rela_path == "conflicting"
+    ));
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use gix::repository::repo_relative_path::Error;
+
+#[test]
+fn repo_relative_path() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_sparse_repo.sh")?;
+    let work_dir = repo.work_dir().expect("non-bare").to_owned();
+    for (path, expected) in [
+        (work_dir.join("dir1/sub/file"), "dir1/sub/file"),
+        (work_dir.join("dir1/./sub/../file"), "dir1/file"),
+        (work_dir.join("does-not-exist/file"), "does-not-exist/file"),
+        (work_dir.join("dir1/.."), ""),
+        (work_dir.clone(), ""),
+    ] {
+        assert_eq!(repo.repo_relative_path(&path)?, expected, "{path:?}");
+    }
+    assert!(matches!(
+        repo.repo_relative_path(work_dir.join("../other")),
+        Err(Error::OutsideWorktree { .. })
+    ));
+    assert!(matches!(
+        repo.repo_relative_path(work_dir.join("dir1/../..")),
+        Err(Error::OutsideWorktree { .. })
+    ));
+    #[cfg(unix)]
+    assert!(
+        matches!(repo.repo_relative_path("/../other"), Err(Error::BeyondRoot { .. })),
+        "'..' isn't silently ignored if there is nothing left to leave"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+fn repo_relative_path_with_symlinks() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_sparse_repo.sh")?;
+    let work_dir = repo.work_dir().expect("non-bare").to_owned();
+    std::os::unix::fs::symlink("dir1", work_dir.join("link"))?;
+    let aliases = gix_testtools::tempfile::tempdir()?;
+    std::os::unix::fs::symlink(&work_dir, aliases.path().join("alias"))?;
+
+    assert_eq!(
+        repo.repo_relative_path(aliases.path().join("alias/dir1/../dir2/file"))?,
+        "dir2/file",
+        "the worktree can be reached through symlinks"
+    );
+    assert_eq!(
+        repo.repo_relative_path(work_dir.join("link"))?,
+        "link",
+        "symlinks are tracked"
+    );
+    assert!(matches!(
+        repo.repo_relative_path(work_dir.join("link/file")),
+        Err(Error::BeyondSymlink { symlink, .. }) if symlink == "link"
+    ));
+
+    let repo = gix::open_opts(&work_dir, crate::restricted().config_overrides(["core.symlinks=false"]))?;
+    assert_eq!(
+        repo.repo_relative_path(work_dir.join("link/file"))?,
+        "link/file",
+        "without symlink support, there is no symlink to be beyond of"
+    );
+    Ok(())
+}
+
+#[test]
+fn worktree_and_prefix_relative_path() -> crate::Result {
+    let repo = crate::named_repo("make_sparse_repo.sh")?;
+    let work_dir = repo.work_dir().expect("non-bare");
+    let expected = std::env::current_dir()?.join(work_dir).join("dir1").join("file");
+    assert_eq!(repo.worktree_path("dir1/file"), Some(expected.clone()));
+    assert_eq!(
+        repo.prefix_relative_path("dir1/file")?,
+        Some(expected),
+        "the current directory is outside of the worktree, so absolute paths are returned"
+    );
+    Ok(())
+}
@@ -0,0 +1,65 @@
+fn repo() -> crate::Result<gix::Repository> {
+    crate::named_repo("make_ls_files_repo.sh")
+}
+
+fn tracked(platform: &mut gix::ls_files::Platform<'_>) -> Vec<(String, u32)> {
+    platform
+        .cached()
+        .map(|tracked| (tracked.rela_path.to_string(), tracked.stage()))
+        .collect()
+}
+
+#[test]
+fn cached_and_unmerged_entries_with_stages() -> crate::Result {
+    let repo = repo()?;
+    let mut platform = repo.ls_files(None::<&str>)?;
+    assert_eq!(
+        tracked(&mut platform),
+        [
+            (".gitignore", 0),
+            ("a", 0),
+            ("conflict", 1),
+            ("conflict", 2),
+            ("conflict", 3),
+            ("dir/b", 0)
+        ]
+        .map(|(path, stage)| (path.to_owned(), stage))
+    );
+
+    let unmerged: Vec<_> = platform
+        .unmerged()
+        .map(|tracked| (tracked.rela_path.to_string(), tracked.stage(), tracked.entry.id))
+        .collect();
+    assert_eq!(unmerged.len(), 3);
+    assert!(unmerged.iter().all(|(path, _, _)| path == "conflict"));
+    assert_ne!(unmerged[1].2, unmerged[2].2, "our and their version differ");
+    Ok(())
+}
+
+#[test]
+fn others_and_ignored_files_are_classified_separately() -> crate::Result {
+    let repo = repo()?;
+    let mut platform = repo.ls_files(None::<&str>)?;
+    assert_eq!(
+        platform.others()?.collect::<Vec<_>>(),
+        ["dir/untracked", "untracked"],
+        "untracked files are listed individually"
+    );
+    assert_eq!(
+        platform.ignored()?.collect::<Vec<_>>(),
+        ["dir/x.log", "ignored-dir/"],
+        "ignored directories are listed as a whole"
+    );
+    Ok(())
+}
+
+#[test]
+fn pathspecs_limit_all_queries() -> crate::Result {
+    let repo = repo()?;
+    let mut platform = repo.ls_files(Some("dir"))?;
+    assert_eq!(tracked(&mut platform), [("dir/b".to_owned(), 0)]);
+    assert_eq!(platform.unmerged().count(), 0);
+    assert_eq!(platform.others()?.collect::<Vec<_>>(), ["dir/untracked"]);
+    assert_eq!(platform.ignored()?.collect::<Vec<_>>(), ["dir/x.log"]);
+    Ok(())
+}
@@ -0,0 +1,38 @@
+#[test]
+fn load_and_resolve_during_revision_walk() -> crate::Result {
+    let (repo, _keep) = crate::repo_rw("make_basic_repo.sh")?;
+    assert!(
+        repo.mailmap()?.entries().is_empty(),
+        "without any mailmap source, it's empty"
+    );
+
+    let head = repo.head_commit()?;
+    let author = head.author()?.to_owned();
+    let committer = head.committer()?.to_owned();
+    std::fs::write(
+        repo.work_dir().expect("non-bare").join(".mailmap"),
+        format!(
+            "Canonical Name <canonical@example.com> <{}>\nCanonical Name <canonical@example.com> <{}>\n",
+            author.email, committer.email
+        ),
+    )?;
+    let mailmap = repo.mailmap()?;
+    assert_eq!(mailmap.entries().len(), 2, "the .mailmap file in the worktree is read");
+
+    let info = head
+        .id()
+        .ancestors()
+        .with_mailmap(&mailmap)
+        .all()?
+        .next()
+        .expect("head")?;
+    for actual in [info.author()?, info.committer()?] {
+        assert_eq!(actual.name, "Canonical Name");
+        assert_eq!(actual.email, "canonical@example.com");
+    }
+    assert_eq!(info.author()?.time, author.time, "only name and email are changed");
+
+    let info = head.id().ancestors().all()?.next().expect("head")?;
+    assert_eq!(info.author()?, author, "without mailmap, identities are unchanged");
+    Ok(())
+}
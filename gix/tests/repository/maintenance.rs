@@ -0,0 +1,627 @@
+use std::{
+    sync::atomic::AtomicBool,
+    time::{Duration, SystemTime},
+};
+
+use gix::refs::transaction::PreviousValue;
+
+use crate::util::{basic_rw_repo, restricted};
+
+fn in_the_future() -> Option<SystemTime> {
+    Some(SystemTime::now() + Duration::from_secs(60))
+}
+
+fn reopen(repo: &gix::Repository) -> crate::Result<gix::Repository> {
+    Ok(gix::open_opts(repo.git_dir(), restricted())?)
+}
+
+#[test]
+fn run_packs_reachable_objects_and_prunes_unreachable_ones() -> crate::Result {
+    let (repo, _tmp) = basic_rw_repo()?;
+    let unreachable = repo.write_blob("unreachable")?.detach();
+
+    let outcome = repo
+        .maintenance()?
+        .with_prune_expire(in_the_future())
+        .with_reflog_expire(None)
+        .run(gix::progress::Discard, &AtomicBool::default())?;
+    assert_eq!(outcome.expired_reflog_entries, 0);
+    let pack = outcome.repack.pack.expect("reachable objects were packed");
+    assert_eq!(
+        pack.index.num_objects, 6,
+        "two commits, two trees and two blobs are reachable"
+    );
+    assert_eq!(outcome.repack.removed_packs, 0, "there was no pack before");
+    assert_eq!(outcome.repack.removed_loose_objects, 6);
+    assert_eq!(outcome.pruned_objects, 1);
+
+    let repo = reopen(&repo)?;
+    assert!(repo.try_find_object(unreachable)?.is_none());
+    let tree = repo.head_commit()?.tree()?;
+    assert_eq!(tree.find_entry("this").expect("present").object()?.data, b"hello\n");
+    assert_eq!(
+        gix::odb::loose::Store::at(repo.objects.store_ref().path(), repo.object_hash())
+            .iter()
+            .count(),
+        0,
+        "all loose objects are now packed or pruned"
+    );
+    Ok(())
+}
+
+#[test]
+fn objects_reachable_only_through_replaced_objects_are_kept() -> crate::Result {
+    let (repo, _tmp) = basic_rw_repo()?;
+    let head = repo.head_commit()?;
+    let parent = head.parent_ids().next().expect("has a parent").detach();
+    let parent_tree = repo.find_object(parent)?.into_commit().tree_id()?.detach();
+    let root = repo.write_object(&gix::objs::Commit {
+        tree: head.tree_id()?.detach(),
+        parents: Default::default(),
+        author: Default::default(),
+        committer: Default::default(),
+        encoding: None,
+        message: "replaced root".into(),
+        extra_headers: Vec::new(),
+    })?;
+    repo.replace_object(head.id, root, PreviousValue::MustNotExist)?;
+    let repo = reopen(&repo)?;
+    assert!(repo.has_replacements());
+
+    let outcome = repo
+        .maintenance()?
+        .with_prune_expire(in_the_future())
+        .with_cruft_packs(false)
+        .run(gix::progress::Discard, &AtomicBool::default())?;
+    assert_eq!(
+        outcome
+            .repack
+            .pack
+            .expect("reachable objects were packed")
+            .index
+            .num_objects,
+        7,
+        "the original commits and their objects are reachable, along with the replacement and its reference"
+    );
+    assert_eq!(outcome.pruned_objects, 0);
+
+    let repo = reopen(&repo)?;
+    for id in [head.id, parent, parent_tree] {
+        assert!(repo.try_find_object(id)?.is_some(), "{id} was kept");
+    }
+    Ok(())
+}
+
+#[test]
+fn prune_spares_expired_objects_that_were_written_again() -> crate::Result {
+    let (repo, _tmp) = basic_rw_repo()?;
+    let rewritten = repo.write_blob("written again")?.detach();
+    let expired = repo.write_blob("expired")?.detach();
+    std::thread::sleep(Duration::from_millis(20));
+    let cutoff = SystemTime::now();
+    std::thread::sleep(Duration::from_millis(20));
+
+    assert_eq!(
+        repo.write_blob("written again")?,
+        rewritten,
+        "the object exists, and isn't written, but freshened"
+    );
+    assert_eq!(
+        repo.maintenance()?
+            .with_prune_expire(Some(cutoff))
+            .prune(&AtomicBool::default())?,
+        1
+    );
+    let repo = reopen(&repo)?;
+    assert!(repo.try_find_object(expired)?.is_none());
+    assert!(
+        repo.try_find_object(rewritten)?.is_some(),
+        "it may be referred to by objects that aren't reachable yet"
+    );
+    Ok(())
+}
+
+#[test]
+fn packed_objects_written_again_are_freshened_or_loosened_if_in_a_cruft_pack() -> crate::Result {
+    let (repo, _tmp) = basic_rw_repo()?;
+    let cruft = repo.write_blob("unreachable")?.detach();
+    let repack = repo
+        .maintenance()?
+        .with_prune_expire(None)
+        .repack_all(gix::progress::Discard, &AtomicBool::default())?;
+    let pack_path = repack
+        .pack
+        .expect("written")
+        .index_path
+        .expect("on disk")
+        .with_extension("pack");
+    assert_eq!(repack.cruft_pack.expect("written").index.num_objects, 1);
+    let repo = reopen(&repo)?;
+    let loose_objects = || gix::odb::loose::Store::at(repo.objects.store_ref().path(), repo.object_hash());
+    assert!(!loose_objects().contains(&cruft));
+
+    let pack_mtime = || -> std::io::Result<SystemTime> { std::fs::metadata(&pack_path)?.modified() };
+    let previous_mtime = pack_mtime()?;
+    std::thread::sleep(Duration::from_millis(20));
+    let reachable = repo
+        .head_commit()?
+        .tree()?
+        .find_entry("this")
+        .expect("present")
+        .id()
+        .detach();
+    assert_eq!(repo.write_blob("hello\n")?, reachable);
+    assert!(
+        !loose_objects().contains(&reachable),
+        "packed objects aren't written again…"
+    );
+    assert!(pack_mtime()? > previous_mtime, "…but their pack is freshened");
+
+    assert_eq!(repo.write_blob("unreachable")?, cruft);
+    assert!(
+        loose_objects().contains(&cruft),
+        "objects in cruft packs have their own modification time, so they are written as loose objects"
+    );
+    Ok(())
+}
+
+#[test]
+fn repack_all_loosens_unreachable_objects_of_unexpired_packs() -> crate::Result {
+    let (repo, _tmp) = basic_rw_repo()?;
+    let blob = repo.write_blob("only reachable through a tag")?.detach();
+    let tag = repo.reference("refs/tags/blob", blob, PreviousValue::MustNotExist, "tag a blob")?;
+    let repack = repo
+        .maintenance()?
+        .pack_loose_objects(gix::progress::Discard, &AtomicBool::default())?;
+    assert_eq!(repack.pack.expect("written").index.num_objects, 7);
+    assert_eq!(repack.removed_loose_objects, 7);
+    tag.delete()?;
+
+    let repo = reopen(&repo)?;
+    let repack = repo
+        .maintenance()?
+        .with_prune_expire(None)
+        .with_cruft_packs(false)
+        .repack_all(gix::progress::Discard, &AtomicBool::default())?;
+    assert_eq!(repack.pack.expect("written").index.num_objects, 6);
+    assert_eq!(repack.removed_packs, 1, "the previous pack was replaced");
+    assert_eq!(
+        repack.loosened_objects, 1,
+        "the blob isn't reachable anymore, but it's not expired yet"
+    );
+
+    let repo = reopen(&repo)?;
+    assert_eq!(repo.find_object(blob)?.data, b"only reachable through a tag");
+    assert_eq!(
+        repo.maintenance()?
+            .with_prune_expire(in_the_future())
+            .prune(&AtomicBool::default())?,
+        1
+    );
+    assert!(reopen(&repo)?.try_find_object(blob)?.is_none());
+    Ok(())
+}
+
+#[test]
+fn repack_all_keeps_unexpired_unreachable_objects_in_a_cruft_pack() -> crate::Result {
+    let (repo, _tmp) = basic_rw_repo()?;
+    let packed = repo.write_blob("only reachable through a tag")?.detach();
+    let tag = repo.reference("refs/tags/blob", packed, PreviousValue::MustNotExist, "tag a blob")?;
+    repo.maintenance()?
+        .pack_loose_objects(gix::progress::Discard, &AtomicBool::default())?;
+    tag.delete()?;
+    let loose = repo.write_blob("never reachable")?.detach();
+
+    let repo = reopen(&repo)?;
+    let repack = repo
+        .maintenance()?
+        .with_prune_expire(None)
+        .repack_all(gix::progress::Discard, &AtomicBool::default())?;
+    assert_eq!(repack.pack.expect("written").index.num_objects, 6);
+    let cruft_pack = repack.cruft_pack.expect("unreachable objects are kept");
+    assert_eq!(cruft_pack.index.num_objects, 2);
+    assert_eq!(repack.removed_packs, 1);
+    assert_eq!(
+        repack.removed_loose_objects, 1,
+        "the unreachable loose object is now in the cruft pack"
+    );
+    assert_eq!(repack.loosened_objects, 0);
+
+    let mtimes =
+        gix::odb::pack::mtimes::File::at(cruft_pack.index_path.expect("written to disk").with_extension("mtimes"))?;
+    assert_eq!(mtimes.num_objects(), 2);
+    assert_eq!(mtimes.pack_checksum(), cruft_pack.index.data_hash);
+    assert!(
+        mtimes.iter().all(|mtime| mtime > 0),
+        "each object retains its modification time"
+    );
+
+    let repo = reopen(&repo)?;
+    assert_eq!(repo.find_object(packed)?.data, b"only reachable through a tag");
+    assert_eq!(repo.find_object(loose)?.data, b"never reachable");
+
+    let repack = repo
+        .maintenance()?
+        .with_prune_expire(in_the_future())
+        .repack_all(gix::progress::Discard, &AtomicBool::default())?;
+    assert!(repack.cruft_pack.is_none(), "all unreachable objects expired");
+    assert_eq!(
+        repack.removed_packs, 1,
+        "the cruft pack was removed, the pack with reachable objects was rewritten in place"
+    );
+    let repo = reopen(&repo)?;
+    assert!(repo.try_find_object(packed)?.is_none());
+    assert!(repo.try_find_object(loose)?.is_none());
+    Ok(())
+}
+
+#[test]
+fn repack_all_writes_a_reachability_bitmap() -> crate::Result {
+    let (repo, _tmp) = basic_rw_repo()?;
+    let repack = repo
+        .maintenance()?
+        .repack_all(gix::progress::Discard, &AtomicBool::default())?;
+    assert!(
+        repack.bitmap.is_none(),
+        "bitmaps are only written by default in bare repositories"
+    );
+
+    let repack = repo
+        .maintenance()?
+        .with_bitmaps(Some(gix::odb::pack::bitmap::write::Selection::Interval(1)))
+        .repack_all(gix::progress::Discard, &AtomicBool::default())?;
+    let outcome = repack.bitmap.expect("written");
+    assert_eq!(outcome.num_bitmaps, 2, "both commits receive a bitmap");
+
+    let pack = repack.pack.expect("written");
+    let bitmap = gix::odb::pack::bitmap::File::at(
+        pack.index_path.expect("written to disk").with_extension("bitmap"),
+        repo.object_hash(),
+    )?;
+    assert_eq!(bitmap.checksum(), outcome.checksum);
+    assert_eq!(bitmap.pack_checksum(), pack.index.data_hash);
+    let head_reachable = bitmap
+        .index_positions()
+        .filter_map(|index_position| bitmap.reachable_from(index_position))
+        .map(|reachable| reachable.len())
+        .max();
+    assert_eq!(head_reachable, Some(6), "the head commit reaches all objects");
+    Ok(())
+}
+
+#[test]
+fn repack_all_with_delta_islands() -> crate::Result {
+    let (mut repo, _tmp) = basic_rw_repo()?;
+    {
+        let mut config = repo.config_snapshot_mut();
+        config.set_raw_value("repack", None, "useDeltaIslands", "true")?;
+        config.set_raw_value("pack", None, "island", "refs/heads/(.*)")?;
+    }
+    let repack = repo
+        .maintenance()?
+        .repack_all(gix::progress::Discard, &AtomicBool::default())?;
+    assert_eq!(repack.pack.expect("written").index.num_objects, 6);
+
+    repo.config_snapshot_mut()
+        .set_raw_value("pack", None, "island", "refs/heads/(")?;
+    let err = repo
+        .maintenance()?
+        .repack_all(gix::progress::Discard, &AtomicBool::default())
+        .unwrap_err();
+    assert!(
+        matches!(err, gix::maintenance::Error::IslandPattern { ref pattern, .. } if pattern == "refs/heads/("),
+        "{err:?}"
+    );
+    assert!(
+        repo.maintenance()?
+            .with_delta_islands(false)
+            .repack_all(gix::progress::Discard, &AtomicBool::default())
+            .is_ok(),
+        "patterns are only used with delta islands enabled"
+    );
+    Ok(())
+}
+
+#[test]
+fn repack_all_leaves_kept_packs_and_their_objects_alone() -> crate::Result {
+    let (repo, _tmp) = basic_rw_repo()?;
+    let maintenance = repo.maintenance()?;
+    let kept = maintenance
+        .repack_all(gix::progress::Discard, &AtomicBool::default())?
+        .pack
+        .and_then(|pack| pack.index_path)
+        .expect("written to disk");
+    let keep_path = maintenance.keep_pack(&kept, "server maintenance".into())?;
+    assert_eq!(std::fs::read(&keep_path)?, b"server maintenance\n");
+    assert_eq!(maintenance.kept_packs()?, vec![kept.clone()]);
+    assert!(matches!(
+        maintenance.keep_pack(kept.with_file_name("pack-missing.idx"), "".into()),
+        Err(gix::maintenance::Error::MissingPack { .. })
+    ));
+
+    let blob = repo.write_blob("new")?.detach();
+    repo.reference("refs/heads/blob", blob, PreviousValue::Any, "make blob reachable")?;
+    let repack = reopen(&repo)?
+        .maintenance()?
+        .with_bitmaps(Some(Default::default()))
+        .with_pack_kept_objects(false)
+        .repack_all(gix::progress::Discard, &AtomicBool::default())?;
+    assert_eq!(
+        repack.pack.expect("written").index.num_objects,
+        1,
+        "only the new object is packed as all others are in the kept pack"
+    );
+    assert_eq!(repack.removed_packs, 0);
+    assert!(kept.is_file(), "the kept pack is left untouched");
+    assert!(
+        repack.bitmap.is_none(),
+        "bitmaps can't be written as the new pack doesn't contain all reachable objects"
+    );
+
+    let repack = reopen(&repo)?
+        .maintenance()?
+        .with_pack_kept_objects(true)
+        .repack_all(gix::progress::Discard, &AtomicBool::default())?;
+    assert_eq!(repack.pack.expect("written").index.num_objects, 7);
+    assert_eq!(repack.removed_packs, 1, "only the pack that wasn't kept is removed");
+    assert!(kept.is_file());
+
+    assert!(maintenance.unkeep_pack(&kept)?);
+    assert!(!maintenance.unkeep_pack(&kept)?, "there is nothing left to remove");
+    assert!(maintenance.kept_packs()?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn multi_pack_index_write_repack_and_expire() -> crate::Result {
+    let (repo, _tmp) = basic_rw_repo()?;
+    let mut blobs = Vec::new();
+    for content in ["", "first", "second"] {
+        if !content.is_empty() {
+            let blob = repo.write_blob(content)?.detach();
+            repo.reference(
+                format!("refs/tags/{content}"),
+                blob,
+                PreviousValue::MustNotExist,
+                "tag a blob",
+            )?;
+            blobs.push(blob);
+        }
+        repo.maintenance()?
+            .pack_loose_objects(gix::progress::Discard, &AtomicBool::default())?;
+    }
+    let num_packs = |repo: &gix::Repository| -> crate::Result<usize> {
+        Ok(std::fs::read_dir(repo.objects.store_ref().path().join("pack"))?
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().extension().map_or(false, |ext| ext == "pack"))
+            .count())
+    };
+    assert_eq!(num_packs(&repo)?, 3);
+
+    let maintenance = repo.maintenance()?;
+    assert!(
+        maintenance
+            .repack_multi_pack_index(0, gix::progress::Discard, &AtomicBool::default())?
+            .is_none(),
+        "there is no multi-pack-index yet"
+    );
+    assert!(maintenance
+        .write_multi_pack_index(gix::progress::Discard, &AtomicBool::default())?
+        .is_some());
+    let repo = reopen(&repo)?;
+    assert!(
+        repo.objects
+            .store_ref()
+            .structure()?
+            .iter()
+            .any(|record| matches!(record, gix::odb::store::structure::Record::MultiIndex { .. })),
+        "the object database picks up the multi-pack-index for lookups"
+    );
+    assert_eq!(repo.find_object(blobs[0])?.data, b"first");
+
+    let maintenance = repo.maintenance()?;
+    assert!(
+        maintenance
+            .repack_multi_pack_index(u64::MAX, gix::progress::Discard, &AtomicBool::default())?
+            .is_none(),
+        "the packs are too small to fill the batch"
+    );
+    assert_eq!(
+        maintenance.expire_multi_pack_index(gix::progress::Discard, &AtomicBool::default())?,
+        0,
+        "all packs provide objects"
+    );
+    let pack = maintenance
+        .repack_multi_pack_index(0, gix::progress::Discard, &AtomicBool::default())?
+        .expect("all packs were selected");
+    assert_eq!(pack.index.num_objects, 8);
+    assert_eq!(num_packs(&repo)?, 4, "old packs remain until they are expired");
+    assert_eq!(
+        maintenance.expire_multi_pack_index(gix::progress::Discard, &AtomicBool::default())?,
+        3,
+        "all objects are provided by the new pack"
+    );
+    assert_eq!(num_packs(&repo)?, 1);
+
+    let repo = reopen(&repo)?;
+    for (blob, expected) in blobs.into_iter().zip([&b"first"[..], b"second"]) {
+        assert_eq!(repo.find_object(blob)?.data, expected);
+    }
+    assert_eq!(
+        repo.head_commit()?
+            .tree()?
+            .find_entry("this")
+            .expect("present")
+            .object()?
+            .data,
+        b"hello\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn multi_pack_index_chain_layers_are_appended() -> crate::Result {
+    let (repo, _tmp) = basic_rw_repo()?;
+    let add_pack = |repo: &gix::Repository, content: &str| -> crate::Result<gix::ObjectId> {
+        let blob = repo.write_blob(content)?.detach();
+        repo.reference(
+            format!("refs/tags/{content}"),
+            blob,
+            PreviousValue::MustNotExist,
+            "tag a blob",
+        )?;
+        repo.maintenance()?
+            .pack_loose_objects(gix::progress::Discard, &AtomicBool::default())?;
+        Ok(blob)
+    };
+    let num_multi_indices = |repo: &gix::Repository| -> crate::Result<usize> {
+        Ok(reopen(repo)?
+            .objects
+            .store_ref()
+            .structure()?
+            .iter()
+            .filter(|record| matches!(record, gix::odb::store::structure::Record::MultiIndex { .. }))
+            .count())
+    };
+    let first = add_pack(&repo, "first")?;
+    let maintenance = repo.maintenance()?;
+    maintenance.write_multi_pack_index(gix::progress::Discard, &AtomicBool::default())?;
+
+    assert!(maintenance
+        .append_multi_pack_index_layer(gix::progress::Discard, &AtomicBool::default())?
+        .is_some());
+    assert!(
+        !repo.objects.store_ref().path().join("pack/multi-pack-index").exists(),
+        "the chain supersedes the single multi-pack-index"
+    );
+    let second = add_pack(&repo, "second")?;
+    assert!(maintenance
+        .append_multi_pack_index_layer(gix::progress::Discard, &AtomicBool::default())?
+        .is_some());
+    assert!(
+        maintenance
+            .append_multi_pack_index_layer(gix::progress::Discard, &AtomicBool::default())?
+            .is_none(),
+        "all packs are already part of the chain"
+    );
+    assert_eq!(num_multi_indices(&repo)?, 2, "each layer is used for lookups");
+
+    let repo = reopen(&repo)?;
+    assert_eq!(repo.find_object(first)?.data, b"first");
+    assert_eq!(repo.find_object(second)?.data, b"second");
+    assert_eq!(
+        repo.head_commit()?
+            .tree()?
+            .find_entry("this")
+            .expect("present")
+            .object()?
+            .data,
+        b"hello\n"
+    );
+
+    repo.maintenance()?
+        .write_multi_pack_index(gix::progress::Discard, &AtomicBool::default())?;
+    assert_eq!(
+        num_multi_indices(&repo)?,
+        1,
+        "a single multi-pack-index replaces the chain"
+    );
+    Ok(())
+}
+
+#[test]
+fn expire_reflogs() -> crate::Result {
+    let (repo, _tmp) = basic_rw_repo()?;
+    let maintenance = repo.maintenance()?;
+    assert_eq!(
+        maintenance.with_reflog_expire(None).expire_reflogs()?,
+        0,
+        "reflogs never expire"
+    );
+
+    let removed = repo
+        .maintenance()?
+        .with_reflog_expire(in_the_future())
+        .expire_reflogs()?;
+    assert!(removed >= 2, "both commits on main are logged");
+    let head_log = repo.find_reference("refs/heads/main")?;
+    assert_eq!(head_log.log_iter().all()?.expect("log file still exists").count(), 0);
+    Ok(())
+}
+
+#[test]
+fn remove_stale_tempfiles() -> crate::Result {
+    let (repo, _tmp) = basic_rw_repo()?;
+    let objects_dir = repo.objects.store_ref().path().to_owned();
+    std::fs::create_dir_all(objects_dir.join("pack"))?;
+    std::fs::write(objects_dir.join("tmp_obj_abc"), b"")?;
+    std::fs::write(objects_dir.join("pack").join("tmp_pack_abc"), b"")?;
+    std::fs::write(objects_dir.join("pack").join(".tmp-123-pack"), b"")?;
+
+    assert_eq!(repo.maintenance()?.remove_stale_tempfiles()?, 0, "tempfiles are recent");
+    assert_eq!(
+        repo.maintenance()?
+            .with_prune_expire(in_the_future())
+            .remove_stale_tempfiles()?,
+        3
+    );
+    assert!(!objects_dir.join("tmp_obj_abc").exists());
+    assert!(objects_dir.join("info").is_dir(), "other files remain untouched");
+    Ok(())
+}
+
+#[test]
+fn all_tasks_fail_in_read_only_repositories() -> crate::Result {
+    let (repo, _tmp) = basic_rw_repo()?;
+    let unreachable = repo.write_blob("unreachable")?.detach();
+    let repo = gix::open_opts(repo.git_dir(), restricted().read_only(true))?;
+    let maintenance = repo.maintenance()?.with_prune_expire(in_the_future());
+    let interrupt = AtomicBool::default();
+    let is_read_only =
+        |res: Result<_, gix::maintenance::Error>| matches!(res, Err(gix::maintenance::Error::ReadOnly(_)));
+
+    assert!(is_read_only(
+        maintenance.run(gix::progress::Discard, &interrupt).map(|_| ())
+    ));
+    assert!(is_read_only(maintenance.expire_reflogs().map(|_| ())));
+    assert!(is_read_only(
+        maintenance
+            .pack_loose_objects(gix::progress::Discard, &interrupt)
+            .map(|_| ())
+    ));
+    assert!(is_read_only(
+        maintenance.repack_all(gix::progress::Discard, &interrupt).map(|_| ())
+    ));
+    assert!(is_read_only(maintenance.prune(&interrupt).map(|_| ())));
+    assert!(is_read_only(maintenance.remove_stale_tempfiles().map(|_| ())));
+    let pack = repo.objects.store_ref().path().join("pack").join("pack-fake.idx");
+    assert!(is_read_only(maintenance.keep_pack(&pack, "reason".into()).map(|_| ())));
+    assert!(is_read_only(maintenance.unkeep_pack(&pack).map(|_| ())));
+    assert!(is_read_only(
+        maintenance
+            .write_multi_pack_index(gix::progress::Discard, &interrupt)
+            .map(|_| ())
+    ));
+    assert!(is_read_only(
+        maintenance
+            .expire_multi_pack_index(gix::progress::Discard, &interrupt)
+            .map(|_| ())
+    ));
+    assert!(is_read_only(
+        maintenance
+            .append_multi_pack_index_layer(gix::progress::Discard, &interrupt)
+            .map(|_| ())
+    ));
+    assert!(is_read_only(
+        maintenance
+            .repack_multi_pack_index(0, gix::progress::Discard, &interrupt)
+            .map(|_| ())
+    ));
+
+    assert!(repo.try_find_object(unreachable)?.is_some(), "nothing was pruned");
+    assert!(
+        !repo.objects.store_ref().path().join("pack").read_dir()?.any(|_| true),
+        "no pack was written"
+    );
+    Ok(())
+}
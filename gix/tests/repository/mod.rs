@@ -1,18 +1,59 @@
 use gix::Repository;
 
+#[cfg(feature = "revision")]
+mod cat_file;
+#[cfg(feature = "attributes")]
+mod check_attr;
+#[cfg(feature = "excludes")]
+mod check_ignore;
+#[cfg(feature = "status")]
+mod checkout;
+#[cfg(feature = "status")]
+mod clean;
 mod config;
+mod connectivity;
+#[cfg(feature = "attributes")]
+mod driver;
 #[cfg(feature = "attributes")]
 mod filter;
+#[cfg(feature = "fsck")]
+mod fsck;
+mod gpg;
+#[cfg(feature = "attributes")]
+mod hash_object;
+mod hook;
+#[cfg(all(feature = "index", feature = "revision"))]
+mod index;
+mod location;
+#[cfg(feature = "status")]
+mod ls_files;
+#[cfg(all(feature = "mailmap", feature = "revision"))]
+mod mailmap;
+#[cfg(feature = "maintenance")]
+mod maintenance;
+#[cfg(feature = "revision")]
+mod notes;
 mod object;
 mod open;
 #[cfg(feature = "attributes")]
 mod pathspec;
 mod reference;
 mod remote;
+mod replace;
+#[cfg(feature = "revision")]
+mod replay;
 mod shallow;
+#[cfg(feature = "status")]
+mod sparse;
+#[cfg(feature = "attributes")]
+mod stage;
 mod state;
+#[cfg(feature = "status")]
+mod status;
 #[cfg(feature = "attributes")]
 mod submodule;
+#[cfg(feature = "revision")]
+mod subtree;
 mod worktree;
 
 #[test]
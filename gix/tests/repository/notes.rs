@@ -0,0 +1,164 @@
+use gix::{
+    bstr::ByteSlice,
+    notes::{merge, Error},
+};
+
+fn note(notes: &gix::notes::Platform<'_>, target: gix_hash::ObjectId) -> crate::Result<Option<String>> {
+    Ok(notes.find(target)?.map(|blob| blob.data.to_str_lossy().into_owned()))
+}
+
+#[test]
+fn add_find_iter_and_remove() -> crate::Result {
+    let (repo, _keep) = crate::repo_rw("make_basic_repo.sh")?;
+    let head = repo.head_id()?.detach();
+    let tree = repo.head_tree_id()?.detach();
+    let notes = repo.notes()?;
+    assert_eq!(
+        notes.notes_ref().as_bstr(),
+        "refs/notes/commits",
+        "the default notes reference"
+    );
+    assert_eq!(note(&notes, head)?, None);
+    assert!(notes.iter()?.is_empty());
+    assert_eq!(notes.remove(head)?, None, "there is nothing to remove");
+
+    let first = notes.add(head, "first\n", false)?;
+    assert_eq!(note(&notes, head)?.as_deref(), Some("first\n"));
+    assert!(
+        matches!(notes.add(head, "second\n", false), Err(Error::Exists { target }) if target == head),
+        "notes aren't overwritten by default"
+    );
+    let second = notes.add(head, "second\n", true)?;
+    assert_eq!(note(&notes, head)?.as_deref(), Some("second\n"));
+    assert_eq!(
+        second.object()?.into_commit().parent_ids().collect::<Vec<_>>(),
+        [first],
+        "each change is recorded as commit on top of the previous one"
+    );
+
+    notes.add(tree, "tree note\n", false)?;
+    let all = notes.iter()?;
+    assert_eq!(
+        all.iter().map(|note| note.target).collect::<Vec<_>>(),
+        {
+            let mut expected = vec![head, tree];
+            expected.sort();
+            expected
+        },
+        "all notes are returned, sorted by target"
+    );
+
+    assert!(notes.remove(head)?.is_some());
+    assert_eq!(note(&notes, head)?, None);
+    assert_eq!(notes.remove(head)?, None);
+    assert_eq!(notes.iter()?.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn fanout_is_read_and_followed() -> crate::Result {
+    let (repo, _keep) = crate::repo_rw("make_basic_repo.sh")?;
+    let head = repo.head_id()?.detach();
+    let tree = repo.head_tree_id()?.detach();
+
+    let fanout_path = |id: gix_hash::ObjectId| {
+        let hex = id.to_hex().to_string();
+        format!("{}/{}", &hex[..2], &hex[2..])
+    };
+    let blob = repo.write_blob("fanned out\n")?;
+    let mut editor = repo.empty_tree().edit()?;
+    editor.upsert(fanout_path(head).as_str(), gix_object::tree::EntryMode::Blob, blob)?;
+    let notes_tree = editor.write()?;
+    repo.commit(
+        "refs/notes/commits",
+        "fanned out notes",
+        notes_tree,
+        gix::commit::NO_PARENT_IDS,
+    )?;
+
+    let notes = repo.notes()?;
+    assert_eq!(note(&notes, head)?.as_deref(), Some("fanned out\n"));
+    assert_eq!(notes.iter()?.len(), 1);
+
+    let commit = notes.add(tree, "tree note\n", false)?;
+    assert!(
+        commit
+            .object()?
+            .into_commit()
+            .tree()?
+            .lookup_entry_by_path(fanout_path(tree), &mut Vec::new())?
+            .is_some(),
+        "new notes follow the existing fan-out"
+    );
+
+    notes.remove(head)?;
+    assert_eq!(notes.iter()?.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn merge() -> crate::Result {
+    let (repo, _keep) = crate::repo_rw("make_basic_repo.sh")?;
+    let head = repo.head_id()?.detach();
+    let tree = repo.head_tree_id()?.detach();
+    let ours = repo.notes()?;
+    let theirs = repo.notes()?.with_ref("refs/notes/theirs".try_into()?);
+
+    assert!(matches!(
+        ours.merge("refs/notes/theirs", merge::Strategy::Union),
+        Err(merge::Error::NotFound { .. })
+    ));
+
+    let base = theirs.add(head, "base\n", false)?;
+    assert_eq!(
+        ours.merge("refs/notes/theirs", merge::Strategy::Union)?,
+        merge::Outcome::FastForward { commit: base.detach() },
+        "without notes of our own, we fast-forward"
+    );
+    assert_eq!(
+        ours.merge("refs/notes/theirs", merge::Strategy::Union)?,
+        merge::Outcome::AlreadyUpToDate
+    );
+
+    ours.add(head, "ours\n", true)?;
+    theirs.add(head, "theirs\n", true)?;
+    theirs.add(tree, "tree note\n", false)?;
+    let commit = match ours.merge("refs/notes/theirs", merge::Strategy::Union)? {
+        merge::Outcome::Merged { commit } => commit,
+        other => unreachable!("a merge is needed, got {other:?}"),
+    };
+    assert_eq!(
+        repo.find_object(commit)?.into_commit().parent_ids().count(),
+        2,
+        "merges have both notes commits as parents"
+    );
+    assert_eq!(
+        note(&ours, head)?.as_deref(),
+        Some("ours\n\ntheirs\n"),
+        "conflicting notes are concatenated"
+    );
+    assert_eq!(
+        note(&ours, tree)?.as_deref(),
+        Some("tree note\n"),
+        "notes that changed only on their side are taken"
+    );
+    assert_eq!(
+        ours.merge("refs/notes/theirs", merge::Strategy::Union)?,
+        merge::Outcome::AlreadyUpToDate
+    );
+
+    ours.add(head, "b\na\n", true)?;
+    theirs.add(head, "a\nc\n", true)?;
+    ours.merge("refs/notes/theirs", merge::Strategy::CatSortUniq)?;
+    assert_eq!(
+        note(&ours, head)?.as_deref(),
+        Some("a\nb\nc\n"),
+        "lines are sorted and deduplicated"
+    );
+
+    ours.add(head, "mine\n", true)?;
+    theirs.add(head, "yours\n", true)?;
+    ours.merge("refs/notes/theirs", merge::Strategy::Ours)?;
+    assert_eq!(note(&ours, head)?.as_deref(), Some("mine\n"));
+    Ok(())
+}
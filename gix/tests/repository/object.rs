@@ -52,6 +52,53 @@ mod write_blob {
         );
         Ok(())
     }
+
+    #[test]
+    fn from_stream_with_size() -> crate::Result {
+        let (_tmp, repo) = empty_bare_repo()?;
+        let oid = repo.write_blob_stream_with_size(&b"hello world"[..], 11)?;
+        assert_eq!(oid, hex_to_id("95d09f2b10159347eece71399a7e2e907ea3df4f"));
+        assert_eq!(oid.object()?.data, b"hello world");
+
+        assert!(
+            repo.write_blob_stream_with_size(&b"hello world"[..], 12).is_err(),
+            "the stream must yield exactly the given amount of bytes"
+        );
+        assert!(repo.write_blob_stream_with_size(&b"hello world"[..], 10).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn loose_objects_are_compressed_as_configured() -> crate::Result {
+        let tmp = gix_testtools::tempfile::tempdir()?;
+        let data = "hello world\n".repeat(100);
+        let mut sizes = Vec::new();
+        for (dir, overrides) in [
+            ("default", vec![]),
+            ("core", vec!["core.compression=0"]),
+            ("loose", vec!["core.compression=9", "core.looseCompression=0"]),
+        ] {
+            let repo: gix::Repository = gix::ThreadSafeRepository::init_opts(
+                tmp.path().join(dir),
+                gix::create::Kind::Bare,
+                gix::create::Options::default(),
+                gix::open::Options::isolated().config_overrides(overrides),
+            )?
+            .into();
+            let id = repo.write_blob(data.as_bytes())?;
+            assert_eq!(id.object()?.data, data.as_bytes());
+            sizes.push(std::fs::metadata(repo.objects.store_ref().path().join(loose_path(id.detach())))?.len());
+        }
+        assert!(sizes[0] < data.len() as u64, "loose objects are compressed by default");
+        assert!(sizes[1] > data.len() as u64, "level 0 stores the object as is");
+        assert_eq!(sizes[2], sizes[1], "core.looseCompression has precedence");
+        Ok(())
+    }
+
+    fn loose_path(id: gix::ObjectId) -> std::path::PathBuf {
+        let hex = id.to_hex().to_string();
+        std::path::Path::new(&hex[..2]).join(&hex[2..])
+    }
 }
 
 #[test]
@@ -108,6 +155,137 @@ fn writes_avoid_io_using_duplicate_check() -> crate::Result {
     Ok(())
 }
 
+mod quarantine_objects {
+    use crate::util::basic_rw_repo;
+
+    #[test]
+    fn objects_become_visible_once_migrated() -> crate::Result {
+        let (repo, _tmp) = basic_rw_repo()?;
+        let head_id = repo.head_id()?.detach();
+        let (quarantined, quarantine) = repo.quarantine_objects()?;
+        assert!(quarantined.has_object(head_id), "all existing objects are visible");
+
+        let new = quarantined.write_blob(b"new")?.detach();
+        assert!(
+            !repo.has_object(new),
+            "new objects aren't visible in the original repository"
+        );
+        assert_eq!(quarantined.objects.store_ref().path(), quarantine.path());
+
+        let outcome = quarantine.migrate()?;
+        assert_eq!(outcome.loose_objects, 1);
+        assert!(repo.has_object(new));
+        Ok(())
+    }
+
+    #[test]
+    fn read_only_repositories_refuse_to_create_one() -> crate::Result {
+        let (repo, _tmp) = basic_rw_repo()?;
+        let repo = gix::open_opts(repo.git_dir(), crate::restricted().read_only(true))?;
+        assert!(matches!(
+            repo.quarantine_objects(),
+            Err(gix::repository::quarantine_objects::Error::ReadOnly(_))
+        ));
+        assert!(
+            !repo
+                .objects
+                .store_ref()
+                .path()
+                .read_dir()?
+                .any(|entry| entry.map_or(false, |entry| entry.file_name().to_string_lossy().starts_with("tmp_"))),
+            "no quarantine directory was created"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn objects_are_discarded_on_drop() -> crate::Result {
+        let (repo, _tmp) = basic_rw_repo()?;
+        let (quarantined, quarantine) = repo.quarantine_objects()?;
+        let new = quarantined.write_blob(b"new")?.detach();
+        drop(quarantine);
+        assert!(!repo.has_object(new));
+        Ok(())
+    }
+}
+
+mod alternates {
+    use crate::util::basic_rw_repo;
+
+    #[test]
+    fn add_and_remove() -> crate::Result {
+        let (repo, _tmp) = basic_rw_repo()?;
+        let (other, _other_tmp) = basic_rw_repo()?;
+        let alternate = other.objects.store_ref().path();
+        assert!(repo.add_alternate(alternate)?);
+        assert!(!repo.add_alternate(alternate)?, "it's listed already");
+        assert!(repo.objects.store_ref().path().join("info/alternates").is_file());
+
+        let read_only = gix::open_opts(repo.git_dir(), crate::restricted().read_only(true))?;
+        assert!(matches!(
+            read_only.remove_alternate(alternate),
+            Err(gix::repository::edit_alternates::Error::ReadOnly(_))
+        ));
+        assert!(matches!(
+            read_only.add_alternate(alternate),
+            Err(gix::repository::edit_alternates::Error::ReadOnly(_))
+        ));
+
+        assert!(repo.remove_alternate(alternate)?);
+        assert!(!repo.remove_alternate(alternate)?, "it's gone already");
+        Ok(())
+    }
+}
+
+mod objects_stats {
+    use crate::util::basic_rw_repo;
+
+    #[test]
+    fn packed_and_loose() -> crate::Result {
+        let repo = crate::named_repo("make_packed_and_loose.sh")?;
+        let stats = repo.objects_stats()?;
+        assert_eq!(stats.loose_objects, 3);
+        assert_eq!(stats.prune_packable, 0);
+        assert_eq!(stats.packs, 1);
+        assert_eq!(stats.packed_objects, 6);
+        assert_eq!(stats.packs_with_bitmap, 0);
+        assert!(!stats.has_multi_pack_index);
+        assert!(stats.loose_size > 0 && stats.packs_size > 0);
+        assert_eq!(stats.garbage, Vec::<std::path::PathBuf>::new());
+        assert_eq!(stats.garbage_size, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn garbage() -> crate::Result {
+        let (repo, _tmp) = basic_rw_repo()?;
+        let objects_dir = repo.objects.store_ref().path().to_owned();
+        let pack_dir = objects_dir.join("pack");
+        std::fs::create_dir_all(&pack_dir)?;
+        let some_object = objects_dir.join("ab").join("not-an-object");
+        std::fs::create_dir_all(some_object.parent().expect("fan-out"))?;
+        std::fs::write(&some_object, b"12")?;
+        std::fs::write(pack_dir.join("pack-1234.pack"), b"345")?;
+        std::fs::write(pack_dir.join("pack-1234.keep"), b"")?;
+        std::fs::write(pack_dir.join("tmp_pack_abc"), b"")?;
+
+        let stats = repo.objects_stats()?;
+        assert_eq!(stats.loose_objects, 6);
+        assert_eq!(stats.packs, 0, "a pack without index doesn't count");
+        assert_eq!(
+            stats.garbage,
+            [
+                some_object,
+                pack_dir.join("pack-1234.pack"),
+                pack_dir.join("tmp_pack_abc"),
+            ],
+            "the .keep file belongs to a pack, even though it's garbage"
+        );
+        assert_eq!(stats.garbage_size, 5);
+        Ok(())
+    }
+}
+
 mod find {
     use gix_pack::Find;
 
@@ -220,6 +398,96 @@ mod tag {
         assert_eq!(tag.message, message);
         Ok(())
     }
+
+    #[test]
+    fn signed() -> crate::Result {
+        let (repo, _keep) = crate::repo_rw("make_basic_repo.sh")?;
+        let current_head_id = repo.head_id()?;
+        let signature = "-----BEGIN PGP SIGNATURE-----\n\nsigned\n-----END PGP SIGNATURE-----\n";
+        let mut payload = Vec::new();
+        let tag_ref = repo.tag_signed(
+            "v1.0.0",
+            current_head_id,
+            gix_object::Kind::Commit,
+            Some(repo.committer().expect("present")?),
+            "message",
+            gix_ref::transaction::PreviousValue::MustNotExist,
+            |data| {
+                payload = data.to_owned();
+                Ok(signature.into())
+            },
+        )?;
+        let tag = tag_ref.id().object()?;
+        assert_eq!(
+            &tag.data[..payload.len()],
+            payload.as_slice(),
+            "the signature is appended to the signed data"
+        );
+        let tag = tag.try_to_tag_ref()?;
+        assert_eq!(tag.message, "message");
+        assert_eq!(tag.pgp_signature.expect("signed"), signature);
+        Ok(())
+    }
+
+    #[test]
+    fn create_annotated_lightweight_and_forced() -> crate::Result {
+        use gix::tag::create::{Error, Options};
+        let (repo, _keep) = crate::repo_rw("make_basic_repo.sh")?;
+        let head_id = repo.head_id()?;
+        let tree_id = head_id.object()?.into_commit().tree_id()?;
+
+        let tag_ref = repo.create_tag("annotated", tree_id, "message", Options::default())?;
+        let tag = tag_ref.id().object()?;
+        let tag = tag.try_to_tag_ref()?;
+        assert_eq!(tag.target(), tree_id);
+        assert_eq!(
+            tag.target_kind,
+            gix_object::Kind::Tree,
+            "the kind of the target is looked up"
+        );
+        assert_eq!(
+            tag.tagger.as_ref().expect("tagger").actor(),
+            repo.committer().expect("present")?.actor(),
+            "the committer is the default tagger"
+        );
+
+        let num_objects_before = repo.objects.iter()?.count();
+        let err = repo
+            .create_tag("annotated", head_id, "message", Options::default())
+            .unwrap_err();
+        assert!(matches!(err, Error::Exists { .. }), "{err:?}");
+        assert_eq!(
+            repo.objects.iter()?.count(),
+            num_objects_before,
+            "no object is written if the tag can't be created"
+        );
+
+        let tag_ref = repo.create_tag(
+            "annotated",
+            head_id,
+            "ignored",
+            Options {
+                force: true,
+                lightweight: true,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(tag_ref.id(), head_id, "lightweight tags point to the target directly");
+
+        let signature = "-----BEGIN PGP SIGNATURE-----\n\nsigned\n-----END PGP SIGNATURE-----\n";
+        let tag_ref = repo.create_tag(
+            "signed",
+            head_id,
+            "message",
+            Options {
+                sign: Some(Box::new(|_data| Ok(signature.into()))),
+                ..Default::default()
+            },
+        )?;
+        let tag = tag_ref.id().object()?;
+        assert_eq!(tag.try_to_tag_ref()?.pgp_signature.expect("signed"), signature);
+        Ok(())
+    }
 }
 
 mod commit_as {
@@ -263,6 +531,61 @@ mod commit_as {
         assert_eq!(commit.author()?, author.to_ref());
         Ok(())
     }
+
+    #[test]
+    fn signed() -> crate::Result {
+        let tmp = tempfile::tempdir()?;
+        let repo = gix::ThreadSafeRepository::init_opts(
+            &tmp,
+            gix::create::Kind::WithWorktree,
+            Default::default(),
+            restricted_and_git(),
+        )?
+        .to_thread_local();
+        let empty_tree = repo.empty_tree();
+        let actor = gix::actor::Signature {
+            name: "c".into(),
+            email: "c@example.com".into(),
+            time: gix::date::Time::new(1, 1800),
+        };
+
+        let err = repo
+            .commit_as_signed(
+                &actor,
+                &actor,
+                "HEAD",
+                "initial",
+                empty_tree.id,
+                gix::commit::NO_PARENT_IDS,
+                |_| Err("signing service unavailable".into()),
+            )
+            .unwrap_err();
+        assert!(matches!(err, gix::commit::Error::Sign(_)));
+        assert!(repo.head()?.is_unborn(), "nothing is written if signing fails");
+
+        let mut payload = Vec::new();
+        let commit_id = repo.commit_as_signed(
+            &actor,
+            &actor,
+            "HEAD",
+            "initial",
+            empty_tree.id,
+            gix::commit::NO_PARENT_IDS,
+            |data| {
+                payload = data.to_owned();
+                Ok("multi\nline\nsignature".into())
+            },
+        )?;
+        let commit = commit_id.object()?.into_commit();
+        let (signature, signed_data) = commit.signature()?.expect("signed");
+        assert_eq!(signature.as_ref(), "multi\nline\nsignature");
+        assert_eq!(
+            signed_data.to_bstring(),
+            payload,
+            "the signed data is exactly what was signed"
+        );
+        Ok(())
+    }
 }
 
 mod commit {
@@ -395,6 +718,100 @@ mod commit {
         );
         Ok(())
     }
+
+    #[cfg(unix)]
+    fn write_hook(dir: &std::path::Path, name: &str, script: &str) -> crate::Result {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(name);
+        std::fs::write(&path, format!("#!/bin/sh\n{script}\n"))?;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    #[serial_test::serial]
+    fn with_hooks_can_have_its_message_altered_and_runs_post_commit() -> crate::Result {
+        let _env = freeze_time();
+        let tmp = tempfile::tempdir()?;
+        let repo = gix::ThreadSafeRepository::init_opts(
+            &tmp,
+            gix::create::Kind::WithWorktree,
+            Default::default(),
+            restricted_and_git(),
+        )?
+        .to_thread_local();
+        let hooks_dir = repo.hooks_dir()?;
+        assert_eq!(
+            hooks_dir,
+            repo.git_dir().join("hooks"),
+            "hooks are in the git dir by default"
+        );
+        write_hook(&hooks_dir, "commit-msg", r#"echo "Signed-off-by: hook" >> "$1""#)?;
+        write_hook(&hooks_dir, "post-commit", "touch post-commit-ran")?;
+
+        let empty_tree_id = repo.write_object(gix::objs::Tree::empty())?;
+        let commit_id = repo.commit_with_hooks("HEAD", "initial\n\n", empty_tree_id, gix::commit::NO_PARENT_IDS)?;
+        assert_eq!(
+            commit_id.object()?.into_commit().message_raw()?,
+            "initial\n\nSigned-off-by: hook\n",
+            "the message as altered by the `commit-msg` hook is used"
+        );
+        assert_eq!(repo.head_id()?, commit_id, "HEAD was updated");
+        assert!(
+            tmp.path().join("post-commit-ran").is_file(),
+            "hooks run in the work tree, and post-commit ran after the commit"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    #[serial_test::serial]
+    fn with_hooks_is_aborted_if_a_hook_fails() -> crate::Result {
+        let _env = freeze_time();
+        let tmp = tempfile::tempdir()?;
+        let mut repo = gix::ThreadSafeRepository::init_opts(
+            &tmp,
+            gix::create::Kind::WithWorktree,
+            Default::default(),
+            restricted_and_git(),
+        )?
+        .to_thread_local();
+        repo.config_snapshot_mut()
+            .set_raw_value("core", None, "hooksPath", "custom-hooks")?;
+        let hooks_dir = repo.hooks_dir()?;
+        assert_eq!(
+            hooks_dir,
+            tmp.path().join("custom-hooks"),
+            "relative hook paths are relative to the work tree"
+        );
+        write_hook(&hooks_dir, "pre-commit", "exit 1")?;
+
+        let empty_tree_id = repo.write_object(gix::objs::Tree::empty())?;
+        let err = repo
+            .commit_with_hooks("HEAD", "initial", empty_tree_id, gix::commit::NO_PARENT_IDS)
+            .unwrap_err();
+        assert!(
+            matches!(err, gix::commit::Error::HookRejected { name: "pre-commit", .. }),
+            "{err:?}"
+        );
+        assert!(repo.head()?.is_unborn(), "nothing was committed");
+
+        std::fs::set_permissions(
+            hooks_dir.join("pre-commit"),
+            std::os::unix::fs::PermissionsExt::from_mode(0o644),
+        )?;
+        assert_eq!(
+            repo.hook_path("pre-commit")?,
+            None,
+            "hooks that aren't executable are ignored"
+        );
+        repo.commit_with_hooks("HEAD", "initial", empty_tree_id, gix::commit::NO_PARENT_IDS)?;
+        assert!(!repo.head()?.is_unborn());
+        Ok(())
+    }
 }
 
 fn empty_bare_repo() -> crate::Result<(tempfile::TempDir, gix::Repository)> {
@@ -27,6 +27,127 @@ fn none_bare_repo_without_index() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn read_only_refuses_writes() -> crate::Result {
+    let (repo, _keep) = crate::repo_rw_opts("make_basic_repo.sh", crate::restricted().read_only(true))?;
+    assert!(repo.is_read_only());
+
+    let err = repo.write_blob(b"hello").unwrap_err();
+    assert!(
+        err.to_string().contains("read-only mode"),
+        "objects can't be written: {err}"
+    );
+
+    let err = repo
+        .reference(
+            "refs/heads/new",
+            repo.head_id()?,
+            gix::refs::transaction::PreviousValue::MustNotExist,
+            "would create",
+        )
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("read-only mode"),
+        "references can't be edited: {err}"
+    );
+    assert!(
+        repo.try_find_reference("refs/heads/new")?.is_none(),
+        "nothing was written"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(all(feature = "index", feature = "parallel"))]
+fn index_cache_shares_index_between_independently_opened_repositories() -> crate::Result {
+    let cache = gix::worktree::IndexCache::default();
+    let open = |cache: Option<&gix::worktree::IndexCache>| {
+        let mut opts = crate::restricted();
+        if let Some(cache) = cache {
+            opts = opts.index_cache(cache.clone());
+        }
+        named_subrepo_opts("make_basic_repo.sh", "", opts)
+    };
+    let (a, b) = (open(Some(&cache))?, open(Some(&cache))?);
+    assert_eq!(cache.len(), 1, "both repositories share the same index storage");
+    assert!(
+        gix::threading::OwnShared::ptr_eq(&a.index()?, &b.index()?),
+        "the index is decoded only once"
+    );
+
+    let c = open(None)?;
+    assert!(
+        !gix::threading::OwnShared::ptr_eq(&a.index()?, &c.index()?),
+        "without a shared cache, each repository has its own index"
+    );
+
+    cache.clear();
+    assert!(cache.is_empty());
+    assert!(
+        gix::threading::OwnShared::ptr_eq(&a.index()?, &b.index()?),
+        "repositories that were opened before keep sharing"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(all(feature = "index", feature = "parallel"))]
+fn index_cache_forgets_least_recently_used_indices() -> crate::Result {
+    let cache = gix::worktree::IndexCache::with_capacity(1);
+    let open = |name: &str| {
+        named_subrepo_opts(
+            "make_basic_repo.sh",
+            name,
+            crate::restricted().index_cache(cache.clone()),
+        )
+    };
+    let a = open("")?;
+    assert_eq!(cache.len(), 1);
+    let b = open("non-bare-repo-without-index")?;
+    assert_eq!(cache.len(), 1, "the index of the first repository was forgotten");
+    assert!(a.index().is_ok(), "repositories keep their index storage");
+    assert!(b.index_or_empty().is_ok());
+
+    let c = open("")?;
+    assert!(
+        !gix::threading::OwnShared::ptr_eq(&a.index()?, &c.index()?),
+        "the index isn't shared anymore after it was forgotten"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(all(feature = "index", feature = "parallel"))]
+fn index_cache_keeps_recently_used_indices() -> crate::Result {
+    let cache = gix::worktree::IndexCache::with_capacity(2);
+    let open = |name: &str| {
+        named_subrepo_opts(
+            "make_basic_repo.sh",
+            name,
+            crate::restricted().index_cache(cache.clone()),
+        )
+    };
+    let a = open("")?;
+    let _b = open("non-bare-repo-without-index")?;
+    let _b_used_again = open("non-bare-repo-without-index")?;
+    let _c = open("bare.git")?;
+    assert_eq!(cache.len(), 2);
+    let a_reopened = open("")?;
+    assert!(
+        !gix::threading::OwnShared::ptr_eq(&a.index()?, &a_reopened.index()?),
+        "the least recently used index was forgotten"
+    );
+
+    let _b_used_again = open("non-bare-repo-without-index")?;
+    let _a_used_again = open("")?;
+    let _c = open("bare.git")?;
+    assert!(
+        gix::threading::OwnShared::ptr_eq(&a_reopened.index()?, &open("")?.index()?),
+        "the index that was used more recently is still shared"
+    );
+    Ok(())
+}
+
 #[test]
 fn non_bare_split_worktree() -> crate::Result {
     for (name, worktree_exists) in [
@@ -231,6 +352,150 @@ mod object_caches {
         assert!(!repo.objects.has_pack_cache());
         Ok(())
     }
+
+    #[test]
+    #[cfg(feature = "max-performance-safe")]
+    fn shared_between_clones_and_reset_from_configuration() -> crate::Result {
+        let opts = gix::open::Options::isolated();
+        let mut repo = named_subrepo_opts("make_config_repos.sh", "disabled-object-caches", opts)?;
+        repo.shared_object_cache_size(64 * 1024);
+        repo.shared_pack_cache_size(64 * 1024);
+        let empty_tree = gix::ObjectId::empty_tree(repo.object_hash());
+
+        let clone = repo.clone();
+        assert!(clone.objects.has_object_cache());
+        assert!(clone.objects.has_pack_cache());
+        assert_eq!(
+            clone.find_object(empty_tree)?.data,
+            repo.find_object(empty_tree)?.data,
+            "both handles see the same objects"
+        );
+
+        repo.pack_cache_size(0);
+        assert!(!repo.objects.has_pack_cache());
+        repo.reset_caches_from_configuration();
+        assert!(!repo.objects.has_object_cache(), "configuration disables all caches");
+        assert!(!repo.objects.has_pack_cache());
+        Ok(())
+    }
+}
+
+mod sha256 {
+    use crate::util::repo_rw;
+
+    #[test]
+    #[cfg(all(feature = "revision", feature = "index"))]
+    fn read_objects_refs_and_index() -> crate::Result {
+        let repo = crate::util::named_repo("make_sha256_repo.sh")?;
+        assert_eq!(repo.object_hash(), gix::hash::Kind::Sha256);
+
+        let head = repo.head_commit()?;
+        assert_eq!(head.id.kind(), gix::hash::Kind::Sha256);
+        assert_eq!(
+            repo.rev_parse_single("v1^{commit}")?,
+            head.id,
+            "annotated tags can be peeled"
+        );
+        let parent = repo.rev_parse_single("@~1")?.object()?.into_commit();
+        assert_eq!(parent.message_raw()?, "first\n", "the parent is read from a pack");
+
+        let tree = head.tree()?;
+        let entry = tree.lookup_entry_by_path("dir/c", &mut Vec::new())?.expect("present");
+        assert_eq!(entry.object()?.data, b"c\n");
+        let entry = parent
+            .tree()?
+            .lookup_entry_by_path("dir/b", &mut Vec::new())?
+            .expect("present");
+        assert_eq!(
+            entry.object()?.data,
+            b"b\n",
+            "packed trees and blobs are decoded as well"
+        );
+
+        let index = repo.index()?;
+        assert_eq!(index.entries().len(), 3);
+        assert!(index.entries().iter().all(|e| e.id.kind() == gix::hash::Kind::Sha256));
+        Ok(())
+    }
+
+    #[test]
+    fn write_objects() -> crate::Result {
+        let (repo, _tmp) = repo_rw("make_sha256_repo.sh")?;
+        let id = repo.write_blob(b"new")?;
+        assert_eq!(id.kind(), gix::hash::Kind::Sha256);
+        assert_eq!(repo.find_object(id)?.data, b"new");
+
+        let tree = gix::objs::Tree {
+            entries: vec![gix::objs::tree::Entry {
+                mode: gix::objs::tree::EntryMode::Blob,
+                filename: "new".into(),
+                oid: id.detach(),
+            }],
+        };
+        let tree_id = repo.write_object(&tree)?;
+        let commit_id = repo.commit("HEAD", "third", tree_id, Some(repo.head_id()?))?;
+        let commit = repo.find_object(commit_id)?.into_commit();
+        assert_eq!(commit.tree_id()?, tree_id);
+        assert_eq!(
+            commit.tree()?.find_entry("new").expect("present").object_id(),
+            id,
+            "trees are written and read with the longer hash"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn written_objects_are_mapped_to_their_compat_ids() -> crate::Result {
+        use gix::objs::WriteTo;
+        let (repo, _tmp) = repo_rw("make_sha256_compat_repo.sh")?;
+        assert_eq!(repo.compat_object_hash(), Some(gix::hash::Kind::Sha1));
+        assert!(
+            repo.compat_map()?.is_none(),
+            "objects written by git without support for it aren't mapped"
+        );
+
+        let blob_id = repo.write_blob(b"new")?;
+        let compat_blob_id = gix::objs::compute_hash(gix::hash::Kind::Sha1, gix::object::Kind::Blob, b"new");
+        assert_eq!(repo.to_compat_id(blob_id)?, Some(compat_blob_id));
+        assert_eq!(repo.from_compat_id(compat_blob_id)?, Some(blob_id.detach()));
+
+        let streamed_id = repo.write_blob_stream_with_size(&b"streamed"[..], 8)?;
+        assert_eq!(
+            repo.to_compat_id(streamed_id)?,
+            Some(gix::objs::compute_hash(
+                gix::hash::Kind::Sha1,
+                gix::object::Kind::Blob,
+                b"streamed"
+            )),
+            "streamed blobs are hashed once more while reading"
+        );
+
+        let tree = |oid| gix::objs::Tree {
+            entries: vec![gix::objs::tree::Entry {
+                mode: gix::objs::tree::EntryMode::Blob,
+                filename: "new".into(),
+                oid,
+            }],
+        };
+        let tree_id = repo.write_object(tree(blob_id.detach()))?;
+        let mut buf = Vec::new();
+        tree(compat_blob_id).write_to(&mut buf)?;
+        let compat_tree_id = gix::objs::compute_hash(gix::hash::Kind::Sha1, gix::object::Kind::Tree, &buf);
+        assert_eq!(
+            repo.to_compat_id(tree_id)?,
+            Some(compat_tree_id),
+            "ids referred to by trees are translated before hashing"
+        );
+
+        let reopened = gix::open_opts(repo.path(), gix::open::Options::isolated())?;
+        assert_eq!(
+            reopened.compat_map()?.expect("configured").len(),
+            3,
+            "the mapping is persisted"
+        );
+        assert_eq!(reopened.from_compat_id(compat_tree_id)?, Some(tree_id.detach()));
+        Ok(())
+    }
 }
 
 mod worktree {
@@ -148,6 +148,31 @@ mod iter_references {
         Ok(())
     }
 
+    #[test]
+    fn matching() -> crate::Result {
+        use gix::bstr::ByteSlice;
+        let repo = repo()?;
+        let names = |include: &[&str], exclude: &[&str]| -> crate::Result<Vec<String>> {
+            let include: Vec<_> = include.iter().map(|p| p.as_bytes().as_bstr()).collect();
+            let exclude: Vec<_> = exclude.iter().map(|p| p.as_bytes().as_bstr()).collect();
+            Ok(repo
+                .references()?
+                .matching(&include, &exclude)?
+                .filter_map(Result::ok)
+                .map(|r| r.name().as_bstr().to_string())
+                .collect())
+        };
+        assert_eq!(
+            names(&["refs/heads/d*", "refs/tags/*1"], &["refs/heads/dt1"])?,
+            ["refs/heads/d1", "refs/tags/dt1", "refs/tags/t1"]
+        );
+        assert_eq!(
+            names(&["refs/remotes"], &["refs/remotes/origin/multi-*"])?,
+            ["refs/remotes/origin/HEAD", "refs/remotes/origin/main"]
+        );
+        Ok(())
+    }
+
     #[test]
     fn prefixed_and_peeled() -> crate::Result {
         let repo = repo()?;
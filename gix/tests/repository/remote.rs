@@ -63,9 +63,26 @@ mod remote_at {
         assert_eq!(remote.url(Direction::Fetch).unwrap().to_bstring(), rewritten_fetch_url);
         assert_eq!(
             remote.url(Direction::Push).unwrap().to_bstring(),
-            "ssh://dev/null",
-            "push-url rewrite rules are applied"
+            "file://dev/null",
+            "push-url rewrite rules don't apply to explicit push urls"
+        );
+
+        let remote = repo
+            .remote_at("file://host/repo")?
+            .push_url("https://github.com/foobar/gitoxide")?;
+        assert_eq!(
+            remote.url(Direction::Push).unwrap().to_bstring(),
+            rewritten_fetch_url,
+            "…but the fetch url rewrite rules do"
         );
+
+        let remote = repo.remote_at("file://host/repo")?;
+        assert_eq!(
+            remote.url(Direction::Push).unwrap().to_bstring(),
+            "ssh://host/repo",
+            "push-url rewrite rules apply to the fetch url if it's used for pushing"
+        );
+        assert_eq!(remote.url(Direction::Fetch).unwrap().to_bstring(), "file://host/repo");
         Ok(())
     }
 
@@ -197,24 +214,39 @@ mod find_remote {
         let expected_push_url: BString = baseline.next().expect("push").into();
 
         let remote = repo.find_remote("origin")?;
-        assert_eq!(remote.url(Direction::Fetch).unwrap().to_bstring(), expected_fetch_url,);
-        {
-            let actual_push_url = remote.url(Direction::Push).unwrap().to_bstring();
-            assert_ne!(
-                actual_push_url, expected_push_url,
-                "here we actually resolve something that git doesn't probably because it's missing the host. Our parser is OK with it for some reason."
-            );
-            assert_eq!(actual_push_url, "ssh://dev/null", "file:// gets replaced actually");
-        }
+        assert_eq!(remote.url(Direction::Fetch).unwrap().to_bstring(), expected_fetch_url);
+        assert_eq!(
+            remote.url(Direction::Push).unwrap().to_bstring(),
+            expected_push_url,
+            "push urls are only subject to insteadOf, which doesn't match here"
+        );
+        assert_eq!(
+            remote.url_as_configured(Direction::Fetch).unwrap().to_bstring(),
+            "https://github.com/foobar/gitoxide",
+            "the original url is still available"
+        );
 
-        let mut remote = repo.try_find_remote_without_url_rewrite("origin").expect("exists")?;
+        let expected_fetch_url: BString = baseline.next().expect("fetch").into();
+        let expected_push_url: BString = baseline.next().expect("push").into();
+        let remote = repo.find_remote("push-rewrite")?;
+        assert_eq!(remote.url(Direction::Fetch).unwrap().to_bstring(), expected_fetch_url);
         assert_eq!(
-            remote.url(Direction::Fetch).unwrap().to_bstring(),
-            "https://github.com/foobar/gitoxide"
+            remote.url(Direction::Push).unwrap().to_bstring(),
+            expected_push_url,
+            "pushInsteadOf applies to the fetch url when pushing"
         );
-        assert_eq!(remote.url(Direction::Push).unwrap().to_bstring(), "file://dev/null");
+        assert_eq!(expected_push_url, "ssh://host/repo");
+        assert_eq!(
+            remote.url_as_configured(Direction::Push).unwrap().to_bstring(),
+            "file://host/repo"
+        );
+
+        let mut remote = repo
+            .try_find_remote_without_url_rewrite("push-rewrite")
+            .expect("exists")?;
+        assert_eq!(remote.url(Direction::Push).unwrap().to_bstring(), "file://host/repo");
         remote.rewrite_urls()?;
-        assert_eq!(remote.url(Direction::Push).unwrap().to_bstring(), "ssh://dev/null");
+        assert_eq!(remote.url(Direction::Push).unwrap().to_bstring(), "ssh://host/repo");
         Ok(())
     }
 
@@ -227,15 +259,15 @@ mod find_remote {
         let expected_fetch_url: BString = baseline.next().expect("fetch").into();
         let expected_push_url: BString = baseline.next().expect("push").into();
         assert_eq!(
-            expected_push_url, "file://dev/null",
-            "git leaves the failed one as is without any indication…"
+            expected_push_url, "invalid:://foobar/gitoxide",
+            "git doesn't validate the rewritten url…"
         );
         assert_eq!(
             expected_fetch_url, "https://github.com/byron/gitoxide",
             "…but is able to replace the fetch url successfully"
         );
 
-        let expected_err_msg = "The rewritten push url \"invalid:://dev/null\" failed to parse";
+        let expected_err_msg = "The rewritten push url \"invalid:://foobar/gitoxide\" failed to parse";
         assert_eq!(
             repo.find_remote("origin").unwrap_err().to_string(),
             expected_err_msg,
@@ -0,0 +1,93 @@
+use gix::refs::transaction::PreviousValue;
+
+use crate::util::{basic_rw_repo, restricted};
+
+fn reopen(repo: &gix::Repository) -> crate::Result<gix::Repository> {
+    Ok(gix::open_opts(repo.git_dir(), restricted())?)
+}
+
+#[test]
+fn replaced_objects_are_looked_up_in_repositories_opened_afterwards() -> crate::Result {
+    let (repo, _tmp) = basic_rw_repo()?;
+    let original = repo.write_blob(b"original")?.detach();
+    let replacement = repo.write_blob(b"replacement")?.detach();
+    assert!(!repo.has_replacements());
+
+    let reference = repo.replace_object(original, replacement, PreviousValue::MustNotExist)?;
+    assert_eq!(reference.name().as_bstr(), format!("refs/replace/{original}"));
+    assert_eq!(
+        repo.find_object(original)?.data,
+        b"original",
+        "replacements are read when opening the repository"
+    );
+
+    let repo = reopen(&repo)?;
+    assert_eq!(repo.replacements().collect::<Vec<_>>(), [(original, replacement)]);
+    assert_eq!(repo.find_object(original)?.data, b"replacement");
+    assert_eq!(repo.find_header(original)?.size(), 11);
+
+    let mut opts = restricted();
+    opts = opts.config_overrides(Some("core.useReplaceRefs=false"));
+    let no_replace = gix::open_opts(repo.git_dir(), opts)?;
+    assert!(!no_replace.has_replacements());
+    assert_eq!(
+        no_replace.find_object(original)?.data,
+        b"original",
+        "replacements can be turned off"
+    );
+
+    assert_eq!(repo.remove_replacement(original)?, Some(replacement));
+    assert_eq!(
+        repo.remove_replacement(original)?,
+        None,
+        "there is nothing left to remove"
+    );
+    let repo = reopen(&repo)?;
+    assert!(!repo.has_replacements());
+    assert_eq!(repo.find_object(original)?.data, b"original");
+    Ok(())
+}
+
+#[test]
+fn only_objects_of_the_same_kind_can_be_replaced() -> crate::Result {
+    let (repo, _tmp) = basic_rw_repo()?;
+    let blob = repo.write_blob(b"blob")?.detach();
+    let head = repo.head_id()?.detach();
+    assert!(matches!(
+        repo.replace_object(head, blob, PreviousValue::Any),
+        Err(gix::repository::replace_object::Error::KindMismatch { .. })
+    ));
+    assert!(matches!(
+        repo.replace_object(blob, blob, PreviousValue::Any),
+        Err(gix::repository::replace_object::Error::SameObject { .. })
+    ));
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "revision")]
+fn traversals_see_replaced_commits_even_with_a_commit_graph() -> crate::Result {
+    let (repo, _tmp) = basic_rw_repo()?;
+    let head = repo.head_commit()?;
+    assert!(gix_testtools::run_git(
+        repo.work_dir().expect("non-bare"),
+        &["commit-graph", "write", "--reachable"]
+    )?
+    .success());
+    let count = |repo: &gix::Repository| -> crate::Result<usize> { Ok(repo.rev_walk(Some(head.id)).all()?.count()) };
+    assert_eq!(count(&repo)?, 2);
+
+    let root = repo.write_object(&gix::objs::Commit {
+        tree: head.tree_id()?.detach(),
+        parents: Default::default(),
+        author: Default::default(),
+        committer: Default::default(),
+        encoding: None,
+        message: "replaced root".into(),
+        extra_headers: Vec::new(),
+    })?;
+    repo.replace_object(head.id, root, PreviousValue::MustNotExist)?;
+    let repo = reopen(&repo)?;
+    assert_eq!(count(&repo)?, 1, "the replacement has no parents");
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use gix::{bstr::ByteSlice, replay::Outcome};
+
+fn id(repo: &gix::Repository, spec: &str) -> crate::Result<gix::ObjectId> {
+    Ok(repo.rev_parse_single(spec)?.detach())
+}
+
+#[test]
+fn commits_are_replayed_onto_new_base() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_replay_repo.sh")?;
+    let (main, topic) = (id(&repo, "main")?, id(&repo, "topic")?);
+    let outcome = repo.replay(main, id(&repo, "topic~2")?, topic)?;
+    let (tip, commits) = match outcome {
+        Outcome::Replayed { tip, commits } => (tip, commits),
+        Outcome::Conflicts { .. } => unreachable!("there are no conflicts"),
+    };
+    assert_eq!(
+        commits.iter().map(|(original, _)| *original).collect::<Vec<_>>(),
+        [id(&repo, "topic~1")?, topic],
+        "commits are replayed oldest first"
+    );
+    assert_eq!(commits[1].1, tip);
+
+    let replayed = repo.find_object(tip)?.into_commit();
+    let original = repo.find_object(topic)?.into_commit();
+    assert_eq!(replayed.message_raw()?, original.message_raw()?);
+    assert_eq!(replayed.author()?, original.author()?);
+    assert_eq!(
+        replayed.parent_ids().map(gix::Id::detach).collect::<Vec<_>>(),
+        [commits[0].1]
+    );
+    assert_eq!(repo.find_object(commits[0].1)?.into_commit().parent_ids().count(), 1);
+
+    let tree = replayed.tree()?;
+    for (path, content) in [("a", "a2\n"), ("b", "b2\n"), ("t", "t\n")] {
+        let entry = tree.lookup_entry_by_path(path, &mut Vec::new())?.expect("present");
+        assert_eq!(entry.object()?.data.as_bstr(), content, "{path}");
+    }
+    assert_eq!(
+        repo.head_id()?,
+        main,
+        "references aren't changed, the worktree isn't touched"
+    );
+    Ok(())
+}
+
+#[test]
+fn already_applied_commits_are_dropped_and_conflicts_are_reported() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_replay_repo.sh")?;
+    let (main, base) = (id(&repo, "main")?, id(&repo, "main~1")?);
+
+    let applied = id(&repo, "applied")?;
+    assert_eq!(
+        repo.replay(main, base, applied)?,
+        Outcome::Replayed {
+            tip: main,
+            commits: vec![(applied, main)]
+        }
+    );
+
+    let conflicting = id(&repo, "conflicting")?;
+    assert_eq!(
+        repo.replay(main, base, conflicting)?,
+        Outcome::Conflicts {
+            commit: conflicting,
+            paths: vec!["b".into()]
+        }
+    );
+
+    let err = repo.replay(main, base, id(&repo, "merge")?).unwrap_err();
+    assert!(matches!(err, gix::replay::Error::MergeCommit { .. }));
+    Ok(())
+}
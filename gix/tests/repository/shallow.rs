@@ -63,6 +63,18 @@ mod traverse {
                     .map(|c| c.map(|c| c.id))
                     .collect::<Result<_, _>>()?;
                 assert_eq!(commits, [hex_to_id("30887839de28edf7ab66c860e5c58b4d445f6b12")]);
+
+                let info = repo
+                    .head_id()?
+                    .ancestors()
+                    .use_commit_graph(toggle)
+                    .all()?
+                    .next()
+                    .expect("one commit")?;
+                assert!(
+                    info.parent_ids.is_empty(),
+                    "commits at the shallow boundary have no parents"
+                );
             }
         }
         Ok(())
@@ -111,6 +123,29 @@ mod traverse {
                     .collect::<Vec<_>>()
                 );
 
+                for split in 0..=commits.len() {
+                    let ancestors = || {
+                        repo.head_id()
+                            .expect("present")
+                            .ancestors()
+                            .use_commit_graph(toggle)
+                            .sorting(Sorting::ByCommitTimeNewestFirst)
+                    };
+                    let mut walk = ancestors().all()?;
+                    let mut resumed = walk
+                        .by_ref()
+                        .take(split)
+                        .map(|c| c.map(|c| c.id))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    for info in ancestors().resume_from(walk.checkpoint()).all()? {
+                        resumed.push(info?.id);
+                    }
+                    assert_eq!(
+                        resumed, commits,
+                        "shallow boundaries are respected when resuming after {split} commits"
+                    );
+                }
+
                 // should be
                 // *   f99771f - (HEAD -> main, origin/main, origin/HEAD) A (18 years ago) <A U Thor>
                 // | * 2d9d136 - C (18 years ago) <A U Thor>
@@ -123,3 +158,64 @@ mod traverse {
         Ok(())
     }
 }
+
+mod grafts {
+    use gix_traverse::commit::Sorting;
+    use serial_test::parallel;
+
+    use crate::util::hex_to_id;
+
+    #[test]
+    #[parallel]
+    fn parents_are_overridden_during_traversal() -> crate::Result {
+        let tmp = gix_testtools::scripted_fixture_writable("make_shallow_repo.sh")?;
+        let repo = gix::open_opts(tmp.path().join("base"), crate::restricted())?;
+        assert!(repo.grafts()?.is_none(), "there is no grafts file by default");
+        assert!(gix_testtools::run_git(
+            repo.work_dir().expect("non-bare"),
+            &["commit-graph", "write", "--no-progress", "--reachable"]
+        )?
+        .success());
+
+        let (c3, c2, c1) = (
+            hex_to_id("30887839de28edf7ab66c860e5c58b4d445f6b12"),
+            hex_to_id("d8523dfd5a7aa16562fa1c3e1d3b4a4494f97876"),
+            hex_to_id("05dc291f5376cde200316cb0b74b00cfebc79ea4"),
+        );
+        std::fs::write(repo.grafts_file(), format!("# skip c2\n{c3} {c1}\n\n"))?;
+        let grafts = repo.grafts()?.expect("present");
+        assert_eq!(grafts.len(), 1);
+        assert_eq!(grafts[&c3].as_slice(), [c1]);
+
+        for toggle in [false, true] {
+            for sorting in [Sorting::BreadthFirst, Sorting::ByCommitTimeNewestFirst] {
+                let infos = repo
+                    .head_id()?
+                    .ancestors()
+                    .use_commit_graph(toggle)
+                    .sorting(sorting)
+                    .all()?
+                    .map(|info| info.map(|info| (info.id, info.parent_ids.into_vec())))
+                    .collect::<Result<Vec<_>, _>>()?;
+                assert_eq!(
+                    infos,
+                    [(c3, vec![c1]), (c1, vec![])],
+                    "the grafted parent is used instead of the actual one"
+                );
+            }
+        }
+
+        std::fs::write(repo.grafts_file(), format!("{c2}\n"))?;
+        let commits = repo
+            .head_id()?
+            .ancestors()
+            .all()?
+            .map(|info| info.map(|info| info.id))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(commits, [c3, c2], "grafts without parents terminate the traversal");
+
+        std::fs::write(repo.grafts_file(), "not-a-hash\n")?;
+        assert!(repo.grafts().is_err(), "malformed lines are errors");
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+use gix::{
+    bstr::{BString, ByteSlice},
+    sparse::Definition,
+};
+
+fn skipped(repo: &gix::Repository) -> crate::Result<Vec<String>> {
+    let index = repo.open_index()?;
+    Ok(index
+        .entries()
+        .iter()
+        .filter(|entry| entry.flags.contains(gix::index::entry::Flags::SKIP_WORKTREE))
+        .map(|entry| entry.path(&index).to_string())
+        .collect())
+}
+
+fn paths(paths: &[&str]) -> Vec<BString> {
+    paths.iter().map(|path| (*path).into()).collect()
+}
+
+#[test]
+fn definition_round_trip_and_matching() {
+    let cone = Definition::Cone {
+        directories: paths(&["dir1/sub/", "dir2", "dir2/nested"]),
+    };
+    let data = cone.to_bytes();
+    assert_eq!(data.as_bstr(), "/*\n!/*/\n/dir1/\n!/dir1/*/\n/dir1/sub/\n/dir2/\n");
+    assert_eq!(
+        Definition::from_bytes(&data, true),
+        Definition::Cone {
+            directories: paths(&["dir1/sub", "dir2"]),
+        },
+        "directories within other directories are redundant"
+    );
+    assert_eq!(
+        Definition::from_bytes(&data, false),
+        Definition::Patterns {
+            patterns: paths(&["/*", "!/*/", "/dir1/", "!/dir1/*/", "/dir1/sub/", "/dir2/"])
+        },
+        "without cone mode, it's read as patterns"
+    );
+    assert!(
+        !Definition::from_bytes(b"*.txt\n", true).is_cone(),
+        "patterns that don't follow the format fall back to pattern matching"
+    );
+
+    for definition in [cone, Definition::from_bytes(&data, false)] {
+        let search = definition.search();
+        for (path, expected) in [
+            ("a", true),
+            ("dir1/file", true),
+            ("dir1/sub/file", true),
+            ("dir1/sub/deeper/file", true),
+            ("dir1/other/file", false),
+            ("dir2/deeper/file", true),
+            ("dir3/file", false),
+        ] {
+            assert_eq!(search.is_included(path.into()), expected, "{path} in {definition:?}");
+        }
+    }
+}
+
+#[test]
+fn set_reapply_and_disable() -> crate::Result {
+    let (mut repo, _tmp) = crate::repo_rw("make_sparse_repo.sh")?;
+    let work_dir = repo.work_dir().expect("non-bare").to_owned();
+    assert_eq!(repo.sparse_checkout()?, None, "disabled by default");
+
+    let cone = Definition::Cone {
+        directories: paths(&["dir1/sub"]),
+    };
+    let outcome = repo.set_sparse_checkout(&cone)?;
+    assert_eq!(outcome.removed, paths(&["dir2/file"]));
+    assert!(outcome.added.is_empty() && outcome.kept.is_empty());
+    assert!(!work_dir.join("dir2").exists(), "empty directories are removed");
+    assert!(work_dir.join("dir1/sub/file").exists());
+    assert_eq!(skipped(&repo)?, ["dir2/file"]);
+    assert_eq!(repo.sparse_checkout()?.as_ref(), Some(&cone));
+    let repo_reopened = gix::open_opts(&work_dir, crate::restricted())?;
+    assert_eq!(
+        repo_reopened.sparse_checkout()?.as_ref(),
+        Some(&cone),
+        "the configuration was written to disk"
+    );
+    assert!(
+        repo_reopened.status(Default::default())?.items.is_empty(),
+        "skipped files don't show up as deleted"
+    );
+
+    std::fs::write(work_dir.join("dir1/file"), "modified")?;
+    let outcome = repo.set_sparse_checkout(&Definition::Patterns {
+        patterns: paths(&["dir2/"]),
+    })?;
+    assert_eq!(outcome.added, paths(&["dir2/file"]));
+    assert_eq!(outcome.removed, paths(&["a", "dir1/sub/file"]));
+    assert_eq!(outcome.kept, paths(&["dir1/file"]), "files with local changes are kept");
+    assert_eq!(std::fs::read(work_dir.join("dir2/file"))?, b"content\n");
+    assert_eq!(skipped(&repo)?, ["a", "dir1/sub/file"]);
+    assert!(!repo.sparse_checkout()?.expect("enabled").is_cone());
+
+    let outcome = repo.disable_sparse_checkout()?;
+    assert_eq!(outcome.added, paths(&["a", "dir1/sub/file"]));
+    assert!(skipped(&repo)?.is_empty());
+    assert_eq!(repo.sparse_checkout()?, None);
+    assert!(repo.sparse_checkout_path().is_file(), "the definition is kept");
+    Ok(())
+}
+
+#[test]
+fn configuration_is_written_per_worktree_if_enabled() -> crate::Result {
+    let (mut repo, _tmp) = crate::repo_rw("make_sparse_repo.sh")?;
+    let mut config = repo.config_snapshot_mut();
+    config.set_raw_value("extensions", None, "worktreeConfig", "true")?;
+    let repo = config.commit_to(gix_config::Source::Local)?;
+
+    repo.set_sparse_checkout(&Definition::Cone {
+        directories: paths(&["dir1"]),
+    })?;
+    let worktree_config = std::fs::read_to_string(repo.git_dir().join("config.worktree"))?;
+    assert_eq!(
+        worktree_config, "[core]\n\tsparseCheckout = true\n\tsparseCheckoutCone = true\n",
+        "like git, the configuration goes to config.worktree"
+    );
+    assert!(
+        !std::fs::read_to_string(repo.common_dir().join("config"))?.contains("sparseCheckout"),
+        "the shared configuration is left alone"
+    );
+    assert!(gix::open_opts(repo.work_dir().expect("non-bare"), crate::restricted())?
+        .sparse_checkout()?
+        .is_some());
+    Ok(())
+}
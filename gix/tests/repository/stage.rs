@@ -0,0 +1,271 @@
+use gix::bstr::ByteSlice;
+
+#[test]
+fn add_remove_and_write() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_status_repos.sh", "changes")?;
+    let mut stage = repo.stage()?;
+    let id = stage.add_path("modified")?;
+    assert_eq!(
+        repo.find_object(id)?.data.as_bstr(),
+        "content\nchange\n",
+        "the file content was written to the object database"
+    );
+    stage.add_path("untracked")?;
+    let id = stage.add_bytes("dir/in-memory", "hello\n", gix::index::entry::Mode::FILE_EXECUTABLE)?;
+    assert_eq!(repo.find_object(id)?.data.as_bstr(), "hello\n");
+    assert!(stage.remove("unchanged"));
+    assert!(
+        !stage.remove("does-not-exist"),
+        "removing a path that isn't present is a no-op"
+    );
+
+    let index = stage.index();
+    assert!(index.tree().is_none(), "the tree cache is invalidated by changes");
+    let entry = index.entry_by_path("dir/in-memory".into()).expect("added");
+    assert_eq!(entry.mode, gix::index::entry::Mode::FILE_EXECUTABLE);
+    assert!(index.entry_by_path("unchanged".into()).is_none());
+
+    assert!(
+        repo.index()?.entry_by_path("untracked".into()).is_none(),
+        "nothing is written yet"
+    );
+    stage.write()?;
+    let index = repo.index()?;
+    for path in ["modified", "untracked", "dir/in-memory"] {
+        assert!(index.entry_by_path(path.into()).is_some(), "{path} was staged");
+    }
+    assert!(index.entry_by_path("unchanged".into()).is_none());
+    Ok(())
+}
+
+#[test]
+fn line_endings_are_converted_with_warnings_for_lossy_round_trips() -> crate::Result {
+    use gix::filter::plumbing::eol::convert_to_git::RoundTripWarning;
+
+    let (mut repo, _tmp) = crate::named_subrepo_rw("make_status_repos.sh", "changes")?;
+    repo.config_snapshot_mut()
+        .set_raw_value("core", None, "autocrlf", "input")?;
+    let mut stage = repo.stage()?;
+    let id = stage.add_bytes("crlf", "a\r\nb\r\n", gix::index::entry::Mode::FILE)?;
+    assert_eq!(
+        repo.find_object(id)?.data.as_bstr(),
+        "a\nb\n",
+        "CRLF is converted to LF"
+    );
+    stage.add_bytes("lf", "a\nb\n", gix::index::entry::Mode::FILE)?;
+    assert_eq!(
+        stage.take_warnings(),
+        [gix::stage::Warning {
+            rela_path: "crlf".into(),
+            kind: RoundTripWarning::CrlfWillBeReplacedByLf
+        }],
+        "by default, `core.safecrlf` warns about CRLF that won't be restored on checkout"
+    );
+    assert!(stage.take_warnings().is_empty(), "warnings are taken");
+
+    repo.config_snapshot_mut()
+        .set_raw_value("core", None, "safecrlf", "true")?;
+    let mut stage = repo.stage()?;
+    let err = stage
+        .add_bytes("crlf", "a\r\n", gix::index::entry::Mode::FILE)
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "CRLF would be replaced by LF in 'crlf'",
+        "lossy conversions can also be prevented"
+    );
+    Ok(())
+}
+
+#[test]
+fn entries_in_the_way_are_replaced() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_status_repos.sh", "changes")?;
+    let mut stage = repo.stage()?;
+    stage.add_bytes("modified/file", "content", gix::index::entry::Mode::FILE)?;
+    stage.add_bytes("dir", "content", gix::index::entry::Mode::FILE)?;
+
+    let paths: Vec<_> = stage
+        .index()
+        .entries()
+        .iter()
+        .map(|entry| entry.path(stage.index()).to_string())
+        .collect();
+    assert_eq!(
+        paths,
+        [
+            ".gitignore",
+            "added",
+            "deleted-in-worktree",
+            "dir",
+            "modified/file",
+            "staged",
+            "unchanged"
+        ],
+        "the file 'modified' and the directory 'dir/' were removed"
+    );
+    Ok(())
+}
+
+#[test]
+fn decomposed_unicode_is_precomposed_if_configured() -> crate::Result {
+    let (mut repo, _tmp) = crate::named_subrepo_rw("make_status_repos.sh", "changes")?;
+    let (decomposed, precomposed) = ("a\u{308}", "\u{e4}");
+    std::fs::write(repo.work_dir().expect("non-bare").join(decomposed), b"content")?;
+    repo.config_snapshot_mut()
+        .set_raw_value("core", None, "precomposeUnicode", "true")?;
+
+    let mut stage = repo.stage()?;
+    stage.add_path(decomposed)?;
+    let index = stage.index();
+    assert!(index.entry_by_path(precomposed.into()).is_some());
+    assert!(
+        index.entry_by_path(decomposed.into()).is_none(),
+        "the path is stored as git would store it"
+    );
+    Ok(())
+}
+
+#[test]
+fn directories_cannot_be_added() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_status_repos.sh", "changes")?;
+    let err = repo.stage()?.add_path("untracked-dir").unwrap_err();
+    assert!(matches!(err, gix::stage::add::Error::IsDirectory { .. }));
+    Ok(())
+}
+
+#[test]
+fn writing_fails_in_read_only_repositories() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_status_repos.sh", "changes")?;
+    let repo = gix::open_opts(repo.git_dir(), crate::restricted().read_only(true))?;
+    let mut stage = repo.stage()?;
+    assert!(stage.write().is_err());
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "status")]
+fn assume_unchanged_and_skip_worktree_hide_worktree_changes() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_status_repos.sh", "changes")?;
+    let worktree_changes = |repo: &gix::Repository| -> crate::Result<Vec<String>> {
+        // Open the repository again as its cached index isn't refreshed if the modification time of the file didn't change.
+        let repo = gix::open_opts(repo.git_dir(), crate::restricted())?;
+        Ok(repo
+            .status(Default::default())?
+            .items
+            .iter()
+            .filter(|item| item.porcelain_v2_code()[1] != '.')
+            .map(|item| item.rela_path().to_string())
+            .collect())
+    };
+    assert_eq!(
+        worktree_changes(&repo)?,
+        [
+            "deleted-in-index",
+            "deleted-in-worktree",
+            "dir/untracked",
+            "modified",
+            "untracked",
+            "untracked-dir/"
+        ]
+    );
+
+    let mut stage = repo.stage()?;
+    assert_eq!(stage.is_assume_unchanged("modified"), Some(false));
+    assert!(stage.set_assume_unchanged("modified", true));
+    assert!(stage.set_skip_worktree("deleted-in-worktree", true));
+    assert!(
+        !stage.set_skip_worktree("untracked", true),
+        "untracked paths can't be marked"
+    );
+    assert_eq!(stage.is_skip_worktree("untracked"), None);
+    stage.write()?;
+
+    assert_eq!(
+        repo.open_index()?.version(),
+        gix::index::Version::V3,
+        "skip-worktree needs extended flags"
+    );
+    let mut stage = repo.stage()?;
+    assert_eq!(stage.is_assume_unchanged("modified"), Some(true));
+    assert_eq!(stage.is_skip_worktree("deleted-in-worktree"), Some(true));
+    assert_eq!(
+        worktree_changes(&repo)?,
+        ["deleted-in-index", "dir/untracked", "untracked", "untracked-dir/"],
+        "the worktree isn't checked for marked entries"
+    );
+
+    assert!(stage.set_assume_unchanged("modified", false));
+    assert!(stage.set_skip_worktree("deleted-in-worktree", false));
+    stage.write()?;
+    assert_eq!(repo.open_index()?.version(), gix::index::Version::V2);
+    assert_eq!(worktree_changes(&repo)?.len(), 6, "changes are visible again");
+    Ok(())
+}
+
+#[cfg(feature = "blob-diff")]
+mod hunks {
+    use gix::{bstr::ByteSlice, stage::Hunk};
+
+    fn hunk(before: std::ops::Range<u32>, after: std::ops::Range<u32>) -> Hunk {
+        Hunk { before, after }
+    }
+
+    #[test]
+    fn stage_selected_hunks_and_lines() -> crate::Result {
+        let (repo, _tmp) = crate::named_subrepo_rw("make_status_repos.sh", "changes")?;
+        let mut stage = repo.stage()?;
+        stage.add_bytes("modified", "a\nb\nc\nd\ne\n", gix::index::entry::Mode::FILE)?;
+        std::fs::write(
+            repo.work_dir().expect("non-bare").join("modified"),
+            "a\nB\nc\nd\ne\nf\ng\n",
+        )?;
+
+        let hunks = stage.worktree_hunks("modified")?;
+        assert_eq!(hunks, [hunk(1..2, 1..2), hunk(5..5, 5..7)]);
+
+        let id = stage.stage_hunks("modified", &[hunk(5..5, 5..6)])?;
+        assert_eq!(
+            repo.find_object(id)?.data.as_bstr(),
+            "a\nb\nc\nd\ne\nf\n",
+            "only the selected line of the second hunk was staged"
+        );
+        assert_eq!(
+            stage.worktree_hunks("modified")?,
+            [hunk(1..2, 1..2), hunk(6..6, 6..7)],
+            "the index changed, and with it the remaining hunks"
+        );
+
+        let err = stage
+            .stage_hunks("modified", &[hunk(0..2, 0..2)])
+            .expect_err("ranges must be within a hunk");
+        assert!(matches!(err, gix::stage::hunks::Error::InvalidHunk { .. }));
+        let err = stage
+            .stage_hunks("untracked", &[])
+            .expect_err("only tracked files have hunks");
+        assert!(matches!(err, gix::stage::hunks::Error::NotInIndex { .. }));
+        Ok(())
+    }
+
+    #[test]
+    fn unstage_selected_hunks_and_lines() -> crate::Result {
+        let (repo, _tmp) = crate::named_subrepo_rw("make_status_repos.sh", "changes")?;
+        let mut stage = repo.stage()?;
+        stage.add_bytes("staged", "first\ncontent\nnew1\nnew2\n", gix::index::entry::Mode::FILE)?;
+
+        let hunks = stage.index_hunks("staged")?;
+        assert_eq!(hunks, [hunk(0..0, 0..1), hunk(1..1, 2..4)], "`HEAD` contains 'content'");
+
+        let id = stage.unstage_hunks("staged", &[hunk(0..0, 0..1), hunk(1..1, 3..4)])?;
+        assert_eq!(repo.find_object(id)?.data.as_bstr(), "content\nnew1\n");
+        assert_eq!(stage.index_hunks("staged")?, [hunk(1..1, 1..2)]);
+
+        let hunks = stage.index_hunks("staged")?;
+        let id = stage.unstage_hunks("staged", &hunks)?;
+        assert_eq!(
+            repo.find_object(id)?.data.as_bstr(),
+            "content\n",
+            "unstaging all hunks yields the version in `HEAD`"
+        );
+        Ok(())
+    }
+}
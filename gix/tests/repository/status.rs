@@ -0,0 +1,355 @@
+use gix::status::{self, Change, Item};
+
+fn codes(outcome: &status::Outcome) -> Vec<(String, String)> {
+    outcome
+        .items
+        .iter()
+        .map(|item| (item.porcelain_v2_code().iter().collect(), item.rela_path().to_string()))
+        .collect()
+}
+
+#[test]
+fn changes_between_head_index_and_worktree() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_status_repos.sh", "changes")?;
+    let outcome = repo.status(Default::default())?;
+    assert_eq!(
+        codes(&outcome),
+        [
+            ("A.", "added"),
+            ("D.", "deleted-in-index"),
+            ("??", "deleted-in-index"),
+            (".D", "deleted-in-worktree"),
+            ("??", "dir/untracked"),
+            (".M", "modified"),
+            ("M.", "staged"),
+            ("??", "untracked"),
+            ("??", "untracked-dir/"),
+        ]
+        .into_iter()
+        .map(|(code, path)| (code.to_owned(), path.to_owned()))
+        .collect::<Vec<_>>()
+    );
+    assert!(outcome.is_dirty());
+    assert_eq!(
+        outcome.items[5],
+        Item::Changed {
+            rela_path: "modified".into(),
+            index: None,
+            worktree: Some(Change::Modified),
+            submodule: None,
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn submodules_are_recursed_into_if_configured() -> crate::Result {
+    let tmp = gix_testtools::scripted_fixture_writable("make_submodules.sh")?;
+    let open = |overrides: &[&str]| {
+        gix::open_opts(
+            tmp.path().join("with-submodules"),
+            crate::restricted().config_overrides(overrides.iter().copied()),
+        )
+    };
+    let repo = open(&[])?;
+    std::fs::write(repo.work_dir().expect("non-bare").join("m1").join("this"), "changed")?;
+    assert!(!repo.status_options()?.recurse_submodules);
+    assert!(
+        repo.status(repo.status_options()?)?.submodules.is_empty(),
+        "submodules aren't recursed into by default"
+    );
+
+    let repo = open(&["submodule.recurse=true"])?;
+    let options = repo.status_options()?;
+    assert!(options.recurse_submodules, "configured by `submodule.recurse`");
+    let outcome = repo.status(options)?;
+    assert_eq!(
+        outcome
+            .submodules
+            .iter()
+            .map(|sm| (sm.name.to_string(), sm.rela_path.to_string()))
+            .collect::<Vec<_>>(),
+        [("dir/m1", "dir/m1"), ("m1", "m1")]
+            .into_iter()
+            .map(|(name, path)| (name.to_owned(), path.to_owned()))
+            .collect::<Vec<_>>(),
+        "all submodules with a worktree checkout are listed by path"
+    );
+    for sm in &outcome.submodules {
+        assert!(sm.index_id.is_some());
+        assert!(!sm.is_commit_changed(), "both submodules are at their recorded commit");
+    }
+    assert!(outcome.submodules[0].outcome.items.is_empty());
+    assert_eq!(
+        codes(&outcome.submodules[1].outcome),
+        [(".M".to_owned(), "this".to_owned())],
+        "paths are relative to the submodule worktree"
+    );
+
+    let outcome = repo.status(status::Options {
+        recurse_submodules: true,
+        submodules: status::Submodule::Ignore,
+        ..Default::default()
+    })?;
+    assert!(outcome.submodules.is_empty(), "ignored submodules aren't recursed into");
+    Ok(())
+}
+
+#[test]
+fn untracked_and_ignored_files_can_be_configured() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_status_repos.sh", "changes")?;
+    let outcome = repo.status(status::Options {
+        untracked: status::Untracked::All,
+        ignored: true,
+        ..Default::default()
+    })?;
+    let untracked_or_ignored: Vec<_> = codes(&outcome)
+        .into_iter()
+        .filter(|(code, _)| code == "??" || code == "!!")
+        .collect();
+    assert_eq!(
+        untracked_or_ignored,
+        [
+            ("??", "deleted-in-index"),
+            ("??", "dir/untracked"),
+            ("!!", "file.ignored"),
+            ("??", "untracked"),
+            ("??", "untracked-dir/a"),
+            ("??", "untracked-dir/b"),
+        ]
+        .into_iter()
+        .map(|(code, path)| (code.to_owned(), path.to_owned()))
+        .collect::<Vec<_>>()
+    );
+
+    let outcome = repo.status(status::Options {
+        untracked: status::Untracked::No,
+        ..Default::default()
+    })?;
+    assert!(
+        outcome.items.iter().all(|item| matches!(item, Item::Changed { .. })),
+        "only tracked changes remain"
+    );
+    Ok(())
+}
+
+#[test]
+fn pathspecs_limit_the_output() -> crate::Result {
+    let (repo, _tmp) = crate::named_subrepo_rw("make_status_repos.sh", "changes")?;
+    let outcome = repo.status(status::Options {
+        patterns: vec!["dir".into(), "staged".into()],
+        ..Default::default()
+    })?;
+    assert_eq!(
+        codes(&outcome),
+        [("??", "dir/untracked"), ("M.", "staged")]
+            .into_iter()
+            .map(|(code, path)| (code.to_owned(), path.to_owned()))
+            .collect::<Vec<_>>()
+    );
+    Ok(())
+}
+
+#[test]
+fn untracked_files_differing_only_in_case_from_tracked_ones_are_folded_if_case_is_ignored() -> crate::Result {
+    let (mut repo, _tmp) = crate::named_subrepo_rw("make_status_repos.sh", "changes")?;
+    std::fs::write(repo.work_dir().expect("non-bare").join("STAGED"), b"content")?;
+    let is_untracked = |outcome: &status::Outcome| {
+        outcome
+            .items
+            .iter()
+            .any(|item| matches!(item, Item::Untracked { rela_path } if rela_path == "STAGED"))
+    };
+    assert!(
+        is_untracked(&repo.status(Default::default())?),
+        "by default, case matters"
+    );
+
+    repo.config_snapshot_mut()
+        .set_raw_value("core", None, "ignoreCase", "true")?;
+    assert!(
+        !is_untracked(&repo.status(Default::default())?),
+        "the file is considered the tracked one, as it would be on a case-insensitive filesystem"
+    );
+    Ok(())
+}
+
+#[test]
+fn untracked_paths_are_precomposed_if_configured() -> crate::Result {
+    let (mut repo, _tmp) = crate::named_subrepo_rw("make_status_repos.sh", "changes")?;
+    let work_dir = repo.work_dir().expect("non-bare").to_owned();
+    std::fs::create_dir(work_dir.join("a\u{308}-dir"))?;
+    std::fs::write(work_dir.join("a\u{308}-dir").join("a\u{308}"), b"content")?;
+    let untracked = |repo: &gix::Repository| -> crate::Result<Vec<String>> {
+        Ok(repo
+            .status(status::Options {
+                untracked: status::Untracked::All,
+                ..Default::default()
+            })?
+            .items
+            .iter()
+            .filter(|item| matches!(item, Item::Untracked { .. }))
+            .map(|item| item.rela_path().to_string())
+            .filter(|path| !path.is_ascii())
+            .collect())
+    };
+    assert_eq!(
+        untracked(&repo)?,
+        ["a\u{308}-dir/a\u{308}"],
+        "by default, paths are used as is"
+    );
+
+    repo.config_snapshot_mut()
+        .set_raw_value("core", None, "precomposeUnicode", "true")?;
+    assert_eq!(
+        untracked(&repo)?,
+        ["\u{e4}-dir/\u{e4}"],
+        "directories are still read from disk"
+    );
+    Ok(())
+}
+
+mod renames {
+    use gix::status::Item;
+
+    use crate::repository::status::codes;
+
+    #[test]
+    fn are_detected_between_head_and_index_if_configured() -> crate::Result {
+        let (repo, _tmp) = crate::named_subrepo_rw("make_status_repos.sh", "renames")?;
+        let outcome = repo.status(repo.status_options()?)?;
+        assert_eq!(
+            codes(&outcome),
+            [
+                ("A.", "added"),
+                ("RM", "exact-renamed"),
+                ("R.", "similar-renamed"),
+                ("D.", "unrelated"),
+            ]
+            .into_iter()
+            .map(|(code, path)| (code.to_owned(), path.to_owned()))
+            .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            outcome.items[2],
+            Item::Rewritten {
+                source_rela_path: "similar".into(),
+                rela_path: "similar-renamed".into(),
+                copy: false,
+                score: 94,
+                in_worktree: false,
+                worktree: None,
+            }
+        );
+        let rewrites = outcome.rewrites.expect("rename tracking was enabled");
+        assert!(
+            rewrites.num_similarity_checks > 0,
+            "the similar file can only be found by similarity"
+        );
+
+        let outcome = repo.status(Default::default())?;
+        assert_eq!(
+            outcome
+                .items
+                .iter()
+                .filter(|item| matches!(item, Item::Rewritten { .. }))
+                .count(),
+            0,
+            "by default, renames are not tracked"
+        );
+        assert!(outcome.rewrites.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn are_detected_between_index_and_worktree_if_configured() -> crate::Result {
+        let (mut repo, _tmp) = crate::named_subrepo_rw("make_status_repos.sh", "worktree-renames")?;
+        let outcome = repo.status(repo.status_options()?)?;
+        assert_eq!(
+            codes(&outcome),
+            [
+                (".R", "exact-renamed"),
+                (".R", "intent-to-add-renamed"),
+                (".M", "modified"),
+                ("??", "modified-copy"),
+                (".R", "similar-renamed"),
+                (".D", "unrelated"),
+                ("??", "untracked"),
+            ]
+            .into_iter()
+            .map(|(code, path)| (code.to_owned(), path.to_owned()))
+            .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            outcome.items[0],
+            Item::Rewritten {
+                source_rela_path: "exact".into(),
+                rela_path: "exact-renamed".into(),
+                copy: false,
+                score: 100,
+                in_worktree: true,
+                worktree: None,
+            }
+        );
+        assert_eq!(
+            outcome.items[4],
+            Item::Rewritten {
+                source_rela_path: "similar".into(),
+                rela_path: "similar-renamed".into(),
+                copy: false,
+                score: 94,
+                in_worktree: true,
+                worktree: None,
+            }
+        );
+        let rewrites = outcome.index_worktree_rewrites.expect("rename tracking was enabled");
+        assert!(
+            rewrites.num_similarity_checks > 0,
+            "the similar file can only be found by similarity"
+        );
+
+        repo.config_snapshot_mut()
+            .set_raw_value("status", None, "renames", "copies")?;
+        let outcome = repo.status(repo.status_options()?)?;
+        assert_eq!(
+            outcome.items[3],
+            Item::Rewritten {
+                source_rela_path: "modified".into(),
+                rela_path: "modified-copy".into(),
+                copy: true,
+                score: 100,
+                in_worktree: true,
+                worktree: None,
+            },
+            "copies are found from the index version of modified files"
+        );
+        assert_eq!(outcome.items[3].porcelain_v2_code(), ['.', 'C']);
+
+        let outcome = repo.status(Default::default())?;
+        assert_eq!(
+            codes(&outcome)[..2],
+            [(".D", "exact"), ("??", "exact-renamed")]
+                .into_iter()
+                .map(|(code, path)| (code.to_owned(), path.to_owned()))
+                .collect::<Vec<_>>(),
+            "by default, renames are not tracked"
+        );
+        assert!(outcome.index_worktree_rewrites.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn status_renames_overrides_diff_renames() -> crate::Result {
+        let (repo, _tmp) = crate::named_subrepo_rw("make_status_repos.sh", "renames-disabled")?;
+        let options = repo.status_options()?;
+        assert!(options.rewrites.is_none(), "status.renames takes precedence");
+        assert_eq!(
+            codes(&repo.status(options)?),
+            [("D.", "exact"), ("A.", "exact-renamed")]
+                .into_iter()
+                .map(|(code, path)| (code.to_owned(), path.to_owned()))
+                .collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+fn messages(tip: gix::Id<'_>) -> crate::Result<Vec<String>> {
+    let mut out = Vec::new();
+    for info in tip.ancestors().all()? {
+        out.push(info?.object()?.message_raw()?.to_string());
+    }
+    Ok(out)
+}
+
+#[test]
+fn split() -> crate::Result {
+    let repo = crate::named_repo("make_subtree_repo.sh")?;
+    let head = repo.head_id()?;
+    let tip = repo.subtree_split(head, "lib")?.expect("lib exists");
+    assert_eq!(
+        messages(tip)?,
+        ["add lib/c on side\n", "add lib/b\n", "add lib\n"],
+        "commits that don't change the directory are skipped, including merges that don't add anything"
+    );
+    assert_eq!(
+        tip.object()?.into_commit().tree_id()?,
+        head.object()?
+            .peel_to_tree()?
+            .lookup_entry_by_path("lib", &mut Vec::new())?
+            .expect("present")
+            .object_id(),
+        "the directory becomes the root"
+    );
+    let commit = tip.object()?.into_commit();
+    let original = repo.rev_parse_single("side")?.object()?.into_commit();
+    assert_eq!(
+        commit.author()?,
+        original.author()?,
+        "author and committer are retained"
+    );
+    assert_eq!(commit.committer()?, original.committer()?);
+
+    assert_eq!(
+        repo.subtree_split(repo.rev_parse_single("side")?, "lib/")?,
+        Some(tip),
+        "the result is the same for each commit that maps to it"
+    );
+    assert_eq!(
+        repo.subtree_split(repo.rev_parse_single("upstream")?, "lib")?,
+        None,
+        "lineages without the directory have no split"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "status")]
+fn merge() -> crate::Result {
+    use gix::subtree::merge::{Error, Outcome};
+    let (repo, _tmp) = crate::repo_rw("make_subtree_repo.sh")?;
+    let work_dir = repo.work_dir().expect("non-bare").to_owned();
+    let head = repo.head_id()?.detach();
+
+    let theirs = repo.rev_parse_single("upstream~1")?.detach();
+    let outcome = repo.subtree_merge(theirs, "vendor", Default::default())?;
+    let commit = match outcome {
+        Outcome::Merged { commit, .. } => commit,
+        Outcome::AlreadyUpToDate => unreachable!("there are changes to merge"),
+    };
+    assert_eq!(repo.head_id()?, commit);
+    let parents: Vec<_> = repo.head_commit()?.parent_ids().map(gix::Id::detach).collect();
+    assert_eq!(parents, [head, theirs]);
+    assert_eq!(
+        repo.head_commit()?.message_raw()?,
+        format!("Merge commit '{theirs}'").as_str()
+    );
+    assert_eq!(
+        std::fs::read(work_dir.join("vendor/a"))?,
+        b"2\n",
+        "the change is applied within the directory"
+    );
+    assert_eq!(
+        std::fs::read(work_dir.join("vendor/b"))?,
+        b"local\n",
+        "local changes are kept"
+    );
+    assert!(!work_dir.join("a").exists());
+    let repo = gix::open_opts(&work_dir, crate::restricted())?;
+    assert!(
+        repo.status(Default::default())?.items.is_empty(),
+        "the merge was checked out"
+    );
+
+    let err = repo
+        .subtree_merge(repo.rev_parse_single("upstream")?, "vendor", Default::default())
+        .unwrap_err();
+    assert!(
+        matches!(&err, Error::Conflicts { paths } if paths == &["vendor/b"]),
+        "changes to the same file on both sides conflict: {err:?}"
+    );
+    assert_eq!(repo.head_id()?, commit, "nothing changes if there are conflicts");
+
+    assert_eq!(
+        repo.subtree_merge(repo.rev_parse_single("upstream~2")?, "vendor", Default::default())?,
+        Outcome::AlreadyUpToDate
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "status")]
+fn merge_with_base_in_our_own_history() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_subtree_repo.sh")?;
+    let work_dir = repo.work_dir().expect("non-bare").to_owned();
+
+    let theirs = repo.rev_parse_single("lib-at-root")?.detach();
+    let outcome = repo.subtree_merge(theirs, "lib", Default::default())?;
+    assert!(matches!(outcome, gix::subtree::merge::Outcome::Merged { .. }));
+    assert_eq!(
+        std::fs::read(work_dir.join("lib/a"))?,
+        b"changed at root\n",
+        "the merge base is our own commit, so its directory at the prefix is used as base"
+    );
+    assert_eq!(std::fs::read(work_dir.join("lib/b"))?, b"b\n");
+    assert!(!work_dir.join("a").exists());
+    Ok(())
+}
@@ -31,6 +31,74 @@ fn archive() -> crate::Result {
     Ok(())
 }
 
+#[test]
+#[cfg(feature = "worktree-archive")]
+fn archive_of_tree_ish() -> crate::Result {
+    let repo = crate::named_repo("make_packed_and_loose.sh")?;
+    let commit = repo.head_commit()?;
+    let archive = |id: gix::ObjectId| -> crate::Result<Vec<u8>> {
+        let mut buf = Vec::<u8>::new();
+        repo.archive(
+            id,
+            &mut buf,
+            &std::sync::atomic::AtomicBool::default(),
+            Default::default(),
+        )?;
+        Ok(buf)
+    };
+
+    let from_commit = archive(commit.id)?;
+    assert_eq!(from_commit.len(), 102, "default format is internal");
+    assert_eq!(
+        from_commit,
+        archive(commit.tree_id()?.detach())?,
+        "commits are peeled to their tree"
+    );
+
+    let blob_id = commit.tree()?.find_entry("this").expect("present").object_id();
+    assert!(
+        matches!(
+            archive(blob_id)
+                .unwrap_err()
+                .downcast_ref::<gix::repository::archive::Error>(),
+            Some(gix::repository::archive::Error::PeelToCommit(_))
+        ),
+        "blobs can't be archived"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "worktree-archive")]
+fn archive_of_commit_substitutes_placeholders_in_export_subst_files() -> crate::Result {
+    use std::io::Read;
+    let repo = crate::named_repo("make_pretty_format_repo.sh")?;
+    let mut buf = Vec::<u8>::new();
+    repo.archive(
+        repo.head_id()?,
+        &mut buf,
+        &std::sync::atomic::AtomicBool::default(),
+        Default::default(),
+    )?;
+
+    let mut stream = gix::worktree::stream::Stream::from_read(std::io::Cursor::new(buf));
+    let mut substituted = None;
+    while let Some(mut entry) = stream.next_entry()? {
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content)?;
+        if entry.relative_path() == "subst.txt" {
+            substituted = Some(content);
+        }
+    }
+    let expected = std::fs::read(repo.work_dir().expect("non-bare").join("baseline").join("subst.txt"))?;
+    assert_eq!(
+        substituted.expect("file is part of the archive"),
+        expected,
+        "placeholders are expanded just like git does it"
+    );
+    Ok(())
+}
+
 mod with_core_worktree_config {
     use std::io::BufRead;
 
@@ -296,6 +364,16 @@ fn run_assertions(main_repo: gix::Repository, should_be_bare: bool) {
         assert_eq!(proxy_lock_reason, expected.locked);
         let proxy_is_locked = actual.is_locked();
         assert_eq!(proxy_is_locked, proxy_lock_reason.is_some());
+        assert_eq!(actual.is_prunable(), expected.prunable.is_some());
+        match actual.head().unwrap() {
+            gix_ref::Target::Symbolic(name) => {
+                assert_eq!(Some(name.as_bstr()), expected.branch.as_ref().map(AsRef::as_ref))
+            }
+            gix_ref::Target::Peeled(id) => {
+                assert_eq!(id, expected.peeled);
+                assert_eq!(expected.branch, None, "detached worktrees have no branch");
+            }
+        }
         // TODO: check id of expected worktree, but need access to .gitdir from worktree base
         let proxy_id = actual.id().to_owned();
         assert_eq!(
@@ -334,3 +412,337 @@ fn run_assertions(main_repo: gix::Repository, should_be_bare: bool) {
         );
     }
 }
+
+#[cfg(feature = "worktree-mutation")]
+mod add {
+    use gix::worktree::add::{Head, Options};
+
+    fn head_name(repo: &gix::Repository) -> Option<String> {
+        repo.head_name().unwrap().map(|name| name.as_bstr().to_string())
+    }
+
+    #[test]
+    #[cfg(feature = "status")]
+    fn new_branch_detached_and_existing_branch() -> crate::Result {
+        use gix::worktree::add::Error;
+        let (repo, tmp) = crate::repo_rw("make_sparse_repo.sh")?;
+        let head_id = repo.head_id()?.detach();
+
+        let linked = repo.add_worktree(
+            tmp.path().join("feature"),
+            Head::NewBranch {
+                name: "feature".into(),
+                commit: head_id,
+            },
+            Options::default(),
+        )?;
+        assert_eq!(head_name(&linked).as_deref(), Some("refs/heads/feature"));
+        assert_eq!(repo.find_reference("feature")?.id(), head_id, "the branch is created");
+        assert_eq!(
+            gix_path::realpath(linked.common_dir())?,
+            gix_path::realpath(repo.git_dir())?,
+            "the common dir resolves to the main git dir"
+        );
+        let work_dir = linked.work_dir().expect("non-bare");
+        assert_eq!(std::fs::read(work_dir.join("dir1/sub/file"))?, b"content\n");
+        let reopened = gix::open_opts(work_dir, crate::restricted())?;
+        assert_eq!(reopened.head_id()?, head_id);
+        assert!(
+            reopened.status(Default::default())?.items.is_empty(),
+            "a clean checkout"
+        );
+
+        let detached = repo.add_worktree(
+            tmp.path().join("feature"),
+            Head::Detached(head_id),
+            Options {
+                lock_reason: Some("on a stick".into()),
+            },
+        );
+        assert!(matches!(detached, Err(Error::DestinationExists { .. })));
+        let detached = repo.add_worktree(
+            tmp.path().join("other").join("feature"),
+            Head::Detached(head_id),
+            Options {
+                lock_reason: Some("on a stick".into()),
+            },
+        )?;
+        assert_eq!(head_name(&detached), None);
+        assert_eq!(detached.head_id()?, head_id);
+
+        let main_branch = head_name(&repo).expect("on a branch");
+        let err = repo
+            .add_worktree(
+                tmp.path().join("main"),
+                Head::Branch(main_branch.into()),
+                Options::default(),
+            )
+            .unwrap_err();
+        assert!(matches!(err, Error::BranchCheckedOut { .. }), "{err:?}");
+        let err = repo
+            .add_worktree(
+                tmp.path().join("main"),
+                Head::NewBranch {
+                    name: "refs/heads/feature".into(),
+                    commit: head_id,
+                },
+                Options::default(),
+            )
+            .unwrap_err();
+        assert!(matches!(err, Error::BranchExists { .. }), "{err:?}");
+
+        let worktrees = repo.worktrees()?;
+        assert_eq!(worktrees.len(), 2);
+        assert_eq!(worktrees[0].id(), "feature");
+        assert_eq!(worktrees[0].lock_reason(), None);
+        assert_eq!(
+            worktrees[0].head()?,
+            gix_ref::Target::Symbolic("refs/heads/feature".try_into()?)
+        );
+        assert_eq!(worktrees[1].id(), "feature1", "names are made unique");
+        assert_eq!(worktrees[1].lock_reason(), Some("on a stick".into()));
+        assert_eq!(worktrees[1].head()?, gix_ref::Target::Peeled(head_id));
+        assert_eq!(
+            worktrees[1].base()?,
+            gix_path::realpath(tmp.path().join("other/feature"))?
+        );
+
+        std::fs::remove_dir_all(tmp.path().join("feature"))?;
+        assert!(worktrees[0].is_prunable());
+        let err = repo
+            .add_worktree(
+                tmp.path().join("main"),
+                Head::Branch("feature".into()),
+                Options::default(),
+            )
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::BranchCheckedOut { .. }),
+            "just like in `git`, the branch remains checked out until the worktree is pruned"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "status")]
+    fn lock_move_and_remove() -> crate::Result {
+        let (repo, tmp) = crate::repo_rw("make_sparse_repo.sh")?;
+        repo.add_worktree(
+            tmp.path().join("wt"),
+            Head::Detached(repo.head_id()?.detach()),
+            Options::default(),
+        )?;
+        let proxy = repo.worktrees()?.pop().expect("just added");
+        assert!(!proxy.is_prunable());
+
+        proxy.lock("reason".into())?;
+        assert_eq!(proxy.lock_reason(), Some("reason".into()));
+        assert_eq!(
+            proxy.lock("again".into()).unwrap_err().kind(),
+            std::io::ErrorKind::AlreadyExists
+        );
+        assert!(matches!(
+            proxy.move_to(tmp.path().join("moved")),
+            Err(gix::worktree::proxy::move_to::Error::Locked { .. })
+        ));
+        assert!(matches!(
+            repo.worktrees()?.pop().expect("present").remove(Default::default()),
+            Err(gix::worktree::proxy::remove::Error::Locked { .. })
+        ));
+        proxy.unlock()?;
+        assert!(!proxy.is_locked());
+
+        proxy.move_to(tmp.path().join("moved"))?;
+        let base = proxy.base()?;
+        assert_eq!(base, gix_path::realpath(tmp.path().join("moved"))?);
+        assert!(!tmp.path().join("wt").exists());
+        let moved = gix::open_opts(&base, crate::restricted())?;
+        assert_eq!(
+            moved.git_dir(),
+            proxy.git_dir(),
+            "the worktree still finds its private git dir"
+        );
+
+        std::fs::write(base.join("untracked"), "")?;
+        assert!(matches!(
+            repo.worktrees()?.pop().expect("present").remove(Default::default()),
+            Err(gix::worktree::proxy::remove::Error::Dirty { .. })
+        ));
+        repo.worktrees()?
+            .pop()
+            .expect("present")
+            .remove(gix::worktree::proxy::remove::Options { force: true })?;
+        assert!(!base.exists());
+        assert!(repo.worktrees()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "status")]
+    fn lock_move_and_remove_fail_in_read_only_repositories() -> crate::Result {
+        let (repo, tmp) = crate::repo_rw("make_sparse_repo.sh")?;
+        repo.add_worktree(
+            tmp.path().join("wt"),
+            Head::Detached(repo.head_id()?.detach()),
+            Options::default(),
+        )?;
+        let repo = gix::open_opts(repo.git_dir(), crate::restricted().read_only(true))?;
+        let proxy = repo.worktrees()?.pop().expect("present");
+
+        assert_eq!(
+            proxy.lock("reason".into()).unwrap_err().kind(),
+            std::io::ErrorKind::PermissionDenied
+        );
+        assert!(!proxy.is_locked());
+        assert_eq!(proxy.unlock().unwrap_err().kind(), std::io::ErrorKind::PermissionDenied);
+        assert!(matches!(
+            proxy.move_to(tmp.path().join("moved")),
+            Err(gix::worktree::proxy::move_to::Error::ReadOnly(_))
+        ));
+        assert!(matches!(
+            repo.worktrees()?
+                .pop()
+                .expect("present")
+                .remove(gix::worktree::proxy::remove::Options { force: true }),
+            Err(gix::worktree::proxy::remove::Error::ReadOnly(_))
+        ));
+        assert!(tmp.path().join("wt").is_dir(), "nothing was moved or removed");
+        assert_eq!(repo.worktrees()?.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn prune() -> crate::Result {
+        use gix::worktree::prune::{Pruned, Reason};
+        let (repo, tmp) = crate::repo_rw("make_sparse_repo.sh")?;
+        let head_id = repo.head_id()?.detach();
+        for (name, lock_reason) in [("a", None), ("b", Some("offline".into())), ("c", None)] {
+            repo.add_worktree(
+                tmp.path().join("worktrees").join(name),
+                Head::Detached(head_id),
+                Options { lock_reason },
+            )?;
+        }
+        std::fs::remove_dir_all(tmp.path().join("worktrees/a"))?;
+        std::fs::remove_dir_all(tmp.path().join("worktrees/b"))?;
+        let worktrees = repo.common_dir().join("worktrees");
+        std::fs::write(worktrees.join("junk"), "")?;
+        std::fs::create_dir(worktrees.join("empty"))?;
+
+        let expected = vec![
+            Pruned {
+                id: "a".into(),
+                reason: Reason::MissingWorktree,
+            },
+            Pruned {
+                id: "empty".into(),
+                reason: Reason::MissingGitdirFile,
+            },
+            Pruned {
+                id: "junk".into(),
+                reason: Reason::NotADirectory,
+            },
+        ];
+        let dry_run = gix::worktree::prune::Options {
+            dry_run: true,
+            expire: None,
+        };
+        assert_eq!(repo.prune_worktrees(dry_run)?, expected);
+        assert_eq!(repo.worktrees()?.len(), 3, "nothing was removed");
+
+        let invalid_only = repo.prune_worktrees(gix::worktree::prune::Options {
+            dry_run: false,
+            expire: Some(std::time::UNIX_EPOCH),
+        })?;
+        assert_eq!(
+            invalid_only,
+            &expected[1..],
+            "missing worktrees are only pruned if they are old enough, unlike invalid ones"
+        );
+        std::fs::remove_file(worktrees.join("a").join("index"))?;
+        assert_eq!(
+            repo.prune_worktrees(gix::worktree::prune::Options {
+                dry_run: false,
+                expire: Some(std::time::UNIX_EPOCH),
+            })?,
+            &expected[..1],
+            "like in `git`, the private index is used to learn when the worktree was last used"
+        );
+        let ids: Vec<_> = repo.worktrees()?.iter().map(|proxy| proxy.id().to_owned()).collect();
+        assert_eq!(ids, ["b", "c"], "locked worktrees are never pruned");
+        assert!(repo.prune_worktrees(Default::default())?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn prune_entries_with_illformed_utf8() -> crate::Result {
+        use std::os::unix::ffi::OsStrExt;
+        let (repo, _tmp) = crate::repo_rw("make_sparse_repo.sh")?;
+        let worktrees = repo.common_dir().join("worktrees");
+        std::fs::create_dir(&worktrees)?;
+        std::fs::write(worktrees.join(std::ffi::OsStr::from_bytes(b"\xff")), "")?;
+        assert_eq!(
+            repo.prune_worktrees(Default::default())?,
+            [gix::worktree::prune::Pruned {
+                id: b"\xff".as_slice().into(),
+                reason: gix::worktree::prune::Reason::NotADirectory,
+            }]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn repair() -> crate::Result {
+        use gix::worktree::repair::{Error, Kind};
+        let (repo, tmp) = crate::repo_rw("make_sparse_repo.sh")?;
+        repo.add_worktree(
+            tmp.path().join("wt"),
+            Head::Detached(repo.head_id()?.detach()),
+            Options::default(),
+        )?;
+        let no_paths = std::iter::empty::<&std::path::Path>;
+        assert!(repo.repair_worktrees(no_paths())?.is_empty(), "nothing to repair");
+
+        let moved = tmp.path().join("moved");
+        std::fs::rename(tmp.path().join("wt"), &moved)?;
+        let repaired = repo.repair_worktrees(Some(&moved))?;
+        assert_eq!(repaired.len(), 1);
+        assert_eq!(repaired[0].id, "wt");
+        assert_eq!(repaired[0].kind, Kind::GitdirFile);
+        let proxy = repo.worktrees()?.pop().expect("present");
+        assert_eq!(proxy.base()?, gix_path::realpath(&moved)?);
+        assert_eq!(
+            gix::open_opts(&moved, crate::restricted())?.git_dir(),
+            proxy.git_dir(),
+            "the worktree is connected to its private git dir"
+        );
+
+        std::fs::write(moved.join(".git"), "gitdir: /moved/repository/.git/worktrees/wt\n")?;
+        let repaired = repo.repair_worktrees(no_paths())?;
+        assert_eq!(repaired.len(), 1);
+        assert_eq!(repaired[0].kind, Kind::DotGitFile);
+        assert_eq!(repaired[0].path, moved.join(".git"));
+        gix::open_opts(&moved, crate::restricted())?;
+
+        std::fs::write(moved.join(".git"), "gitdir: /moved/repository/.git/worktrees/wt\n")?;
+        let repaired = repo.repair_worktrees(Some(&moved))?;
+        assert_eq!(
+            repaired.iter().map(|r| r.kind).collect::<Vec<_>>(),
+            [Kind::DotGitFile],
+            "the worktree is found by its id"
+        );
+        assert!(repo.repair_worktrees(Some(&moved))?.is_empty());
+
+        assert!(matches!(
+            repo.repair_worktrees(Some(tmp.path())),
+            Err(Error::NotALinkedWorktree { .. })
+        ));
+        let stray = tmp.path().join("stray");
+        std::fs::create_dir(&stray)?;
+        std::fs::write(stray.join(".git"), "gitdir: /elsewhere/unknown\n")?;
+        let err = repo.repair_worktrees(Some(&stray)).unwrap_err();
+        assert!(matches!(err, Error::UnknownWorktree { .. }), "{err:?}");
+        Ok(())
+    }
+}
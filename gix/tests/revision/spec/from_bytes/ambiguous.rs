@@ -124,6 +124,18 @@ fn commits_can_be_disambiguated_with_commit_specific_transformations() {
     }
 }
 
+#[test]
+fn describe_output_is_disambiguated_by_commit() {
+    let repo = repo("ambiguous_blob_tree_commit").unwrap();
+    for spec in ["v1.0-1-g0000000000", "anything-0-g0000000000"] {
+        assert_eq!(
+            parse_spec_no_baseline(spec, &repo).unwrap(),
+            Spec::from_id(hex_to_id("0000000000e4f9fbd19cf1e932319e5ad0d1d00b").attach(&repo)),
+            "the prefix in the output of `git describe` always refers to a commit"
+        );
+    }
+}
+
 #[test]
 fn tags_can_be_disambiguated_with_commit_specific_transformations() {
     let repo = repo("ambiguous_commits").unwrap();
@@ -141,3 +141,167 @@ mod open {
         Ok(())
     }
 }
+
+mod checked_out_id {
+    use crate::submodule::repo;
+
+    #[test]
+    fn is_head_of_submodule_repository() -> crate::Result {
+        let with_submodules = repo("with-submodules")?;
+        for sm in with_submodules.submodules()?.expect("modules present") {
+            assert_eq!(sm.checked_out_id()?, sm.index_id()?, "both submodules are checked out");
+        }
+
+        let after_clone = repo("with-submodules-after-clone")?;
+        let sm = after_clone
+            .submodules()?
+            .into_iter()
+            .flatten()
+            .next()
+            .expect("one submodule");
+        assert_eq!(sm.checked_out_id()?, None, "the submodule repository wasn't cloned yet");
+        assert!(sm.index_id()?.is_some());
+        Ok(())
+    }
+}
+
+mod init {
+    #[test]
+    fn writes_resolved_url_and_activates() -> crate::Result {
+        let (mut repo, tmp) = crate::named_subrepo_rw("make_submodules.sh", "with-submodules-after-clone")?;
+        let mut config = repo.config_snapshot_mut();
+        config.remove_section("submodule", Some("m1".into()));
+        config.commit_to(gix::config::Source::Local)?;
+        let repo = gix::open_opts(repo.path(), crate::restricted())?;
+
+        let sm = repo.submodules()?.into_iter().flatten().next().expect("one submodule");
+        assert_eq!(
+            sm.url()?.to_bstring(),
+            "../module1",
+            "the url from .gitmodules is relative"
+        );
+        let url = sm.resolved_url()?;
+        assert!(
+            url.path.ends_with(b"/module1") && gix_path::from_bstr(url.path.as_ref()).is_dir(),
+            "relative urls are resolved against the url of the remote of the superproject, which points to a sibling directory"
+        );
+        assert!(sm.init()?, "the url was newly registered");
+
+        let repo = gix::open_opts(tmp.path().join("with-submodules-after-clone"), crate::restricted())?;
+        let config = repo.config_snapshot();
+        assert_eq!(
+            config.string("submodule.m1.url").expect("set").as_ref(),
+            url.to_bstring(),
+            "the resolved url is written"
+        );
+        assert_eq!(config.boolean("submodule.m1.active"), Some(true));
+
+        let sm = repo.submodules()?.into_iter().flatten().next().expect("one submodule");
+        assert!(!sm.init()?, "nothing changes if the submodule was already initialized");
+        Ok(())
+    }
+}
+
+#[cfg(all(
+    feature = "blocking-network-client",
+    feature = "status",
+    feature = "worktree-mutation"
+))]
+mod perform_update {
+    use std::sync::atomic::AtomicBool;
+
+    use gix::submodule::perform_update::Outcome;
+
+    #[test]
+    fn clones_and_checks_out_recorded_commit() -> crate::Result {
+        let (repo, _tmp) = crate::named_subrepo_rw("make_submodules.sh", "with-submodules-after-clone")?;
+        let sm = repo.submodules()?.into_iter().flatten().next().expect("one submodule");
+        let id = sm.index_id()?.expect("recorded in index");
+
+        let outcome = sm
+            .perform_update(gix::progress::Discard, &AtomicBool::default())?
+            .expect("checkout is the default");
+        assert_eq!(
+            outcome,
+            Outcome {
+                id,
+                previous_id: None,
+                cloned: true,
+                fetched: false,
+            }
+        );
+        assert_eq!(sm.checked_out_id()?, Some(id));
+        let state = sm.state()?;
+        assert!(state.repository_exists && state.worktree_checkout && !state.is_old_form);
+        let work_dir = sm.work_dir()?;
+        assert_eq!(std::fs::read(work_dir.join("this"))?, b"hello\n");
+        assert!(work_dir.join("subdir").join("that").is_file());
+
+        let sm_repo = sm.open()?.expect("cloned");
+        assert_eq!(sm_repo.kind(), gix::repository::Kind::Submodule);
+        assert!(sm_repo.head()?.is_detached());
+        assert_eq!(
+            gix_path::realpath(sm_repo.work_dir().expect("non-bare"))?,
+            gix_path::realpath(&work_dir)?
+        );
+        assert!(
+            sm_repo.index()?.entry_by_path("this".into()).is_some(),
+            "the index was written"
+        );
+
+        let outcome = sm
+            .perform_update(gix::progress::Discard, &AtomicBool::default())?
+            .expect("checkout");
+        assert_eq!(
+            outcome,
+            Outcome {
+                id,
+                previous_id: Some(id),
+                cloned: false,
+                fetched: false,
+            },
+            "nothing to do if the recorded commit is checked out already"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn checks_out_recorded_commit_in_existing_repository() -> crate::Result {
+        let (repo, _tmp) = crate::named_subrepo_rw("make_submodules.sh", "with-submodules")?;
+        let sm = repo.submodules()?.into_iter().flatten().next().expect("one submodule");
+        let id = sm.index_id()?.expect("recorded in index");
+        let sm_repo = sm.open()?.expect("initialized");
+        let parent_id = sm_repo
+            .find_object(id)?
+            .try_into_commit()?
+            .parent_ids()
+            .next()
+            .expect("one parent")
+            .detach();
+        sm_repo.checkout_tree(parent_id, Default::default())?;
+        sm_repo.reference(
+            "HEAD",
+            parent_id,
+            gix::refs::transaction::PreviousValue::Any,
+            "move to parent",
+        )?;
+        let work_dir = sm.work_dir()?;
+        assert_eq!(std::fs::read(work_dir.join("this"))?, b"");
+
+        let outcome = sm
+            .perform_update(gix::progress::Discard, &AtomicBool::default())?
+            .expect("checkout");
+        assert_eq!(
+            outcome,
+            Outcome {
+                id,
+                previous_id: Some(parent_id),
+                cloned: false,
+                fetched: false,
+            }
+        );
+        assert_eq!(sm.checked_out_id()?, Some(id));
+        assert_eq!(std::fs::read(work_dir.join("this"))?, b"hello\n");
+        Ok(())
+    }
+}
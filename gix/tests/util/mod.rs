@@ -43,6 +43,12 @@ pub fn named_subrepo_opts(
     Ok(ThreadSafeRepository::open_opts(repo_path, opts)?.to_thread_local())
 }
 
+pub fn named_subrepo_rw(fixture: &str, name: &str) -> Result<(Repository, tempfile::TempDir)> {
+    let repo_path = gix_testtools::scripted_fixture_writable(fixture)?;
+    let repo = ThreadSafeRepository::open_opts(repo_path.path().join(name), restricted())?.to_thread_local();
+    Ok((repo, repo_path))
+}
+
 pub fn restricted() -> open::Options {
     open::Options::isolated().config_overrides(["user.name=gitoxide", "user.email=gitoxide@localhost"])
 }
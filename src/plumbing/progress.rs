@@ -408,10 +408,6 @@ static GIT_CONFIG: &[Record] = &[
         config: "http.sslCipherList",
         usage: NotPlanned { reason: "on demand" }
     },
-    Record {
-        config: "http.sslVerify",
-        usage: NotPlanned { reason: "on demand" }
-    },
     Record {
         config: "http.sslCert",
         usage: NotPlanned { reason: "on demand" }
@@ -527,6 +523,7 @@ pub fn show_progress() -> anyhow::Result<()> {
                 let config = key.logical_name();
                 let note = key.note().map(|note| match note {
                     gix::config::tree::Note::Deviation(n) | gix::config::tree::Note::Informative(n) => n.to_string(),
+                    gix::config::tree::Note::Deprecated(n) => format!("deprecated: {n}"),
                 });
                 let link = key.link().map(|link| match link {
                     gix::config::tree::Link::FallbackKey(key) => format!("fallback is '{}'", key.logical_name()),